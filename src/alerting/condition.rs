@@ -0,0 +1,496 @@
+use crate::monitoring::to_units;
+use alloy::primitives::U256;
+use eyre::{eyre, Result};
+use std::cmp::Ordering;
+use std::fmt;
+
+/// Value a rule condition compares
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Metric {
+    /// Current balance
+    Balance,
+    /// Signed change since the previous check
+    Change,
+    Increase,
+    Decrease,
+    /// Signed change in percent of the previous balance
+    ChangePercent,
+    IncreasePercent,
+    DecreasePercent,
+    /// Seconds since the balance last changed
+    NoChangeFor,
+    /// Seconds until the balance reaches its next low balance threshold at the recent spend rate
+    Runway,
+    /// Sum of decreases within the last given number of seconds
+    Outflow(u64),
+    /// Sum of increases within the last given number of seconds
+    Inflow(u64),
+}
+
+impl Metric {
+    fn parse(name: &str) -> Option<Self> {
+        Some(match name {
+            "balance" => Self::Balance,
+            "change" => Self::Change,
+            "increase" => Self::Increase,
+            "decrease" => Self::Decrease,
+            "change_percent" => Self::ChangePercent,
+            "increase_percent" => Self::IncreasePercent,
+            "decrease_percent" => Self::DecreasePercent,
+            "no_change_for" => Self::NoChangeFor,
+            "runway" => Self::Runway,
+            _ => return None,
+        })
+    }
+
+    /// Window of windowed metrics in seconds
+    pub fn window(&self) -> Option<u64> {
+        match self {
+            Self::Outflow(window) | Self::Inflow(window) => Some(*window),
+            _ => None,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Balance => "balance",
+            Self::Change => "change",
+            Self::Increase => "increase",
+            Self::Decrease => "decrease",
+            Self::ChangePercent => "change_percent",
+            Self::IncreasePercent => "increase_percent",
+            Self::DecreasePercent => "decrease_percent",
+            Self::NoChangeFor => "no_change_for",
+            Self::Runway => "runway",
+            Self::Outflow(_) => "outflow",
+            Self::Inflow(_) => "inflow",
+        }
+    }
+
+    fn is_percent(&self) -> bool {
+        matches!(self, Self::ChangePercent | Self::IncreasePercent | Self::DecreasePercent)
+    }
+
+    /// Whether the value is a duration in seconds
+    fn is_duration(&self) -> bool {
+        matches!(self, Self::NoChangeFor | Self::Runway)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparison {
+    Greater,
+    GreaterOrEqual,
+    Less,
+    LessOrEqual,
+    Equal,
+    NotEqual,
+}
+
+impl Comparison {
+    fn holds(&self, left: f64, right: f64) -> bool {
+        match self {
+            Self::Greater => left > right,
+            Self::GreaterOrEqual => left >= right,
+            Self::Less => left < right,
+            Self::LessOrEqual => left <= right,
+            Self::Equal => left == right,
+            Self::NotEqual => left != right,
+        }
+    }
+
+    /// Whether the comparison holds for a left side ordered this way against the right
+    fn holds_ordering(&self, ordering: Ordering) -> bool {
+        match self {
+            Self::Greater => ordering.is_gt(),
+            Self::GreaterOrEqual => ordering.is_ge(),
+            Self::Less => ordering.is_lt(),
+            Self::LessOrEqual => ordering.is_le(),
+            Self::Equal => ordering.is_eq(),
+            Self::NotEqual => ordering.is_ne(),
+        }
+    }
+
+    fn symbol(&self) -> &'static str {
+        match self {
+            Self::Greater => ">",
+            Self::GreaterOrEqual => ">=",
+            Self::Less => "<",
+            Self::LessOrEqual => "<=",
+            Self::Equal => "==",
+            Self::NotEqual => "!=",
+        }
+    }
+}
+
+/// Balance of one asset at a check, with what's needed for change metrics
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Observation {
+    pub balance: f64,
+    /// Raw balance and the decimals it is denominated in. When set, `balance` conditions
+    /// compare it exactly against the threshold in base units.
+    pub raw_balance: Option<(U256, u8)>,
+    /// Balance at the previous check, None on the first one
+    pub previous: Option<f64>,
+    /// Raw balance at the previous check, in the decimals of `raw_balance`. When both are
+    /// set, `change`, `increase` and `decrease` conditions are compared in base units too.
+    pub raw_previous: Option<U256>,
+    /// Seconds since the balance last changed
+    pub unchanged_secs: u64,
+    /// Recent balance changes as (seconds ago, signed change), including this check's
+    pub recent_changes: Vec<(u64, f64)>,
+    /// Seconds until the next low balance threshold at the recent spend rate, None if
+    /// the asset isn't being spent
+    pub runway_secs: Option<u64>,
+}
+
+impl Observation {
+    /// Whether `metric comparison value` holds. Balances with a raw value are compared in
+    /// base units, since f64 loses precision on large or finely divided amounts.
+    fn compare(&self, metric: Metric, comparison: Comparison, value: f64) -> bool {
+        match self.raw_ordering(metric, value) {
+            Some(ordering) => comparison.holds_ordering(ordering),
+            None => comparison.holds(self.value(metric), value),
+        }
+    }
+
+    /// Ordering of the metric against `value` in base units, None for metrics without raw values
+    fn raw_ordering(&self, metric: Metric, value: f64) -> Option<Ordering> {
+        let (raw, decimals) = self.raw_balance?;
+        if metric == Metric::Balance {
+            return (value >= 0.0).then(|| raw.cmp(&to_units(value, decimals)));
+        }
+        let previous = self.raw_previous?;
+        let (negative, amount) = match metric {
+            Metric::Change if raw < previous => (true, previous - raw),
+            Metric::Change | Metric::Increase => (false, raw.saturating_sub(previous)),
+            Metric::Decrease => (false, previous.saturating_sub(raw)),
+            _ => return None,
+        };
+        // Compare the signed amount with a signed threshold
+        Some(match (negative, value >= 0.0) {
+            (false, true) => amount.cmp(&to_units(value, decimals)),
+            (true, false) => to_units(-value, decimals).cmp(&amount),
+            (false, false) => Ordering::Greater,
+            (true, true) => Ordering::Less,
+        })
+    }
+
+    fn value(&self, metric: Metric) -> f64 {
+        let change = self.previous.map(|previous| self.balance - previous).unwrap_or(0.0);
+        let percent = |change: f64| match self.previous {
+            Some(previous) if previous != 0.0 => change / previous.abs() * 100.0,
+            _ if change == 0.0 => 0.0,
+            _ => change.signum() * f64::INFINITY,
+        };
+        match metric {
+            Metric::Balance => self.balance,
+            Metric::Change => change,
+            Metric::Increase => change.max(0.0),
+            Metric::Decrease => (-change).max(0.0),
+            Metric::ChangePercent => percent(change),
+            Metric::IncreasePercent => percent(change.max(0.0)),
+            Metric::DecreasePercent => percent((-change).max(0.0)).abs(),
+            Metric::NoChangeFor => self.unchanged_secs as f64,
+            Metric::Runway => self.runway_secs.map_or(f64::INFINITY, |secs| secs as f64),
+            Metric::Outflow(window) => self.recent_within(window).filter(|c| *c < 0.0).map(|c| -c).sum(),
+            Metric::Inflow(window) => self.recent_within(window).filter(|c| *c > 0.0).sum(),
+        }
+    }
+
+    fn recent_within(&self, window: u64) -> impl Iterator<Item = f64> + '_ {
+        self.recent_changes.iter().filter(move |(age, _)| *age < window).map(|(_, change)| *change)
+    }
+}
+
+/// Rule condition, e.g. `decrease > 1 ETH or (balance < 5 and no_change_for > 7d)`
+#[derive(Debug, Clone, PartialEq)]
+pub enum Condition {
+    Compare { metric: Metric, comparison: Comparison, value: f64 },
+    All(Vec<Condition>),
+    Any(Vec<Condition>),
+}
+
+impl Condition {
+    /// Parse a condition. `and` binds tighter than `or`; numbers may carry a unit
+    /// for readability (`1 ETH`, `50%`), `no_change_for` and `runway` take a duration
+    /// with s, m, h, d or w, and `outflow`/`inflow` a window such as `outflow(1h)`
+    pub fn parse(input: &str) -> Result<Self> {
+        let tokens = tokenize(input)?;
+        let mut parser = Parser { tokens, position: 0 };
+        let condition = parser.any()?;
+        match parser.peek() {
+            None => Ok(condition),
+            Some(token) => Err(eyre!("unexpected '{}' in condition '{}'", token, input)),
+        }
+    }
+
+    /// Longest window of the condition's windowed metrics, 0 if it has none
+    pub fn max_window(&self) -> u64 {
+        match self {
+            Self::Compare { metric, .. } => metric.window().unwrap_or(0),
+            Self::All(conditions) | Self::Any(conditions) => conditions.iter().map(Self::max_window).max().unwrap_or(0),
+        }
+    }
+
+    pub fn matches(&self, observation: &Observation) -> bool {
+        match self {
+            Self::Compare { metric, comparison, value } => observation.compare(*metric, *comparison, *value),
+            Self::All(conditions) => conditions.iter().all(|c| c.matches(observation)),
+            Self::Any(conditions) => conditions.iter().any(|c| c.matches(observation)),
+        }
+    }
+}
+
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let join = |f: &mut fmt::Formatter<'_>, conditions: &[Condition], separator: &str| -> fmt::Result {
+            for (i, condition) in conditions.iter().enumerate() {
+                if i > 0 {
+                    write!(f, " {} ", separator)?;
+                }
+                match condition {
+                    Self::Compare { .. } => write!(f, "{}", condition)?,
+                    _ => write!(f, "({})", condition)?,
+                }
+            }
+            Ok(())
+        };
+        match self {
+            Self::Compare { metric, comparison, value } if metric.is_duration() => {
+                write!(f, "{} {} {}", metric.name(), comparison.symbol(), format_duration(*value as u64))
+            }
+            Self::Compare { metric, comparison, value } if metric.window().is_some() => write!(
+                f,
+                "{}({}) {} {}",
+                metric.name(),
+                format_duration(metric.window().unwrap_or(0)),
+                comparison.symbol(),
+                value
+            ),
+            Self::Compare { metric, comparison, value } if metric.is_percent() => {
+                write!(f, "{} {} {}%", metric.name(), comparison.symbol(), value)
+            }
+            Self::Compare { metric, comparison, value } => write!(f, "{} {} {}", metric.name(), comparison.symbol(), value),
+            Self::All(conditions) => join(f, conditions, "and"),
+            Self::Any(conditions) => join(f, conditions, "or"),
+        }
+    }
+}
+
+fn format_duration(secs: u64) -> String {
+    for (unit, size) in [("w", 604_800), ("d", 86_400), ("h", 3_600), ("m", 60)] {
+        if secs >= size && secs.is_multiple_of(size) {
+            return format!("{}{}", secs / size, unit);
+        }
+    }
+    format!("{}s", secs)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Number(f64),
+    Compare(Comparison),
+    Percent,
+    Open,
+    Close,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Word(word) => write!(f, "{}", word),
+            Self::Number(number) => write!(f, "{}", number),
+            Self::Compare(comparison) => write!(f, "{}", comparison.symbol()),
+            Self::Percent => write!(f, "%"),
+            Self::Open => write!(f, "("),
+            Self::Close => write!(f, ")"),
+        }
+    }
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = input.char_indices().peekable();
+
+    while let Some(&(start, c)) = chars.peek() {
+        let take_while = |chars: &mut std::iter::Peekable<std::str::CharIndices>, f: fn(char) -> bool| {
+            let mut end = start;
+            while let Some(&(i, c)) = chars.peek() {
+                if !f(c) {
+                    break;
+                }
+                end = i + c.len_utf8();
+                chars.next();
+            }
+            &input[start..end]
+        };
+
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '(' | ')' | '%' => {
+                chars.next();
+                tokens.push(match c {
+                    '(' => Token::Open,
+                    ')' => Token::Close,
+                    _ => Token::Percent,
+                });
+            }
+            '>' | '<' | '=' | '!' | '&' | '|' => {
+                let symbol = take_while(&mut chars, |c| matches!(c, '>' | '<' | '=' | '!' | '&' | '|'));
+                tokens.push(match symbol {
+                    ">" => Token::Compare(Comparison::Greater),
+                    ">=" => Token::Compare(Comparison::GreaterOrEqual),
+                    "<" => Token::Compare(Comparison::Less),
+                    "<=" => Token::Compare(Comparison::LessOrEqual),
+                    "==" | "=" => Token::Compare(Comparison::Equal),
+                    "!=" => Token::Compare(Comparison::NotEqual),
+                    "&&" => Token::Word("and".to_string()),
+                    "||" => Token::Word("or".to_string()),
+                    _ => return Err(eyre!("unknown operator '{}' in condition '{}'", symbol, input)),
+                });
+            }
+            c if c.is_ascii_digit() || c == '.' => {
+                let number = take_while(&mut chars, |c| c.is_ascii_digit() || c == '.' || c == '_');
+                let value = number.replace('_', "").parse().map_err(|_| eyre!("invalid number '{}' in condition '{}'", number, input))?;
+                tokens.push(Token::Number(value));
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let word = take_while(&mut chars, |c| c.is_alphanumeric() || c == '_');
+                tokens.push(Token::Word(word.to_lowercase()));
+            }
+            _ => return Err(eyre!("unexpected '{}' in condition '{}'", c, input)),
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn eat_word(&mut self, word: &str) -> bool {
+        if self.peek() == Some(&Token::Word(word.to_string())) {
+            self.position += 1;
+            return true;
+        }
+        false
+    }
+
+    fn any(&mut self) -> Result<Condition> {
+        let mut conditions = vec![self.all()?];
+        while self.eat_word("or") {
+            conditions.push(self.all()?);
+        }
+        Ok(if conditions.len() == 1 { conditions.remove(0) } else { Condition::Any(conditions) })
+    }
+
+    fn all(&mut self) -> Result<Condition> {
+        let mut conditions = vec![self.term()?];
+        while self.eat_word("and") {
+            conditions.push(self.term()?);
+        }
+        Ok(if conditions.len() == 1 { conditions.remove(0) } else { Condition::All(conditions) })
+    }
+
+    fn term(&mut self) -> Result<Condition> {
+        match self.next() {
+            Some(Token::Open) => {
+                let condition = self.any()?;
+                match self.next() {
+                    Some(Token::Close) => Ok(condition),
+                    _ => Err(eyre!("missing ')'")),
+                }
+            }
+            Some(Token::Word(name)) => {
+                let metric = match name.as_str() {
+                    "outflow" => Metric::Outflow(self.window(&name)?),
+                    "inflow" => Metric::Inflow(self.window(&name)?),
+                    _ => Metric::parse(&name).ok_or_else(|| {
+                        eyre!(
+                            "unknown metric '{}', expected balance, change, increase, decrease, change_percent, \
+                             increase_percent, decrease_percent, no_change_for, runway, outflow(<window>) or inflow(<window>)",
+                            name
+                        )
+                    })?,
+                };
+                let Some(Token::Compare(comparison)) = self.next() else {
+                    return Err(eyre!("expected a comparison after '{}'", name));
+                };
+                let Some(Token::Number(value)) = self.next() else {
+                    return Err(eyre!("expected a number after '{} {}'", name, comparison.symbol()));
+                };
+                let value = self.unit(metric, value)?;
+                Ok(Condition::Compare { metric, comparison, value })
+            }
+            Some(token) => Err(eyre!("expected a metric, found '{}'", token)),
+            None => Err(eyre!("condition is incomplete")),
+        }
+    }
+
+    /// Parse the "(<duration>)" window of a windowed metric into seconds
+    fn window(&mut self, name: &str) -> Result<u64> {
+        let usage = || eyre!("{} needs a window such as {}(1h)", name, name);
+        if self.next() != Some(Token::Open) {
+            return Err(usage());
+        }
+        let Some(Token::Number(value)) = self.next() else {
+            return Err(usage());
+        };
+        let window = self.duration(value, name)?;
+        if self.next() != Some(Token::Close) {
+            return Err(usage());
+        }
+        if window < 1.0 {
+            return Err(usage());
+        }
+        Ok(window as u64)
+    }
+
+    /// Apply the duration unit following a number, in seconds
+    fn duration(&mut self, value: f64, name: &str) -> Result<f64> {
+        let multiplier = match self.next() {
+            Some(Token::Word(unit)) => match unit.as_str() {
+                "s" => 1.0,
+                "m" => 60.0,
+                "h" => 3_600.0,
+                "d" => 86_400.0,
+                "w" => 604_800.0,
+                _ => return Err(eyre!("unknown duration unit '{}', expected s, m, h, d or w", unit)),
+            },
+            _ => return Err(eyre!("{} needs a duration such as 7d or 12h", name)),
+        };
+        Ok(value * multiplier)
+    }
+
+    /// Apply the unit following a number: durations become seconds, others are
+    /// only checked to not be keywords
+    fn unit(&mut self, metric: Metric, value: f64) -> Result<f64> {
+        if metric.is_duration() {
+            return self.duration(value, metric.name());
+        }
+
+        match self.peek() {
+            Some(Token::Percent) if metric.is_percent() => self.position += 1,
+            Some(Token::Percent) => return Err(eyre!("'%' only applies to *_percent metrics")),
+            Some(Token::Word(word)) if word != "and" && word != "or" => self.position += 1,
+            _ => {}
+        }
+        Ok(value)
+    }
+}
@@ -0,0 +1,5 @@
+mod condition;
+mod rules;
+
+pub use condition::{Comparison, Condition, Metric, Observation};
+pub use rules::{RuleAlert, RuleEngine, RULE_ALERT_KIND};
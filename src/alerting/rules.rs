@@ -0,0 +1,534 @@
+use super::condition::{Condition, Observation};
+use crate::config::{AddressConfig, AlertSeverity, BudgetConfig, NetworkConfig, RuleConfig};
+use crate::monitoring::{from_units, outflow_since, BalanceInfo, Runway};
+use crate::notifiers::MonitorAlert;
+use crate::storage::HistoryStore;
+use alloy::primitives::{utils::format_units, U256};
+use eyre::{eyre, Result};
+use serde_json::json;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::Mutex;
+
+/// Alert kind of rule alerts
+pub const RULE_ALERT_KIND: &str = "rule";
+
+/// Part of a budget period that must have passed before the spend is projected,
+/// so a single early transaction doesn't project to many times the budget
+const MIN_PROJECTION_FRACTION: f64 = 0.1;
+
+struct Rule {
+    config: RuleConfig,
+    /// None for budget rules
+    condition: Option<Condition>,
+    /// (network, alias) of the addresses carrying one of the rule's tags
+    tagged: HashSet<(String, String)>,
+}
+
+impl Rule {
+    fn selects(&self, network: &str, alias: &str) -> bool {
+        let config = &self.config;
+        (config.networks.is_empty() || config.networks.iter().any(|name| name == network))
+            && ((config.addresses.is_empty() && config.tags.is_empty())
+                || config.addresses.iter().any(|a| a == alias)
+                || self.tagged.contains(&(network.to_string(), alias.to_string())))
+    }
+
+    fn applies_to(&self, balance: &BalanceInfo) -> bool {
+        !self.config.aggregate && self.selects(&balance.network_name, &balance.alias)
+    }
+
+    fn applies_to_asset(&self, asset: &str, is_native: bool) -> bool {
+        if self.config.assets.is_empty() {
+            return is_native;
+        }
+        self.config.assets.iter().any(|a| a == "*" || a == asset || (is_native && a == "native"))
+    }
+}
+
+/// One asset of a balance check
+struct Asset<'a> {
+    name: &'a str,
+    is_native: bool,
+    /// Raw balance and its decimals
+    raw: (U256, u8),
+    /// Raw balance at the previous check
+    previous_raw: Option<U256>,
+    formatted: &'a str,
+    previous_formatted: Option<&'a str>,
+}
+
+/// Key of per-asset state: (network, alias, asset)
+type AssetKey = (String, String, String);
+
+/// Key of the state of an aggregate rule's total: (rule index, network, asset)
+type TotalKey = (usize, String, String);
+
+/// How far an address is over its budget
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+enum BudgetLevel {
+    /// The spend so far, extrapolated over the period, exceeds the budget
+    Projected,
+    /// The spend so far exceeds the budget
+    Exceeded,
+}
+
+/// Total of an aggregate rule at the previous check
+struct TotalState {
+    /// Raw and formatted total, None before the first check
+    previous: Option<(U256, String)>,
+    changed_at: u64,
+    changes: VecDeque<(u64, f64)>,
+}
+
+#[derive(Default)]
+struct RuleState {
+    /// Raw balance and time of its last change per asset
+    last_change: HashMap<AssetKey, (U256, u64)>,
+    /// Rules that matched at the previous check, by rule index
+    active: HashSet<(usize, AssetKey)>,
+    /// Balance changes per asset as (timestamp, signed change) within the longest rule window
+    changes: HashMap<AssetKey, VecDeque<(u64, f64)>>,
+    /// Totals of aggregate rules
+    totals: HashMap<TotalKey, TotalState>,
+    /// Aggregate rules that matched at the previous check
+    active_totals: HashSet<TotalKey>,
+    /// Highest level alerted per budget rule and asset, with the start of its period
+    budgets: HashMap<(usize, AssetKey), (u64, BudgetLevel)>,
+}
+
+/// Alert raised by a rule, with the channels it's routed to
+#[derive(Debug, Clone)]
+pub struct RuleAlert {
+    pub rule: String,
+    pub severity: AlertSeverity,
+    /// Channel names; empty sends to every channel
+    pub channels: Vec<String>,
+    pub alert: MonitorAlert,
+}
+
+/// Evaluates alert rules against each balance check. A rule alerts when its
+/// condition starts to hold, and again on each balance change while it holds.
+/// Aggregate rules are evaluated once per network cycle on the summed balances,
+/// budget rules on the balance history.
+pub struct RuleEngine {
+    rules: Vec<Rule>,
+    /// Longest window of `outflow`/`inflow` metrics, 0 if no rule uses them
+    max_window: u64,
+    state: Mutex<RuleState>,
+}
+
+impl RuleEngine {
+    pub fn new(configs: &[RuleConfig]) -> Result<Self> {
+        let rules = configs
+            .iter()
+            .map(|config| {
+                let condition = match config.budget {
+                    Some(_) => None,
+                    None => Some(Condition::parse(&config.condition).map_err(|e| eyre!("rule '{}': {}", config.name, e))?),
+                };
+                Ok(Rule { config: config.clone(), condition, tagged: HashSet::new() })
+            })
+            .collect::<Result<Vec<Rule>>>()?;
+        let max_window = rules.iter().filter_map(|rule| rule.condition.as_ref()).map(Condition::max_window).max().unwrap_or(0);
+        Ok(Self { rules, max_window, state: Mutex::new(RuleState::default()) })
+    }
+
+    /// Resolve the rules' `tags` against the configured addresses
+    pub fn with_address_tags(mut self, networks: &[NetworkConfig]) -> Self {
+        for rule in &mut self.rules {
+            rule.tagged = networks
+                .iter()
+                .flat_map(|network| network.addresses.iter().map(move |addr| (network, addr)))
+                .filter(|(_, addr)| addr.tags.iter().any(|tag| rule.config.tags.contains(tag)))
+                .map(|(network, addr)| (network.name.clone(), addr.alias.clone()))
+                .collect();
+        }
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Whether any rule is evaluated on totals
+    pub fn has_aggregates(&self) -> bool {
+        self.rules.iter().any(|rule| rule.config.aggregate)
+    }
+
+    /// Whether any rule is a spending budget
+    pub fn has_budgets(&self) -> bool {
+        self.rules.iter().any(|rule| rule.config.budget.is_some())
+    }
+
+    /// Evaluate every rule for the assets of `current`; `previous` is the balance
+    /// from the previous check
+    pub fn evaluate(&self, current: &BalanceInfo, previous: Option<&BalanceInfo>, now: u64) -> Vec<RuleAlert> {
+        self.evaluate_with_runways(current, previous, &HashMap::new(), now)
+    }
+
+    /// Evaluate every rule with the runway forecasts of the assets, by asset name
+    pub fn evaluate_with_runways(
+        &self,
+        current: &BalanceInfo,
+        previous: Option<&BalanceInfo>,
+        runways: &HashMap<String, Runway>,
+        now: u64,
+    ) -> Vec<RuleAlert> {
+        let rules: Vec<(usize, &Rule, &Condition)> = self
+            .rules
+            .iter()
+            .enumerate()
+            .filter(|(_, rule)| rule.applies_to(current))
+            .filter_map(|(index, rule)| Some((index, rule, rule.condition.as_ref()?)))
+            .collect();
+        if rules.is_empty() {
+            return Vec::new();
+        }
+
+        let mut assets = vec![Asset {
+            name: &current.native_symbol,
+            is_native: true,
+            raw: (current.eth_balance, current.native_decimals),
+            previous_raw: previous.map(|p| p.eth_balance),
+            formatted: &current.eth_formatted,
+            previous_formatted: previous.map(|p| p.eth_formatted.as_str()),
+        }];
+        for token in &current.token_balances {
+            let before = previous.and_then(|p| p.token_balances.iter().find(|t| t.alias == token.alias));
+            assets.push(Asset {
+                name: &token.alias,
+                is_native: false,
+                raw: (token.balance, token.decimals),
+                previous_raw: before.map(|t| t.balance),
+                formatted: &token.formatted,
+                previous_formatted: before.map(|t| t.formatted.as_str()),
+            });
+        }
+
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let mut alerts = Vec::new();
+        for asset in &assets {
+            let key: AssetKey = (current.network_name.clone(), current.alias.clone(), asset.name.to_string());
+            let (raw, decimals) = asset.raw;
+            let changed = asset.previous_raw.is_some_and(|previous| previous != raw);
+
+            let last_change = state.last_change.entry(key.clone()).or_insert((raw, now));
+            if last_change.0 != raw {
+                *last_change = (raw, now);
+            }
+            let mut observation = Observation {
+                balance: from_units(raw, decimals),
+                raw_balance: Some(asset.raw),
+                previous: asset.previous_raw.map(|previous| from_units(previous, decimals)),
+                raw_previous: asset.previous_raw,
+                unchanged_secs: now.saturating_sub(last_change.1),
+                recent_changes: Vec::new(),
+                runway_secs: runways.get(asset.name).map(|runway| runway.remaining.as_secs()),
+            };
+            if self.max_window > 0 {
+                let changes = state.changes.entry(key.clone()).or_default();
+                observation.recent_changes = self.recent_changes(changes, &observation, now);
+            }
+
+            for &(index, rule, condition) in &rules {
+                if !rule.applies_to_asset(asset.name, asset.is_native) {
+                    continue;
+                }
+                let active_key = (index, key.clone());
+                if !condition.matches(&observation) {
+                    state.active.remove(&active_key);
+                    continue;
+                }
+                if state.active.insert(active_key) || changed {
+                    alerts.push(rule_alert(rule, condition, current, asset, &observation, runways.get(asset.name)));
+                }
+            }
+        }
+        alerts
+    }
+
+    /// Evaluate the aggregate rules applying to a network on the sums of their addresses'
+    /// balances. A rule is skipped while any of its addresses wasn't read in this cycle,
+    /// since a partial total would understate it.
+    pub fn evaluate_totals(&self, network: &str, addresses: &[AddressConfig], balances: &[BalanceInfo], now: u64) -> Vec<RuleAlert> {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let mut alerts = Vec::new();
+        for (index, rule) in self.rules.iter().enumerate().filter(|(_, rule)| rule.config.aggregate) {
+            let Some(condition) = &rule.condition else { continue };
+            let members: Option<Vec<&BalanceInfo>> = addresses
+                .iter()
+                .filter(|addr| rule.selects(network, &addr.alias))
+                .map(|addr| balances.iter().find(|balance| balance.alias == addr.alias))
+                .collect();
+            let Some(members) = members.filter(|members| !members.is_empty()) else { continue };
+
+            for total in Total::of(&members).into_iter().filter(|total| rule.applies_to_asset(&total.asset, total.is_native)) {
+                let key: TotalKey = (index, network.to_string(), total.asset.clone());
+                let entry = state.totals.entry(key.clone()).or_insert_with(|| TotalState {
+                    previous: None,
+                    changed_at: now,
+                    changes: VecDeque::new(),
+                });
+                let previous = entry.previous.replace((total.sum, total.formatted.clone()));
+                let changed = previous.as_ref().is_some_and(|(sum, _)| *sum != total.sum);
+                if changed {
+                    entry.changed_at = now;
+                }
+                let previous_sum = previous.as_ref().map(|(sum, _)| *sum);
+                let previous_formatted = previous.map(|(_, formatted)| formatted);
+                let mut observation = Observation {
+                    balance: from_units(total.sum, total.decimals),
+                    raw_balance: Some((total.sum, total.decimals)),
+                    previous: previous_sum.map(|sum| from_units(sum, total.decimals)),
+                    raw_previous: previous_sum,
+                    unchanged_secs: now.saturating_sub(entry.changed_at),
+                    recent_changes: Vec::new(),
+                    runway_secs: None,
+                };
+                if self.max_window > 0 {
+                    observation.recent_changes = self.recent_changes(&mut entry.changes, &observation, now);
+                }
+
+                if !condition.matches(&observation) {
+                    state.active_totals.remove(&key);
+                    continue;
+                }
+                if state.active_totals.insert(key) || changed {
+                    alerts.push(total_alert(rule, condition, &members, &total, previous_formatted.as_deref(), &observation));
+                }
+            }
+        }
+        alerts
+    }
+
+    /// Evaluate the budget rules applying to `current` on what its assets spent since
+    /// the start of the budget period: the sum of the balance decreases in the history,
+    /// up to the current balance. Incoming transfers don't offset spending. A rule
+    /// alerts once per period when the spend is projected to exceed the budget, and
+    /// once when it does.
+    pub fn evaluate_budgets(&self, history: &HistoryStore, current: &BalanceInfo, now: u64) -> Vec<RuleAlert> {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let mut alerts = Vec::new();
+        for (index, rule) in self.rules.iter().enumerate().filter(|(_, rule)| rule.applies_to(current)) {
+            let Some(budget) = &rule.config.budget else { continue };
+            let period_start = budget.period.start(now);
+            let mut assets = vec![(current.native_symbol.as_str(), true)];
+            assets.extend(current.token_balances.iter().map(|token| (token.alias.as_str(), false)));
+            for (asset, is_native) in assets.into_iter().filter(|&(asset, is_native)| rule.applies_to_asset(asset, is_native)) {
+                let spent = outflow_since(history, current, asset, is_native, period_start, now);
+                let elapsed = now.saturating_sub(period_start).max(1) as f64;
+                let projected = (elapsed >= budget.period.secs() as f64 * MIN_PROJECTION_FRACTION)
+                    .then(|| spent * budget.period.secs() as f64 / elapsed);
+
+                let level = if spent > budget.amount {
+                    BudgetLevel::Exceeded
+                } else if budget.projected && projected.is_some_and(|projected| projected > budget.amount) {
+                    BudgetLevel::Projected
+                } else {
+                    continue;
+                };
+                let key = (index, (current.network_name.clone(), current.alias.clone(), asset.to_string()));
+                let alerted = state.budgets.get(&key).filter(|(start, _)| *start == period_start).map(|&(_, level)| level);
+                if alerted.is_some_and(|alerted| alerted >= level) {
+                    continue;
+                }
+                state.budgets.insert(key, (period_start, level));
+                alerts.push(budget_alert(rule, budget, current, asset, level, spent, projected));
+            }
+        }
+        alerts
+    }
+
+    /// Record the change of an observation and return the changes within the longest window
+    fn recent_changes(&self, changes: &mut VecDeque<(u64, f64)>, observation: &Observation, now: u64) -> Vec<(u64, f64)> {
+        if let Some(previous) = observation.previous.filter(|&previous| previous != observation.balance) {
+            changes.push_back((now, observation.balance - previous));
+        }
+        while changes.front().is_some_and(|&(at, _)| at.saturating_add(self.max_window) <= now) {
+            changes.pop_front();
+        }
+        changes.iter().map(|&(at, change)| (now.saturating_sub(at), change)).collect()
+    }
+}
+
+/// Sum of one asset over several addresses
+struct Total {
+    asset: String,
+    is_native: bool,
+    sum: U256,
+    decimals: u8,
+    formatted: String,
+}
+
+impl Total {
+    /// Native and token totals of the balances; a token some addresses don't hold counts as 0 there
+    fn of(balances: &[&BalanceInfo]) -> Vec<Total> {
+        let Some(first) = balances.first() else { return Vec::new() };
+        let native = balances.iter().fold(U256::ZERO, |sum, balance| sum.saturating_add(balance.eth_balance));
+        let mut totals = vec![Total::new(&first.native_symbol, true, native, first.native_decimals)];
+
+        let mut tokens: Vec<(&str, U256, u8)> = Vec::new();
+        for token in balances.iter().flat_map(|balance| &balance.token_balances) {
+            match tokens.iter_mut().find(|(alias, _, _)| *alias == token.alias) {
+                Some((_, sum, _)) => *sum = sum.saturating_add(token.balance),
+                None => tokens.push((&token.alias, token.balance, token.decimals)),
+            }
+        }
+        totals.extend(tokens.into_iter().map(|(alias, sum, decimals)| Total::new(alias, false, sum, decimals)));
+        totals
+    }
+
+    fn new(asset: &str, is_native: bool, sum: U256, decimals: u8) -> Self {
+        let formatted = format_units(sum, decimals).unwrap_or_else(|_| sum.to_string());
+        Self { asset: asset.to_string(), is_native, sum, decimals, formatted }
+    }
+}
+
+fn budget_alert(
+    rule: &Rule,
+    budget: &BudgetConfig,
+    balance: &BalanceInfo,
+    asset: &str,
+    level: BudgetLevel,
+    spent: f64,
+    projected: Option<f64>,
+) -> RuleAlert {
+    let severity = rule.config.severity;
+    let period = budget.period.as_str();
+    let mut lines = vec![
+        format!("Address: {:?}", balance.address),
+        format!("Spent this {}: {:.4} of {} {}", period, spent, budget.amount, asset),
+    ];
+    if let Some(projected) = projected {
+        lines.push(format!("Projected for the {}: {:.4} {}", period, projected, asset));
+    }
+    lines.push(format!("Severity: {}", severity.as_str()));
+    let status = match level {
+        BudgetLevel::Projected => "projected over budget",
+        BudgetLevel::Exceeded => "over budget",
+    };
+
+    RuleAlert {
+        rule: rule.config.name.clone(),
+        severity,
+        channels: rule.config.channels.clone(),
+        alert: MonitorAlert {
+            kind: RULE_ALERT_KIND.to_string(),
+            network_name: balance.network_name.clone(),
+            chain_id: Some(balance.chain_id),
+            title: format!("{} {}: {} {}", severity.emoji(), rule.config.name, balance.alias, status),
+            lines,
+            data: json!({
+                "rule": rule.config.name,
+                "severity": severity.as_str(),
+                "budget": budget.amount,
+                "period": period,
+                "level": match level {
+                    BudgetLevel::Projected => "projected",
+                    BudgetLevel::Exceeded => "exceeded",
+                },
+                "alias": balance.alias,
+                "address": balance.address,
+                "asset": asset,
+                "spent": spent,
+                "projected": projected,
+            }),
+            dedup_id: None,
+        },
+    }
+}
+
+fn total_alert(
+    rule: &Rule,
+    condition: &Condition,
+    members: &[&BalanceInfo],
+    total: &Total,
+    previous: Option<&str>,
+    observation: &Observation,
+) -> RuleAlert {
+    let severity = rule.config.severity;
+    let aliases: Vec<&str> = members.iter().map(|balance| balance.alias.as_str()).collect();
+    let mut lines = vec![format!("Addresses: {}", aliases.join(", "))];
+    match previous {
+        Some(previous) if previous != total.formatted => {
+            lines.push(format!("Total: {} → {} {}", previous, total.formatted, total.asset))
+        }
+        _ => lines.push(format!("Total: {} {}", total.formatted, total.asset)),
+    }
+    lines.push(format!("Rule: {}", condition));
+    lines.push(format!("Severity: {}", severity.as_str()));
+
+    RuleAlert {
+        rule: rule.config.name.clone(),
+        severity,
+        channels: rule.config.channels.clone(),
+        alert: MonitorAlert {
+            kind: RULE_ALERT_KIND.to_string(),
+            network_name: members[0].network_name.clone(),
+            chain_id: Some(members[0].chain_id),
+            title: format!("{} {}: {} addresses", severity.emoji(), rule.config.name, members.len()),
+            lines,
+            data: json!({
+                "rule": rule.config.name,
+                "severity": severity.as_str(),
+                "condition": condition.to_string(),
+                "aggregate": true,
+                "addresses": aliases,
+                "asset": total.asset,
+                "balance": total.formatted,
+                "previous_balance": previous,
+                "unchanged_secs": observation.unchanged_secs,
+            }),
+            dedup_id: None,
+        },
+    }
+}
+
+fn rule_alert(
+    rule: &Rule,
+    condition: &Condition,
+    balance: &BalanceInfo,
+    asset: &Asset,
+    observation: &Observation,
+    runway: Option<&Runway>,
+) -> RuleAlert {
+    let severity = rule.config.severity;
+    let mut lines = vec![format!("Address: {:?}", balance.address)];
+    match asset.previous_formatted {
+        Some(previous) if previous != asset.formatted => {
+            lines.push(format!("Balance: {} → {} {}", previous, asset.formatted, asset.name))
+        }
+        _ => lines.push(format!("Balance: {} {}", asset.formatted, asset.name)),
+    }
+    if let Some(runway) = runway {
+        lines.push(format!("Runway: {} at {:.4} {}/day", runway.describe(), runway.daily_spend, asset.name));
+    }
+    lines.push(format!("Rule: {}", condition));
+    lines.push(format!("Severity: {}", severity.as_str()));
+
+    RuleAlert {
+        rule: rule.config.name.clone(),
+        severity,
+        channels: rule.config.channels.clone(),
+        alert: MonitorAlert {
+            kind: RULE_ALERT_KIND.to_string(),
+            network_name: balance.network_name.clone(),
+            chain_id: Some(balance.chain_id),
+            title: format!("{} {}: {}", severity.emoji(), rule.config.name, balance.alias),
+            lines,
+            data: json!({
+                "rule": rule.config.name,
+                "severity": severity.as_str(),
+                "condition": condition.to_string(),
+                "alias": balance.alias,
+                "address": balance.address,
+                "asset": asset.name,
+                "balance": asset.formatted,
+                "previous_balance": asset.previous_formatted,
+                "unchanged_secs": observation.unchanged_secs,
+                "runway_secs": observation.runway_secs,
+            }),
+            dedup_id: None,
+        },
+    }
+}
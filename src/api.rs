@@ -0,0 +1,312 @@
+use crate::config::ApiConfig;
+use crate::logger::compare_balances;
+use crate::monitoring::{AddressRegistry, BalanceInfo, GasPrice, GasTracker, HealthTracker};
+use crate::notifiers::{AuditLog, LowBalanceTracker};
+use crate::providers::{NetworkRpcStatus, RpcHealth};
+use crate::storage::{AuditQuery, BalanceStorage, HistoryStore};
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use chrono::{DateTime, Utc};
+use eyre::Result;
+use serde::Deserialize;
+use serde_json::json;
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::sync::RwLock;
+use tracing::{error, info};
+
+/// Shared state for API handlers
+#[derive(Clone)]
+pub struct ApiState {
+    storage: Arc<RwLock<BalanceStorage>>,
+    history: Option<Arc<RwLock<HistoryStore>>>,
+    gas: Option<Arc<GasTracker>>,
+    audit: Option<AuditLog>,
+    health: Option<Arc<HealthTracker>>,
+    rpc: Option<Arc<RpcHealth>>,
+    low_balance: Option<LowBalanceTracker>,
+    registry: Option<Arc<AddressRegistry>>,
+    /// Intervals without a successful check before a network is unhealthy
+    unhealthy_after_intervals: u32,
+    started_at: Instant,
+}
+
+impl ApiState {
+    pub fn new(storage: Arc<RwLock<BalanceStorage>>) -> Self {
+        Self {
+            storage,
+            history: None,
+            gas: None,
+            audit: None,
+            health: None,
+            rpc: None,
+            low_balance: None,
+            registry: None,
+            unhealthy_after_intervals: 3,
+            started_at: Instant::now(),
+        }
+    }
+
+    /// Enable the /changes endpoint using balance history
+    pub fn with_history(mut self, history: Arc<RwLock<HistoryStore>>) -> Self {
+        self.history = Some(history);
+        self
+    }
+
+    /// Enable the /gas endpoint
+    pub fn with_gas(mut self, gas: Arc<GasTracker>) -> Self {
+        self.gas = Some(gas);
+        self
+    }
+
+    /// Enable the /audit endpoint
+    pub fn with_audit(mut self, audit: AuditLog) -> Self {
+        self.audit = Some(audit);
+        self
+    }
+
+    /// Report per-network check status in /health and /ready
+    pub fn with_health(mut self, health: Arc<HealthTracker>, unhealthy_after_intervals: u32) -> Self {
+        self.health = Some(health);
+        self.unhealthy_after_intervals = unhealthy_after_intervals;
+        self
+    }
+
+    /// Enable the /rpc endpoint
+    pub fn with_rpc(mut self, rpc: Arc<RpcHealth>) -> Self {
+        self.rpc = Some(rpc);
+        self
+    }
+
+    /// Enable the /throttles endpoint
+    pub fn with_low_balance(mut self, low_balance: LowBalanceTracker) -> Self {
+        self.low_balance = Some(low_balance);
+        self
+    }
+
+    /// Filter /balances by address tag
+    pub fn with_registry(mut self, registry: Arc<AddressRegistry>) -> Self {
+        self.registry = Some(registry);
+        self
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct BalancesQuery {
+    /// Only addresses carrying this tag
+    tag: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChangesQuery {
+    /// Unix timestamp in seconds or RFC 3339 date-time
+    since: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct AuditParams {
+    /// Unix timestamp in seconds or RFC 3339 date-time
+    since: Option<String>,
+    until: Option<String>,
+    network: Option<String>,
+    channel: Option<String>,
+    limit: Option<usize>,
+}
+
+/// Build the API router
+pub fn router(state: ApiState) -> Router {
+    Router::new()
+        .route("/balances", get(list_balances))
+        .route("/balances/{network}/{alias}", get(get_balance))
+        .route("/changes", get(get_changes))
+        .route("/gas", get(list_gas))
+        .route("/rpc", get(list_rpc))
+        .route("/audit", get(list_audit))
+        .route("/throttles", get(list_throttles))
+        .route("/health", get(health))
+        .route("/ready", get(ready))
+        .with_state(state)
+}
+
+/// Start the API server in the background
+pub async fn spawn_api_server(config: &ApiConfig, state: ApiState) -> Result<()> {
+    let listener = tokio::net::TcpListener::bind(config.listen).await?;
+    info!("🌍 HTTP API listening on http://{}", config.listen);
+
+    tokio::spawn(async move {
+        if let Err(e) = axum::serve(listener, router(state)).await {
+            error!("HTTP API server error: {}", e);
+        }
+    });
+
+    Ok(())
+}
+
+fn error(status: StatusCode, message: &str) -> Response {
+    (status, Json(json!({ "error": message }))).into_response()
+}
+
+/// Parse `since` as Unix seconds or RFC 3339
+fn parse_since(since: &str) -> Option<u64> {
+    if let Ok(timestamp) = since.parse::<u64>() {
+        return Some(timestamp);
+    }
+    let datetime = DateTime::parse_from_rfc3339(since).ok()?;
+    u64::try_from(datetime.timestamp()).ok()
+}
+
+/// Latest balances of all addresses, sorted by network and alias
+async fn sorted_balances(storage: &RwLock<BalanceStorage>) -> Vec<BalanceInfo> {
+    let storage = storage.read().await;
+    let mut balances: Vec<BalanceInfo> = storage.balances.values().cloned().collect();
+    balances.sort_by(|a, b| (&a.network_name, &a.alias).cmp(&(&b.network_name, &b.alias)));
+    balances
+}
+
+async fn list_balances(State(state): State<ApiState>, Query(query): Query<BalancesQuery>) -> Response {
+    let mut balances = sorted_balances(&state.storage).await;
+    if let Some(tag) = &query.tag {
+        let tagged = state.registry.as_ref().map(|registry| registry.tagged(tag)).unwrap_or_default();
+        if tagged.is_empty() {
+            return error(StatusCode::NOT_FOUND, "no address has this tag");
+        }
+        balances.retain(|balance| tagged.contains(&(balance.network_name.clone(), balance.alias.clone())));
+    }
+    Json(balances).into_response()
+}
+
+async fn get_balance(
+    State(state): State<ApiState>,
+    Path((network, alias)): Path<(String, String)>,
+) -> Response {
+    let storage = state.storage.read().await;
+    match storage.get(&network, &alias) {
+        Some(balance) => Json(balance.clone()).into_response(),
+        None => error(StatusCode::NOT_FOUND, "balance not found"),
+    }
+}
+
+/// Balance changes of each address between `since` and now
+async fn get_changes(State(state): State<ApiState>, Query(query): Query<ChangesQuery>) -> Response {
+    let Some(history) = &state.history else {
+        return error(StatusCode::SERVICE_UNAVAILABLE, "balance history is disabled");
+    };
+    let Some(since) = parse_since(&query.since) else {
+        return error(StatusCode::BAD_REQUEST, "since must be a Unix timestamp or RFC 3339 date-time");
+    };
+
+    let baseline = history.read().await.snapshot_at(since);
+    let changes: Vec<_> = sorted_balances(&state.storage).await
+        .iter()
+        .map(|balance| compare_balances(balance, &baseline))
+        .filter(|summary| summary.has_changes())
+        .map(|summary| summary.to_json())
+        .collect();
+
+    Json(json!({ "since": since, "changes": changes })).into_response()
+}
+
+/// Latest gas prices of networks with gas tracking
+async fn list_gas(State(state): State<ApiState>) -> Json<Vec<GasPrice>> {
+    match &state.gas {
+        Some(gas) => Json(gas.all().await),
+        None => Json(Vec::new()),
+    }
+}
+
+/// Per-node RPC request counters and latency of each network
+async fn list_rpc(State(state): State<ApiState>) -> Json<Vec<NetworkRpcStatus>> {
+    match &state.rpc {
+        Some(rpc) => Json(rpc.all()),
+        None => Json(Vec::new()),
+    }
+}
+
+/// Low balance alert schedule and the addresses currently being throttled
+async fn list_throttles(State(state): State<ApiState>) -> Response {
+    let Some(low_balance) = &state.low_balance else {
+        return error(StatusCode::SERVICE_UNAVAILABLE, "low balance alerts are not tracked");
+    };
+    Json(json!({
+        "schedule": low_balance.schedule(),
+        "throttles": low_balance.throttled().await,
+    }))
+    .into_response()
+}
+
+/// Alert deliveries from the audit log, newest first
+async fn list_audit(State(state): State<ApiState>, Query(params): Query<AuditParams>) -> Response {
+    let Some(audit) = &state.audit else {
+        return error(StatusCode::SERVICE_UNAVAILABLE, "alert audit log is disabled");
+    };
+    let parse = |value: &Option<String>| value.as_deref().map(parse_since).map(|t| t.ok_or(()));
+    let (Ok(since), Ok(until)) = (parse(&params.since).transpose(), parse(&params.until).transpose()) else {
+        return error(StatusCode::BAD_REQUEST, "since and until must be Unix timestamps or RFC 3339 date-times");
+    };
+
+    let query = AuditQuery {
+        since,
+        until,
+        network: params.network,
+        channel: params.channel.map(|channel| channel.to_lowercase()),
+        limit: params.limit,
+    };
+    match audit.query(&query).await {
+        Ok(entries) => Json(entries).into_response(),
+        Err(e) => error(StatusCode::INTERNAL_SERVER_ERROR, &format!("failed to read audit log: {}", e)),
+    }
+}
+
+/// Liveness: 503 if any network had no successful check within the allowed intervals
+async fn health(State(state): State<ApiState>) -> Response {
+    let addresses = state.storage.read().await.balances.len();
+    let now = Utc::now().timestamp() as u64;
+    let networks = match &state.health {
+        Some(health) => health.all().await,
+        None => Vec::new(),
+    };
+
+    let mut healthy = true;
+    let networks: Vec<_> = networks
+        .iter()
+        .map(|network| {
+            let stale = network.is_stale(now, state.unhealthy_after_intervals);
+            healthy &= !stale;
+            let mut value = json!(network);
+            value["last_success_age_secs"] = json!(network.last_success_age(now));
+            value["healthy"] = json!(!stale);
+            value
+        })
+        .collect();
+
+    let status = if healthy { StatusCode::OK } else { StatusCode::SERVICE_UNAVAILABLE };
+    let body = json!({
+        "status": if healthy { "ok" } else { "unhealthy" },
+        "uptime_secs": state.started_at.elapsed().as_secs(),
+        "addresses": addresses,
+        "networks": networks,
+    });
+    (status, Json(body)).into_response()
+}
+
+/// Readiness: 503 until every network completed a successful check
+async fn ready(State(state): State<ApiState>) -> Response {
+    let networks = match &state.health {
+        Some(health) => health.all().await,
+        None => Vec::new(),
+    };
+    let pending: Vec<&str> = networks
+        .iter()
+        .filter(|network| network.last_success.is_none())
+        .map(|network| network.network_name.as_str())
+        .collect();
+
+    if pending.is_empty() {
+        Json(json!({ "status": "ready" })).into_response()
+    } else {
+        (StatusCode::SERVICE_UNAVAILABLE, Json(json!({ "status": "starting", "pending": pending }))).into_response()
+    }
+}
@@ -0,0 +1,335 @@
+use crate::run;
+use chrono::{Local, NaiveDate};
+use clap::{Parser, Subcommand, ValueEnum};
+use eyre::Result;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use Oxwatcher::{
+    balance_monitor_config, create_fallback_provider, create_state_backend, diagnose_config, export_balances,
+    export_history, load_history, test_change_alert, test_report, webhook_notifiers, BalanceMonitor, Config, ExportFormat, ExportKind, FallbackConfig, InstanceLease,
+    MonitorAlert, Notifier, StateBackup, TelegramNotifier,
+};
+
+#[derive(Parser)]
+#[command(version, about = "Blockchain balance monitor with Telegram, Slack, Discord, Matrix and webhook alerts")]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<CliCommand>,
+    /// Config file
+    #[arg(short, long, global = true, env = "OXWATCHER_CONFIG", default_value = "config.yaml")]
+    pub config: String,
+    /// Directory for state files, overriding data_dir in the config
+    #[arg(long, global = true, env = "OXWATCHER_DATA_DIR")]
+    pub data_dir: Option<String>,
+    /// What the watcher writes to stdout; other subcommands ignore it
+    #[arg(long, global = true, value_enum, default_value = "text")]
+    pub output: OutputMode,
+    /// Run every check but only log alerts, keeping state in a temporary copy of
+    /// data_dir; other subcommands ignore it
+    #[arg(long, global = true)]
+    pub dry_run: bool,
+}
+
+impl Cli {
+    /// Load the config file and apply command line overrides
+    pub fn load_config(&self) -> Result<Config> {
+        let mut config = Config::from_file(&self.config)?;
+        if let Some(data_dir) = &self.data_dir {
+            config.data_dir = data_dir.clone();
+        }
+        Ok(config)
+    }
+}
+
+#[derive(Subcommand)]
+pub enum CliCommand {
+    /// Run the watcher (default)
+    Run,
+    /// Read balances of all EVM networks and print them, without alerts or saving state
+    Check {
+        /// Check once and exit; otherwise repeat every interval_secs
+        #[arg(long)]
+        once: bool,
+    },
+    /// Check the config file and print every problem found, then exit
+    ValidateConfig {
+        /// Skip checks that need the network: RPC nodes, token contracts and the Telegram token
+        #[arg(long)]
+        offline: bool,
+    },
+    /// Export current balances or daily history snapshots as CSV or XLSX
+    Export {
+        /// What to export
+        #[arg(value_enum, default_value = "balances")]
+        what: ExportWhat,
+        /// First day of history to export (YYYY-MM-DD)
+        #[arg(long, required_if_eq("what", "history"))]
+        from: Option<NaiveDate>,
+        /// Last day of history to export (YYYY-MM-DD), defaults to today
+        #[arg(long)]
+        to: Option<NaiveDate>,
+        /// Output file, stdout if omitted
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// File format
+        #[arg(long, value_enum, default_value = "csv")]
+        format: ExportFileFormat,
+    },
+    /// Send a test alert, a synthetic balance change and a sample report to every configured channel
+    SendTestAlert,
+    /// Upgrade state files in data_dir to the current schema version, then exit
+    Migrate,
+    /// Write all state (balances, chats, alert states, audit log, history) to one gzip-compressed backup file
+    Backup {
+        /// Backup file to write
+        path: PathBuf,
+    },
+    /// Replace the state with a backup file; stop the watcher first
+    Restore {
+        /// Backup file written by `backup`
+        path: PathBuf,
+    },
+    /// Encrypt a secret read from stdin with encryption.key, for use as an `enc:v1:` config value
+    EncryptSecret,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputMode {
+    /// Logs and the startup banner
+    Text,
+    /// One JSON event per line; logs go to stderr
+    JsonStream,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum ExportWhat {
+    Balances,
+    History,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum ExportFileFormat {
+    Csv,
+    Xlsx,
+}
+
+/// Run a command
+pub async fn execute(command: CliCommand, cli: &Cli) -> Result<()> {
+    match command {
+        CliCommand::Run => run(cli.load_config()?, cli.output, cli.dry_run).await,
+        CliCommand::Check { once } => {
+            let config = cli.load_config()?;
+            loop {
+                check(&config).await?;
+                if once {
+                    return Ok(());
+                }
+                tokio::time::sleep(config.interval).await;
+            }
+        }
+        // Diagnostics report a broken config instead of stopping at its first error
+        CliCommand::ValidateConfig { offline } => validate_config(&cli.config, offline).await,
+        CliCommand::Export { what, from, to, output, format } => {
+            let kind = match what {
+                ExportWhat::Balances => ExportKind::Balances,
+                ExportWhat::History => {
+                    let from = from.ok_or_else(|| eyre::eyre!("--from is required for history exports"))?;
+                    ExportKind::history(from, to.unwrap_or_else(|| Local::now().date_naive()))?
+                }
+            };
+            let format = match format {
+                ExportFileFormat::Csv => ExportFormat::Csv,
+                ExportFileFormat::Xlsx => ExportFormat::Xlsx,
+            };
+            export(&cli.load_config()?, kind, format, output).await
+        }
+        CliCommand::SendTestAlert => send_test_alert(&cli.load_config()?).await,
+        CliCommand::Migrate => migrate(&cli.load_config()?).await,
+        CliCommand::Backup { path } => backup(&cli.load_config()?, &path).await,
+        CliCommand::Restore { path } => restore(&cli.load_config()?, &path).await,
+        CliCommand::EncryptSecret => encrypt_secret(&cli.load_config()?),
+    }
+}
+
+/// Read and print balances of every EVM network once; fails if any read failed
+async fn check(config: &Config) -> Result<()> {
+    let mut failed = 0;
+    for network in &config.networks {
+        println!("🌐 {} (Chain ID: {})", network.name, network.chain_id);
+
+        let provider_config = FallbackConfig::new(network.resolved_rpc_nodes(), config.active_transport_count)
+            .with_timeout(config.rpc_timeout_for(network))
+            .with_strategy(config.rpc_strategy_for(network));
+        let monitor = BalanceMonitor::new(create_fallback_provider(provider_config)?, balance_monitor_config(config, network));
+
+        for result in monitor.check(network.name.clone(), network.chain_id).await {
+            match result {
+                Ok(info) => {
+                    let mut assets = vec![format!("{} {}", info.eth_formatted, info.native_symbol)];
+                    assets.extend(info.token_balances.iter().map(|token| format!("{} {}", token.formatted, token.alias)));
+                    assets.extend(info.nft_balances.iter().map(|nft| format!("{} {}", nft.count, nft.label())));
+                    println!("  📍 {} ({:?}): {}", info.alias, info.address, assets.join(", "));
+                }
+                Err(e) => {
+                    failed += 1;
+                    println!("  ❌ {}", e);
+                }
+            }
+        }
+    }
+
+    if failed > 0 {
+        eyre::bail!("{} balance read(s) failed", failed);
+    }
+    Ok(())
+}
+
+/// Print every problem of the config file; fails if any of them is an error
+async fn validate_config(config_path: &str, offline: bool) -> Result<()> {
+    let content = std::fs::read_to_string(config_path)?;
+    let diagnostics = diagnose_config(&content, offline).await;
+    for diagnostic in &diagnostics {
+        println!("{}", diagnostic);
+    }
+
+    let errors = diagnostics.iter().filter(|d| d.is_error()).count();
+    if errors > 0 {
+        eyre::bail!("{} problem(s) found in {}", errors, config_path);
+    }
+
+    let config = Config::from_file(config_path)?;
+    let addresses: usize = config.networks.iter().map(|network| network.addresses.len()).sum();
+    let tokens: usize = config.networks.iter().map(|network| network.tokens.len()).sum();
+    println!("✅ Config is valid");
+    println!("   {} EVM network(s), {} address(es), {} token(s)", config.networks.len(), addresses, tokens);
+    if !config.tron.is_empty() || !config.cosmos.is_empty() {
+        println!("   {} Tron network(s), {} Cosmos chain(s)", config.tron.len(), config.cosmos.len());
+    }
+    Ok(())
+}
+
+/// Write an export to `output`, or stdout
+async fn export(config: &Config, kind: ExportKind, format: ExportFormat, output: Option<PathBuf>) -> Result<()> {
+    let file = match kind {
+        ExportKind::Balances => {
+            let backend = create_state_backend(&config.storage, &config.data_dir, config.state_cipher()?).await?;
+            let storage = backend.load_balances().await?;
+            export_balances(&storage.balances.values().cloned().collect::<Vec<_>>(), format)?
+        }
+        ExportKind::History { from, to } => {
+            let history = load_history(config)?.ok_or_else(|| eyre::eyre!("history export needs history.enabled: true"))?;
+            export_history(&history, from, to, format)?
+        }
+    };
+
+    match output {
+        Some(path) => {
+            std::fs::write(&path, file)?;
+            eprintln!("Wrote {}", path.display());
+        }
+        None => std::io::Write::write_all(&mut std::io::stdout(), &file)?,
+    }
+    Ok(())
+}
+
+/// Send a test alert, a synthetic balance change and a sample report through each channel
+/// and report which ones failed
+async fn send_test_alert(config: &Config) -> Result<()> {
+    let mut channels: Vec<Box<dyn Notifier>> = Vec::new();
+    if config.telegram_configs().next().is_some() {
+        let backend = create_state_backend(&config.storage, &config.data_dir, config.state_cipher()?).await?;
+        let storage = Arc::new(RwLock::new(backend.load_balances().await?));
+        for (telegram_config, bot_name) in config.telegram_chat_stores() {
+            let telegram = TelegramNotifier::new(telegram_config, bot_name, Arc::clone(&storage), Arc::clone(&backend)).await;
+            println!("{}: {} registered chat(s)", telegram.name(), telegram.get_registered_chats_count().await);
+            channels.push(Box::new(telegram));
+        }
+    }
+    channels.extend(webhook_notifiers(config));
+    if channels.is_empty() {
+        eyre::bail!("no notification channels configured");
+    }
+
+    // Changes are shown on the first network, so chats subscribed to it receive them
+    let (network_name, chain_id) = config.networks.first().map_or(("Test network", 0), |n| (n.name.as_str(), n.chain_id));
+    let alert = MonitorAlert::test("`Oxwatcher send-test-alert`");
+    let changes = test_change_alert(network_name, chain_id);
+    let report = test_report(network_name, chain_id);
+    let mut failed = 0;
+    for channel in &channels {
+        let results = [
+            ("alert", channel.send_alert(&alert).await),
+            ("balance change", channel.send_change_alert(&changes).await),
+            ("report", channel.send_report(&report).await),
+        ];
+        for (what, result) in results {
+            match result {
+                Ok(()) => println!("✅ {} {}", channel.name(), what),
+                Err(e) => {
+                    failed += 1;
+                    println!("❌ {} {}: {}", channel.name(), what, e);
+                }
+            }
+        }
+    }
+
+    if failed > 0 {
+        eyre::bail!("{} test message(s) failed", failed);
+    }
+    Ok(())
+}
+
+/// Upgrade the stored state to the current schema and print what changed
+async fn migrate(config: &Config) -> Result<()> {
+    let backend = create_state_backend(&config.storage, &config.data_dir, config.state_cipher()?).await?;
+    let upgraded = backend.migrate_state().await?;
+    if upgraded.is_empty() {
+        println!("✅ {} state is up to date", backend.name());
+    }
+    for line in upgraded {
+        println!("⬆️  {}", line);
+    }
+    Ok(())
+}
+
+/// Write a backup of all state to `path`
+async fn backup(config: &Config, path: &Path) -> Result<()> {
+    let backend = create_state_backend(&config.storage, &config.data_dir, config.state_cipher()?).await?;
+    let backup = StateBackup::collect(backend.as_ref(), &config.telegram_bot_names(), &config.history_path()).await?;
+    backup.save_to_file(path, config.state_cipher()?.as_ref())?;
+    println!("✅ Backed up {} to {}", backup.summary(), path.display());
+    Ok(())
+}
+
+/// Replace the state with the backup at `path`. The instance lease is held meanwhile,
+/// so the restore fails instead of racing a running watcher.
+async fn restore(config: &Config, path: &Path) -> Result<()> {
+    let backup = StateBackup::load_from_file(path, config.state_cipher()?.as_ref())?;
+    let backend = create_state_backend(&config.storage, &config.data_dir, config.state_cipher()?).await?;
+    let election = config.leader_election.clone().unwrap_or_default();
+    let lease = InstanceLease::new(Arc::clone(&backend), &election);
+    lease.acquire(false).await?;
+    let history_path = config.history_path();
+    let restored = tokio::select! {
+        restored = backup.restore(backend.as_ref(), &history_path) => restored,
+        lost = lease.hold() => Err(lost),
+    };
+    lease.release().await;
+    restored?;
+    println!("✅ Restored {} into {} state", backup.summary(), backend.name());
+    Ok(())
+}
+
+/// Print the `enc:v1:` form of the first line of stdin
+fn encrypt_secret(config: &Config) -> Result<()> {
+    let cipher = config.state_cipher()?.ok_or_else(|| eyre::eyre!("encrypt-secret needs encryption.key in the config"))?;
+    let mut secret = String::new();
+    std::io::stdin().read_line(&mut secret)?;
+    let secret = secret.trim_end_matches(['\r', '\n']);
+    if secret.is_empty() {
+        eyre::bail!("no secret on stdin");
+    }
+    println!("{}", cipher.encrypt_secret(secret)?);
+    Ok(())
+}
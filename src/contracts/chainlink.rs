@@ -0,0 +1,17 @@
+use alloy::sol;
+
+sol! {
+    #[sol(rpc)]
+    #[derive(Debug)]
+    interface IAggregatorV3 {
+        function decimals() external view returns (uint8);
+        function description() external view returns (string);
+        function latestRoundData() external view returns (
+            uint80 roundId,
+            int256 answer,
+            uint256 startedAt,
+            uint256 updatedAt,
+            uint80 answeredInRound
+        );
+    }
+}
@@ -0,0 +1,10 @@
+use alloy::sol;
+
+sol! {
+    #[sol(rpc)]
+    #[derive(Debug)]
+    interface IERC1155 {
+        function balanceOf(address account, uint256 id) external view returns (uint256);
+        function balanceOfBatch(address[] accounts, uint256[] ids) external view returns (uint256[]);
+    }
+}
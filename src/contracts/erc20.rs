@@ -9,5 +9,7 @@ sol! {
         function symbol() external view returns (string);
         function name() external view returns (string);
         function transfer(address to, uint256 amount) external returns (bool);
+
+        event Transfer(address indexed from, address indexed to, uint256 value);
     }
 }
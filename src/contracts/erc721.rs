@@ -0,0 +1,12 @@
+use alloy::sol;
+
+sol! {
+    #[sol(rpc)]
+    #[derive(Debug)]
+    interface IERC721 {
+        function balanceOf(address owner) external view returns (uint256);
+        function ownerOf(uint256 tokenId) external view returns (address);
+        function name() external view returns (string);
+        function symbol() external view returns (string);
+    }
+}
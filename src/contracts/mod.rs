@@ -1,3 +1,17 @@
+mod chainlink;
+mod erc1155;
 mod erc20;
+mod erc721;
+mod multicall;
+mod ownable;
+mod safe;
+mod steth;
 
+pub use chainlink::IAggregatorV3;
+pub use erc1155::IERC1155;
 pub use erc20::IERC20;
+pub use erc721::IERC721;
+pub use multicall::{IMulticall3, MULTICALL3_ADDRESS};
+pub use ownable::IOwnable;
+pub use safe::ISafe;
+pub use steth::IStETH;
@@ -0,0 +1,9 @@
+use alloy::sol;
+
+sol! {
+    #[sol(rpc)]
+    #[derive(Debug)]
+    interface IOwnable {
+        function owner() external view returns (address);
+    }
+}
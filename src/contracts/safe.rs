@@ -0,0 +1,11 @@
+use alloy::sol;
+
+sol! {
+    #[sol(rpc)]
+    #[derive(Debug)]
+    interface ISafe {
+        function getOwners() external view returns (address[]);
+        function getThreshold() external view returns (uint256);
+        function nonce() external view returns (uint256);
+    }
+}
@@ -0,0 +1,10 @@
+use alloy::sol;
+
+sol! {
+    #[sol(rpc)]
+    #[derive(Debug)]
+    interface IStETH {
+        function sharesOf(address account) external view returns (uint256);
+        function getPooledEthByShares(uint256 sharesAmount) external view returns (uint256);
+    }
+}
@@ -0,0 +1,231 @@
+use crate::config::{expand_env, Config};
+use crate::contracts::IERC20;
+use crate::providers::{create_fallback_provider, node_chain_id, FallbackConfig};
+use alloy::primitives::Address;
+use serde_yaml::Value;
+use std::fmt;
+use std::time::Duration;
+use teloxide::prelude::*;
+use teloxide::RequestError;
+
+/// How long to wait for the Telegram API
+const TELEGRAM_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Config keys under `networks` and `address_books` whose values are EVM addresses
+const ADDRESS_KEYS: [&str; 3] = ["address", "chainlink_feed", "multicall_address"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    /// The watcher would fail or misbehave
+    Error,
+    /// Works, but likely not what was intended
+    Warning,
+}
+
+/// One problem found in a config file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub message: String,
+}
+
+impl Diagnostic {
+    pub fn error(message: impl Into<String>) -> Self {
+        Self { severity: Severity::Error, message: message.into() }
+    }
+
+    pub fn warning(message: impl Into<String>) -> Self {
+        Self { severity: Severity::Warning, message: message.into() }
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.severity {
+            Severity::Error => write!(f, "❌ {}", self.message),
+            Severity::Warning => write!(f, "⚠️ {}", self.message),
+        }
+    }
+}
+
+/// Check every problem of a config file: YAML syntax, address checksums and
+/// validation rules, then unless `offline` RPC nodes, token contracts and the
+/// Telegram bot token
+pub async fn diagnose_config(content: &str, offline: bool) -> Vec<Diagnostic> {
+    let mut diagnostics = match serde_yaml::from_str::<Value>(content) {
+        Ok(mut value) => match expand_env(&mut value) {
+            Ok(()) => address_diagnostics(&value),
+            Err(e) => return vec![Diagnostic::error(e.to_string())],
+        },
+        // Config::parse below reports the syntax error
+        Err(_) => Vec::new(),
+    };
+
+    let config = match Config::parse(content) {
+        Ok(config) => config,
+        Err(e) => {
+            let mut message = e.to_string();
+            // YAML reads unquoted 0x values that fit in 128 bits as integers
+            if message.contains("invalid type: integer") {
+                message.push_str(" (quote hex values such as addresses)");
+            }
+            diagnostics.push(Diagnostic::error(message));
+            return diagnostics;
+        }
+    };
+    diagnostics.extend(config.problems().into_iter().map(Diagnostic::error));
+
+    if !offline {
+        diagnostics.extend(online_diagnostics(&config).await);
+    }
+    diagnostics
+}
+
+/// Check that EVM addresses are quoted, valid and EIP-55 checksummed. Works on the
+/// YAML value since parsed addresses no longer carry their original case.
+pub fn address_diagnostics(yaml: &Value) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    if let Value::Mapping(root) = yaml {
+        for section in ["address_books", "networks"] {
+            if let Some(value) = root.get(section) {
+                collect_address_diagnostics(value, section, &mut diagnostics);
+            }
+        }
+    }
+    diagnostics
+}
+
+fn collect_address_diagnostics(value: &Value, path: &str, diagnostics: &mut Vec<Diagnostic>) {
+    match value {
+        Value::Mapping(map) => {
+            for (key, value) in map {
+                let Some(key) = key.as_str() else { continue };
+                let path = format!("{}.{}", path, key);
+                if ADDRESS_KEYS.contains(&key) {
+                    diagnostics.extend(check_address(&path, value));
+                } else {
+                    collect_address_diagnostics(value, &path, diagnostics);
+                }
+            }
+        }
+        Value::Sequence(items) => {
+            for (i, item) in items.iter().enumerate() {
+                collect_address_diagnostics(item, &format!("{}[{}]", path, i), diagnostics);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn check_address(path: &str, value: &Value) -> Option<Diagnostic> {
+    let raw = match value {
+        Value::String(raw) => raw,
+        Value::Null => return None,
+        // Unquoted 0x values that fit in an integer are read as numbers by YAML
+        Value::Number(_) => return Some(Diagnostic::error(format!("{}: address must be quoted", path))),
+        _ => return Some(Diagnostic::error(format!("{}: expected an address", path))),
+    };
+
+    if Address::parse_checksummed(raw, None).is_ok() {
+        return None;
+    }
+    let Ok(address) = raw.parse::<Address>() else {
+        return Some(Diagnostic::error(format!("{}: '{}' is not a valid address", path, raw)));
+    };
+
+    let hex = raw.trim_start_matches("0x");
+    let mixed_case = hex.chars().any(|c| c.is_ascii_lowercase()) && hex.chars().any(|c| c.is_ascii_uppercase());
+    Some(if mixed_case {
+        Diagnostic::error(format!("{}: checksum mismatch in {}, expected {} (possible typo)", path, raw, address))
+    } else {
+        Diagnostic::warning(format!("{}: {} is not checksummed, use {}", path, raw, address))
+    })
+}
+
+/// Query every RPC node's chain ID, each token's decimals() and symbol(), and the
+/// Telegram bot behind the token
+pub async fn online_diagnostics(config: &Config) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+
+    for network in &config.networks {
+        let nodes = network.resolved_rpc_nodes();
+        let mut reachable = false;
+        for node in &nodes {
+            let host = node.url.host_str().unwrap_or_default();
+            match node_chain_id(node).await {
+                Ok(chain_id) if chain_id != network.chain_id => diagnostics.push(Diagnostic::error(format!(
+                    "network '{}': RPC node {} reports chain ID {} instead of {}",
+                    network.name, host, chain_id, network.chain_id
+                ))),
+                Ok(_) => reachable = true,
+                Err(e) => diagnostics.push(Diagnostic::error(format!(
+                    "network '{}': RPC node {} is unreachable: {}",
+                    network.name, host, e
+                ))),
+            }
+        }
+        for node in &network.resolved_archive_rpc_nodes() {
+            let host = node.url.host_str().unwrap_or_default();
+            match node_chain_id(node).await {
+                Ok(chain_id) if chain_id != network.chain_id => diagnostics.push(Diagnostic::error(format!(
+                    "network '{}': archive RPC node {} reports chain ID {} instead of {}",
+                    network.name, host, chain_id, network.chain_id
+                ))),
+                Ok(_) => {}
+                Err(e) => diagnostics.push(Diagnostic::error(format!(
+                    "network '{}': archive RPC node {} is unreachable: {}",
+                    network.name, host, e
+                ))),
+            }
+        }
+        if !reachable || network.tokens.is_empty() {
+            continue;
+        }
+
+        let provider_config = FallbackConfig::new(nodes, config.active_transport_count)
+            .with_timeout(config.rpc_timeout_for(network))
+            .with_strategy(config.rpc_strategy_for(network));
+        let provider = match create_fallback_provider(provider_config) {
+            Ok(provider) => provider,
+            Err(e) => {
+                diagnostics.push(Diagnostic::error(format!("network '{}': {}", network.name, e)));
+                continue;
+            }
+        };
+        for token in &network.tokens {
+            let contract = IERC20::new(token.address, &provider);
+            if let Err(e) = contract.decimals().call().await {
+                diagnostics.push(Diagnostic::error(format!(
+                    "network '{}': token '{}' ({}) doesn't answer decimals(): {}",
+                    network.name, token.alias, token.address, e
+                )));
+            }
+            if let Err(e) = contract.symbol().call().await {
+                diagnostics.push(Diagnostic::error(format!(
+                    "network '{}': token '{}' ({}) doesn't answer symbol(): {}",
+                    network.name, token.alias, token.address, e
+                )));
+            }
+        }
+    }
+
+    for telegram in config.telegram_configs() {
+        let label = telegram.channel_name().to_lowercase();
+        match tokio::time::timeout(TELEGRAM_TIMEOUT, Bot::new(&telegram.bot_token).get_me()).await {
+            Ok(Ok(_)) => {}
+            Ok(Err(RequestError::Api(e))) => {
+                diagnostics.push(Diagnostic::error(format!("{} bot_token was rejected: {}", label, e)))
+            }
+            // Other errors may contain the request URL, which includes the token
+            Ok(Err(_)) | Err(_) => {
+                diagnostics.push(Diagnostic::error(format!("couldn't reach the Telegram API to check {} bot_token", label)))
+            }
+        }
+    }
+
+    diagnostics
+}
@@ -0,0 +1,71 @@
+use eyre::{eyre, Result};
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::rand::{SecureRandom, SystemRandom};
+use std::fmt;
+
+/// Prefix of encrypted config values, e.g. `bot_token: "enc:v1:9f2c..."`
+pub const ENCRYPTED_PREFIX: &str = "enc:v1:";
+
+/// AES-256-GCM key for data at rest: registered chats, backups and `enc:v1:` config
+/// values. Ciphertexts are hex of a random 96-bit nonce followed by the sealed data.
+#[derive(Clone)]
+pub struct StateCipher {
+    key: LessSafeKey,
+}
+
+impl StateCipher {
+    /// Key from 64 hex characters (32 bytes), e.g. the output of `openssl rand -hex 32`
+    pub fn from_hex(key: &str) -> Result<Self> {
+        let bytes = hex::decode(key.trim()).map_err(|_| eyre!("encryption key must be 64 hex characters"))?;
+        if bytes.len() != 32 {
+            return Err(eyre!("encryption key must be 64 hex characters (32 bytes), got {} bytes", bytes.len()));
+        }
+        let key = UnboundKey::new(&AES_256_GCM, &bytes).map_err(|_| eyre!("invalid encryption key"))?;
+        Ok(Self { key: LessSafeKey::new(key) })
+    }
+
+    /// Encrypt `plaintext` with a fresh random nonce
+    pub fn encrypt(&self, plaintext: &[u8]) -> Result<String> {
+        let mut nonce = [0u8; NONCE_LEN];
+        SystemRandom::new().fill(&mut nonce).map_err(|_| eyre!("no randomness available for the encryption nonce"))?;
+
+        let mut sealed = plaintext.to_vec();
+        self.key
+            .seal_in_place_append_tag(Nonce::assume_unique_for_key(nonce), Aad::empty(), &mut sealed)
+            .map_err(|_| eyre!("encryption failed"))?;
+        Ok(format!("{}{}", hex::encode(nonce), hex::encode(sealed)))
+    }
+
+    /// Decrypt the output of `encrypt`; fails for another key or tampered data
+    pub fn decrypt(&self, ciphertext: &str) -> Result<Vec<u8>> {
+        let bytes = hex::decode(ciphertext.trim()).map_err(|_| eyre!("encrypted data is not hex"))?;
+        if bytes.len() < NONCE_LEN {
+            return Err(eyre!("encrypted data is truncated"));
+        }
+        let (nonce, sealed) = bytes.split_at(NONCE_LEN);
+        let nonce = Nonce::try_assume_unique_for_key(nonce).map_err(|_| eyre!("invalid nonce"))?;
+
+        let mut sealed = sealed.to_vec();
+        let plaintext = self.key
+            .open_in_place(nonce, Aad::empty(), &mut sealed)
+            .map_err(|_| eyre!("decryption failed: wrong encryption key or corrupted data"))?;
+        Ok(plaintext.to_vec())
+    }
+
+    /// Encrypted config value: `enc:v1:` followed by the ciphertext
+    pub fn encrypt_secret(&self, secret: &str) -> Result<String> {
+        Ok(format!("{}{}", ENCRYPTED_PREFIX, self.encrypt(secret.as_bytes())?))
+    }
+
+    /// Plain text of an `enc:v1:` config value
+    pub fn decrypt_secret(&self, value: &str) -> Result<String> {
+        let ciphertext = value.strip_prefix(ENCRYPTED_PREFIX).ok_or_else(|| eyre!("value doesn't start with {}", ENCRYPTED_PREFIX))?;
+        String::from_utf8(self.decrypt(ciphertext)?).map_err(|_| eyre!("decrypted value is not UTF-8"))
+    }
+}
+
+impl fmt::Debug for StateCipher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("StateCipher(AES-256-GCM)")
+    }
+}
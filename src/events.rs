@@ -0,0 +1,184 @@
+use crate::logger::BalanceChangeSummary;
+use crate::monitoring::BalanceInfo;
+use crate::storage::AuditEntry;
+use serde::Serialize;
+use serde_json::Value;
+use std::io::{self, Write};
+use std::sync::Mutex;
+use tokio::sync::broadcast::{self, error::RecvError};
+use tracing::warn;
+
+/// Events buffered per subscriber; a subscriber falling further behind skips the oldest
+const BUS_CAPACITY: usize = 1024;
+
+/// Something the watcher did, published on the [`EventBus`] and written to the JSON event
+/// stream. The `event` tag and field names are a stable interface for tools reading the stream.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum MonitorEvent {
+    /// A network's check cycle started
+    CheckStarted {
+        network: String,
+        /// Absent for Cosmos chains
+        #[serde(skip_serializing_if = "Option::is_none")]
+        chain_id: Option<u64>,
+    },
+    /// Balances of one address were read
+    BalanceFetched(BalanceInfo),
+    /// A network's check cycle finished
+    CheckCompleted {
+        network: String,
+        /// Absent for Cosmos chains
+        #[serde(skip_serializing_if = "Option::is_none")]
+        chain_id: Option<u64>,
+        /// Addresses read successfully
+        addresses: usize,
+        /// Addresses that couldn't be read
+        errors: usize,
+        /// Last error of the cycle
+        #[serde(skip_serializing_if = "Option::is_none")]
+        error: Option<String>,
+    },
+    /// An address's balances changed since the previous check
+    ChangeDetected {
+        network: String,
+        chain_id: u64,
+        alias: String,
+        address: String,
+        /// Changed assets as in webhook payloads
+        changes: Value,
+        nft_changes: Value,
+        transactions: Value,
+    },
+    /// An alert was delivered to a channel, or delivery failed
+    AlertSent {
+        channel: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        chat: Option<String>,
+        kind: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        network: Option<String>,
+        subject: String,
+        success: bool,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        error: Option<String>,
+    },
+    /// Balances of an address couldn't be read
+    RpcError {
+        network: String,
+        error: String,
+    },
+    /// An RPC node stopped answering, or answered again
+    RpcFailover {
+        network: String,
+        node: String,
+        up: bool,
+        /// Times the node started failing since the watcher started
+        failovers: u64,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        error: Option<String>,
+    },
+}
+
+impl MonitorEvent {
+    pub fn change_detected(changes: &BalanceChangeSummary) -> Self {
+        let json = changes.to_json();
+        Self::ChangeDetected {
+            network: changes.network_name.clone(),
+            chain_id: changes.chain_id,
+            alias: changes.alias.clone(),
+            address: changes.address.clone(),
+            changes: json["changes"].clone(),
+            nft_changes: json["nft_changes"].clone(),
+            transactions: json["transactions"].clone(),
+        }
+    }
+
+    pub fn alert_sent(entry: &AuditEntry) -> Self {
+        Self::AlertSent {
+            channel: entry.channel.clone(),
+            chat: entry.chat.clone(),
+            kind: entry.kind.clone(),
+            network: entry.network.clone(),
+            subject: entry.subject.clone(),
+            success: entry.success,
+            error: entry.error.clone(),
+        }
+    }
+
+    /// NDJSON line with the event and its Unix timestamp in seconds
+    pub fn to_line(&self, timestamp: u64) -> String {
+        #[derive(Serialize)]
+        struct Line<'a> {
+            timestamp: u64,
+            #[serde(flatten)]
+            event: &'a MonitorEvent,
+        }
+        serde_json::to_string(&Line { timestamp, event: self }).expect("events serialize to JSON")
+    }
+}
+
+/// Writes events as newline-delimited JSON (`--output json-stream`)
+pub struct EventStream {
+    writer: Mutex<Box<dyn Write + Send>>,
+}
+
+impl EventStream {
+    pub fn new(writer: Box<dyn Write + Send>) -> Self {
+        Self { writer: Mutex::new(writer) }
+    }
+
+    /// Stream to stdout; logs must go elsewhere so the stream stays parseable
+    pub fn stdout() -> Self {
+        Self::new(Box::new(io::stdout()))
+    }
+
+    /// Write one event line, flushed right away so readers see it immediately
+    pub fn emit(&self, event: &MonitorEvent) {
+        let line = event.to_line(chrono::Utc::now().timestamp() as u64);
+        let mut writer = self.writer.lock().unwrap_or_else(|e| e.into_inner());
+        if let Err(e) = writeln!(writer, "{}", line).and_then(|_| writer.flush()) {
+            warn!("Failed to write event: {}", e);
+        }
+    }
+
+    /// Write every event of a bus subscription until the bus is dropped
+    pub async fn run(self, mut events: broadcast::Receiver<MonitorEvent>) {
+        loop {
+            match events.recv().await {
+                Ok(event) => self.emit(&event),
+                Err(RecvError::Lagged(skipped)) => warn!("Event stream fell behind, {} events skipped", skipped),
+                Err(RecvError::Closed) => break,
+            }
+        }
+    }
+}
+
+/// Broadcast channel of [`MonitorEvent`]s. Monitors publish to it without knowing who
+/// listens; the JSON event stream and library users subscribe.
+#[derive(Clone)]
+pub struct EventBus {
+    sender: broadcast::Sender<MonitorEvent>,
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        Self { sender: broadcast::channel(BUS_CAPACITY).0 }
+    }
+
+    /// Receive events published from now on
+    pub fn subscribe(&self) -> broadcast::Receiver<MonitorEvent> {
+        self.sender.subscribe()
+    }
+
+    /// Publish an event to current subscribers; without subscribers it's dropped
+    pub fn publish(&self, event: MonitorEvent) {
+        let _ = self.sender.send(event);
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
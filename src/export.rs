@@ -0,0 +1,225 @@
+use crate::monitoring::BalanceInfo;
+use crate::storage::HistoryStore;
+use chrono::{NaiveDate, NaiveTime};
+use eyre::{eyre, Result};
+use rust_xlsxwriter::{Format, Workbook};
+
+/// Longest range of days a history export covers
+pub const MAX_HISTORY_DAYS: i64 = 366;
+
+/// What to export
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportKind {
+    /// Latest balance of every address
+    Balances,
+    /// End-of-day balances (UTC) for each day in the range, inclusive
+    History { from: NaiveDate, to: NaiveDate },
+}
+
+impl ExportKind {
+    /// Parse "balances" or "history <from> <to>" with dates as YYYY-MM-DD
+    pub fn parse(args: &str) -> Result<Self> {
+        let parts: Vec<&str> = args.split_whitespace().collect();
+        match parts[..] {
+            [] | ["balances"] => Ok(Self::Balances),
+            ["history", from, to] => Self::history(parse_date(from)?, parse_date(to)?),
+            _ => Err(eyre!("expected `balances` or `history <from> <to>` (dates as YYYY-MM-DD)")),
+        }
+    }
+
+    /// History export of `from..=to`, at most `MAX_HISTORY_DAYS` long
+    pub fn history(from: NaiveDate, to: NaiveDate) -> Result<Self> {
+        if from > to {
+            return Err(eyre!("start date {} is after end date {}", from, to));
+        }
+        if (to - from).num_days() >= MAX_HISTORY_DAYS {
+            return Err(eyre!("history exports cover at most {} days", MAX_HISTORY_DAYS));
+        }
+        Ok(Self::History { from, to })
+    }
+
+    /// File name for the export, e.g. balances-2026-10-16.csv
+    pub fn file_name(&self, today: NaiveDate, format: ExportFormat) -> String {
+        match self {
+            Self::Balances => format!("balances-{}.{}", today, format.extension()),
+            Self::History { from, to } => format!("history-{}-to-{}.{}", from, to, format.extension()),
+        }
+    }
+}
+
+/// File format of an export
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ExportFormat {
+    #[default]
+    Csv,
+    /// Excel workbook with numbers stored as numbers
+    Xlsx,
+}
+
+impl ExportFormat {
+    /// Split a trailing "csv" or "xlsx" off command arguments, defaulting to CSV
+    pub fn split(args: &str) -> (Self, &str) {
+        let args = args.trim_end();
+        for (suffix, format) in [("xlsx", Self::Xlsx), ("csv", Self::Csv)] {
+            if let Some(rest) = args.strip_suffix(suffix).filter(|rest| rest.is_empty() || rest.ends_with(char::is_whitespace)) {
+                return (format, rest.trim_end());
+            }
+        }
+        (Self::Csv, args)
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Xlsx => "xlsx",
+        }
+    }
+}
+
+fn parse_date(value: &str) -> Result<NaiveDate> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|_| eyre!("invalid date '{}', expected YYYY-MM-DD", value))
+}
+
+const BALANCE_COLUMNS: [&str; 8] = ["network", "chain_id", "alias", "address", "asset", "balance", "usd_value", "block_number"];
+
+/// Columns holding numbers: written as numbers in XLSX exports, as they are in CSV
+const NUMBER_COLUMNS: [&str; 4] = ["chain_id", "balance", "usd_value", "block_number"];
+
+/// Spreadsheet apps run a cell starting with one of these as a formula
+const FORMULA_PREFIXES: [char; 6] = ['=', '+', '-', '@', '\t', '\r'];
+
+/// Quote a CSV field if it contains a delimiter, quote or line break. Text starting
+/// like a formula gets a leading `'`, so a token symbol or alias such as `=HYPERLINK(..)`
+/// shows as text instead of running when the file is opened in Excel.
+fn csv_field(value: &str) -> String {
+    let value = if value.starts_with(FORMULA_PREFIXES) { format!("'{}", value) } else { value.to_string() };
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+/// Exported rows under their column names
+struct Table {
+    columns: Vec<&'static str>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    /// Latest balances, sorted by network and alias
+    fn balances(balances: &[BalanceInfo]) -> Self {
+        let mut sorted: Vec<&BalanceInfo> = balances.iter().collect();
+        sorted.sort_by(|a, b| (&a.network_name, &a.alias).cmp(&(&b.network_name, &b.alias)));
+        Self { columns: BALANCE_COLUMNS.to_vec(), rows: sorted.into_iter().flat_map(balance_rows).collect() }
+    }
+
+    /// End-of-day (UTC) snapshots from history, one block of rows per day
+    fn history(history: &HistoryStore, from: NaiveDate, to: NaiveDate) -> Self {
+        let mut rows = Vec::new();
+        for day in from.iter_days().take_while(|day| *day <= to) {
+            let end_of_day = day.and_time(NaiveTime::MIN).and_utc().timestamp() + 24 * 60 * 60 - 1;
+            let snapshot = history.snapshot_at(end_of_day.max(0) as u64);
+            let mut balances: Vec<&BalanceInfo> = snapshot.balances.values().collect();
+            balances.sort_by(|a, b| (&a.network_name, &a.alias).cmp(&(&b.network_name, &b.alias)));
+            for balance in balances {
+                for row in balance_rows(balance) {
+                    rows.push([vec![day.to_string()], row].concat());
+                }
+            }
+        }
+        Self { columns: [&["date"][..], &BALANCE_COLUMNS].concat(), rows }
+    }
+
+    fn to_csv(&self) -> String {
+        let mut csv = format!("{}\n", self.columns.join(","));
+        for row in &self.rows {
+            let fields: Vec<String> = self.columns.iter()
+                .zip(row)
+                .map(|(name, field)| if NUMBER_COLUMNS.contains(name) { field.clone() } else { csv_field(field) })
+                .collect();
+            csv.push_str(&fields.join(","));
+            csv.push('\n');
+        }
+        csv
+    }
+
+    /// Single-sheet workbook; number columns hold numbers so they can be summed,
+    /// everything else is stored as text and never read as a formula
+    fn to_xlsx(&self) -> Result<Vec<u8>> {
+        let mut workbook = Workbook::new();
+        let sheet = workbook.add_worksheet();
+        let bold = Format::new().set_bold();
+        for (col, name) in self.columns.iter().enumerate() {
+            sheet.write_string_with_format(0, col as u16, *name, &bold)?;
+        }
+        for (row, fields) in self.rows.iter().enumerate() {
+            let row = row as u32 + 1;
+            for (col, (name, field)) in self.columns.iter().zip(fields).enumerate() {
+                let col = col as u16;
+                match field.parse::<f64>() {
+                    Ok(number) if NUMBER_COLUMNS.contains(name) => sheet.write_number(row, col, number)?,
+                    _ if field.is_empty() => continue,
+                    _ => sheet.write_string(row, col, field)?,
+                };
+            }
+        }
+        Ok(workbook.save_to_buffer()?)
+    }
+
+    fn encode(&self, format: ExportFormat) -> Result<Vec<u8>> {
+        match format {
+            ExportFormat::Csv => Ok(self.to_csv().into_bytes()),
+            ExportFormat::Xlsx => self.to_xlsx(),
+        }
+    }
+}
+
+/// One row per asset: the native currency, each token and each NFT holding
+fn balance_rows(balance: &BalanceInfo) -> Vec<Vec<String>> {
+    let usd = |value: Option<f64>| value.map(|v| format!("{:.2}", v)).unwrap_or_default();
+    let mut assets = vec![(balance.native_symbol.clone(), balance.eth_formatted.clone(), usd(balance.eth_usd_value))];
+    for token in &balance.token_balances {
+        assets.push((token.alias.clone(), token.formatted.clone(), usd(token.usd_value)));
+    }
+    for nft in &balance.nft_balances {
+        assets.push((nft.label(), nft.count.to_string(), String::new()));
+    }
+
+    let block = balance.block_number.map(|n| n.to_string()).unwrap_or_default();
+    assets
+        .into_iter()
+        .map(|(asset, amount, usd)| {
+            vec![
+                balance.network_name.clone(),
+                balance.chain_id.to_string(),
+                balance.alias.clone(),
+                format!("{:?}", balance.address),
+                asset,
+                amount,
+                usd,
+                block.clone(),
+            ]
+        })
+        .collect()
+}
+
+/// CSV of the given balances, sorted by network and alias
+pub fn balances_csv(balances: &[BalanceInfo]) -> String {
+    Table::balances(balances).to_csv()
+}
+
+/// CSV of end-of-day (UTC) snapshots from history, one block of rows per day
+pub fn history_csv(history: &HistoryStore, from: NaiveDate, to: NaiveDate) -> String {
+    Table::history(history, from, to).to_csv()
+}
+
+/// Export of the given balances in `format`
+pub fn export_balances(balances: &[BalanceInfo], format: ExportFormat) -> Result<Vec<u8>> {
+    Table::balances(balances).encode(format)
+}
+
+/// Export of end-of-day (UTC) snapshots from history in `format`
+pub fn export_history(history: &HistoryStore, from: NaiveDate, to: NaiveDate, format: ExportFormat) -> Result<Vec<u8>> {
+    Table::history(history, from, to).encode(format)
+}
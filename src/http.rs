@@ -0,0 +1,25 @@
+use reqwest::Client;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+/// Time allowed to open a connection to an API
+pub const HTTP_CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Time allowed for a whole request, from connecting to reading the response
+pub const HTTP_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Client shared by notifiers, price sources and the monitors' API clients. A stalled
+/// endpoint fails its request after the timeouts instead of holding up the caller, and
+/// clones share one connection pool.
+pub fn http_client() -> Client {
+    static CLIENT: OnceLock<Client> = OnceLock::new();
+    CLIENT
+        .get_or_init(|| {
+            Client::builder()
+                .connect_timeout(HTTP_CONNECT_TIMEOUT)
+                .timeout(HTTP_REQUEST_TIMEOUT)
+                .build()
+                .expect("HTTP client builds, as with Client::new()")
+        })
+        .clone()
+}
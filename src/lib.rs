@@ -1,15 +1,90 @@
+pub mod alerting;
+pub mod api;
 pub mod config;
 pub mod contracts;
+pub mod diagnostics;
+pub mod encryption;
+pub mod events;
+pub mod export;
+pub mod http;
+pub mod log_file;
 pub mod logger;
 pub mod monitoring;
+pub mod notifiers;
+pub mod pricing;
 pub mod providers;
 pub mod storage;
 pub mod telegram;
+pub mod watcher;
 
-pub use config::{AddressConfig, AlertSettings, Config, DailyReportConfig, NetworkConfig, TelegramConfig, TokenConfig};
-pub use contracts::IERC20;
-pub use logger::{compare_balances, log_balance_changes, log_balances, log_balances_json};
-pub use monitoring::{BalanceInfo, BalanceMonitor, BalanceMonitorConfig, TokenBalance};
-pub use providers::{create_fallback_provider, FallbackConfig};
-pub use storage::BalanceStorage;
-pub use telegram::TelegramNotifier;
+pub use alerting::{Comparison, Condition, Metric, Observation, RuleAlert, RuleEngine, RULE_ALERT_KIND};
+pub use api::{router, spawn_api_server, ApiState};
+pub use config::{
+    expand_env, AddressBookSource, AddressConfig, AddressType, AlertDedupConfig, AlertSettings, AlertSeverity,
+    AnomalyConfig, ApiConfig, BackupConfig, BasicAuthConfig, BeaconConfig, BudgetConfig, BudgetPeriod, Config,
+    ConfirmationTag, Confirmations, ContractWatchConfig, CorridorConfig, CosmosAddressConfig,
+    CosmosChainConfig, CosmosDenomConfig, DailyReportConfig, DiscordConfig, DiscordTarget, DrainedConfig, DryRunDir,
+    EncryptionConfig, EntityConfig, EscalationConfig, FeedConfig, GasConfig, GroupConfig, GroupingConfig,
+    HeartbeatConfig, HistoryConfig, IndexerConfig, LeaderElectionConfig, LogFileConfig, LogFormat,
+    LogRotation, LoggingConfig, MaintenanceWindowConfig, MatrixConfig, MessageTemplates, MonitorMode,
+    NetworkConfig, NftConfig, NftStandard, PagerDutyConfig, PriceSourceConfig, PricingConfig, RetryConfig,
+    Role, RollupConfig, RpcNodeConfig, RpcStrategy, RuleConfig, S3Config, SlackConfig, StorageConfig,
+    TelegramAccess, TelegramConfig, TelegramSilentConfig, TelegramTopicsConfig, ThresholdTier, ThrottleConfig,
+    TokenConfig, TokenDiscoveryConfig, TokenType, TronAddressConfig, TronConfig, TronTokenConfig,
+    TwilioConfig, TxLookupConfig, ValidatorConfig, ViewCallConfig, WatchlistConfig, WebhookConfig,
+};
+pub use contracts::{
+    IAggregatorV3, IMulticall3, IOwnable, ISafe, IStETH, IERC1155, IERC20, IERC721, MULTICALL3_ADDRESS,
+};
+pub use diagnostics::{address_diagnostics, diagnose_config, online_diagnostics, Diagnostic, Severity};
+pub use encryption::{StateCipher, ENCRYPTED_PREFIX};
+pub use events::{EventBus, EventStream, MonitorEvent};
+pub use export::{balances_csv, export_balances, export_history, history_csv, ExportFormat, ExportKind, MAX_HISTORY_DAYS};
+pub use http::{http_client, HTTP_CONNECT_TIMEOUT, HTTP_REQUEST_TIMEOUT};
+pub use log_file::RotatingFile;
+pub use logger::{
+    compare_balances, init_tracing, log_balance_changes, log_balance_check, log_balances, log_balances_json,
+    BalanceChange, BalanceChangeSummary, NftChange, TokenBalanceChange,
+};
+pub use monitoring::{
+    discover_tokens, drained_assets, format_gwei, format_tron_address, from_units, lookup_transactions,
+    outflow_since, parse_tron_address, parse_watchlist, read_contract_state, read_feed_round,
+    runway_threshold_eth, spawn_watchlist_reload, to_units, unexpected_destinations, ActiveMaintenance,
+    AddressRegistry, Anomaly, AnomalyDetector, AssetRunway, BalanceInfo, BalanceMonitor, BalanceMonitorConfig,
+    BeaconClient, ChangeStats, CheckContext, ContractChange, ContractState, ContractWatcher, CorridorEvent,
+    CorridorMonitor, CosmosClient, CosmosCoin, CosmosEvent, CosmosMonitor, CosmosStates, CustomAlert,
+    CustomCheck, CustomChecks, DrainedAsset, Entities, EntityEvent, EntityTotal, FeeRange, FeedIssue,
+    FeedMonitor, FeedRound, GasCrossings, GasLevel, GasPrice, GasTracker, GroupEvent, GroupMonitor,
+    GroupTotal, HealthTracker, InactivityEvent, InactivityMonitor, IndexerClient, IndexerTransfer,
+    MaintenanceSchedule, MaintenanceWindow, NetworkHealth, NftBalance, RollupEvent, RollupMonitor, Runway,
+    RunwayForecasts, SafeChange, SafeState, SafeWatcher, TokenBalance, TransferDirection, TronMonitor,
+    TxAttribution, ValidatorDetails, ValidatorEvent, ValidatorInfo, ValidatorMonitor, ViewCall, ViewCallLevel,
+    ViewCallMonitor, ViewCallUpdate, ViewValue, Watchlist, DEFAULT_DECIMALS, MAX_BATCH_SIZE,
+    TRON_MAINNET_CHAIN_ID,
+};
+pub use notifiers::{
+    fit_embed, render_template, spawn_daily_report_scheduler, spawn_heartbeat, split_message, test_change_alert,
+    test_report, AckOutcome, AlertDedup, AuditLog, AuditSubject, BalanceReport, ChangeDigest, DedupKey,
+    DiscordNotifier, DryRunNotifier, EscalationStep, Escalator, Heartbeat, LowBalanceAlert, LowBalanceTracker,
+    MatrixNotifier, MonitorAlert, NetChange, NodeFailovers, Notifier, NotifierSet, PagerDutyClient, SendQueue,
+    SlackNotifier, ThrottleStatus, TopUp, TopUpTargets, TwilioNotifier, WebhookNotifier, ACK_CALLBACK_PREFIX,
+    DRAINED_KIND, FLAGGED_KIND, HEARTBEAT_KIND, LOW_BALANCE_KIND, TEST_ALERT_KIND, TEST_WALLET_ALIAS,
+    UNEXPECTED_DESTINATION_KIND, UNKNOWN_DESTINATION_KIND,
+};
+pub use pricing::{
+    balance_value_usd, check_chainlink_round, format_usd, AssetPrices, EntityValue, Portfolio, PriceOracle, ValueTotal,
+};
+pub use providers::{
+    create_fallback_provider, create_node_provider, create_ws_provider, spawn_chain_id_checks,
+    verify_chain_ids, FallbackConfig, NetworkRpcStatus, NodeStatus, RateLimitedTransport, RateLimiter,
+    RpcHealth, RpcNode, TrackedTransport,
+};
+pub use storage::{
+    create_state_backend, migrate_file, migrate_state, prune_backups, schema_version, sign_v4, spawn_backups,
+    Acknowledgement, AlertStateStorage, AuditEntry, AuditQuery, BalanceStorage, ChatRegistration, ChatStorage,
+    ChatSubscription, CosmosAddressState, CosmosStateStorage, DynamicAddress, DynamicAddressStorage,
+    EscalationStorage, FileBackend, HistoryPoint, HistoryStore, InstanceLease, PostgresBackend, S3Client,
+    StateBackend, StateBackup, TrackedAlert, STATE_SCHEMA_VERSION,
+};
+pub use telegram::{BalanceView, TelegramNotifier};
+pub use watcher::{balance_monitor_config, load_history, webhook_notifiers, Watcher};
@@ -0,0 +1,119 @@
+use chrono::{DateTime, Local};
+use eyre::Result;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::config::LogRotation;
+
+impl LogRotation {
+    /// Period a time falls in; a new period starts a new file
+    fn period(&self, time: DateTime<Local>) -> String {
+        match self {
+            Self::Hourly => time.format("%Y-%m-%d-%H").to_string(),
+            Self::Daily => time.format("%Y-%m-%d").to_string(),
+            Self::Never => String::new(),
+        }
+    }
+}
+
+struct ActiveFile {
+    file: File,
+    size: u64,
+    period: String,
+}
+
+/// Log file that is rotated by time and size. The active file keeps its name;
+/// rotated files get a timestamp suffix and only the newest `max_files` are kept.
+pub struct RotatingFile {
+    path: PathBuf,
+    rotation: LogRotation,
+    max_bytes: Option<u64>,
+    max_files: usize,
+    active: Mutex<ActiveFile>,
+}
+
+impl RotatingFile {
+    pub fn open(path: impl AsRef<Path>, rotation: LogRotation, max_bytes: Option<u64>, max_files: usize) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let active = Self::open_active(&path, rotation)?;
+
+        Ok(Self {
+            path,
+            rotation,
+            max_bytes,
+            max_files,
+            active: Mutex::new(active),
+        })
+    }
+
+    fn open_active(path: &Path, rotation: LogRotation) -> io::Result<ActiveFile> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        let metadata = file.metadata()?;
+        // An existing file belongs to the period it was last written in,
+        // so a restart after midnight still rotates yesterday's file
+        let modified = metadata.modified().map(DateTime::<Local>::from).unwrap_or_else(|_| Local::now());
+        Ok(ActiveFile { file, size: metadata.len(), period: rotation.period(modified) })
+    }
+
+    /// Rotated files, oldest first
+    pub fn rotated_files(&self) -> Vec<PathBuf> {
+        let Some(name) = self.path.file_name().and_then(|n| n.to_str()) else {
+            return Vec::new();
+        };
+        let prefix = format!("{}.", name);
+        let dir = self.path.parent().filter(|p| !p.as_os_str().is_empty()).unwrap_or(Path::new("."));
+
+        let mut files: Vec<PathBuf> = fs::read_dir(dir)
+            .map(|entries| {
+                entries
+                    .filter_map(|entry| entry.ok())
+                    .filter(|entry| entry.file_name().to_str().is_some_and(|n| n.starts_with(&prefix)))
+                    .map(|entry| entry.path())
+                    .collect()
+            })
+            .unwrap_or_default();
+        files.sort();
+        files
+    }
+
+    fn rotate(&self, active: &mut ActiveFile) -> io::Result<()> {
+        active.file.flush()?;
+        let mut rotated = self.path.clone().into_os_string();
+        rotated.push(format!(".{}", Local::now().format("%Y-%m-%dT%H-%M-%S%.3f")));
+        fs::rename(&self.path, rotated)?;
+
+        let files = self.rotated_files();
+        let excess = files.len().saturating_sub(self.max_files);
+        for old in &files[..excess] {
+            let _ = fs::remove_file(old);
+        }
+
+        *active = Self::open_active(&self.path, self.rotation)?;
+        Ok(())
+    }
+}
+
+impl Write for &RotatingFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut active = self.active.lock().unwrap_or_else(|e| e.into_inner());
+
+        let new_period = active.period != self.rotation.period(Local::now());
+        let too_large = self.max_bytes.is_some_and(|max| active.size > 0 && active.size + buf.len() as u64 > max);
+        if new_period || too_large {
+            self.rotate(&mut active)?;
+        }
+
+        active.file.write_all(buf)?;
+        active.size += buf.len() as u64;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.active.lock().unwrap_or_else(|e| e.into_inner()).file.flush()
+    }
+}
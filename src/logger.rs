@@ -1,11 +1,76 @@
-use crate::monitoring::BalanceInfo;
+use crate::config::{LogFormat, LoggingConfig};
+use crate::log_file::RotatingFile;
+use crate::monitoring::{BalanceInfo, TransferDirection, TxAttribution};
 use crate::storage::BalanceStorage;
 use alloy::primitives::U256;
 use eyre::Result;
+use serde_json::{json, Value};
 use std::collections::HashMap;
+use std::io;
+use std::path::Path;
+use std::sync::Arc;
+use tracing::{debug, error, info};
+use tracing_subscriber::fmt::{self, MakeWriter};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{EnvFilter, Layer, Registry};
+
+type BoxedLayer = Box<dyn Layer<Registry> + Send + Sync>;
+
+/// Formatting layer for `format`, writing to `writer`
+fn format_layer<W>(format: LogFormat, writer: W, ansi: bool) -> BoxedLayer
+where
+    W: for<'w> MakeWriter<'w> + Send + Sync + 'static,
+{
+    match format {
+        LogFormat::Text => fmt::layer().with_target(false).with_ansi(ansi).with_writer(writer).boxed(),
+        LogFormat::Json => fmt::layer().json().with_current_span(true).with_span_list(true).with_writer(writer).boxed(),
+    }
+}
+
+/// Install the global tracing subscriber and return the console format in use.
+/// RUST_LOG overrides the configured level and LOG_FORMAT ("text" or "json") the format.
+/// With `logging.file`, logs are also written to a rotating file under `data_dir`.
+/// Console logs go to stderr with `to_stderr`, e.g. while stdout carries the event stream.
+pub fn init_tracing(config: &LoggingConfig, data_dir: &str, to_stderr: bool) -> Result<LogFormat> {
+    let filter = match EnvFilter::try_from_default_env() {
+        Ok(filter) => filter,
+        Err(_) => EnvFilter::try_new(&config.level)?,
+    };
+    let format = match std::env::var("LOG_FORMAT").ok().as_deref() {
+        None => config.format,
+        Some("text") => LogFormat::Text,
+        Some("json") => LogFormat::Json,
+        Some(other) => eyre::bail!("invalid LOG_FORMAT '{}', expected \"text\" or \"json\"", other),
+    };
+
+    let console = if to_stderr {
+        format_layer(format, io::stderr, true)
+    } else {
+        format_layer(format, io::stdout, true)
+    };
+    let mut layers = vec![console.with_filter(filter).boxed()];
+    if let Some(file_config) = &config.file {
+        let file = RotatingFile::open(
+            Path::new(data_dir).join(&file_config.name),
+            file_config.rotation,
+            file_config.max_size_mb.map(|mb| mb * 1024 * 1024),
+            file_config.max_files,
+        )?;
+        let file_filter = EnvFilter::try_new(&file_config.level)?;
+        layers.push(format_layer(format, Arc::new(file), false).with_filter(file_filter).boxed());
+    }
+
+    tracing_subscriber::registry()
+        .with(layers)
+        .try_init()
+        .map_err(|e| eyre::eyre!("failed to install log subscriber: {}", e))?;
+
+    Ok(format)
+}
 
 /// Represents a change in balance
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum BalanceChange {
     Increase,
     Decrease,
@@ -13,18 +78,89 @@ pub enum BalanceChange {
 }
 
 /// Token balance change details
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct TokenBalanceChange {
     pub alias: String,
     pub old_balance: U256,
     pub new_balance: U256,
     pub old_formatted: String,
     pub new_formatted: String,
+    /// Decimals the balances are denominated in
+    pub decimals: u8,
+    /// USD value of the new balance, if priced
+    pub usd_value: Option<f64>,
     pub change: BalanceChange,
 }
 
+impl TokenBalanceChange {
+    /// JSON representation used by webhooks and the HTTP API
+    pub fn to_json(&self) -> Value {
+        json!({
+            "asset": self.alias,
+            "direction": match self.change {
+                BalanceChange::Increase => "increase",
+                BalanceChange::Decrease => "decrease",
+                BalanceChange::NoChange => "none",
+            },
+            "old_balance": self.old_balance.to_string(),
+            "new_balance": self.new_balance.to_string(),
+            "old_formatted": self.old_formatted,
+            "new_formatted": self.new_formatted,
+            "usd_value": self.usd_value,
+            "diff": calculate_diff(&self.new_balance, &self.old_balance, self.decimals),
+            "percent": calculate_percent_change(&self.new_balance, &self.old_balance),
+        })
+    }
+}
+
+/// Change in an NFT holding
+#[derive(Debug, Clone)]
+pub struct NftChange {
+    /// Collection alias, with the token ID if tracked individually
+    pub label: String,
+    pub old_count: U256,
+    pub new_count: U256,
+}
+
+impl NftChange {
+    /// NFT entered a wallet that held none
+    pub fn entered(&self) -> bool {
+        self.old_count.is_zero() && !self.new_count.is_zero()
+    }
+
+    /// Wallet no longer holds any of the NFT
+    pub fn left(&self) -> bool {
+        !self.old_count.is_zero() && self.new_count.is_zero()
+    }
+
+    /// Short description, e.g. "left wallet (1 → 0)"
+    pub fn describe(&self) -> String {
+        let action = if self.entered() {
+            "entered wallet"
+        } else if self.left() {
+            "left wallet"
+        } else if self.new_count > self.old_count {
+            "increased"
+        } else {
+            "decreased"
+        };
+        format!("{} ({} → {})", action, self.old_count, self.new_count)
+    }
+
+    /// JSON representation used by webhooks and the HTTP API
+    pub fn to_json(&self) -> Value {
+        json!({
+            "nft": self.label,
+            "old_count": self.old_count.to_string(),
+            "new_count": self.new_count.to_string(),
+            "entered": self.entered(),
+            "left": self.left(),
+        })
+    }
+}
+
 /// Balance change summary for an address
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct BalanceChangeSummary {
     pub network_name: String,
     pub chain_id: u64,
@@ -32,6 +168,10 @@ pub struct BalanceChangeSummary {
     pub address: String,
     pub eth_change: Option<TokenBalanceChange>,
     pub token_changes: Vec<TokenBalanceChange>,
+    /// NFT holdings that changed
+    pub nft_changes: Vec<NftChange>,
+    /// Transactions behind the changes, if found
+    pub transactions: Vec<TxAttribution>,
 }
 
 impl BalanceChangeSummary {
@@ -44,7 +184,52 @@ impl BalanceChangeSummary {
         let tokens_changed = self.token_changes.iter()
             .any(|c| !matches!(c.change, BalanceChange::NoChange));
 
-        eth_changed || tokens_changed
+        eth_changed || tokens_changed || !self.nft_changes.is_empty()
+    }
+
+    /// JSON representation with only the changed assets
+    pub fn to_json(&self) -> Value {
+        json!({
+            "network": self.network_name,
+            "chain_id": self.chain_id,
+            "alias": self.alias,
+            "address": self.address,
+            "changes": self.changed_assets().map(TokenBalanceChange::to_json).collect::<Vec<_>>(),
+            "nft_changes": self.nft_changes.iter().map(NftChange::to_json).collect::<Vec<_>>(),
+            "transactions": self.transactions.iter().map(TxAttribution::to_json).collect::<Vec<_>>(),
+        })
+    }
+
+    /// Ignore changes of a token smaller than `percent` of its previous balance
+    pub fn ignore_changes_below(&mut self, alias: &str, percent: f64) {
+        for change in self.token_changes.iter_mut().filter(|c| c.alias == alias) {
+            let percent_change = calculate_percent_change(&change.new_balance, &change.old_balance);
+            if !change.old_balance.is_zero() && percent_change.abs() < percent {
+                change.change = BalanceChange::NoChange;
+            }
+        }
+    }
+
+    /// Iterate over ETH and token changes that are not `NoChange`
+    pub fn changed_assets(&self) -> impl Iterator<Item = &TokenBalanceChange> {
+        self.eth_change.iter()
+            .chain(self.token_changes.iter())
+            .filter(|c| !matches!(c.change, BalanceChange::NoChange))
+    }
+
+    /// Assets whose balance dropped without an outgoing transaction of them in
+    /// `transactions`: the lookup failed, the transfer was older than the blocks
+    /// searched, or it was an internal transaction. Any outgoing native transaction
+    /// accounts for a native drop, since token transfers pay gas.
+    pub fn unattributed_outflows(&self) -> Vec<&TokenBalanceChange> {
+        let sent = |asset: &str, with_value: bool| {
+            self.transactions.iter().any(|tx| {
+                tx.direction == TransferDirection::Outgoing && tx.asset == asset && !(with_value && tx.value.is_zero())
+            })
+        };
+        let native = self.eth_change.iter().filter(|_| !sent("ETH", false));
+        let tokens = self.token_changes.iter().filter(|c| !sent(&c.alias, true));
+        native.chain(tokens).filter(|c| matches!(c.change, BalanceChange::Decrease)).collect()
     }
 }
 
@@ -55,6 +240,7 @@ pub fn compare_balances(
 ) -> BalanceChangeSummary {
     let mut eth_change = None;
     let mut token_changes = Vec::new();
+    let mut nft_changes = Vec::new();
 
     if let Some(previous) = storage.get(&current.network_name, &current.alias) {
         // Compare ETH balance
@@ -67,11 +253,13 @@ pub fn compare_balances(
         };
 
         eth_change = Some(TokenBalanceChange {
-            alias: "ETH".to_string(),
+            alias: current.native_symbol.clone(),
             old_balance: previous.eth_balance,
             new_balance: current.eth_balance,
             old_formatted: previous.eth_formatted.clone(),
             new_formatted: current.eth_formatted.clone(),
+            decimals: current.native_decimals,
+            usd_value: current.eth_usd_value,
             change,
         });
 
@@ -82,9 +270,14 @@ pub fn compare_balances(
 
         for current_token in &current.token_balances {
             if let Some(previous_token) = previous_tokens.get(current_token.alias.as_str()) {
-                let change = if current_token.balance > previous_token.balance {
+                // Rebasing tokens compare shares, which only move on transfers
+                let (new_amount, old_amount) = match (current_token.shares, previous_token.shares) {
+                    (Some(new_shares), Some(old_shares)) => (new_shares, old_shares),
+                    _ => (current_token.balance, previous_token.balance),
+                };
+                let change = if new_amount > old_amount {
                     BalanceChange::Increase
-                } else if current_token.balance < previous_token.balance {
+                } else if new_amount < old_amount {
                     BalanceChange::Decrease
                 } else {
                     BalanceChange::NoChange
@@ -96,6 +289,8 @@ pub fn compare_balances(
                     new_balance: current_token.balance,
                     old_formatted: previous_token.formatted.clone(),
                     new_formatted: current_token.formatted.clone(),
+                    decimals: current_token.decimals,
+                    usd_value: current_token.usd_value,
                     change,
                 });
             } else {
@@ -106,6 +301,8 @@ pub fn compare_balances(
                     new_balance: current_token.balance,
                     old_formatted: "0".to_string(),
                     new_formatted: current_token.formatted.clone(),
+                    decimals: current_token.decimals,
+                    usd_value: current_token.usd_value,
                     change: if current_token.balance > U256::ZERO {
                         BalanceChange::Increase
                     } else {
@@ -114,6 +311,21 @@ pub fn compare_balances(
                 });
             }
         }
+
+        // Compare NFT holdings (newly configured collections have no baseline yet)
+        for current_nft in &current.nft_balances {
+            let previous_nft = previous.nft_balances.iter()
+                .find(|n| n.alias == current_nft.alias && n.token_id == current_nft.token_id);
+            if let Some(previous_nft) = previous_nft {
+                if previous_nft.count != current_nft.count {
+                    nft_changes.push(NftChange {
+                        label: current_nft.label(),
+                        old_count: previous_nft.count,
+                        new_count: current_nft.count,
+                    });
+                }
+            }
+        }
     }
 
     BalanceChangeSummary {
@@ -123,6 +335,8 @@ pub fn compare_balances(
         address: format!("{:?}", current.address),
         eth_change,
         token_changes,
+        nft_changes,
+        transactions: Vec::new(),
     }
 }
 
@@ -132,85 +346,88 @@ pub fn log_balance_changes(change_summary: &BalanceChangeSummary) {
         return;
     }
 
-    println!(
+    info!(
+        network = %change_summary.network_name,
+        alias = %change_summary.alias,
         "🔔 Balance Alert [{}]: {} ({})",
         change_summary.network_name,
         change_summary.alias,
         shorten_address(&change_summary.address)
     );
 
-    // Log ETH changes
-    if let Some(eth) = &change_summary.eth_change {
-        if !matches!(eth.change, BalanceChange::NoChange) {
-            let (symbol, sign) = match eth.change {
-                BalanceChange::Increase => ("📈", "+"),
-                BalanceChange::Decrease => ("📉", ""),
-                BalanceChange::NoChange => ("  ", ""),
-            };
-
-            let diff = calculate_diff(&eth.new_balance, &eth.old_balance);
-            let percent = calculate_percent_change(&eth.new_balance, &eth.old_balance);
-
-            if percent.abs() >= 0.01 {
-                println!("   {} ETH: {}{} ({:+.2}%) | {} → {}",
-                    symbol,
-                    sign,
-                    diff,
-                    percent,
-                    eth.old_formatted,
-                    eth.new_formatted
-                );
-            } else {
-                println!("   {} ETH: {}{} | {} → {}",
-                    symbol,
-                    sign,
-                    diff,
-                    eth.old_formatted,
-                    eth.new_formatted
-                );
-            }
-        }
+    for change in change_summary.changed_assets() {
+        log_asset_change(&change_summary.alias, change);
     }
 
-    // Log token changes
-    for token in &change_summary.token_changes {
-        if !matches!(token.change, BalanceChange::NoChange) {
-            let (symbol, sign) = match token.change {
-                BalanceChange::Increase => ("📈", "+"),
-                BalanceChange::Decrease => ("📉", ""),
-                BalanceChange::NoChange => ("  ", ""),
-            };
-
-            let diff = calculate_diff(&token.new_balance, &token.old_balance);
-            let percent = calculate_percent_change(&token.new_balance, &token.old_balance);
-
-            if percent.abs() >= 0.01 {
-                println!("   {} {}: {}{} ({:+.2}%) | {} → {}",
-                    symbol,
-                    token.alias,
-                    sign,
-                    diff,
-                    percent,
-                    token.old_formatted,
-                    token.new_formatted
-                );
-            } else {
-                println!("   {} {}: {}{} | {} → {}",
-                    symbol,
-                    token.alias,
-                    sign,
-                    diff,
-                    token.old_formatted,
-                    token.new_formatted
-                );
-            }
-        }
+    for nft in &change_summary.nft_changes {
+        info!(alias = %change_summary.alias, asset = %nft.label, "🖼️  {}: {}", nft.label, nft.describe());
+    }
+
+    for tx in &change_summary.transactions {
+        let counterparty = tx.counterparty
+            .map(|a| shorten_address(&format!("{:?}", a)))
+            .unwrap_or_else(|| "contract creation".to_string());
+        info!(
+            alias = %change_summary.alias,
+            asset = %tx.asset,
+            tx_hash = ?tx.tx_hash,
+            "🧾 {} {} {} | {:?}",
+            tx.asset,
+            tx.preposition(),
+            counterparty,
+            tx.tx_hash
+        );
     }
-    println!();
+}
+
+/// Log a successful balance read at debug level, for the log file
+pub fn log_balance_check(info: &BalanceInfo) {
+    let tokens: Vec<String> = info.token_balances.iter()
+        .map(|token| format!("{} {}", token.formatted, token.alias))
+        .collect();
+    debug!(
+        network = %info.network_name,
+        alias = %info.alias,
+        block = ?info.block_number,
+        "Checked {}: {} {}{}{}",
+        info.alias,
+        info.eth_formatted,
+        info.native_symbol,
+        if tokens.is_empty() { "" } else { ", " },
+        tokens.join(", ")
+    );
+}
+
+/// Log one changed asset with its old and new balance as fields
+fn log_asset_change(alias: &str, change: &TokenBalanceChange) {
+    let (symbol, sign) = match change.change {
+        BalanceChange::Increase => ("📈", "+"),
+        BalanceChange::Decrease => ("📉", ""),
+        BalanceChange::NoChange => ("  ", ""),
+    };
+
+    let diff = calculate_diff(&change.new_balance, &change.old_balance, change.decimals);
+    let percent = calculate_percent_change(&change.new_balance, &change.old_balance);
+    let percent_suffix = if percent.abs() >= 0.01 { format!(" ({:+.2}%)", percent) } else { String::new() };
+
+    info!(
+        alias = %alias,
+        asset = %change.alias,
+        old = %change.old_formatted,
+        new = %change.new_formatted,
+        "{} {}: {}{}{} | {} → {}",
+        symbol,
+        change.alias,
+        sign,
+        diff,
+        percent_suffix,
+        change.old_formatted,
+        change.new_formatted
+    );
 }
 
 /// Shorten address for display
-fn shorten_address(address: &str) -> String {
+pub(crate) fn shorten_address(address: &str) -> String {
     if address.len() > 10 {
         format!("{}...{}", &address[..6], &address[address.len()-4..])
     } else {
@@ -218,21 +435,21 @@ fn shorten_address(address: &str) -> String {
     }
 }
 
-/// Calculate difference between two U256 values
-fn calculate_diff(new: &U256, old: &U256) -> String {
+/// Calculate difference between two U256 values with `decimals` decimals
+pub(crate) fn calculate_diff(new: &U256, old: &U256, decimals: u8) -> String {
     use alloy::primitives::utils::format_units;
 
     if new > old {
         let diff = *new - *old;
-        format_units(diff, 18).unwrap_or_else(|_| diff.to_string())
+        format_units(diff, decimals).unwrap_or_else(|_| diff.to_string())
     } else {
         let diff = *old - *new;
-        format_units(diff, 18).unwrap_or_else(|_| diff.to_string())
+        format_units(diff, decimals).unwrap_or_else(|_| diff.to_string())
     }
 }
 
 /// Calculate percent change
-fn calculate_percent_change(new: &U256, old: &U256) -> f64 {
+pub(crate) fn calculate_percent_change(new: &U256, old: &U256) -> f64 {
     if *old == U256::ZERO {
         return 0.0;
     }
@@ -249,22 +466,25 @@ fn calculate_percent_change(new: &U256, old: &U256) -> f64 {
 
 /// Simple console logging
 pub fn log_balances(results: &[Result<BalanceInfo>]) {
-    println!("=== Balance Check ===\n");
-
     for result in results {
         match result {
             Ok(info) => {
-                println!("📌 [{}] {} ({})", info.network_name, info.alias, info.address);
-                println!("   ETH: {}", info.eth_formatted);
-
-                for token_balance in &info.token_balances {
-                    println!("   {}: {}", token_balance.alias, token_balance.formatted);
-                }
-                println!();
-            }
-            Err(e) => {
-                println!("❌ Error: {}\n", e);
+                let tokens: Vec<String> = info.token_balances.iter()
+                    .map(|token| format!("{}: {}", token.alias, token.formatted))
+                    .collect();
+                info!(
+                    network = %info.network_name,
+                    alias = %info.alias,
+                    "📌 [{}] {} ({}) {}: {} {}",
+                    info.network_name,
+                    info.alias,
+                    info.address,
+                    info.native_symbol,
+                    info.eth_formatted,
+                    tokens.join(", ")
+                );
             }
+            Err(e) => error!("Error: {}", e),
         }
     }
 }
@@ -289,7 +509,7 @@ pub fn log_balances_json(results: &[Result<BalanceInfo>]) -> Result<()> {
                 "tokens": tokens,
             });
 
-            println!("{}", serde_json::to_string(&log)?);
+            info!(balance = %serde_json::to_string(&log)?, "Balance of {}", info.alias);
         }
     }
 
@@ -1,98 +1,45 @@
 use Oxwatcher::{
-    compare_balances, create_fallback_provider, log_balance_changes, AlertSettings, BalanceMonitor,
-    BalanceMonitorConfig, BalanceStorage, Config, FallbackConfig, NetworkConfig, TelegramNotifier,
+    format_tron_address, format_usd, init_tracing, AddressType, Config, Confirmations, EventStream, LogFormat,
+    MonitorMode, TokenType, Watcher,
 };
 use chrono::Local;
+use clap::Parser;
+use cli::{Cli, CliCommand, OutputMode};
 use eyre::Result;
-use std::collections::HashMap;
-use std::sync::Arc;
-use tokio::sync::RwLock;
+
+mod cli;
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    let config_path = "config.yaml";
-
-    // Load configuration
-    let config = Config::from_file(config_path)?;
-
-    // Create data directory if it doesn't exist
-    std::fs::create_dir_all(&config.data_dir)?;
-
-    // Build storage path using data_dir from config
-    let storage_path = format!("{}/balances.json", config.data_dir);
-
-    // Print startup banner
-    print_startup_banner(&config);
-
-    // Load previous balance storage
-    let storage = Arc::new(RwLock::new(BalanceStorage::load_from_file(&storage_path)?));
-
-    // Initialize Telegram notifier if configured
-    let telegram_notifier = if let Some(telegram_config) = &config.telegram {
-        let notifier = TelegramNotifier::new(telegram_config, Arc::clone(&storage), &config.data_dir);
-
-        // Count loaded chats
-        let loaded_chats = notifier.get_registered_chats_count().await;
-        if loaded_chats > 0 {
-            println!("📲 Loaded {} authorized Telegram chat(s)", loaded_chats);
-        }
-
-        // Spawn command handler
-        notifier.clone().spawn_command_handler();
-
-        // Spawn daily report scheduler if configured
-        if telegram_config.daily_report.is_some() {
-            notifier.clone().spawn_daily_report_scheduler();
-        }
+    let mut cli = Cli::parse();
+    let command = cli.command.take().unwrap_or(CliCommand::Run);
+    cli::execute(command, &cli).await
+}
 
-        Some(Arc::new(notifier))
+async fn run(mut config: Config, output: OutputMode, dry_run: bool) -> Result<()> {
+    // A dry run never touches the real state; its copy is removed on exit
+    let _dry_run_dir = if dry_run {
+        let dir = std::env::temp_dir().join(format!("oxwatcher-dry-run-{}", std::process::id()));
+        Some(config.use_dry_run_dir(&dir)?)
     } else {
         None
     };
 
-    println!("✅ Balance monitoring started");
-    println!("💾 Data directory: {}", config.data_dir);
-    println!("💾 Storage file: {}", storage_path);
-    println!();
-
-    // Spawn monitoring task for each network
-    let mut handles = Vec::new();
-
-    let alert_settings = config.get_alert_settings();
-
-    for network in config.networks.clone() {
-        let storage_clone = Arc::clone(&storage);
-        let telegram_clone = telegram_notifier.clone();
-        let alert_settings_clone = alert_settings.clone();
-        let interval = config.interval;
-        let active_transport_count = config.active_transport_count;
-        let storage_path_clone = storage_path.to_string();
-
-        let handle = tokio::spawn(async move {
-            if let Err(e) = monitor_network(
-                network,
-                storage_clone,
-                telegram_clone,
-                alert_settings_clone,
-                interval,
-                active_transport_count,
-                storage_path_clone,
-            )
-            .await
-            {
-                eprintln!("❌ Network monitoring error: {}", e);
-            }
-        });
+    // Install logging before anything else reports; the event stream keeps stdout to itself
+    let json_stream = output == OutputMode::JsonStream;
+    let log_format = init_tracing(&config.logging, &config.data_dir, json_stream)?;
 
-        handles.push(handle);
+    // Print startup banner; JSON logs stay one object per line
+    if log_format == LogFormat::Text && !json_stream {
+        print_startup_banner(&config);
     }
 
-    // Wait for all tasks to complete (they run indefinitely)
-    for handle in handles {
-        let _ = handle.await;
+    // Monitors publish what they do on the event bus; subscribe before anything is published
+    let watcher = Watcher::new(config).with_dry_run(dry_run);
+    if json_stream {
+        tokio::spawn(EventStream::stdout().run(watcher.events().subscribe()));
     }
-
-    Ok(())
+    watcher.run().await
 }
 
 fn print_startup_banner(config: &Config) {
@@ -110,6 +57,7 @@ fn print_startup_banner(config: &Config) {
     println!("⚙️  Global Settings:");
     println!("   • Check interval: {} seconds", config.interval.as_secs());
     println!("   • Active RPC connections: {}", config.active_transport_count);
+    println!("   • RPC strategy: {}", config.rpc_strategy);
     println!();
 
     // Networks configuration
@@ -117,6 +65,47 @@ fn print_startup_banner(config: &Config) {
     for (idx, network) in config.networks.iter().enumerate() {
         println!("   {}. {} (Chain ID: {})", idx + 1, network.name, network.chain_id);
         println!("      • RPC nodes: {}", network.rpc_nodes.len());
+        if !network.archive_rpc_nodes.is_empty() {
+            println!("      • Archive RPC nodes: {}", network.archive_rpc_nodes.len());
+        }
+        if let Some(interval) = network.interval {
+            println!("      • Check interval: {} seconds", interval.as_secs());
+        }
+        if let Some(strategy) = network.rpc_strategy {
+            println!("      • RPC strategy: {}", strategy);
+        }
+        if let Some(rollup) = &network.rollup {
+            println!("      • Sequencer stall alert after: {} seconds", rollup.max_block_age.as_secs());
+            if let (Some(bridge), Some(l1)) = (rollup.bridge, &rollup.l1_network) {
+                println!("      • Bridge: {} on {}", bridge, l1);
+            }
+        }
+        if network.mode == MonitorMode::Ws {
+            println!("      • Mode: WebSocket (new block subscription)");
+        }
+        if network.confirmations != Confirmations::default() {
+            println!("      • Reading balances at: {} block", network.confirmations);
+        }
+        if let Some(gas) = &network.gas {
+            let above = gas.above_gwei.map(|g| format!("> {} gwei", g));
+            let below = gas.below_gwei.map(|g| format!("< {} gwei", g));
+            let thresholds: Vec<String> = above.into_iter().chain(below).collect();
+            if thresholds.is_empty() {
+                println!("      • Gas tracking: on");
+            } else {
+                println!("      • Gas tracking: alert at {}", thresholds.join(", "));
+            }
+            if gas.tracks_blob_fee() {
+                let above = gas.blob_above_gwei.map(|g| format!("> {} gwei", g));
+                let below = gas.blob_below_gwei.map(|g| format!("< {} gwei", g));
+                let thresholds: Vec<String> = above.into_iter().chain(below).collect();
+                if thresholds.is_empty() {
+                    println!("      • Blob fee tracking: on");
+                } else {
+                    println!("      • Blob fee tracking: alert at {}", thresholds.join(", "));
+                }
+            }
+        }
         println!("      • Addresses to monitor: {}", network.addresses.len());
 
         // Show addresses with thresholds
@@ -126,6 +115,68 @@ fn print_startup_banner(config: &Config) {
             } else {
                 println!("         - {}", addr.alias);
             }
+            if let Some(runway) = addr.min_tx_runway {
+                println!("           ⛽ Gas runway alert: < {} txs @ {} gas", runway, addr.tx_gas_limit);
+            }
+            if let Some(usd) = addr.min_balance_usd {
+                println!("           💲 USD low balance alert: < {}", format_usd(usd));
+            }
+            if let Some(target) = addr.top_up_to {
+                println!("           💸 Top-up suggestions: to {} ETH", target);
+            }
+            if addr.kind == AddressType::Safe {
+                println!("           🔐 Safe: tracking owners and threshold");
+            }
+        }
+
+        if !network.nfts.is_empty() {
+            println!("      • NFT collections to track: {}", network.nfts.len());
+            for nft in &network.nfts {
+                if nft.token_ids.is_empty() {
+                    println!("         - {}", nft.alias);
+                } else {
+                    println!("         - {} ({} token IDs)", nft.alias, nft.token_ids.len());
+                }
+            }
+        }
+
+        if !network.contracts.is_empty() {
+            println!("      • Contracts to watch: {}", network.contracts.len());
+            for contract in &network.contracts {
+                println!("         - {} ({})", contract.alias, contract.address);
+            }
+        }
+
+        if !network.feeds.is_empty() {
+            println!("      • Price feeds to monitor: {}", network.feeds.len());
+            for feed in &network.feeds {
+                let mut checks = Vec::new();
+                if let Some(heartbeat) = feed.heartbeat {
+                    checks.push(format!("heartbeat {}s", heartbeat.as_secs()));
+                }
+                if let Some(percent) = feed.deviation_percent {
+                    checks.push(format!("deviation > {}%", percent));
+                }
+                println!("         - {} ({})", feed.alias, checks.join(", "));
+            }
+        }
+
+        if !network.view_calls.is_empty() {
+            println!("      • View calls to monitor: {}", network.view_calls.len());
+            for call in &network.view_calls {
+                let mut limits = Vec::new();
+                if let Some(min) = call.min {
+                    limits.push(format!("< {}", min));
+                }
+                if let Some(max) = call.max {
+                    limits.push(format!("> {}", max));
+                }
+                if limits.is_empty() {
+                    println!("         - {}: {}", call.alias, call.function);
+                } else {
+                    println!("         - {}: {} (⚠️  Alert: {})", call.alias, call.function, limits.join(", "));
+                }
+            }
         }
 
         if !network.tokens.is_empty() {
@@ -136,8 +187,21 @@ fn print_startup_banner(config: &Config) {
                 } else {
                     println!("         - {}", token.alias);
                 }
+                if let Some(usd) = token.min_balance_usd {
+                    println!("           💲 USD low balance alert: < {}", format_usd(usd));
+                }
+                if token.kind == TokenType::Rebasing {
+                    println!("           🔁 Rebasing: changes detected on shares");
+                }
             }
         }
+        if let Some(discovery) = &network.token_discovery {
+            println!("      • Token discovery: transfers of the last {} blocks", discovery.lookback_blocks);
+        }
+
+        if network.price.is_some() || network.tokens.iter().any(|t| t.price.is_some()) {
+            println!("      • USD pricing: enabled");
+        }
 
         if idx < config.networks.len() - 1 {
             println!();
@@ -145,20 +209,72 @@ fn print_startup_banner(config: &Config) {
     }
     println!();
 
+    // Beacon-chain validators
+    if let Some(beacon) = &config.beacon {
+        println!("🛰️  Validators ({}) on {}:", beacon.validators.len(), beacon.name);
+        println!("   • Check interval: {} seconds", beacon.interval.as_secs());
+        println!("   • Missed attestation alert: {} epochs in a row", beacon.missed_attestations);
+        for validator in &beacon.validators {
+            println!("      - {} ({})", validator.alias, validator.id);
+        }
+        println!();
+    }
+
+    // Tron networks
+    for tron in &config.tron {
+        println!("🔺 {} (Chain ID: {}):", tron.name, tron.chain_id);
+        println!("   • TronGrid: {}", tron.api_url);
+        if let Some(interval) = tron.interval {
+            println!("   • Check interval: {} seconds", interval.as_secs());
+        }
+        for addr in &tron.addresses {
+            match addr.min_balance_trx {
+                Some(threshold) => println!("      - {} ({}) (⚠️  Low balance alert: < {} TRX)", addr.alias, format_tron_address(&addr.address), threshold),
+                None => println!("      - {} ({})", addr.alias, format_tron_address(&addr.address)),
+            }
+        }
+        for token in &tron.tokens {
+            println!("      • {} ({})", token.alias, format_tron_address(&token.address));
+        }
+        println!();
+    }
+
+    // Cosmos SDK chains
+    for chain in &config.cosmos {
+        println!("🌌 {} ({}):", chain.name, chain.chain_id);
+        println!("   • LCD: {}", chain.lcd_url);
+        if let Some(interval) = chain.interval {
+            println!("   • Check interval: {} seconds", interval.as_secs());
+        }
+        for address in &chain.addresses {
+            println!("      - {} ({})", address.alias, address.address);
+        }
+        println!();
+    }
+
     // Telegram configuration
-    if let Some(telegram) = &config.telegram {
-        println!("📱 Telegram Notifications: ENABLED");
+    for telegram in config.telegram_configs() {
+        println!("📱 {} Notifications: ENABLED", telegram.channel_name());
 
         // Check if public mode
-        let is_public = telegram.allowed_users.iter().any(|u| u == "all");
-        if is_public {
+        let access = &telegram.access;
+        if access.is_public() {
             println!("   • Access mode: 🌍 PUBLIC (anyone can use the bot)");
         } else {
             println!("   • Access mode: 🔒 PRIVATE");
-            println!("   • Authorized users: {}", telegram.allowed_users.len());
-            for user in &telegram.allowed_users {
+            println!("   • Authorized users: {}", access.allowed_users.len() + access.allowed_user_ids.len());
+            for user in &access.allowed_users {
                 println!("      - @{}", user);
             }
+            for user_id in &access.allowed_user_ids {
+                println!("      - user ID {}", user_id);
+            }
+            if !access.allowed_chat_ids.is_empty() {
+                println!("   • Authorized chats: {}", access.allowed_chat_ids.len());
+                for chat_id in &access.allowed_chat_ids {
+                    println!("      - {}", chat_id);
+                }
+            }
         }
         println!();
 
@@ -191,116 +307,68 @@ fn print_startup_banner(config: &Config) {
         println!("   💬 Bot Commands:");
         println!("      - /balance - Show current balances");
         println!("      - /report - Get on-demand diff report");
-    } else {
+    }
+    if config.telegram_configs().next().is_none() {
         println!("📱 Telegram Notifications: DISABLED");
     }
 
-    println!();
-    println!("═══════════════════════════════════════════════════════════════");
-    println!();
-}
-
-async fn monitor_network(
-    network: NetworkConfig,
-    storage: Arc<RwLock<BalanceStorage>>,
-    telegram_notifier: Option<Arc<TelegramNotifier>>,
-    alert_settings: AlertSettings,
-    interval: std::time::Duration,
-    active_transport_count: std::num::NonZeroUsize,
-    storage_path: String,
-) -> Result<()> {
-    println!("🌐 Starting monitor for network: {} (Chain ID: {})", network.name, network.chain_id);
-
-    // Build threshold maps for low balance alerts
-    let mut address_thresholds: HashMap<String, f64> = HashMap::new();
-    for addr in &network.addresses {
-        if let Some(threshold) = addr.min_balance_eth {
-            address_thresholds.insert(addr.alias.clone(), threshold);
+    // Slack configuration
+    if let Some(slack) = &config.slack {
+        println!();
+        println!("💬 Slack Notifications: ENABLED");
+        if let Some(channel) = &slack.channel {
+            println!("   • Channel: {}", channel);
         }
+        println!("   • Balance change alerts: {}",
+            if slack.alerts.balance_change { "✅ ENABLED" } else { "❌ DISABLED" });
+        println!("   • Low balance alerts: {}",
+            if slack.alerts.low_balance { "✅ ENABLED" } else { "❌ DISABLED" });
     }
 
-    let mut token_thresholds: HashMap<String, f64> = HashMap::new();
-    for token in &network.tokens {
-        if let Some(threshold) = token.min_balance {
-            token_thresholds.insert(token.alias.clone(), threshold);
+    // Discord configuration
+    if let Some(discord) = &config.discord {
+        println!();
+        println!("🎮 Discord Notifications: ENABLED");
+        println!("   • Delivery: {}", if discord.bot_token.is_some() { "bot API / webhooks" } else { "webhooks" });
+        if !discord.networks.is_empty() {
+            println!("   • Per-network channels: {}", discord.networks.len());
         }
+        println!("   • Balance change alerts: {}",
+            if discord.alerts.balance_change { "✅ ENABLED" } else { "❌ DISABLED" });
+        println!("   • Low balance alerts: {}",
+            if discord.alerts.low_balance { "✅ ENABLED" } else { "❌ DISABLED" });
     }
 
-    // Create provider for this network
-    let provider_config = FallbackConfig::new(network.rpc_nodes.clone(), active_transport_count);
-    let provider = create_fallback_provider(provider_config)?;
-
-    // Create monitor for this network
-    let monitor_config = BalanceMonitorConfig::new(network.addresses.clone(), network.tokens.clone(), interval);
-    let monitor = BalanceMonitor::new(provider, monitor_config);
-
-    // Main monitoring loop for this network
-    loop {
-        let results = monitor.check(network.name.clone(), network.chain_id).await;
-        let mut all_balances = Vec::new();
-
-        // Process each result
-        for result in results {
-            match result {
-                Ok(balance_info) => {
-                    // Compare with previous balances
-                    let changes = {
-                        let storage_read = storage.read().await;
-                        compare_balances(&balance_info, &storage_read)
-                    };
-
-                    // Log only if there are changes
-                    if changes.has_changes() {
-                        log_balance_changes(&changes);
-
-                        // Send Telegram alert if enabled and balance_change alerts are enabled
-                        if alert_settings.balance_change {
-                            if let Some(ref notifier) = telegram_notifier {
-                                if let Err(e) = notifier.send_alert(&changes).await {
-                                    eprintln!("⚠️  Failed to send Telegram alert: {}", e);
-                                }
-                            }
-                        }
-                    }
-
-                    // Check for low balance alerts if enabled
-                    if alert_settings.low_balance {
-                        if let Some(ref notifier) = telegram_notifier {
-                            let eth_threshold = address_thresholds.get(&balance_info.alias).copied();
-                            if let Err(e) = notifier.check_low_balance_alerts(&balance_info, eth_threshold, &token_thresholds).await {
-                                eprintln!("⚠️  Failed to check low balance alerts: {}", e);
-                            }
-                        }
-                    }
-
-                    // Store balance for later
-                    all_balances.push(balance_info.clone());
-
-                    // Update storage with new balance
-                    {
-                        let mut storage_write = storage.write().await;
-                        storage_write.update(&balance_info);
-                    }
-                }
-                Err(e) => {
-                    eprintln!("❌ Error checking balance on {}: {}\n", network.name, e);
-                }
-            }
-        }
+    // Matrix configuration
+    if let Some(matrix) = &config.matrix {
+        println!();
+        println!("🟩 Matrix Notifications: ENABLED");
+        println!("   • Homeserver: {}", matrix.homeserver_url);
+        println!("   • Rooms: {}", matrix.rooms.join(", "));
+        println!("   • Balance change alerts: {}",
+            if matrix.alerts.balance_change { "✅ ENABLED" } else { "❌ DISABLED" });
+        println!("   • Low balance alerts: {}",
+            if matrix.alerts.low_balance { "✅ ENABLED" } else { "❌ DISABLED" });
+    }
 
-        // Update Telegram notifier with latest balances
-        if let Some(ref notifier) = telegram_notifier {
-            notifier.update_balances(all_balances).await;
-        }
+    // Twilio configuration
+    if let Some(twilio) = &config.twilio {
+        println!();
+        println!("📟 SMS via Twilio: ENABLED (critical alerts only)");
+        println!("   • Numbers: {}", twilio.to.join(", "));
+        println!("   • Cap: {} alerts per hour", twilio.max_per_hour);
+    }
 
-        // Save storage to file after each check
-        {
-            let storage_read = storage.read().await;
-            if let Err(e) = storage_read.save_to_file(&storage_path) {
-                eprintln!("⚠️  Failed to save storage: {}", e);
-            }
+    // Webhook configuration
+    if !config.webhooks.is_empty() {
+        println!();
+        println!("🪝 Webhooks ({}):", config.webhooks.len());
+        for webhook in &config.webhooks {
+            println!("   • {}{}", webhook.url, if webhook.secret.is_some() { " (signed)" } else { "" });
         }
-
-        tokio::time::sleep(interval).await;
     }
+
+    println!();
+    println!("═══════════════════════════════════════════════════════════════");
+    println!();
 }
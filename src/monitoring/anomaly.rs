@@ -0,0 +1,123 @@
+use crate::config::{AddressConfig, AnomalyConfig};
+use crate::monitoring::{from_units, BalanceInfo};
+use crate::storage::{HistoryPoint, HistoryStore};
+use std::collections::HashMap;
+
+/// Mean and standard deviation of an asset's past balance changes
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChangeStats {
+    pub mean: f64,
+    pub stddev: f64,
+    pub samples: usize,
+}
+
+impl ChangeStats {
+    /// Stats of the non-zero differences between consecutive balances
+    pub fn from_balances(balances: &[f64]) -> Option<Self> {
+        let changes: Vec<f64> = balances.windows(2).map(|pair| pair[1] - pair[0]).filter(|c| *c != 0.0).collect();
+        if changes.is_empty() {
+            return None;
+        }
+        let samples = changes.len();
+        let mean = changes.iter().sum::<f64>() / samples as f64;
+        let variance = changes.iter().map(|c| (c - mean).powi(2)).sum::<f64>() / samples as f64;
+        Some(Self { mean, stddev: variance.sqrt(), samples })
+    }
+
+    /// Distance of `change` from the mean in standard deviations. The deviation is
+    /// at least 10% of the mean change size, so perfectly regular changes (e.g. a
+    /// fixed daily payout) don't flag every small variation.
+    pub fn z_score(&self, change: f64) -> f64 {
+        let scale = self.stddev.max(self.mean.abs() * 0.1);
+        if scale == 0.0 {
+            return 0.0;
+        }
+        (change - self.mean).abs() / scale
+    }
+}
+
+/// Balance change far outside an asset's usual changes
+#[derive(Debug, Clone, PartialEq)]
+pub struct Anomaly {
+    pub asset: String,
+    pub change: f64,
+    pub stats: ChangeStats,
+    pub z_score: f64,
+}
+
+impl Anomaly {
+    pub fn lines(&self) -> Vec<String> {
+        vec![
+            format!("Change: {:+} {}", self.change, self.asset),
+            format!(
+                "Usual change: {:+.4} ± {:.4} over {} changes",
+                self.stats.mean, self.stats.stddev, self.stats.samples
+            ),
+            format!("Deviation: {:.1}σ", self.z_score),
+        ]
+    }
+}
+
+/// Flags balance changes of addresses with an `anomaly` setting that are more than
+/// `sensitivity` standard deviations away from their past changes in the history store
+#[derive(Debug, Default)]
+pub struct AnomalyDetector {
+    settings: HashMap<String, AnomalyConfig>,
+}
+
+impl AnomalyDetector {
+    pub fn new(addresses: &[AddressConfig]) -> Self {
+        let settings = addresses
+            .iter()
+            .filter_map(|addr| Some((addr.alias.clone(), addr.anomaly.clone()?)))
+            .collect();
+        Self { settings }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.settings.is_empty()
+    }
+
+    /// Compare the change from `previous` to `current` with the address's history;
+    /// call before `current` is recorded in it
+    pub fn check(&self, history: &HistoryStore, current: &BalanceInfo, previous: &BalanceInfo, now: u64) -> Vec<Anomaly> {
+        let Some(settings) = self.settings.get(&current.alias) else {
+            return Vec::new();
+        };
+        let from = settings.lookback_days.map(|days| now.saturating_sub(days * 24 * 3600)).unwrap_or(0);
+        let points = history.get_range(&current.network_name, &current.alias, from, now);
+
+        let native = (current.native_symbol.clone(), current.eth_balance, previous.eth_balance, current.native_decimals);
+        let mut assets = vec![native];
+        for token in &current.token_balances {
+            if let Some(before) = previous.token_balances.iter().find(|t| t.alias == token.alias) {
+                assets.push((token.alias.clone(), token.balance, before.balance, token.decimals));
+            }
+        }
+
+        let mut anomalies = Vec::new();
+        for (asset, now_balance, before_balance, decimals) in assets {
+            if now_balance == before_balance {
+                continue;
+            }
+            let change = from_units(now_balance, decimals) - from_units(before_balance, decimals);
+            let balances: Vec<f64> = points.iter().filter_map(|point| asset_amount(point, &asset, current)).collect();
+            let Some(stats) = ChangeStats::from_balances(&balances).filter(|stats| stats.samples >= settings.min_samples) else {
+                continue;
+            };
+            let z_score = stats.z_score(change);
+            if z_score > settings.sensitivity {
+                anomalies.push(Anomaly { asset, change, stats, z_score });
+            }
+        }
+        anomalies
+    }
+}
+
+/// Amount of the native currency (by symbol) or a token (by alias) at a history point
+fn asset_amount(point: &HistoryPoint, asset: &str, current: &BalanceInfo) -> Option<f64> {
+    if asset == current.native_symbol {
+        return Some(from_units(point.balance.eth_balance, point.balance.native_decimals));
+    }
+    point.balance.token_balances.iter().find(|t| t.alias == asset).map(|t| from_units(t.balance, t.decimals))
+}
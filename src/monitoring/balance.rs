@@ -1,30 +1,85 @@
 use alloy::{
-    primitives::{Address, utils::format_units, U256},
+    eips::{BlockId, BlockNumberOrTag},
+    primitives::{Address, B256, Bytes, utils::format_units, U256},
     providers::Provider,
+    rpc::{client::BatchRequest, types::TransactionRequest},
+    sol_types::{SolCall, SolValue},
 };
 use eyre::Result;
+use futures::stream::{self, StreamExt};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::num::NonZeroUsize;
 use std::time::Duration;
+use tokio::sync::RwLock;
+use tracing::{debug, warn};
 
-use crate::config::{AddressConfig, TokenConfig};
-use crate::contracts::IERC20;
+use crate::config::{AddressConfig, ConfirmationTag, Confirmations, NftConfig, NftStandard, TokenConfig, TokenType};
+use crate::contracts::{IMulticall3, IStETH, IERC1155, IERC20, IERC721};
 
 /// Configuration for balance monitoring
 #[derive(Debug, Clone)]
 pub struct BalanceMonitorConfig {
     pub addresses: Vec<AddressConfig>,
     pub tokens: Vec<TokenConfig>,
+    pub nfts: Vec<NftConfig>,
     pub interval: Duration,
+    /// Multicall3 contract used to batch reads (None = individual calls)
+    pub multicall: Option<Address>,
+    /// Block balances are read at relative to head
+    pub confirmations: Confirmations,
+    /// Addresses read at the same time without multicall
+    pub concurrency: NonZeroUsize,
+    /// Read native balances through JSON-RPC batch requests without multicall
+    pub batch_rpc: bool,
 }
 
+/// Most eth_getBalance calls sent in one batch request; many providers reject larger batches
+pub const MAX_BATCH_SIZE: usize = 100;
+
 impl BalanceMonitorConfig {
     pub fn new(addresses: Vec<AddressConfig>, tokens: Vec<TokenConfig>, interval: Duration) -> Self {
         Self {
             addresses,
             tokens,
+            nfts: Vec::new(),
             interval,
+            multicall: None,
+            confirmations: Confirmations::default(),
+            concurrency: NonZeroUsize::MIN,
+            batch_rpc: false,
         }
     }
+
+    /// Batch all balance reads through the given Multicall3 contract
+    pub fn with_multicall(mut self, multicall: Address) -> Self {
+        self.multicall = Some(multicall);
+        self
+    }
+
+    /// Track NFT holdings for every address
+    pub fn with_nfts(mut self, nfts: Vec<NftConfig>) -> Self {
+        self.nfts = nfts;
+        self
+    }
+
+    /// Read balances behind head to avoid alerts for changes that get reorged out
+    pub fn with_confirmations(mut self, confirmations: Confirmations) -> Self {
+        self.confirmations = confirmations;
+        self
+    }
+
+    /// Read up to `concurrency` addresses at the same time when not using multicall
+    pub fn with_concurrency(mut self, concurrency: NonZeroUsize) -> Self {
+        self.concurrency = concurrency;
+        self
+    }
+
+    /// Read native balances of all addresses in JSON-RPC batches when not using multicall
+    pub fn with_batch_rpc(mut self, batch_rpc: bool) -> Self {
+        self.batch_rpc = batch_rpc;
+        self
+    }
 }
 
 /// Token balance
@@ -34,6 +89,104 @@ pub struct TokenBalance {
     #[serde(with = "u256_serde")]
     pub balance: U256,
     pub formatted: String,
+    /// Decimals `balance` is denominated in
+    #[serde(default = "default_decimals")]
+    pub decimals: u8,
+    /// Balance value in USD, if a price source is configured
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usd_value: Option<f64>,
+    /// Shares behind a rebasing token balance, if the token exposes sharesOf()
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shares: Option<U256>,
+}
+
+/// NFT holding: tokens held from a collection, or of a specific token ID
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NftBalance {
+    /// Collection alias
+    pub alias: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_id: Option<U256>,
+    #[serde(with = "u256_serde")]
+    pub count: U256,
+}
+
+impl NftBalance {
+    /// Display name, e.g. "Treasury Punks" or "Treasury Punks #42"
+    pub fn label(&self) -> String {
+        match self.token_id {
+            Some(id) => format!("{} #{}", self.alias, id),
+            None => self.alias.clone(),
+        }
+    }
+}
+
+/// Balance read of one token, through balanceOf or the token's `balance_method`
+struct TokenRead<'a> {
+    token: &'a TokenConfig,
+    /// Selector of `balance_method`, None for balanceOf
+    selector: Option<[u8; 4]>,
+}
+
+impl TokenRead<'_> {
+    fn call_data(&self, owner: Address) -> Bytes {
+        match self.selector {
+            Some(selector) => [selector.as_slice(), owner.into_word().as_slice()].concat().into(),
+            None => IERC20::balanceOfCall { account: owner }.abi_encode().into(),
+        }
+    }
+
+    /// Both functions return a uint256, multiplied by `balance_scale` when set. A balance
+    /// that overflows when scaled is treated as a failed read
+    fn decode(&self, data: &[u8]) -> Option<U256> {
+        let balance = U256::abi_decode(data).ok()?;
+        match self.token.balance_scale {
+            Some(scale) => {
+                let scaled = balance.checked_mul(to_units(scale, DEFAULT_DECIMALS))?;
+                Some(scaled / U256::from(10).pow(U256::from(DEFAULT_DECIMALS)))
+            }
+            None => Some(balance),
+        }
+    }
+}
+
+/// Single NFT read performed for every address
+struct NftRead<'a> {
+    nft: &'a NftConfig,
+    token_id: Option<U256>,
+}
+
+impl NftRead<'_> {
+    fn call_data(&self, owner: Address) -> Bytes {
+        match (self.nft.standard, self.token_id) {
+            (NftStandard::Erc1155, Some(id)) => IERC1155::balanceOfCall { account: owner, id }.abi_encode(),
+            (_, Some(id)) => IERC721::ownerOfCall { tokenId: id }.abi_encode(),
+            (_, None) => IERC721::balanceOfCall { owner }.abi_encode(),
+        }
+        .into()
+    }
+
+    fn decode(&self, owner: Address, data: &[u8]) -> Option<U256> {
+        match (self.nft.standard, self.token_id) {
+            (NftStandard::Erc1155, Some(_)) => IERC1155::balanceOfCall::abi_decode_returns(data).ok(),
+            (_, Some(_)) => IERC721::ownerOfCall::abi_decode_returns(data).ok()
+                .map(|holder| U256::from(holder == owner)),
+            (_, None) => IERC721::balanceOfCall::abi_decode_returns(data).ok(),
+        }
+    }
+
+    /// ownerOf reverts for tokens that don't exist (e.g. burned), which means not held
+    fn revert_means_zero(&self) -> bool {
+        self.nft.standard == NftStandard::Erc721 && self.token_id.is_some()
+    }
+
+    fn balance(&self, count: U256) -> NftBalance {
+        NftBalance {
+            alias: self.nft.alias.clone(),
+            token_id: self.token_id,
+            count,
+        }
+    }
 }
 
 /// Balance check result
@@ -47,7 +200,73 @@ pub struct BalanceInfo {
     #[serde(with = "u256_serde")]
     pub eth_balance: U256,
     pub eth_formatted: String,
+    /// Symbol of the native currency, e.g. "ETH" or "TRX"
+    #[serde(default = "default_native_symbol")]
+    pub native_symbol: String,
+    /// Decimals `eth_balance` is denominated in, e.g. 18 for ETH or 6 for TRX
+    #[serde(default = "default_decimals")]
+    pub native_decimals: u8,
+    /// Native balance value in USD, if a price source is configured
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub eth_usd_value: Option<f64>,
     pub token_balances: Vec<TokenBalance>,
+    /// Tracked NFT holdings
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub nft_balances: Vec<NftBalance>,
+    /// Block the balances were read at
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_number: Option<u64>,
+    /// Hash of `block_number`, used to detect reorgs between checks
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_hash: Option<B256>,
+}
+
+impl BalanceInfo {
+    /// True if native, token and NFT balances all equal those of `other`
+    pub fn same_balances(&self, other: &BalanceInfo) -> bool {
+        self.eth_balance == other.eth_balance
+            && self.token_balances.len() == other.token_balances.len()
+            && self.token_balances.iter().zip(&other.token_balances)
+                .all(|(a, b)| a.alias == b.alias && a.balance == b.balance)
+            && self.nft_balances == other.nft_balances
+    }
+}
+
+fn default_native_symbol() -> String {
+    "ETH".to_string()
+}
+
+/// Decimals of native EVM balances, also assumed for tokens whose decimals() can't be read
+pub const DEFAULT_DECIMALS: u8 = 18;
+
+fn default_decimals() -> u8 {
+    DEFAULT_DECIMALS
+}
+
+/// Convert an amount in whole units (e.g. a threshold of 1.5) to base units of an asset
+/// with `decimals` decimals, so it can be compared with raw balances exactly. Digits
+/// beyond `decimals` are dropped; non-positive amounts give zero.
+pub fn to_units(amount: f64, decimals: u8) -> U256 {
+    if amount.is_nan() || amount <= 0.0 {
+        return U256::ZERO;
+    }
+    if amount.is_infinite() {
+        return U256::MAX;
+    }
+    // Display never uses exponent notation, and gives the shortest digits that round-trip
+    let text = amount.to_string();
+    let (whole, fraction) = text.split_once('.').unwrap_or((&text, ""));
+    let fraction: String = fraction.chars()
+        .chain(std::iter::repeat('0'))
+        .take(decimals as usize)
+        .collect();
+    U256::from_str_radix(&format!("{}{}", whole, fraction), 10).unwrap_or(U256::MAX)
+}
+
+/// Convert a raw amount of an asset with `decimals` decimals to whole units, for
+/// statistics and rates. Thresholds should be compared in base units with `to_units`.
+pub fn from_units(amount: U256, decimals: u8) -> f64 {
+    f64::from(amount) / 10f64.powi(decimals as i32)
 }
 
 // Custom serialization for U256
@@ -92,51 +311,160 @@ mod address_serde {
     }
 }
 
+/// Calculate the ETH balance needed to cover `min_tx_runway` transactions
+/// of `tx_gas_limit` gas each at the given base fee
+pub fn runway_threshold_eth(min_tx_runway: u64, tx_gas_limit: u64, base_fee: u128) -> f64 {
+    let required = U256::from(min_tx_runway) * U256::from(tx_gas_limit) * U256::from(base_fee);
+    format_units(required, "ether")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(0.0)
+}
+
 /// Balance monitoring
 pub struct BalanceMonitor<P> {
     provider: P,
     config: BalanceMonitorConfig,
+    /// Token decimals read from the contracts
+    decimals: RwLock<HashMap<Address, u8>>,
+    /// Balances read at the last block checked, reused while the head hasn't moved
+    cache: RwLock<BlockCache>,
+}
+
+/// Balances read at one block, by alias and address
+#[derive(Default)]
+struct BlockCache {
+    block: Option<(u64, B256)>,
+    balances: HashMap<(String, Address), BalanceInfo>,
 }
 
 impl<P: Provider> BalanceMonitor<P> {
     pub fn new(provider: P, config: BalanceMonitorConfig) -> Self {
-        Self { provider, config }
+        Self { provider, config, decimals: RwLock::new(HashMap::new()), cache: RwLock::new(BlockCache::default()) }
+    }
+
+    /// Decimals of a token: configured, or read once from its decimals(). Falls back to
+    /// DEFAULT_DECIMALS (and retries next time) if the call fails.
+    pub async fn token_decimals(&self, token: &TokenConfig) -> u8 {
+        if let Some(decimals) = token.decimals {
+            return decimals;
+        }
+        if let Some(decimals) = self.decimals.read().await.get(&token.address) {
+            return *decimals;
+        }
+        match IERC20::new(token.address, &self.provider).decimals().call().await {
+            Ok(decimals) => {
+                self.decimals.write().await.insert(token.address, decimals);
+                decimals
+            }
+            Err(e) => {
+                warn!("Error getting decimals of {}, assuming {}: {}", token.alias, DEFAULT_DECIMALS, e);
+                DEFAULT_DECIMALS
+            }
+        }
     }
 
-    /// Get balance for a single address
+    /// Get balance for a single address at the given block
     pub async fn get_balance(
         &self,
         network_name: String,
         chain_id: u64,
         alias: String,
         address: Address,
+        block: BlockId,
+    ) -> Result<BalanceInfo> {
+        self.read_address(network_name, chain_id, alias, address, block, None).await
+    }
+
+    /// Native balances of the addresses at the given block, in order, read with one
+    /// JSON-RPC batch request per `MAX_BATCH_SIZE` addresses
+    pub async fn batch_native_balances(&self, addresses: &[Address], block: BlockId) -> Result<Vec<U256>> {
+        let mut balances = Vec::with_capacity(addresses.len());
+        for chunk in addresses.chunks(MAX_BATCH_SIZE) {
+            let mut batch = BatchRequest::new(self.provider.client());
+            let waiters = chunk.iter()
+                .map(|address| batch.add_call::<_, U256>("eth_getBalance", &(address, block)))
+                .collect::<Result<Vec<_>, _>>()?;
+            batch.send().await?;
+            for waiter in waiters {
+                balances.push(waiter.await?);
+            }
+        }
+        Ok(balances)
+    }
+
+    /// Read an address's balances; the native balance is only fetched if not already known
+    async fn read_address(
+        &self,
+        network_name: String,
+        chain_id: u64,
+        alias: String,
+        address: Address,
+        block: BlockId,
+        eth_balance: Option<U256>,
     ) -> Result<BalanceInfo> {
         // ETH balance
-        let eth_balance = self.provider.get_balance(address).await?;
+        let eth_balance = match eth_balance {
+            Some(balance) => balance,
+            None => self.provider.get_balance(address).block_id(block).await?,
+        };
         let eth_formatted = format_units(eth_balance, "ether")?;
 
         // Token balances
         let mut token_balances = Vec::new();
-        for token in &self.config.tokens {
-            let token_contract = IERC20::new(token.address, &self.provider);
-
-            match token_contract.balanceOf(address).call().await {
-                Ok(balance) => {
-                    let formatted = format_units(balance, 18)
+        for read in self.token_reads() {
+            let token = read.token;
+            let request = TransactionRequest::default()
+                .to(token.address)
+                .input(read.call_data(address).into());
+            match self.provider.call(request).block(block).await.map(|data| read.decode(&data)) {
+                Ok(Some(balance)) => {
+                    let decimals = self.token_decimals(token).await;
+                    let formatted = format_units(balance, decimals)
                         .unwrap_or_else(|_| balance.to_string());
 
+                    let shares = if token.kind == TokenType::Rebasing {
+                        IStETH::new(token.address, &self.provider).sharesOf(address).block(block).call().await.ok()
+                    } else {
+                        None
+                    };
+
                     token_balances.push(TokenBalance {
                         alias: token.alias.clone(),
                         balance,
                         formatted,
+                        decimals,
+                        usd_value: None,
+                        shares,
                     });
                 }
+                Ok(None) => warn!("Error decoding balance {} for {}", token.alias, address),
                 Err(e) => {
-                    eprintln!("Error getting balance {} for {}: {}", token.alias, address, e);
+                    warn!("Error getting balance {} for {}: {}", token.alias, address, e);
                 }
             }
         }
 
+        // NFT holdings
+        let mut nft_balances = Vec::new();
+        for read in self.nft_reads() {
+            let request = TransactionRequest::default()
+                .to(read.nft.address)
+                .input(read.call_data(address).into());
+            let count = match self.provider.call(request).block(block).await {
+                Ok(data) => read.decode(address, &data),
+                Err(_) if read.revert_means_zero() => Some(U256::ZERO),
+                Err(e) => {
+                    warn!("Error getting NFT balance {} for {}: {}", read.nft.alias, address, e);
+                    continue;
+                }
+            };
+            match count {
+                Some(count) => nft_balances.push(read.balance(count)),
+                None => warn!("Error decoding NFT balance {} for {}", read.nft.alias, address),
+            }
+        }
+
         Ok(BalanceInfo {
             network_name,
             chain_id,
@@ -144,29 +472,368 @@ impl<P: Provider> BalanceMonitor<P> {
             address,
             eth_balance,
             eth_formatted,
+            native_symbol: default_native_symbol(),
+            native_decimals: DEFAULT_DECIMALS,
+            eth_usd_value: None,
             token_balances,
+            nft_balances,
+            block_number: None,
+            block_hash: None,
         })
     }
 
-    /// Check balances for all addresses
+    /// Check balances for the given addresses at the given block using a single Multicall3 eth_call
+    pub async fn check_multicall(
+        &self,
+        multicall: Address,
+        addresses: &[AddressConfig],
+        network_name: &str,
+        chain_id: u64,
+        block: BlockId,
+    ) -> Result<Vec<BalanceInfo>> {
+        let contract = IMulticall3::new(multicall, &self.provider);
+
+        // Per address: getEthBalance, balanceOf for each token, sharesOf for
+        // each rebasing token, then NFT reads
+        let rebasing = self.rebasing_tokens();
+        let token_reads = self.token_reads();
+        let nft_reads = self.nft_reads();
+        let mut decimals = Vec::new();
+        for read in &token_reads {
+            decimals.push(self.token_decimals(read.token).await);
+        }
+        let mut calls = Vec::new();
+        for addr_config in addresses {
+            calls.push(IMulticall3::Call3 {
+                target: multicall,
+                allowFailure: true,
+                callData: IMulticall3::getEthBalanceCall { addr: addr_config.address }
+                    .abi_encode()
+                    .into(),
+            });
+            for read in &token_reads {
+                calls.push(IMulticall3::Call3 {
+                    target: read.token.address,
+                    allowFailure: true,
+                    callData: read.call_data(addr_config.address),
+                });
+            }
+            for token in &rebasing {
+                calls.push(IMulticall3::Call3 {
+                    target: token.address,
+                    allowFailure: true,
+                    callData: IStETH::sharesOfCall { account: addr_config.address }
+                        .abi_encode()
+                        .into(),
+                });
+            }
+            for read in &nft_reads {
+                calls.push(IMulticall3::Call3 {
+                    target: read.nft.address,
+                    allowFailure: true,
+                    callData: read.call_data(addr_config.address),
+                });
+            }
+        }
+
+        let returns = contract.aggregate3(calls).block(block).call().await?;
+        let token_count = token_reads.len();
+        let nft_offset = 1 + token_count + rebasing.len();
+        let stride = nft_offset + nft_reads.len();
+        if returns.len() != addresses.len() * stride {
+            eyre::bail!("multicall returned {} results, expected {}", returns.len(), addresses.len() * stride);
+        }
+
+        let mut balances = Vec::new();
+        for (addr_config, chunk) in addresses.iter().zip(returns.chunks(stride)) {
+            let eth_result = &chunk[0];
+            if !eth_result.success {
+                eyre::bail!("getEthBalance failed for {}", addr_config.address);
+            }
+            let eth_balance = IMulticall3::getEthBalanceCall::abi_decode_returns(&eth_result.returnData)?;
+            let eth_formatted = format_units(eth_balance, "ether")?;
+
+            // Shares of rebasing tokens, missing if the token has no sharesOf()
+            let shares: Vec<(&str, U256)> = rebasing.iter()
+                .zip(&chunk[1 + token_count..nft_offset])
+                .filter(|(_, result)| result.success)
+                .filter_map(|(token, result)| {
+                    let shares = IStETH::sharesOfCall::abi_decode_returns(&result.returnData).ok()?;
+                    Some((token.alias.as_str(), shares))
+                })
+                .collect();
+
+            let mut token_balances = Vec::new();
+            for ((read, &decimals), result) in token_reads.iter().zip(&decimals).zip(&chunk[1..=token_count]) {
+                let token = read.token;
+                let decoded = if result.success {
+                    read.decode(&result.returnData)
+                } else {
+                    None
+                };
+
+                match decoded {
+                    Some(balance) => {
+                        let formatted = format_units(balance, decimals)
+                            .unwrap_or_else(|_| balance.to_string());
+
+                        token_balances.push(TokenBalance {
+                            alias: token.alias.clone(),
+                            balance,
+                            formatted,
+                            decimals,
+                            usd_value: None,
+                            shares: shares.iter().find(|(alias, _)| *alias == token.alias).map(|(_, s)| *s),
+                        });
+                    }
+                    None => {
+                        warn!("Error getting balance {} for {}: multicall sub-call failed", token.alias, addr_config.address);
+                    }
+                }
+            }
+
+            let mut nft_balances = Vec::new();
+            for (read, result) in nft_reads.iter().zip(&chunk[nft_offset..]) {
+                let count = if result.success {
+                    read.decode(addr_config.address, &result.returnData)
+                } else if read.revert_means_zero() {
+                    Some(U256::ZERO)
+                } else {
+                    None
+                };
+
+                match count {
+                    Some(count) => nft_balances.push(read.balance(count)),
+                    None => {
+                        warn!("Error getting NFT balance {} for {}: multicall sub-call failed", read.nft.alias, addr_config.address);
+                    }
+                }
+            }
+
+            balances.push(BalanceInfo {
+                network_name: network_name.to_string(),
+                chain_id,
+                alias: addr_config.alias.clone(),
+                address: addr_config.address,
+                eth_balance,
+                eth_formatted,
+                native_symbol: default_native_symbol(),
+                native_decimals: DEFAULT_DECIMALS,
+                eth_usd_value: None,
+                token_balances,
+                nft_balances,
+                block_number: None,
+                block_hash: None,
+            });
+        }
+
+        Ok(balances)
+    }
+
+    /// Tokens whose shares are read alongside the balance
+    fn rebasing_tokens(&self) -> Vec<&TokenConfig> {
+        self.config.tokens.iter().filter(|t| t.kind == TokenType::Rebasing).collect()
+    }
+
+    /// One balance read per token; tokens with an invalid `balance_method` are skipped
+    fn token_reads(&self) -> Vec<TokenRead<'_>> {
+        self.config.tokens.iter()
+            .filter_map(|token| match token.balance_function() {
+                Ok(function) => Some(TokenRead { token, selector: function.map(|function| function.selector().0) }),
+                Err(e) => {
+                    warn!("Skipping token {}: {}", token.alias, e);
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// One read per collection, or per token ID when IDs are configured
+    fn nft_reads(&self) -> Vec<NftRead<'_>> {
+        self.config.nfts.iter()
+            .flat_map(|nft| {
+                let ids: Vec<Option<U256>> = if nft.token_ids.is_empty() {
+                    vec![None]
+                } else {
+                    nft.token_ids.iter().copied().map(Some).collect()
+                };
+                ids.into_iter().map(move |token_id| NftRead { nft, token_id })
+            })
+            .collect()
+    }
+
+    /// Check balances for all configured addresses
     pub async fn check(&self, network_name: String, chain_id: u64) -> Vec<Result<BalanceInfo>> {
-        let mut results = Vec::new();
+        self.check_addresses(&self.config.addresses, network_name, chain_id).await
+    }
+
+    /// Check balances for the given addresses (e.g. including ones added at runtime)
+    pub async fn check_addresses(
+        &self,
+        addresses: &[AddressConfig],
+        network_name: String,
+        chain_id: u64,
+    ) -> Vec<Result<BalanceInfo>> {
+        // Pin all reads to one block so ETH and token balances are consistent
+        let head = match self.read_block().await {
+            Ok(block) => block,
+            // Unconfirmed reads would defeat the point of waiting for confirmations
+            Err(e) if self.config.confirmations != Confirmations::Blocks(0) => {
+                return addresses.iter()
+                    .map(|_| Err(eyre::eyre!("failed to fetch {} block: {}", self.config.confirmations, e)))
+                    .collect();
+            }
+            Err(e) => {
+                warn!("Failed to fetch latest block on {}, reading unpinned balances: {}", network_name, e);
+                None
+            }
+        };
+        let block = head.map_or(BlockId::latest(), |(number, _)| BlockId::number(number));
 
-        for addr_config in &self.config.addresses {
-            let result = self
-                .get_balance(
-                    network_name.clone(),
-                    chain_id,
-                    addr_config.alias.clone(),
-                    addr_config.address,
-                )
-                .await;
-            results.push(result);
+        // Balances can't change while the block stays the same, e.g. on slow chains with short intervals
+        let mut cached = HashMap::new();
+        if let Some(head) = head {
+            let mut cache = self.cache.write().await;
+            if cache.block == Some(head) {
+                cached = std::mem::take(&mut cache.balances);
+            }
+        }
+        let missing: Vec<AddressConfig> = addresses.iter()
+            .filter(|addr_config| !cached.contains_key(&(addr_config.alias.clone(), addr_config.address)))
+            .cloned()
+            .collect();
+        if missing.len() < addresses.len() {
+            debug!("Block unchanged on {}, reusing {} cached balances", network_name, addresses.len() - missing.len());
         }
 
+        // Nothing to read when every balance is cached; even an empty multicall is a request
+        let read = if missing.is_empty() {
+            Vec::new()
+        } else {
+            self.read_balances(&missing, network_name, chain_id, block).await
+        };
+        let mut read = read.into_iter();
+        let mut results = Vec::with_capacity(addresses.len());
+        for addr_config in addresses {
+            match cached.get(&(addr_config.alias.clone(), addr_config.address)) {
+                Some(balance) => results.push(Ok(balance.clone())),
+                None => {
+                    let mut result = read.next().unwrap_or_else(|| Err(eyre::eyre!("balance of {} not read", addr_config.alias)));
+                    if let Ok(balance) = &mut result {
+                        balance.block_number = head.map(|(number, _)| number);
+                        balance.block_hash = head.map(|(_, hash)| hash);
+                    }
+                    results.push(result);
+                }
+            }
+        }
+
+        if let Some(head) = head {
+            let balances = results.iter()
+                .flatten()
+                .map(|balance| ((balance.alias.clone(), balance.address), balance.clone()))
+                .collect();
+            *self.cache.write().await = BlockCache { block: Some(head), balances };
+        }
         results
     }
 
+    /// Number and hash of the block balances were last read at
+    pub async fn last_block(&self) -> Option<(u64, B256)> {
+        self.cache.read().await.block
+    }
+
+    async fn read_balances(
+        &self,
+        addresses: &[AddressConfig],
+        network_name: String,
+        chain_id: u64,
+        block: BlockId,
+    ) -> Vec<Result<BalanceInfo>> {
+        if let Some(multicall) = self.config.multicall {
+            match self.check_multicall(multicall, addresses, &network_name, chain_id, block).await {
+                Ok(balances) => return balances.into_iter().map(Ok).collect(),
+                Err(e) => {
+                    warn!("Multicall failed on {}, falling back to individual calls: {}", network_name, e);
+                }
+            }
+        }
+
+        // One request for all native balances instead of one per address
+        let mut native = Vec::new();
+        if self.config.batch_rpc && addresses.len() > 1 {
+            let owners: Vec<Address> = addresses.iter().map(|addr_config| addr_config.address).collect();
+            match self.batch_native_balances(&owners, block).await {
+                Ok(balances) => native = balances,
+                Err(e) => warn!("Batch request failed on {}, reading balances individually: {}", network_name, e),
+            }
+        }
+
+        // Futures are built up front so the stream doesn't borrow `addresses` (keeps it Send);
+        // results keep the order of `addresses`
+        let reads: Vec<_> = addresses
+            .iter()
+            .enumerate()
+            .map(|(i, addr_config)| {
+                let eth_balance = native.get(i).copied();
+                self.read_address(network_name.clone(), chain_id, addr_config.alias.clone(), addr_config.address, block, eth_balance)
+            })
+            .collect();
+        stream::iter(reads).buffered(self.config.concurrency.get()).collect().await
+    }
+
+    /// Get current base fee per gas in wei (falls back to gas price on pre-London chains)
+    pub async fn get_base_fee(&self) -> Result<u128> {
+        let block = self.provider.get_block_by_number(BlockNumberOrTag::Latest).await?;
+
+        match block.and_then(|b| b.header.base_fee_per_gas) {
+            Some(base_fee) => Ok(base_fee as u128),
+            None => Ok(self.provider.get_gas_price().await?),
+        }
+    }
+
+    /// Get suggested priority fee per gas in wei
+    pub async fn get_priority_fee(&self) -> Result<u128> {
+        Ok(self.provider.get_max_priority_fee_per_gas().await?)
+    }
+
+    /// EIP-4844 blob base fee; fails on chains without blobs
+    pub async fn get_blob_base_fee(&self) -> Result<u128> {
+        Ok(self.provider.get_blob_base_fee().await?)
+    }
+
+    /// Number and hash of the block to read balances at, based on `confirmations`
+    async fn read_block(&self) -> Result<Option<(u64, B256)>> {
+        let tag = match self.config.confirmations {
+            Confirmations::Blocks(0) => BlockNumberOrTag::Latest,
+            Confirmations::Blocks(n) => {
+                let latest = self.provider.get_block_number().await?;
+                BlockNumberOrTag::Number(latest.saturating_sub(n))
+            }
+            Confirmations::Tag(ConfirmationTag::Safe) => BlockNumberOrTag::Safe,
+            Confirmations::Tag(ConfirmationTag::Finalized) => BlockNumberOrTag::Finalized,
+        };
+
+        let block = self.provider.get_block_by_number(tag).await?;
+        Ok(block.map(|b| (b.header.number, b.header.hash)))
+    }
+
+    /// Check whether the block at `number` still has the given hash (false after a reorg)
+    pub async fn is_canonical(&self, number: u64, hash: B256) -> Result<bool> {
+        let block = self.provider.get_block_by_number(BlockNumberOrTag::Number(number)).await?;
+        Ok(block.is_some_and(|b| b.header.hash == hash))
+    }
+
+    /// Provider used for balance reads
+    pub fn provider(&self) -> &P {
+        &self.provider
+    }
+
+    /// Tokens checked for every address
+    pub fn tokens(&self) -> &[TokenConfig] {
+        &self.config.tokens
+    }
+
     /// Check interval from configuration
     pub fn interval(&self) -> Duration {
         self.config.interval
@@ -0,0 +1,303 @@
+use eyre::Result;
+use reqwest::{Client, Url};
+use serde::Deserialize;
+use serde_with::{serde_as, DisplayFromStr};
+use std::collections::HashMap;
+use tokio::sync::RwLock;
+use tracing::warn;
+
+use crate::config::{BeaconConfig, ThrottleConfig, ValidatorConfig};
+use crate::http::http_client;
+use crate::storage::AlertState;
+
+const SLOTS_PER_EPOCH: u64 = 32;
+const GWEI_PER_ETH: f64 = 1e9;
+
+/// Validator state from the beacon node
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ValidatorInfo {
+    #[serde_as(as = "DisplayFromStr")]
+    pub index: u64,
+    /// Current balance in gwei
+    #[serde_as(as = "DisplayFromStr")]
+    pub balance: u64,
+    /// e.g. "active_ongoing", "active_exiting" or "exited_slashed"
+    pub status: String,
+    pub validator: ValidatorDetails,
+}
+
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ValidatorDetails {
+    pub pubkey: String,
+    #[serde_as(as = "DisplayFromStr")]
+    pub effective_balance: u64,
+    pub slashed: bool,
+}
+
+impl ValidatorInfo {
+    pub fn balance_eth(&self) -> f64 {
+        self.balance as f64 / GWEI_PER_ETH
+    }
+
+    pub fn effective_balance_eth(&self) -> f64 {
+        self.validator.effective_balance as f64 / GWEI_PER_ETH
+    }
+
+    /// Whether this is the validator a config entry refers to, by index or pubkey
+    pub fn matches(&self, id: &str) -> bool {
+        id == self.index.to_string() || id.eq_ignore_ascii_case(&self.validator.pubkey)
+    }
+}
+
+#[derive(Deserialize)]
+struct Data<T> {
+    data: T,
+}
+
+#[serde_as]
+#[derive(Deserialize)]
+struct Liveness {
+    #[serde_as(as = "DisplayFromStr")]
+    index: u64,
+    is_live: bool,
+}
+
+/// Client for the standard beacon node REST API
+#[derive(Clone)]
+pub struct BeaconClient {
+    client: Client,
+    base_url: Url,
+}
+
+impl BeaconClient {
+    pub fn new(base_url: Url) -> Self {
+        Self {
+            client: http_client(),
+            base_url,
+        }
+    }
+
+    fn url(&self, path: &str) -> Result<Url> {
+        Ok(Url::parse(&format!("{}{}", self.base_url.as_str().trim_end_matches('/'), path))?)
+    }
+
+    async fn parse<T: for<'de> Deserialize<'de>>(response: reqwest::Response) -> Result<T> {
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            eyre::bail!("beacon node returned {}: {}", status, body);
+        }
+        Ok(response.json::<Data<T>>().await?.data)
+    }
+
+    /// Validators at the head state, by index or pubkey
+    pub async fn validators(&self, ids: &[String]) -> Result<Vec<ValidatorInfo>> {
+        let mut url = self.url("/eth/v1/beacon/states/head/validators")?;
+        url.query_pairs_mut().append_pair("id", &ids.join(","));
+        Self::parse(self.client.get(url).send().await?).await
+    }
+
+    /// Epoch of the head block
+    pub async fn head_epoch(&self) -> Result<u64> {
+        #[serde_as]
+        #[derive(Deserialize)]
+        struct Message {
+            #[serde_as(as = "DisplayFromStr")]
+            slot: u64,
+        }
+        #[derive(Deserialize)]
+        struct Header {
+            message: Message,
+        }
+        #[derive(Deserialize)]
+        struct HeaderData {
+            header: Header,
+        }
+
+        let url = self.url("/eth/v1/beacon/headers/head")?;
+        let head: HeaderData = Self::parse(self.client.get(url).send().await?).await?;
+        Ok(head.header.message.slot / SLOTS_PER_EPOCH)
+    }
+
+    /// Whether each validator performed its duties (attested) in an epoch
+    pub async fn liveness(&self, epoch: u64, indices: &[u64]) -> Result<HashMap<u64, bool>> {
+        let url = self.url(&format!("/eth/v1/validator/liveness/{}", epoch))?;
+        let body: Vec<String> = indices.iter().map(|i| i.to_string()).collect();
+        let liveness: Vec<Liveness> = Self::parse(self.client.post(url).json(&body).send().await?).await?;
+        Ok(liveness.into_iter().map(|l| (l.index, l.is_live)).collect())
+    }
+}
+
+/// Something worth alerting about a validator
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValidatorEvent {
+    Slashed,
+    StatusChanged { old: String, new: String },
+    /// Consecutive epochs without attesting; repeats are throttled
+    MissedAttestations { streak: u32, alert_number: u32, next_alert: String },
+    BalanceDecreased { old_gwei: u64, new_gwei: u64 },
+}
+
+impl ValidatorEvent {
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Slashed => "🚨 Validator was slashed".to_string(),
+            Self::StatusChanged { old, new } => format!("Status: {} → {}", old, new),
+            Self::MissedAttestations { streak, alert_number, next_alert } => {
+                format!("❌ Missed attestations: {} epochs in a row (alert #{}, {})", streak, alert_number, next_alert)
+            }
+            Self::BalanceDecreased { old_gwei, new_gwei } => format!(
+                "📉 Balance: {:.6} → {:.6} ETH",
+                *old_gwei as f64 / GWEI_PER_ETH,
+                *new_gwei as f64 / GWEI_PER_ETH
+            ),
+        }
+    }
+
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Slashed => "slashed",
+            Self::StatusChanged { .. } => "status_changed",
+            Self::MissedAttestations { .. } => "missed_attestations",
+            Self::BalanceDecreased { .. } => "balance_decreased",
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct ValidatorState {
+    info: Option<ValidatorInfo>,
+    missed_streak: u32,
+    missed_alerts: AlertState,
+}
+
+/// Tracks beacon-chain validators and turns their state into alert events
+pub struct ValidatorMonitor {
+    client: BeaconClient,
+    config: BeaconConfig,
+    throttle: ThrottleConfig,
+    states: RwLock<HashMap<String, ValidatorState>>,
+    last_epoch: RwLock<Option<u64>>,
+}
+
+impl ValidatorMonitor {
+    pub fn new(config: BeaconConfig) -> Self {
+        Self {
+            client: BeaconClient::new(config.url.clone()),
+            throttle: config.throttle.clone().unwrap_or_default(),
+            config,
+            states: RwLock::new(HashMap::new()),
+            last_epoch: RwLock::new(None),
+        }
+    }
+
+    /// Schedule of repeated missed attestation alerts, unless the config sets its own `throttle`
+    pub fn with_throttle(mut self, throttle: ThrottleConfig) -> Self {
+        if self.config.throttle.is_none() {
+            self.throttle = throttle;
+        }
+        self
+    }
+
+    pub fn config(&self) -> &BeaconConfig {
+        &self.config
+    }
+
+    /// Fetch all validators and return those with events
+    pub async fn check(&self) -> Result<Vec<(&ValidatorConfig, ValidatorInfo, Vec<ValidatorEvent>)>> {
+        let ids: Vec<String> = self.config.validators.iter().map(|v| v.id.clone()).collect();
+        let infos = self.client.validators(&ids).await?;
+
+        // Attestations of the last finished epoch, once per epoch
+        let liveness = self.liveness(&infos).await;
+
+        let mut results = Vec::new();
+        for validator in &self.config.validators {
+            let Some(info) = infos.iter().find(|info| info.matches(&validator.id)) else {
+                warn!("Validator {} ({}) not found on the beacon node", validator.alias, validator.id);
+                continue;
+            };
+            let live = liveness.as_ref().and_then(|l| l.get(&info.index).copied());
+            let events = self.record(&validator.alias, info.clone(), live).await;
+            if !events.is_empty() {
+                results.push((validator, info.clone(), events));
+            }
+        }
+
+        Ok(results)
+    }
+
+    async fn liveness(&self, infos: &[ValidatorInfo]) -> Option<HashMap<u64, bool>> {
+        let epoch = match self.client.head_epoch().await {
+            Ok(epoch) => epoch.checked_sub(1)?,
+            Err(e) => {
+                warn!("Failed to fetch beacon head: {}", e);
+                return None;
+            }
+        };
+
+        let mut last_epoch = self.last_epoch.write().await;
+        if *last_epoch == Some(epoch) {
+            return None;
+        }
+
+        let indices: Vec<u64> = infos.iter().map(|info| info.index).collect();
+        match self.client.liveness(epoch, &indices).await {
+            Ok(liveness) => {
+                *last_epoch = Some(epoch);
+                Some(liveness)
+            }
+            Err(e) => {
+                warn!("Failed to fetch validator liveness for epoch {}: {}", epoch, e);
+                None
+            }
+        }
+    }
+
+    /// Store a reading and return its events. `live` is whether the validator
+    /// attested in the last epoch, None when not checked this time.
+    pub async fn record(&self, alias: &str, info: ValidatorInfo, live: Option<bool>) -> Vec<ValidatorEvent> {
+        let mut states = self.states.write().await;
+        let state = states.entry(alias.to_string()).or_default();
+        let mut events = Vec::new();
+        let throttle = &self.throttle;
+
+        let was_slashed = state.info.as_ref().is_some_and(|previous| previous.validator.slashed);
+        if info.validator.slashed && !was_slashed {
+            events.push(ValidatorEvent::Slashed);
+        }
+
+        if let Some(previous) = &state.info {
+            if previous.status != info.status {
+                events.push(ValidatorEvent::StatusChanged { old: previous.status.clone(), new: info.status.clone() });
+            }
+            if previous.balance.saturating_sub(info.balance) > self.config.balance_drop_gwei {
+                events.push(ValidatorEvent::BalanceDecreased { old_gwei: previous.balance, new_gwei: info.balance });
+            }
+        }
+
+        match live {
+            Some(true) => {
+                state.missed_streak = 0;
+                state.missed_alerts.reset();
+            }
+            Some(false) => {
+                state.missed_streak += 1;
+                if state.missed_streak >= self.config.missed_attestations && state.missed_alerts.should_send_alert(throttle) {
+                    events.push(ValidatorEvent::MissedAttestations {
+                        streak: state.missed_streak,
+                        alert_number: state.missed_alerts.alert_count + 1,
+                        next_alert: state.missed_alerts.next_alert_hint(throttle),
+                    });
+                    state.missed_alerts.record_alert_sent();
+                }
+            }
+            None => {}
+        }
+
+        state.info = Some(info);
+        events
+    }
+}
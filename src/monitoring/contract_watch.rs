@@ -0,0 +1,166 @@
+use alloy::{
+    primitives::{b256, keccak256, Address, B256, U256},
+    providers::Provider,
+    rpc::{json_rpc::ErrorPayload, types::TransactionRequest},
+    sol_types::SolCall,
+};
+use eyre::Result;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use tokio::sync::RwLock;
+use tracing::warn;
+
+use crate::config::ContractWatchConfig;
+use crate::contracts::IOwnable;
+
+/// EIP-1967 storage slot holding a proxy's implementation address
+const IMPLEMENTATION_SLOT: B256 = b256!("360894a13ba1a3210667c828492db98dca3e2076cc3735a920a3ca505d382bbc");
+
+/// EIP-1967 storage slot holding a proxy's admin address
+const ADMIN_SLOT: B256 = b256!("b53127684a568b3173ae13b9f8a6016e243e63b6e8ee1178d6a717850b5d6103");
+
+/// Code and control of a watched contract at one point in time
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ContractState {
+    /// keccak256 of the deployed bytecode
+    pub code_hash: B256,
+    /// Result of owner(), None if the contract has no owner() function
+    pub owner: Option<Address>,
+    /// EIP-1967 implementation address, None for non-proxies
+    pub implementation: Option<Address>,
+    /// EIP-1967 admin address, None for non-proxies
+    pub admin: Option<Address>,
+}
+
+/// A single field that changed between two contract states
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContractChange {
+    pub field: &'static str,
+    pub old: String,
+    pub new: String,
+}
+
+impl ContractChange {
+    pub fn to_json(&self) -> Value {
+        json!({ "field": self.field, "old": self.old, "new": self.new })
+    }
+}
+
+impl ContractState {
+    /// Fields that differ from a previous state
+    pub fn changes_since(&self, previous: &ContractState) -> Vec<ContractChange> {
+        let mut changes = Vec::new();
+        if self.code_hash != previous.code_hash {
+            changes.push(ContractChange {
+                field: "code",
+                old: format!("{:?}", previous.code_hash),
+                new: format!("{:?}", self.code_hash),
+            });
+        }
+
+        let addresses = [
+            ("owner", previous.owner, self.owner),
+            ("implementation", previous.implementation, self.implementation),
+            ("admin", previous.admin, self.admin),
+        ];
+        for (field, old, new) in addresses {
+            if old != new {
+                changes.push(ContractChange { field, old: describe_address(old), new: describe_address(new) });
+            }
+        }
+
+        changes
+    }
+}
+
+fn describe_address(address: Option<Address>) -> String {
+    address.map(|a| a.to_string()).unwrap_or_else(|| "none".to_string())
+}
+
+/// Read a contract's bytecode hash, owner and EIP-1967 proxy slots
+pub async fn read_contract_state<P: Provider>(provider: &P, address: Address) -> Result<ContractState> {
+    let code = provider.get_code_at(address).await?;
+
+    let request = TransactionRequest::default()
+        .to(address)
+        .input(IOwnable::ownerCall {}.abi_encode().into());
+    let owner = match provider.call(request).await {
+        Ok(data) => IOwnable::ownerCall::abi_decode_returns(&data).ok(),
+        // owner() reverted or doesn't exist. Any other error, e.g. a rate limit, fails the
+        // reading, so it can't be mistaken for the owner being removed.
+        Err(e) if e.as_error_resp().is_some_and(is_revert) => None,
+        Err(e) => return Err(e.into()),
+    };
+
+    Ok(ContractState {
+        code_hash: keccak256(&code),
+        owner,
+        implementation: read_address_slot(provider, address, IMPLEMENTATION_SLOT).await?,
+        admin: read_address_slot(provider, address, ADMIN_SLOT).await?,
+    })
+}
+
+/// True if the node ran the call and it reverted: EIP-1474 code 3, or geth's -32000
+/// "execution reverted", which carries no data when the function doesn't exist
+fn is_revert(error: &ErrorPayload) -> bool {
+    !error.is_retry_err() && (error.code == 3 || error.message.contains("revert"))
+}
+
+/// Address stored in a slot, None if the slot is empty
+async fn read_address_slot<P: Provider>(provider: &P, address: Address, slot: B256) -> Result<Option<Address>> {
+    let value = provider.get_storage_at(address, slot.into()).await?;
+    if value == U256::ZERO {
+        return Ok(None);
+    }
+    Ok(Some(Address::from_word(value.into())))
+}
+
+/// Watches contracts for bytecode and ownership changes, such as proxy upgrades
+/// and admin key rotation. The first reading of each contract is the baseline.
+#[derive(Debug, Default)]
+pub struct ContractWatcher {
+    contracts: Vec<ContractWatchConfig>,
+    states: RwLock<HashMap<Address, ContractState>>,
+}
+
+impl ContractWatcher {
+    pub fn new(contracts: Vec<ContractWatchConfig>) -> Self {
+        Self {
+            contracts,
+            states: RwLock::new(HashMap::new()),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.contracts.is_empty()
+    }
+
+    /// Read all watched contracts and return the ones that changed since the last check
+    pub async fn check<P: Provider>(&self, provider: &P) -> Vec<(&ContractWatchConfig, Vec<ContractChange>)> {
+        let mut changed = Vec::new();
+
+        for contract in &self.contracts {
+            let state = match read_contract_state(provider, contract.address).await {
+                Ok(state) => state,
+                Err(e) => {
+                    warn!("Error reading contract {} ({}): {}", contract.alias, contract.address, e);
+                    continue;
+                }
+            };
+
+            if let Some(changes) = self.record(contract.address, state).await {
+                changed.push((contract, changes));
+            }
+        }
+
+        changed
+    }
+
+    /// Store a reading. Returns the changes against the previous reading, if any.
+    pub async fn record(&self, address: Address, state: ContractState) -> Option<Vec<ContractChange>> {
+        let previous = self.states.write().await.insert(address, state.clone())?;
+        let changes = state.changes_since(&previous);
+        (!changes.is_empty()).then_some(changes)
+    }
+}
@@ -0,0 +1,66 @@
+use super::to_units;
+use crate::config::{AddressConfig, CorridorConfig};
+use alloy::primitives::U256;
+use std::collections::HashMap;
+use tokio::sync::RwLock;
+
+/// Position of a balance relative to its expected corridor
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CorridorEvent {
+    /// Balance dropped below the corridor
+    Below { min: f64 },
+    /// Balance rose above the corridor
+    Above { max: f64 },
+    /// Balance is back inside the corridor after leaving it
+    Inside,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Side {
+    Below,
+    Above,
+}
+
+/// Alerts when the native balance of an address with a `corridor` leaves its expected range
+#[derive(Debug, Default)]
+pub struct CorridorMonitor {
+    corridors: HashMap<String, CorridorConfig>,
+    /// Aliases reported outside their corridor, with the side they left on
+    outside: RwLock<HashMap<String, Side>>,
+}
+
+impl CorridorMonitor {
+    pub fn new(addresses: &[AddressConfig]) -> Self {
+        let corridors = addresses
+            .iter()
+            .filter_map(|addr| Some((addr.alias.clone(), addr.corridor?)))
+            .collect();
+        Self { corridors, outside: RwLock::new(HashMap::new()) }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.corridors.is_empty()
+    }
+
+    /// Check a raw balance with `decimals` decimals. Leaving the corridor is reported once
+    /// per side; the first balance back inside reports the address inside again.
+    pub async fn check(&self, alias: &str, balance: U256, decimals: u8) -> Option<CorridorEvent> {
+        let corridor = self.corridors.get(alias)?;
+        let (side, event) = match (corridor.min, corridor.max) {
+            (Some(min), _) if balance < to_units(min, decimals) => (Some(Side::Below), CorridorEvent::Below { min }),
+            (_, Some(max)) if balance > to_units(max, decimals) => (Some(Side::Above), CorridorEvent::Above { max }),
+            _ => (None, CorridorEvent::Inside),
+        };
+
+        let mut outside = self.outside.write().await;
+        let previous = outside.get(alias).copied();
+        if previous == side {
+            return None;
+        }
+        match side {
+            Some(side) => outside.insert(alias.to_string(), side),
+            None => outside.remove(alias),
+        };
+        Some(event)
+    }
+}
@@ -0,0 +1,313 @@
+use alloy::primitives::{utils::format_units, U256};
+use eyre::Result;
+use reqwest::{Client, Url};
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tracing::warn;
+
+use super::to_units;
+use crate::config::{CosmosAddressConfig, CosmosChainConfig, ThrottleConfig};
+use crate::http::http_client;
+use crate::storage::{CosmosStateStorage, StateBackend};
+
+/// Amount of a denom, in base units
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CosmosCoin {
+    pub denom: String,
+    pub amount: U256,
+}
+
+#[derive(Deserialize)]
+struct Coin {
+    denom: String,
+    /// Integer for bank balances, decimal for distribution rewards
+    amount: String,
+}
+
+impl Coin {
+    fn parse(self) -> Option<CosmosCoin> {
+        let integer = self.amount.split('.').next().unwrap_or_default();
+        Some(CosmosCoin { denom: self.denom, amount: U256::from_str_radix(integer, 10).ok()? })
+    }
+}
+
+#[derive(Deserialize)]
+struct BalancesResponse {
+    balances: Vec<Coin>,
+}
+
+#[derive(Deserialize)]
+struct RewardsResponse {
+    #[serde(default)]
+    total: Vec<Coin>,
+}
+
+/// Client for a Cosmos SDK LCD (REST) endpoint
+#[derive(Clone)]
+pub struct CosmosClient {
+    client: Client,
+    lcd_url: Url,
+}
+
+impl CosmosClient {
+    pub fn new(lcd_url: Url) -> Self {
+        Self {
+            client: http_client(),
+            lcd_url,
+        }
+    }
+
+    async fn get<T: for<'de> Deserialize<'de>>(&self, path: &str) -> Result<T> {
+        let url = Url::parse(&format!("{}{}", self.lcd_url.as_str().trim_end_matches('/'), path))?;
+        let response = self.client.get(url).send().await?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            eyre::bail!("LCD returned {}: {}", status, body);
+        }
+        Ok(response.json().await?)
+    }
+
+    /// Bank balances of an address, including IBC denoms. Denoms with zero balance are omitted.
+    pub async fn balances(&self, address: &str) -> Result<Vec<CosmosCoin>> {
+        let response: BalancesResponse = self
+            .get(&format!("/cosmos/bank/v1beta1/balances/{}?pagination.limit=1000", address))
+            .await?;
+        Ok(response.balances.into_iter().filter_map(Coin::parse).collect())
+    }
+
+    /// Unclaimed staking rewards of a delegator, summed over validators
+    pub async fn rewards(&self, address: &str) -> Result<Vec<CosmosCoin>> {
+        let response: RewardsResponse = self
+            .get(&format!("/cosmos/distribution/v1beta1/delegators/{}/rewards", address))
+            .await?;
+        Ok(response.total.into_iter().filter_map(Coin::parse).collect())
+    }
+}
+
+/// Something worth alerting about a Cosmos address
+#[derive(Debug, Clone, PartialEq)]
+pub enum CosmosEvent {
+    Changed { symbol: String, old: String, new: String },
+    LowBalance { symbol: String, amount: String, threshold: f64, alert_number: u32, next_alert: String },
+    RewardsReady { symbol: String, amount: String, threshold: f64 },
+}
+
+impl CosmosEvent {
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Changed { symbol, old, new } => format!("💰 {}: {} → {}", symbol, old, new),
+            Self::LowBalance { symbol, amount, threshold, alert_number, next_alert } => format!(
+                "⚠️ Low balance #{}: {} {} (below {}). {}",
+                alert_number, amount, symbol, threshold, next_alert
+            ),
+            Self::RewardsReady { symbol, amount, threshold } => {
+                format!("🎁 Staking rewards: {} {} (above {}), ready to claim", amount, symbol, threshold)
+            }
+        }
+    }
+
+    /// The event without its throttle hints, which differ between instances
+    pub fn dedup_id(&self) -> String {
+        match self {
+            Self::Changed { symbol, old, new } => format!("{}:{}->{}", symbol, old, new),
+            Self::LowBalance { symbol, threshold, .. } => format!("{}<{}", symbol, threshold),
+            Self::RewardsReady { symbol, threshold, .. } => format!("{} rewards>{}", symbol, threshold),
+        }
+    }
+}
+
+/// Last readings and alert state of the Cosmos addresses of all chains, saved to the
+/// state backend so a restart picks up where the watcher left off
+#[derive(Clone, Default)]
+pub struct CosmosStates {
+    storage: Arc<RwLock<CosmosStateStorage>>,
+    backend: Option<Arc<dyn StateBackend>>,
+}
+
+impl CosmosStates {
+    pub async fn new(backend: Arc<dyn StateBackend>) -> Self {
+        let storage = backend.load_cosmos_states().await.unwrap_or_else(|e| {
+            warn!("Failed to load Cosmos state: {}", e);
+            CosmosStateStorage::new()
+        });
+
+        Self { storage: Arc::new(RwLock::new(storage)), backend: Some(backend) }
+    }
+}
+
+/// Tracks native and IBC balances and staking rewards on a Cosmos SDK chain
+pub struct CosmosMonitor {
+    client: CosmosClient,
+    config: CosmosChainConfig,
+    throttle: ThrottleConfig,
+    states: CosmosStates,
+}
+
+impl CosmosMonitor {
+    pub fn new(config: CosmosChainConfig) -> Self {
+        Self {
+            client: CosmosClient::new(config.lcd_url.clone()),
+            config,
+            throttle: ThrottleConfig::default(),
+            states: CosmosStates::default(),
+        }
+    }
+
+    /// Keep address state in `states` instead of memory only
+    pub fn with_states(mut self, states: CosmosStates) -> Self {
+        self.states = states;
+        self
+    }
+
+    /// Schedule of repeated low balance alerts
+    pub fn with_throttle(mut self, throttle: ThrottleConfig) -> Self {
+        self.throttle = throttle;
+        self
+    }
+
+    pub fn config(&self) -> &CosmosChainConfig {
+        &self.config
+    }
+
+    /// Display symbol and decimals of a denom, e.g. "ATOM" and 6 for an IBC denom
+    fn denom_info(&self, denom: &str) -> (String, u8) {
+        match self.config.denoms.iter().find(|d| d.denom == denom) {
+            Some(info) => (info.symbol.clone(), info.decimals),
+            None => (denom.to_string(), 0),
+        }
+    }
+
+    /// Amount in display units, e.g. "1.500000" for 1500000 uatom
+    pub fn format(&self, denom: &str, amount: U256) -> String {
+        let (_, decimals) = self.denom_info(denom);
+        format_units(amount, decimals).unwrap_or_else(|_| amount.to_string())
+    }
+
+    /// Threshold configured for a denom, by symbol or denom
+    fn threshold(&self, thresholds: &HashMap<String, f64>, denom: &str) -> Option<f64> {
+        let (symbol, _) = self.denom_info(denom);
+        thresholds.get(&symbol).or_else(|| thresholds.get(denom)).copied()
+    }
+
+    /// Fetch all addresses and return the events of each, or the error its balances failed with
+    pub async fn check(&self) -> Vec<(&CosmosAddressConfig, Result<Vec<CosmosEvent>>)> {
+        let mut results = Vec::new();
+
+        for address in &self.config.addresses {
+            let balances = match self.client.balances(&address.address).await {
+                Ok(balances) => balances,
+                Err(e) => {
+                    results.push((address, Err(e)));
+                    continue;
+                }
+            };
+            let rewards = if address.rewards_above.is_empty() {
+                Vec::new()
+            } else {
+                self.client.rewards(&address.address).await.unwrap_or_else(|e| {
+                    warn!("Failed to fetch staking rewards on {}: {}: {}", self.config.name, address.alias, e);
+                    Vec::new()
+                })
+            };
+
+            results.push((address, Ok(self.record(address, &balances, &rewards).await)));
+        }
+
+        results
+    }
+
+    /// Store a reading and return its events. The first reading is the baseline
+    /// for change alerts; low balance alerts repeat on the low balance schedule.
+    /// The address state is saved to the state backend when it changed.
+    pub async fn record(
+        &self,
+        address: &CosmosAddressConfig,
+        balances: &[CosmosCoin],
+        rewards: &[CosmosCoin],
+    ) -> Vec<CosmosEvent> {
+        let mut storage = self.states.storage.write().await;
+        let state = storage.get_or_create(&self.config.name, &address.alias);
+        let before = state.clone();
+        let current: HashMap<String, U256> = balances.iter().map(|c| (c.denom.clone(), c.amount)).collect();
+        let mut events = Vec::new();
+
+        // Balance changes, including denoms that appeared or were emptied
+        if let Some(previous) = &state.balances {
+            let mut denoms: Vec<&String> = previous.keys().chain(current.keys()).collect();
+            denoms.sort();
+            denoms.dedup();
+            for denom in denoms {
+                let old = previous.get(denom).copied().unwrap_or_default();
+                let new = current.get(denom).copied().unwrap_or_default();
+                if old != new {
+                    events.push(CosmosEvent::Changed {
+                        symbol: self.denom_info(denom).0,
+                        old: self.format(denom, old),
+                        new: self.format(denom, new),
+                    });
+                }
+            }
+        }
+
+        // Low balances; a missing denom has a zero balance
+        let mut low = Vec::new();
+        for (key, &threshold) in &address.min_balances {
+            let denom = self.config.denoms.iter()
+                .find(|d| &d.symbol == key)
+                .map(|d| d.denom.as_str())
+                .unwrap_or(key);
+            let amount = current.get(denom).copied().unwrap_or_default();
+            let (symbol, decimals) = self.denom_info(denom);
+            if amount < to_units(threshold, decimals) {
+                low.push((symbol, self.format(denom, amount), threshold));
+            }
+        }
+        if low.is_empty() {
+            state.low_alerts.reset();
+        } else if state.low_alerts.should_send_alert(&self.throttle) {
+            let alert_number = state.low_alerts.alert_count + 1;
+            let next_alert = state.low_alerts.next_alert_hint(&self.throttle);
+            for (symbol, amount, threshold) in low {
+                events.push(CosmosEvent::LowBalance {
+                    symbol,
+                    amount,
+                    threshold,
+                    alert_number,
+                    next_alert: next_alert.clone(),
+                });
+            }
+            state.low_alerts.record_alert_sent();
+        }
+
+        // Rewards crossing their claim threshold; claimed rewards drop out of the response
+        let mut ready = HashSet::new();
+        for coin in rewards {
+            let Some(threshold) = self.threshold(&address.rewards_above, &coin.denom) else {
+                continue;
+            };
+            let (symbol, decimals) = self.denom_info(&coin.denom);
+            if coin.amount > to_units(threshold, decimals) {
+                if !state.rewards_ready.contains(&coin.denom) {
+                    let amount = self.format(&coin.denom, coin.amount);
+                    events.push(CosmosEvent::RewardsReady { symbol, amount, threshold });
+                }
+                ready.insert(coin.denom.clone());
+            }
+        }
+        state.rewards_ready = ready;
+
+        state.balances = Some(current);
+
+        if *state != before {
+            if let Some(backend) = &self.states.backend {
+                if let Err(e) = backend.save_cosmos_states(&storage).await {
+                    warn!("Failed to save Cosmos state: {}", e);
+                }
+            }
+        }
+        events
+    }
+}
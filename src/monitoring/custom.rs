@@ -0,0 +1,144 @@
+use alloy::providers::Provider;
+use async_trait::async_trait;
+use eyre::Result;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tracing::warn;
+
+use super::BalanceInfo;
+use crate::config::{AlertSeverity, NetworkConfig};
+use crate::notifiers::MonitorAlert;
+
+/// Identical custom alerts are sent at most once per hour by default
+const DEFAULT_REPEAT_AFTER_SECS: u64 = 3600;
+
+/// What a custom check sees of the network cycle it runs in
+pub struct CheckContext<'a> {
+    pub network: &'a NetworkConfig,
+    pub provider: &'a dyn Provider,
+    /// Balances read during this cycle
+    pub balances: &'a [BalanceInfo],
+    /// Unix timestamp in seconds
+    pub now: u64,
+}
+
+/// Alert raised by a custom check, routed like rule alerts
+#[derive(Debug, Clone)]
+pub struct CustomAlert {
+    pub severity: AlertSeverity,
+    /// Channel names; empty sends to every channel
+    pub channels: Vec<String>,
+    /// Address the alert is about, so maintenance windows for it apply
+    pub alias: Option<String>,
+    pub alert: MonitorAlert,
+}
+
+impl CustomAlert {
+    /// Warning sent to every channel
+    pub fn new(alert: MonitorAlert) -> Self {
+        Self { severity: AlertSeverity::Warning, channels: Vec::new(), alias: None, alert }
+    }
+
+    pub fn with_severity(mut self, severity: AlertSeverity) -> Self {
+        self.severity = severity;
+        self
+    }
+
+    pub fn with_channels(mut self, channels: Vec<String>) -> Self {
+        self.channels = channels;
+        self
+    }
+
+    pub fn with_alias(mut self, alias: &str) -> Self {
+        self.alias = Some(alias.to_string());
+        self
+    }
+}
+
+/// Check run once per network cycle after balances were read, for checks the config
+/// can't express, e.g. reads of proprietary contracts. Its alerts go through the same
+/// maintenance windows, escalation and channel routing as rule alerts.
+#[async_trait]
+pub trait CustomCheck: Send + Sync {
+    /// Name shown in logs
+    fn name(&self) -> &str;
+
+    /// Whether the check runs on a network; every EVM network by default
+    fn applies_to(&self, _network: &NetworkConfig) -> bool {
+        true
+    }
+
+    /// Run the check; an error is logged and the cycle goes on
+    async fn run(&self, context: &CheckContext<'_>) -> Result<Vec<CustomAlert>>;
+}
+
+/// Registered custom checks. An alert with the same network, kind and title as one
+/// sent less than `repeat_after_secs` ago is dropped, so a condition that holds for
+/// many cycles doesn't alert on each of them.
+pub struct CustomChecks {
+    checks: Vec<Box<dyn CustomCheck>>,
+    repeat_after_secs: u64,
+    /// Last time each alert was let through, by (network, kind, title)
+    sent: Mutex<HashMap<(String, String, String), u64>>,
+}
+
+impl CustomChecks {
+    pub fn new() -> Self {
+        Self {
+            checks: Vec::new(),
+            repeat_after_secs: DEFAULT_REPEAT_AFTER_SECS,
+            sent: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Register a check
+    pub fn with_check(mut self, check: impl CustomCheck + 'static) -> Self {
+        self.checks.push(Box::new(check));
+        self
+    }
+
+    /// Minimum time between identical alerts
+    pub fn with_repeat_after(mut self, secs: u64) -> Self {
+        self.repeat_after_secs = secs;
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.checks.is_empty()
+    }
+
+    /// Names of the registered checks
+    pub fn names(&self) -> Vec<&str> {
+        self.checks.iter().map(|check| check.name()).collect()
+    }
+
+    /// Run every check applying to the network; returns the alerts not throttled
+    pub async fn run(&self, context: &CheckContext<'_>) -> Vec<CustomAlert> {
+        let mut alerts = Vec::new();
+        for check in self.checks.iter().filter(|check| check.applies_to(context.network)) {
+            match check.run(context).await {
+                Ok(raised) => alerts.extend(raised),
+                Err(e) => warn!("Custom check {} failed on {}: {}", check.name(), context.network.name, e),
+            }
+        }
+        alerts.retain(|alert| self.take(alert, context.now));
+        alerts
+    }
+
+    /// Record an alert as sent at `now`; false if an identical one was sent recently
+    pub fn take(&self, alert: &CustomAlert, now: u64) -> bool {
+        let key = (alert.alert.network_name.clone(), alert.alert.kind.clone(), alert.alert.title.clone());
+        let mut sent = self.sent.lock().unwrap_or_else(|e| e.into_inner());
+        if sent.get(&key).is_some_and(|&at| now < at + self.repeat_after_secs) {
+            return false;
+        }
+        sent.insert(key, now);
+        true
+    }
+}
+
+impl Default for CustomChecks {
+    fn default() -> Self {
+        Self::new()
+    }
+}
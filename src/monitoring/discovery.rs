@@ -0,0 +1,109 @@
+use alloy::{
+    primitives::{Address, B256, U256},
+    providers::Provider,
+    rpc::types::Filter,
+    sol_types::SolEvent,
+};
+use eyre::Result;
+use std::collections::{BTreeSet, HashSet};
+
+use super::{to_units, IndexerClient};
+use crate::config::{TokenConfig, TokenDiscoveryConfig};
+use crate::contracts::IERC20;
+use crate::logger::shorten_address;
+
+/// Longest symbol used as an alias; spam tokens often use a URL as their symbol
+const MAX_SYMBOL_LEN: usize = 16;
+
+/// Find the ERC-20 tokens the holders received in the last `lookback_blocks` and
+/// still hold at least `min_balance` of, leaving out `known` and ignored tokens.
+/// Transfers come from the indexer when given, from the nodes' logs otherwise.
+/// Tokens are aliased by their symbol, followed by the short contract address when
+/// the symbol is unusable or already taken. Only `[A-Za-z0-9._-]` symbols are
+/// used, so an airdrop can't put markup into alert messages.
+pub async fn discover_tokens<P: Provider>(
+    provider: &P,
+    indexer: Option<&IndexerClient>,
+    config: &TokenDiscoveryConfig,
+    holders: &[Address],
+    known: &[TokenConfig],
+) -> Result<Vec<TokenConfig>> {
+    let mut taken: HashSet<String> = known.iter().map(|token| token.alias.clone()).collect();
+    let mut found = Vec::new();
+    for address in received_tokens(provider, indexer, config, holders).await? {
+        if known.iter().any(|token| token.address == address) || config.ignore.contains(&address) {
+            continue;
+        }
+        let contract = IERC20::new(address, provider);
+        // Contracts without decimals(), e.g. NFT collections sharing the Transfer event, aren't ERC-20s
+        let Ok(decimals) = contract.decimals().call().await else { continue };
+
+        let min = to_units(config.min_balance, decimals).max(U256::from(1));
+        let mut held = false;
+        for holder in holders {
+            if contract.balanceOf(*holder).call().await.is_ok_and(|balance| balance >= min) {
+                held = true;
+                break;
+            }
+        }
+        if !held {
+            continue;
+        }
+
+        let symbol = contract.symbol().call().await.ok().filter(|symbol| {
+            !symbol.is_empty()
+                && symbol.len() <= MAX_SYMBOL_LEN
+                && symbol.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-'))
+        });
+        let alias = match symbol {
+            Some(symbol) if !taken.contains(&symbol) => symbol,
+            symbol => format!("{} {}", symbol.as_deref().unwrap_or("Token"), shorten_address(&address.to_string())),
+        };
+        taken.insert(alias.clone());
+
+        let mut token = TokenConfig::new(&alias, address);
+        token.decimals = Some(decimals);
+        found.push(token);
+    }
+    Ok(found)
+}
+
+/// Contracts that emitted a Transfer to one of the holders in the last `lookback_blocks`.
+/// Logs are scanned back from head in ranges of `max_range` blocks.
+async fn received_tokens<P: Provider>(
+    provider: &P,
+    indexer: Option<&IndexerClient>,
+    config: &TokenDiscoveryConfig,
+    holders: &[Address],
+) -> Result<BTreeSet<Address>> {
+    let mut tokens = BTreeSet::new();
+    if holders.is_empty() {
+        return Ok(tokens);
+    }
+    let head = provider.get_block_number().await?;
+    let mut start = head.saturating_sub(config.lookback_blocks.saturating_sub(1));
+
+    if let Some(indexer) = indexer {
+        for holder in holders {
+            let transfers = indexer.transfers("tokentx", *holder, start, head).await?;
+            tokens.extend(
+                transfers.iter()
+                    .filter(|transfer| transfer.to.parse() == Ok(*holder))
+                    .filter_map(|transfer| transfer.contract_address.parse::<Address>().ok()),
+            );
+        }
+        return Ok(tokens);
+    }
+
+    // Transfer(from, to, value): topic2 = to
+    let filter = Filter::new()
+        .event_signature(IERC20::Transfer::SIGNATURE_HASH)
+        .topic2(holders.iter().map(|holder| holder.into_word()).collect::<Vec<B256>>());
+    while start <= head {
+        let end = head.min(start.saturating_add(config.max_range.max(1) - 1));
+        let logs = provider.get_logs(&filter.clone().from_block(start).to_block(end)).await?;
+        tokens.extend(logs.iter().map(|log| log.address()));
+        start = end + 1;
+    }
+    Ok(tokens)
+}
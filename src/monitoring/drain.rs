@@ -0,0 +1,38 @@
+use super::{to_units, BalanceInfo};
+use alloy::primitives::U256;
+
+/// Asset whose balance dropped to (near) zero since the previous check
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DrainedAsset {
+    /// Native symbol such as "ETH", or token alias
+    pub asset: String,
+    pub old_formatted: String,
+    pub new_formatted: String,
+}
+
+/// Assets of `current` that were drained since `previous`: the balance was above zero
+/// and fell to at most `dust_percent` of it
+pub fn drained_assets(current: &BalanceInfo, previous: &BalanceInfo, dust_percent: f64) -> Vec<DrainedAsset> {
+    // new / old <= dust_percent / 100, compared on raw balances with the percentage in 1e-4 steps
+    let dust = to_units(dust_percent, 4);
+    let drained = |asset: &str, (old, old_formatted): (U256, &str), (new, new_formatted): (U256, &str)| {
+        let is_drained = !old.is_zero()
+            && new.saturating_mul(U256::from(1_000_000)) <= old.saturating_mul(dust);
+        is_drained.then(|| DrainedAsset {
+            asset: asset.to_string(),
+            old_formatted: old_formatted.to_string(),
+            new_formatted: new_formatted.to_string(),
+        })
+    };
+
+    let native = drained(
+        &current.native_symbol,
+        (previous.eth_balance, &previous.eth_formatted),
+        (current.eth_balance, &current.eth_formatted),
+    );
+    let tokens = current.token_balances.iter().filter_map(|token| {
+        let old = previous.token_balances.iter().find(|old| old.alias == token.alias)?;
+        drained(&token.alias, (old.balance, &old.formatted), (token.balance, &token.formatted))
+    });
+    native.into_iter().chain(tokens).collect()
+}
@@ -0,0 +1,122 @@
+use super::BalanceInfo;
+use crate::config::{EntityConfig, NetworkConfig};
+use crate::pricing::balance_value_usd;
+use std::collections::{BTreeSet, HashSet};
+use std::sync::Mutex;
+
+/// USD value of all of an entity's balances
+#[derive(Debug, Clone, PartialEq)]
+pub struct EntityTotal {
+    pub name: String,
+    /// Networks the entity has addresses on, sorted
+    pub networks: Vec<String>,
+    pub usd: f64,
+}
+
+/// Change of an entity's USD total relative to its minimum
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EntityEvent {
+    /// Total dropped below `min_total_usd`
+    Below { min: f64 },
+    /// Total is back at or above the minimum
+    Recovered { min: f64 },
+}
+
+struct Entity {
+    config: EntityConfig,
+    /// (network, alias) of the entity's addresses
+    members: HashSet<(String, String)>,
+}
+
+/// Entities resolved against the configured addresses. Their balances are summed
+/// across networks for reports, and checked against `min_total_usd`.
+#[derive(Default)]
+pub struct Entities {
+    entities: Vec<Entity>,
+    /// Names of the entities reported below their minimum
+    below: Mutex<HashSet<String>>,
+}
+
+impl Entities {
+    pub fn new(configs: &[EntityConfig], networks: &[NetworkConfig]) -> Self {
+        let entities = configs
+            .iter()
+            .map(|config| Entity {
+                config: config.clone(),
+                members: networks
+                    .iter()
+                    .flat_map(|network| network.addresses.iter().map(move |addr| (network, addr)))
+                    .filter(|(_, addr)| config.contains(addr))
+                    .map(|(network, addr)| (network.name.clone(), addr.alias.clone()))
+                    .collect(),
+            })
+            .collect();
+        Self { entities, below: Mutex::new(HashSet::new()) }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entities.is_empty()
+    }
+
+    /// Whether any entity has a minimum to check
+    pub fn has_thresholds(&self) -> bool {
+        self.entities.iter().any(|entity| entity.config.min_total_usd.is_some())
+    }
+
+    /// Names of the entities in config order
+    pub fn names(&self) -> Vec<&str> {
+        self.entities.iter().map(|entity| entity.config.name.as_str()).collect()
+    }
+
+    /// Entity an address belongs to, if any
+    pub fn entity_of(&self, network: &str, alias: &str) -> Option<&str> {
+        self.entities
+            .iter()
+            .find(|entity| entity.members.contains(&(network.to_string(), alias.to_string())))
+            .map(|entity| entity.config.name.as_str())
+    }
+
+    /// (network, alias) of an entity's addresses; None for an unknown entity
+    pub fn members(&self, name: &str) -> Option<&HashSet<(String, String)>> {
+        self.entities.iter().find(|entity| entity.config.name == name).map(|entity| &entity.members)
+    }
+
+    /// USD total of an entity over the balances; None while any of its addresses
+    /// has no balance or no price, since a partial total would understate it
+    pub fn total(&self, name: &str, balances: &[BalanceInfo]) -> Option<EntityTotal> {
+        let members = self.members(name)?;
+        let mut networks = BTreeSet::new();
+        let mut usd = 0.0;
+        for (network, alias) in members {
+            let balance = balances.iter().find(|b| &b.network_name == network && &b.alias == alias)?;
+            usd += balance_value_usd(balance)?;
+            networks.insert(network.clone());
+        }
+        (!members.is_empty()).then(|| EntityTotal { name: name.to_string(), networks: networks.into_iter().collect(), usd })
+    }
+
+    /// Check entity totals against their minimums over the latest balances of every
+    /// network, returning the state transitions
+    pub fn check(&self, balances: &[BalanceInfo]) -> Vec<(EntityTotal, EntityEvent)> {
+        let mut events = Vec::new();
+        let mut below = self.below.lock().unwrap_or_else(|e| e.into_inner());
+        for entity in &self.entities {
+            let Some(min) = entity.config.min_total_usd else { continue };
+            let Some(total) = self.total(&entity.config.name, balances) else { continue };
+
+            let is_below = total.usd < min;
+            if is_below == below.contains(&total.name) {
+                continue;
+            }
+            let event = if is_below {
+                below.insert(total.name.clone());
+                EntityEvent::Below { min }
+            } else {
+                below.remove(&total.name);
+                EntityEvent::Recovered { min }
+            };
+            events.push((total, event));
+        }
+        events
+    }
+}
@@ -0,0 +1,171 @@
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use tokio::sync::RwLock;
+
+use crate::config::GasConfig;
+
+const WEI_PER_GWEI: f64 = 1e9;
+
+/// Period the blob base fee range is kept for
+const BLOB_FEE_WINDOW_SECS: u64 = 24 * 3600;
+
+/// Gas prices observed on a network
+#[derive(Debug, Clone, Serialize)]
+pub struct GasPrice {
+    pub network_name: String,
+    pub chain_id: u64,
+    /// Base fee per gas in wei (gas price on pre-London chains)
+    pub base_fee_wei: u128,
+    /// Suggested priority fee per gas in wei, if the node supports it
+    pub priority_fee_wei: Option<u128>,
+    /// EIP-4844 blob base fee per blob gas in wei, when tracked
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub blob_base_fee_wei: Option<u128>,
+    /// Blob base fee range over the last 24 hours, filled in when recorded
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub blob_base_fee_24h: Option<FeeRange>,
+    /// When the prices were read (Unix timestamp in seconds)
+    pub updated_at: u64,
+}
+
+/// Fee in gwei without trailing zeros, exact down to 1 wei since blob fees are
+/// often far below 1 gwei
+pub fn format_gwei(wei: u128) -> String {
+    let gwei = format!("{}.{:09}", wei / 1_000_000_000, wei % 1_000_000_000);
+    gwei.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+/// Lowest, average and highest fee over a period, in wei
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct FeeRange {
+    pub min_wei: u128,
+    pub avg_wei: u128,
+    pub max_wei: u128,
+    /// Readings the range is computed from
+    pub samples: usize,
+}
+
+impl FeeRange {
+    fn of(fees: impl IntoIterator<Item = u128>) -> Option<Self> {
+        let (mut min_wei, mut max_wei, mut sum, mut samples) = (u128::MAX, 0, 0u128, 0);
+        for fee in fees {
+            min_wei = min_wei.min(fee);
+            max_wei = max_wei.max(fee);
+            sum = sum.saturating_add(fee);
+            samples += 1;
+        }
+        (samples > 0).then(|| Self { min_wei, avg_wei: sum / samples as u128, max_wei, samples })
+    }
+
+}
+
+impl GasPrice {
+    pub fn base_fee_gwei(&self) -> f64 {
+        self.base_fee_wei as f64 / WEI_PER_GWEI
+    }
+
+    pub fn priority_fee_gwei(&self) -> Option<f64> {
+        self.priority_fee_wei.map(|fee| fee as f64 / WEI_PER_GWEI)
+    }
+
+    pub fn blob_base_fee_gwei(&self) -> Option<f64> {
+        self.blob_base_fee_wei.map(|fee| fee as f64 / WEI_PER_GWEI)
+    }
+}
+
+/// Threshold crossings of a gas reading; None where the level didn't change or is
+/// back to normal
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GasCrossings {
+    pub base_fee: Option<GasLevel>,
+    pub blob_fee: Option<GasLevel>,
+}
+
+/// Where the base fee is relative to configured thresholds
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum GasLevel {
+    #[default]
+    Normal,
+    Above,
+    Below,
+}
+
+impl GasLevel {
+    /// Classify a base fee against the thresholds
+    pub fn of(base_fee_gwei: f64, config: &GasConfig) -> Self {
+        Self::between(base_fee_gwei, config.above_gwei, config.below_gwei)
+    }
+
+    /// Classify a blob base fee against the blob thresholds
+    pub fn of_blob(blob_fee_gwei: f64, config: &GasConfig) -> Self {
+        Self::between(blob_fee_gwei, config.blob_above_gwei, config.blob_below_gwei)
+    }
+
+    fn between(fee_gwei: f64, above: Option<f64>, below: Option<f64>) -> Self {
+        if above.is_some_and(|above| fee_gwei > above) {
+            Self::Above
+        } else if below.is_some_and(|below| fee_gwei < below) {
+            Self::Below
+        } else {
+            Self::Normal
+        }
+    }
+}
+
+/// Latest gas prices per network, shared with the bot and the HTTP API
+#[derive(Debug, Default)]
+pub struct GasTracker {
+    prices: RwLock<HashMap<String, GasPrice>>,
+    levels: RwLock<HashMap<String, GasLevel>>,
+    blob_levels: RwLock<HashMap<String, GasLevel>>,
+    /// Blob base fee readings of the last 24 hours per network, oldest first
+    blob_fees: RwLock<HashMap<String, VecDeque<(u64, u128)>>>,
+}
+
+impl GasTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a reading. Returns the new levels when the base fee or the blob base fee
+    /// crosses above or below a threshold, so an alert is sent once per crossing rather
+    /// than every check.
+    pub async fn record(&self, mut price: GasPrice, config: &GasConfig) -> GasCrossings {
+        let network = price.network_name.clone();
+        let level = GasLevel::of(price.base_fee_gwei(), config);
+        let previous = self.levels.write().await.insert(network.clone(), level).unwrap_or_default();
+        let mut crossings = GasCrossings {
+            base_fee: (level != previous && level != GasLevel::Normal).then_some(level),
+            blob_fee: None,
+        };
+
+        if let Some(blob_fee) = price.blob_base_fee_wei {
+            let mut blob_fees = self.blob_fees.write().await;
+            let readings = blob_fees.entry(network.clone()).or_default();
+            readings.push_back((price.updated_at, blob_fee));
+            while readings.front().is_some_and(|&(at, _)| at + BLOB_FEE_WINDOW_SECS < price.updated_at) {
+                readings.pop_front();
+            }
+            price.blob_base_fee_24h = FeeRange::of(readings.iter().map(|&(_, fee)| fee));
+
+            let level = GasLevel::of_blob(blob_fee as f64 / WEI_PER_GWEI, config);
+            let previous = self.blob_levels.write().await.insert(network.clone(), level).unwrap_or_default();
+            crossings.blob_fee = (level != previous && level != GasLevel::Normal).then_some(level);
+        }
+
+        self.prices.write().await.insert(network, price);
+        crossings
+    }
+
+    /// Latest gas prices for a network
+    pub async fn get(&self, network_name: &str) -> Option<GasPrice> {
+        self.prices.read().await.get(network_name).cloned()
+    }
+
+    /// Latest gas prices for all tracked networks, sorted by network
+    pub async fn all(&self) -> Vec<GasPrice> {
+        let mut prices: Vec<GasPrice> = self.prices.read().await.values().cloned().collect();
+        prices.sort_by(|a, b| a.network_name.cmp(&b.network_name));
+        prices
+    }
+}
@@ -0,0 +1,96 @@
+use super::{to_units, BalanceInfo};
+use crate::config::{AddressConfig, GroupConfig};
+use alloy::primitives::{utils::format_units, U256};
+use std::collections::HashSet;
+use tokio::sync::RwLock;
+
+/// Total native balance of a group's addresses on one network
+#[derive(Debug, Clone, PartialEq)]
+pub struct GroupTotal {
+    pub addresses: usize,
+    pub total: U256,
+    pub formatted: String,
+    pub symbol: String,
+    pub decimals: u8,
+}
+
+impl GroupTotal {
+    /// Sum the native balances of one network's addresses; None without balances
+    pub fn of<'a>(balances: impl IntoIterator<Item = &'a BalanceInfo>) -> Option<Self> {
+        let mut balances = balances.into_iter().peekable();
+        let first = balances.peek()?;
+        let (symbol, decimals) = (first.native_symbol.clone(), first.native_decimals);
+        let (addresses, total) = balances.fold((0, U256::ZERO), |(count, total), balance| {
+            (count + 1, total.saturating_add(balance.eth_balance))
+        });
+        let formatted = format_units(total, decimals).unwrap_or_else(|_| total.to_string());
+        Some(Self { addresses, total, formatted, symbol, decimals })
+    }
+}
+
+/// Change of a group's total relative to its minimum
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GroupEvent {
+    /// Total dropped below `min_total_balance`
+    Below { min: f64 },
+    /// Total is back at or above the minimum
+    Recovered { min: f64 },
+}
+
+/// Alerts when the total native balance of a tagged group on one network drops below
+/// its `min_total_balance`, even if no single address crosses its own threshold
+#[derive(Debug, Default)]
+pub struct GroupMonitor {
+    groups: Vec<GroupConfig>,
+    /// Tags of the groups reported below their minimum
+    below: RwLock<HashSet<String>>,
+}
+
+impl GroupMonitor {
+    /// Monitor for the groups with a minimum applying to a network
+    pub fn new(groups: &[GroupConfig], network: &str) -> Self {
+        let groups = groups
+            .iter()
+            .filter(|group| group.min_total_balance.is_some())
+            .filter(|group| group.networks.is_empty() || group.networks.iter().any(|name| name == network))
+            .cloned()
+            .collect();
+        Self { groups, below: RwLock::new(HashSet::new()) }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.groups.is_empty()
+    }
+
+    /// Check group totals after a cycle. A group is skipped while any of its addresses
+    /// couldn't be read, since a partial total would understate it.
+    pub async fn check(&self, addresses: &[AddressConfig], balances: &[BalanceInfo]) -> Vec<(GroupConfig, GroupTotal, GroupEvent)> {
+        let mut events = Vec::new();
+        let mut below = self.below.write().await;
+        for group in &self.groups {
+            let Some(min) = group.min_total_balance else { continue };
+            let members: Vec<&BalanceInfo> = addresses
+                .iter()
+                .filter(|addr| addr.tags.contains(&group.tag))
+                .map(|addr| balances.iter().find(|balance| balance.alias == addr.alias))
+                .collect::<Option<_>>()
+                .unwrap_or_default();
+            let Some(total) = GroupTotal::of(members) else { continue };
+
+            let is_below = total.total < to_units(min, total.decimals);
+            let was_below = below.contains(&group.tag);
+            if is_below == was_below {
+                continue;
+            }
+            let event = if is_below {
+                below.insert(group.tag.clone());
+                GroupEvent::Below { min }
+            } else {
+                below.remove(&group.tag);
+                GroupEvent::Recovered { min }
+            };
+            events.push((group.clone(), total, event));
+        }
+        events
+    }
+}
@@ -0,0 +1,114 @@
+use crate::events::MonitorEvent;
+use serde::Serialize;
+use std::collections::HashMap;
+use tokio::sync::RwLock;
+
+/// Check status of one monitored network
+#[derive(Debug, Clone, Serialize)]
+pub struct NetworkHealth {
+    pub network_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub chain_id: Option<u64>,
+    /// Expected time between checks in seconds
+    pub interval_secs: u64,
+    /// Number of configured RPC nodes (or API endpoints)
+    pub rpc_nodes: usize,
+    /// When monitoring started (Unix timestamp in seconds)
+    pub started_at: u64,
+    /// Last check that read at least one balance
+    pub last_success: Option<u64>,
+    pub last_error: Option<String>,
+    pub last_error_at: Option<u64>,
+    /// Checks in a row that read no balance at all
+    pub consecutive_failures: u32,
+    /// Checks that hit at least one error since start
+    pub error_count: u64,
+}
+
+impl NetworkHealth {
+    /// True if the latest check succeeded
+    pub fn is_ok(&self) -> bool {
+        self.last_success.is_some() && self.consecutive_failures == 0
+    }
+
+    /// Seconds since the last successful check, or since start if there was none
+    pub fn last_success_age(&self, now: u64) -> u64 {
+        now.saturating_sub(self.last_success.unwrap_or(self.started_at))
+    }
+
+    /// True if no check succeeded within `max_missed` intervals
+    pub fn is_stale(&self, now: u64, max_missed: u32) -> bool {
+        self.last_success_age(now) > self.interval_secs.max(1) * u64::from(max_missed)
+    }
+}
+
+/// Last successful check per network, shared with the HTTP API health endpoints
+#[derive(Debug, Default)]
+pub struct HealthTracker {
+    networks: RwLock<HashMap<String, NetworkHealth>>,
+}
+
+impl HealthTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start tracking a network; staleness is measured from `now` until its first check
+    pub async fn register(&self, network_name: &str, chain_id: Option<u64>, interval_secs: u64, rpc_nodes: usize, now: u64) {
+        let health = NetworkHealth {
+            network_name: network_name.to_string(),
+            chain_id,
+            interval_secs,
+            rpc_nodes,
+            started_at: now,
+            last_success: None,
+            last_error: None,
+            last_error_at: None,
+            consecutive_failures: 0,
+            error_count: 0,
+        };
+        self.networks.write().await.insert(network_name.to_string(), health);
+    }
+
+    /// Record the outcome of a check cycle. `error` is kept even for successful
+    /// cycles where only some addresses failed.
+    pub async fn record_check(&self, network_name: &str, succeeded: bool, error: Option<String>, now: u64) {
+        let mut networks = self.networks.write().await;
+        let Some(health) = networks.get_mut(network_name) else {
+            return;
+        };
+
+        if succeeded {
+            health.last_success = Some(now);
+            health.consecutive_failures = 0;
+        } else {
+            health.consecutive_failures += 1;
+        }
+        if let Some(error) = error {
+            health.error_count += 1;
+            health.last_error = Some(error);
+            health.last_error_at = Some(now);
+        }
+    }
+
+    /// Record a `check_completed` event; other events are ignored. A cycle counts as
+    /// successful if any address could be read.
+    pub async fn record_event(&self, event: &MonitorEvent) {
+        if let MonitorEvent::CheckCompleted { network, addresses, error, .. } = event {
+            let succeeded = *addresses > 0 || error.is_none();
+            self.record_check(network, succeeded, error.clone(), chrono::Utc::now().timestamp() as u64).await;
+        }
+    }
+
+    /// Health of a network
+    pub async fn get(&self, network_name: &str) -> Option<NetworkHealth> {
+        self.networks.read().await.get(network_name).cloned()
+    }
+
+    /// Health of all networks, sorted by name
+    pub async fn all(&self) -> Vec<NetworkHealth> {
+        let mut networks: Vec<NetworkHealth> = self.networks.read().await.values().cloned().collect();
+        networks.sort_by(|a, b| a.network_name.cmp(&b.network_name));
+        networks
+    }
+}
@@ -0,0 +1,56 @@
+use crate::config::AddressConfig;
+use std::collections::HashMap;
+use tokio::sync::RwLock;
+
+/// Change in an address's activity
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InactivityEvent {
+    /// Balances haven't changed for longer than the address allows
+    Inactive { since: u64, limit_secs: u64 },
+    /// Balances changed again after an inactivity alert
+    Active { inactive_secs: u64 },
+}
+
+/// Alerts when balances of an address with `max_inactive_hours` stop changing,
+/// e.g. a distributor wallet whose payout job died
+#[derive(Debug, Default)]
+pub struct InactivityMonitor {
+    /// Maximum time without balance changes by alias, in seconds
+    limits: HashMap<String, u64>,
+    /// Aliases reported inactive, with the last change they were reported for
+    alerted: RwLock<HashMap<String, u64>>,
+}
+
+impl InactivityMonitor {
+    pub fn new(addresses: &[AddressConfig]) -> Self {
+        let limits = addresses
+            .iter()
+            .filter_map(|addr| Some((addr.alias.clone(), addr.max_inactive_hours? * 3600)))
+            .collect();
+        Self { limits, alerted: RwLock::new(HashMap::new()) }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.limits.is_empty()
+    }
+
+    /// Check an address whose balances last changed at `last_changed`. Inactivity is
+    /// reported once; the next change after that reports the address active again.
+    pub async fn check(&self, alias: &str, last_changed: u64, now: u64) -> Option<InactivityEvent> {
+        let limit_secs = *self.limits.get(alias)?;
+        let mut alerted = self.alerted.write().await;
+
+        match alerted.get(alias).copied() {
+            Some(reported) if reported == last_changed => None,
+            Some(reported) => {
+                alerted.remove(alias);
+                Some(InactivityEvent::Active { inactive_secs: last_changed.saturating_sub(reported) })
+            }
+            None if now.saturating_sub(last_changed) > limit_secs => {
+                alerted.insert(alias.to_string(), last_changed);
+                Some(InactivityEvent::Inactive { since: last_changed, limit_secs })
+            }
+            None => None,
+        }
+    }
+}
@@ -0,0 +1,225 @@
+use alloy::primitives::{Address, TxHash, U256};
+use eyre::Result;
+use reqwest::{Client, Url};
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use tracing::warn;
+
+use super::{TransferDirection, TxAttribution};
+use crate::config::{IndexerConfig, TokenConfig};
+use crate::http::http_client;
+
+/// Transfer record of the account API: a transaction, an internal transaction or a
+/// token transfer. Fields are strings, empty when missing.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct IndexerTransfer {
+    pub hash: String,
+    pub block_number: String,
+    pub from: String,
+    /// Empty for contract creations
+    pub to: String,
+    pub value: String,
+    /// Token contract of token transfers
+    pub contract_address: String,
+    /// "1" for failed transactions
+    pub is_error: String,
+}
+
+impl IndexerTransfer {
+    /// Block of the record, None if it can't be parsed
+    fn block(&self) -> Option<u64> {
+        self.block_number.parse().ok()
+    }
+
+    /// Whether the hash, block and sender parse, which every record of the API has
+    fn is_valid(&self) -> bool {
+        self.hash.parse::<TxHash>().is_ok() && self.block().is_some() && self.from.parse::<Address>().is_ok()
+    }
+
+    /// Attribution of the transfer relative to `address`; None if it failed, doesn't
+    /// involve the address or can't be parsed
+    fn attribution(&self, address: Address, asset: &str) -> Option<TxAttribution> {
+        if self.is_error == "1" {
+            return None;
+        }
+        let from: Address = self.from.parse().ok()?;
+        let to: Option<Address> = self.to.parse().ok();
+        let direction = if from == address {
+            TransferDirection::Outgoing
+        } else if to == Some(address) {
+            TransferDirection::Incoming
+        } else {
+            return None;
+        };
+        Some(TxAttribution {
+            tx_hash: self.hash.parse::<TxHash>().ok()?,
+            block_number: self.block()?,
+            asset: asset.to_string(),
+            direction,
+            counterparty: match direction {
+                TransferDirection::Outgoing => to,
+                TransferDirection::Incoming => Some(from),
+            },
+            value: U256::from_str_radix(&self.value, 10).unwrap_or_default(),
+            url: None,
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct IndexerResponse {
+    status: String,
+    message: String,
+    result: Value,
+}
+
+/// Etherscan answers at most 10,000 records per query, however it is paged
+const MAX_RECORDS: usize = 10_000;
+
+/// Client for the account module of an Etherscan-compatible API (Etherscan, Blockscout),
+/// used instead of the nodes for transaction history
+#[derive(Clone)]
+pub struct IndexerClient {
+    client: Client,
+    url: Url,
+    api_key: Option<String>,
+    chain_id: u64,
+    page_size: usize,
+}
+
+impl IndexerClient {
+    pub fn new(config: &IndexerConfig, chain_id: u64) -> Self {
+        Self { client: http_client(), url: config.url.clone(), api_key: config.api_key.clone(), chain_id, page_size: MAX_RECORDS }
+    }
+
+    /// Request at most `page_size` records per query, for APIs with a lower cap
+    pub fn with_page_size(mut self, page_size: usize) -> Self {
+        self.page_size = page_size.clamp(1, MAX_RECORDS);
+        self
+    }
+
+    /// Records of an account `action` (txlist, txlistinternal or tokentx) for the
+    /// address in blocks `from_block..=to_block`, oldest first. A full page is
+    /// continued from its last block, since the API doesn't page past its cap.
+    /// Records that can't be parsed are skipped with a warning.
+    pub async fn transfers(&self, action: &str, address: Address, from_block: u64, to_block: u64) -> Result<Vec<IndexerTransfer>> {
+        let mut transfers = Vec::new();
+        let mut skipped = 0;
+        let mut start = from_block;
+        loop {
+            let records = self.query(action, address, start, to_block).await?;
+            let full = records.len() >= self.page_size;
+            let mut page: Vec<IndexerTransfer> = Vec::with_capacity(records.len());
+            for record in records {
+                match serde_json::from_value::<IndexerTransfer>(record) {
+                    Ok(transfer) if transfer.is_valid() => page.push(transfer),
+                    _ => skipped += 1,
+                }
+            }
+            let last_block = page.last().and_then(IndexerTransfer::block);
+            match last_block {
+                // The last block may be cut off, so it is read again by the next query
+                Some(last) if full && last > start => {
+                    page.retain(|transfer| transfer.block() != Some(last));
+                    transfers.extend(page);
+                    start = last;
+                }
+                _ => {
+                    if full {
+                        warn!("Indexer {} history of {:?} fills a whole page from block {}, later records are left out", action, address, start);
+                    }
+                    transfers.extend(page);
+                    break;
+                }
+            }
+        }
+        if skipped > 0 {
+            warn!("Failed to parse {} indexer {} record(s) of {:?}, skipped", skipped, action, address);
+        }
+        Ok(transfers)
+    }
+
+    /// One query of an account `action`, with up to `page_size` raw records
+    async fn query(&self, action: &str, address: Address, from_block: u64, to_block: u64) -> Result<Vec<Value>> {
+        let mut url = self.url.clone();
+        url.query_pairs_mut()
+            .append_pair("chainid", &self.chain_id.to_string())
+            .append_pair("module", "account")
+            .append_pair("action", action)
+            .append_pair("address", &format!("{:?}", address))
+            .append_pair("startblock", &from_block.to_string())
+            .append_pair("endblock", &to_block.to_string())
+            .append_pair("page", "1")
+            .append_pair("offset", &self.page_size.to_string())
+            .append_pair("sort", "asc");
+        if let Some(key) = &self.api_key {
+            url.query_pairs_mut().append_pair("apikey", key);
+        }
+
+        let response = self.client.get(url).send().await?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            eyre::bail!("indexer API error {}: {}", status, body);
+        }
+        let response: IndexerResponse = response.json().await?;
+        match response.result {
+            Value::Array(records) => Ok(records),
+            // "No transactions found" comes with status 0 and an empty result
+            _ if response.message.starts_with("No ") => Ok(Vec::new()),
+            result => eyre::bail!("indexer API {} ({}): {}", response.message, response.status, result.as_str().unwrap_or_default()),
+        }
+    }
+
+    /// Find transactions that moved the given assets for `address` in blocks
+    /// `from_block..=to_block`. Unlike the node lookup, native transfers include
+    /// internal transactions, and the range isn't capped.
+    pub async fn lookup_transactions(
+        &self,
+        address: Address,
+        native: bool,
+        tokens: &[&TokenConfig],
+        from_block: u64,
+        to_block: u64,
+        explorer: Option<&Url>,
+    ) -> Result<Vec<TxAttribution>> {
+        let mut found = Vec::new();
+        if from_block > to_block {
+            return Ok(found);
+        }
+
+        if !tokens.is_empty() {
+            let aliases: HashMap<Address, &str> = tokens.iter().map(|token| (token.address, token.alias.as_str())).collect();
+            for transfer in self.transfers("tokentx", address, from_block, to_block).await? {
+                let Some(asset) = transfer.contract_address.parse().ok().and_then(|token: Address| aliases.get(&token)) else {
+                    continue;
+                };
+                found.extend(transfer.attribution(address, asset));
+            }
+        }
+
+        if native {
+            let transactions = self.transfers("txlist", address, from_block, to_block).await?;
+            let internal = self.transfers("txlistinternal", address, from_block, to_block).await?;
+            // Outgoing transactions pay gas even without value; other transfers only matter with value
+            found.extend(
+                transactions.iter()
+                    .filter_map(|transfer| transfer.attribution(address, "ETH"))
+                    .filter(|tx| tx.direction == TransferDirection::Outgoing || !tx.value.is_zero()),
+            );
+            found.extend(
+                internal.iter()
+                    .filter_map(|transfer| transfer.attribution(address, "ETH"))
+                    .filter(|tx| !tx.value.is_zero()),
+            );
+        }
+
+        found.sort_by_key(|tx| tx.block_number);
+        for tx in &mut found {
+            tx.url = explorer.and_then(|explorer| explorer.join(&format!("tx/{:?}", tx.tx_hash)).ok());
+        }
+        Ok(found)
+    }
+}
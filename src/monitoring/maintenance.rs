@@ -0,0 +1,187 @@
+use crate::config::MaintenanceWindowConfig;
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone};
+use croner::Cron;
+use eyre::{eyre, Result};
+use tokio::sync::RwLock;
+
+/// When a configured maintenance window is open
+#[derive(Debug, Clone)]
+enum Schedule {
+    /// Opens at every cron match, for `duration_secs`
+    Recurring { cron: Box<Cron>, duration_secs: u64 },
+    /// Open from `start` until `end` (Unix timestamps)
+    Once { start: u64, end: u64 },
+}
+
+/// Configured maintenance window
+#[derive(Debug, Clone)]
+pub struct MaintenanceWindow {
+    name: String,
+    schedule: Schedule,
+    networks: Vec<String>,
+    addresses: Vec<String>,
+}
+
+impl MaintenanceWindow {
+    pub fn new(config: &MaintenanceWindowConfig) -> Result<Self> {
+        let schedule = match (&config.cron, config.duration_mins, &config.start, &config.end) {
+            (Some(cron), Some(duration_mins), None, None) => {
+                let cron = Cron::new(cron).parse().map_err(|e| eyre!("invalid cron '{}': {}", cron, e))?;
+                if duration_mins == 0 {
+                    return Err(eyre!("duration_mins must be greater than 0"));
+                }
+                let duration_secs = duration_mins.checked_mul(60).ok_or_else(|| eyre!("duration_mins is too large"))?;
+                Schedule::Recurring { cron: Box::new(cron), duration_secs }
+            }
+            (Some(_), None, None, None) => return Err(eyre!("cron windows need duration_mins")),
+            (None, None, Some(start), Some(end)) => {
+                let (start, end) = (parse_time(start)?, parse_time(end)?);
+                if end <= start {
+                    return Err(eyre!("end must be after start"));
+                }
+                Schedule::Once { start, end }
+            }
+            (None, None, _, _) => return Err(eyre!("set either cron and duration_mins, or start and end")),
+            _ => return Err(eyre!("cron/duration_mins and start/end can't be combined")),
+        };
+
+        let name = config.name.clone().unwrap_or_else(|| match &schedule {
+            Schedule::Recurring { cron, .. } => cron.pattern.to_string(),
+            Schedule::Once { .. } => "scheduled maintenance".to_string(),
+        });
+        Ok(Self { name, schedule, networks: config.networks.clone(), addresses: config.addresses.clone() })
+    }
+
+    fn applies_to(&self, network: &str, alias: &str) -> bool {
+        (self.networks.is_empty() || self.networks.iter().any(|n| n == network))
+            && (self.addresses.is_empty() || self.addresses.iter().any(|a| a == alias))
+    }
+
+    /// End of the window if it's open at `now`
+    fn open_until(&self, now: u64) -> Option<u64> {
+        match &self.schedule {
+            Schedule::Once { start, end } => (*start <= now && now < *end).then_some(*end),
+            Schedule::Recurring { cron, duration_secs } => {
+                // The latest start that still covers `now` is the first match after now - duration
+                let from = local_time(now.saturating_sub(*duration_secs) + 1)?;
+                let start = cron.find_next_occurrence(&from, true).ok()?.timestamp().max(0) as u64;
+                (start <= now).then_some(start.saturating_add(*duration_secs))
+            }
+        }
+    }
+
+    fn scope(&self) -> String {
+        let mut scope = Vec::new();
+        if !self.networks.is_empty() {
+            scope.push(self.networks.join(", "));
+        }
+        if !self.addresses.is_empty() {
+            scope.push(self.addresses.join(", "));
+        }
+        if scope.is_empty() {
+            "all networks".to_string()
+        } else {
+            scope.join(" / ")
+        }
+    }
+}
+
+fn local_time(timestamp: u64) -> Option<DateTime<Local>> {
+    DateTime::from_timestamp(timestamp as i64, 0).map(|t| t.with_timezone(&Local))
+}
+
+/// Parse an RFC 3339 timestamp or "YYYY-MM-DD HH:MM" in local time
+fn parse_time(value: &str) -> Result<u64> {
+    let time = match DateTime::parse_from_rfc3339(value) {
+        Ok(time) => time.timestamp(),
+        Err(_) => NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M")
+            .ok()
+            .and_then(|time| Local.from_local_datetime(&time).earliest())
+            .ok_or_else(|| eyre!("invalid time '{}', expected RFC 3339 or YYYY-MM-DD HH:MM", value))?
+            .timestamp(),
+    };
+    Ok(time.max(0) as u64)
+}
+
+/// Maintenance window started with /maintenance
+#[derive(Debug, Clone)]
+struct AdhocWindow {
+    network: Option<String>,
+    until: u64,
+    started_by: String,
+}
+
+/// Open maintenance window, for /status and /maintenance
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ActiveMaintenance {
+    pub name: String,
+    /// Networks and addresses covered, e.g. "all networks" or "Ethereum / Treasury"
+    pub scope: String,
+    /// When the window closes (Unix timestamp)
+    pub until: u64,
+}
+
+/// Configured and ad-hoc maintenance windows. While one is open for an address,
+/// its balance change, rule and anomaly alerts are suppressed; balances are still recorded
+/// and low balance alerts are still sent.
+#[derive(Debug, Default)]
+pub struct MaintenanceSchedule {
+    windows: Vec<MaintenanceWindow>,
+    adhoc: RwLock<Vec<AdhocWindow>>,
+}
+
+impl MaintenanceSchedule {
+    pub fn new(configs: &[MaintenanceWindowConfig]) -> Result<Self> {
+        let windows = configs.iter().map(MaintenanceWindow::new).collect::<Result<_>>()?;
+        Ok(Self { windows, adhoc: RwLock::new(Vec::new()) })
+    }
+
+    /// Name of the window suppressing alerts for the address at `now`, if any
+    pub async fn suppressing(&self, network: &str, alias: &str, now: u64) -> Option<String> {
+        let adhoc = self.adhoc.read().await;
+        if let Some(window) = adhoc.iter().find(|w| w.until > now && w.network.as_deref().is_none_or(|n| n == network)) {
+            return Some(format!("ad-hoc maintenance by {}", window.started_by));
+        }
+        self.windows
+            .iter()
+            .find(|window| window.applies_to(network, alias) && window.open_until(now).is_some())
+            .map(|window| window.name.clone())
+    }
+
+    /// Start an ad-hoc window for one network, or all if `network` is None
+    pub async fn start(&self, network: Option<String>, until: u64, started_by: &str) {
+        let mut adhoc = self.adhoc.write().await;
+        adhoc.retain(|window| window.network != network);
+        adhoc.push(AdhocWindow { network, until, started_by: started_by.to_string() });
+    }
+
+    /// End all ad-hoc windows; returns how many were still open
+    pub async fn end(&self, now: u64) -> usize {
+        let mut adhoc = self.adhoc.write().await;
+        let open = adhoc.iter().filter(|window| window.until > now).count();
+        adhoc.clear();
+        open
+    }
+
+    /// Windows open at `now`
+    pub async fn active(&self, now: u64) -> Vec<ActiveMaintenance> {
+        let mut active: Vec<ActiveMaintenance> = self
+            .adhoc
+            .read()
+            .await
+            .iter()
+            .filter(|window| window.until > now)
+            .map(|window| ActiveMaintenance {
+                name: format!("ad-hoc by {}", window.started_by),
+                scope: window.network.clone().unwrap_or_else(|| "all networks".to_string()),
+                until: window.until,
+            })
+            .collect();
+        for window in &self.windows {
+            if let Some(until) = window.open_until(now) {
+                active.push(ActiveMaintenance { name: window.name.clone(), scope: window.scope(), until });
+            }
+        }
+        active
+    }
+}
@@ -1,3 +1,54 @@
+mod anomaly;
 mod balance;
+mod beacon;
+mod contract_watch;
+mod corridor;
+mod cosmos;
+mod custom;
+mod discovery;
+mod drain;
+mod entity;
+mod gas;
+mod group;
+mod health;
+mod indexer;
+mod inactivity;
+mod maintenance;
+mod price_feed;
+mod registry;
+mod rollup;
+mod runway;
+mod safe;
+mod tron;
+mod tx_lookup;
+mod view_call;
+mod watchlist;
 
-pub use balance::{BalanceInfo, BalanceMonitor, BalanceMonitorConfig, TokenBalance};
+pub use anomaly::{Anomaly, AnomalyDetector, ChangeStats};
+pub use balance::{
+    from_units, runway_threshold_eth, to_units, BalanceInfo, BalanceMonitor, BalanceMonitorConfig, NftBalance,
+    TokenBalance, DEFAULT_DECIMALS, MAX_BATCH_SIZE,
+};
+pub use beacon::{BeaconClient, ValidatorDetails, ValidatorEvent, ValidatorInfo, ValidatorMonitor};
+pub use contract_watch::{read_contract_state, ContractChange, ContractState, ContractWatcher};
+pub use corridor::{CorridorEvent, CorridorMonitor};
+pub use cosmos::{CosmosClient, CosmosCoin, CosmosEvent, CosmosMonitor, CosmosStates};
+pub use custom::{CheckContext, CustomAlert, CustomCheck, CustomChecks};
+pub use discovery::discover_tokens;
+pub use drain::{drained_assets, DrainedAsset};
+pub use entity::{Entities, EntityEvent, EntityTotal};
+pub use gas::{format_gwei, FeeRange, GasCrossings, GasLevel, GasPrice, GasTracker};
+pub use group::{GroupEvent, GroupMonitor, GroupTotal};
+pub use health::{HealthTracker, NetworkHealth};
+pub use indexer::{IndexerClient, IndexerTransfer};
+pub use inactivity::{InactivityEvent, InactivityMonitor};
+pub use maintenance::{ActiveMaintenance, MaintenanceSchedule, MaintenanceWindow};
+pub use price_feed::{read_feed_round, FeedIssue, FeedMonitor, FeedRound};
+pub use registry::AddressRegistry;
+pub use rollup::{RollupEvent, RollupMonitor};
+pub use runway::{outflow_since, AssetRunway, Runway, RunwayForecasts};
+pub use safe::{SafeChange, SafeState, SafeWatcher};
+pub use tron::{format_tron_address, parse_tron_address, TronMonitor, TRON_MAINNET_CHAIN_ID};
+pub use tx_lookup::{lookup_transactions, unexpected_destinations, TransferDirection, TxAttribution};
+pub use view_call::{ViewCall, ViewCallLevel, ViewCallMonitor, ViewCallUpdate, ViewValue};
+pub use watchlist::{parse_watchlist, spawn_watchlist_reload, Watchlist};
@@ -0,0 +1,131 @@
+use alloy::{primitives::Address, providers::Provider};
+use eyre::Result;
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio::sync::RwLock;
+use tracing::warn;
+
+use crate::config::FeedConfig;
+use crate::contracts::IAggregatorV3;
+
+/// Latest round of a Chainlink aggregator
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FeedRound {
+    /// Answer scaled by the feed's decimals
+    pub answer: f64,
+    /// When the answer was last updated (Unix timestamp in seconds)
+    pub updated_at: u64,
+}
+
+impl FeedRound {
+    /// Time since the answer was last updated
+    pub fn age(&self, now: u64) -> Duration {
+        Duration::from_secs(now.saturating_sub(self.updated_at))
+    }
+}
+
+/// Read the latest round of a Chainlink aggregator
+pub async fn read_feed_round<P: Provider>(provider: &P, feed: Address) -> Result<FeedRound> {
+    let aggregator = IAggregatorV3::new(feed, provider);
+
+    let decimals = aggregator.decimals().call().await?;
+    let round = aggregator.latestRoundData().call().await?;
+
+    let answer: f64 = round.answer.to_string().parse()?;
+    Ok(FeedRound {
+        answer: answer / 10f64.powi(decimals as i32),
+        updated_at: u64::try_from(round.updatedAt).unwrap_or(u64::MAX),
+    })
+}
+
+/// Problem found with a feed on a check
+#[derive(Debug, Clone, PartialEq)]
+pub enum FeedIssue {
+    /// The answer is older than the feed's heartbeat
+    Stale { age: Duration, heartbeat: Duration },
+    /// The answer moved more than the deviation threshold since the previous check
+    Deviation { previous: f64, percent: f64 },
+}
+
+impl FeedIssue {
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Stale { age, heartbeat } => {
+                format!("No update for {} min (heartbeat: {} min)", age.as_secs() / 60, heartbeat.as_secs() / 60)
+            }
+            Self::Deviation { previous, percent } => format!("Moved {:+.2}% from {}", percent, previous),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct FeedState {
+    answer: f64,
+    stale: bool,
+}
+
+/// Monitors Chainlink feeds for stale answers and large moves between checks
+#[derive(Debug, Default)]
+pub struct FeedMonitor {
+    feeds: Vec<FeedConfig>,
+    states: RwLock<HashMap<String, FeedState>>,
+}
+
+impl FeedMonitor {
+    pub fn new(feeds: Vec<FeedConfig>) -> Self {
+        Self {
+            feeds,
+            states: RwLock::new(HashMap::new()),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.feeds.is_empty()
+    }
+
+    /// Read all feeds and return the ones with new issues
+    pub async fn check<P: Provider>(&self, provider: &P, now: u64) -> Vec<(&FeedConfig, FeedRound, Vec<FeedIssue>)> {
+        let mut results = Vec::new();
+
+        for feed in &self.feeds {
+            match read_feed_round(provider, feed.address).await {
+                Ok(round) => {
+                    let issues = self.record(feed, round, now).await;
+                    if !issues.is_empty() {
+                        results.push((feed, round, issues));
+                    }
+                }
+                Err(e) => warn!("Error reading feed {} ({}): {}", feed.alias, feed.address, e),
+            }
+        }
+
+        results
+    }
+
+    /// Store a round and return its issues. Staleness is reported once when the
+    /// feed goes stale; deviation is measured against the previous check.
+    pub async fn record(&self, feed: &FeedConfig, round: FeedRound, now: u64) -> Vec<FeedIssue> {
+        let mut issues = Vec::new();
+
+        let stale = feed.heartbeat.is_some_and(|heartbeat| round.age(now) > heartbeat);
+        let previous = self.states.write().await
+            .insert(feed.alias.clone(), FeedState { answer: round.answer, stale });
+
+        if let (true, Some(heartbeat)) = (stale, feed.heartbeat) {
+            if !previous.is_some_and(|p| p.stale) {
+                issues.push(FeedIssue::Stale { age: round.age(now), heartbeat });
+            }
+        }
+
+        if let (Some(threshold), Some(previous)) = (feed.deviation_percent, previous) {
+            if previous.answer != 0.0 {
+                let percent = (round.answer - previous.answer) / previous.answer.abs() * 100.0;
+                if percent.abs() > threshold {
+                    issues.push(FeedIssue::Deviation { previous: previous.answer, percent });
+                }
+            }
+        }
+
+        issues
+    }
+}
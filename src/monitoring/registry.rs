@@ -0,0 +1,111 @@
+use crate::config::{AddressConfig, NetworkConfig};
+use crate::storage::{DynamicAddress, DynamicAddressStorage, StateBackend};
+use alloy::primitives::Address;
+use eyre::Result;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// Runtime set of monitored addresses: addresses from config plus ones added at runtime
+pub struct AddressRegistry {
+    /// Addresses from config by network name
+    configured: HashMap<String, Vec<AddressConfig>>,
+    dynamic: RwLock<DynamicAddressStorage>,
+    backend: Arc<dyn StateBackend>,
+}
+
+impl AddressRegistry {
+    /// Create registry from configured networks and load runtime addresses from the backend
+    pub async fn new(networks: &[NetworkConfig], backend: Arc<dyn StateBackend>) -> Result<Self> {
+        let configured = networks.iter()
+            .map(|network| (network.name.clone(), network.addresses.clone()))
+            .collect();
+        let dynamic = backend.load_dynamic_addresses().await?;
+
+        Ok(Self {
+            configured,
+            dynamic: RwLock::new(dynamic),
+            backend,
+        })
+    }
+
+    /// All addresses to monitor on a network
+    pub async fn addresses(&self, network: &str) -> Vec<AddressConfig> {
+        let mut addresses = self.configured.get(network).cloned().unwrap_or_default();
+        let dynamic = self.dynamic.read().await;
+        addresses.extend(dynamic.addresses.iter()
+            .filter(|entry| entry.network == network)
+            .map(|entry| entry.config.clone()));
+        addresses
+    }
+
+    /// (network, alias) of the configured addresses carrying a tag; runtime addresses have no tags
+    pub fn tagged(&self, tag: &str) -> HashSet<(String, String)> {
+        self.configured.iter()
+            .flat_map(|(network, addresses)| {
+                addresses.iter()
+                    .filter(|addr| addr.tags.iter().any(|t| t == tag))
+                    .map(move |addr| (network.clone(), addr.alias.clone()))
+            })
+            .collect()
+    }
+
+    /// Addresses added at runtime
+    pub async fn dynamic_addresses(&self) -> Vec<DynamicAddress> {
+        self.dynamic.read().await.addresses.clone()
+    }
+
+    /// Add an address to a network and persist it
+    pub async fn add(&self, network: &str, alias: &str, address: Address) -> Result<()> {
+        if !self.configured.contains_key(network) {
+            eyre::bail!("unknown network '{}'", network);
+        }
+        // Aliases end up in HTML alerts, so keep markup and control characters out of them
+        if alias.chars().any(|c| matches!(c, '<' | '>' | '&') || c.is_control()) {
+            eyre::bail!("alias '{}' can't contain <, >, & or control characters", alias.escape_debug());
+        }
+
+        // Check and insert under one lock, so concurrent adds can't both pass the check
+        let mut dynamic = self.dynamic.write().await;
+        let configured = self.configured.get(network).into_iter().flatten();
+        let added = dynamic.addresses.iter().filter(|entry| entry.network == network).map(|entry| &entry.config);
+        if configured.chain(added).any(|a| a.alias == alias || a.address == address) {
+            eyre::bail!("'{}' or {} is already monitored on {}", alias, address, network);
+        }
+        dynamic.addresses.push(DynamicAddress {
+            network: network.to_string(),
+            config: AddressConfig::new(alias, address),
+        });
+        if let Err(e) = self.backend.save_dynamic_addresses(&dynamic).await {
+            dynamic.addresses.pop();
+            return Err(e);
+        }
+
+        Ok(())
+    }
+
+    /// Remove runtime-added addresses with the given alias and persist the change.
+    /// Returns the removed entries. Addresses from config can't be removed.
+    pub async fn remove(&self, alias: &str) -> Result<Vec<DynamicAddress>> {
+        let in_config = self.configured.values().flatten().any(|a| a.alias == alias);
+
+        let mut dynamic = self.dynamic.write().await;
+        let (removed, kept): (Vec<_>, Vec<_>) = dynamic.addresses.drain(..)
+            .partition(|entry| entry.config.alias == alias);
+        dynamic.addresses = kept;
+
+        if removed.is_empty() {
+            if in_config {
+                eyre::bail!("'{}' is defined in the config file and can't be removed at runtime", alias);
+            }
+            eyre::bail!("no address with alias '{}'", alias);
+        }
+
+        if let Err(e) = self.backend.save_dynamic_addresses(&dynamic).await {
+            dynamic.addresses.extend(removed);
+            return Err(e);
+        }
+
+        Ok(removed)
+    }
+}
@@ -0,0 +1,108 @@
+use alloy::{
+    eips::BlockNumberOrTag,
+    primitives::U256,
+    providers::{DynProvider, Provider},
+};
+use std::time::Duration;
+use tokio::sync::RwLock;
+use tracing::warn;
+
+use super::{from_units, to_units};
+use crate::config::RollupConfig;
+
+/// Change of a rollup's sequencer or bridge state
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RollupEvent {
+    /// The latest L2 block is older than `max_block_age`
+    SequencerStalled { block: u64, age: Duration },
+    /// The sequencer produces blocks again
+    SequencerResumed { block: u64 },
+    /// The bridge's ETH balance on L1 dropped below `min_bridge_balance`
+    BridgeLow { balance: f64, min: f64 },
+    /// The bridge balance is back at or above the minimum
+    BridgeRecovered { balance: f64, min: f64 },
+}
+
+#[derive(Debug, Default)]
+struct RollupState {
+    stalled: bool,
+    bridge_low: bool,
+}
+
+/// Alerts when a rollup's sequencer stops producing blocks, which balance checks
+/// alone don't show, and when its canonical bridge on L1 runs low
+#[derive(Debug)]
+pub struct RollupMonitor {
+    config: RollupConfig,
+    /// Provider and chain ID of the L1 network the bridge is read on
+    l1: Option<(DynProvider, u64)>,
+    state: RwLock<RollupState>,
+}
+
+impl RollupMonitor {
+    pub fn new(config: RollupConfig) -> Self {
+        Self { config, l1: None, state: RwLock::new(RollupState::default()) }
+    }
+
+    /// Read the bridge balance through `provider`, whose nodes must report `chain_id`
+    pub fn with_l1_provider(mut self, provider: DynProvider, chain_id: u64) -> Self {
+        self.l1 = Some((provider, chain_id));
+        self
+    }
+
+    pub fn config(&self) -> &RollupConfig {
+        &self.config
+    }
+
+    /// Record the latest L2 block. A stall is reported once, when the block gets older
+    /// than `max_block_age`; the first newer block reports the sequencer resumed.
+    pub async fn record_block(&self, block: u64, timestamp: u64, now: u64) -> Option<RollupEvent> {
+        let age = Duration::from_secs(now.saturating_sub(timestamp));
+        let stalled = age > self.config.max_block_age;
+        let mut state = self.state.write().await;
+        if stalled == state.stalled {
+            return None;
+        }
+        state.stalled = stalled;
+        Some(if stalled { RollupEvent::SequencerStalled { block, age } } else { RollupEvent::SequencerResumed { block } })
+    }
+
+    /// Record the bridge's ETH balance in wei, reporting when it crosses `min_bridge_balance`
+    pub async fn record_bridge_balance(&self, wei: U256) -> Option<RollupEvent> {
+        let min = self.config.min_bridge_balance?;
+        let low = wei < to_units(min, 18);
+        let balance = from_units(wei, 18);
+        let mut state = self.state.write().await;
+        if low == state.bridge_low {
+            return None;
+        }
+        state.bridge_low = low;
+        Some(if low { RollupEvent::BridgeLow { balance, min } } else { RollupEvent::BridgeRecovered { balance, min } })
+    }
+
+    /// Read the latest L2 block, and the bridge balance through the L1 provider when
+    /// a bridge is configured. Read errors, and an L1 node on another chain, are logged
+    /// and skip that part of the check.
+    pub async fn check<P: Provider>(&self, l2: &P, now: u64) -> Vec<RollupEvent> {
+        let mut events = Vec::new();
+        match l2.get_block_by_number(BlockNumberOrTag::Latest).await {
+            Ok(Some(block)) => events.extend(self.record_block(block.header.number, block.header.timestamp, now).await),
+            Ok(None) => warn!("Latest L2 block not found"),
+            Err(e) => warn!("Failed to read the latest L2 block: {}", e),
+        }
+
+        if let (Some(bridge), Some((l1, chain_id))) = (self.config.bridge, &self.l1) {
+            match l1.get_chain_id().await {
+                Ok(actual) if actual != *chain_id => {
+                    warn!("L1 node reports chain ID {} instead of {}, skipping the bridge balance of {}", actual, chain_id, bridge);
+                }
+                Ok(_) => match l1.get_balance(bridge).await {
+                    Ok(balance) => events.extend(self.record_bridge_balance(balance).await),
+                    Err(e) => warn!("Failed to read the bridge balance of {}: {}", bridge, e),
+                },
+                Err(e) => warn!("Failed to read the L1 chain ID for the bridge balance of {}: {}", bridge, e),
+            }
+        }
+        events
+    }
+}
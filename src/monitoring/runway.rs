@@ -0,0 +1,162 @@
+use std::collections::HashMap;
+use std::sync::RwLock;
+use std::time::Duration;
+
+use super::BalanceInfo;
+use crate::config::ThresholdTier;
+use crate::storage::HistoryStore;
+
+/// Least history a spend rate is estimated from
+const MIN_SPAN_SECS: u64 = 3600;
+
+/// Time until an asset's balance reaches its next low balance threshold at the recent
+/// spend rate
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Runway {
+    /// Average spend per day over the lookback
+    pub daily_spend: f64,
+    /// Balance the forecast runs to: the highest threshold still below the balance, or 0
+    pub floor: f64,
+    pub remaining: Duration,
+}
+
+impl Runway {
+    pub fn days(&self) -> f64 {
+        self.remaining.as_secs_f64() / 86400.0
+    }
+
+    /// E.g. "~3.2 days", or hours under a day
+    pub fn describe(&self) -> String {
+        let days = self.days();
+        if days >= 1.0 {
+            format!("~{:.1} days", days)
+        } else {
+            format!("~{:.0} hours", days * 24.0)
+        }
+    }
+}
+
+/// Latest runway of an asset, as shown in reports
+#[derive(Debug, Clone, PartialEq)]
+pub struct AssetRunway {
+    pub network_name: String,
+    pub alias: String,
+    pub asset: String,
+    pub runway: Runway,
+}
+
+impl AssetRunway {
+    /// E.g. "Relayer ETH: ~3.2 days at 0.1500/day"
+    pub fn describe(&self) -> String {
+        format!("{} {}: {} at {:.4}/day", self.alias, self.asset, self.runway.describe(), self.runway.daily_spend)
+    }
+}
+
+/// Balance of the native asset or a token as a number, None if the balance doesn't hold the token
+fn asset_amount(balance: &BalanceInfo, asset: &str, is_native: bool) -> Option<f64> {
+    let formatted = if is_native {
+        &balance.eth_formatted
+    } else {
+        &balance.token_balances.iter().find(|token| token.alias == asset)?.formatted
+    };
+    formatted.parse().ok()
+}
+
+/// Sum of the decreases of an asset's balance from `from` up to `current`, starting at
+/// the last recorded balance before `from`. Increases such as top-ups don't offset them.
+pub fn outflow_since(history: &HistoryStore, current: &BalanceInfo, asset: &str, is_native: bool, from: u64, now: u64) -> f64 {
+    let baseline = history.get_at(&current.network_name, &current.alias, from);
+    let amounts: Vec<f64> = baseline
+        .into_iter()
+        .chain(history.get_range(&current.network_name, &current.alias, from, now))
+        .filter(|point| point.timestamp < now)
+        .map(|point| &point.balance)
+        .chain([current])
+        .filter_map(|balance| asset_amount(balance, asset, is_native))
+        .collect();
+    amounts.windows(2).map(|pair| (pair[0] - pair[1]).max(0.0)).sum()
+}
+
+/// Forecasts how long each asset lasts at its spend rate over the last `lookback`,
+/// keeping the latest forecast of every asset for reports
+#[derive(Debug)]
+pub struct RunwayForecasts {
+    lookback: Duration,
+    /// Latest runway by (network, alias, asset)
+    latest: RwLock<HashMap<(String, String, String), Runway>>,
+}
+
+impl RunwayForecasts {
+    pub fn new(lookback: Duration) -> Self {
+        Self { lookback, latest: RwLock::new(HashMap::new()) }
+    }
+
+    /// Runway of each asset of `current` that was spent from within the lookback, by
+    /// asset name. An asset runs to the highest of its threshold tiers still below its
+    /// balance, or to 0 once below all of them. Assets with less than an hour of
+    /// history or without spending have no runway.
+    pub fn forecast(
+        &self,
+        history: &HistoryStore,
+        current: &BalanceInfo,
+        native_tiers: &[ThresholdTier],
+        token_tiers: &HashMap<String, Vec<ThresholdTier>>,
+        now: u64,
+    ) -> HashMap<String, Runway> {
+        let from = now.saturating_sub(self.lookback.as_secs());
+        let start = match history.get_at(&current.network_name, &current.alias, from) {
+            Some(_) => Some(from),
+            None => history.get_range(&current.network_name, &current.alias, from, now).first().map(|point| point.timestamp),
+        };
+
+        let mut assets = vec![(current.native_symbol.as_str(), true, native_tiers)];
+        for token in &current.token_balances {
+            assets.push((&token.alias, false, token_tiers.get(&token.alias).map(Vec::as_slice).unwrap_or_default()));
+        }
+
+        let span = start.map(|start| now.saturating_sub(start)).unwrap_or_default();
+
+        let mut runways = HashMap::new();
+        let mut latest = self.latest.write().unwrap_or_else(|e| e.into_inner());
+        for (asset, is_native, tiers) in assets {
+            let key = (current.network_name.clone(), current.alias.clone(), asset.to_string());
+            let balance = asset_amount(current, asset, is_native).unwrap_or_default();
+            let spent = outflow_since(history, current, asset, is_native, from, now);
+            if span < MIN_SPAN_SECS || spent <= 0.0 {
+                latest.remove(&key);
+                continue;
+            }
+
+            let daily_spend = spent / span as f64 * 86400.0;
+            let floor = tiers.iter().map(|tier| tier.below).filter(|&below| below < balance).fold(0.0, f64::max);
+            let secs = (balance - floor) / daily_spend * 86400.0;
+            // A runway too long for a Duration, or NaN from non-finite amounts, isn't a forecast
+            let Ok(remaining) = Duration::try_from_secs_f64(if secs < 0.0 { 0.0 } else { secs }) else {
+                latest.remove(&key);
+                continue;
+            };
+            let runway = Runway { daily_spend, floor, remaining };
+            latest.insert(key, runway);
+            runways.insert(asset.to_string(), runway);
+        }
+        runways
+    }
+
+    /// Latest runways, shortest first
+    pub fn all(&self) -> Vec<AssetRunway> {
+        let latest = self.latest.read().unwrap_or_else(|e| e.into_inner());
+        let mut runways: Vec<AssetRunway> = latest
+            .iter()
+            .map(|((network_name, alias, asset), runway)| AssetRunway {
+                network_name: network_name.clone(),
+                alias: alias.clone(),
+                asset: asset.clone(),
+                runway: *runway,
+            })
+            .collect();
+        runways.sort_by(|a, b| {
+            a.runway.remaining.cmp(&b.runway.remaining).then_with(|| (&a.network_name, &a.alias).cmp(&(&b.network_name, &b.alias)))
+        });
+        runways
+    }
+}
@@ -0,0 +1,149 @@
+use alloy::{primitives::Address, providers::Provider};
+use eyre::Result;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use tokio::sync::RwLock;
+use tracing::warn;
+
+use crate::config::AddressConfig;
+use crate::contracts::ISafe;
+
+/// Signing setup of a Safe multisig
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SafeState {
+    pub owners: Vec<Address>,
+    pub threshold: u64,
+    /// Number of executed transactions
+    pub nonce: u64,
+}
+
+impl SafeState {
+    /// Read owners, threshold and nonce from a Safe contract
+    pub async fn read<P: Provider>(provider: &P, address: Address) -> Result<Self> {
+        let safe = ISafe::new(address, provider);
+        let owners = safe.getOwners().call().await?;
+        let threshold = safe.getThreshold().call().await?;
+        let nonce = safe.nonce().call().await?;
+
+        Ok(Self {
+            owners,
+            threshold: threshold.saturating_to(),
+            nonce: nonce.saturating_to(),
+        })
+    }
+}
+
+/// Change in a Safe's owners or signing threshold
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SafeChange {
+    pub added_owners: Vec<Address>,
+    pub removed_owners: Vec<Address>,
+    /// Previous and new threshold, if it changed
+    pub threshold: Option<(u64, u64)>,
+    /// Nonce at the time of the change
+    pub nonce: u64,
+    /// Owner count and threshold after the change
+    pub owner_count: usize,
+    pub new_threshold: u64,
+}
+
+impl SafeChange {
+    /// Compare two readings; None if owners and threshold are unchanged
+    pub fn between(previous: &SafeState, current: &SafeState) -> Option<Self> {
+        let added_owners: Vec<Address> = current.owners.iter()
+            .filter(|owner| !previous.owners.contains(owner))
+            .copied()
+            .collect();
+        let removed_owners: Vec<Address> = previous.owners.iter()
+            .filter(|owner| !current.owners.contains(owner))
+            .copied()
+            .collect();
+        let threshold = (previous.threshold != current.threshold).then_some((previous.threshold, current.threshold));
+
+        if added_owners.is_empty() && removed_owners.is_empty() && threshold.is_none() {
+            return None;
+        }
+
+        Some(Self {
+            added_owners,
+            removed_owners,
+            threshold,
+            nonce: current.nonce,
+            owner_count: current.owners.len(),
+            new_threshold: current.threshold,
+        })
+    }
+
+    /// Human-readable lines, e.g. "➕ Owner added: 0x…"
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        lines.extend(self.added_owners.iter().map(|owner| format!("➕ Owner added: {}", owner)));
+        lines.extend(self.removed_owners.iter().map(|owner| format!("➖ Owner removed: {}", owner)));
+        if let Some((old, new)) = self.threshold {
+            lines.push(format!("✍️ Threshold: {} → {}", old, new));
+        }
+        lines.push(format!("Signers: {} of {} (nonce {})", self.new_threshold, self.owner_count, self.nonce));
+        lines
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "added_owners": self.added_owners,
+            "removed_owners": self.removed_owners,
+            "old_threshold": self.threshold.map(|(old, _)| old),
+            "threshold": self.new_threshold,
+            "owner_count": self.owner_count,
+            "nonce": self.nonce,
+        })
+    }
+}
+
+/// Tracks owners, threshold and nonce of addresses configured as `type: safe`.
+/// The first reading of each Safe is the baseline.
+#[derive(Debug, Default)]
+pub struct SafeWatcher {
+    safes: Vec<AddressConfig>,
+    states: RwLock<HashMap<Address, SafeState>>,
+}
+
+impl SafeWatcher {
+    pub fn new(safes: Vec<AddressConfig>) -> Self {
+        Self {
+            safes,
+            states: RwLock::new(HashMap::new()),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.safes.is_empty()
+    }
+
+    /// Read all Safes and return the ones whose owners or threshold changed
+    pub async fn check<P: Provider>(&self, provider: &P) -> Vec<(&AddressConfig, SafeChange)> {
+        let mut changed = Vec::new();
+
+        for safe in &self.safes {
+            match SafeState::read(provider, safe.address).await {
+                Ok(state) => {
+                    if let Some(change) = self.record(safe.address, state).await {
+                        changed.push((safe, change));
+                    }
+                }
+                Err(e) => warn!("Error reading Safe {} ({}): {}", safe.alias, safe.address, e),
+            }
+        }
+
+        changed
+    }
+
+    /// Store a reading and return the change against the previous one
+    pub async fn record(&self, address: Address, state: SafeState) -> Option<SafeChange> {
+        let previous = self.states.write().await.insert(address, state.clone())?;
+        SafeChange::between(&previous, &state)
+    }
+
+    /// Latest reading of a Safe
+    pub async fn get(&self, address: Address) -> Option<SafeState> {
+        self.states.read().await.get(&address).cloned()
+    }
+}
@@ -0,0 +1,159 @@
+use alloy::primitives::{utils::format_units, Address, U256};
+use eyre::Result;
+use reqwest::{Client, Url};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+use crate::config::TronConfig;
+use crate::http::http_client;
+use crate::monitoring::{BalanceInfo, TokenBalance};
+
+/// Chain ID of Tron mainnet, as reported by eth_chainId on TronGrid
+pub const TRON_MAINNET_CHAIN_ID: u64 = 728126428;
+
+/// Prefix byte of Tron mainnet addresses
+const TRON_ADDRESS_PREFIX: u8 = 0x41;
+
+/// Decimals of TRX (1 TRX = 1,000,000 sun)
+const TRX_DECIMALS: u8 = 6;
+
+/// Parse a base58 Tron address ("T…") or its hex form ("41…" or "0x…") into the
+/// 20-byte account address shared with the EVM
+pub fn parse_tron_address(s: &str) -> Result<Address> {
+    if s.starts_with('T') {
+        let bytes = bs58::decode(s)
+            .with_check(None)
+            .into_vec()
+            .map_err(|e| eyre::eyre!("invalid Tron address '{}': {}", s, e))?;
+        if bytes.len() != 21 || bytes[0] != TRON_ADDRESS_PREFIX {
+            eyre::bail!("invalid Tron address '{}'", s);
+        }
+        return Ok(Address::from_slice(&bytes[1..]));
+    }
+
+    let hex = s.strip_prefix("0x").unwrap_or(s);
+    let hex = if hex.len() == 42 { hex.strip_prefix("41").unwrap_or(hex) } else { hex };
+    hex.parse().map_err(|_| eyre::eyre!("invalid Tron address '{}'", s))
+}
+
+/// Base58 form of an account address, e.g. "TR7NHqjeKQxGTCi8q8ZY4pL8otSzgjLj6t"
+pub fn format_tron_address(address: &Address) -> String {
+    let mut bytes = vec![TRON_ADDRESS_PREFIX];
+    bytes.extend_from_slice(address.as_slice());
+    bs58::encode(bytes).with_check().into_string()
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct TronAccount {
+    /// TRX balance in sun; absent for accounts without TRX
+    #[serde(default)]
+    balance: u64,
+    /// TRC-20 balances as single-entry maps of contract address to amount
+    #[serde(default)]
+    trc20: Vec<HashMap<String, String>>,
+}
+
+#[derive(Deserialize)]
+struct AccountsResponse {
+    #[serde(default)]
+    data: Vec<TronAccount>,
+}
+
+/// Reads TRX and TRC-20 balances from the TronGrid REST API
+pub struct TronMonitor {
+    client: Client,
+    config: TronConfig,
+}
+
+impl TronMonitor {
+    pub fn new(config: TronConfig) -> Self {
+        Self {
+            client: http_client(),
+            config,
+        }
+    }
+
+    pub fn config(&self) -> &TronConfig {
+        &self.config
+    }
+
+    async fn account(&self, address: &Address) -> Result<TronAccount> {
+        let url = Url::parse(&format!(
+            "{}/v1/accounts/{}",
+            self.config.api_url.as_str().trim_end_matches('/'),
+            format_tron_address(address)
+        ))?;
+        let mut request = self.client.get(url);
+        if let Some(ref api_key) = self.config.api_key {
+            request = request.header("TRON-PRO-API-KEY", api_key);
+        }
+
+        let response = request.send().await?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            eyre::bail!("TronGrid returned {}: {}", status, body);
+        }
+        let response: AccountsResponse = response.json().await?;
+        // Accounts that never received TRX are not activated and have no data
+        Ok(response.data.into_iter().next().unwrap_or_default())
+    }
+
+    /// Fetch balances of all configured addresses
+    pub async fn check_addresses(&self) -> Vec<Result<BalanceInfo>> {
+        let mut results = Vec::new();
+        for address in &self.config.addresses {
+            let result = self.account(&address.address).await
+                .map(|account| self.balance_info(&address.alias, address.address, &account));
+            results.push(result);
+        }
+        results
+    }
+
+    fn balance_info(&self, alias: &str, address: Address, account: &TronAccount) -> BalanceInfo {
+        let trc20: HashMap<Address, U256> = account.trc20.iter()
+            .flat_map(|entry| entry.iter())
+            .filter_map(|(contract, amount)| {
+                Some((parse_tron_address(contract).ok()?, U256::from_str_radix(amount, 10).ok()?))
+            })
+            .collect();
+
+        let token_balances = self.config.tokens.iter()
+            .map(|token| {
+                let balance = trc20.get(&token.address).copied().unwrap_or_default();
+                TokenBalance {
+                    alias: token.alias.clone(),
+                    balance,
+                    formatted: format_units(balance, token.decimals).unwrap_or_else(|_| balance.to_string()),
+                    decimals: token.decimals,
+                    usd_value: None,
+                    shares: None,
+                }
+            })
+            .collect();
+
+        let sun = U256::from(account.balance);
+        BalanceInfo {
+            network_name: self.config.name.clone(),
+            chain_id: self.config.chain_id,
+            alias: alias.to_string(),
+            address,
+            eth_balance: sun,
+            eth_formatted: format_units(sun, TRX_DECIMALS).unwrap_or_else(|_| sun.to_string()),
+            native_symbol: "TRX".to_string(),
+            native_decimals: TRX_DECIMALS,
+            eth_usd_value: None,
+            token_balances,
+            nft_balances: Vec::new(),
+            block_number: None,
+            block_hash: None,
+        }
+    }
+
+    /// Balance from a TronGrid /v1/accounts response body
+    pub fn parse_account(&self, alias: &str, address: Address, body: &str) -> Result<BalanceInfo> {
+        let response: AccountsResponse = serde_json::from_str(body)?;
+        let account = response.data.into_iter().next().unwrap_or_default();
+        Ok(self.balance_info(alias, address, &account))
+    }
+}
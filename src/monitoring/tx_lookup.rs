@@ -0,0 +1,208 @@
+use alloy::{
+    consensus::Transaction as _,
+    eips::BlockNumberOrTag,
+    network::TransactionResponse,
+    primitives::{Address, TxHash, U256},
+    providers::Provider,
+    rpc::types::Filter,
+    sol_types::SolEvent,
+};
+use eyre::Result;
+use reqwest::Url;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+use crate::config::{TokenConfig, TxLookupConfig};
+use crate::contracts::IERC20;
+
+/// Direction of a transfer relative to the monitored address
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransferDirection {
+    Incoming,
+    Outgoing,
+}
+
+/// Transaction that moved funds to or from a monitored address
+#[derive(Debug, Clone)]
+pub struct TxAttribution {
+    pub tx_hash: TxHash,
+    pub block_number: u64,
+    /// Asset name ("ETH" or token alias)
+    pub asset: String,
+    pub direction: TransferDirection,
+    /// Other side of the transfer (None for contract creations)
+    pub counterparty: Option<Address>,
+    /// Amount moved in the asset's base units; 0 for transactions only paying gas
+    pub value: U256,
+    /// Block explorer link, if the network has an explorer_url
+    pub url: Option<Url>,
+}
+
+impl TxAttribution {
+    /// "from" for incoming transfers, "to" for outgoing ones
+    pub fn preposition(&self) -> &'static str {
+        match self.direction {
+            TransferDirection::Incoming => "from",
+            TransferDirection::Outgoing => "to",
+        }
+    }
+
+    /// JSON representation used by webhooks and the HTTP API
+    pub fn to_json(&self) -> Value {
+        json!({
+            "tx_hash": format!("{:?}", self.tx_hash),
+            "block_number": self.block_number,
+            "asset": self.asset,
+            "direction": match self.direction {
+                TransferDirection::Incoming => "incoming",
+                TransferDirection::Outgoing => "outgoing",
+            },
+            "counterparty": self.counterparty.map(|a| format!("{:?}", a)),
+            "value": self.value.to_string(),
+            "url": self.url.as_ref().map(Url::as_str),
+        })
+    }
+}
+
+/// Outgoing transfers to destinations not in `allowed`. Transactions moving no value,
+/// like the gas paid for a token transfer, don't count.
+pub fn unexpected_destinations<'a>(transactions: &'a [TxAttribution], allowed: &[Address]) -> Vec<&'a TxAttribution> {
+    transactions
+        .iter()
+        .filter(|tx| tx.direction == TransferDirection::Outgoing && !tx.value.is_zero())
+        .filter(|tx| !tx.counterparty.is_some_and(|destination| allowed.contains(&destination)))
+        .collect()
+}
+
+/// Find transactions that moved the given assets for `address` in blocks `from_block..=to_block`.
+///
+/// Token transfers come from Transfer logs. Native transfers are found by scanning
+/// full blocks, so only the last `max_native_blocks` are checked and transfers made
+/// by contracts (internal transactions) are not visible.
+#[allow(clippy::too_many_arguments)]
+pub async fn lookup_transactions<P: Provider>(
+    provider: &P,
+    config: &TxLookupConfig,
+    address: Address,
+    native: bool,
+    tokens: &[&TokenConfig],
+    from_block: u64,
+    to_block: u64,
+    explorer: Option<&Url>,
+) -> Result<Vec<TxAttribution>> {
+    let mut found = Vec::new();
+    if from_block > to_block {
+        return Ok(found);
+    }
+
+    if !tokens.is_empty() {
+        let from = from_block.max(to_block.saturating_sub(config.max_blocks.saturating_sub(1)));
+        found.extend(lookup_token_transfers(provider, address, tokens, from, to_block).await?);
+    }
+
+    if native {
+        let from = from_block.max(to_block.saturating_sub(config.max_native_blocks.saturating_sub(1)));
+        found.extend(lookup_native_transfers(provider, address, from, to_block).await?);
+    }
+
+    found.sort_by_key(|tx| tx.block_number);
+    for tx in &mut found {
+        tx.url = explorer.and_then(|explorer| explorer.join(&format!("tx/{:?}", tx.tx_hash)).ok());
+    }
+
+    Ok(found)
+}
+
+async fn lookup_token_transfers<P: Provider>(
+    provider: &P,
+    address: Address,
+    tokens: &[&TokenConfig],
+    from_block: u64,
+    to_block: u64,
+) -> Result<Vec<TxAttribution>> {
+    let aliases: HashMap<Address, &str> = tokens.iter()
+        .map(|token| (token.address, token.alias.as_str()))
+        .collect();
+    let filter = Filter::new()
+        .address(aliases.keys().copied().collect::<Vec<_>>())
+        .event_signature(IERC20::Transfer::SIGNATURE_HASH)
+        .from_block(from_block)
+        .to_block(to_block);
+
+    // Transfer(from, to, value): topic1 = from, topic2 = to
+    let outgoing = provider.get_logs(&filter.clone().topic1(address.into_word())).await?;
+    let incoming = provider.get_logs(&filter.topic2(address.into_word())).await?;
+
+    let mut found = Vec::new();
+    for log in outgoing.iter().chain(&incoming) {
+        let (Some(tx_hash), Some(block_number)) = (log.transaction_hash, log.block_number) else {
+            continue;
+        };
+        let Some(&asset) = aliases.get(&log.address()) else {
+            continue;
+        };
+        let Ok(transfer) = log.log_decode::<IERC20::Transfer>() else {
+            continue;
+        };
+
+        let transfer = transfer.inner.data;
+        let (direction, counterparty) = if transfer.from == address {
+            (TransferDirection::Outgoing, transfer.to)
+        } else {
+            (TransferDirection::Incoming, transfer.from)
+        };
+        found.push(TxAttribution {
+            tx_hash,
+            block_number,
+            asset: asset.to_string(),
+            direction,
+            counterparty: Some(counterparty),
+            value: transfer.value,
+            url: None,
+        });
+    }
+
+    Ok(found)
+}
+
+async fn lookup_native_transfers<P: Provider>(
+    provider: &P,
+    address: Address,
+    from_block: u64,
+    to_block: u64,
+) -> Result<Vec<TxAttribution>> {
+    let mut found = Vec::new();
+
+    for number in from_block..=to_block {
+        let Some(block) = provider.get_block_by_number(BlockNumberOrTag::Number(number)).full().await? else {
+            continue;
+        };
+
+        for tx in block.transactions.txns() {
+            let (from, to) = (tx.from(), tx.to());
+            // Outgoing transactions pay gas even without value, incoming ones only matter with value
+            let direction = if from == address {
+                TransferDirection::Outgoing
+            } else if to == Some(address) && !tx.value().is_zero() {
+                TransferDirection::Incoming
+            } else {
+                continue;
+            };
+
+            found.push(TxAttribution {
+                tx_hash: tx.tx_hash(),
+                block_number: number,
+                asset: "ETH".to_string(),
+                direction,
+                counterparty: match direction {
+                    TransferDirection::Outgoing => to,
+                    TransferDirection::Incoming => Some(from),
+                },
+                value: tx.value(),
+                url: None,
+            });
+        }
+    }
+
+    Ok(found)
+}
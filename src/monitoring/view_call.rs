@@ -0,0 +1,190 @@
+use alloy::{
+    dyn_abi::{DynSolType, DynSolValue, FunctionExt, JsonAbiExt},
+    json_abi::Function,
+    primitives::{utils::format_units, Bytes},
+    providers::Provider,
+    rpc::types::TransactionRequest,
+};
+use eyre::{Result, WrapErr};
+use std::collections::HashMap;
+use tokio::sync::RwLock;
+use tracing::warn;
+
+use crate::config::ViewCallConfig;
+
+/// A configured view call with its function parsed and call data encoded
+#[derive(Debug, Clone)]
+pub struct ViewCall {
+    pub config: ViewCallConfig,
+    function: Function,
+    input: Bytes,
+}
+
+impl ViewCall {
+    /// Parse the function signature and encode the arguments
+    pub fn new(config: ViewCallConfig) -> Result<Self> {
+        let signature = format!("function {} view returns ({})", config.function, config.returns);
+        let function = Function::parse(&signature)
+            .wrap_err_with(|| format!("invalid function '{}' returning '{}'", config.function, config.returns))?;
+
+        if function.inputs.len() != config.args.len() {
+            eyre::bail!("{} expects {} args, got {}", config.function, function.inputs.len(), config.args.len());
+        }
+        let args = function.inputs.iter().zip(&config.args)
+            .map(|(param, arg)| {
+                let ty = DynSolType::parse(&param.ty)?;
+                ty.coerce_str(arg).wrap_err_with(|| format!("invalid {} argument '{}'", param.ty, arg))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let input = function.abi_encode_input(&args)?.into();
+
+        if (config.min.is_some() || config.max.is_some()) && !is_numeric(&config.returns) {
+            eyre::bail!("min/max require a uint or int return type, got '{}'", config.returns);
+        }
+
+        Ok(Self { config, function, input })
+    }
+
+    /// Decode returned data into a value
+    pub fn decode(&self, data: &[u8]) -> Result<ViewValue> {
+        let value = self.function.abi_decode_output(data)?
+            .into_iter()
+            .next()
+            .ok_or_else(|| eyre::eyre!("{} returned no value", self.config.function))?;
+        Ok(ViewValue::new(&value, self.config.decimals))
+    }
+
+    /// Call the function at the latest block
+    pub async fn read<P: Provider>(&self, provider: &P) -> Result<ViewValue> {
+        let request = TransactionRequest::default()
+            .to(self.config.address)
+            .input(self.input.clone().into());
+        let data = provider.call(request).await?;
+        self.decode(&data)
+    }
+
+    /// Function call as written in the config, e.g. "balanceOf(0x…)"
+    pub fn describe(&self) -> String {
+        format!("{}({})", self.function.name, self.config.args.join(", "))
+    }
+}
+
+fn is_numeric(ty: &str) -> bool {
+    ty.starts_with("uint") || ty.starts_with("int")
+}
+
+/// Value returned by a view call
+#[derive(Debug, Clone, PartialEq)]
+pub struct ViewValue {
+    /// Display form, scaled by `decimals` for numbers
+    pub formatted: String,
+    /// Numeric value for thresholds, None for non-numeric types
+    pub number: Option<f64>,
+}
+
+impl ViewValue {
+    fn new(value: &DynSolValue, decimals: u8) -> Self {
+        let formatted = match value {
+            DynSolValue::Uint(n, _) => format_units(*n, decimals).unwrap_or_else(|_| n.to_string()),
+            DynSolValue::Int(n, _) => format_units(*n, decimals).unwrap_or_else(|_| n.to_string()),
+            DynSolValue::Bool(b) => b.to_string(),
+            DynSolValue::Address(a) => a.to_string(),
+            DynSolValue::String(s) => s.clone(),
+            other => format!("{:?}", other),
+        };
+        let number = match value {
+            DynSolValue::Uint(..) | DynSolValue::Int(..) => formatted.parse().ok(),
+            _ => None,
+        };
+        Self { formatted, number }
+    }
+}
+
+/// Where a numeric value is relative to its min/max
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ViewCallLevel {
+    #[default]
+    Normal,
+    Below,
+    Above,
+}
+
+impl ViewCallLevel {
+    pub fn of(value: &ViewValue, config: &ViewCallConfig) -> Self {
+        match value.number {
+            Some(n) if config.min.is_some_and(|min| n < min) => Self::Below,
+            Some(n) if config.max.is_some_and(|max| n > max) => Self::Above,
+            _ => Self::Normal,
+        }
+    }
+}
+
+/// What happened to a view call's value since the previous check
+#[derive(Debug, Clone, PartialEq)]
+pub struct ViewCallUpdate {
+    pub value: ViewValue,
+    /// Previous value, when it changed and change alerts are enabled
+    pub changed_from: Option<String>,
+    /// New level, when the value crossed below min or above max
+    pub crossed: Option<ViewCallLevel>,
+}
+
+/// Monitors the values returned by configured view calls
+#[derive(Debug, Default)]
+pub struct ViewCallMonitor {
+    calls: Vec<ViewCall>,
+    /// Last value and level per call alias
+    state: RwLock<HashMap<String, (ViewValue, ViewCallLevel)>>,
+}
+
+impl ViewCallMonitor {
+    pub fn new(configs: Vec<ViewCallConfig>) -> Result<Self> {
+        let calls = configs.into_iter()
+            .map(|config| {
+                let alias = config.alias.clone();
+                ViewCall::new(config).wrap_err_with(|| format!("view call '{}'", alias))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self { calls, state: RwLock::new(HashMap::new()) })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.calls.is_empty()
+    }
+
+    /// Run all calls and return the ones whose value should be alerted on
+    pub async fn check<P: Provider>(&self, provider: &P) -> Vec<(&ViewCall, ViewCallUpdate)> {
+        let mut updates = Vec::new();
+
+        for call in &self.calls {
+            match call.read(provider).await {
+                Ok(value) => {
+                    if let Some(update) = self.record(&call.config, value).await {
+                        updates.push((call, update));
+                    }
+                }
+                Err(e) => warn!("Error calling {} on {}: {}", call.describe(), call.config.address, e),
+            }
+        }
+
+        updates
+    }
+
+    /// Store a value. Returns an update when it changed or crossed a threshold;
+    /// the first value of each call is the baseline and only alerts if out of range.
+    pub async fn record(&self, config: &ViewCallConfig, value: ViewValue) -> Option<ViewCallUpdate> {
+        let level = ViewCallLevel::of(&value, config);
+        let previous = self.state.write().await.insert(config.alias.clone(), (value.clone(), level));
+        let (previous_value, previous_level) = match previous {
+            Some((value, level)) => (Some(value), level),
+            None => (None, ViewCallLevel::Normal),
+        };
+
+        let changed_from = previous_value
+            .filter(|previous| config.alert_on_change && previous.formatted != value.formatted)
+            .map(|previous| previous.formatted);
+        let crossed = (level != previous_level && level != ViewCallLevel::Normal).then_some(level);
+
+        (changed_from.is_some() || crossed.is_some()).then_some(ViewCallUpdate { value, changed_from, crossed })
+    }
+}
@@ -0,0 +1,110 @@
+use alloy::primitives::Address;
+use eyre::{eyre, Result, WrapErr};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio::sync::RwLock;
+use tracing::{info, warn};
+
+use super::{TransferDirection, TxAttribution};
+use crate::config::WatchlistConfig;
+
+/// Parse a watchlist: one address per line, optionally followed by a label. Blank
+/// lines and `#` comments are skipped. Addresses without a label are labelled "flagged".
+pub fn parse_watchlist(text: &str) -> Result<HashMap<Address, String>> {
+    let mut entries = HashMap::new();
+    for (number, line) in text.lines().enumerate() {
+        let line = line.split('#').next().unwrap_or_default().trim();
+        if line.is_empty() {
+            continue;
+        }
+        let (address, label) = line.split_once(|c: char| c.is_whitespace() || c == ',').unwrap_or((line, ""));
+        let address: Address = address.parse().map_err(|e| eyre!("line {}: invalid address '{}': {}", number + 1, address, e))?;
+        let label = label.trim_start_matches(',').trim();
+        entries.insert(address, if label.is_empty() { "flagged".to_string() } else { label.to_string() });
+    }
+    Ok(entries)
+}
+
+/// Flagged counterparties read from a file, re-read when the file changes
+pub struct Watchlist {
+    config: WatchlistConfig,
+    entries: RwLock<HashMap<Address, String>>,
+    /// Modification time of the file when it was last read
+    modified: RwLock<Option<SystemTime>>,
+}
+
+impl Watchlist {
+    /// Read the watchlist file; fails if it's missing or has an invalid line
+    pub async fn load(config: &WatchlistConfig) -> Result<Self> {
+        let watchlist = Self { config: config.clone(), entries: RwLock::new(HashMap::new()), modified: RwLock::new(None) };
+        watchlist.reload().await?;
+        Ok(watchlist)
+    }
+
+    /// Channels flagged transfers are sent to; empty for all
+    pub fn channels(&self) -> &[String] {
+        &self.config.channels
+    }
+
+    /// Re-read the file if it changed since the last read. Returns whether it was read;
+    /// on error the current entries are kept.
+    pub async fn reload(&self) -> Result<bool> {
+        let path = &self.config.path;
+        let modified = tokio::fs::metadata(path)
+            .await
+            .and_then(|metadata| metadata.modified())
+            .wrap_err_with(|| format!("failed to read watchlist {}", path))?;
+        if *self.modified.read().await == Some(modified) {
+            return Ok(false);
+        }
+        let text = tokio::fs::read_to_string(path)
+            .await
+            .wrap_err_with(|| format!("failed to read watchlist {}", path))?;
+        let entries = parse_watchlist(&text).wrap_err_with(|| format!("invalid watchlist {}", path))?;
+        *self.entries.write().await = entries;
+        *self.modified.write().await = Some(modified);
+        Ok(true)
+    }
+
+    /// Number of flagged addresses
+    pub async fn len(&self) -> usize {
+        self.entries.read().await.len()
+    }
+
+    pub async fn is_empty(&self) -> bool {
+        self.entries.read().await.is_empty()
+    }
+
+    /// Label of a flagged address
+    pub async fn label(&self, address: &Address) -> Option<String> {
+        self.entries.read().await.get(address).cloned()
+    }
+
+    /// Transactions whose counterparty is flagged, with its label. Outgoing transfers
+    /// are only included with `outgoing` set.
+    pub async fn flagged<'a>(&self, transactions: &'a [TxAttribution]) -> Vec<(&'a TxAttribution, String)> {
+        let entries = self.entries.read().await;
+        transactions
+            .iter()
+            .filter(|tx| self.config.outgoing || tx.direction == TransferDirection::Incoming)
+            .filter_map(|tx| Some((tx, entries.get(tx.counterparty.as_ref()?)?.clone())))
+            .collect()
+    }
+}
+
+/// Check the watchlist file for changes every `reload_secs`
+pub fn spawn_watchlist_reload(watchlist: Arc<Watchlist>) {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(Duration::from_secs(watchlist.config.reload_secs));
+        ticker.tick().await;
+        loop {
+            ticker.tick().await;
+            match watchlist.reload().await {
+                Ok(true) => info!("🚩 Watchlist reloaded: {} flagged addresses", watchlist.len().await),
+                Ok(false) => {}
+                Err(e) => warn!("{:#}; keeping the previous list", e),
+            }
+        }
+    });
+}
@@ -0,0 +1,192 @@
+use super::LowBalanceAlert;
+use crate::logger::{BalanceChangeSummary, TokenBalanceChange};
+use crate::monitoring::{BalanceInfo, DrainedAsset, TxAttribution};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+/// Alert kind of test alerts. Like heartbeats they reach every chat regardless of
+/// subscriptions, so any chat can confirm its channel works.
+pub const TEST_ALERT_KIND: &str = "test";
+
+/// Alert kind of drained alerts. They reach every chat, including muted ones.
+pub const DRAINED_KIND: &str = "drained";
+
+/// Alert kind of transfers from or to an address on the watchlist
+pub const FLAGGED_KIND: &str = "flagged_counterparty";
+
+/// Alert kind of outgoing transfers to a destination not on the address's allow-list
+pub const UNEXPECTED_DESTINATION_KIND: &str = "unexpected_destination";
+
+/// Alert kind of outflows from an address with an allow-list whose transaction wasn't found
+pub const UNKNOWN_DESTINATION_KIND: &str = "unknown_destination";
+
+/// Alert kind of low balance alerts, also used for critical tiers sent as escalated alerts
+pub const LOW_BALANCE_KIND: &str = "low_balance";
+
+/// Network-level alert raised by monitors other than balance checks (e.g. gas prices)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MonitorAlert {
+    /// Event name used in webhook payloads, e.g. "gas_price"
+    pub kind: String,
+    pub network_name: String,
+    /// EVM chain ID, None for chains without numeric IDs (e.g. Cosmos)
+    pub chain_id: Option<u64>,
+    /// Short headline, e.g. "Gas above 50 gwei"
+    pub title: String,
+    /// Detail lines shown in the message body
+    pub lines: Vec<String>,
+    /// Structured details merged into webhook payloads
+    pub data: Value,
+    /// What tells this event apart from others of the same kind and title, e.g. the changed
+    /// value; None if the title alone identifies it. Detail lines are left out of the
+    /// dedup key since they carry live values that differ between instances.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dedup_id: Option<String>,
+}
+
+impl MonitorAlert {
+    /// Alert sent to verify channel configuration
+    pub fn test(source: &str) -> Self {
+        Self {
+            kind: TEST_ALERT_KIND.to_string(),
+            network_name: "All networks".to_string(),
+            chain_id: None,
+            title: "🧪 Test alert".to_string(),
+            lines: vec![format!("Sent by {} to check that this channel receives alerts.", source)],
+            data: json!({ "source": source }),
+            dedup_id: None,
+        }
+    }
+
+    /// Emergency alert for an address whose balances dropped to (near) zero
+    pub fn drained(balance: &BalanceInfo, assets: &[DrainedAsset]) -> Self {
+        let mut lines = vec![format!("Address: {:?}", balance.address)];
+        lines.extend(assets.iter().map(|asset| format!("{}: {} → {}", asset.asset, asset.old_formatted, asset.new_formatted)));
+        Self {
+            kind: DRAINED_KIND.to_string(),
+            network_name: balance.network_name.clone(),
+            chain_id: Some(balance.chain_id),
+            title: format!("🆘 {} drained", balance.alias),
+            lines,
+            data: json!({
+                "alias": balance.alias,
+                "address": balance.address,
+                "assets": assets.iter().map(|asset| json!({
+                    "asset": asset.asset,
+                    "old": asset.old_formatted,
+                    "new": asset.new_formatted,
+                })).collect::<Vec<_>>(),
+            }),
+            dedup_id: None,
+        }
+    }
+
+    /// Critical alert for a transfer whose counterparty is on the watchlist
+    pub fn flagged(changes: &BalanceChangeSummary, tx: &TxAttribution, label: &str) -> Self {
+        let counterparty = tx.counterparty.map(|address| format!("{:?}", address)).unwrap_or_default();
+        let mut lines = vec![
+            format!("Address: {}", changes.address),
+            format!("{} {} {} ({})", tx.asset, tx.preposition(), counterparty, label),
+            format!("Tx: {:?}", tx.tx_hash),
+        ];
+        lines.extend(tx.url.as_ref().map(|url| format!("Explorer: {}", url)));
+        Self {
+            kind: FLAGGED_KIND.to_string(),
+            network_name: changes.network_name.clone(),
+            chain_id: Some(changes.chain_id),
+            title: format!("🚩 {} transfer {} flagged address", changes.alias, tx.preposition()),
+            lines,
+            data: json!({
+                "severity": "critical",
+                "alias": changes.alias,
+                "address": changes.address,
+                "label": label,
+                "transaction": tx.to_json(),
+            }),
+            dedup_id: Some(format!("{:?}", tx.tx_hash)),
+        }
+    }
+
+    /// Critical alert for an outgoing transfer to a destination not on the address's `allowed_destinations`
+    pub fn unexpected_destination(changes: &BalanceChangeSummary, tx: &TxAttribution) -> Self {
+        let destination = tx.counterparty.map(|address| format!("{:?}", address)).unwrap_or_else(|| "contract creation".to_string());
+        let mut lines = vec![
+            format!("Address: {}", changes.address),
+            format!("{} to {}", tx.asset, destination),
+            format!("Tx: {:?}", tx.tx_hash),
+        ];
+        lines.extend(tx.url.as_ref().map(|url| format!("Explorer: {}", url)));
+        Self {
+            kind: UNEXPECTED_DESTINATION_KIND.to_string(),
+            network_name: changes.network_name.clone(),
+            chain_id: Some(changes.chain_id),
+            title: format!("🚨 {} sent funds to an unexpected destination", changes.alias),
+            lines,
+            data: json!({
+                "severity": "critical",
+                "alias": changes.alias,
+                "address": changes.address,
+                "transaction": tx.to_json(),
+            }),
+            dedup_id: Some(format!("{:?}", tx.tx_hash)),
+        }
+    }
+
+    /// Critical alert for a balance drop of an address with `allowed_destinations` whose
+    /// outgoing transaction wasn't found, so its destination can't be checked
+    pub fn unknown_destination(changes: &BalanceChangeSummary, change: &TokenBalanceChange) -> Self {
+        Self {
+            kind: UNKNOWN_DESTINATION_KIND.to_string(),
+            network_name: changes.network_name.clone(),
+            chain_id: Some(changes.chain_id),
+            title: format!("🚨 {} sent {} to an unknown destination", changes.alias, change.alias),
+            lines: vec![
+                format!("Address: {}", changes.address),
+                format!("{}: {} → {}", change.alias, change.old_formatted, change.new_formatted),
+                "No outgoing transaction was found, e.g. it's older than the blocks searched or an internal transaction".to_string(),
+            ],
+            data: json!({
+                "severity": "critical",
+                "alias": changes.alias,
+                "address": changes.address,
+                "asset": change.alias,
+                "old_balance": change.old_formatted,
+                "new_balance": change.new_formatted,
+            }),
+            dedup_id: Some(format!("{}->{}", change.old_balance, change.new_balance)),
+        }
+    }
+
+    /// Critical low balance tier, sent like other critical alerts so it can be acknowledged and escalated
+    pub fn low_balance(alert: &LowBalanceAlert) -> Self {
+        let unit = if alert.is_native { format!(" {}", alert.asset) } else { String::new() };
+        let mut lines = vec![
+            format!("Address: {:?}", alert.address),
+            format!("{}: {} (below {}{})", alert.asset, alert.formatted, alert.threshold, unit),
+        ];
+        lines.extend(Some(alert.runway_text()).filter(|runway| !runway.is_empty()));
+        lines.push(alert.call_to_action().to_string());
+        Self {
+            kind: LOW_BALANCE_KIND.to_string(),
+            network_name: alert.network_name.clone(),
+            chain_id: Some(alert.chain_id),
+            title: format!("{} Low balance #{}: {}", alert.severity.emoji(), alert.alert_number, alert.alias),
+            lines,
+            data: json!({
+                "severity": alert.severity,
+                "alias": alert.alias,
+                "address": alert.address,
+                "asset": alert.asset,
+                "balance": alert.formatted,
+                "threshold": alert.threshold,
+                "alert_number": alert.alert_number,
+            }),
+            dedup_id: Some(alert.threshold.to_string()),
+        }
+    }
+
+    /// " (Chain ID: 1)" for EVM chains, empty otherwise
+    pub fn chain_suffix(&self) -> String {
+        self.chain_id.map(|id| format!(" (Chain ID: {})", id)).unwrap_or_default()
+    }
+}
@@ -0,0 +1,111 @@
+use super::{BalanceReport, ChangeDigest, LowBalanceAlert, MonitorAlert};
+use crate::config::AlertSeverity;
+use crate::events::{EventBus, MonitorEvent};
+use crate::logger::BalanceChangeSummary;
+use crate::storage::{AuditEntry, AuditQuery, StateBackend};
+use chrono::Utc;
+use eyre::Result;
+use sha2::{Digest, Sha256};
+use std::fmt::Debug;
+use std::sync::Arc;
+use tracing::warn;
+
+/// What an alert was about, shared by all delivery records of that alert
+#[derive(Debug, Clone)]
+pub struct AuditSubject {
+    pub kind: String,
+    pub network: Option<String>,
+    pub subject: String,
+    pub content_hash: String,
+    /// Severity of low balance and rule alerts
+    pub severity: Option<AlertSeverity>,
+}
+
+impl AuditSubject {
+    pub fn change(changes: &BalanceChangeSummary) -> Self {
+        Self::new("balance_change", Some(&changes.network_name), changes.alias.clone(), changes)
+    }
+
+    pub fn digest(digest: &ChangeDigest) -> Self {
+        let subject = format!("{} balance changes", digest.len());
+        Self::new("balance_change", Some(&digest.network_name), subject, digest)
+    }
+
+    pub fn low_balance(alert: &LowBalanceAlert) -> Self {
+        let subject = format!("{} {} below {}", alert.alias, alert.asset, alert.threshold);
+        Self { severity: Some(alert.severity), ..Self::new("low_balance", Some(&alert.network_name), subject, alert) }
+    }
+
+    pub fn alert(alert: &MonitorAlert) -> Self {
+        let severity = alert.data.get("severity").and_then(|severity| serde_json::from_value(severity.clone()).ok());
+        Self { severity, ..Self::new(&alert.kind, Some(&alert.network_name), alert.title.clone(), alert) }
+    }
+
+    pub fn report(report: &BalanceReport) -> Self {
+        let subject = format!("Daily report ({} addresses)", report.address_count);
+        Self::new("report", None, subject, report)
+    }
+
+    /// Hash the alert content so deliveries on different channels can be matched
+    fn new(kind: &str, network: Option<&str>, subject: String, content: &impl Debug) -> Self {
+        let digest = Sha256::digest(format!("{:?}", content).as_bytes());
+        Self {
+            kind: kind.to_string(),
+            network: network.map(str::to_string),
+            subject,
+            content_hash: hex::encode(digest),
+            severity: None,
+        }
+    }
+
+    /// Delivery record for one channel (and chat) sent now
+    pub fn entry(&self, channel: &str, chat: Option<String>, result: &Result<()>) -> AuditEntry {
+        AuditEntry {
+            sent_at: Utc::now().timestamp() as u64,
+            channel: channel.to_lowercase(),
+            chat,
+            kind: self.kind.clone(),
+            network: self.network.clone(),
+            subject: self.subject.clone(),
+            content_hash: self.content_hash.clone(),
+            success: result.is_ok(),
+            error: result.as_ref().err().map(|e| e.to_string()),
+        }
+    }
+}
+
+/// Alert delivery audit log persisted in the state backend
+#[derive(Clone)]
+pub struct AuditLog {
+    backend: Arc<dyn StateBackend>,
+    events: Option<EventBus>,
+}
+
+impl AuditLog {
+    pub fn new(backend: Arc<dyn StateBackend>) -> Self {
+        Self { backend, events: None }
+    }
+
+    /// Also publish every delivery record as an `alert_sent` event
+    pub fn with_events(mut self, events: EventBus) -> Self {
+        self.events = Some(events);
+        self
+    }
+
+    /// Persist a delivery record; failures are logged and never block alerting. The
+    /// `alert_sent` event is only a notification: bus subscribers that fall behind skip
+    /// events, so the record is written here rather than by one of them.
+    pub async fn record(&self, entry: AuditEntry) {
+        if let Some(events) = &self.events {
+            events.publish(MonitorEvent::alert_sent(&entry));
+        }
+        if let Err(e) = self.backend.append_audit(&entry).await {
+            warn!("Failed to write alert audit entry to {} backend: {}", self.backend.name(), e);
+        }
+    }
+
+    /// Delivery records matching query, newest first
+    pub async fn query(&self, query: &AuditQuery) -> Result<Vec<AuditEntry>> {
+        self.backend.load_audit(query).await
+    }
+}
@@ -0,0 +1,121 @@
+use super::{BalanceReport, ChangeDigest, LowBalanceAlert, MonitorAlert, HEARTBEAT_KIND};
+use crate::config::AlertDedupConfig;
+use crate::logger::{BalanceChange, BalanceChangeSummary};
+use crate::storage::StateBackend;
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// Identity of an alert across instances. The hash covers what changed, not when it
+/// was seen, prices or transaction attribution, which can differ between instances
+/// watching the same wallets.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DedupKey {
+    pub network: String,
+    /// Address alias, or alert kind for network-level alerts
+    pub subject: String,
+    pub hash: String,
+}
+
+impl DedupKey {
+    /// Balance change of one address: the old and new balance of each changed asset
+    pub fn change(changes: &BalanceChangeSummary) -> Self {
+        let mut parts = vec![changes.address.clone()];
+        for change in changes.eth_change.iter().chain(&changes.token_changes) {
+            if !matches!(change.change, BalanceChange::NoChange) {
+                parts.push(format!("{}:{}->{}", change.alias, change.old_balance, change.new_balance));
+            }
+        }
+        for nft in &changes.nft_changes {
+            parts.push(format!("{}:{}->{}", nft.label, nft.old_count, nft.new_count));
+        }
+        Self::new(&changes.network_name, &changes.alias, &parts)
+    }
+
+    /// Digest of one check cycle: the changes it lists
+    pub fn digest(digest: &ChangeDigest) -> Self {
+        let parts: Vec<String> = digest.changes.iter().map(|changes| Self::change(changes).hash).collect();
+        Self::new(&digest.network_name, "digest", &parts)
+    }
+
+    /// Low balance alert: the asset and the threshold it fell below
+    pub fn low_balance(alert: &LowBalanceAlert) -> Self {
+        let parts = [alert.address.to_string(), alert.asset.clone(), alert.threshold.to_string()];
+        Self::new(&alert.network_name, &alert.alias, &parts)
+    }
+
+    /// Network-level alert: its title, which names the subject and state transition, and
+    /// its `dedup_id`. Detail lines are left out since they carry live values such as the
+    /// base fee or minutes without a block. None for heartbeats, which every instance
+    /// sends to show it is alive.
+    pub fn alert(alert: &MonitorAlert) -> Option<Self> {
+        if alert.kind == HEARTBEAT_KIND {
+            return None;
+        }
+        let mut parts = vec![alert.title.clone()];
+        parts.extend(alert.dedup_id.clone());
+        Some(Self::new(&alert.network_name, &alert.kind, &parts))
+    }
+
+    /// Daily report: one per day
+    pub fn report(report: &BalanceReport) -> Self {
+        Self::new("", "report", &[report.generated_at.date_naive().to_string()])
+    }
+
+    /// The same alert sent to one channel. Each channel is claimed separately, so a
+    /// failed delivery on one channel doesn't suppress the others.
+    pub fn channel(&self, channel: &str) -> Self {
+        Self::new(&self.network, &self.subject, &[self.hash.clone(), channel.to_string()])
+    }
+
+    fn new(network: &str, subject: &str, parts: &[String]) -> Self {
+        Self {
+            network: network.to_string(),
+            subject: subject.to_string(),
+            hash: hex::encode(Sha256::digest(parts.join("\n").as_bytes())),
+        }
+    }
+}
+
+/// Claims alerts per channel in the state backend before they are sent, so active
+/// instances sharing a database send each alert once: the first to claim it sends it,
+/// the others skip it until the window has passed. A delivery that fails releases its
+/// claim, so a retry or another instance can still send it.
+#[derive(Clone)]
+pub struct AlertDedup {
+    backend: Arc<dyn StateBackend>,
+    window: Duration,
+}
+
+impl AlertDedup {
+    pub fn new(backend: Arc<dyn StateBackend>, config: &AlertDedupConfig) -> Self {
+        Self {
+            backend,
+            window: Duration::from_secs(config.window_secs),
+        }
+    }
+
+    /// True if this instance should send the alert. If the backend can't be reached
+    /// the alert is sent anyway: a duplicate is better than a missed alert.
+    pub async fn claim(&self, key: &DedupKey) -> bool {
+        match self.backend.claim_alert(&key.network, &key.subject, &key.hash, self.window).await {
+            Ok(true) => true,
+            Ok(false) => {
+                info!("Skipping {} alert on {}: already sent by another instance", key.subject, key.network);
+                false
+            }
+            Err(e) => {
+                warn!("Failed to claim {} alert in {} backend, sending it anyway: {}", key.subject, self.backend.name(), e);
+                true
+            }
+        }
+    }
+
+    /// Give up the claim on an alert whose delivery failed
+    pub async fn release(&self, key: &DedupKey) {
+        if let Err(e) = self.backend.release_alert(&key.network, &key.subject, &key.hash).await {
+            warn!("Failed to release {} alert claim in {} backend: {}", key.subject, self.backend.name(), e);
+        }
+    }
+}
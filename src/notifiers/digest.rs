@@ -0,0 +1,133 @@
+use crate::logger::{BalanceChange, BalanceChangeSummary};
+use crate::monitoring::from_units;
+use alloy::primitives::{utils::format_units, I256, U256};
+use serde_json::{json, Value};
+
+/// Net change of one asset across the addresses of a digest, in base units
+#[derive(Debug, Clone, PartialEq)]
+pub struct NetChange {
+    pub asset: String,
+    pub amount: I256,
+    pub decimals: u8,
+}
+
+impl NetChange {
+    /// Signed amount in whole units, e.g. "-1500" or "+0.25"
+    pub fn formatted(&self) -> String {
+        let text = format_units(self.amount, self.decimals).unwrap_or_else(|_| self.amount.to_string());
+        let text = if text.contains('.') { text.trim_end_matches('0').trim_end_matches('.') } else { &text };
+        if self.amount.is_positive() { format!("+{}", text) } else { text.to_string() }
+    }
+
+    /// Signed amount in whole units as a float, for JSON
+    pub fn value(&self) -> f64 {
+        let value = from_units(self.amount.unsigned_abs(), self.decimals);
+        if self.amount.is_negative() { -value } else { value }
+    }
+}
+
+/// Balance changes of one network found in a single check cycle, sent as one message
+/// instead of one alert per address (e.g. during a batch payout)
+#[derive(Debug, Clone)]
+pub struct ChangeDigest {
+    pub network_name: String,
+    pub chain_id: u64,
+    pub changes: Vec<BalanceChangeSummary>,
+    /// Addresses listed in full before the rest are collapsed into a summary
+    pub max_items: usize,
+}
+
+impl ChangeDigest {
+    pub fn new(network_name: &str, chain_id: u64, max_items: usize) -> Self {
+        Self { network_name: network_name.to_string(), chain_id, changes: Vec::new(), max_items }
+    }
+
+    pub fn push(&mut self, changes: BalanceChangeSummary) {
+        self.changes.push(changes);
+    }
+
+    pub fn len(&self) -> usize {
+        self.changes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Changes shown in full
+    pub fn listed(&self) -> &[BalanceChangeSummary] {
+        &self.changes[..self.changes.len().min(self.max_items)]
+    }
+
+    /// Number of changes collapsed into the summary
+    pub fn collapsed(&self) -> usize {
+        self.changes.len().saturating_sub(self.max_items)
+    }
+
+    /// Only the changes matching `keep`, e.g. the addresses a chat is subscribed to
+    pub fn filtered(&self, keep: impl Fn(&BalanceChangeSummary) -> bool) -> Self {
+        Self {
+            changes: self.changes.iter().filter(|changes| keep(changes)).cloned().collect(),
+            ..Self::new(&self.network_name, self.chain_id, self.max_items)
+        }
+    }
+
+    /// Net change of each asset across all addresses, in order of first appearance
+    pub fn net_changes(&self) -> Vec<NetChange> {
+        let signed = |amount: U256| I256::try_from(amount).unwrap_or(I256::MAX);
+        let mut totals: Vec<NetChange> = Vec::new();
+        for change in self.changes.iter().flat_map(BalanceChangeSummary::changed_assets) {
+            let amount = signed(change.new_balance).saturating_sub(signed(change.old_balance));
+            match totals.iter_mut().find(|total| total.asset == change.alias && total.decimals == change.decimals) {
+                Some(total) => total.amount = total.amount.saturating_add(amount),
+                None => totals.push(NetChange { asset: change.alias.clone(), amount, decimals: change.decimals }),
+            }
+        }
+        totals
+    }
+
+    /// True if any asset increased or decreased, respectively
+    pub fn directions(&self) -> (bool, bool) {
+        let (mut increased, mut decreased) = (false, false);
+        for changes in &self.changes {
+            for change in changes.changed_assets() {
+                increased |= matches!(change.change, BalanceChange::Increase);
+                decreased |= matches!(change.change, BalanceChange::Decrease);
+            }
+            for nft in &changes.nft_changes {
+                increased |= nft.new_count > nft.old_count;
+                decreased |= nft.new_count < nft.old_count;
+            }
+        }
+        (increased, decreased)
+    }
+
+    /// Plain text lines after the listed changes: addresses not shown and net changes
+    pub fn summary_lines(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        if self.collapsed() > 0 {
+            let noun = if self.collapsed() == 1 { "address" } else { "addresses" };
+            lines.push(format!("…and {} more {}", self.collapsed(), noun));
+        }
+        let net: Vec<String> = self.net_changes()
+            .iter()
+            .filter(|net| !net.amount.is_zero())
+            .map(|net| format!("{} {}", net.formatted(), net.asset))
+            .collect();
+        if !net.is_empty() {
+            lines.push(format!("Net change: {}", net.join(", ")));
+        }
+        lines
+    }
+
+    /// JSON representation with every change, including collapsed ones
+    pub fn to_json(&self) -> Value {
+        json!({
+            "network": self.network_name,
+            "chain_id": self.chain_id,
+            "count": self.changes.len(),
+            "changes": self.changes.iter().map(BalanceChangeSummary::to_json).collect::<Vec<_>>(),
+            "net_changes": self.net_changes().iter().map(|net| (net.asset.clone(), json!(net.value()))).collect::<serde_json::Map<_, _>>(),
+        })
+    }
+}
@@ -0,0 +1,432 @@
+use super::{AuditSubject, BalanceReport, ChangeDigest, LowBalanceAlert, MonitorAlert, Notifier};
+use crate::config::{AlertSettings, DiscordConfig, DiscordTarget};
+use crate::http::http_client;
+use crate::logger::{calculate_diff, calculate_percent_change, shorten_address, BalanceChange, BalanceChangeSummary};
+use async_trait::async_trait;
+use eyre::Result;
+use reqwest::Client;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+const DISCORD_API_URL: &str = "https://discord.com/api/v10";
+
+/// Embed colors
+const COLOR_INCREASE: u32 = 0x2ECC71;
+const COLOR_DECREASE: u32 = 0xE74C3C;
+const COLOR_MIXED: u32 = 0xF1C40F;
+const COLOR_LOW_BALANCE: u32 = 0xE67E22;
+const COLOR_REPORT: u32 = 0x3498DB;
+const COLOR_ALERT: u32 = 0x9B59B6;
+
+/// Transactions listed in a change embed before truncating
+const MAX_TRANSACTION_LINES: usize = 8;
+
+/// Embed limits in characters; Discord rejects the whole message if one is exceeded
+const MAX_TITLE_LEN: usize = 256;
+const MAX_DESCRIPTION_LEN: usize = 4096;
+const MAX_FIELDS: usize = 25;
+const MAX_FIELD_NAME_LEN: usize = 256;
+const MAX_FIELD_VALUE_LEN: usize = 1024;
+const MAX_FOOTER_LEN: usize = 2048;
+const MAX_EMBED_LEN: usize = 6000;
+
+/// Discord notifier sending rich embeds via webhook or bot API
+pub struct DiscordNotifier {
+    client: Client,
+    bot_token: Option<String>,
+    default_target: DiscordTarget,
+    network_targets: HashMap<String, DiscordTarget>,
+    alerts: AlertSettings,
+    show_full_address: bool,
+}
+
+impl DiscordNotifier {
+    pub fn new(config: &DiscordConfig) -> Self {
+        Self {
+            client: http_client(),
+            bot_token: config.bot_token.clone(),
+            default_target: config.target.clone(),
+            network_targets: config.networks.clone(),
+            alerts: config.alerts.clone(),
+            show_full_address: config.show_full_address,
+        }
+    }
+
+    /// Destination for a network, falling back to the default target
+    fn target_for(&self, network_name: &str) -> &DiscordTarget {
+        self.network_targets.get(network_name).unwrap_or(&self.default_target)
+    }
+
+    /// Send a single embed to the target
+    async fn send_embed(&self, target: &DiscordTarget, embed: Value) -> Result<()> {
+        let payload = json!({ "embeds": [fit_embed(embed)] });
+
+        let request = if let Some(webhook_url) = &target.webhook_url {
+            self.client.post(webhook_url.clone())
+        } else if let (Some(channel_id), Some(token)) = (target.channel_id, &self.bot_token) {
+            self.client
+                .post(format!("{}/channels/{}/messages", DISCORD_API_URL, channel_id))
+                .header("Authorization", format!("Bot {}", token))
+        } else {
+            eyre::bail!("Discord target has neither webhook_url nor channel_id with bot_token");
+        };
+
+        let response = request.json(&payload).send().await?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            eyre::bail!("Discord API returned {}: {}", status, body);
+        }
+
+        Ok(())
+    }
+
+    fn display_address(&self, address: &str) -> String {
+        if self.show_full_address {
+            address.to_string()
+        } else {
+            shorten_address(address)
+        }
+    }
+
+    /// Build embed for balance changes, green for increases and red for decreases
+    fn change_embed(&self, changes: &BalanceChangeSummary) -> Value {
+        let mut increased = false;
+        let mut decreased = false;
+        let mut fields = Vec::new();
+
+        for change in changes.changed_assets() {
+            let (emoji, sign) = match change.change {
+                BalanceChange::Increase => {
+                    increased = true;
+                    ("📈", "+")
+                }
+                _ => {
+                    decreased = true;
+                    ("📉", "")
+                }
+            };
+            let diff = calculate_diff(&change.new_balance, &change.old_balance, change.decimals);
+            let percent = calculate_percent_change(&change.new_balance, &change.old_balance);
+
+            let mut value = format!("{} **{}{}**", emoji, sign, diff);
+            if percent.abs() >= 0.01 {
+                value.push_str(&format!(" ({:+.2}%)", percent));
+            }
+            value.push_str(&format!("\n{} → {}", change.old_formatted, change.new_formatted));
+
+            fields.push(json!({ "name": change.alias, "value": value, "inline": false }));
+        }
+
+        for nft in &changes.nft_changes {
+            let emoji = if nft.new_count > nft.old_count {
+                increased = true;
+                "📥"
+            } else {
+                decreased = true;
+                "📤"
+            };
+            fields.push(json!({ "name": format!("🖼️ {}", nft.label), "value": format!("{} {}", emoji, nft.describe()), "inline": false }));
+        }
+
+        if !changes.transactions.is_empty() {
+            // Embed field values are limited to 1024 characters
+            let mut lines: Vec<String> = changes.transactions.iter()
+                .take(MAX_TRANSACTION_LINES)
+                .map(|tx| {
+                    let counterparty = tx.counterparty
+                        .map(|address| self.display_address(&format!("{:?}", address)))
+                        .unwrap_or_else(|| "contract creation".to_string());
+                    let hash = shorten_address(&format!("{:?}", tx.tx_hash));
+                    let hash = match &tx.url {
+                        Some(url) => format!("[{}]({})", hash, url),
+                        None => format!("`{}`", hash),
+                    };
+                    format!("{} {} `{}` · {}", tx.asset, tx.preposition(), counterparty, hash)
+                })
+                .collect();
+            if changes.transactions.len() > MAX_TRANSACTION_LINES {
+                lines.push(format!("…and {} more", changes.transactions.len() - MAX_TRANSACTION_LINES));
+            }
+            fields.push(json!({ "name": "🧾 Transactions", "value": lines.join("\n"), "inline": false }));
+        }
+
+        let color = match (increased, decreased) {
+            (true, false) => COLOR_INCREASE,
+            (false, true) => COLOR_DECREASE,
+            _ => COLOR_MIXED,
+        };
+
+        json!({
+            "title": format!("🔔 Balance Alert: {}", changes.alias),
+            "description": format!("🌐 **{}** (Chain ID: {})\n`{}`",
+                changes.network_name, changes.chain_id, self.display_address(&changes.address)),
+            "color": color,
+            "fields": fields,
+        })
+    }
+
+    /// Build embed for balance changes of one check cycle, one field per listed address
+    fn digest_embed(&self, digest: &ChangeDigest) -> Value {
+        let fields: Vec<Value> = digest.listed().iter()
+            .map(|summary| {
+                let lines: Vec<String> = summary.changed_assets()
+                    .map(|change| {
+                        let (emoji, sign) = match change.change {
+                            BalanceChange::Increase => ("📈", "+"),
+                            _ => ("📉", ""),
+                        };
+                        let diff = calculate_diff(&change.new_balance, &change.old_balance, change.decimals);
+                        format!("{} {}: {}{} | {} → {}", emoji, change.alias, sign, diff, change.old_formatted, change.new_formatted)
+                    })
+                    .chain(summary.nft_changes.iter().map(|nft| format!("🖼️ {}: {}", nft.label, nft.describe())))
+                    .collect();
+                json!({
+                    "name": format!("{} · {}", summary.alias, self.display_address(&summary.address)),
+                    "value": lines.join("\n"),
+                    "inline": false,
+                })
+            })
+            .collect();
+
+        let color = match digest.directions() {
+            (true, false) => COLOR_INCREASE,
+            (false, true) => COLOR_DECREASE,
+            _ => COLOR_MIXED,
+        };
+
+        let mut description = format!("🌐 **{}** (Chain ID: {})", digest.network_name, digest.chain_id);
+        for line in digest.summary_lines() {
+            description.push_str(&format!("\n{}", line));
+        }
+
+        json!({
+            "title": format!("🔔 Balance Alert · {} addresses", digest.len()),
+            "description": description,
+            "color": color,
+            "fields": fields,
+        })
+    }
+
+    fn low_balance_embed(&self, alert: &LowBalanceAlert) -> Value {
+        let unit = if alert.is_native { format!(" {}", alert.asset) } else { String::new() };
+        let mut fields = vec![
+            json!({ "name": alert.asset, "value": alert.formatted, "inline": true }),
+            json!({ "name": "Threshold", "value": format!("{}{}", alert.threshold, unit), "inline": true }),
+        ];
+        if alert.runway.is_some() {
+            fields.push(json!({ "name": "Runway", "value": alert.runway_text(), "inline": true }));
+        }
+        if let Some(top_up) = &alert.top_up {
+            let mut value = format!("`{}`", top_up.payment_link);
+            if !top_up.data.is_empty() {
+                value.push_str(&format!("\nCalldata: `{}`", top_up.data));
+            }
+            if let Some(url) = &top_up.safe_url {
+                value.push_str(&format!("\n[Open the Safe transaction builder]({})", url));
+            }
+            fields.push(json!({ "name": top_up.describe(), "value": value, "inline": false }));
+        }
+
+        json!({
+            "title": format!("{} Low Balance Alert #{}: {}", alert.severity.emoji(), alert.alert_number, alert.alias),
+            "description": format!("🌐 **{}** (Chain ID: {})\n`{}`\n\n🚨 **{}**",
+                alert.network_name, alert.chain_id, self.display_address(&format!("{:?}", alert.address)), alert.call_to_action()),
+            "color": COLOR_LOW_BALANCE,
+            "fields": fields,
+            "footer": { "text": alert.next_alert },
+        })
+    }
+
+    fn report_embed(&self, report: &BalanceReport) -> Value {
+        let mut fields = Vec::new();
+
+        for summary in &report.changes {
+            let lines: Vec<String> = summary.changed_assets()
+                .map(|change| {
+                    let (emoji, sign) = match change.change {
+                        BalanceChange::Increase => ("📈", "+"),
+                        _ => ("📉", ""),
+                    };
+                    let diff = calculate_diff(&change.new_balance, &change.old_balance, change.decimals);
+                    format!("{} {}: {}{} | {} → {}", emoji, change.alias, sign, diff, change.old_formatted, change.new_formatted)
+                })
+                .chain(summary.nft_changes.iter().map(|nft| format!("🖼️ {}: {}", nft.label, nft.describe())))
+                .collect();
+
+            fields.push(json!({
+                "name": format!("{} | {}", summary.network_name, summary.alias),
+                "value": lines.join("\n"),
+                "inline": false,
+            }));
+        }
+
+        if !report.portfolio.is_empty() {
+            fields.push(json!({
+                "name": "💰 Portfolio total",
+                "value": report.portfolio.total.describe(),
+                "inline": false,
+            }));
+        }
+        if !report.failovers.is_empty() {
+            let lines: Vec<String> = report.failovers.iter().map(|node| format!("{}: {}", node.network_name, node.describe())).collect();
+            fields.push(json!({
+                "name": "🔌 RPC failovers",
+                "value": lines.join("\n"),
+                "inline": false,
+            }));
+        }
+        if !report.runways.is_empty() {
+            let lines: Vec<String> = report.runways.iter().map(|runway| format!("{}: {}", runway.network_name, runway.describe())).collect();
+            fields.push(json!({
+                "name": "⏳ Runway",
+                "value": lines.join("\n"),
+                "inline": false,
+            }));
+        }
+
+        let description = if !report.has_data() {
+            "No balance data available yet.".to_string()
+        } else if report.changes.is_empty() {
+            "✅ No balance changes detected in the last period.".to_string()
+        } else {
+            format!("📈 **Total changes:** {}", report.total_changes())
+        };
+
+        json!({
+            "title": "📊 Daily Balance Report",
+            "description": description,
+            "color": COLOR_REPORT,
+            "fields": fields,
+            "timestamp": report.generated_at.to_rfc3339(),
+        })
+    }
+}
+
+/// Cut an embed down to Discord's limits. Texts are cut at a line break where possible,
+/// fields past the 25th are replaced by a count of the hidden ones, and if the embed is
+/// still over 6000 characters in total its last fields are hidden and then its
+/// description is shortened.
+pub fn fit_embed(mut embed: Value) -> Value {
+    let Some(object) = embed.as_object_mut() else {
+        return embed;
+    };
+    fit_text(object.get_mut("title"), MAX_TITLE_LEN);
+    fit_text(object.get_mut("description"), MAX_DESCRIPTION_LEN);
+    if let Some(footer) = object.get_mut("footer") {
+        fit_text(footer.get_mut("text"), MAX_FOOTER_LEN);
+    }
+
+    let mut fields = match object.remove("fields") {
+        Some(Value::Array(fields)) => fields,
+        _ => Vec::new(),
+    };
+    for field in &mut fields {
+        fit_text(field.get_mut("name"), MAX_FIELD_NAME_LEN);
+        fit_text(field.get_mut("value"), MAX_FIELD_VALUE_LEN);
+    }
+    let mut hidden = 0;
+    if fields.len() > MAX_FIELDS {
+        hidden = fields.len() - (MAX_FIELDS - 1);
+        fields.truncate(MAX_FIELDS - 1);
+    }
+    let texts_len = ["title", "description"].iter().map(|key| text_len(object.get(*key))).sum::<usize>()
+        + text_len(object.get("footer").and_then(|footer| footer.get("text")));
+    loop {
+        let more = (hidden > 0).then(|| json!({ "name": "…", "value": format!("…and {} more", hidden), "inline": false }));
+        let len = texts_len + fields.iter().chain(&more).map(field_len).sum::<usize>();
+        if len <= MAX_EMBED_LEN || fields.is_empty() {
+            fields.extend(more);
+            if len > MAX_EMBED_LEN {
+                let description = text_len(object.get("description"));
+                fit_text(object.get_mut("description"), description.saturating_sub(len - MAX_EMBED_LEN));
+            }
+            break;
+        }
+        fields.pop();
+        hidden += 1;
+    }
+    if !fields.is_empty() {
+        object.insert("fields".to_string(), Value::Array(fields));
+    }
+    embed
+}
+
+/// Cut a text value to at most `limit` characters, marking the cut with `…`
+fn fit_text(value: Option<&mut Value>, limit: usize) {
+    if let Some(Value::String(text)) = value {
+        if text.chars().count() > limit {
+            *text = truncate(text, limit);
+        }
+    }
+}
+
+/// First `limit` characters of `text` ending in `…`, cut at the last line break in them if any
+fn truncate(text: &str, limit: usize) -> String {
+    if limit == 0 {
+        return String::new();
+    }
+    let end = text.char_indices().nth(limit - 1).map_or(text.len(), |(i, _)| i);
+    let kept = &text[..end];
+    let kept = match kept.rfind('\n') {
+        Some(i) if i > 0 => &kept[..i],
+        _ => kept,
+    };
+    format!("{}…", kept)
+}
+
+fn text_len(value: Option<&Value>) -> usize {
+    value.and_then(Value::as_str).map_or(0, |text| text.chars().count())
+}
+
+fn field_len(field: &Value) -> usize {
+    text_len(field.get("name")) + text_len(field.get("value"))
+}
+
+#[async_trait]
+impl Notifier for DiscordNotifier {
+    fn name(&self) -> &str {
+        "Discord"
+    }
+
+    fn sends(&self, subject: &AuditSubject) -> bool {
+        self.alerts.allows(&subject.kind)
+    }
+
+    async fn send_change_alert(&self, changes: &BalanceChangeSummary) -> Result<()> {
+        if !self.alerts.balance_change || !changes.has_changes() {
+            return Ok(());
+        }
+        let target = self.target_for(&changes.network_name);
+        self.send_embed(target, self.change_embed(changes)).await
+    }
+
+    async fn send_change_digest(&self, digest: &ChangeDigest) -> Result<()> {
+        if !self.alerts.balance_change || digest.is_empty() {
+            return Ok(());
+        }
+        let target = self.target_for(&digest.network_name);
+        self.send_embed(target, self.digest_embed(digest)).await
+    }
+
+    async fn send_low_balance_alert(&self, alert: &LowBalanceAlert) -> Result<()> {
+        if !self.alerts.low_balance {
+            return Ok(());
+        }
+        let target = self.target_for(&alert.network_name);
+        self.send_embed(target, self.low_balance_embed(alert)).await
+    }
+
+    async fn send_alert(&self, alert: &MonitorAlert) -> Result<()> {
+        let embed = json!({
+            "title": format!("🔔 {}", alert.title),
+            "description": format!("🌐 **{}**{}\n\n{}", alert.network_name, alert.chain_suffix(), alert.lines.join("\n")),
+            "color": COLOR_ALERT,
+        });
+        self.send_embed(self.target_for(&alert.network_name), embed).await
+    }
+
+    async fn send_report(&self, report: &BalanceReport) -> Result<()> {
+        self.send_embed(&self.default_target, self.report_embed(report)).await
+    }
+}
@@ -0,0 +1,67 @@
+use super::{AuditSubject, BalanceReport, ChangeDigest, LowBalanceAlert, MonitorAlert, Notifier};
+use crate::logger::BalanceChangeSummary;
+use async_trait::async_trait;
+use eyre::Result;
+use tracing::info;
+
+/// Channel of a dry run: logs what `inner` would send instead of sending it. The
+/// channel keeps its name and alert settings, so routing and filtering work as in a
+/// real run.
+pub struct DryRunNotifier {
+    inner: Box<dyn Notifier>,
+}
+
+impl DryRunNotifier {
+    pub fn new(inner: Box<dyn Notifier>) -> Self {
+        Self { inner }
+    }
+
+    fn log(&self, subject: &AuditSubject, what: &str) {
+        if self.inner.sends(subject) {
+            info!(channel = self.inner.name(), "🧪 Dry run, not sent: {}", what);
+        }
+    }
+}
+
+#[async_trait]
+impl Notifier for DryRunNotifier {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    async fn send_change_alert(&self, changes: &BalanceChangeSummary) -> Result<()> {
+        if changes.has_changes() {
+            self.log(&AuditSubject::change(changes), &format!("balance change of {} on {}", changes.alias, changes.network_name));
+        }
+        Ok(())
+    }
+
+    async fn send_change_digest(&self, digest: &ChangeDigest) -> Result<()> {
+        self.log(&AuditSubject::digest(digest), &format!("{} balance changes on {}", digest.len(), digest.network_name));
+        Ok(())
+    }
+
+    async fn send_low_balance_alert(&self, alert: &LowBalanceAlert) -> Result<()> {
+        self.log(&AuditSubject::low_balance(alert), &format!(
+            "{} low balance alert #{}: {} {} {} below {}",
+            alert.severity.emoji(), alert.alert_number, alert.alias, alert.formatted, alert.asset, alert.threshold
+        ));
+        Ok(())
+    }
+
+    async fn send_alert(&self, alert: &MonitorAlert) -> Result<()> {
+        self.log(&AuditSubject::alert(alert), &format!("{} ({})", alert.title, alert.network_name));
+        Ok(())
+    }
+
+    async fn send_report(&self, report: &BalanceReport) -> Result<()> {
+        self.log(&AuditSubject::report(report), &format!(
+            "report of {} addresses, {} changed", report.address_count, report.changes.len()
+        ));
+        Ok(())
+    }
+
+    fn sends(&self, subject: &AuditSubject) -> bool {
+        self.inner.sends(subject)
+    }
+}
@@ -0,0 +1,199 @@
+use super::{MonitorAlert, PagerDutyClient};
+use crate::config::EscalationConfig;
+use crate::storage::{Acknowledgement, EscalationStorage, StateBackend, TrackedAlert};
+use std::sync::Arc;
+use tokio::sync::{RwLock, RwLockWriteGuard};
+use tracing::warn;
+
+/// Prefix of the Acknowledge button's callback data, followed by the alert ID
+pub const ACK_CALLBACK_PREFIX: &str = "ack:";
+
+/// Tracked alerts are forgotten a week after they were raised
+const RETENTION_SECS: u64 = 7 * 24 * 3600;
+
+/// Action due for a critical alert nobody acknowledged in time
+#[derive(Debug, Clone)]
+pub enum EscalationStep {
+    /// Send the alert to its original channels again
+    Resend(TrackedAlert),
+    /// Send the alert to the escalation targets
+    Escalate(TrackedAlert),
+}
+
+impl EscalationStep {
+    /// Alert to send for this step, with the step in its title
+    pub fn alert(&self, now: u64) -> MonitorAlert {
+        let (prefix, tracked) = match self {
+            Self::Resend(tracked) => ("🔁 Unacknowledged", tracked),
+            Self::Escalate(tracked) => ("📟 Escalated", tracked),
+        };
+        let mut alert = tracked.alert.clone();
+        alert.title = format!("{}: {}", prefix, alert.title);
+        alert.lines.push(format!("Raised {} min ago, not acknowledged", now.saturating_sub(tracked.raised_at) / 60));
+        alert.dedup_id = Some(format!("#{} {}", tracked.id, tracked.resends));
+        alert
+    }
+
+    pub fn tracked(&self) -> &TrackedAlert {
+        match self {
+            Self::Resend(tracked) | Self::Escalate(tracked) => tracked,
+        }
+    }
+}
+
+/// Result of pressing Acknowledge
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AckOutcome {
+    Acknowledged,
+    AlreadyAcknowledged(Acknowledgement),
+    /// Alert is unknown or was forgotten after a week
+    Unknown,
+}
+
+/// Tracks critical alerts until they're acknowledged. Unacknowledged alerts are re-sent
+/// every `ack_timeout_mins` up to `resends` times, then escalated once.
+///
+/// The backend holds the state: it's read before every change and each tracked alert is
+/// written on its own, so instances sharing the backend see each other's alerts and
+/// acknowledgements.
+pub struct Escalator {
+    ack_timeout_secs: u64,
+    resends: u32,
+    pagerduty: Option<PagerDutyClient>,
+    backend: Arc<dyn StateBackend>,
+    /// State last read from the backend, used while it can't be read
+    state: RwLock<EscalationStorage>,
+}
+
+impl Escalator {
+    /// Create escalator, restoring alert state from the backend
+    pub async fn new(config: &EscalationConfig, backend: Arc<dyn StateBackend>) -> Self {
+        let state = match backend.load_escalations().await {
+            Ok(state) => state,
+            Err(e) => {
+                warn!("Failed to load escalation state from {} backend: {}", backend.name(), e);
+                EscalationStorage::new()
+            }
+        };
+
+        Self {
+            ack_timeout_secs: config.ack_timeout_mins * 60,
+            resends: config.resends,
+            pagerduty: config.pagerduty.as_ref().map(PagerDutyClient::new),
+            backend,
+            state: RwLock::new(state),
+        }
+    }
+
+    pub fn pagerduty(&self) -> Option<&PagerDutyClient> {
+        self.pagerduty.as_ref()
+    }
+
+    /// Current state from the backend, or the last known one if it can't be read. The
+    /// guard keeps other tasks of this instance from changing it meanwhile.
+    async fn load(&self) -> RwLockWriteGuard<'_, EscalationStorage> {
+        let mut state = self.state.write().await;
+        match self.backend.load_escalations().await {
+            Ok(loaded) => *state = loaded,
+            Err(e) => warn!("Failed to load escalation state from {} backend: {}", self.backend.name(), e),
+        }
+        state
+    }
+
+    /// Persist one tracked alert; failures are logged and never block alerting
+    async fn save(&self, tracked: &TrackedAlert) {
+        if let Err(e) = self.backend.save_escalation(tracked).await {
+            warn!("Failed to save alert #{} to {} backend: {}", tracked.id, self.backend.name(), e);
+        }
+    }
+
+    /// Start tracking a critical alert sent at `now`; returns the ID for its Acknowledge button
+    pub async fn open(&self, alert: &MonitorAlert, channels: &[String], now: u64) -> u64 {
+        let mut state = self.state.write().await;
+        let id = match self.backend.next_escalation_id().await {
+            Ok(id) => id,
+            Err(e) => {
+                warn!("Failed to take an escalation ID from {} backend: {}", self.backend.name(), e);
+                state.next_id + 1
+            }
+        };
+        state.next_id = state.next_id.max(id);
+        let tracked = TrackedAlert {
+            id,
+            alert: alert.clone(),
+            channels: channels.to_vec(),
+            raised_at: now,
+            last_sent: now,
+            resends: 0,
+            escalated_at: None,
+            acknowledged: None,
+        };
+        self.save(&tracked).await;
+        state.alerts.push(tracked);
+        id
+    }
+
+    /// Acknowledge an alert, and its PagerDuty incident if it was escalated there
+    pub async fn acknowledge(&self, id: u64, by: &str, now: u64) -> AckOutcome {
+        let mut state = self.load().await;
+        let Some(tracked) = state.get_mut(id) else {
+            return AckOutcome::Unknown;
+        };
+        if let Some(acknowledgement) = &tracked.acknowledged {
+            return AckOutcome::AlreadyAcknowledged(acknowledgement.clone());
+        }
+        tracked.acknowledged = Some(Acknowledgement { by: by.to_string(), at: now });
+        let tracked = tracked.clone();
+        self.save(&tracked).await;
+        drop(state);
+
+        if let (Some(pagerduty), Some(_)) = (&self.pagerduty, tracked.escalated_at) {
+            if let Err(e) = pagerduty.acknowledge(&tracked).await {
+                warn!("Failed to acknowledge PagerDuty incident for alert #{}: {}", tracked.id, e);
+            }
+        }
+        AckOutcome::Acknowledged
+    }
+
+    /// Re-sends and escalations due at `now`, recorded as done. Alerts raised more than
+    /// a week ago are dropped.
+    pub async fn due(&self, now: u64) -> Vec<EscalationStep> {
+        let mut state = self.load().await;
+        let expired: Vec<u64> = state.alerts.iter()
+            .filter(|tracked| now.saturating_sub(tracked.raised_at) >= RETENTION_SECS)
+            .map(|tracked| tracked.id)
+            .collect();
+        if !expired.is_empty() {
+            state.alerts.retain(|tracked| !expired.contains(&tracked.id));
+            if let Err(e) = self.backend.delete_escalations(&expired).await {
+                warn!("Failed to delete expired alerts from {} backend: {}", self.backend.name(), e);
+            }
+        }
+
+        let mut steps = Vec::new();
+        for tracked in state.alerts.iter_mut() {
+            if !tracked.is_open() || now < tracked.last_sent + self.ack_timeout_secs {
+                continue;
+            }
+            tracked.last_sent = now;
+            if tracked.resends < self.resends {
+                tracked.resends += 1;
+                steps.push(EscalationStep::Resend(tracked.clone()));
+            } else {
+                tracked.escalated_at = Some(now);
+                steps.push(EscalationStep::Escalate(tracked.clone()));
+            }
+        }
+
+        for step in &steps {
+            self.save(step.tracked()).await;
+        }
+        steps
+    }
+
+    /// Alerts nobody acknowledged yet, oldest first
+    pub async fn unacknowledged(&self) -> Vec<TrackedAlert> {
+        let state = self.load().await;
+        state.alerts.iter().filter(|tracked| tracked.acknowledged.is_none()).cloned().collect()
+    }
+}
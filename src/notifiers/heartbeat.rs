@@ -0,0 +1,117 @@
+use super::{MonitorAlert, Notifier, NotifierSet};
+use crate::config::HeartbeatConfig;
+use crate::monitoring::{HealthTracker, NetworkHealth};
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::{debug, info, warn};
+
+/// Alert kind of heartbeat messages. Heartbeats reach every chat regardless of
+/// subscriptions, since they are about the watcher rather than a network.
+pub const HEARTBEAT_KIND: &str = "heartbeat";
+
+/// Summary of watcher health over one heartbeat period
+#[derive(Debug, Clone)]
+pub struct Heartbeat {
+    pub networks_total: usize,
+    pub networks_ok: usize,
+    /// Networks whose latest check failed or that never had a successful check
+    pub failing: Vec<String>,
+    /// Checks that hit an RPC or API error during the period
+    pub errors: u64,
+    pub period_hours: u64,
+    pub uptime_secs: u64,
+}
+
+impl Heartbeat {
+    /// Summarize network health; `previous_errors` holds error counts at the start of the period
+    pub fn build(
+        networks: &[NetworkHealth],
+        previous_errors: &HashMap<String, u64>,
+        period_hours: u64,
+        uptime_secs: u64,
+    ) -> Self {
+        let errors = networks
+            .iter()
+            .map(|n| n.error_count.saturating_sub(previous_errors.get(&n.network_name).copied().unwrap_or(0)))
+            .sum();
+
+        Self {
+            networks_total: networks.len(),
+            networks_ok: networks.iter().filter(|n| n.is_ok()).count(),
+            failing: networks.iter().filter(|n| !n.is_ok()).map(|n| n.network_name.clone()).collect(),
+            errors,
+            period_hours,
+            uptime_secs,
+        }
+    }
+
+    pub fn to_alert(&self) -> MonitorAlert {
+        let title = if self.failing.is_empty() {
+            format!("✅ Watcher alive, {} network(s) OK", self.networks_ok)
+        } else {
+            format!("⚠️ Watcher alive, {} of {} network(s) failing", self.failing.len(), self.networks_total)
+        };
+
+        let mut lines = vec![
+            format!("Networks OK: {}/{}", self.networks_ok, self.networks_total),
+            format!("RPC errors in last {}h: {}", self.period_hours, self.errors),
+            format!("Uptime: {}h {}m", self.uptime_secs / 3600, self.uptime_secs % 3600 / 60),
+        ];
+        if !self.failing.is_empty() {
+            lines.push(format!("Failing: {}", self.failing.join(", ")));
+        }
+
+        MonitorAlert {
+            kind: HEARTBEAT_KIND.to_string(),
+            network_name: "All networks".to_string(),
+            chain_id: None,
+            title,
+            lines,
+            data: json!({
+                "networks_total": self.networks_total,
+                "networks_ok": self.networks_ok,
+                "failing": self.failing,
+                "errors": self.errors,
+                "period_hours": self.period_hours,
+                "uptime_secs": self.uptime_secs,
+            }),
+            dedup_id: None,
+        }
+    }
+}
+
+/// Send a heartbeat every `interval_hours`, and/or request the ping URL
+pub fn spawn_heartbeat(config: HeartbeatConfig, notifiers: Arc<NotifierSet>, health: Arc<HealthTracker>) {
+    let started_at = Instant::now();
+    let client = reqwest::Client::new();
+
+    tokio::spawn(async move {
+        let interval = Duration::from_secs(config.interval_hours * 3600);
+        let mut previous_errors: HashMap<String, u64> = HashMap::new();
+        info!("💓 Heartbeat every {} hour(s)", config.interval_hours);
+
+        loop {
+            tokio::time::sleep(interval).await;
+
+            let networks = health.all().await;
+            let heartbeat = Heartbeat::build(&networks, &previous_errors, config.interval_hours, started_at.elapsed().as_secs());
+            previous_errors = networks.iter().map(|n| (n.network_name.clone(), n.error_count)).collect();
+
+            if config.notify {
+                if let Err(e) = notifiers.send_alert(&heartbeat.to_alert()).await {
+                    warn!("Failed to send heartbeat: {}", e);
+                }
+            }
+
+            if let Some(url) = &config.ping_url {
+                match client.get(url.clone()).timeout(Duration::from_secs(10)).send().await {
+                    Ok(response) if response.status().is_success() => debug!("Heartbeat ping delivered"),
+                    Ok(response) => warn!("Heartbeat ping returned {}", response.status()),
+                    Err(e) => warn!("Heartbeat ping failed: {}", e),
+                }
+            }
+        }
+    });
+}
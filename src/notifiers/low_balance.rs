@@ -0,0 +1,240 @@
+use super::{render_template, TopUp};
+use crate::config::{AlertSeverity, ThresholdTier, ThrottleConfig};
+use crate::monitoring::{to_units, BalanceInfo, Runway};
+use crate::storage::{AlertStateStorage, StateBackend};
+use alloy::primitives::{Address, U256};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tracing::warn;
+
+/// Low balance alert ready to be delivered
+#[derive(Debug, Clone)]
+pub struct LowBalanceAlert {
+    pub network_name: String,
+    pub chain_id: u64,
+    pub alias: String,
+    pub address: Address,
+    /// Asset name (native symbol such as "ETH", or token alias)
+    pub asset: String,
+    /// True for the native currency, false for tokens
+    pub is_native: bool,
+    /// Current formatted balance
+    pub formatted: String,
+    /// Current balance value in USD, if priced
+    pub usd_value: Option<f64>,
+    pub threshold: f64,
+    /// Severity of the threshold tier
+    pub severity: AlertSeverity,
+    /// Channels that receive the alert, empty for all
+    pub channels: Vec<String>,
+    /// Custom message of the threshold tier, with placeholders filled in
+    pub message: Option<String>,
+    /// Alert number in the current low balance streak (starts at 1)
+    pub alert_number: u32,
+    /// Human-readable hint about when the next alert will be sent
+    pub next_alert: String,
+    /// Time until the next lower threshold, or until empty, at the recent spend rate
+    pub runway: Option<Runway>,
+    /// Suggested transfer back to the configured top-up target
+    pub top_up: Option<TopUp>,
+}
+
+impl LowBalanceAlert {
+    /// Fill in {alias}, {network}, {asset}, {balance} and {threshold}
+    pub fn render(&self, template: &str) -> String {
+        render_template(template, &[
+            ("alias", self.alias.clone()),
+            ("network", self.network_name.clone()),
+            ("asset", self.asset.clone()),
+            ("balance", self.formatted.clone()),
+            ("threshold", self.threshold.to_string()),
+        ])
+    }
+
+    /// Call to action shown in the alert
+    pub fn call_to_action(&self) -> &str {
+        self.message.as_deref().unwrap_or("Please top up your balance!")
+    }
+
+    /// E.g. "~3.2 days of runway left", or "~3.2 days of runway left until 1 ETH" while
+    /// a lower threshold remains; empty without a forecast
+    pub fn runway_text(&self) -> String {
+        let Some(runway) = &self.runway else { return String::new() };
+        let unit = if self.is_native { format!(" {}", self.asset) } else { String::new() };
+        match runway.floor {
+            floor if floor > 0.0 => format!("{} of runway left until {}{}", runway.describe(), floor, unit),
+            _ => format!("{} of runway left", runway.describe()),
+        }
+    }
+}
+
+/// Address in a low balance streak
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ThrottleStatus {
+    /// "network:alias"
+    pub key: String,
+    /// Alerts sent in the current streak
+    pub alert_count: u32,
+    /// Unix timestamp in seconds
+    pub last_sent: u64,
+    /// When the next alert may be sent (Unix timestamp in seconds)
+    pub next_alert_at: u64,
+}
+
+/// Evaluates low balance thresholds and throttles repeated alerts
+#[derive(Clone)]
+pub struct LowBalanceTracker {
+    state: Arc<RwLock<AlertStateStorage>>,
+    schedule: ThrottleConfig,
+    backend: Arc<dyn StateBackend>,
+}
+
+impl LowBalanceTracker {
+    pub async fn new(backend: Arc<dyn StateBackend>) -> Self {
+        let state = backend.load_alert_states().await.unwrap_or_else(|e| {
+            warn!("Failed to load alert state: {}", e);
+            AlertStateStorage::new()
+        });
+
+        Self {
+            state: Arc::new(RwLock::new(state)),
+            schedule: ThrottleConfig::default(),
+            backend,
+        }
+    }
+
+    /// Use a custom schedule of repeated alerts
+    pub fn with_schedule(mut self, schedule: ThrottleConfig) -> Self {
+        self.schedule = schedule;
+        self
+    }
+
+    pub fn schedule(&self) -> &ThrottleConfig {
+        &self.schedule
+    }
+
+    /// Addresses in a low balance streak, sorted by key
+    pub async fn throttled(&self) -> Vec<ThrottleStatus> {
+        let state = self.state.read().await;
+        let mut throttled: Vec<ThrottleStatus> = state.states.iter()
+            .filter(|(_, state)| state.alert_count > 0)
+            .map(|(key, state)| ThrottleStatus {
+                key: key.clone(),
+                alert_count: state.alert_count,
+                last_sent: state.last_sent,
+                next_alert_at: state.next_alert_at(&self.schedule),
+            })
+            .collect();
+        throttled.sort_by(|a, b| a.key.cmp(&b.key));
+        throttled
+    }
+
+    /// Check balance against thresholds and return alerts that should be sent now.
+    /// Alerts are counted as sent when returned.
+    pub async fn check(
+        &self,
+        balance: &BalanceInfo,
+        min_eth_threshold: Option<f64>,
+        token_thresholds: &HashMap<String, f64>,
+    ) -> Vec<LowBalanceAlert> {
+        let native: Vec<ThresholdTier> = min_eth_threshold.map(ThresholdTier::below).into_iter().collect();
+        let tokens = token_thresholds.iter()
+            .map(|(alias, &threshold)| (alias.clone(), vec![ThresholdTier::below(threshold)]))
+            .collect();
+        self.check_tiers(balance, &native, &tokens).await
+    }
+
+    /// Check balance against threshold tiers and return alerts that should be sent now.
+    /// Each asset alerts with the most severe tier it is below. Reaching a more severe
+    /// tier than alerted so far bypasses throttling and starts a new streak.
+    pub async fn check_tiers(
+        &self,
+        balance: &BalanceInfo,
+        native_tiers: &[ThresholdTier],
+        token_tiers: &HashMap<String, Vec<ThresholdTier>>,
+    ) -> Vec<LowBalanceAlert> {
+        let mut alert_storage = self.state.write().await;
+        let alert_state = alert_storage.get_or_create(&balance.network_name, &balance.alias);
+
+        // Check ETH balance
+        let eth_tier = breached_tier(native_tiers, balance.eth_balance, balance.native_decimals);
+
+        // Check token balances
+        let low_tokens: Vec<_> = balance.token_balances.iter()
+            .filter_map(|token| {
+                let tiers = token_tiers.get(&token.alias)?;
+                Some((token, breached_tier(tiers, token.balance, token.decimals)?))
+            })
+            .collect();
+
+        // If balance is back to normal, reset alert state
+        let Some(severity) = eth_tier.iter().chain(low_tokens.iter().map(|(_, tier)| tier)).map(|tier| tier.severity).max() else {
+            if alert_state.alert_count > 0 {
+                alert_state.reset();
+                if let Err(e) = self.backend.save_alert_states(&alert_storage).await {
+                    warn!("Failed to save alert state: {}", e);
+                }
+            }
+            return Vec::new();
+        };
+
+        // A more severe tier alerts right away, otherwise follow the throttling schedule
+        if alert_state.severity.is_some_and(|alerted| severity > alerted) {
+            alert_state.reset();
+        } else if !alert_state.should_send_alert(&self.schedule) {
+            return Vec::new(); // Too soon to send another alert
+        }
+
+        let alert_number = alert_state.alert_count + 1;
+        let next_alert = alert_state.next_alert_hint(&self.schedule);
+        let alert = |asset: &str, is_native: bool, formatted: &str, usd_value: Option<f64>, tier: &ThresholdTier| {
+            let mut alert = LowBalanceAlert {
+                network_name: balance.network_name.clone(),
+                chain_id: balance.chain_id,
+                alias: balance.alias.clone(),
+                address: balance.address,
+                asset: asset.to_string(),
+                is_native,
+                formatted: formatted.to_string(),
+                usd_value,
+                threshold: tier.below,
+                severity: tier.severity,
+                channels: tier.channels.clone(),
+                message: None,
+                alert_number,
+                next_alert: next_alert.clone(),
+                runway: None,
+                top_up: None,
+            };
+            alert.message = tier.message.as_deref().map(|template| alert.render(template));
+            alert
+        };
+
+        let mut alerts = Vec::new();
+        if let Some(tier) = eth_tier {
+            alerts.push(alert(&balance.native_symbol, true, &balance.eth_formatted, balance.eth_usd_value, tier));
+        }
+        for (token, tier) in low_tokens {
+            alerts.push(alert(&token.alias, false, &token.formatted, token.usd_value, tier));
+        }
+
+        // Record that alert was sent
+        alert_state.record_alert_sent();
+        alert_state.severity = Some(severity);
+        if let Err(e) = self.backend.save_alert_states(&alert_storage).await {
+            warn!("Failed to save alert state: {}", e);
+        }
+
+        alerts
+    }
+}
+
+/// Most severe tier a raw balance with `decimals` decimals is below, the lowest threshold
+/// among equally severe ones
+fn breached_tier(tiers: &[ThresholdTier], balance: U256, decimals: u8) -> Option<&ThresholdTier> {
+    tiers.iter()
+        .filter(|tier| balance < to_units(tier.below, decimals))
+        .max_by(|a, b| a.severity.cmp(&b.severity).then(b.below.total_cmp(&a.below)))
+}
@@ -0,0 +1,333 @@
+use super::{AuditSubject, BalanceReport, ChangeDigest, LowBalanceAlert, MonitorAlert, Notifier};
+use crate::config::{AlertSettings, MatrixConfig};
+use crate::http::http_client;
+use crate::logger::{calculate_diff, calculate_percent_change, shorten_address, BalanceChange, BalanceChangeSummary};
+use async_trait::async_trait;
+use eyre::Result;
+use reqwest::{Client, Url};
+use serde_json::json;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::warn;
+
+/// Matrix notifier posting HTML messages to rooms through the client-server API
+pub struct MatrixNotifier {
+    client: Client,
+    homeserver_url: Url,
+    access_token: String,
+    rooms: Vec<String>,
+    alerts: AlertSettings,
+    show_full_address: bool,
+    /// Makes transaction IDs unique within this process
+    next_txn: AtomicU64,
+}
+
+impl MatrixNotifier {
+    pub fn new(config: &MatrixConfig) -> Self {
+        Self {
+            client: http_client(),
+            homeserver_url: config.homeserver_url.clone(),
+            access_token: config.access_token.clone(),
+            rooms: config.rooms.clone(),
+            alerts: config.alerts.clone(),
+            show_full_address: config.show_full_address,
+            next_txn: AtomicU64::new(0),
+        }
+    }
+
+    /// Endpoint sending a message event to a room. The transaction ID lets the homeserver
+    /// drop duplicates if a request is retried.
+    pub fn send_url(&self, room: &str, txn_id: &str) -> Result<Url> {
+        let mut url = self.homeserver_url.clone();
+        url.path_segments_mut()
+            .map_err(|_| eyre::eyre!("Matrix homeserver_url {} is not an http(s) URL", self.homeserver_url))?
+            .pop_if_empty()
+            .extend(["_matrix", "client", "v3", "rooms", room, "send", "m.room.message", txn_id]);
+        Ok(url)
+    }
+
+    fn txn_id(&self) -> String {
+        let millis = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis();
+        format!("oxwatcher-{}-{}", millis, self.next_txn.fetch_add(1, Ordering::Relaxed))
+    }
+
+    /// Send an HTML message to every room; fails if any room couldn't be reached
+    async fn post(&self, html: &str) -> Result<()> {
+        let content = json!({
+            "msgtype": "m.text",
+            "body": plain_text(html),
+            "format": "org.matrix.custom.html",
+            "formatted_body": html,
+        });
+
+        let mut failed = 0;
+        for room in &self.rooms {
+            if let Err(e) = self.post_to_room(room, &content).await {
+                warn!("Failed to send Matrix message to {}: {}", room, e);
+                failed += 1;
+            }
+        }
+        if failed > 0 {
+            eyre::bail!("{} of {} Matrix room(s) failed", failed, self.rooms.len());
+        }
+        Ok(())
+    }
+
+    async fn post_to_room(&self, room: &str, content: &serde_json::Value) -> Result<()> {
+        let response = self.client
+            .put(self.send_url(room, &self.txn_id())?)
+            .bearer_auth(&self.access_token)
+            .json(content)
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            eyre::bail!("Matrix homeserver returned {}: {}", status, body);
+        }
+        Ok(())
+    }
+
+    fn display_address(&self, address: &str) -> String {
+        if self.show_full_address {
+            address.to_string()
+        } else {
+            shorten_address(address)
+        }
+    }
+
+    /// Format change message in Matrix HTML
+    fn format_change_message(&self, changes: &BalanceChangeSummary) -> String {
+        let mut message = String::from("🔔 <b>Balance Alert</b><br><br>");
+        message.push_str(&format!("🌐 <b>{}</b> (Chain ID: {})<br>", escape(&changes.network_name), changes.chain_id));
+        message.push_str(&format!("📍 <b>{}</b><br>", escape(&changes.alias)));
+        message.push_str(&format!("<code>{}</code><br><br>", self.display_address(&changes.address)));
+
+        for change in changes.changed_assets() {
+            let (emoji, sign) = match change.change {
+                BalanceChange::Increase => ("📈", "+"),
+                _ => ("📉", ""),
+            };
+            let diff = calculate_diff(&change.new_balance, &change.old_balance, change.decimals);
+            let percent = calculate_percent_change(&change.new_balance, &change.old_balance);
+
+            message.push_str(&format!("💰 <b>{}</b><br>", escape(&change.alias)));
+            if percent.abs() >= 0.01 {
+                message.push_str(&format!("{} <b>{}{}</b> ({:+.2}%)<br>", emoji, sign, diff, percent));
+            } else {
+                message.push_str(&format!("{} <b>{}{}</b><br>", emoji, sign, diff));
+            }
+            message.push_str(&format!("{} → {}<br><br>", change.old_formatted, change.new_formatted));
+        }
+
+        for nft in &changes.nft_changes {
+            let emoji = if nft.new_count > nft.old_count { "📥" } else { "📤" };
+            message.push_str(&format!("🖼️ <b>{}</b><br>{} {}<br><br>", escape(&nft.label), emoji, nft.describe()));
+        }
+
+        if !changes.transactions.is_empty() {
+            message.push_str("🧾 <b>Transactions</b><br>");
+            for tx in &changes.transactions {
+                let counterparty = tx.counterparty
+                    .map(|address| self.display_address(&format!("{:?}", address)))
+                    .unwrap_or_else(|| "contract creation".to_string());
+                let hash = shorten_address(&format!("{:?}", tx.tx_hash));
+                let hash = match &tx.url {
+                    Some(url) => format!("<a href=\"{}\">{}</a>", escape(url.as_str()), hash),
+                    None => format!("<code>{}</code>", hash),
+                };
+                message.push_str(&format!("• {} {} <code>{}</code> · {}<br>",
+                    escape(&tx.asset), tx.preposition(), counterparty, hash));
+            }
+        }
+
+        message
+    }
+
+    /// Format balance changes of one check cycle in Matrix HTML
+    fn format_digest_message(&self, digest: &ChangeDigest) -> String {
+        let mut message = format!("🔔 <b>Balance Alert</b> · {} addresses<br><br>", digest.len());
+        message.push_str(&format!("🌐 <b>{}</b> (Chain ID: {})<br><br>", escape(&digest.network_name), digest.chain_id));
+
+        for summary in digest.listed() {
+            message.push_str(&format!("📍 <b>{}</b> <code>{}</code><br>",
+                escape(&summary.alias), self.display_address(&summary.address)));
+            message.push_str(&self.format_asset_lines(summary));
+            message.push_str("<br>");
+        }
+
+        for line in digest.summary_lines() {
+            message.push_str(&format!("{}<br>", escape(&line)));
+        }
+        message
+    }
+
+    /// One line per changed asset and NFT collection, as in digests and reports
+    fn format_asset_lines(&self, summary: &BalanceChangeSummary) -> String {
+        let mut lines = String::new();
+        for change in summary.changed_assets() {
+            let (emoji, sign) = match change.change {
+                BalanceChange::Increase => ("📈", "+"),
+                _ => ("📉", ""),
+            };
+            let diff = calculate_diff(&change.new_balance, &change.old_balance, change.decimals);
+            lines.push_str(&format!("&nbsp;&nbsp;{} {}: {}{} | {} → {}<br>",
+                emoji, escape(&change.alias), sign, diff, change.old_formatted, change.new_formatted));
+        }
+        for nft in &summary.nft_changes {
+            lines.push_str(&format!("&nbsp;&nbsp;🖼️ {}: {}<br>", escape(&nft.label), nft.describe()));
+        }
+        lines
+    }
+
+    /// Format low balance alert in Matrix HTML
+    fn format_low_balance_message(&self, alert: &LowBalanceAlert) -> String {
+        let unit = if alert.is_native { format!(" {}", escape(&alert.asset)) } else { String::new() };
+        let runway = match alert.runway_text() {
+            text if text.is_empty() => text,
+            text => format!("⏳ {}<br>", escape(&text)),
+        };
+
+        format!("{} <b>LOW BALANCE ALERT #{}</b><br><br>\
+                 🌐 <b>{}</b> (Chain ID: {})<br>\
+                 📍 <b>{}</b><br>\
+                 <code>{}</code><br><br>\
+                 💰 {}: <b>{}</b><br>\
+                 📉 Below threshold: <b>{}</b>{}<br>\
+                 {}{}\
+                 🚨 <b>{}</b><br><br>\
+                 ⏰ {}",
+            alert.severity.emoji(),
+            alert.alert_number,
+            escape(&alert.network_name),
+            alert.chain_id,
+            escape(&alert.alias),
+            self.display_address(&format!("{:?}", alert.address)),
+            escape(&alert.asset),
+            alert.formatted,
+            alert.threshold,
+            unit,
+            runway,
+            top_up_html(alert),
+            escape(alert.call_to_action()),
+            alert.next_alert
+        )
+    }
+
+    /// Format diff report in Matrix HTML
+    fn format_report(&self, report: &BalanceReport) -> String {
+        if !report.has_data() {
+            return "📊 <b>Daily Balance Report</b><br><br>No balance data available yet.".to_string();
+        }
+
+        let mut message = String::from("📊 <b>Daily Balance Report</b><br>");
+        message.push_str(&format!("📅 {}<br><br>", report.generated_at.format("%Y-%m-%d %H:%M:%S")));
+
+        for summary in &report.changes {
+            message.push_str(&format!("🌐 <b>{}</b> | 📍 <b>{}</b><br>", escape(&summary.network_name), escape(&summary.alias)));
+            message.push_str(&format!("<code>{}</code><br>", self.display_address(&summary.address)));
+            message.push_str(&self.format_asset_lines(summary));
+            message.push_str("<br>");
+        }
+
+        if report.changes.is_empty() {
+            message.push_str("✅ No balance changes detected in the last period.<br>");
+        } else {
+            message.push_str(&format!("📈 <b>Total changes:</b> {}<br>", report.total_changes()));
+        }
+
+        if !report.portfolio.is_empty() {
+            message.push_str(&format!("💰 <b>Portfolio total:</b> {}<br>", report.portfolio.total.describe()));
+        }
+        for node in &report.failovers {
+            message.push_str(&format!("🔌 {}: {}<br>", escape(&node.network_name), escape(&node.describe())));
+        }
+        for runway in &report.runways {
+            message.push_str(&format!("⏳ {}: {}<br>", escape(&runway.network_name), escape(&runway.describe())));
+        }
+
+        message
+    }
+}
+
+/// Escape text for HTML message bodies
+/// Suggested top-up of a low balance alert, empty without a suggestion
+fn top_up_html(alert: &LowBalanceAlert) -> String {
+    let Some(top_up) = &alert.top_up else { return String::new() };
+    let mut lines = format!("💸 <b>{}</b><br><code>{}</code><br>", escape(&top_up.describe()), escape(&top_up.payment_link));
+    if !top_up.data.is_empty() {
+        lines.push_str(&format!("Calldata: <code>{}</code><br>", top_up.data));
+    }
+    if let Some(url) = &top_up.safe_url {
+        lines.push_str(&format!("<a href=\"{}\">Open the Safe transaction builder</a><br>", escape(url.as_str())));
+    }
+    lines
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+/// Plain text fallback of an HTML body, for clients that don't render HTML
+fn plain_text(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut rest = html;
+    while let Some(start) = rest.find('<') {
+        text.push_str(&rest[..start]);
+        let Some(end) = rest[start..].find('>') else {
+            rest = &rest[start..];
+            break;
+        };
+        if rest[start..start + end].eq_ignore_ascii_case("<br") {
+            text.push('\n');
+        }
+        rest = &rest[start + end + 1..];
+    }
+    text.push_str(rest);
+    text.replace("&nbsp;", " ").replace("&lt;", "<").replace("&gt;", ">").replace("&quot;", "\"").replace("&amp;", "&")
+}
+
+#[async_trait]
+impl Notifier for MatrixNotifier {
+    fn name(&self) -> &str {
+        "Matrix"
+    }
+
+    fn sends(&self, subject: &AuditSubject) -> bool {
+        self.alerts.allows(&subject.kind)
+    }
+
+    async fn send_change_alert(&self, changes: &BalanceChangeSummary) -> Result<()> {
+        if !self.alerts.balance_change || !changes.has_changes() {
+            return Ok(());
+        }
+        self.post(&self.format_change_message(changes)).await
+    }
+
+    async fn send_change_digest(&self, digest: &ChangeDigest) -> Result<()> {
+        if !self.alerts.balance_change || digest.is_empty() {
+            return Ok(());
+        }
+        self.post(&self.format_digest_message(digest)).await
+    }
+
+    async fn send_low_balance_alert(&self, alert: &LowBalanceAlert) -> Result<()> {
+        if !self.alerts.low_balance {
+            return Ok(());
+        }
+        self.post(&self.format_low_balance_message(alert)).await
+    }
+
+    async fn send_alert(&self, alert: &MonitorAlert) -> Result<()> {
+        let mut message = format!("🔔 <b>{}</b><br><br>🌐 <b>{}</b>{}<br>",
+            escape(&alert.title), escape(&alert.network_name), alert.chain_suffix());
+        for line in &alert.lines {
+            message.push_str(&format!("{}<br>", escape(line)));
+        }
+        self.post(&message).await
+    }
+
+    async fn send_report(&self, report: &BalanceReport) -> Result<()> {
+        self.post(&self.format_report(report)).await
+    }
+}
@@ -0,0 +1,293 @@
+mod alert;
+mod audit;
+mod dedup;
+mod digest;
+mod discord;
+mod dry_run;
+mod escalation;
+mod heartbeat;
+mod low_balance;
+mod matrix;
+mod pagerduty;
+mod report;
+mod sample;
+mod send_queue;
+mod slack;
+mod template;
+mod top_up;
+mod twilio;
+mod webhook;
+
+pub use alert::{MonitorAlert, DRAINED_KIND, FLAGGED_KIND, LOW_BALANCE_KIND, TEST_ALERT_KIND, UNEXPECTED_DESTINATION_KIND, UNKNOWN_DESTINATION_KIND};
+pub use audit::{AuditLog, AuditSubject};
+pub use dedup::{AlertDedup, DedupKey};
+pub use digest::{ChangeDigest, NetChange};
+pub use discord::{fit_embed, DiscordNotifier};
+pub use dry_run::DryRunNotifier;
+pub use escalation::{AckOutcome, EscalationStep, Escalator, ACK_CALLBACK_PREFIX};
+pub use heartbeat::{spawn_heartbeat, Heartbeat, HEARTBEAT_KIND};
+pub use low_balance::{LowBalanceAlert, LowBalanceTracker, ThrottleStatus};
+pub use matrix::MatrixNotifier;
+pub use pagerduty::PagerDutyClient;
+pub use report::{spawn_daily_report_scheduler, BalanceReport, NodeFailovers};
+pub(crate) use report::{previous_day_snapshot, report_baseline};
+pub use sample::{test_change_alert, test_report, TEST_WALLET_ALIAS};
+pub use send_queue::{split_message, SendQueue};
+pub use slack::SlackNotifier;
+pub use template::{render_template, template_placeholders};
+pub use top_up::{TopUp, TopUpTargets};
+pub use twilio::TwilioNotifier;
+pub use webhook::{WebhookNotifier, SIGNATURE_HEADER};
+use tracing::{debug, warn};
+
+use crate::logger::BalanceChangeSummary;
+use crate::monitoring::BalanceInfo;
+use async_trait::async_trait;
+use eyre::{eyre, Result};
+use futures::future::{join_all, BoxFuture};
+
+/// Notification channel for balance alerts and reports
+#[async_trait]
+pub trait Notifier: Send + Sync {
+    /// Channel name used in logs
+    fn name(&self) -> &str;
+
+    /// Send alert for detected balance changes
+    async fn send_change_alert(&self, changes: &BalanceChangeSummary) -> Result<()>;
+
+    /// Send balance changes of one check cycle together (default: one alert per address)
+    async fn send_change_digest(&self, digest: &ChangeDigest) -> Result<()> {
+        for changes in &digest.changes {
+            self.send_change_alert(changes).await?;
+        }
+        Ok(())
+    }
+
+    /// Send low balance alert (throttling is already applied by the caller)
+    async fn send_low_balance_alert(&self, alert: &LowBalanceAlert) -> Result<()>;
+
+    /// Send network-level alert from other monitors (gas prices, ...)
+    async fn send_alert(&self, alert: &MonitorAlert) -> Result<()>;
+
+    /// Send critical alert that can be acknowledged as `ack_id` (default: a regular alert)
+    async fn send_critical_alert(&self, alert: &MonitorAlert, _ack_id: u64) -> Result<()> {
+        self.send_alert(alert).await
+    }
+
+    /// Send balance diff report
+    async fn send_report(&self, report: &BalanceReport) -> Result<()>;
+
+    /// Receive latest balances after each check (used by interactive channels)
+    async fn update_balances(&self, _balances: &[BalanceInfo]) {}
+
+    /// False if the channel doesn't deliver this alert, e.g. alerts of its kind are disabled
+    fn sends(&self, _subject: &AuditSubject) -> bool {
+        true
+    }
+
+    /// True if the channel writes its own audit entries (e.g. one per chat)
+    fn audits_deliveries(&self) -> bool {
+        false
+    }
+}
+
+/// Fans out notifications to all configured channels at once
+#[derive(Default)]
+pub struct NotifierSet {
+    notifiers: Vec<Box<dyn Notifier>>,
+    audit: Option<AuditLog>,
+    dedup: Option<AlertDedup>,
+}
+
+impl NotifierSet {
+    /// Create empty notifier set
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a notification channel
+    pub fn push(&mut self, notifier: Box<dyn Notifier>) {
+        self.notifiers.push(notifier);
+    }
+
+    /// Record every delivery attempt in the audit log
+    pub fn set_audit(&mut self, audit: AuditLog) {
+        self.audit = Some(audit);
+    }
+
+    /// Send each alert once across instances sharing the state backend
+    pub fn set_dedup(&mut self, dedup: AlertDedup) {
+        self.dedup = Some(dedup);
+    }
+
+    /// Send to each of `notifiers` with `send` concurrently, so a stalled channel doesn't
+    /// hold up the others, auditing each delivery under `subject`. Channels that don't send
+    /// alerts of the subject's kind are skipped. With dedup, each
+    /// channel's delivery is claimed first and skipped if another instance already sent
+    /// it; a failed delivery releases the claim. Fails if every channel that tried to
+    /// send failed, so callers learn the notification went nowhere.
+    async fn deliver<'a>(
+        &'a self,
+        notifiers: impl Iterator<Item = &'a Box<dyn Notifier>>,
+        key: impl FnOnce() -> Option<DedupKey>,
+        subject: Option<&AuditSubject>,
+        what: &str,
+        send: impl Fn(&'a dyn Notifier) -> BoxFuture<'a, Result<()>>,
+    ) -> Result<()> {
+        let key = self.dedup.as_ref().and_then(|dedup| Some((dedup, key()?)));
+        let deliveries = notifiers.filter(|notifier| subject.is_none_or(|subject| notifier.sends(subject))).map(|notifier| {
+            let claim = key.as_ref().map(|(dedup, key)| (*dedup, key.channel(notifier.name())));
+            let send = &send;
+            async move {
+                if let Some((dedup, key)) = &claim {
+                    if !dedup.claim(key).await {
+                        return None;
+                    }
+                }
+                let result = send(notifier.as_ref()).await;
+                if let Some(subject) = subject {
+                    self.audit(notifier.as_ref(), subject, &result).await;
+                }
+                match &result {
+                    Ok(()) => debug!(channel = notifier.name(), "Delivered {}", what),
+                    Err(e) => {
+                        warn!("Failed to send {} {}: {}", notifier.name(), what, e);
+                        if let Some((dedup, key)) = &claim {
+                            dedup.release(key).await;
+                        }
+                    }
+                }
+                Some(result.map_err(|e| format!("{}: {}", notifier.name(), e)))
+            }
+        });
+        // Channels skipped because another instance claimed the delivery don't count
+        let results: Vec<_> = join_all(deliveries).await.into_iter().flatten().collect();
+        if !results.is_empty() && results.iter().all(Result::is_err) {
+            let errors: Vec<_> = results.into_iter().filter_map(Result::err).collect();
+            return Err(eyre!("no channel delivered the {}: {}", what, errors.join("; ")));
+        }
+        Ok(())
+    }
+
+    /// Write audit entry for a channel unless it audits its own deliveries
+    async fn audit(&self, notifier: &dyn Notifier, subject: &AuditSubject, result: &Result<()>) {
+        if let Some(audit) = &self.audit {
+            if notifier.sends(subject) && !notifier.audits_deliveries() {
+                audit.record(subject.entry(notifier.name(), None, result)).await;
+            }
+        }
+    }
+
+    /// Channels named in `channels` (case-insensitive), or every channel if empty. A channel
+    /// type also selects its named instances, e.g. `telegram` selects `Telegram:ops`.
+    fn selected<'a>(&'a self, channels: &'a [String]) -> impl Iterator<Item = &'a Box<dyn Notifier>> + 'a {
+        self.notifiers.iter().filter(move |notifier| {
+            let name = notifier.name();
+            let kind = name.split_once(':').map_or(name, |(kind, _)| kind);
+            channels.is_empty()
+                || channels.iter().any(|channel| channel.eq_ignore_ascii_case(name) || channel.eq_ignore_ascii_case(kind))
+        })
+    }
+
+    /// Send alert to the channels named in `channels` (case-insensitive), or every channel if empty
+    pub async fn send_alert_to(&self, alert: &MonitorAlert, channels: &[String]) -> Result<()> {
+        let subject = AuditSubject::alert(alert);
+        let what = format!("{} alert", alert.kind);
+        self.deliver(self.selected(channels), || DedupKey::alert(alert), Some(&subject), &what, |notifier| {
+            notifier.send_alert(alert)
+        })
+        .await
+    }
+
+    /// Send critical alert with an Acknowledge button where the channel supports one
+    pub async fn send_critical_alert_to(&self, alert: &MonitorAlert, channels: &[String], ack_id: u64) -> Result<()> {
+        let subject = AuditSubject::alert(alert);
+        let what = format!("critical {} alert #{}", alert.kind, ack_id);
+        self.deliver(self.selected(channels), || DedupKey::alert(alert), Some(&subject), &what, |notifier| {
+            notifier.send_critical_alert(alert, ack_id)
+        })
+        .await
+    }
+
+    /// Start tracking a critical alert in `escalator` and send it to `channels` with its
+    /// Acknowledge button. With dedup the alert is claimed first, so only the instance
+    /// sending it tracks it, and the others don't re-send escalations nobody can acknowledge.
+    pub async fn send_escalated_alert_to(
+        &self,
+        escalator: &Escalator,
+        alert: &MonitorAlert,
+        channels: &[String],
+        now: u64,
+    ) -> Result<()> {
+        if let (Some(dedup), Some(key)) = (&self.dedup, DedupKey::alert(alert)) {
+            if !dedup.claim(&key.channel("escalation")).await {
+                return Ok(());
+            }
+        }
+        let ack_id = escalator.open(alert, channels, now).await;
+        self.send_critical_alert_to(alert, channels, ack_id).await
+    }
+
+    /// Check if no channels are configured
+    pub fn is_empty(&self) -> bool {
+        self.notifiers.is_empty()
+    }
+
+    /// Number of configured channels
+    pub fn len(&self) -> usize {
+        self.notifiers.len()
+    }
+}
+
+#[async_trait]
+impl Notifier for NotifierSet {
+    fn name(&self) -> &str {
+        "all"
+    }
+
+    async fn send_change_alert(&self, changes: &BalanceChangeSummary) -> Result<()> {
+        // Summaries without changes are only passed on, never claimed or audited
+        let subject = changes.has_changes().then(|| AuditSubject::change(changes));
+        let what = format!("balance change alert for {}", changes.alias);
+        let key = || subject.is_some().then(|| DedupKey::change(changes));
+        self.deliver(self.notifiers.iter(), key, subject.as_ref(), &what, |notifier| notifier.send_change_alert(changes))
+            .await
+    }
+
+    /// A single change is sent as a regular alert
+    async fn send_change_digest(&self, digest: &ChangeDigest) -> Result<()> {
+        if let [changes] = digest.changes.as_slice() {
+            return self.send_change_alert(changes).await;
+        }
+        let subject = AuditSubject::digest(digest);
+        let what = format!("digest of {} changes on {}", digest.len(), digest.network_name);
+        let key = || Some(DedupKey::digest(digest));
+        self.deliver(self.notifiers.iter(), key, Some(&subject), &what, |notifier| notifier.send_change_digest(digest))
+            .await
+    }
+
+    async fn send_low_balance_alert(&self, alert: &LowBalanceAlert) -> Result<()> {
+        let subject = AuditSubject::low_balance(alert);
+        let what = format!("low balance alert for {} {}", alert.alias, alert.asset);
+        let key = || Some(DedupKey::low_balance(alert));
+        self.deliver(self.selected(&alert.channels), key, Some(&subject), &what, |notifier| {
+            notifier.send_low_balance_alert(alert)
+        })
+        .await
+    }
+
+    async fn send_alert(&self, alert: &MonitorAlert) -> Result<()> {
+        self.send_alert_to(alert, &[]).await
+    }
+
+    async fn send_report(&self, report: &BalanceReport) -> Result<()> {
+        let subject = AuditSubject::report(report);
+        let key = || Some(DedupKey::report(report));
+        self.deliver(self.notifiers.iter(), key, Some(&subject), "daily report", |notifier| notifier.send_report(report))
+            .await
+    }
+
+    async fn update_balances(&self, balances: &[BalanceInfo]) {
+        join_all(self.notifiers.iter().map(|notifier| notifier.update_balances(balances))).await;
+    }
+}
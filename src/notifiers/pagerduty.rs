@@ -0,0 +1,65 @@
+use crate::config::PagerDutyConfig;
+use crate::http::http_client;
+use crate::storage::TrackedAlert;
+use eyre::Result;
+use reqwest::Client;
+use serde_json::{json, Value};
+
+const EVENTS_URL: &str = "https://events.pagerduty.com/v2/enqueue";
+
+/// Opens and acknowledges PagerDuty incidents through the Events API v2
+pub struct PagerDutyClient {
+    client: Client,
+    routing_key: String,
+}
+
+impl PagerDutyClient {
+    pub fn new(config: &PagerDutyConfig) -> Self {
+        Self {
+            client: http_client(),
+            routing_key: config.routing_key.clone(),
+        }
+    }
+
+    /// Event for an alert; the dedup key ties the acknowledgement to the incident
+    pub fn event(&self, action: &str, tracked: &TrackedAlert) -> Value {
+        let mut event = json!({
+            "routing_key": self.routing_key,
+            "event_action": action,
+            "dedup_key": format!("oxwatcher-alert-{}", tracked.id),
+        });
+        if action == "trigger" {
+            event["payload"] = json!({
+                "summary": format!("{} ({})", tracked.alert.title, tracked.alert.network_name),
+                "source": "oxwatcher",
+                "severity": "critical",
+                "custom_details": {
+                    "network": tracked.alert.network_name,
+                    "chain_id": tracked.alert.chain_id,
+                    "details": tracked.alert.lines,
+                },
+            });
+        }
+        event
+    }
+
+    /// Open an incident for an escalated alert
+    pub async fn trigger(&self, tracked: &TrackedAlert) -> Result<()> {
+        self.send(self.event("trigger", tracked)).await
+    }
+
+    /// Acknowledge the incident of an alert acknowledged in the watcher
+    pub async fn acknowledge(&self, tracked: &TrackedAlert) -> Result<()> {
+        self.send(self.event("acknowledge", tracked)).await
+    }
+
+    async fn send(&self, event: Value) -> Result<()> {
+        let response = self.client.post(EVENTS_URL).json(&event).send().await?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            eyre::bail!("PagerDuty returned {}: {}", status, body);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,209 @@
+use super::{Notifier, NotifierSet};
+use crate::config::DailyReportConfig;
+use crate::logger::{compare_balances, BalanceChangeSummary};
+use crate::monitoring::{AssetRunway, BalanceInfo, Entities, RunwayForecasts};
+use crate::pricing::Portfolio;
+use crate::providers::{NetworkRpcStatus, RpcHealth};
+use crate::storage::{BalanceStorage, HistoryStore, StateBackend};
+use chrono::{DateTime, Local, NaiveTime};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tracing::{info, warn};
+
+/// Runways shown in reports are those shorter than this many days
+const REPORT_RUNWAY_DAYS: f64 = 30.0;
+
+/// Balance diff report across all addresses and networks
+#[derive(Debug)]
+pub struct BalanceReport {
+    pub generated_at: DateTime<Local>,
+    /// Number of addresses covered by the report
+    pub address_count: usize,
+    /// Addresses with at least one balance change
+    pub changes: Vec<BalanceChangeSummary>,
+    /// USD totals compared against the previous day
+    pub portfolio: Portfolio,
+    /// RPC nodes that failed over since the previous report
+    pub failovers: Vec<NodeFailovers>,
+    /// Assets forecast to reach a low balance threshold within 30 days, shortest runway first
+    pub runways: Vec<AssetRunway>,
+}
+
+/// Failovers of one RPC node in a report period
+#[derive(Debug, Clone, PartialEq)]
+pub struct NodeFailovers {
+    pub network_name: String,
+    pub node: String,
+    pub count: u64,
+}
+
+impl NodeFailovers {
+    /// E.g. "eth.llamarpc.com failed 14 times"
+    pub fn describe(&self) -> String {
+        format!("{} failed {} time{}", self.node, self.count, if self.count == 1 { "" } else { "s" })
+    }
+}
+
+impl BalanceReport {
+    /// Build report by diffing current balances against a baseline.
+    /// Portfolio totals are compared against `previous_day` when available.
+    pub fn build(balances: &[BalanceInfo], baseline: &BalanceStorage, previous_day: Option<&BalanceStorage>) -> Self {
+        let changes = balances
+            .iter()
+            .map(|balance| compare_balances(balance, baseline))
+            .filter(|summary| summary.has_changes())
+            .collect();
+
+        Self {
+            generated_at: Local::now(),
+            address_count: balances.len(),
+            changes,
+            portfolio: Portfolio::build(balances, previous_day),
+            failovers: Vec::new(),
+            runways: Vec::new(),
+        }
+    }
+
+    /// Add the failovers of each RPC node since `reported`, the node failover counts
+    /// at the previous report
+    pub fn with_failovers(mut self, networks: &[NetworkRpcStatus], reported: &HashMap<(String, String), u64>) -> Self {
+        self.failovers = networks
+            .iter()
+            .flat_map(|network| network.nodes.iter().map(move |node| (network, node)))
+            .filter_map(|(network, node)| {
+                let key = (network.network_name.clone(), node.node.clone());
+                let count = node.failovers.saturating_sub(reported.get(&key).copied().unwrap_or_default());
+                (count > 0).then_some(NodeFailovers { network_name: key.0, node: key.1, count })
+            })
+            .collect();
+        self
+    }
+
+    /// Add the latest runway forecasts of the reported addresses that are shorter than 30 days
+    pub fn with_runways(mut self, balances: &[BalanceInfo], forecasts: &RunwayForecasts) -> Self {
+        self.runways = forecasts
+            .all()
+            .into_iter()
+            .filter(|runway| runway.runway.days() < REPORT_RUNWAY_DAYS)
+            .filter(|runway| balances.iter().any(|b| b.network_name == runway.network_name && b.alias == runway.alias))
+            .collect();
+        self
+    }
+
+    /// Add entity totals to the portfolio
+    pub fn with_entities(mut self, entities: &Entities) -> Self {
+        self.portfolio = self.portfolio.with_entities(entities);
+        self
+    }
+
+    /// Check if there was any balance data to report on
+    pub fn has_data(&self) -> bool {
+        self.address_count > 0
+    }
+
+    /// Total number of changed assets and NFT holdings across all addresses
+    pub fn total_changes(&self) -> usize {
+        self.changes.iter().map(|summary| summary.changed_assets().count() + summary.nft_changes.len()).sum()
+    }
+}
+
+/// Balances as of 24 hours ago from history, if history is enabled
+pub(crate) async fn previous_day_snapshot(history: Option<&RwLock<HistoryStore>>) -> Option<BalanceStorage> {
+    let history = history?.read().await;
+    let day_ago = (Local::now().timestamp() - 24 * 60 * 60).max(0) as u64;
+    Some(history.snapshot_at(day_ago))
+}
+
+/// Balances a report is diffed against: those of the previous daily report, or
+/// the history snapshot from 24 hours ago before the first one
+pub(crate) async fn report_baseline(
+    backend: &dyn StateBackend,
+    history: Option<&RwLock<HistoryStore>>,
+) -> Option<BalanceStorage> {
+    match backend.load_report_baseline().await {
+        Ok(Some(baseline)) => return Some(baseline),
+        Ok(None) => {}
+        Err(e) => warn!("Failed to load report baseline from {} backend: {}", backend.name(), e),
+    }
+    previous_day_snapshot(history).await
+}
+
+/// Start daily report scheduler that sends reports to all notifiers
+#[allow(clippy::too_many_arguments)]
+pub fn spawn_daily_report_scheduler(
+    notifiers: Arc<NotifierSet>,
+    storage: Arc<RwLock<BalanceStorage>>,
+    history: Option<Arc<RwLock<HistoryStore>>>,
+    backend: Arc<dyn StateBackend>,
+    report_config: DailyReportConfig,
+    entities: Arc<Entities>,
+    rpc: Arc<RpcHealth>,
+    runways: Arc<RunwayForecasts>,
+) {
+    if !report_config.enabled {
+        return;
+    }
+
+    let report_time = report_config.time;
+    tokio::spawn(async move {
+        // Node failover counts at the previous report
+        let mut reported: HashMap<(String, String), u64> = HashMap::new();
+        loop {
+            // Parse target time (HH:MM)
+            let target_time = if let Ok(time) = NaiveTime::parse_from_str(&report_time, "%H:%M") {
+                time
+            } else {
+                warn!("Invalid daily report time format: {}. Expected HH:MM", report_time);
+                return;
+            };
+
+            // Calculate sleep duration until next report time
+            let now = Local::now();
+            let target_datetime = now.date_naive().and_time(target_time);
+
+            let duration = if now.time() < target_time {
+                // Target is today
+                (target_datetime - now.naive_local()).to_std().unwrap()
+            } else {
+                // Target is tomorrow
+                let tomorrow = now.date_naive().succ_opt().unwrap().and_time(target_time);
+                (tomorrow - now.naive_local()).to_std().unwrap()
+            };
+
+            info!("Next daily report scheduled in {} hours", duration.as_secs() / 3600);
+            tokio::time::sleep(duration).await;
+
+            // Build and send report
+            let previous_day = previous_day_snapshot(history.as_deref()).await;
+            let baseline = report_baseline(backend.as_ref(), history.as_deref()).await;
+            let networks = rpc.all();
+            let (report, snapshot) = {
+                let storage = storage.read().await;
+                let mut balances: Vec<BalanceInfo> = storage.balances.values().cloned().collect();
+                balances.sort_by(|a, b| (&a.network_name, &a.alias).cmp(&(&b.network_name, &b.alias)));
+                let report = BalanceReport::build(&balances, baseline.as_ref().unwrap_or(&storage), previous_day.as_ref())
+                    .with_entities(&entities)
+                    .with_failovers(&networks, &reported)
+                    .with_runways(&balances, &runways);
+                (report, storage.clone())
+            };
+            match notifiers.send_report(&report).await {
+                // The next report covers the period since this one
+                Ok(()) => {
+                    if let Err(e) = backend.save_report_baseline(&snapshot).await {
+                        warn!("Failed to save report baseline: {}", e);
+                    }
+                    reported = networks
+                        .iter()
+                        .flat_map(|network| network.nodes.iter().map(move |node| ((network.network_name.clone(), node.node.clone()), node.failovers)))
+                        .collect();
+                }
+                Err(e) => warn!("Failed to send daily report: {}", e),
+            }
+
+            // Sleep for a minute to avoid sending multiple reports
+            tokio::time::sleep(std::time::Duration::from_secs(60)).await;
+        }
+    });
+}
@@ -0,0 +1,54 @@
+use super::BalanceReport;
+use crate::logger::{compare_balances, BalanceChangeSummary};
+use crate::monitoring::{to_units, BalanceInfo, TokenBalance};
+use crate::storage::BalanceStorage;
+use alloy::primitives::Address;
+
+/// Alias of the synthetic wallet in test change alerts and reports. Its address is the
+/// zero address, so webhook receivers can tell test changes from real ones.
+pub const TEST_WALLET_ALIAS: &str = "🧪 Test wallet";
+
+/// Synthetic wallet on `network_name` holding `eth` ETH and `usdc` USDC
+fn test_wallet(network_name: &str, chain_id: u64, eth: f64, usdc: f64) -> BalanceInfo {
+    BalanceInfo {
+        network_name: network_name.to_string(),
+        chain_id,
+        alias: TEST_WALLET_ALIAS.to_string(),
+        address: Address::ZERO,
+        eth_balance: to_units(eth, 18),
+        eth_formatted: eth.to_string(),
+        native_symbol: "ETH".to_string(),
+        native_decimals: 18,
+        eth_usd_value: None,
+        nft_balances: vec![],
+        block_number: None,
+        block_hash: None,
+        token_balances: vec![TokenBalance {
+            alias: "USDC".to_string(),
+            balance: to_units(usdc, 6),
+            formatted: usdc.to_string(),
+            decimals: 6,
+            usd_value: None,
+            shares: None,
+        }],
+    }
+}
+
+/// Balances before and after the test change: 1.5 ETH spent and 250 USDC received
+fn test_change_balances(network_name: &str, chain_id: u64) -> (BalanceStorage, BalanceInfo) {
+    let mut before = BalanceStorage::new();
+    before.update(&test_wallet(network_name, chain_id, 10.0, 1000.0));
+    (before, test_wallet(network_name, chain_id, 8.5, 1250.0))
+}
+
+/// Synthetic balance change on `network_name`, to check change alerts end to end
+pub fn test_change_alert(network_name: &str, chain_id: u64) -> BalanceChangeSummary {
+    let (before, after) = test_change_balances(network_name, chain_id);
+    compare_balances(&after, &before)
+}
+
+/// Sample daily report with the test change
+pub fn test_report(network_name: &str, chain_id: u64) -> BalanceReport {
+    let (before, after) = test_change_balances(network_name, chain_id);
+    BalanceReport::build(&[after], &before, None)
+}
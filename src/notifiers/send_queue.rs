@@ -0,0 +1,163 @@
+use crate::providers::RateLimiter;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// Paces outgoing chat messages to stay within a messenger's global rate limit and
+/// its per-chat limits. Sends wait for their slot instead of being rejected.
+#[derive(Debug)]
+pub struct SendQueue {
+    global: RateLimiter,
+    chats: Mutex<HashMap<i64, Arc<RateLimiter>>>,
+}
+
+impl SendQueue {
+    pub fn new(messages_per_second: f64) -> Self {
+        Self {
+            global: RateLimiter::new(messages_per_second),
+            chats: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Limiter of a chat, created on its first message
+    fn chat(&self, chat_id: i64, messages_per_second: f64) -> Arc<RateLimiter> {
+        let mut chats = self.chats.lock().unwrap_or_else(|e| e.into_inner());
+        chats.entry(chat_id).or_insert_with(|| Arc::new(RateLimiter::new(messages_per_second))).clone()
+    }
+
+    /// Wait until a message may be sent to `chat_id`, which accepts at most
+    /// `messages_per_second` (its rate is fixed by the first call)
+    pub async fn acquire(&self, chat_id: i64, messages_per_second: f64) {
+        self.chat(chat_id, messages_per_second).acquire().await;
+        self.global.acquire().await;
+    }
+}
+
+/// Split an HTML message into parts of at most `limit` characters, at line breaks where
+/// possible. Lines longer than the limit are cut between characters, tags and entities,
+/// never inside a tag or entity. Tags still open at a cut are closed at the end of the
+/// part and opened again at the start of the next one, so every part is valid markup.
+pub fn split_message(text: &str, limit: usize) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut part = Part::new(limit);
+    for line in text.split_inclusive('\n') {
+        if part.has_content() && !part.fits(line.chars().count(), part.closing_len()) {
+            parts.push(part.finish());
+            part = part.next();
+        }
+        for atom in atoms(line) {
+            let closing = match tag(atom) {
+                Some(Tag::Open(name)) => part.closing_len() + name.chars().count() + 3,
+                Some(Tag::Close(_)) => part.closing_len().saturating_sub(atom.chars().count()),
+                None => part.closing_len(),
+            };
+            if part.has_content() && !part.fits(atom.chars().count(), closing) {
+                parts.push(part.finish());
+                part = part.next();
+            }
+            part.push(atom);
+        }
+    }
+    if part.has_content() || parts.is_empty() {
+        parts.push(part.finish());
+    }
+    parts
+}
+
+/// Message part being filled by `split_message`
+struct Part {
+    limit: usize,
+    text: String,
+    len: usize,
+    /// Length of the tags reopened from the previous part
+    reopened_len: usize,
+    /// Opening tags not closed yet, with their names
+    open: Vec<(String, String)>,
+}
+
+impl Part {
+    fn new(limit: usize) -> Self {
+        Self { limit, text: String::new(), len: 0, reopened_len: 0, open: Vec::new() }
+    }
+
+    /// Part starting with the tags still open in this one
+    fn next(&self) -> Self {
+        let text: String = self.open.iter().map(|(opening, _)| opening.as_str()).collect();
+        let len = text.chars().count();
+        Self { limit: self.limit, text, len, reopened_len: len, open: self.open.clone() }
+    }
+
+    fn has_content(&self) -> bool {
+        self.len > self.reopened_len
+    }
+
+    /// Length of the closing tags of the open tags
+    fn closing_len(&self) -> usize {
+        self.open.iter().map(|(_, name)| name.chars().count() + 3).sum()
+    }
+
+    /// Whether `len` more characters fit, with closing tags of length `closing` after them
+    fn fits(&self, len: usize, closing: usize) -> bool {
+        self.len + len + closing <= self.limit
+    }
+
+    fn push(&mut self, atom: &str) {
+        match tag(atom) {
+            Some(Tag::Open(name)) => self.open.push((atom.to_string(), name.to_string())),
+            Some(Tag::Close(name)) => {
+                if let Some(index) = self.open.iter().rposition(|(_, open)| open == name) {
+                    self.open.remove(index);
+                }
+            }
+            None => {}
+        }
+        self.text.push_str(atom);
+        self.len += atom.chars().count();
+    }
+
+    /// Text of the part with its open tags closed
+    fn finish(&self) -> String {
+        let mut text = self.text.clone();
+        for (_, name) in self.open.iter().rev() {
+            text.push_str(&format!("</{}>", name));
+        }
+        text
+    }
+}
+
+/// HTML tag of a message
+enum Tag<'a> {
+    Open(&'a str),
+    Close(&'a str),
+}
+
+/// Name of an opening or closing tag, None for text, entities and self-closing tags
+fn tag(atom: &str) -> Option<Tag<'_>> {
+    let inner = atom.strip_prefix('<')?.strip_suffix('>')?;
+    if inner.ends_with('/') {
+        return None;
+    }
+    match inner.strip_prefix('/') {
+        Some(name) => Some(Tag::Close(name.trim())),
+        None => Some(Tag::Open(inner.split_whitespace().next()?)),
+    }
+}
+
+/// Pieces a message may be cut between: whole tags, whole entities and single characters
+fn atoms(text: &str) -> Vec<&str> {
+    let mut atoms = Vec::new();
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        let end = match c {
+            '<' => rest.find('>').map(|end| end + 1),
+            '&' => rest[1..]
+                .find(|c: char| !c.is_ascii_alphanumeric() && c != '#')
+                .filter(|&end| rest[1 + end..].starts_with(';') && end > 0)
+                .map(|end| end + 2),
+            _ => None,
+        }
+        .unwrap_or(c.len_utf8());
+        atoms.push(&rest[..end]);
+        rest = &rest[end..];
+    }
+    atoms
+}
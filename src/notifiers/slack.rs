@@ -0,0 +1,267 @@
+use super::{AuditSubject, BalanceReport, ChangeDigest, LowBalanceAlert, MonitorAlert, Notifier};
+use crate::config::{AlertSettings, SlackConfig};
+use crate::http::http_client;
+use crate::logger::{calculate_diff, calculate_percent_change, shorten_address, BalanceChange, BalanceChangeSummary};
+use async_trait::async_trait;
+use eyre::Result;
+use reqwest::{Client, Url};
+use serde_json::json;
+
+/// Slack notifier posting to an incoming webhook
+pub struct SlackNotifier {
+    client: Client,
+    webhook_url: Url,
+    channel: Option<String>,
+    alerts: AlertSettings,
+    show_full_address: bool,
+}
+
+impl SlackNotifier {
+    pub fn new(config: &SlackConfig) -> Self {
+        Self {
+            client: http_client(),
+            webhook_url: config.webhook_url.clone(),
+            channel: config.channel.clone(),
+            alerts: config.alerts.clone(),
+            show_full_address: config.show_full_address,
+        }
+    }
+
+    /// Post mrkdwn text to the webhook
+    async fn post(&self, text: &str) -> Result<()> {
+        let mut payload = json!({ "text": text });
+        if let Some(channel) = &self.channel {
+            payload["channel"] = json!(channel);
+        }
+
+        let response = self.client.post(self.webhook_url.clone()).json(&payload).send().await?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            eyre::bail!("Slack webhook returned {}: {}", status, body);
+        }
+
+        Ok(())
+    }
+
+    fn display_address(&self, address: &str) -> String {
+        if self.show_full_address {
+            address.to_string()
+        } else {
+            shorten_address(address)
+        }
+    }
+
+    /// Format change message in Slack mrkdwn
+    fn format_change_message(&self, changes: &BalanceChangeSummary) -> String {
+        let mut message = String::from("🔔 *Balance Alert*\n\n");
+        message.push_str(&format!("🌐 *{}* (Chain ID: {})\n", changes.network_name, changes.chain_id));
+        message.push_str(&format!("📍 *{}*\n", changes.alias));
+        message.push_str(&format!("`{}`\n\n", self.display_address(&changes.address)));
+
+        for change in changes.changed_assets() {
+            let (emoji, sign) = match change.change {
+                BalanceChange::Increase => ("📈", "+"),
+                _ => ("📉", ""),
+            };
+            let diff = calculate_diff(&change.new_balance, &change.old_balance, change.decimals);
+            let percent = calculate_percent_change(&change.new_balance, &change.old_balance);
+
+            message.push_str(&format!("💰 *{}*\n", change.alias));
+            if percent.abs() >= 0.01 {
+                message.push_str(&format!("{} *{}{}* ({:+.2}%)\n", emoji, sign, diff, percent));
+            } else {
+                message.push_str(&format!("{} *{}{}*\n", emoji, sign, diff));
+            }
+            message.push_str(&format!("{} → {}\n\n", change.old_formatted, change.new_formatted));
+        }
+
+        for nft in &changes.nft_changes {
+            let emoji = if nft.new_count > nft.old_count { "📥" } else { "📤" };
+            message.push_str(&format!("🖼️ *{}*\n{} {}\n\n", nft.label, emoji, nft.describe()));
+        }
+
+        if !changes.transactions.is_empty() {
+            message.push_str("🧾 *Transactions*\n");
+            for tx in &changes.transactions {
+                let counterparty = tx.counterparty
+                    .map(|address| self.display_address(&format!("{:?}", address)))
+                    .unwrap_or_else(|| "contract creation".to_string());
+                let hash = shorten_address(&format!("{:?}", tx.tx_hash));
+                let hash = match &tx.url {
+                    Some(url) => format!("<{}|{}>", url, hash),
+                    None => format!("`{}`", hash),
+                };
+                message.push_str(&format!("• {} {} `{}` · {}\n", tx.asset, tx.preposition(), counterparty, hash));
+            }
+        }
+
+        message
+    }
+
+    /// Format balance changes of one check cycle in Slack mrkdwn
+    fn format_digest_message(&self, digest: &ChangeDigest) -> String {
+        let mut message = format!("🔔 *Balance Alert* · {} addresses\n\n", digest.len());
+        message.push_str(&format!("🌐 *{}* (Chain ID: {})\n\n", digest.network_name, digest.chain_id));
+
+        for summary in digest.listed() {
+            message.push_str(&format!("📍 *{}* `{}`\n", summary.alias, self.display_address(&summary.address)));
+            for change in summary.changed_assets() {
+                let (emoji, sign) = match change.change {
+                    BalanceChange::Increase => ("📈", "+"),
+                    _ => ("📉", ""),
+                };
+                let diff = calculate_diff(&change.new_balance, &change.old_balance, change.decimals);
+                message.push_str(&format!("   {} {}: {}{} | {} → {}\n",
+                    emoji, change.alias, sign, diff, change.old_formatted, change.new_formatted));
+            }
+            for nft in &summary.nft_changes {
+                message.push_str(&format!("   🖼️ {}: {}\n", nft.label, nft.describe()));
+            }
+            message.push('\n');
+        }
+
+        for line in digest.summary_lines() {
+            message.push_str(&format!("{}\n", line));
+        }
+        message
+    }
+
+    /// Format low balance alert in Slack mrkdwn
+    fn format_low_balance_message(&self, alert: &LowBalanceAlert) -> String {
+        let unit = if alert.is_native { format!(" {}", alert.asset) } else { String::new() };
+        let runway = match alert.runway_text() {
+            text if text.is_empty() => text,
+            text => format!("⏳ {}\n", text),
+        };
+
+        format!("{} *LOW BALANCE ALERT #{}*\n\n\
+                 🌐 *{}* (Chain ID: {})\n\
+                 📍 *{}*\n\
+                 `{}`\n\n\
+                 💰 {}: *{}*\n\
+                 📉 Below threshold: *{}*{}\n\
+                 {}{}\
+                 🚨 *{}*\n\n\
+                 ⏰ {}",
+            alert.severity.emoji(),
+            alert.alert_number,
+            alert.network_name,
+            alert.chain_id,
+            alert.alias,
+            self.display_address(&format!("{:?}", alert.address)),
+            alert.asset,
+            alert.formatted,
+            alert.threshold,
+            unit,
+            runway,
+            top_up_text(alert),
+            alert.call_to_action(),
+            alert.next_alert
+        )
+    }
+
+    /// Format diff report in Slack mrkdwn
+    fn format_report(&self, report: &BalanceReport) -> String {
+        if !report.has_data() {
+            return "📊 *Daily Balance Report*\n\nNo balance data available yet.".to_string();
+        }
+
+        let mut message = String::from("📊 *Daily Balance Report*\n");
+        message.push_str(&format!("📅 {}\n\n", report.generated_at.format("%Y-%m-%d %H:%M:%S")));
+
+        for summary in &report.changes {
+            message.push_str(&format!("🌐 *{}* | 📍 *{}*\n", summary.network_name, summary.alias));
+            message.push_str(&format!("`{}`\n", self.display_address(&summary.address)));
+
+            for change in summary.changed_assets() {
+                let (emoji, sign) = match change.change {
+                    BalanceChange::Increase => ("📈", "+"),
+                    _ => ("📉", ""),
+                };
+                let diff = calculate_diff(&change.new_balance, &change.old_balance, change.decimals);
+                message.push_str(&format!("   {} {}: {}{} | {} → {}\n",
+                    emoji, change.alias, sign, diff, change.old_formatted, change.new_formatted));
+            }
+            for nft in &summary.nft_changes {
+                message.push_str(&format!("   🖼️ {}: {}\n", nft.label, nft.describe()));
+            }
+            message.push('\n');
+        }
+
+        if report.changes.is_empty() {
+            message.push_str("✅ No balance changes detected in the last period.\n");
+        } else {
+            message.push_str(&format!("📈 *Total changes:* {}\n", report.total_changes()));
+        }
+
+        if !report.portfolio.is_empty() {
+            message.push_str(&format!("💰 *Portfolio total:* {}\n", report.portfolio.total.describe()));
+        }
+        for node in &report.failovers {
+            message.push_str(&format!("🔌 {}: {}\n", node.network_name, node.describe()));
+        }
+        for runway in &report.runways {
+            message.push_str(&format!("⏳ {}: {}\n", runway.network_name, runway.describe()));
+        }
+
+        message
+    }
+}
+
+#[async_trait]
+impl Notifier for SlackNotifier {
+    fn name(&self) -> &str {
+        "Slack"
+    }
+
+    fn sends(&self, subject: &AuditSubject) -> bool {
+        self.alerts.allows(&subject.kind)
+    }
+
+    async fn send_change_alert(&self, changes: &BalanceChangeSummary) -> Result<()> {
+        if !self.alerts.balance_change || !changes.has_changes() {
+            return Ok(());
+        }
+        self.post(&self.format_change_message(changes)).await
+    }
+
+    async fn send_change_digest(&self, digest: &ChangeDigest) -> Result<()> {
+        if !self.alerts.balance_change || digest.is_empty() {
+            return Ok(());
+        }
+        self.post(&self.format_digest_message(digest)).await
+    }
+
+    async fn send_low_balance_alert(&self, alert: &LowBalanceAlert) -> Result<()> {
+        if !self.alerts.low_balance {
+            return Ok(());
+        }
+        self.post(&self.format_low_balance_message(alert)).await
+    }
+
+    async fn send_alert(&self, alert: &MonitorAlert) -> Result<()> {
+        let mut message = format!("🔔 *{}*\n\n🌐 *{}*{}\n", alert.title, alert.network_name, alert.chain_suffix());
+        for line in &alert.lines {
+            message.push_str(&format!("{}\n", line));
+        }
+        self.post(&message).await
+    }
+
+    async fn send_report(&self, report: &BalanceReport) -> Result<()> {
+        self.post(&self.format_report(report)).await
+    }
+}
+
+/// Suggested top-up of a low balance alert, empty without a suggestion
+fn top_up_text(alert: &LowBalanceAlert) -> String {
+    let Some(top_up) = &alert.top_up else { return String::new() };
+    let mut lines = format!("💸 *{}*\n`{}`\n", top_up.describe(), top_up.payment_link);
+    if !top_up.data.is_empty() {
+        lines.push_str(&format!("Calldata: `{}`\n", top_up.data));
+    }
+    if let Some(url) = &top_up.safe_url {
+        lines.push_str(&format!("<{}|Open the Safe transaction builder>\n", url));
+    }
+    lines
+}
@@ -0,0 +1,35 @@
+/// Fill the `{name}` placeholders of a message template. Placeholders without a value
+/// are kept as written so a typo shows up in the message instead of vanishing.
+pub fn render_template(template: &str, vars: &[(&str, String)]) -> String {
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        rendered.push_str(&rest[..start]);
+        let placeholder = &rest[start..];
+        let value = placeholder.find('}').and_then(|end| {
+            let (_, value) = vars.iter().find(|(name, _)| *name == &placeholder[1..end])?;
+            Some((value, end))
+        });
+        match value {
+            Some((value, end)) => {
+                rendered.push_str(value);
+                rest = &placeholder[end + 1..];
+            }
+            None => {
+                rendered.push('{');
+                rest = &placeholder[1..];
+            }
+        }
+    }
+    rendered.push_str(rest);
+    rendered
+}
+
+/// Names of the `{name}` placeholders used in a template
+pub fn template_placeholders(template: &str) -> Vec<&str> {
+    template.split('{')
+        .skip(1)
+        .filter_map(|part| part.split_once('}').map(|(name, _)| name))
+        .filter(|name| !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_'))
+        .collect()
+}
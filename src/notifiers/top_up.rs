@@ -0,0 +1,126 @@
+use crate::config::NetworkConfig;
+use crate::contracts::IERC20;
+use crate::monitoring::{to_units, BalanceInfo};
+use alloy::primitives::{utils::format_units, Address, Bytes, U256};
+use alloy::sol_types::SolCall;
+use reqwest::Url;
+use std::collections::HashMap;
+
+/// Transaction builder app opened in the funding Safe
+const SAFE_TX_BUILDER_URL: &str = "https://apps-portal.safe.global/tx-builder";
+
+/// EIP-3770 short name of a chain, as used in Safe web app links
+fn safe_chain_prefix(chain_id: u64) -> Option<&'static str> {
+    Some(match chain_id {
+        1 => "eth",
+        10 => "oeth",
+        56 => "bnb",
+        100 => "gno",
+        137 => "matic",
+        324 => "zksync",
+        8453 => "base",
+        42161 => "arb1",
+        43114 => "avax",
+        59144 => "linea",
+        534352 => "scr",
+        11155111 => "sep",
+        _ => return None,
+    })
+}
+
+/// Transfer that brings a low balance back to its top-up target, ready to be signed
+/// elsewhere. The watcher never signs or sends it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TopUp {
+    /// Asset name (native symbol such as "ETH", or token alias)
+    pub asset: String,
+    /// Amount to send, formatted, e.g. "2.5"
+    pub amount: String,
+    /// Transaction target: the low address for the native currency, the token contract for tokens
+    pub to: Address,
+    /// Native value of the transaction in wei
+    pub value: U256,
+    /// Calldata: empty for the native currency, transfer(address,uint256) for tokens
+    pub data: Bytes,
+    /// EIP-681 payment request understood by most wallets
+    pub payment_link: String,
+    /// Transaction builder of the funding Safe, on chains the Safe web app supports
+    pub safe_url: Option<Url>,
+}
+
+impl TopUp {
+    /// E.g. "Top up 2.5 ETH"
+    pub fn describe(&self) -> String {
+        format!("Top up {} {}", self.amount, self.asset)
+    }
+}
+
+/// Targets low balances are suggested to be topped up to on a network
+#[derive(Debug, Clone, Default)]
+pub struct TopUpTargets {
+    chain_id: u64,
+    /// Native target by address alias
+    native: HashMap<String, f64>,
+    /// Token contract and target by token alias
+    tokens: HashMap<String, (Address, f64)>,
+    safe: Option<Address>,
+}
+
+impl TopUpTargets {
+    pub fn new(network: &NetworkConfig) -> Self {
+        Self {
+            chain_id: network.chain_id,
+            native: network.addresses.iter()
+                .filter_map(|addr| Some((addr.alias.clone(), addr.top_up_to?)))
+                .collect(),
+            tokens: network.tokens.iter()
+                .filter_map(|token| Some((token.alias.clone(), (token.address, token.top_up_to?))))
+                .collect(),
+            safe: network.top_up_safe,
+        }
+    }
+
+    /// Top-up of `asset` of `balance` to its target, None without a target or when the
+    /// balance already reaches it
+    pub fn suggest(&self, balance: &BalanceInfo, asset: &str, is_native: bool) -> Option<TopUp> {
+        let (target, current, decimals) = if is_native {
+            (*self.native.get(&balance.alias)?, balance.eth_balance, balance.native_decimals)
+        } else {
+            let (_, target) = self.tokens.get(asset)?;
+            let token = balance.token_balances.iter().find(|token| token.alias == asset)?;
+            (*target, token.balance, token.decimals)
+        };
+        let amount = to_units(target, decimals).checked_sub(current).filter(|amount| !amount.is_zero())?;
+
+        let (to, value, data, payment_link) = if is_native {
+            let link = format!("ethereum:{}@{}?value={}", balance.address, self.chain_id, amount);
+            (balance.address, amount, Bytes::new(), link)
+        } else {
+            let (token, _) = self.tokens[asset];
+            let data = IERC20::transferCall { to: balance.address, amount }.abi_encode();
+            let link = format!("ethereum:{}@{}/transfer?address={}&uint256={}", token, self.chain_id, balance.address, amount);
+            (token, U256::ZERO, data.into(), link)
+        };
+
+        let mut formatted = format_units(amount, decimals).unwrap_or_else(|_| amount.to_string());
+        if formatted.contains('.') {
+            formatted = formatted.trim_end_matches('0').trim_end_matches('.').to_string();
+        }
+        let safe_url = self.safe.zip(safe_chain_prefix(self.chain_id)).and_then(|(safe, prefix)| {
+            Url::parse_with_params("https://app.safe.global/apps/open", &[
+                ("safe", format!("{}:{}", prefix, safe)),
+                ("appUrl", SAFE_TX_BUILDER_URL.to_string()),
+            ]).ok()
+        });
+
+        Some(TopUp {
+            asset: asset.to_string(),
+            amount: formatted,
+            to,
+            value,
+            data,
+            payment_link,
+            safe_url,
+        })
+    }
+}
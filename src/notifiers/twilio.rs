@@ -0,0 +1,149 @@
+use super::{AuditSubject, BalanceReport, LowBalanceAlert, MonitorAlert, Notifier, DRAINED_KIND, TEST_ALERT_KIND};
+use crate::config::{AlertSeverity, TwilioConfig};
+use crate::http::http_client;
+use crate::logger::BalanceChangeSummary;
+use async_trait::async_trait;
+use eyre::Result;
+use reqwest::Client;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::time::Instant;
+use tracing::warn;
+
+const TWILIO_API_URL: &str = "https://api.twilio.com/2010-04-01";
+
+/// Longest SMS body sent, so an alert costs a few message segments at most
+const MAX_SMS_CHARS: usize = 306;
+
+/// Period of the SMS rate cap
+const CAP_WINDOW: Duration = Duration::from_secs(3600);
+
+/// Last-resort channel texting critical alerts to on-call phones through Twilio.
+/// Only critical low balance and rule alerts, drained alerts and test alerts are sent.
+pub struct TwilioNotifier {
+    client: Client,
+    account_sid: String,
+    auth_token: String,
+    from: String,
+    to: Vec<String>,
+    max_per_hour: usize,
+    /// Times of the alerts texted in the last hour
+    sent: Mutex<VecDeque<Instant>>,
+}
+
+impl TwilioNotifier {
+    pub fn new(config: &TwilioConfig) -> Self {
+        Self {
+            client: http_client(),
+            account_sid: config.account_sid.clone(),
+            auth_token: config.auth_token.clone(),
+            from: config.from.clone(),
+            to: config.to.clone(),
+            max_per_hour: config.max_per_hour as usize,
+            sent: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    /// Whether an alert is texted: critical alerts, and test alerts to verify the channel
+    pub fn is_texted(kind: &str, severity: Option<AlertSeverity>) -> bool {
+        kind == DRAINED_KIND || kind == TEST_ALERT_KIND || severity == Some(AlertSeverity::Critical)
+    }
+
+    /// Count an alert against the hourly cap; false if the cap is reached
+    pub fn take_slot(&self, now: Instant) -> bool {
+        let mut sent = self.sent.lock().unwrap_or_else(|e| e.into_inner());
+        while sent.front().is_some_and(|&at| now.duration_since(at) >= CAP_WINDOW) {
+            sent.pop_front();
+        }
+        if sent.len() >= self.max_per_hour {
+            return false;
+        }
+        sent.push_back(now);
+        true
+    }
+
+    /// Text a message to every number; fails if any number couldn't be reached
+    async fn text(&self, body: &str) -> Result<()> {
+        if !self.take_slot(Instant::now()) {
+            eyre::bail!("SMS cap of {} alerts per hour reached", self.max_per_hour);
+        }
+
+        let body = truncate(body, MAX_SMS_CHARS);
+        let mut failed = 0;
+        for to in &self.to {
+            if let Err(e) = self.send_sms(to, &body).await {
+                warn!("Failed to send SMS to {}: {}", to, e);
+                failed += 1;
+            }
+        }
+        if failed > 0 {
+            eyre::bail!("{} of {} SMS recipient(s) failed", failed, self.to.len());
+        }
+        Ok(())
+    }
+
+    async fn send_sms(&self, to: &str, body: &str) -> Result<()> {
+        let url = format!("{}/Accounts/{}/Messages.json", TWILIO_API_URL, self.account_sid);
+        let response = self.client
+            .post(url)
+            .basic_auth(&self.account_sid, Some(&self.auth_token))
+            .form(&[("From", self.from.as_str()), ("To", to), ("Body", body)])
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            eyre::bail!("Twilio returned {}: {}", status, body);
+        }
+        Ok(())
+    }
+}
+
+/// Cut text to `max` characters, marking the cut with an ellipsis
+fn truncate(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        return text.to_string();
+    }
+    let mut truncated: String = text.chars().take(max - 1).collect();
+    truncated.push('…');
+    truncated
+}
+
+#[async_trait]
+impl Notifier for TwilioNotifier {
+    fn name(&self) -> &str {
+        "Twilio"
+    }
+
+    fn sends(&self, subject: &AuditSubject) -> bool {
+        Self::is_texted(&subject.kind, subject.severity)
+    }
+
+    async fn send_change_alert(&self, _changes: &BalanceChangeSummary) -> Result<()> {
+        Ok(())
+    }
+
+    async fn send_low_balance_alert(&self, alert: &LowBalanceAlert) -> Result<()> {
+        if alert.severity != AlertSeverity::Critical {
+            return Ok(());
+        }
+        self.text(&format!(
+            "LOW BALANCE {} on {}: {} {} (threshold {}). {}",
+            alert.alias, alert.network_name, alert.formatted, alert.asset, alert.threshold, alert.call_to_action()
+        ))
+        .await
+    }
+
+    async fn send_alert(&self, alert: &MonitorAlert) -> Result<()> {
+        let subject = AuditSubject::alert(alert);
+        if !self.sends(&subject) {
+            return Ok(());
+        }
+        self.text(&format!("{} ({}): {}", alert.title, alert.network_name, alert.lines.join("; "))).await
+    }
+
+    async fn send_report(&self, _report: &BalanceReport) -> Result<()> {
+        Ok(())
+    }
+}
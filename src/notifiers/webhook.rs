@@ -0,0 +1,223 @@
+use super::{AuditSubject, BalanceReport, ChangeDigest, LowBalanceAlert, MonitorAlert, Notifier};
+use crate::config::{AlertSettings, RetryConfig, WebhookConfig, MAX_RETRY_ATTEMPTS, MAX_RETRY_BACKOFF_SECS};
+use crate::logger::BalanceChangeSummary;
+use crate::pricing::{Portfolio, ValueTotal};
+use async_trait::async_trait;
+use chrono::Utc;
+use eyre::Result;
+use hmac::{Hmac, Mac};
+use reqwest::{Client, Url};
+use serde_json::{json, Value};
+use sha2::Sha256;
+use std::collections::HashMap;
+use std::time::Duration;
+use tracing::warn;
+
+/// Header carrying the HMAC-SHA256 signature of the request body
+pub const SIGNATURE_HEADER: &str = "X-Oxwatcher-Signature";
+
+/// Notifier that POSTs JSON events to an arbitrary HTTP endpoint
+pub struct WebhookNotifier {
+    client: Client,
+    url: Url,
+    headers: HashMap<String, String>,
+    secret: Option<String>,
+    retry: RetryConfig,
+    alerts: AlertSettings,
+}
+
+impl WebhookNotifier {
+    pub fn new(config: &WebhookConfig) -> Self {
+        Self {
+            client: Client::builder()
+                .timeout(Duration::from_secs(config.retry.timeout_secs))
+                .build()
+                .expect("HTTP client builds, as with Client::new()"),
+            url: config.url.clone(),
+            headers: config.headers.clone(),
+            secret: config.secret.clone(),
+            retry: config.retry.clone(),
+            alerts: config.alerts.clone(),
+        }
+    }
+
+    /// Sign body with HMAC-SHA256, returns "sha256=<hex>"
+    pub fn sign(secret: &str, body: &[u8]) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+            .expect("HMAC accepts keys of any length");
+        mac.update(body);
+        format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+    }
+
+    /// POST payload, retrying on connection errors, timeouts, 429 and 5xx responses
+    async fn post(&self, payload: &Value) -> Result<()> {
+        let body = serde_json::to_vec(payload)?;
+        let max_backoff = Duration::from_secs(MAX_RETRY_BACKOFF_SECS);
+        let mut backoff = Duration::from_secs(self.retry.backoff_secs).min(max_backoff);
+        let max_attempts = self.retry.max_attempts.clamp(1, MAX_RETRY_ATTEMPTS);
+        let mut attempt = 1;
+
+        loop {
+            let mut request = self.client
+                .post(self.url.clone())
+                .header("Content-Type", "application/json")
+                .body(body.clone());
+            for (name, value) in &self.headers {
+                request = request.header(name, value);
+            }
+            if let Some(secret) = &self.secret {
+                request = request.header(SIGNATURE_HEADER, Self::sign(secret, &body));
+            }
+
+            let error = match request.send().await {
+                Ok(response) if response.status().is_success() => return Ok(()),
+                Ok(response) => {
+                    let status = response.status();
+                    let retryable = status.is_server_error() || status.as_u16() == 429;
+                    let error = eyre::eyre!("webhook {} returned {}", self.url, status);
+                    if !retryable {
+                        return Err(error);
+                    }
+                    error
+                }
+                Err(e) => eyre::eyre!("webhook {} request failed: {}", self.url, e),
+            };
+
+            if attempt >= max_attempts {
+                return Err(error);
+            }
+
+            warn!("{} (attempt {}/{}), retrying in {}s", error, attempt, max_attempts, backoff.as_secs());
+            tokio::time::sleep(backoff).await;
+            backoff = (backoff * 2).min(max_backoff);
+            attempt += 1;
+        }
+    }
+
+    fn portfolio_json(portfolio: &Portfolio) -> Value {
+        let total_json = |total: &ValueTotal| json!({
+            "usd": total.usd,
+            "previous_usd": total.previous_usd,
+        });
+
+        json!({
+            "total": total_json(&portfolio.total),
+            "networks": portfolio.networks.iter()
+                .map(|n| json!({ "network": n.network_name, "total": total_json(&n.total) }))
+                .collect::<Vec<_>>(),
+            "addresses": portfolio.addresses.iter()
+                .map(|a| json!({ "network": a.network_name, "alias": a.alias, "total": total_json(&a.total) }))
+                .collect::<Vec<_>>(),
+            "entities": portfolio.entities.iter()
+                .map(|e| json!({ "entity": e.name, "addresses": e.addresses, "total": total_json(&e.total) }))
+                .collect::<Vec<_>>(),
+        })
+    }
+}
+
+#[async_trait]
+impl Notifier for WebhookNotifier {
+    fn name(&self) -> &str {
+        "Webhook"
+    }
+
+    fn sends(&self, subject: &AuditSubject) -> bool {
+        self.alerts.allows(&subject.kind)
+    }
+
+    async fn send_change_alert(&self, changes: &BalanceChangeSummary) -> Result<()> {
+        if !self.alerts.balance_change || !changes.has_changes() {
+            return Ok(());
+        }
+
+        let mut payload = changes.to_json();
+        payload["event"] = json!("balance_change");
+        payload["timestamp"] = json!(Utc::now().to_rfc3339());
+        self.post(&payload).await
+    }
+
+    async fn send_change_digest(&self, digest: &ChangeDigest) -> Result<()> {
+        if !self.alerts.balance_change || digest.is_empty() {
+            return Ok(());
+        }
+
+        let mut payload = digest.to_json();
+        payload["event"] = json!("balance_change_digest");
+        payload["timestamp"] = json!(Utc::now().to_rfc3339());
+        self.post(&payload).await
+    }
+
+    async fn send_low_balance_alert(&self, alert: &LowBalanceAlert) -> Result<()> {
+        if !self.alerts.low_balance {
+            return Ok(());
+        }
+
+        let payload = json!({
+            "event": "low_balance",
+            "timestamp": Utc::now().to_rfc3339(),
+            "network": alert.network_name,
+            "chain_id": alert.chain_id,
+            "alias": alert.alias,
+            "address": format!("{:?}", alert.address),
+            "asset": alert.asset,
+            "balance": alert.formatted,
+            "usd_value": alert.usd_value,
+            "threshold": alert.threshold,
+            "severity": alert.severity,
+            "message": alert.message,
+            "alert_number": alert.alert_number,
+            "runway_secs": alert.runway.map(|runway| runway.remaining.as_secs()),
+            "top_up": alert.top_up.as_ref().map(|top_up| json!({
+                "asset": top_up.asset,
+                "amount": top_up.amount,
+                "to": format!("{:?}", top_up.to),
+                "value": top_up.value.to_string(),
+                "data": top_up.data,
+                "payment_link": top_up.payment_link,
+                "safe_url": top_up.safe_url,
+            })),
+        });
+        self.post(&payload).await
+    }
+
+    async fn send_alert(&self, alert: &MonitorAlert) -> Result<()> {
+        let mut payload = json!({
+            "event": alert.kind,
+            "timestamp": Utc::now().to_rfc3339(),
+            "network": alert.network_name,
+            "chain_id": alert.chain_id,
+            "title": alert.title,
+            "details": alert.lines,
+        });
+        if let (Some(payload), Some(data)) = (payload.as_object_mut(), alert.data.as_object()) {
+            for (key, value) in data {
+                payload.entry(key.clone()).or_insert_with(|| value.clone());
+            }
+        }
+        self.post(&payload).await
+    }
+
+    async fn send_report(&self, report: &BalanceReport) -> Result<()> {
+        let payload = json!({
+            "event": "report",
+            "timestamp": report.generated_at.to_rfc3339(),
+            "address_count": report.address_count,
+            "total_changes": report.total_changes(),
+            "addresses": report.changes.iter().map(BalanceChangeSummary::to_json).collect::<Vec<_>>(),
+            "portfolio": Self::portfolio_json(&report.portfolio),
+            "rpc_failovers": report.failovers.iter()
+                .map(|node| json!({ "network": node.network_name, "node": node.node, "failovers": node.count }))
+                .collect::<Vec<_>>(),
+            "runways": report.runways.iter()
+                .map(|runway| json!({
+                    "network": runway.network_name,
+                    "alias": runway.alias,
+                    "asset": runway.asset,
+                    "runway_secs": runway.runway.remaining.as_secs(),
+                    "daily_spend": runway.runway.daily_spend,
+                }))
+                .collect::<Vec<_>>(),
+        });
+        self.post(&payload).await
+    }
+}
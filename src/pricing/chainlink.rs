@@ -0,0 +1,42 @@
+use crate::contracts::IAggregatorV3;
+use alloy::primitives::{Address, I256, U256};
+use alloy::providers::Provider;
+use eyre::Result;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Read the latest answer of a Chainlink aggregator, scaled by its decimals. Rounds
+/// rejected by `check_chainlink_round` are an error, so the caller falls back to
+/// another source instead of pricing with a frozen answer.
+pub async fn fetch_chainlink_price<P: Provider>(provider: &P, feed: Address, heartbeat: Duration) -> Result<f64> {
+    let aggregator = IAggregatorV3::new(feed, provider);
+
+    let decimals = aggregator.decimals().call().await?;
+    let round = aggregator.latestRoundData().call().await?;
+
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+    check_chainlink_round(&round, now, heartbeat).map_err(|e| eyre::eyre!("feed {} {}", feed, e))?;
+
+    let answer: f64 = round.answer.to_string().parse()?;
+    Ok(answer / 10f64.powi(decimals as i32))
+}
+
+/// Reject a round whose answer isn't positive, that was carried over from an earlier
+/// round (`answeredInRound < roundId`), or that was last updated more than `heartbeat`
+/// before `now` (Unix seconds)
+pub fn check_chainlink_round(round: &IAggregatorV3::latestRoundDataReturn, now: u64, heartbeat: Duration) -> Result<()> {
+    if round.answer <= I256::ZERO {
+        eyre::bail!("returned non-positive answer {}", round.answer);
+    }
+    if round.answeredInRound < round.roundId {
+        eyre::bail!("answer is from round {} before the latest round {}", round.answeredInRound, round.roundId);
+    }
+    if round.updatedAt == U256::ZERO {
+        eyre::bail!("round {} is not complete", round.roundId);
+    }
+    let updated_at = u64::try_from(round.updatedAt).unwrap_or(u64::MAX);
+    let age = now.saturating_sub(updated_at);
+    if age > heartbeat.as_secs() {
+        eyre::bail!("answer is stale: last updated {} min ago (heartbeat: {} min)", age / 60, heartbeat.as_secs() / 60);
+    }
+    Ok(())
+}
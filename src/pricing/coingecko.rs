@@ -0,0 +1,55 @@
+use crate::http::http_client;
+use eyre::Result;
+use reqwest::{Client, Url};
+use std::collections::HashMap;
+
+/// Client for the CoinGecko simple price endpoint
+#[derive(Clone)]
+pub struct CoinGeckoClient {
+    client: Client,
+    base_url: Url,
+    api_key: Option<String>,
+}
+
+impl CoinGeckoClient {
+    pub fn new(base_url: Url, api_key: Option<String>) -> Self {
+        Self {
+            client: http_client(),
+            base_url,
+            api_key,
+        }
+    }
+
+    /// Fetch USD prices for the given coin ids. Unknown ids are missing from the result.
+    pub async fn fetch_prices(&self, ids: &[&str]) -> Result<HashMap<String, f64>> {
+        let endpoint = format!("{}/simple/price", self.base_url.as_str().trim_end_matches('/'));
+        let mut url = Url::parse(&endpoint)?;
+        url.query_pairs_mut()
+            .append_pair("ids", &ids.join(","))
+            .append_pair("vs_currencies", "usd");
+
+        let mut request = self.client.get(url);
+        if let Some(key) = &self.api_key {
+            // Pro and demo keys use different headers
+            let header = if self.base_url.host_str() == Some("pro-api.coingecko.com") {
+                "x-cg-pro-api-key"
+            } else {
+                "x-cg-demo-api-key"
+            };
+            request = request.header(header, key);
+        }
+
+        let response = request.send().await?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            eyre::bail!("CoinGecko API error {}: {}", status, body);
+        }
+
+        let quotes: HashMap<String, HashMap<String, f64>> = response.json().await?;
+        Ok(quotes
+            .into_iter()
+            .filter_map(|(id, quote)| Some((id, *quote.get("usd")?)))
+            .collect())
+    }
+}
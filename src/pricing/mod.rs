@@ -0,0 +1,35 @@
+mod chainlink;
+mod coingecko;
+mod oracle;
+mod portfolio;
+
+pub use chainlink::{check_chainlink_round, fetch_chainlink_price};
+pub use coingecko::CoinGeckoClient;
+pub use oracle::{AssetPrices, PriceOracle};
+pub use portfolio::{balance_value_usd, AddressValue, EntityValue, NetworkValue, Portfolio, ValueTotal};
+
+/// Format a USD amount for display ("$8,400" above $1,000, "$12.50" below)
+pub fn format_usd(value: f64) -> String {
+    let sign = if value < 0.0 { "-" } else { "" };
+    let value = value.abs();
+
+    if value < 1000.0 {
+        return format!("{}${:.2}", sign, value);
+    }
+
+    let digits = format!("{:.0}", value);
+    let mut grouped = String::new();
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(c);
+    }
+
+    format!("{}${}", sign, grouped)
+}
+
+/// Approximate USD suffix for a balance line, e.g. " (~$8,400)", or empty if unpriced
+pub fn usd_suffix(value: Option<f64>) -> String {
+    value.map(|v| format!(" (~{})", format_usd(v))).unwrap_or_default()
+}
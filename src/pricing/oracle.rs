@@ -0,0 +1,138 @@
+use super::{fetch_chainlink_price, CoinGeckoClient};
+use crate::config::{NetworkConfig, PriceSourceConfig, PricingConfig};
+use crate::monitoring::BalanceInfo;
+use alloy::providers::Provider;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+use tracing::warn;
+
+/// USD prices resolved for a network's assets
+#[derive(Debug, Clone, Default)]
+pub struct AssetPrices {
+    /// Native currency price
+    pub native: Option<f64>,
+    /// Token prices by alias
+    pub tokens: HashMap<String, f64>,
+}
+
+impl AssetPrices {
+    /// Fill USD values of a balance from its formatted amounts
+    pub fn apply(&self, balance: &mut BalanceInfo) {
+        balance.eth_usd_value = self.native.and_then(|price| usd_value(&balance.eth_formatted, price));
+
+        for token in &mut balance.token_balances {
+            token.usd_value = self.tokens.get(&token.alias)
+                .and_then(|&price| usd_value(&token.formatted, price));
+        }
+    }
+}
+
+fn usd_value(formatted: &str, price: f64) -> Option<f64> {
+    formatted.parse::<f64>().ok().map(|amount| amount * price)
+}
+
+/// Price lookups for one network with a TTL cache
+#[derive(Clone)]
+pub struct PriceOracle {
+    coingecko: CoinGeckoClient,
+    cache: Arc<RwLock<HashMap<String, (f64, Instant)>>>,
+    ttl: Duration,
+}
+
+impl PriceOracle {
+    pub fn new(config: &PricingConfig) -> Self {
+        Self {
+            coingecko: CoinGeckoClient::new(config.coingecko_url.clone(), config.coingecko_api_key.clone()),
+            cache: Arc::new(RwLock::new(HashMap::new())),
+            ttl: Duration::from_secs(config.cache_secs),
+        }
+    }
+
+    async fn cached(&self, key: &str) -> Option<f64> {
+        let cache = self.cache.read().await;
+        cache.get(key)
+            .filter(|(_, fetched_at)| fetched_at.elapsed() < self.ttl)
+            .map(|(price, _)| *price)
+    }
+
+    async fn store(&self, key: String, price: f64) {
+        self.cache.write().await.insert(key, (price, Instant::now()));
+    }
+
+    /// Fetch uncached CoinGecko prices in a single request
+    async fn prefetch_coingecko(&self, ids: &[&str]) {
+        let mut missing = Vec::new();
+        for &id in ids {
+            if self.cached(&format!("coingecko:{}", id)).await.is_none() && !missing.contains(&id) {
+                missing.push(id);
+            }
+        }
+        if missing.is_empty() {
+            return;
+        }
+
+        match self.coingecko.fetch_prices(&missing).await {
+            Ok(prices) => {
+                for (id, price) in prices {
+                    self.store(format!("coingecko:{}", id), price).await;
+                }
+            }
+            Err(e) => warn!("Failed to fetch CoinGecko prices: {}", e),
+        }
+    }
+
+    /// Get USD price from a source, trying the Chainlink feed before CoinGecko
+    pub async fn price<P: Provider>(&self, provider: &P, source: &PriceSourceConfig) -> Option<f64> {
+        if let Some(feed) = source.chainlink_feed {
+            let key = format!("chainlink:{:?}", feed);
+            if let Some(price) = self.cached(&key).await {
+                return Some(price);
+            }
+            match fetch_chainlink_price(provider, feed, Duration::from_secs(source.heartbeat_secs)).await {
+                Ok(price) => {
+                    self.store(key, price).await;
+                    return Some(price);
+                }
+                Err(e) => warn!("Failed to read Chainlink feed {:?}: {}", feed, e),
+            }
+        }
+
+        let id = source.coingecko_id.as_deref()?;
+        let key = format!("coingecko:{}", id);
+        if let Some(price) = self.cached(&key).await {
+            return Some(price);
+        }
+        self.prefetch_coingecko(&[id]).await;
+        self.cached(&key).await
+    }
+
+    /// Resolve prices for the native currency and tokens of a network
+    pub async fn resolve<P: Provider>(&self, provider: &P, network: &NetworkConfig) -> AssetPrices {
+        let sources: Vec<&PriceSourceConfig> = network.price.iter()
+            .chain(network.tokens.iter().filter_map(|t| t.price.as_ref()))
+            .collect();
+
+        // Batch CoinGecko-only sources into one request
+        let coingecko_ids: Vec<&str> = sources.iter()
+            .filter(|s| s.chainlink_feed.is_none())
+            .filter_map(|s| s.coingecko_id.as_deref())
+            .collect();
+        self.prefetch_coingecko(&coingecko_ids).await;
+
+        let mut prices = AssetPrices::default();
+        if let Some(source) = &network.price {
+            prices.native = self.price(provider, source).await;
+        }
+        for token in &network.tokens {
+            if let Some(source) = &token.price {
+                if let Some(price) = self.price(provider, source).await {
+                    prices.tokens.insert(token.alias.clone(), price);
+                }
+            }
+        }
+
+        prices
+    }
+}
@@ -0,0 +1,162 @@
+use super::format_usd;
+use crate::monitoring::{BalanceInfo, Entities};
+use crate::storage::BalanceStorage;
+use std::collections::BTreeMap;
+
+/// USD value of all priced assets of a balance, None if nothing is priced
+pub fn balance_value_usd(balance: &BalanceInfo) -> Option<f64> {
+    let values: Vec<f64> = balance.eth_usd_value.into_iter()
+        .chain(balance.token_balances.iter().filter_map(|t| t.usd_value))
+        .collect();
+
+    (!values.is_empty()).then(|| values.iter().sum())
+}
+
+/// Current USD value together with the value at the comparison point
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ValueTotal {
+    pub usd: f64,
+    /// Value at the comparison point (e.g. 24h ago). For aggregates this is only
+    /// known when every included address has one, so changes compare like with like.
+    pub previous_usd: Option<f64>,
+}
+
+impl ValueTotal {
+    /// Empty aggregate with a known (zero) previous value
+    fn zero() -> Self {
+        Self {
+            usd: 0.0,
+            previous_usd: Some(0.0),
+        }
+    }
+
+    fn add(&mut self, other: ValueTotal) {
+        self.usd += other.usd;
+        self.previous_usd = self.previous_usd.zip(other.previous_usd).map(|(a, b)| a + b);
+    }
+
+    /// Absolute change in USD
+    pub fn change_usd(&self) -> Option<f64> {
+        Some(self.usd - self.previous_usd?)
+    }
+
+    /// Relative change in percent
+    pub fn change_percent(&self) -> Option<f64> {
+        let previous = self.previous_usd?;
+        (previous != 0.0).then(|| (self.usd - previous) / previous * 100.0)
+    }
+
+    /// Format as "$8,400 (+$120, +1.45%)", omitting the change if unknown
+    pub fn describe(&self) -> String {
+        let value = format_usd(self.usd);
+        match (self.change_usd(), self.change_percent()) {
+            (Some(change), Some(percent)) => {
+                let sign = if change >= 0.0 { "+" } else { "" };
+                format!("{} ({}{}, {:+.2}%)", value, sign, format_usd(change), percent)
+            }
+            (Some(change), None) => {
+                let sign = if change >= 0.0 { "+" } else { "" };
+                format!("{} ({}{})", value, sign, format_usd(change))
+            }
+            _ => value,
+        }
+    }
+}
+
+/// USD value of one monitored address
+#[derive(Debug, Clone)]
+pub struct AddressValue {
+    pub network_name: String,
+    pub alias: String,
+    pub total: ValueTotal,
+}
+
+/// USD value of all addresses on one network
+#[derive(Debug, Clone)]
+pub struct NetworkValue {
+    pub network_name: String,
+    pub total: ValueTotal,
+}
+
+/// USD value of one entity's addresses across networks
+#[derive(Debug, Clone)]
+pub struct EntityValue {
+    pub name: String,
+    /// Number of priced addresses included
+    pub addresses: usize,
+    pub total: ValueTotal,
+}
+
+/// Aggregated USD value per address, per network and overall
+#[derive(Debug, Clone, Default)]
+pub struct Portfolio {
+    /// Priced addresses sorted by network and alias
+    pub addresses: Vec<AddressValue>,
+    /// Networks sorted by name
+    pub networks: Vec<NetworkValue>,
+    /// Entities with priced addresses, in config order
+    pub entities: Vec<EntityValue>,
+    pub total: ValueTotal,
+}
+
+impl Portfolio {
+    /// Build portfolio from current balances, compared against a previous snapshot
+    pub fn build(balances: &[BalanceInfo], previous: Option<&BalanceStorage>) -> Self {
+        let mut addresses = Vec::new();
+        let mut networks: BTreeMap<String, ValueTotal> = BTreeMap::new();
+        let mut total = ValueTotal::zero();
+
+        for balance in balances {
+            let Some(usd) = balance_value_usd(balance) else {
+                continue;
+            };
+            let previous_usd = previous
+                .and_then(|storage| storage.get(&balance.network_name, &balance.alias))
+                .and_then(balance_value_usd);
+            let value = ValueTotal { usd, previous_usd };
+
+            networks.entry(balance.network_name.clone()).or_insert_with(ValueTotal::zero).add(value);
+            total.add(value);
+            addresses.push(AddressValue {
+                network_name: balance.network_name.clone(),
+                alias: balance.alias.clone(),
+                total: value,
+            });
+        }
+
+        addresses.sort_by(|a, b| (&a.network_name, &a.alias).cmp(&(&b.network_name, &b.alias)));
+
+        Self {
+            addresses,
+            networks: networks.into_iter()
+                .map(|(network_name, total)| NetworkValue { network_name, total })
+                .collect(),
+            entities: Vec::new(),
+            total,
+        }
+    }
+
+    /// Add the totals of entities over their priced addresses
+    pub fn with_entities(mut self, entities: &Entities) -> Self {
+        self.entities = entities
+            .names()
+            .into_iter()
+            .filter_map(|name| {
+                let members: Vec<&AddressValue> = self.addresses.iter()
+                    .filter(|address| entities.entity_of(&address.network_name, &address.alias) == Some(name))
+                    .collect();
+                let total = members.iter().fold(ValueTotal::zero(), |mut total, address| {
+                    total.add(address.total);
+                    total
+                });
+                (!members.is_empty()).then(|| EntityValue { name: name.to_string(), addresses: members.len(), total })
+            })
+            .collect();
+        self
+    }
+
+    /// Check if any balance was priced
+    pub fn is_empty(&self) -> bool {
+        self.addresses.is_empty()
+    }
+}
@@ -0,0 +1,57 @@
+use super::{create_node_provider, RpcNode};
+use crate::config::RpcNodeConfig;
+use alloy::providers::Provider;
+use eyre::Result;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{error, info, warn};
+
+/// How long to wait for a node's eth_chainId
+const CHAIN_ID_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How often node chain IDs are re-verified after startup
+const CHAIN_ID_CHECK_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Read a node's chain ID
+pub(crate) async fn node_chain_id(node: &RpcNodeConfig) -> Result<u64> {
+    let provider = create_node_provider(node)?;
+    Ok(tokio::time::timeout(CHAIN_ID_TIMEOUT, provider.get_chain_id()).await??)
+}
+
+/// Check each node's eth_chainId against the network's chain ID. Mismatched nodes
+/// are excluded from the fallback set; nodes that match again are included.
+/// Nodes that can't be reached keep their current state.
+pub async fn verify_chain_ids(network_name: &str, chain_id: u64, nodes: &[RpcNodeConfig], trackers: &[Arc<RpcNode>]) {
+    for (node, tracker) in nodes.iter().zip(trackers) {
+        let host = node.url.host_str().unwrap_or_default();
+        match node_chain_id(node).await {
+            Ok(actual) if actual != chain_id => {
+                error!(
+                    "🚫 RPC node {} on {} reports chain ID {} instead of {}, excluding it from the fallback set",
+                    host, network_name, actual, chain_id
+                );
+                tracker.set_excluded(Some(format!("chain ID {} instead of {}", actual, chain_id)));
+            }
+            Ok(_) => {
+                if tracker.excluded().is_some() {
+                    info!("RPC node {} on {} reports chain ID {} again, including it", host, network_name, chain_id);
+                    tracker.set_excluded(None);
+                }
+            }
+            Err(e) => {
+                let error = tracker.redact(&e.to_string(), host);
+                warn!("Failed to verify chain ID of RPC node {} on {}: {}", host, network_name, error);
+            }
+        }
+    }
+}
+
+/// Re-verify node chain IDs every `CHAIN_ID_CHECK_INTERVAL`
+pub fn spawn_chain_id_checks(network_name: String, chain_id: u64, nodes: Vec<RpcNodeConfig>, trackers: Vec<Arc<RpcNode>>) {
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(CHAIN_ID_CHECK_INTERVAL).await;
+            verify_chain_ids(&network_name, chain_id, &nodes, &trackers).await;
+        }
+    });
+}
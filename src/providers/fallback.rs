@@ -1,48 +1,212 @@
 use alloy::{
     providers::ProviderBuilder,
-    rpc::client::RpcClient,
+    rpc::{
+        client::RpcClient,
+        json_rpc::{RequestPacket, ResponsePacket},
+    },
     transports::{
-        http::{reqwest::Url, Http},
+        http::{
+            reqwest::{
+                header::{HeaderMap, HeaderName, HeaderValue, AUTHORIZATION},
+                Client,
+            },
+            Http,
+        },
         layers::FallbackLayer,
+        Authorization, TransportError, TransportErrorKind, TransportFut,
     },
 };
+use super::{RateLimitedTransport, RateLimiter, RpcNode, TrackedTransport};
+use crate::config::{RpcNodeConfig, RpcStrategy};
 use eyre::Result;
 use std::num::NonZeroUsize;
-use tower::ServiceBuilder;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tower::{Service, ServiceBuilder, ServiceExt};
+
+/// Seconds after its last error a failing node is tried first again, so a preferred
+/// node gets its requests back once it recovers
+const RETRY_FAILING_SECS: u64 = 30;
 
 /// Configuration for fallback provider
 pub struct FallbackConfig {
-    pub rpc_urls: Vec<Url>,
+    pub rpc_nodes: Vec<RpcNodeConfig>,
     pub active_transport_count: NonZeroUsize,
+    /// Per-node trackers, in the same order as `rpc_nodes` (optional)
+    pub trackers: Vec<Arc<RpcNode>>,
+    /// Timeout of each request to a node (optional)
+    pub timeout: Option<Duration>,
+    /// Order nodes are tried in (default: latency)
+    pub strategy: RpcStrategy,
 }
 
 impl FallbackConfig {
-    pub fn new(rpc_urls: Vec<Url>, active_transport_count: NonZeroUsize) -> Self {
+    pub fn new(rpc_nodes: Vec<RpcNodeConfig>, active_transport_count: NonZeroUsize) -> Self {
         Self {
-            rpc_urls,
+            rpc_nodes,
             active_transport_count,
+            trackers: Vec::new(),
+            timeout: None,
+            strategy: RpcStrategy::default(),
         }
     }
+
+    /// Record request outcomes and latency of each node
+    pub fn with_tracking(mut self, trackers: Vec<Arc<RpcNode>>) -> Self {
+        self.trackers = trackers;
+        self
+    }
+
+    /// Fail requests a node doesn't answer within `timeout`, so the next node is tried
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Try nodes in the order of `strategy`
+    pub fn with_strategy(mut self, strategy: RpcStrategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+}
+
+/// Fallback trying one node at a time, in config order (`priority`) or starting at
+/// the next node for each request (`round_robin`). Nodes that failed their latest
+/// request or are at their rate limit move to the back, so the others absorb their
+/// requests until they answer again.
+#[derive(Clone)]
+pub struct OrderedFallback<S> {
+    transports: Vec<S>,
+    /// Per-node trackers and rate limiters, in the same order as `transports`
+    trackers: Vec<Option<Arc<RpcNode>>>,
+    limiters: Vec<Option<Arc<RateLimiter>>>,
+    strategy: RpcStrategy,
+    /// Node the next round-robin request starts at
+    next: Arc<AtomicUsize>,
+}
+
+impl<S> OrderedFallback<S> {
+    fn new(transports: Vec<S>, trackers: Vec<Option<Arc<RpcNode>>>, limiters: Vec<Option<Arc<RateLimiter>>>, strategy: RpcStrategy) -> Self {
+        Self { transports, trackers, limiters, strategy, next: Arc::new(AtomicUsize::new(0)) }
+    }
+
+    /// Indices of the nodes in the order they are tried for the next request
+    fn order(&self) -> Vec<usize> {
+        let count = self.transports.len();
+        let start = match self.strategy {
+            RpcStrategy::RoundRobin if count > 0 => self.next.fetch_add(1, Ordering::Relaxed) % count,
+            _ => 0,
+        };
+        let now = chrono::Utc::now().timestamp() as u64;
+        let mut order: Vec<usize> = (0..count).map(|i| (start + i) % count).collect();
+        // Stable sort keeps the strategy's order within each rank
+        order.sort_by_key(|&i| {
+            let status = self.trackers[i].as_ref().map(|node| node.status()).unwrap_or_default();
+            let retry_due = status.last_error_at.is_some_and(|at| now >= at + RETRY_FAILING_SECS);
+            let limited = self.limiters[i].as_ref().is_some_and(|limiter| !limiter.is_available());
+            match () {
+                _ if status.excluded.is_some() => 3,
+                _ if !status.is_up() && !retry_due => 2,
+                _ if limited => 1,
+                _ => 0,
+            }
+        });
+        order
+    }
+}
+
+impl<S> Service<RequestPacket> for OrderedFallback<S>
+where
+    S: Service<RequestPacket, Response = ResponsePacket, Error = TransportError, Future = TransportFut<'static>>
+        + Clone
+        + Send
+        + 'static,
+{
+    type Response = ResponsePacket;
+    type Error = TransportError;
+    type Future = TransportFut<'static>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: RequestPacket) -> Self::Future {
+        let transports: Vec<S> = self.order().into_iter().map(|i| self.transports[i].clone()).collect();
+        Box::pin(async move {
+            let mut last_error = None;
+            for transport in transports {
+                match transport.oneshot(req.clone()).await {
+                    Ok(response) => return Ok(response),
+                    Err(e) => last_error = Some(e),
+                }
+            }
+            Err(last_error.unwrap_or_else(|| TransportErrorKind::custom_str("no RPC nodes configured")))
+        })
+    }
+}
+
+/// HTTP transport for a node, with a dedicated client when it needs extra headers or a timeout
+fn http_transport(node: &RpcNodeConfig, timeout: Option<Duration>) -> Result<Http<Client>> {
+    let mut headers = HeaderMap::new();
+    for (name, value) in &node.headers {
+        let mut value = HeaderValue::from_str(value)?;
+        value.set_sensitive(true);
+        headers.insert(HeaderName::from_bytes(name.as_bytes())?, value);
+    }
+    if let Some(auth) = &node.basic_auth {
+        let mut value = HeaderValue::from_str(&Authorization::basic(&auth.username, &auth.password).to_string())?;
+        value.set_sensitive(true);
+        headers.insert(AUTHORIZATION, value);
+    }
+
+    if headers.is_empty() && timeout.is_none() {
+        return Ok(Http::new(node.url.clone()));
+    }
+    let mut builder = Client::builder().default_headers(headers);
+    if let Some(timeout) = timeout {
+        builder = builder.timeout(timeout);
+    }
+    let client = builder.build()?;
+    Ok(Http::with_client(client, node.url.clone()))
+}
+
+/// Creates a provider talking to a single node, used to verify nodes individually
+pub fn create_node_provider(node: &RpcNodeConfig) -> Result<impl alloy::providers::Provider> {
+    let client = RpcClient::builder().transport(http_transport(node, None)?, false);
+    Ok(ProviderBuilder::new().connect_client(client))
 }
 
 /// Creates a provider with fallback support
 pub fn create_fallback_provider(
     config: FallbackConfig,
 ) -> Result<impl alloy::providers::Provider> {
-    let fallback_layer = FallbackLayer::default()
-        .with_active_transport_count(config.active_transport_count);
+    let transports = config
+        .rpc_nodes
+        .iter()
+        .enumerate()
+        .map(|(idx, node)| {
+            let tracked = TrackedTransport::new(http_transport(node, config.timeout)?, config.trackers.get(idx).cloned());
+            Ok(RateLimitedTransport::new(tracked, node.rate_limit))
+        })
+        .collect::<Result<Vec<_>>>()?;
 
-    let transports: Vec<Http<_>> = config
-        .rpc_urls
-        .into_iter()
-        .map(Http::new)
-        .collect();
-
-    let transport = ServiceBuilder::new()
-        .layer(fallback_layer)
-        .service(transports);
-
-    let client = RpcClient::builder().transport(transport, false);
+    let client = match config.strategy {
+        RpcStrategy::Latency => {
+            let fallback_layer = FallbackLayer::default()
+                .with_active_transport_count(config.active_transport_count);
+            let transport = ServiceBuilder::new()
+                .layer(fallback_layer)
+                .service(transports);
+            RpcClient::builder().transport(transport, false)
+        }
+        strategy => {
+            let trackers = (0..transports.len()).map(|idx| config.trackers.get(idx).cloned()).collect();
+            let limiters = transports.iter().map(|transport| transport.limiter()).collect();
+            RpcClient::builder().transport(OrderedFallback::new(transports, trackers, limiters, strategy), false)
+        }
+    };
     let provider = ProviderBuilder::new().connect_client(client);
 
     Ok(provider)
@@ -0,0 +1,249 @@
+use alloy::{
+    rpc::json_rpc::{RequestPacket, ResponsePacket},
+    transports::{http::reqwest::Url, TransportError, TransportErrorKind, TransportFut},
+};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex, RwLock};
+use std::task::{Context, Poll};
+use std::time::Instant;
+use tower::Service;
+
+/// Request counters and latency of one RPC node
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct NodeStatus {
+    /// Node host; the full URL is not exposed since it often contains an API key
+    pub node: String,
+    /// Unique node ID: the host, followed by a hash of the full URL if it has a port, path
+    /// or query, so endpoints on one provider host (e.g. two API keys) stay apart
+    pub id: String,
+    pub successes: u64,
+    pub errors: u64,
+    /// Errors since the last successful request
+    pub consecutive_errors: u32,
+    pub last_latency_ms: Option<u64>,
+    /// Exponential moving average of request latency
+    pub avg_latency_ms: Option<f64>,
+    pub last_success_at: Option<u64>,
+    pub last_error: Option<String>,
+    pub last_error_at: Option<u64>,
+    /// Times the node started failing after answering, each moving its requests to the other nodes
+    pub failovers: u64,
+    /// Start of the node's current run of errors, None while it answers
+    pub down_since: Option<u64>,
+    /// Why the node is excluded from the fallback set, e.g. a chain ID mismatch
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub excluded: Option<String>,
+}
+
+impl NodeStatus {
+    /// True if the node answered its latest request
+    pub fn is_up(&self) -> bool {
+        self.consecutive_errors == 0
+    }
+
+    /// True if the node received any request
+    pub fn is_used(&self) -> bool {
+        self.successes + self.errors > 0
+    }
+}
+
+/// Shared status of one RPC node, updated by its transport
+#[derive(Debug)]
+pub struct RpcNode {
+    /// Full node URL, only used to redact it from error messages
+    url: Url,
+    status: Mutex<NodeStatus>,
+}
+
+impl RpcNode {
+    pub fn new(url: &Url) -> Self {
+        let host = url.host_str().unwrap_or(url.as_str()).to_string();
+        let id = match (url.port(), url.path(), url.query()) {
+            (None, "/", None) => host.clone(),
+            _ => format!("{}#{}", host, &hex::encode(Sha256::digest(url.as_str().as_bytes()))[..8]),
+        };
+        let status = NodeStatus { node: host, id, ..Default::default() };
+        Self { url: url.clone(), status: Mutex::new(status) }
+    }
+
+    /// Replace the node URL and its path in an error with the host, since either may carry an API key
+    pub(crate) fn redact(&self, error: &str, host: &str) -> String {
+        let full = self.url.as_str();
+        let mut redacted = error.replace(full, host);
+        if let Some(trimmed) = full.strip_suffix('/') {
+            redacted = redacted.replace(trimmed, host);
+        }
+        if let Some(query) = self.url.query() {
+            redacted = redacted.replace(query, "");
+        }
+        if self.url.path().len() > 1 {
+            redacted = redacted.replace(self.url.path(), "");
+        }
+        redacted
+    }
+
+    fn update(&self, update: impl FnOnce(&mut NodeStatus)) {
+        update(&mut self.status.lock().unwrap_or_else(|e| e.into_inner()));
+    }
+
+    fn record_latency(status: &mut NodeStatus, latency_ms: u64) {
+        status.last_latency_ms = Some(latency_ms);
+        status.avg_latency_ms = Some(match status.avg_latency_ms {
+            Some(avg) => avg * 0.8 + latency_ms as f64 * 0.2,
+            None => latency_ms as f64,
+        });
+    }
+
+    pub fn record_success(&self, latency_ms: u64, now: u64) {
+        self.update(|status| {
+            status.successes += 1;
+            status.consecutive_errors = 0;
+            status.down_since = None;
+            status.last_success_at = Some(now);
+            Self::record_latency(status, latency_ms);
+        });
+    }
+
+    /// Record a failed request; the node URL is redacted from `error` before it is stored
+    pub fn record_error(&self, error: &str, latency_ms: u64, now: u64) {
+        self.update(|status| {
+            let error = self.redact(error, &status.node);
+            if status.consecutive_errors == 0 {
+                status.failovers += 1;
+                status.down_since = Some(now);
+            }
+            status.errors += 1;
+            status.consecutive_errors += 1;
+            status.last_error = Some(error);
+            status.last_error_at = Some(now);
+            Self::record_latency(status, latency_ms);
+        });
+    }
+
+    /// Exclude the node from the fallback set, or include it again with `None`
+    pub fn set_excluded(&self, reason: Option<String>) {
+        self.update(|status| status.excluded = reason);
+    }
+
+    pub fn excluded(&self) -> Option<String> {
+        self.status.lock().unwrap_or_else(|e| e.into_inner()).excluded.clone()
+    }
+
+    pub fn status(&self) -> NodeStatus {
+        self.status.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+}
+
+/// RPC node status of one network
+#[derive(Debug, Clone, Serialize)]
+pub struct NetworkRpcStatus {
+    pub network_name: String,
+    pub nodes: Vec<NodeStatus>,
+}
+
+impl NetworkRpcStatus {
+    /// True if every node that received requests failed its latest one or is excluded
+    pub fn all_failing(&self) -> bool {
+        let mut used = self.nodes.iter().filter(|node| node.is_used() || node.excluded.is_some()).peekable();
+        used.peek().is_some() && used.all(|node| !node.is_up() || node.excluded.is_some())
+    }
+}
+
+/// Per-node RPC status of all networks, shared with the bot and the HTTP API
+#[derive(Debug, Default)]
+pub struct RpcHealth {
+    networks: RwLock<BTreeMap<String, Vec<Arc<RpcNode>>>>,
+}
+
+impl RpcHealth {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create trackers for a network's RPC nodes, in the same order as `urls`. A network
+    /// already registered with the same nodes keeps its trackers, so providers built from
+    /// another network's nodes share their health and exclusions
+    pub fn register(&self, network_name: &str, urls: &[Url]) -> Vec<Arc<RpcNode>> {
+        let mut networks = self.networks.write().unwrap_or_else(|e| e.into_inner());
+        if let Some(nodes) = networks.get(network_name) {
+            if nodes.iter().map(|node| &node.url).eq(urls) {
+                return nodes.clone();
+            }
+        }
+        let nodes: Vec<Arc<RpcNode>> = urls.iter().map(|url| Arc::new(RpcNode::new(url))).collect();
+        networks.insert(network_name.to_string(), nodes.clone());
+        nodes
+    }
+
+    /// Node status of a network
+    pub fn network(&self, network_name: &str) -> Option<NetworkRpcStatus> {
+        let networks = self.networks.read().unwrap_or_else(|e| e.into_inner());
+        networks.get(network_name).map(|nodes| NetworkRpcStatus {
+            network_name: network_name.to_string(),
+            nodes: nodes.iter().map(|node| node.status()).collect(),
+        })
+    }
+
+    /// Node status of all networks, sorted by name
+    pub fn all(&self) -> Vec<NetworkRpcStatus> {
+        let networks = self.networks.read().unwrap_or_else(|e| e.into_inner());
+        networks
+            .iter()
+            .map(|(name, nodes)| NetworkRpcStatus {
+                network_name: name.clone(),
+                nodes: nodes.iter().map(|node| node.status()).collect(),
+            })
+            .collect()
+    }
+}
+
+/// Transport wrapper recording the outcome and latency of every request
+#[derive(Debug, Clone)]
+pub struct TrackedTransport<S> {
+    inner: S,
+    node: Option<Arc<RpcNode>>,
+}
+
+impl<S> TrackedTransport<S> {
+    pub fn new(inner: S, node: Option<Arc<RpcNode>>) -> Self {
+        Self { inner, node }
+    }
+}
+
+impl<S> Service<RequestPacket> for TrackedTransport<S>
+where
+    S: Service<RequestPacket, Response = ResponsePacket, Error = TransportError, Future = TransportFut<'static>>,
+{
+    type Response = ResponsePacket;
+    type Error = TransportError;
+    type Future = TransportFut<'static>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: RequestPacket) -> Self::Future {
+        let Some(node) = self.node.clone() else {
+            return self.inner.call(req);
+        };
+        // Excluded nodes fail immediately so the fallback layer uses the others
+        if let Some(reason) = node.excluded() {
+            return Box::pin(async move { Err(TransportErrorKind::custom_str(&format!("node excluded: {}", reason))) });
+        }
+        let future = self.inner.call(req);
+
+        Box::pin(async move {
+            let started = Instant::now();
+            let result = future.await;
+            let latency_ms = started.elapsed().as_millis() as u64;
+            let now = chrono::Utc::now().timestamp() as u64;
+            match &result {
+                Ok(_) => node.record_success(latency_ms, now),
+                Err(e) => node.record_error(&e.to_string(), latency_ms, now),
+            }
+            result
+        })
+    }
+}
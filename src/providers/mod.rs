@@ -1,3 +1,12 @@
+mod chain_id;
 mod fallback;
+mod health;
+mod rate_limit;
+mod ws;
 
-pub use fallback::{create_fallback_provider, FallbackConfig};
+pub(crate) use chain_id::node_chain_id;
+pub use chain_id::{spawn_chain_id_checks, verify_chain_ids};
+pub use fallback::{create_fallback_provider, create_node_provider, FallbackConfig};
+pub use health::{NetworkRpcStatus, NodeStatus, RpcHealth, RpcNode, TrackedTransport};
+pub use rate_limit::{RateLimitedTransport, RateLimiter};
+pub use ws::create_ws_provider;
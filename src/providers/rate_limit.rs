@@ -0,0 +1,102 @@
+use alloy::{
+    rpc::json_rpc::{RequestPacket, ResponsePacket},
+    transports::{TransportError, TransportFut},
+};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tokio::time::Instant;
+use tower::Service;
+
+/// Spaces requests to at most `requests_per_second`, allowing a burst of up to
+/// one second's worth of requests after an idle period
+#[derive(Debug)]
+pub struct RateLimiter {
+    interval: Duration,
+    burst: Duration,
+    /// Time the next request would be sent at if requests were evenly spaced
+    next_slot: Mutex<Option<Instant>>,
+}
+
+impl RateLimiter {
+    pub fn new(requests_per_second: f64) -> Self {
+        let interval = Duration::from_secs_f64(1.0 / requests_per_second);
+        Self {
+            interval,
+            burst: Duration::from_secs(1).saturating_sub(interval),
+            next_slot: Mutex::new(None),
+        }
+    }
+
+    /// Reserve a slot and return how long to wait before using it
+    pub fn reserve(&self) -> Duration {
+        let now = Instant::now();
+        let mut next_slot = self.next_slot.lock().unwrap_or_else(|e| e.into_inner());
+        let slot = next_slot.map_or(now, |slot| slot.max(now));
+        *next_slot = Some(slot + self.interval);
+        slot.saturating_duration_since(now + self.burst)
+    }
+
+    /// True if a request could be sent without waiting
+    pub fn is_available(&self) -> bool {
+        let next_slot = self.next_slot.lock().unwrap_or_else(|e| e.into_inner());
+        next_slot.is_none_or(|slot| slot <= Instant::now() + self.burst)
+    }
+
+    /// Wait until a request may be sent
+    pub async fn acquire(&self) {
+        let wait = self.reserve();
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+/// Transport wrapper delaying requests to stay within a node's rate limit
+#[derive(Debug, Clone)]
+pub struct RateLimitedTransport<S> {
+    inner: S,
+    limiter: Option<Arc<RateLimiter>>,
+}
+
+impl<S> RateLimitedTransport<S> {
+    pub fn new(inner: S, requests_per_second: Option<f64>) -> Self {
+        Self {
+            inner,
+            limiter: requests_per_second.map(|rps| Arc::new(RateLimiter::new(rps))),
+        }
+    }
+
+    /// Rate limiter of the node, None without a limit
+    pub fn limiter(&self) -> Option<Arc<RateLimiter>> {
+        self.limiter.clone()
+    }
+}
+
+impl<S> Service<RequestPacket> for RateLimitedTransport<S>
+where
+    S: Service<RequestPacket, Response = ResponsePacket, Error = TransportError, Future = TransportFut<'static>>
+        + Clone
+        + Send
+        + 'static,
+{
+    type Response = ResponsePacket;
+    type Error = TransportError;
+    type Future = TransportFut<'static>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: RequestPacket) -> Self::Future {
+        let Some(limiter) = self.limiter.clone() else {
+            return self.inner.call(req);
+        };
+
+        let mut inner = self.inner.clone();
+        Box::pin(async move {
+            limiter.acquire().await;
+            inner.call(req).await
+        })
+    }
+}
@@ -0,0 +1,14 @@
+use alloy::{
+    providers::{Provider, ProviderBuilder, WsConnect},
+    transports::http::reqwest::Url,
+};
+use eyre::Result;
+
+/// Creates a WebSocket provider for block subscriptions
+pub async fn create_ws_provider(url: Url) -> Result<impl Provider> {
+    let provider = ProviderBuilder::new()
+        .connect_ws(WsConnect::new(url.as_str()))
+        .await?;
+
+    Ok(provider)
+}
@@ -0,0 +1,44 @@
+use super::atomic::write_atomic;
+use crate::config::AddressConfig;
+use eyre::Result;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+/// Address added at runtime (e.g. via the Telegram /add command)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DynamicAddress {
+    pub network: String,
+    #[serde(flatten)]
+    pub config: AddressConfig,
+}
+
+/// Storage for addresses added at runtime
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DynamicAddressStorage {
+    pub addresses: Vec<DynamicAddress>,
+}
+
+impl DynamicAddressStorage {
+    pub fn new() -> Self {
+        Self {
+            addresses: Vec::new(),
+        }
+    }
+
+    /// Load from file, return empty storage if file doesn't exist
+    pub fn load_from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Ok(Self::new());
+        }
+
+        let content = fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    /// Save to file
+    pub fn save_to_file<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        write_atomic(path.as_ref(), serde_json::to_string_pretty(&self)?.as_bytes())
+    }
+}
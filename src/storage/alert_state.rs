@@ -0,0 +1,127 @@
+use super::schema::{read_state, write_state};
+use crate::config::{AlertSeverity, ThrottleConfig};
+use eyre::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::SystemTime;
+
+/// Alert state for tracking when alerts were last sent
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AlertState {
+    /// Last time alert was sent (Unix timestamp in seconds)
+    pub last_sent: u64,
+    /// Number of alerts sent (used to determine next interval)
+    pub alert_count: u32,
+    /// Highest severity alerted in the current streak; a more severe threshold tier
+    /// alerts immediately and starts a new streak
+    #[serde(default)]
+    pub severity: Option<AlertSeverity>,
+}
+
+impl AlertState {
+    pub fn new() -> Self {
+        Self {
+            last_sent: 0,
+            alert_count: 0,
+            severity: None,
+        }
+    }
+
+    /// Human-readable hint about when the alert after the one being sent now will follow,
+    /// e.g. "Next alert in 10 minutes" or "Alerts every 20 hours"
+    pub(crate) fn next_alert_hint(&self, schedule: &ThrottleConfig) -> String {
+        let sent = self.alert_count + 1;
+        let interval = format_interval(schedule.interval_secs(sent));
+        if schedule.is_repeating(sent) {
+            format!("Alerts every {}", interval)
+        } else {
+            format!("Next alert in {}", interval)
+        }
+    }
+
+    /// When the next alert may be sent (Unix timestamp in seconds)
+    pub fn next_alert_at(&self, schedule: &ThrottleConfig) -> u64 {
+        self.last_sent + schedule.interval_secs(self.alert_count)
+    }
+
+    /// Check if enough time has passed to send another alert
+    pub(crate) fn should_send_alert(&self, schedule: &ThrottleConfig) -> bool {
+        let now = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+
+        now >= self.next_alert_at(schedule)
+    }
+
+    /// Record that an alert was sent
+    pub(crate) fn record_alert_sent(&mut self) {
+        self.last_sent = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        self.alert_count += 1;
+    }
+
+    /// Reset alert state (e.g., when balance goes back above threshold)
+    pub(crate) fn reset(&mut self) {
+        self.last_sent = 0;
+        self.alert_count = 0;
+        self.severity = None;
+    }
+}
+
+/// Storage for alert states
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AlertStateStorage {
+    /// Map of "network:alias" to alert state
+    pub states: HashMap<String, AlertState>,
+}
+
+impl AlertStateStorage {
+    pub fn new() -> Self {
+        Self {
+            states: HashMap::new(),
+        }
+    }
+
+    /// Load from file, return empty storage if file doesn't exist
+    pub fn load_from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Ok(Self::new());
+        }
+        read_state(path, None)
+    }
+
+    /// Save to file
+    pub fn save_to_file<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        write_state(path.as_ref(), self, None)
+    }
+
+    fn make_key(network: &str, alias: &str) -> String {
+        format!("{}:{}", network, alias)
+    }
+
+    pub(crate) fn get_or_create(&mut self, network: &str, alias: &str) -> &mut AlertState {
+        let key = Self::make_key(network, alias);
+        self.states.entry(key).or_default()
+    }
+}
+
+impl Default for AlertState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Format whole hours as "1 hour" or "5 hours", anything else in minutes
+fn format_interval(secs: u64) -> String {
+    let (amount, unit) = if secs >= 3600 && secs.is_multiple_of(3600) { (secs / 3600, "hour") } else { (secs / 60, "minute") };
+    if amount == 1 {
+        format!("1 {}", unit)
+    } else {
+        format!("{} {}s", amount, unit)
+    }
+}
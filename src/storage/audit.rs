@@ -0,0 +1,112 @@
+use eyre::Result;
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use tracing::warn;
+
+/// Default number of entries returned by an audit query
+const DEFAULT_LIMIT: usize = 50;
+
+/// Upper bound on entries returned by an audit query
+const MAX_LIMIT: usize = 1000;
+
+/// Single alert delivery attempt on one channel
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    /// Unix timestamp in seconds
+    pub sent_at: u64,
+    /// Notification channel (telegram, slack, discord, webhook, ...)
+    pub channel: String,
+    /// Chat ID for channels that deliver to several chats
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chat: Option<String>,
+    /// Alert kind (balance_change, low_balance, report, or MonitorAlert kind)
+    pub kind: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub network: Option<String>,
+    /// Short description of what the alert was about
+    pub subject: String,
+    /// SHA-256 of the alert content, identical across channels
+    pub content_hash: String,
+    pub success: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Filter for reading the audit log
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct AuditQuery {
+    /// Only entries sent at or after this Unix timestamp
+    pub since: Option<u64>,
+    /// Only entries sent at or before this Unix timestamp
+    pub until: Option<u64>,
+    pub network: Option<String>,
+    pub channel: Option<String>,
+    pub limit: Option<usize>,
+}
+
+impl AuditQuery {
+    /// Check if entry passes all filters
+    pub fn matches(&self, entry: &AuditEntry) -> bool {
+        self.since.is_none_or(|since| entry.sent_at >= since)
+            && self.until.is_none_or(|until| entry.sent_at <= until)
+            && self.network.as_ref().is_none_or(|network| entry.network.as_ref() == Some(network))
+            && self.channel.as_ref().is_none_or(|channel| &entry.channel == channel)
+    }
+
+    /// Maximum number of entries to return
+    pub fn limit(&self) -> usize {
+        self.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT)
+    }
+}
+
+/// Append entry to a JSON lines audit file
+pub fn append_audit_file<P: AsRef<Path>>(path: P, entry: &AuditEntry) -> Result<()> {
+    let path = path.as_ref();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", serde_json::to_string(entry)?)?;
+    Ok(())
+}
+
+/// Read every entry of a JSON lines audit file in the order they were appended
+pub fn read_audit_file<P: AsRef<Path>>(path: P) -> Result<Vec<AuditEntry>> {
+    query_entries(path.as_ref(), |_| true)
+}
+
+/// Read matching entries from a JSON lines audit file, newest first
+pub fn query_audit_file<P: AsRef<Path>>(path: P, query: &AuditQuery) -> Result<Vec<AuditEntry>> {
+    let mut entries = query_entries(path.as_ref(), |entry| query.matches(entry))?;
+
+    // Newest first; reversing before the stable sort puts later appends first within a second
+    entries.reverse();
+    entries.sort_by_key(|entry| Reverse(entry.sent_at));
+    entries.truncate(query.limit());
+    Ok(entries)
+}
+
+/// Entries of an audit file passing `filter`, in file order; corrupt lines are skipped
+fn query_entries(path: &Path, filter: impl Fn(&AuditEntry) -> bool) -> Result<Vec<AuditEntry>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let content = fs::read_to_string(path)?;
+    Ok(content
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .filter_map(|line| match serde_json::from_str::<AuditEntry>(line) {
+            Ok(entry) => Some(entry),
+            Err(e) => {
+                warn!("Skipping corrupt audit line: {}", e);
+                None
+            }
+        })
+        .filter(|entry| filter(entry))
+        .collect())
+}
@@ -0,0 +1,465 @@
+use super::atomic::write_atomic;
+use super::audit::{append_audit_file, query_audit_file, read_audit_file};
+use super::lease::LeaseRecord;
+use super::schema::{migrate_file, STATE_SCHEMA_VERSION};
+use super::{
+    AlertStateStorage, AuditEntry, AuditQuery, BalanceStorage, ChatStorage, CosmosStateStorage, DynamicAddressStorage,
+    EscalationStorage, PostgresBackend, TrackedAlert,
+};
+use crate::config::StorageConfig;
+use crate::encryption::StateCipher;
+use async_trait::async_trait;
+use eyre::Result;
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{File, OpenOptions, TryLockError};
+use std::io::{Seek, SeekFrom, Write};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::Mutex;
+
+/// Create the state backend selected in config. Registered chats are encrypted with
+/// `cipher` if given.
+pub async fn create_state_backend(
+    config: &StorageConfig,
+    data_dir: &str,
+    cipher: Option<StateCipher>,
+) -> Result<Arc<dyn StateBackend>> {
+    match config {
+        StorageConfig::File => {
+            std::fs::create_dir_all(data_dir)?;
+            let mut backend = FileBackend::new(data_dir);
+            if let Some(cipher) = cipher {
+                backend = backend.with_cipher(cipher);
+            }
+            Ok(Arc::new(backend))
+        }
+        StorageConfig::Postgres { url } => {
+            let mut backend = PostgresBackend::connect(url).await?;
+            if let Some(cipher) = cipher {
+                backend = backend.with_cipher(cipher);
+            }
+            Ok(Arc::new(backend))
+        }
+    }
+}
+
+/// Persistent storage for watcher state
+#[async_trait]
+pub trait StateBackend: Send + Sync {
+    /// Backend name used in logs
+    fn name(&self) -> &str;
+
+    async fn load_balances(&self) -> Result<BalanceStorage>;
+    async fn save_balances(&self, balances: &BalanceStorage) -> Result<()>;
+
+    /// Registered chats of a Telegram bot, `None` for the main `telegram` bot
+    async fn load_chats(&self, bot: Option<&str>) -> Result<ChatStorage>;
+    async fn save_chats(&self, bot: Option<&str>, chats: &ChatStorage) -> Result<()>;
+
+    async fn load_alert_states(&self) -> Result<AlertStateStorage>;
+    async fn save_alert_states(&self, states: &AlertStateStorage) -> Result<()>;
+
+    async fn load_dynamic_addresses(&self) -> Result<DynamicAddressStorage>;
+    async fn save_dynamic_addresses(&self, addresses: &DynamicAddressStorage) -> Result<()>;
+
+    /// Balances at the last daily report, None before the first report
+    async fn load_report_baseline(&self) -> Result<Option<BalanceStorage>>;
+    async fn save_report_baseline(&self, baseline: &BalanceStorage) -> Result<()>;
+    /// Drop the baseline, so the next report starts over as the first one
+    async fn clear_report_baseline(&self) -> Result<()>;
+
+    /// Acknowledgement and escalation state of critical alerts
+    async fn load_escalations(&self) -> Result<EscalationStorage>;
+    /// Replace the whole escalation state, e.g. when restoring a backup
+    async fn save_escalations(&self, escalations: &EscalationStorage) -> Result<()>;
+    /// Take the ID of a new tracked alert, unique across instances sharing the backend
+    async fn next_escalation_id(&self) -> Result<u64>;
+    /// Insert or update one tracked alert, leaving the others as they are
+    async fn save_escalation(&self, tracked: &TrackedAlert) -> Result<()>;
+    /// Forget tracked alerts by ID
+    async fn delete_escalations(&self, ids: &[u64]) -> Result<()>;
+
+    /// Last readings and alert state of Cosmos addresses
+    async fn load_cosmos_states(&self) -> Result<CosmosStateStorage>;
+    async fn save_cosmos_states(&self, states: &CosmosStateStorage) -> Result<()>;
+
+    /// Append an alert delivery record to the audit log
+    async fn append_audit(&self, entry: &AuditEntry) -> Result<()>;
+    /// Read audit entries matching query, newest first
+    async fn load_audit(&self, query: &AuditQuery) -> Result<Vec<AuditEntry>>;
+    /// Read the whole audit log, oldest first
+    async fn load_audit_log(&self) -> Result<Vec<AuditEntry>>;
+
+    /// Size in bytes of each state file or table
+    async fn storage_sizes(&self) -> Result<Vec<(String, u64)>>;
+
+    /// Upgrade stored state to the current schema, returning a line per upgrade.
+    /// Backends that migrate when connecting have nothing left to do.
+    async fn migrate_state(&self) -> Result<Vec<String>> {
+        Ok(Vec::new())
+    }
+
+    /// Take or renew lease `name` for `holder` for `duration`, atomically across
+    /// processes. Returns the other holder instead if it still holds the lease.
+    async fn acquire_lease(&self, name: &str, holder: &str, duration: Duration) -> Result<Option<String>>;
+    /// Give up lease `name` if `holder` still holds it
+    async fn release_lease(&self, name: &str, holder: &str) -> Result<()>;
+
+    /// Claim the alert identified by `network`, `subject` and `hash` for sending. False if
+    /// it was already claimed within the last `window`, e.g. by another instance.
+    async fn claim_alert(&self, network: &str, subject: &str, hash: &str, window: Duration) -> Result<bool>;
+
+    /// Drop the claim on an alert that couldn't be delivered, so it can be claimed again
+    async fn release_alert(&self, network: &str, subject: &str, hash: &str) -> Result<()>;
+}
+
+/// JSON files under data_dir (balances.json, telegram_chats.json, alert_states.json, dynamic_addresses.json,
+/// report_baseline.json, escalations.json, cosmos_states.json, audit.jsonl). Additional Telegram bots keep their chats in
+/// telegram_chats_<name>.json, instance leases live in <name>.lock and alert dedup claims in alert_dedup.json.
+///
+/// Every file is written atomically, and reads and writes go through one lock, so tasks
+/// sharing the backend never see or produce a partly written file. With a cipher,
+/// telegram_chats*.json are encrypted.
+///
+/// A lease is an exclusive OS lock on its lock file, held until it is released or the
+/// process exits, so two processes can never both take it. The file itself only records
+/// the holder and expiry for whoever finds it taken.
+pub struct FileBackend {
+    data_dir: PathBuf,
+    lock: Mutex<()>,
+    cipher: Option<StateCipher>,
+    /// Locked lease files by lease name, with their holder
+    leases: std::sync::Mutex<HashMap<String, (String, File)>>,
+}
+
+impl FileBackend {
+    pub fn new<P: Into<PathBuf>>(data_dir: P) -> Self {
+        Self {
+            data_dir: data_dir.into(),
+            lock: Mutex::new(()),
+            cipher: None,
+            leases: std::sync::Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Encrypt registered chats; plain chat files are still read and encrypted on the next save
+    pub fn with_cipher(mut self, cipher: StateCipher) -> Self {
+        self.cipher = Some(cipher);
+        self
+    }
+
+    /// Path of the balance snapshot file
+    pub fn balances_path(&self) -> PathBuf {
+        self.data_dir.join("balances.json")
+    }
+
+    fn chats_path(&self, bot: Option<&str>) -> PathBuf {
+        match bot {
+            Some(bot) => self.data_dir.join(format!("telegram_chats_{}.json", bot)),
+            None => self.data_dir.join("telegram_chats.json"),
+        }
+    }
+
+    fn alert_states_path(&self) -> PathBuf {
+        self.data_dir.join("alert_states.json")
+    }
+
+    fn dynamic_addresses_path(&self) -> PathBuf {
+        self.data_dir.join("dynamic_addresses.json")
+    }
+
+    fn report_baseline_path(&self) -> PathBuf {
+        self.data_dir.join("report_baseline.json")
+    }
+
+    fn escalations_path(&self) -> PathBuf {
+        self.data_dir.join("escalations.json")
+    }
+
+    fn cosmos_states_path(&self) -> PathBuf {
+        self.data_dir.join("cosmos_states.json")
+    }
+
+    fn audit_path(&self) -> PathBuf {
+        self.data_dir.join("audit.jsonl")
+    }
+
+    fn alert_dedup_path(&self) -> PathBuf {
+        self.data_dir.join("alert_dedup.json")
+    }
+
+    fn lease_path(&self, name: &str) -> PathBuf {
+        self.data_dir.join(format!("{}.lock", name))
+    }
+
+    /// Holder recorded in the lock file of lease `name`, None if it is empty or unreadable
+    fn lease_holder(&self, name: &str) -> Option<String> {
+        let content = std::fs::read_to_string(self.lease_path(name)).ok()?;
+        serde_json::from_str::<LeaseRecord>(&content).ok().map(|lease| lease.holder)
+    }
+
+    /// Versioned state files: balances, report baseline, alert states, Cosmos states and the
+    /// chats of every bot
+    fn versioned_paths(&self) -> Result<Vec<PathBuf>> {
+        let mut paths =
+            vec![self.balances_path(), self.report_baseline_path(), self.alert_states_path(), self.cosmos_states_path()];
+        if self.data_dir.is_dir() {
+            for entry in std::fs::read_dir(&self.data_dir)? {
+                let name = entry?.file_name().to_string_lossy().into_owned();
+                if name.starts_with("telegram_chats") && name.ends_with(".json") {
+                    paths.push(self.data_dir.join(name));
+                }
+            }
+        }
+        Ok(paths)
+    }
+}
+
+#[async_trait]
+impl StateBackend for FileBackend {
+    fn name(&self) -> &str {
+        "file"
+    }
+
+    async fn load_balances(&self) -> Result<BalanceStorage> {
+        let _guard = self.lock.lock().await;
+        BalanceStorage::load_from_file(self.balances_path())
+    }
+
+    async fn save_balances(&self, balances: &BalanceStorage) -> Result<()> {
+        let _guard = self.lock.lock().await;
+        balances.save_to_file(self.balances_path())
+    }
+
+    async fn load_chats(&self, bot: Option<&str>) -> Result<ChatStorage> {
+        let _guard = self.lock.lock().await;
+        ChatStorage::load_from_file(self.chats_path(bot), self.cipher.as_ref())
+    }
+
+    async fn save_chats(&self, bot: Option<&str>, chats: &ChatStorage) -> Result<()> {
+        let _guard = self.lock.lock().await;
+        chats.save_to_file(self.chats_path(bot), self.cipher.as_ref())
+    }
+
+    async fn load_alert_states(&self) -> Result<AlertStateStorage> {
+        let _guard = self.lock.lock().await;
+        AlertStateStorage::load_from_file(self.alert_states_path())
+    }
+
+    async fn save_alert_states(&self, states: &AlertStateStorage) -> Result<()> {
+        let _guard = self.lock.lock().await;
+        states.save_to_file(self.alert_states_path())
+    }
+
+    async fn load_dynamic_addresses(&self) -> Result<DynamicAddressStorage> {
+        let _guard = self.lock.lock().await;
+        DynamicAddressStorage::load_from_file(self.dynamic_addresses_path())
+    }
+
+    async fn save_dynamic_addresses(&self, addresses: &DynamicAddressStorage) -> Result<()> {
+        let _guard = self.lock.lock().await;
+        addresses.save_to_file(self.dynamic_addresses_path())
+    }
+
+    async fn load_report_baseline(&self) -> Result<Option<BalanceStorage>> {
+        let _guard = self.lock.lock().await;
+        let path = self.report_baseline_path();
+        if !path.exists() {
+            return Ok(None);
+        }
+        Ok(Some(BalanceStorage::load_from_file(path)?))
+    }
+
+    async fn save_report_baseline(&self, baseline: &BalanceStorage) -> Result<()> {
+        let _guard = self.lock.lock().await;
+        baseline.save_to_file(self.report_baseline_path())
+    }
+
+    async fn clear_report_baseline(&self) -> Result<()> {
+        let _guard = self.lock.lock().await;
+        match std::fs::remove_file(self.report_baseline_path()) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+
+    async fn load_escalations(&self) -> Result<EscalationStorage> {
+        let _guard = self.lock.lock().await;
+        EscalationStorage::load_from_file(self.escalations_path())
+    }
+
+    async fn save_escalations(&self, escalations: &EscalationStorage) -> Result<()> {
+        let _guard = self.lock.lock().await;
+        escalations.save_to_file(self.escalations_path())
+    }
+
+    async fn next_escalation_id(&self) -> Result<u64> {
+        let _guard = self.lock.lock().await;
+        let mut escalations = EscalationStorage::load_from_file(self.escalations_path())?;
+        escalations.next_id += 1;
+        escalations.save_to_file(self.escalations_path())?;
+        Ok(escalations.next_id)
+    }
+
+    async fn save_escalation(&self, tracked: &TrackedAlert) -> Result<()> {
+        let _guard = self.lock.lock().await;
+        let mut escalations = EscalationStorage::load_from_file(self.escalations_path())?;
+        match escalations.get_mut(tracked.id) {
+            Some(stored) => *stored = tracked.clone(),
+            None => escalations.alerts.push(tracked.clone()),
+        }
+        escalations.save_to_file(self.escalations_path())
+    }
+
+    async fn delete_escalations(&self, ids: &[u64]) -> Result<()> {
+        let _guard = self.lock.lock().await;
+        let mut escalations = EscalationStorage::load_from_file(self.escalations_path())?;
+        escalations.alerts.retain(|tracked| !ids.contains(&tracked.id));
+        escalations.save_to_file(self.escalations_path())
+    }
+
+    async fn load_cosmos_states(&self) -> Result<CosmosStateStorage> {
+        let _guard = self.lock.lock().await;
+        CosmosStateStorage::load_from_file(self.cosmos_states_path())
+    }
+
+    async fn save_cosmos_states(&self, states: &CosmosStateStorage) -> Result<()> {
+        let _guard = self.lock.lock().await;
+        states.save_to_file(self.cosmos_states_path())
+    }
+
+    async fn append_audit(&self, entry: &AuditEntry) -> Result<()> {
+        let _guard = self.lock.lock().await;
+        append_audit_file(self.audit_path(), entry)
+    }
+
+    async fn load_audit(&self, query: &AuditQuery) -> Result<Vec<AuditEntry>> {
+        let _guard = self.lock.lock().await;
+        query_audit_file(self.audit_path(), query)
+    }
+
+    async fn load_audit_log(&self) -> Result<Vec<AuditEntry>> {
+        let _guard = self.lock.lock().await;
+        read_audit_file(self.audit_path())
+    }
+
+    async fn storage_sizes(&self) -> Result<Vec<(String, u64)>> {
+        let paths = [
+            self.balances_path(),
+            self.chats_path(None),
+            self.alert_states_path(),
+            self.dynamic_addresses_path(),
+            self.report_baseline_path(),
+            self.escalations_path(),
+            self.cosmos_states_path(),
+            self.audit_path(),
+        ];
+        Ok(paths
+            .iter()
+            .filter_map(|path| {
+                let size = std::fs::metadata(path).ok()?.len();
+                Some((path.file_name()?.to_string_lossy().into_owned(), size))
+            })
+            .collect())
+    }
+
+    async fn migrate_state(&self) -> Result<Vec<String>> {
+        let _guard = self.lock.lock().await;
+        let mut upgraded = Vec::new();
+        for path in self.versioned_paths()? {
+            if let Some(version) = migrate_file(&path, self.cipher.as_ref())? {
+                let name = path.file_name().unwrap_or_default().to_string_lossy();
+                upgraded.push(format!("{}: schema version {} → {}", name, version, STATE_SCHEMA_VERSION));
+            }
+        }
+        Ok(upgraded)
+    }
+
+    async fn acquire_lease(&self, name: &str, holder: &str, duration: Duration) -> Result<Option<String>> {
+        let _guard = self.lock.lock().await;
+        let lease = LeaseRecord {
+            holder: holder.to_string(),
+            expires_at: SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() + duration.as_secs(),
+        };
+
+        let mut leases = self.leases.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some((current, file)) = leases.get_mut(name) {
+            if current != holder {
+                return Ok(Some(current.clone()));
+            }
+            write_lease(file, &lease)?;
+            return Ok(None);
+        }
+
+        // The lock file is never replaced or deleted: the OS lock belongs to its inode
+        let mut file = OpenOptions::new().read(true).write(true).create(true).truncate(false).open(self.lease_path(name))?;
+        match file.try_lock() {
+            Ok(()) => {}
+            Err(TryLockError::WouldBlock) => {
+                return Ok(Some(self.lease_holder(name).unwrap_or_else(|| "another instance".to_string())));
+            }
+            Err(TryLockError::Error(e)) => return Err(e.into()),
+        }
+        write_lease(&mut file, &lease)?;
+        leases.insert(name.to_string(), (holder.to_string(), file));
+        Ok(None)
+    }
+
+    async fn release_lease(&self, name: &str, holder: &str) -> Result<()> {
+        let _guard = self.lock.lock().await;
+        let mut leases = self.leases.lock().unwrap_or_else(|e| e.into_inner());
+        if leases.get(name).is_some_and(|(current, _)| current == holder) {
+            if let Some((_, file)) = leases.remove(name) {
+                // Closing the file releases the lock
+                file.set_len(0)?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn claim_alert(&self, network: &str, subject: &str, hash: &str, window: Duration) -> Result<bool> {
+        let _guard = self.lock.lock().await;
+        let path = self.alert_dedup_path();
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+
+        // Claim time by network/subject/hash; a corrupt file only costs a duplicate alert
+        let mut claims: BTreeMap<String, u64> = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        claims.retain(|_, claimed_at| *claimed_at + window.as_secs() > now);
+
+        let key = format!("{}/{}/{}", network, subject, hash);
+        if claims.contains_key(&key) {
+            return Ok(false);
+        }
+        claims.insert(key, now);
+        write_atomic(&path, &serde_json::to_vec(&claims)?)?;
+        Ok(true)
+    }
+
+    async fn release_alert(&self, network: &str, subject: &str, hash: &str) -> Result<()> {
+        let _guard = self.lock.lock().await;
+        let path = self.alert_dedup_path();
+        let Some(mut claims) = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str::<BTreeMap<String, u64>>(&content).ok())
+        else {
+            return Ok(());
+        };
+        if claims.remove(&format!("{}/{}/{}", network, subject, hash)).is_some() {
+            write_atomic(&path, &serde_json::to_vec(&claims)?)?;
+        }
+        Ok(())
+    }
+}
+
+/// Replace the record in a locked lease file in place
+fn write_lease(file: &mut File, lease: &LeaseRecord) -> Result<()> {
+    file.set_len(0)?;
+    file.seek(SeekFrom::Start(0))?;
+    file.write_all(&serde_json::to_vec(lease)?)?;
+    file.sync_data()?;
+    Ok(())
+}
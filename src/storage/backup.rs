@@ -0,0 +1,247 @@
+use super::atomic::write_atomic;
+use super::s3::S3Client;
+use super::schema::{decode_state, encode_state};
+use super::{
+    AlertStateStorage, AuditEntry, AuditQuery, BalanceStorage, ChatStorage, CosmosStateStorage, DynamicAddressStorage,
+    EscalationStorage, HistoryPoint, StateBackend,
+};
+use crate::config::{is_bot_name, BackupConfig};
+use crate::encryption::StateCipher;
+use chrono::Utc;
+use eyre::Result;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tracing::{info, warn};
+
+/// File name prefix of scheduled backups in data_dir/backups
+const BACKUP_PREFIX: &str = "oxwatcher-backup-";
+
+/// File name extensions of scheduled backups: gzip-compressed, or plain JSON as written
+/// by earlier versions
+const BACKUP_EXTENSIONS: [&str; 2] = [".json.gz", ".json"];
+
+/// First bytes of gzip data
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Everything the watcher persists, in one gzip-compressed JSON document. Restoring it on a new host
+/// keeps balances, alert throttling, chats and history where the old host left off,
+/// and works across storage backends.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StateBackup {
+    /// Unix timestamp of the backup
+    pub created_at: u64,
+    pub balances: BalanceStorage,
+    #[serde(default)]
+    pub report_baseline: Option<BalanceStorage>,
+    /// Chats of the main `telegram` bot
+    pub chats: ChatStorage,
+    /// Chats of the `telegram_bots` by name
+    #[serde(default)]
+    pub bot_chats: BTreeMap<String, ChatStorage>,
+    pub alert_states: AlertStateStorage,
+    pub dynamic_addresses: DynamicAddressStorage,
+    pub escalations: EscalationStorage,
+    /// Last readings and alert state of Cosmos addresses
+    #[serde(default)]
+    pub cosmos_states: CosmosStateStorage,
+    /// Alert delivery audit log, oldest first
+    #[serde(default)]
+    pub audit: Vec<AuditEntry>,
+    /// Balance history, oldest first
+    #[serde(default)]
+    pub history: Vec<HistoryPoint>,
+}
+
+impl StateBackup {
+    /// Read all state from `backend`, the chats of each bot in `bots` and the history file
+    pub async fn collect(backend: &dyn StateBackend, bots: &[String], history_path: &Path) -> Result<Self> {
+        let mut bot_chats = BTreeMap::new();
+        for bot in bots {
+            bot_chats.insert(bot.clone(), backend.load_chats(Some(bot)).await?);
+        }
+
+        Ok(Self {
+            created_at: SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs(),
+            balances: backend.load_balances().await?,
+            report_baseline: backend.load_report_baseline().await?,
+            chats: backend.load_chats(None).await?,
+            bot_chats,
+            alert_states: backend.load_alert_states().await?,
+            dynamic_addresses: backend.load_dynamic_addresses().await?,
+            escalations: backend.load_escalations().await?,
+            cosmos_states: backend.load_cosmos_states().await?,
+            audit: backend.load_audit_log().await?,
+            history: read_history(history_path)?,
+        })
+    }
+
+    /// Write the backup to `backend` and the history file, replacing their state.
+    /// Audit entries are appended, skipping those not newer than the latest entry
+    /// already logged, so restoring twice doesn't duplicate them. Nothing is written if
+    /// a bot name in the backup isn't valid, since the names end up in file names.
+    pub async fn restore(&self, backend: &dyn StateBackend, history_path: &Path) -> Result<()> {
+        if let Some(bot) = self.bot_chats.keys().find(|bot| !is_bot_name(bot)) {
+            eyre::bail!("backup has chats of bot '{}', but bot names may only contain letters, digits, - and _", bot);
+        }
+
+        backend.save_balances(&self.balances).await?;
+        match &self.report_baseline {
+            Some(baseline) => backend.save_report_baseline(baseline).await?,
+            None => backend.clear_report_baseline().await?,
+        }
+        backend.save_chats(None, &self.chats).await?;
+        for (bot, chats) in &self.bot_chats {
+            backend.save_chats(Some(bot), chats).await?;
+        }
+        backend.save_alert_states(&self.alert_states).await?;
+        backend.save_dynamic_addresses(&self.dynamic_addresses).await?;
+        backend.save_escalations(&self.escalations).await?;
+        backend.save_cosmos_states(&self.cosmos_states).await?;
+
+        let latest = AuditQuery { limit: Some(1), ..Default::default() };
+        let logged_until = backend.load_audit(&latest).await?.first().map(|entry| entry.sent_at);
+        for entry in self.audit.iter().filter(|entry| logged_until.is_none_or(|until| entry.sent_at > until)) {
+            backend.append_audit(entry).await?;
+        }
+
+        let mut content = String::new();
+        for point in &self.history {
+            content.push_str(&serde_json::to_string(point)?);
+            content.push('\n');
+        }
+        write_atomic(history_path, content.as_bytes())
+    }
+
+    /// Counts of what the backup holds, for logs and command output
+    pub fn summary(&self) -> String {
+        let chats = self.chats.registrations.len()
+            + self.bot_chats.values().map(|chats| chats.registrations.len()).sum::<usize>();
+        format!(
+            "{} balances, {} chats, {} alert states, {} added addresses, {} audit entries, {} history points",
+            self.balances.balances.len(),
+            chats,
+            self.alert_states.states.len(),
+            self.dynamic_addresses.addresses.len(),
+            self.audit.len(),
+            self.history.len()
+        )
+    }
+
+    /// Load a backup, compressed or plain JSON, failing for backups of a newer schema
+    /// version. Encrypted backups need `cipher`.
+    pub fn load_from_file<P: AsRef<Path>>(path: P, cipher: Option<&StateCipher>) -> Result<Self> {
+        let path = path.as_ref();
+        let mut content = fs::read(path)?;
+        if content.starts_with(&GZIP_MAGIC) {
+            let mut json = Vec::new();
+            GzDecoder::new(content.as_slice()).read_to_end(&mut json)?;
+            content = json;
+        }
+        decode_state(&content, path, cipher)
+    }
+
+    /// Save the backup gzip-compressed with the current schema version, encrypted with
+    /// `cipher` if given
+    pub fn save_to_file<P: AsRef<Path>>(&self, path: P, cipher: Option<&StateCipher>) -> Result<()> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&encode_state(self, cipher)?)?;
+        write_atomic(path.as_ref(), &encoder.finish()?)
+    }
+}
+
+/// Points of a history file in file order, skipping corrupt lines
+fn read_history(path: &Path) -> Result<Vec<HistoryPoint>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    Ok(fs::read_to_string(path)?
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| match serde_json::from_str(line) {
+            Ok(point) => Some(point),
+            Err(e) => {
+                warn!("Skipping corrupt history line: {}", e);
+                None
+            }
+        })
+        .collect())
+}
+
+/// Back up all state every `interval_hours` to `data_dir/backups`, keeping the latest
+/// `keep` files, and upload each backup to S3 if configured. Backups are encrypted
+/// with `cipher` if given.
+pub fn spawn_backups(
+    config: BackupConfig,
+    backend: Arc<dyn StateBackend>,
+    bots: Vec<String>,
+    data_dir: PathBuf,
+    history_path: PathBuf,
+    cipher: Option<StateCipher>,
+) {
+    let s3 = config.s3.clone().map(S3Client::new);
+
+    tokio::spawn(async move {
+        let interval = Duration::from_secs(config.interval_hours * 3600);
+        let dir = data_dir.join("backups");
+        info!("🗄️  Backing up state every {} hour(s) to {}", config.interval_hours, dir.display());
+
+        loop {
+            tokio::time::sleep(interval).await;
+
+            let name = format!("{}{}{}", BACKUP_PREFIX, Utc::now().format("%Y%m%dT%H%M%SZ"), BACKUP_EXTENSIONS[0]);
+            let backup = match StateBackup::collect(backend.as_ref(), &bots, &history_path).await {
+                Ok(backup) => backup,
+                Err(e) => {
+                    warn!("Failed to read state for backup: {}", e);
+                    continue;
+                }
+            };
+            let path = dir.join(&name);
+            if let Err(e) = fs::create_dir_all(&dir).map_err(Into::into).and_then(|_| backup.save_to_file(&path, cipher.as_ref())) {
+                warn!("Failed to write backup {}: {}", path.display(), e);
+                continue;
+            }
+            info!("🗄️  Backed up state to {}: {}", path.display(), backup.summary());
+            if let Err(e) = prune_backups(&dir, config.keep) {
+                warn!("Failed to delete old backups: {}", e);
+            }
+
+            if let Some(s3) = &s3 {
+                let uploaded = match fs::read(&path) {
+                    Ok(body) => s3.put_object(&name, body).await,
+                    Err(e) => Err(e.into()),
+                };
+                match uploaded {
+                    Ok(()) => info!("🗄️  Uploaded backup {} to S3", name),
+                    Err(e) => warn!("Failed to upload backup {} to S3: {}", name, e),
+                }
+            }
+        }
+    });
+}
+
+/// Delete all but the newest `keep` backups in `dir`
+pub fn prune_backups(dir: &Path, keep: usize) -> Result<()> {
+    // Names embed the UTC time, so they sort oldest first
+    let mut backups: Vec<PathBuf> = fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with(BACKUP_PREFIX) && BACKUP_EXTENSIONS.iter().any(|ext| name.ends_with(ext)))
+        })
+        .collect();
+    backups.sort();
+    for old in &backups[..backups.len().saturating_sub(keep)] {
+        fs::remove_file(old)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,97 @@
+use super::schema::{read_state, write_state};
+use crate::monitoring::BalanceInfo;
+use alloy::primitives::B256;
+use eyre::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Storage for balance snapshots
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BalanceStorage {
+    /// Map of "network:alias" to balance info
+    pub balances: HashMap<String, BalanceInfo>,
+    /// Map of "network:alias" to when its balances last changed (Unix timestamp),
+    /// or were first read
+    #[serde(default)]
+    pub last_changed: HashMap<String, u64>,
+}
+
+impl BalanceStorage {
+    /// Create new empty storage
+    pub fn new() -> Self {
+        Self {
+            balances: HashMap::new(),
+            last_changed: HashMap::new(),
+        }
+    }
+
+    /// Load from file, return empty storage if file doesn't exist
+    pub fn load_from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+
+        if !path.exists() {
+            return Ok(Self::new());
+        }
+
+        read_state(path, None)
+    }
+
+    /// Save to file
+    pub fn save_to_file<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        write_state(path.as_ref(), self, None)
+    }
+
+    /// Generate storage key from network name and alias
+    fn make_key(network_name: &str, alias: &str) -> String {
+        format!("{}:{}", network_name, alias)
+    }
+
+    /// Update with new balance info
+    pub fn update(&mut self, info: &BalanceInfo) {
+        self.update_at(info, chrono::Utc::now().timestamp() as u64);
+    }
+
+    /// Update with balance info read at `now`, recording it as the last change
+    /// if any balance differs from the stored one
+    pub fn update_at(&mut self, info: &BalanceInfo, now: u64) {
+        let key = Self::make_key(&info.network_name, &info.alias);
+        let changed = self.balances.get(&key).is_none_or(|previous| !previous.same_balances(info));
+        if changed || !self.last_changed.contains_key(&key) {
+            self.last_changed.insert(key.clone(), now);
+        }
+        self.balances.insert(key, info.clone());
+    }
+
+    /// When balances of an address last changed, or were first read
+    pub fn last_changed(&self, network_name: &str, alias: &str) -> Option<u64> {
+        self.last_changed.get(&Self::make_key(network_name, alias)).copied()
+    }
+
+    /// Remove stored balance, e.g. when an address is no longer monitored
+    pub fn remove(&mut self, network_name: &str, alias: &str) -> Option<BalanceInfo> {
+        let key = Self::make_key(network_name, alias);
+        self.last_changed.remove(&key);
+        self.balances.remove(&key)
+    }
+
+    /// Get previous balance by network name and alias
+    pub fn get(&self, network_name: &str, alias: &str) -> Option<&BalanceInfo> {
+        let key = Self::make_key(network_name, alias);
+        self.balances.get(&key)
+    }
+
+    /// Most recent block number and hash that balances on a network were read at
+    pub fn latest_block(&self, network_name: &str) -> Option<(u64, B256)> {
+        self.balances.values()
+            .filter(|info| info.network_name == network_name)
+            .filter_map(|info| Some((info.block_number?, info.block_hash?)))
+            .max_by_key(|&(number, _)| number)
+    }
+}
+
+impl Default for BalanceStorage {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,86 @@
+use super::schema::{read_state, write_state};
+use crate::encryption::StateCipher;
+use eyre::Result;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Alert filter for a chat. Unset fields match anything.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChatSubscription {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub network: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alias: Option<String>,
+}
+
+impl ChatSubscription {
+    /// Check if alerts for an address match this filter
+    pub fn matches(&self, network: &str, alias: &str) -> bool {
+        self.network.as_deref().is_none_or(|n| n == network) && self.alias.as_deref().is_none_or(|a| a == alias)
+    }
+
+    /// Human-readable description, e.g. "Ethereum / treasury" or "* / treasury"
+    pub fn describe(&self) -> String {
+        format!("{} / {}", self.network.as_deref().unwrap_or("*"), self.alias.as_deref().unwrap_or("*"))
+    }
+}
+
+/// Registration information for a chat
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatRegistration {
+    pub chat_id: i64,
+    pub user_id: i64,
+    pub username: String,
+    /// Alert filters; an empty list receives all alerts
+    #[serde(default)]
+    pub subscriptions: Vec<ChatSubscription>,
+    /// Balance change alerts are suppressed until this time (Unix timestamp in seconds)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub muted_until: Option<u64>,
+}
+
+impl ChatRegistration {
+    /// Check if this chat wants alerts for an address
+    pub fn wants(&self, network: &str, alias: &str) -> bool {
+        self.subscriptions.is_empty() || self.subscriptions.iter().any(|s| s.matches(network, alias))
+    }
+
+    /// Check if this chat wants network-level alerts (e.g. gas prices)
+    pub fn wants_network(&self, network: &str) -> bool {
+        self.subscriptions.is_empty()
+            || self.subscriptions.iter().any(|s| s.network.as_deref().is_none_or(|n| n == network))
+    }
+
+    /// Check if alerts are muted at the given Unix timestamp
+    pub fn is_muted(&self, now: u64) -> bool {
+        self.muted_until.is_some_and(|until| now < until)
+    }
+}
+
+/// Storage for registered chat IDs
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ChatStorage {
+    pub registrations: Vec<ChatRegistration>,
+}
+
+impl ChatStorage {
+    pub fn new() -> Self {
+        Self {
+            registrations: Vec::new(),
+        }
+    }
+
+    /// Load from file, return empty storage if file doesn't exist. Encrypted files need `cipher`.
+    pub fn load_from_file<P: AsRef<Path>>(path: P, cipher: Option<&StateCipher>) -> Result<Self> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Ok(Self::new());
+        }
+        read_state(path, cipher)
+    }
+
+    /// Save to file, encrypted with `cipher` if given
+    pub fn save_to_file<P: AsRef<Path>>(&self, path: P, cipher: Option<&StateCipher>) -> Result<()> {
+        write_state(path.as_ref(), self, cipher)
+    }
+}
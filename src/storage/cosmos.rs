@@ -0,0 +1,55 @@
+use super::schema::{read_state, write_state};
+use super::AlertState;
+use alloy::primitives::U256;
+use eyre::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+/// Last reading and alert state of a Cosmos address
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CosmosAddressState {
+    /// Balances by denom in base units, None before the first reading
+    #[serde(default)]
+    pub balances: Option<HashMap<String, U256>>,
+    #[serde(default)]
+    pub low_alerts: AlertState,
+    /// Denoms whose staking rewards are above their claim threshold
+    #[serde(default)]
+    pub rewards_ready: HashSet<String>,
+}
+
+/// Storage for the state of Cosmos addresses
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CosmosStateStorage {
+    /// Map of "chain:alias" to address state
+    pub states: HashMap<String, CosmosAddressState>,
+}
+
+impl CosmosStateStorage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load from file, return empty storage if file doesn't exist
+    pub fn load_from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Ok(Self::new());
+        }
+        read_state(path, None)
+    }
+
+    /// Save to file
+    pub fn save_to_file<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        write_state(path.as_ref(), self, None)
+    }
+
+    fn make_key(chain: &str, alias: &str) -> String {
+        format!("{}:{}", chain, alias)
+    }
+
+    pub(crate) fn get_or_create(&mut self, chain: &str, alias: &str) -> &mut CosmosAddressState {
+        self.states.entry(Self::make_key(chain, alias)).or_default()
+    }
+}
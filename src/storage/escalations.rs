@@ -0,0 +1,80 @@
+use super::atomic::write_atomic;
+use crate::notifiers::MonitorAlert;
+use eyre::Result;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+/// Who acknowledged a critical alert and when
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Acknowledgement {
+    pub by: String,
+    pub at: u64,
+}
+
+/// Critical alert waiting for an acknowledgement, and what was done about it so far
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrackedAlert {
+    pub id: u64,
+    pub alert: MonitorAlert,
+    /// Channels the alert was sent to, empty for all
+    #[serde(default)]
+    pub channels: Vec<String>,
+    pub raised_at: u64,
+    /// Last time the alert was sent or escalated (Unix timestamp in seconds)
+    pub last_sent: u64,
+    /// Re-sends to the original channels so far
+    #[serde(default)]
+    pub resends: u32,
+    #[serde(default)]
+    pub escalated_at: Option<u64>,
+    #[serde(default)]
+    pub acknowledged: Option<Acknowledgement>,
+}
+
+impl TrackedAlert {
+    /// Neither acknowledged nor escalated yet
+    pub fn is_open(&self) -> bool {
+        self.acknowledged.is_none() && self.escalated_at.is_none()
+    }
+}
+
+/// Lifecycle state of critical alerts
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EscalationStorage {
+    /// ID given to the next tracked alert
+    #[serde(default)]
+    pub next_id: u64,
+    #[serde(default)]
+    pub alerts: Vec<TrackedAlert>,
+}
+
+impl EscalationStorage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load from file, return empty storage if file doesn't exist
+    pub fn load_from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Ok(Self::new());
+        }
+
+        let content = fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    /// Save to file
+    pub fn save_to_file<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        write_atomic(path.as_ref(), serde_json::to_string_pretty(&self)?.as_bytes())
+    }
+
+    pub fn get(&self, id: u64) -> Option<&TrackedAlert> {
+        self.alerts.iter().find(|tracked| tracked.id == id)
+    }
+
+    pub(crate) fn get_mut(&mut self, id: u64) -> Option<&mut TrackedAlert> {
+        self.alerts.iter_mut().find(|tracked| tracked.id == id)
+    }
+}
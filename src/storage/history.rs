@@ -0,0 +1,156 @@
+use super::atomic::write_atomic;
+use super::BalanceStorage;
+use crate::monitoring::BalanceInfo;
+use eyre::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tracing::warn;
+
+/// Timestamped balance point
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryPoint {
+    /// Unix timestamp in seconds
+    pub timestamp: u64,
+    pub balance: BalanceInfo,
+}
+
+/// Append-only balance history stored as JSON lines.
+///
+/// A point is recorded when a balance changes, or when the last recorded
+/// point is older than `sample_interval`. Points older than `retention` are
+/// pruned from memory and the file.
+#[derive(Debug, Clone)]
+pub struct HistoryStore {
+    path: PathBuf,
+    retention: Duration,
+    sample_interval: Duration,
+    /// Map of "network:alias" to points sorted by timestamp
+    series: HashMap<String, Vec<HistoryPoint>>,
+    last_pruned: u64,
+}
+
+impl HistoryStore {
+    /// Load history from file, return empty history if file doesn't exist
+    pub fn load_from_file<P: AsRef<Path>>(path: P, retention: Duration, sample_interval: Duration) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let mut series: HashMap<String, Vec<HistoryPoint>> = HashMap::new();
+
+        if path.exists() {
+            let content = fs::read_to_string(&path)?;
+            for line in content.lines().filter(|l| !l.trim().is_empty()) {
+                match serde_json::from_str::<HistoryPoint>(line) {
+                    Ok(point) => {
+                        let key = Self::make_key(&point.balance.network_name, &point.balance.alias);
+                        series.entry(key).or_default().push(point);
+                    }
+                    Err(e) => warn!("Skipping corrupt history line: {}", e),
+                }
+            }
+            for points in series.values_mut() {
+                points.sort_by_key(|p| p.timestamp);
+            }
+        }
+
+        Ok(Self {
+            path,
+            retention,
+            sample_interval,
+            series,
+            last_pruned: 0,
+        })
+    }
+
+    /// Generate series key from network name and alias
+    fn make_key(network_name: &str, alias: &str) -> String {
+        format!("{}:{}", network_name, alias)
+    }
+
+    /// Record balance at timestamp. Returns true if a point was appended.
+    pub fn record(&mut self, info: &BalanceInfo, timestamp: u64) -> Result<bool> {
+        let key = Self::make_key(&info.network_name, &info.alias);
+        let points = self.series.entry(key).or_default();
+
+        if let Some(last) = points.last() {
+            let unchanged = last.balance.same_balances(info);
+            let recent = timestamp < last.timestamp + self.sample_interval.as_secs();
+            if unchanged && recent {
+                return Ok(false);
+            }
+        }
+
+        let point = HistoryPoint {
+            timestamp,
+            balance: info.clone(),
+        };
+
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        writeln!(file, "{}", serde_json::to_string(&point)?)?;
+        points.push(point);
+
+        // Prune at most once per hour
+        if timestamp >= self.last_pruned + 3600 {
+            self.prune(timestamp)?;
+        }
+
+        Ok(true)
+    }
+
+    /// Drop points older than the retention period and rewrite the file
+    pub fn prune(&mut self, now: u64) -> Result<()> {
+        let cutoff = now.saturating_sub(self.retention.as_secs());
+        let mut removed = false;
+
+        for points in self.series.values_mut() {
+            // Keep the newest point before the cutoff so get_at still works at the edge
+            let first_kept = points.iter().position(|p| p.timestamp >= cutoff).unwrap_or(points.len());
+            let drop_count = first_kept.saturating_sub(1);
+            if drop_count > 0 {
+                points.drain(..drop_count);
+                removed = true;
+            }
+        }
+        self.series.retain(|_, points| !points.is_empty());
+        self.last_pruned = now;
+
+        if removed {
+            let mut content = String::new();
+            for point in self.series.values().flatten() {
+                content.push_str(&serde_json::to_string(point)?);
+                content.push('\n');
+            }
+            write_atomic(&self.path, content.as_bytes())?;
+        }
+
+        Ok(())
+    }
+
+    /// Get points for an address within [from, to]
+    pub fn get_range(&self, network_name: &str, alias: &str, from: u64, to: u64) -> Vec<&HistoryPoint> {
+        let key = Self::make_key(network_name, alias);
+        self.series
+            .get(&key)
+            .map(|points| points.iter().filter(|p| p.timestamp >= from && p.timestamp <= to).collect())
+            .unwrap_or_default()
+    }
+
+    /// Get balance as of timestamp (latest point at or before it)
+    pub fn get_at(&self, network_name: &str, alias: &str, timestamp: u64) -> Option<&HistoryPoint> {
+        let key = Self::make_key(network_name, alias);
+        self.series.get(&key)?.iter().rev().find(|p| p.timestamp <= timestamp)
+    }
+
+    /// Build a snapshot of all balances as of timestamp, usable as a report baseline
+    pub fn snapshot_at(&self, timestamp: u64) -> BalanceStorage {
+        let mut storage = BalanceStorage::new();
+        for points in self.series.values() {
+            if let Some(point) = points.iter().rev().find(|p| p.timestamp <= timestamp) {
+                storage.update(&point.balance);
+            }
+        }
+        storage
+    }
+}
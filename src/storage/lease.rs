@@ -0,0 +1,138 @@
+use super::StateBackend;
+use crate::config::LeaderElectionConfig;
+use eyre::{eyre, Report, Result};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::Instant;
+use tracing::{info, warn};
+
+/// Lease as stored in a lock file of the file backend
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct LeaseRecord {
+    pub holder: String,
+    /// Unix timestamp the lease expires at unless renewed
+    pub expires_at: u64,
+}
+
+/// Lease letting one watcher instance at a time work on shared state, so two
+/// instances on the same data_dir or database don't send every alert twice.
+/// The holder renews it while running; standbys take over once it expires.
+pub struct InstanceLease {
+    backend: Arc<dyn StateBackend>,
+    name: String,
+    holder: String,
+    duration: Duration,
+}
+
+impl InstanceLease {
+    /// Lease held as `<hostname>:<pid>`
+    pub fn new(backend: Arc<dyn StateBackend>, config: &LeaderElectionConfig) -> Self {
+        Self {
+            backend,
+            name: config.name.clone(),
+            holder: instance_id(),
+            duration: Duration::from_secs(config.lease_secs),
+        }
+    }
+
+    /// Hold the lease under another id
+    pub fn with_holder(mut self, holder: impl Into<String>) -> Self {
+        self.holder = holder.into();
+        self
+    }
+
+    pub fn holder(&self) -> &str {
+        &self.holder
+    }
+
+    /// Take the lease. Fails if another instance holds it, unless `standby`, in which
+    /// case it waits for that instance's lease to expire and takes over.
+    pub async fn acquire(&self, standby: bool) -> Result<()> {
+        let mut waiting = false;
+        loop {
+            match self.backend.acquire_lease(&self.name, &self.holder, self.duration).await {
+                Ok(None) if waiting => {
+                    info!("👑 Took over lease '{}' as {}", self.name, self.holder);
+                    return Ok(());
+                }
+                Ok(None) => {
+                    info!("🔒 Holding lease '{}' as {}", self.name, self.holder);
+                    return Ok(());
+                }
+                Ok(Some(leader)) if !standby => {
+                    return Err(eyre!(
+                        "another instance ({}) is running on the same state; stop it, or wait up to {}s for its lease '{}' to expire",
+                        leader,
+                        self.duration.as_secs(),
+                        self.name
+                    ));
+                }
+                Ok(Some(leader)) => {
+                    if !waiting {
+                        info!("⏳ Standing by: {} holds lease '{}'", leader, self.name);
+                        waiting = true;
+                    }
+                }
+                Err(e) if !standby => return Err(e),
+                Err(e) => warn!("Failed to check lease '{}': {}", self.name, e),
+            }
+            tokio::time::sleep(self.renew_interval()).await;
+        }
+    }
+
+    /// Renew the lease every third of its duration. Returns once the lease is lost:
+    /// taken over by another instance, or not renewed in time. The instance steps down
+    /// half a renewal interval before the lease expires, so a standby can't take it over
+    /// while this instance still sends alerts.
+    pub async fn hold(&self) -> Report {
+        let mut renewed = Instant::now();
+        loop {
+            tokio::time::sleep(self.renew_interval()).await;
+            let step_down = renewed + self.duration - self.renew_interval() / 2;
+            let attempted = Instant::now();
+            let renewal = self.backend.acquire_lease(&self.name, &self.holder, self.duration);
+            let error = match tokio::time::timeout_at(step_down, renewal).await {
+                Ok(Ok(None)) => {
+                    renewed = attempted;
+                    continue;
+                }
+                Ok(Ok(Some(leader))) => return eyre!("lease '{}' was taken over by {}", self.name, leader),
+                Ok(Err(e)) => e,
+                Err(_) => eyre!("timed out"),
+            };
+            warn!("Failed to renew lease '{}': {}", self.name, error);
+            // The next attempt would come too late to stop before the lease expires
+            if Instant::now() + self.renew_interval() > step_down {
+                return eyre!(
+                    "lease '{}' about to expire: failed to renew it for {}s",
+                    self.name,
+                    renewed.elapsed().as_secs()
+                );
+            }
+        }
+    }
+
+    /// Give up the lease so a standby can take over right away
+    pub async fn release(&self) {
+        match self.backend.release_lease(&self.name, &self.holder).await {
+            Ok(()) => info!("🔓 Released lease '{}'", self.name),
+            Err(e) => warn!("Failed to release lease '{}': {}", self.name, e),
+        }
+    }
+
+    fn renew_interval(&self) -> Duration {
+        self.duration / 3
+    }
+}
+
+/// `<hostname>:<pid>` of this process
+fn instance_id() -> String {
+    let host = std::env::var("HOSTNAME")
+        .ok()
+        .or_else(|| std::fs::read_to_string("/etc/hostname").ok())
+        .map(|host| host.trim().to_string())
+        .filter(|host| !host.is_empty())
+        .unwrap_or_else(|| "unknown".to_string());
+    format!("{}:{}", host, std::process::id())
+}
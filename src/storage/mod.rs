@@ -0,0 +1,30 @@
+mod addresses;
+mod alert_state;
+mod atomic;
+mod audit;
+mod backend;
+mod backup;
+mod balance;
+mod chats;
+mod cosmos;
+mod escalations;
+mod history;
+mod lease;
+mod postgres;
+mod s3;
+mod schema;
+
+pub use addresses::{DynamicAddress, DynamicAddressStorage};
+pub use alert_state::{AlertState, AlertStateStorage};
+pub use audit::{AuditEntry, AuditQuery};
+pub use backend::{create_state_backend, FileBackend, StateBackend};
+pub use backup::{prune_backups, spawn_backups, StateBackup};
+pub use balance::BalanceStorage;
+pub use chats::{ChatRegistration, ChatStorage, ChatSubscription};
+pub use cosmos::{CosmosAddressState, CosmosStateStorage};
+pub use escalations::{Acknowledgement, EscalationStorage, TrackedAlert};
+pub use history::{HistoryPoint, HistoryStore};
+pub use lease::InstanceLease;
+pub use postgres::PostgresBackend;
+pub use s3::{sign_v4, S3Client};
+pub use schema::{migrate_file, migrate_state, schema_version, STATE_SCHEMA_VERSION};
@@ -0,0 +1,791 @@
+use super::{
+    AlertState, AlertStateStorage, AuditEntry, AuditQuery, BalanceStorage, ChatRegistration, ChatStorage,
+    CosmosStateStorage, DynamicAddress, DynamicAddressStorage, EscalationStorage, StateBackend, TrackedAlert,
+};
+use crate::encryption::StateCipher;
+use crate::monitoring::BalanceInfo;
+use async_trait::async_trait;
+use eyre::{eyre, Result};
+use native_tls::TlsConnector;
+use postgres_native_tls::MakeTlsConnector;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tokio_postgres::Client;
+use tracing::{info, warn};
+
+/// Schema migrations applied in order on startup
+const MIGRATIONS: &[(i32, &str)] = &[
+    (
+        1,
+        "CREATE TABLE oxwatcher_balances (
+            key TEXT PRIMARY KEY,
+            data JSONB NOT NULL,
+            updated_at TIMESTAMPTZ NOT NULL DEFAULT now()
+        );
+        CREATE TABLE oxwatcher_telegram_chats (
+            chat_id BIGINT PRIMARY KEY,
+            user_id BIGINT NOT NULL,
+            username TEXT NOT NULL
+        );
+        CREATE TABLE oxwatcher_alert_states (
+            key TEXT PRIMARY KEY,
+            last_sent BIGINT NOT NULL,
+            alert_count INTEGER NOT NULL
+        );",
+    ),
+    (
+        2,
+        "CREATE TABLE oxwatcher_dynamic_addresses (
+            network TEXT NOT NULL,
+            alias TEXT NOT NULL,
+            data JSONB NOT NULL,
+            PRIMARY KEY (network, alias)
+        );",
+    ),
+    (
+        3,
+        "ALTER TABLE oxwatcher_telegram_chats ADD COLUMN subscriptions JSONB NOT NULL DEFAULT '[]';",
+    ),
+    (4, "ALTER TABLE oxwatcher_telegram_chats ADD COLUMN muted_until BIGINT;"),
+    (
+        5,
+        "CREATE TABLE oxwatcher_alert_audit (
+            id BIGSERIAL PRIMARY KEY,
+            sent_at BIGINT NOT NULL,
+            channel TEXT NOT NULL,
+            chat TEXT,
+            kind TEXT NOT NULL,
+            network TEXT,
+            subject TEXT NOT NULL,
+            content_hash TEXT NOT NULL,
+            success BOOLEAN NOT NULL,
+            error TEXT
+        );
+        CREATE INDEX oxwatcher_alert_audit_sent_at ON oxwatcher_alert_audit (sent_at);",
+    ),
+    (
+        6,
+        "CREATE TABLE oxwatcher_report_baseline (
+            key TEXT PRIMARY KEY,
+            data JSONB NOT NULL
+        );
+        CREATE TABLE oxwatcher_report_baseline_meta (
+            id INTEGER PRIMARY KEY,
+            saved_at TIMESTAMPTZ NOT NULL DEFAULT now()
+        );",
+    ),
+    (7, "ALTER TABLE oxwatcher_balances ADD COLUMN last_changed BIGINT;"),
+    (
+        8,
+        "CREATE TABLE oxwatcher_escalations (
+            id BIGINT PRIMARY KEY,
+            data JSONB NOT NULL
+        );
+        CREATE TABLE oxwatcher_escalation_meta (
+            id INTEGER PRIMARY KEY,
+            next_id BIGINT NOT NULL
+        );",
+    ),
+    (9, "ALTER TABLE oxwatcher_alert_states ADD COLUMN severity TEXT;"),
+    (
+        10,
+        "ALTER TABLE oxwatcher_telegram_chats ADD COLUMN bot TEXT NOT NULL DEFAULT '';
+        ALTER TABLE oxwatcher_telegram_chats DROP CONSTRAINT oxwatcher_telegram_chats_pkey;
+        ALTER TABLE oxwatcher_telegram_chats ADD PRIMARY KEY (bot, chat_id);",
+    ),
+    (
+        11,
+        "CREATE TABLE oxwatcher_leases (
+            name TEXT PRIMARY KEY,
+            holder TEXT NOT NULL,
+            expires_at BIGINT NOT NULL
+        );",
+    ),
+    (
+        12,
+        "CREATE TABLE oxwatcher_alert_dedup (
+            network TEXT NOT NULL,
+            subject TEXT NOT NULL,
+            hash TEXT NOT NULL,
+            claimed_at BIGINT NOT NULL,
+            PRIMARY KEY (network, subject, hash)
+        );
+        CREATE INDEX oxwatcher_alert_dedup_claimed_at ON oxwatcher_alert_dedup (claimed_at);",
+    ),
+    (
+        13,
+        "CREATE TABLE oxwatcher_cosmos_states (
+            key TEXT PRIMARY KEY,
+            data JSONB NOT NULL
+        );",
+    ),
+    (
+        14,
+        "CREATE SEQUENCE oxwatcher_escalation_ids MINVALUE 0;
+        SELECT setval('oxwatcher_escalation_ids', GREATEST(
+            (SELECT COALESCE(MAX(next_id), 0) FROM oxwatcher_escalation_meta),
+            (SELECT COALESCE(MAX(id), 0) FROM oxwatcher_escalations)
+        ));
+        DROP TABLE oxwatcher_escalation_meta;",
+    ),
+    (
+        15,
+        "CREATE TABLE oxwatcher_telegram_chats_encrypted (
+            bot TEXT PRIMARY KEY,
+            data TEXT NOT NULL
+        );",
+    ),
+];
+
+/// Advisory lock key serializing migrations across instances
+const MIGRATION_LOCK_KEY: i64 = 0x0a7c_4e55;
+
+/// PostgreSQL backend for sharing state between watcher instances.
+///
+/// Balances, alert states, Cosmos states and escalations are upserted per key, so instances
+/// monitoring different networks don't overwrite each other. Escalation IDs come from a
+/// sequence, so instances never hand out the same one.
+///
+/// With a cipher, the registered chats of each bot are kept as one encrypted row in
+/// oxwatcher_telegram_chats_encrypted instead of a row per chat in oxwatcher_telegram_chats.
+pub struct PostgresBackend {
+    url: String,
+    client: Mutex<Option<Client>>,
+    cipher: Option<StateCipher>,
+}
+
+impl PostgresBackend {
+    /// Connect and apply pending schema migrations
+    pub async fn connect(url: &str) -> Result<Self> {
+        let backend = Self {
+            url: url.to_string(),
+            client: Mutex::new(None),
+            cipher: None,
+        };
+
+        {
+            let mut guard = backend.client.lock().await;
+            let client = Self::open(&backend.url).await?;
+            Self::migrate(&client).await?;
+            *guard = Some(client);
+        }
+
+        Ok(backend)
+    }
+
+    /// Encrypt registered chats with `cipher`. Plain chat rows are still read until
+    /// the chats are next saved, which encrypts them.
+    pub fn with_cipher(mut self, cipher: StateCipher) -> Self {
+        self.cipher = Some(cipher);
+        self
+    }
+
+    async fn open(url: &str) -> Result<Client> {
+        let connector = MakeTlsConnector::new(TlsConnector::new()?);
+        let (client, connection) = tokio_postgres::connect(url, connector).await?;
+
+        tokio::spawn(async move {
+            if let Err(e) = connection.await {
+                warn!("PostgreSQL connection error: {}", e);
+            }
+        });
+
+        Ok(client)
+    }
+
+    /// Apply migrations not yet recorded in oxwatcher_schema_migrations
+    async fn migrate(client: &Client) -> Result<()> {
+        client
+            .batch_execute(
+                "CREATE TABLE IF NOT EXISTS oxwatcher_schema_migrations (
+                    version INTEGER PRIMARY KEY,
+                    applied_at TIMESTAMPTZ NOT NULL DEFAULT now()
+                )",
+            )
+            .await?;
+
+        client.execute("SELECT pg_advisory_lock($1)", &[&MIGRATION_LOCK_KEY]).await?;
+        let result = Self::apply_pending(client).await;
+        client.execute("SELECT pg_advisory_unlock($1)", &[&MIGRATION_LOCK_KEY]).await?;
+        result
+    }
+
+    async fn apply_pending(client: &Client) -> Result<()> {
+        let applied: Vec<i32> = client
+            .query("SELECT version FROM oxwatcher_schema_migrations", &[])
+            .await?
+            .iter()
+            .map(|row| row.get(0))
+            .collect();
+
+        for (version, sql) in MIGRATIONS {
+            if applied.contains(version) {
+                continue;
+            }
+
+            client.batch_execute("BEGIN").await?;
+            let result = async {
+                client.batch_execute(sql).await?;
+                client
+                    .execute("INSERT INTO oxwatcher_schema_migrations (version) VALUES ($1)", &[version])
+                    .await?;
+                Ok::<_, tokio_postgres::Error>(())
+            }
+            .await;
+
+            match result {
+                Ok(()) => {
+                    client.batch_execute("COMMIT").await?;
+                    info!("🗄️  Applied PostgreSQL migration {}", version);
+                }
+                Err(e) => {
+                    client.batch_execute("ROLLBACK").await?;
+                    eyre::bail!("PostgreSQL migration {} failed: {}", version, e);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Get a live client, reconnecting if the connection was closed
+    async fn client(&self) -> Result<tokio::sync::MutexGuard<'_, Option<Client>>> {
+        let mut guard = self.client.lock().await;
+        if guard.as_ref().is_none_or(|c| c.is_closed()) {
+            *guard = Some(Self::open(&self.url).await?);
+        }
+        Ok(guard)
+    }
+}
+
+/// Audit entry from a row of sent_at, channel, chat, kind, network, subject, content_hash, success, error
+fn audit_entry(row: &tokio_postgres::Row) -> AuditEntry {
+    AuditEntry {
+        sent_at: row.get::<_, i64>(0) as u64,
+        channel: row.get(1),
+        chat: row.get(2),
+        kind: row.get(3),
+        network: row.get(4),
+        subject: row.get(5),
+        content_hash: row.get(6),
+        success: row.get(7),
+        error: row.get(8),
+    }
+}
+
+#[async_trait]
+impl StateBackend for PostgresBackend {
+    fn name(&self) -> &str {
+        "postgres"
+    }
+
+    async fn load_balances(&self) -> Result<BalanceStorage> {
+        let guard = self.client().await?;
+        let client = guard.as_ref().expect("client is connected");
+
+        let mut storage = BalanceStorage::new();
+        for row in client.query("SELECT key, data, last_changed FROM oxwatcher_balances", &[]).await? {
+            let key: String = row.get(0);
+            let data: serde_json::Value = row.get(1);
+            if let Some(last_changed) = row.get::<_, Option<i64>>(2) {
+                storage.last_changed.insert(key.clone(), last_changed as u64);
+            }
+            storage.balances.insert(key, serde_json::from_value::<BalanceInfo>(data)?);
+        }
+
+        Ok(storage)
+    }
+
+    async fn save_balances(&self, balances: &BalanceStorage) -> Result<()> {
+        let guard = self.client().await?;
+        let client = guard.as_ref().expect("client is connected");
+
+        let statement = client
+            .prepare(
+                "INSERT INTO oxwatcher_balances (key, data, last_changed, updated_at) VALUES ($1, $2, $3, now())
+                 ON CONFLICT (key) DO UPDATE SET data = EXCLUDED.data, last_changed = EXCLUDED.last_changed, updated_at = now()",
+            )
+            .await?;
+        for (key, info) in &balances.balances {
+            let last_changed = balances.last_changed.get(key).map(|&at| at as i64);
+            client.execute(&statement, &[key, &serde_json::to_value(info)?, &last_changed]).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn load_chats(&self, bot: Option<&str>) -> Result<ChatStorage> {
+        let guard = self.client().await?;
+        let client = guard.as_ref().expect("client is connected");
+
+        let encrypted = client
+            .query_opt("SELECT data FROM oxwatcher_telegram_chats_encrypted WHERE bot = $1", &[&bot.unwrap_or_default()])
+            .await?;
+        if let Some(row) = encrypted {
+            let cipher = self.cipher.as_ref().ok_or_else(|| eyre!("registered chats are encrypted; set encryption.key"))?;
+            return Ok(serde_json::from_slice(&cipher.decrypt(row.get(0))?)?);
+        }
+
+        let mut registrations = Vec::new();
+        for row in client
+            .query(
+                "SELECT chat_id, user_id, username, subscriptions, muted_until FROM oxwatcher_telegram_chats WHERE bot = $1",
+                &[&bot.unwrap_or_default()],
+            )
+            .await?
+        {
+            let subscriptions: serde_json::Value = row.get(3);
+            registrations.push(ChatRegistration {
+                chat_id: row.get(0),
+                user_id: row.get(1),
+                username: row.get(2),
+                subscriptions: serde_json::from_value(subscriptions)?,
+                muted_until: row.get::<_, Option<i64>>(4).map(|until| until as u64),
+            });
+        }
+
+        Ok(ChatStorage { registrations })
+    }
+
+    async fn save_chats(&self, bot: Option<&str>, chats: &ChatStorage) -> Result<()> {
+        let bot = bot.unwrap_or_default();
+        let mut guard = self.client().await?;
+        let client = guard.as_mut().expect("client is connected");
+
+        // Chat set is replaced as a whole so unregistered chats are removed
+        let transaction = client.transaction().await?;
+        transaction.execute("DELETE FROM oxwatcher_telegram_chats WHERE bot = $1", &[&bot]).await?;
+        if let Some(cipher) = &self.cipher {
+            transaction
+                .execute(
+                    "INSERT INTO oxwatcher_telegram_chats_encrypted (bot, data) VALUES ($1, $2)
+                     ON CONFLICT (bot) DO UPDATE SET data = EXCLUDED.data",
+                    &[&bot, &cipher.encrypt(&serde_json::to_vec(chats)?)?],
+                )
+                .await?;
+            transaction.commit().await?;
+            return Ok(());
+        }
+        let encrypted = transaction
+            .query_opt("SELECT 1 FROM oxwatcher_telegram_chats_encrypted WHERE bot = $1", &[&bot])
+            .await?;
+        if encrypted.is_some() {
+            return Err(eyre!("registered chats are encrypted; set encryption.key"));
+        }
+        for reg in &chats.registrations {
+            transaction
+                .execute(
+                    "INSERT INTO oxwatcher_telegram_chats (bot, chat_id, user_id, username, subscriptions, muted_until)
+                     VALUES ($1, $2, $3, $4, $5, $6)",
+                    &[
+                        &bot,
+                        &reg.chat_id,
+                        &reg.user_id,
+                        &reg.username,
+                        &serde_json::to_value(&reg.subscriptions)?,
+                        &reg.muted_until.map(|until| until as i64),
+                    ],
+                )
+                .await?;
+        }
+        transaction.commit().await?;
+
+        Ok(())
+    }
+
+    async fn load_alert_states(&self) -> Result<AlertStateStorage> {
+        let guard = self.client().await?;
+        let client = guard.as_ref().expect("client is connected");
+
+        let mut storage = AlertStateStorage::new();
+        for row in client.query("SELECT key, last_sent, alert_count, severity FROM oxwatcher_alert_states", &[]).await? {
+            let last_sent: i64 = row.get(1);
+            let alert_count: i32 = row.get(2);
+            let severity: Option<String> = row.get(3);
+            storage.states.insert(
+                row.get(0),
+                AlertState {
+                    last_sent: last_sent as u64,
+                    alert_count: alert_count as u32,
+                    severity: severity.and_then(|severity| serde_json::from_value(serde_json::Value::String(severity)).ok()),
+                },
+            );
+        }
+
+        Ok(storage)
+    }
+
+    async fn save_alert_states(&self, states: &AlertStateStorage) -> Result<()> {
+        let guard = self.client().await?;
+        let client = guard.as_ref().expect("client is connected");
+
+        let statement = client
+            .prepare(
+                "INSERT INTO oxwatcher_alert_states (key, last_sent, alert_count, severity) VALUES ($1, $2, $3, $4)
+                 ON CONFLICT (key) DO UPDATE SET last_sent = EXCLUDED.last_sent, alert_count = EXCLUDED.alert_count,
+                 severity = EXCLUDED.severity",
+            )
+            .await?;
+        for (key, state) in &states.states {
+            client
+                .execute(&statement, &[key, &(state.last_sent as i64), &(state.alert_count as i32), &state.severity.map(|s| s.as_str())])
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn load_dynamic_addresses(&self) -> Result<DynamicAddressStorage> {
+        let guard = self.client().await?;
+        let client = guard.as_ref().expect("client is connected");
+
+        let mut addresses = Vec::new();
+        for row in client.query("SELECT network, data FROM oxwatcher_dynamic_addresses ORDER BY network, alias", &[]).await? {
+            let data: serde_json::Value = row.get(1);
+            addresses.push(DynamicAddress {
+                network: row.get(0),
+                config: serde_json::from_value(data)?,
+            });
+        }
+
+        Ok(DynamicAddressStorage { addresses })
+    }
+
+    async fn save_dynamic_addresses(&self, addresses: &DynamicAddressStorage) -> Result<()> {
+        let mut guard = self.client().await?;
+        let client = guard.as_mut().expect("client is connected");
+
+        // Replaced as a whole so removed addresses are deleted
+        let transaction = client.transaction().await?;
+        transaction.execute("DELETE FROM oxwatcher_dynamic_addresses", &[]).await?;
+        for entry in &addresses.addresses {
+            transaction
+                .execute(
+                    "INSERT INTO oxwatcher_dynamic_addresses (network, alias, data) VALUES ($1, $2, $3)",
+                    &[&entry.network, &entry.config.alias, &serde_json::to_value(&entry.config)?],
+                )
+                .await?;
+        }
+        transaction.commit().await?;
+
+        Ok(())
+    }
+
+    async fn load_report_baseline(&self) -> Result<Option<BalanceStorage>> {
+        let guard = self.client().await?;
+        let client = guard.as_ref().expect("client is connected");
+
+        // An empty baseline is still a baseline; the meta row tells it apart from none
+        if client.query_opt("SELECT saved_at FROM oxwatcher_report_baseline_meta", &[]).await?.is_none() {
+            return Ok(None);
+        }
+
+        let mut storage = BalanceStorage::new();
+        for row in client.query("SELECT key, data FROM oxwatcher_report_baseline", &[]).await? {
+            let key: String = row.get(0);
+            let data: serde_json::Value = row.get(1);
+            storage.balances.insert(key, serde_json::from_value::<BalanceInfo>(data)?);
+        }
+
+        Ok(Some(storage))
+    }
+
+    async fn save_report_baseline(&self, baseline: &BalanceStorage) -> Result<()> {
+        let mut guard = self.client().await?;
+        let client = guard.as_mut().expect("client is connected");
+
+        // Replaced as a whole so addresses removed since the last report don't linger
+        let transaction = client.transaction().await?;
+        transaction.execute("DELETE FROM oxwatcher_report_baseline", &[]).await?;
+        for (key, info) in &baseline.balances {
+            transaction
+                .execute(
+                    "INSERT INTO oxwatcher_report_baseline (key, data) VALUES ($1, $2)",
+                    &[key, &serde_json::to_value(info)?],
+                )
+                .await?;
+        }
+        transaction
+            .execute(
+                "INSERT INTO oxwatcher_report_baseline_meta (id, saved_at) VALUES (1, now())
+                 ON CONFLICT (id) DO UPDATE SET saved_at = now()",
+                &[],
+            )
+            .await?;
+        transaction.commit().await?;
+
+        Ok(())
+    }
+
+    async fn clear_report_baseline(&self) -> Result<()> {
+        let mut guard = self.client().await?;
+        let client = guard.as_mut().expect("client is connected");
+
+        let transaction = client.transaction().await?;
+        transaction.execute("DELETE FROM oxwatcher_report_baseline", &[]).await?;
+        transaction.execute("DELETE FROM oxwatcher_report_baseline_meta", &[]).await?;
+        transaction.commit().await?;
+
+        Ok(())
+    }
+
+    async fn load_escalations(&self) -> Result<EscalationStorage> {
+        let guard = self.client().await?;
+        let client = guard.as_ref().expect("client is connected");
+
+        let mut storage = EscalationStorage::new();
+        let row = client.query_one("SELECT last_value FROM oxwatcher_escalation_ids", &[]).await?;
+        storage.next_id = row.get::<_, i64>(0) as u64;
+        for row in client.query("SELECT data FROM oxwatcher_escalations ORDER BY id", &[]).await? {
+            let data: serde_json::Value = row.get(0);
+            storage.alerts.push(serde_json::from_value::<TrackedAlert>(data)?);
+        }
+
+        Ok(storage)
+    }
+
+    async fn save_escalations(&self, escalations: &EscalationStorage) -> Result<()> {
+        let mut guard = self.client().await?;
+        let client = guard.as_mut().expect("client is connected");
+
+        let transaction = client.transaction().await?;
+        transaction.execute("DELETE FROM oxwatcher_escalations", &[]).await?;
+        for tracked in &escalations.alerts {
+            transaction
+                .execute(
+                    "INSERT INTO oxwatcher_escalations (id, data) VALUES ($1, $2)",
+                    &[&(tracked.id as i64), &serde_json::to_value(tracked)?],
+                )
+                .await?;
+        }
+        // IDs never go back, so old Acknowledge buttons can't match a new alert
+        transaction
+            .query_one(
+                "SELECT setval('oxwatcher_escalation_ids', GREATEST($1, (SELECT last_value FROM oxwatcher_escalation_ids)))",
+                &[&(escalations.next_id as i64)],
+            )
+            .await?;
+        transaction.commit().await?;
+
+        Ok(())
+    }
+
+    async fn next_escalation_id(&self) -> Result<u64> {
+        let guard = self.client().await?;
+        let client = guard.as_ref().expect("client is connected");
+
+        let row = client.query_one("SELECT nextval('oxwatcher_escalation_ids')", &[]).await?;
+        Ok(row.get::<_, i64>(0) as u64)
+    }
+
+    async fn save_escalation(&self, tracked: &TrackedAlert) -> Result<()> {
+        let guard = self.client().await?;
+        let client = guard.as_ref().expect("client is connected");
+
+        client
+            .execute(
+                "INSERT INTO oxwatcher_escalations (id, data) VALUES ($1, $2)
+                 ON CONFLICT (id) DO UPDATE SET data = EXCLUDED.data",
+                &[&(tracked.id as i64), &serde_json::to_value(tracked)?],
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn delete_escalations(&self, ids: &[u64]) -> Result<()> {
+        let guard = self.client().await?;
+        let client = guard.as_ref().expect("client is connected");
+
+        let ids: Vec<i64> = ids.iter().map(|id| *id as i64).collect();
+        client.execute("DELETE FROM oxwatcher_escalations WHERE id = ANY($1)", &[&ids]).await?;
+
+        Ok(())
+    }
+
+    async fn load_cosmos_states(&self) -> Result<CosmosStateStorage> {
+        let guard = self.client().await?;
+        let client = guard.as_ref().expect("client is connected");
+
+        let mut storage = CosmosStateStorage::new();
+        for row in client.query("SELECT key, data FROM oxwatcher_cosmos_states", &[]).await? {
+            let data: serde_json::Value = row.get(1);
+            storage.states.insert(row.get(0), serde_json::from_value(data)?);
+        }
+
+        Ok(storage)
+    }
+
+    async fn save_cosmos_states(&self, states: &CosmosStateStorage) -> Result<()> {
+        let guard = self.client().await?;
+        let client = guard.as_ref().expect("client is connected");
+
+        let statement = client
+            .prepare(
+                "INSERT INTO oxwatcher_cosmos_states (key, data) VALUES ($1, $2)
+                 ON CONFLICT (key) DO UPDATE SET data = EXCLUDED.data",
+            )
+            .await?;
+        for (key, state) in &states.states {
+            client.execute(&statement, &[key, &serde_json::to_value(state)?]).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn append_audit(&self, entry: &AuditEntry) -> Result<()> {
+        let guard = self.client().await?;
+        let client = guard.as_ref().expect("client is connected");
+
+        client
+            .execute(
+                "INSERT INTO oxwatcher_alert_audit
+                     (sent_at, channel, chat, kind, network, subject, content_hash, success, error)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)",
+                &[
+                    &(entry.sent_at as i64),
+                    &entry.channel,
+                    &entry.chat,
+                    &entry.kind,
+                    &entry.network,
+                    &entry.subject,
+                    &entry.content_hash,
+                    &entry.success,
+                    &entry.error,
+                ],
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn load_audit(&self, query: &AuditQuery) -> Result<Vec<AuditEntry>> {
+        let guard = self.client().await?;
+        let client = guard.as_ref().expect("client is connected");
+
+        let rows = client
+            .query(
+                "SELECT sent_at, channel, chat, kind, network, subject, content_hash, success, error
+                 FROM oxwatcher_alert_audit
+                 WHERE ($1::BIGINT IS NULL OR sent_at >= $1)
+                   AND ($2::BIGINT IS NULL OR sent_at <= $2)
+                   AND ($3::TEXT IS NULL OR network = $3)
+                   AND ($4::TEXT IS NULL OR channel = $4)
+                 ORDER BY sent_at DESC, id DESC
+                 LIMIT $5",
+                &[
+                    &query.since.map(|since| since as i64),
+                    &query.until.map(|until| until as i64),
+                    &query.network,
+                    &query.channel,
+                    &(query.limit() as i64),
+                ],
+            )
+            .await?;
+
+        Ok(rows.iter().map(audit_entry).collect())
+    }
+
+    async fn load_audit_log(&self) -> Result<Vec<AuditEntry>> {
+        let guard = self.client().await?;
+        let client = guard.as_ref().expect("client is connected");
+
+        let rows = client
+            .query(
+                "SELECT sent_at, channel, chat, kind, network, subject, content_hash, success, error
+                 FROM oxwatcher_alert_audit
+                 ORDER BY id",
+                &[],
+            )
+            .await?;
+
+        Ok(rows.iter().map(audit_entry).collect())
+    }
+
+    async fn storage_sizes(&self) -> Result<Vec<(String, u64)>> {
+        let guard = self.client().await?;
+        let client = guard.as_ref().expect("client is connected");
+
+        let rows = client
+            .query(
+                "SELECT relname::TEXT, pg_total_relation_size(oid) FROM pg_class
+                 WHERE relkind = 'r' AND relname LIKE 'oxwatcher\\_%' ORDER BY relname",
+                &[],
+            )
+            .await?;
+        Ok(rows.iter().map(|row| (row.get(0), row.get::<_, i64>(1) as u64)).collect())
+    }
+
+    async fn acquire_lease(&self, name: &str, holder: &str, duration: Duration) -> Result<Option<String>> {
+        let guard = self.client().await?;
+        let client = guard.as_ref().expect("client is connected");
+
+        // Expiry uses the database clock, so instances with skewed clocks agree on it
+        let taken = client
+            .query_opt(
+                "INSERT INTO oxwatcher_leases (name, holder, expires_at)
+                 VALUES ($1, $2, EXTRACT(EPOCH FROM now())::BIGINT + $3)
+                 ON CONFLICT (name) DO UPDATE SET holder = EXCLUDED.holder, expires_at = EXCLUDED.expires_at
+                 WHERE oxwatcher_leases.holder = EXCLUDED.holder
+                    OR oxwatcher_leases.expires_at <= EXTRACT(EPOCH FROM now())::BIGINT
+                 RETURNING holder",
+                &[&name, &holder, &(duration.as_secs() as i64)],
+            )
+            .await?;
+        if taken.is_some() {
+            return Ok(None);
+        }
+
+        let row = client.query_opt("SELECT holder FROM oxwatcher_leases WHERE name = $1", &[&name]).await?;
+        // Released between the two queries; the next attempt takes it
+        Ok(Some(row.map_or_else(|| "another instance".to_string(), |row| row.get(0))))
+    }
+
+    async fn release_lease(&self, name: &str, holder: &str) -> Result<()> {
+        let guard = self.client().await?;
+        let client = guard.as_ref().expect("client is connected");
+
+        client
+            .execute("DELETE FROM oxwatcher_leases WHERE name = $1 AND holder = $2", &[&name, &holder])
+            .await?;
+        Ok(())
+    }
+
+    async fn claim_alert(&self, network: &str, subject: &str, hash: &str, window: Duration) -> Result<bool> {
+        let guard = self.client().await?;
+        let client = guard.as_ref().expect("client is connected");
+        let window = window.as_secs() as i64;
+
+        // The upsert is atomic, so of two instances claiming at once exactly one gets a row back
+        let claimed = client
+            .query_opt(
+                "INSERT INTO oxwatcher_alert_dedup (network, subject, hash, claimed_at)
+                 VALUES ($1, $2, $3, EXTRACT(EPOCH FROM now())::BIGINT)
+                 ON CONFLICT (network, subject, hash) DO UPDATE SET claimed_at = EXCLUDED.claimed_at
+                 WHERE oxwatcher_alert_dedup.claimed_at + $4 <= EXCLUDED.claimed_at
+                 RETURNING claimed_at",
+                &[&network, &subject, &hash, &window],
+            )
+            .await?;
+        client
+            .execute(
+                "DELETE FROM oxwatcher_alert_dedup WHERE claimed_at <= EXTRACT(EPOCH FROM now())::BIGINT - $1",
+                &[&window],
+            )
+            .await?;
+
+        Ok(claimed.is_some())
+    }
+
+    async fn release_alert(&self, network: &str, subject: &str, hash: &str) -> Result<()> {
+        let guard = self.client().await?;
+        let client = guard.as_ref().expect("client is connected");
+        client
+            .execute(
+                "DELETE FROM oxwatcher_alert_dedup WHERE network = $1 AND subject = $2 AND hash = $3",
+                &[&network, &subject, &hash],
+            )
+            .await?;
+        Ok(())
+    }
+}
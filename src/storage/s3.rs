@@ -0,0 +1,105 @@
+use crate::config::S3Config;
+use chrono::Utc;
+use eyre::{eyre, Result};
+use hmac::{Hmac, Mac};
+use reqwest::Url;
+use sha2::{Digest, Sha256};
+use std::time::Duration;
+
+/// Uploads objects to an S3-compatible bucket with path-style URLs, signing
+/// requests with AWS Signature Version 4
+pub struct S3Client {
+    config: S3Config,
+    client: reqwest::Client,
+}
+
+impl S3Client {
+    pub fn new(config: S3Config) -> Self {
+        Self {
+            config,
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// Store `body` under the configured prefix followed by `name`
+    pub async fn put_object(&self, name: &str, body: Vec<u8>) -> Result<()> {
+        let path = format!("/{}/{}", self.config.bucket, uri_encode(&format!("{}{}", self.config.prefix, name)));
+        let url = Url::parse(&format!("{}{}", self.config.endpoint.as_str().trim_end_matches('/'), path))?;
+        let host = match url.port() {
+            Some(port) => format!("{}:{}", url.host_str().unwrap_or_default(), port),
+            None => url.host_str().unwrap_or_default().to_string(),
+        };
+        let payload_hash = hex::encode(Sha256::digest(&body));
+        let amz_date = Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+
+        let headers = [("host", host.as_str()), ("x-amz-content-sha256", &payload_hash), ("x-amz-date", &amz_date)];
+        let signature = sign_v4(&self.config.secret_access_key, &self.config.region, "PUT", &path, &headers, &payload_hash, &amz_date);
+        let authorization = format!(
+            "AWS4-HMAC-SHA256 Credential={}/{}/{}/s3/aws4_request, SignedHeaders=host;x-amz-content-sha256;x-amz-date, Signature={}",
+            self.config.access_key_id, &amz_date[..8], self.config.region, signature
+        );
+
+        let response = self.client
+            .put(url)
+            .header("x-amz-content-sha256", &payload_hash)
+            .header("x-amz-date", &amz_date)
+            .header("Authorization", authorization)
+            .body(body)
+            .timeout(Duration::from_secs(60))
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            let status = response.status();
+            return Err(eyre!("S3 upload returned {}: {}", status, response.text().await.unwrap_or_default()));
+        }
+        Ok(())
+    }
+}
+
+/// AWS Signature Version 4 of an S3 request. `headers` are the signed headers with
+/// lowercase names sorted by name, `path` the URI-encoded path and `amz_date` the
+/// request time as YYYYMMDDTHHMMSSZ.
+pub fn sign_v4(
+    secret_access_key: &str,
+    region: &str,
+    method: &str,
+    path: &str,
+    headers: &[(&str, &str)],
+    payload_hash: &str,
+    amz_date: &str,
+) -> String {
+    let canonical_headers: String = headers.iter().map(|(name, value)| format!("{}:{}\n", name, value.trim())).collect();
+    let signed_headers = headers.iter().map(|(name, _)| *name).collect::<Vec<_>>().join(";");
+    let canonical_request = format!("{}\n{}\n\n{}\n{}\n{}", method, path, canonical_headers, signed_headers, payload_hash);
+
+    let date = &amz_date[..8];
+    let scope = format!("{}/{}/s3/aws4_request", date, region);
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+        amz_date,
+        scope,
+        hex::encode(Sha256::digest(canonical_request.as_bytes()))
+    );
+
+    let mut key = hmac(format!("AWS4{}", secret_access_key).as_bytes(), date.as_bytes());
+    for part in [region, "s3", "aws4_request"] {
+        key = hmac(&key, part.as_bytes());
+    }
+    hex::encode(hmac(&key, string_to_sign.as_bytes()))
+}
+
+fn hmac(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// Percent-encode an object key for the request path, keeping `/` separators
+fn uri_encode(key: &str) -> String {
+    key.bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => (byte as char).to_string(),
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
@@ -0,0 +1,129 @@
+use super::atomic::write_atomic;
+use crate::encryption::StateCipher;
+use eyre::{eyre, Result};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::fs;
+use std::path::Path;
+use tracing::info;
+
+/// Version of the JSON state file format written by this build, stored as
+/// `schema_version` in balances.json, report_baseline.json, alert_states.json,
+/// cosmos_states.json and telegram_chats*.json. Files written before versioning have none and are version 1.
+pub const STATE_SCHEMA_VERSION: u32 = 2;
+
+/// Upgrade of state file JSON to the next version; the first one upgrades version 1
+type Migration = fn(&mut Value);
+
+const MIGRATIONS: &[Migration] = &[
+    // 2: files carry schema_version; fields added before it are filled in with defaults on load
+    |_| {},
+];
+
+/// Version of state file JSON, 1 if it has none
+pub fn schema_version(state: &Value) -> u32 {
+    state.get("schema_version").and_then(Value::as_u64).map_or(1, |version| (version as u32).max(1))
+}
+
+/// Bring state file JSON up to STATE_SCHEMA_VERSION and return the version it had.
+/// Fails for files written by a newer build, which this one can't read safely.
+pub fn migrate_state(state: &mut Value) -> Result<u32> {
+    let version = schema_version(state);
+    if version > STATE_SCHEMA_VERSION {
+        eyre::bail!(
+            "state schema version {} is newer than the supported version {}; upgrade Oxwatcher",
+            version,
+            STATE_SCHEMA_VERSION
+        );
+    }
+    for migration in &MIGRATIONS[version as usize - 1..] {
+        migration(state);
+    }
+    if let Some(object) = state.as_object_mut() {
+        object.insert("schema_version".to_string(), STATE_SCHEMA_VERSION.into());
+    }
+    Ok(version)
+}
+
+/// JSON of a state file, decrypted with `cipher` if the file is encrypted, and
+/// whether it was
+fn read_value(path: &Path, cipher: Option<&StateCipher>) -> Result<(Value, bool)> {
+    parse_value(&fs::read(path)?, path, cipher)
+}
+
+/// JSON of state file content read from `path`, decrypted with `cipher` if it is
+/// encrypted, and whether it was
+fn parse_value(content: &[u8], path: &Path, cipher: Option<&StateCipher>) -> Result<(Value, bool)> {
+    let state: Value = serde_json::from_slice(content)?;
+    let Some(ciphertext) = state.get("encrypted").and_then(Value::as_str) else {
+        return Ok((state, false));
+    };
+    let cipher = cipher.ok_or_else(|| eyre!("{} is encrypted, set encryption.key to read it", path.display()))?;
+    let plaintext = cipher.decrypt(ciphertext).map_err(|e| eyre!("{}: {}", path.display(), e))?;
+    Ok((serde_json::from_slice(&plaintext)?, true))
+}
+
+/// Write state file JSON, encrypted with `cipher` if given
+fn write_value(path: &Path, state: &Value, cipher: Option<&StateCipher>) -> Result<()> {
+    write_atomic(path, &encode_value(state, cipher)?)
+}
+
+/// State file content of JSON, encrypted with `cipher` if given. Encrypted files keep
+/// the schema version readable without the key.
+fn encode_value(state: &Value, cipher: Option<&StateCipher>) -> Result<Vec<u8>> {
+    let content = match cipher {
+        Some(cipher) => serde_json::to_string_pretty(&json!({
+            "schema_version": schema_version(state),
+            "encrypted": cipher.encrypt(&serde_json::to_vec(state)?)?,
+        }))?,
+        None => serde_json::to_string_pretty(state)?,
+    };
+    Ok(content.into_bytes())
+}
+
+/// Read a state file, migrating older versions in memory. The upgraded format is
+/// written with the next save. Encrypted files need `cipher`; plain ones are read
+/// either way.
+pub(crate) fn read_state<T: DeserializeOwned>(path: &Path, cipher: Option<&StateCipher>) -> Result<T> {
+    decode_state(&fs::read(path)?, path, cipher)
+}
+
+/// `read_state` of content already read from `path`
+pub(crate) fn decode_state<T: DeserializeOwned>(content: &[u8], path: &Path, cipher: Option<&StateCipher>) -> Result<T> {
+    let (mut state, _) = parse_value(content, path, cipher)?;
+    let version = migrate_state(&mut state)?;
+    if version < STATE_SCHEMA_VERSION {
+        info!("🗄️  Migrating {} from schema version {} to {}", path.display(), version, STATE_SCHEMA_VERSION);
+    }
+    Ok(serde_json::from_value(state)?)
+}
+
+/// Write a state file with the current schema version, encrypted with `cipher` if given
+pub(crate) fn write_state<T: Serialize>(path: &Path, state: &T, cipher: Option<&StateCipher>) -> Result<()> {
+    write_atomic(path, &encode_state(state, cipher)?)
+}
+
+/// Content `write_state` would write
+pub(crate) fn encode_state<T: Serialize>(state: &T, cipher: Option<&StateCipher>) -> Result<Vec<u8>> {
+    let mut state = serde_json::to_value(state)?;
+    if let Some(object) = state.as_object_mut() {
+        object.insert("schema_version".to_string(), STATE_SCHEMA_VERSION.into());
+    }
+    encode_value(&state, cipher)
+}
+
+/// Upgrade a state file in place, keeping it encrypted if it was. Returns the version
+/// it had, or None if it doesn't exist or is already current.
+pub fn migrate_file(path: &Path, cipher: Option<&StateCipher>) -> Result<Option<u32>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let (mut state, encrypted) = read_value(path, cipher)?;
+    let version = migrate_state(&mut state)?;
+    if version == STATE_SCHEMA_VERSION {
+        return Ok(None);
+    }
+    write_value(path, &state, cipher.filter(|_| encrypted))?;
+    Ok(Some(version))
+}
@@ -1,227 +1,356 @@
-use crate::config::{TelegramConfig, DailyReportConfig};
-use crate::logger::{BalanceChange, BalanceChangeSummary};
+use crate::config::{
+    AlertSettings, MessageTemplates, Role, TelegramAccess, TelegramConfig, TelegramSilentConfig,
+    TelegramTopicsConfig,
+};
+use crate::export::{export_balances, export_history, ExportFormat, ExportKind};
+use crate::logger::{BalanceChange, BalanceChangeSummary, TokenBalanceChange};
 use crate::monitoring::BalanceInfo;
-use crate::storage::BalanceStorage;
-use alloy::primitives::U256;
+use crate::notifiers::{
+    previous_day_snapshot, render_template, report_baseline, split_message, test_change_alert, test_report,
+    AckOutcome, AuditLog, AuditSubject, BalanceReport, ChangeDigest, Escalator, LowBalanceAlert,
+    LowBalanceTracker, MonitorAlert, Notifier, SendQueue, ACK_CALLBACK_PREFIX, DRAINED_KIND, HEARTBEAT_KIND,
+    LOW_BALANCE_KIND, TEST_ALERT_KIND,
+};
+use crate::pricing::{format_usd, usd_suffix, Portfolio};
+use crate::storage::{
+    AuditQuery, BalanceStorage, ChatRegistration, ChatStorage, ChatSubscription, HistoryStore, StateBackend,
+};
+use crate::monitoring::{
+    format_gwei, AddressRegistry, Entities, GasPrice, GasTracker, GroupTotal, HealthTracker,
+    MaintenanceSchedule, RunwayForecasts,
+};
+use crate::providers::RpcHealth;
+use alloy::primitives::{Address, U256};
+use async_trait::async_trait;
+use chrono::{DateTime, Local, Utc};
 use eyre::Result;
-use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::fs;
-use std::path::Path;
+use reqwest::Url;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
-use std::time::SystemTime;
+use std::time::Instant;
 use teloxide::prelude::*;
-use teloxide::types::ChatId;
+use teloxide::types::{ChatId, InlineKeyboardButton, InlineKeyboardMarkup, InputFile, MessageId, ThreadId};
 use teloxide::utils::command::BotCommands;
+use teloxide::utils::html;
 use tokio::sync::RwLock;
-use chrono::{Local, NaiveTime};
-
-/// Registration information for a chat
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct ChatRegistration {
-    chat_id: i64,
-    user_id: i64,
-    username: String,
+use tracing::warn;
+
+/// Addresses per page in /balance navigation
+const BALANCE_PAGE_SIZE: usize = 8;
+
+/// Longest text Telegram accepts in one message; longer messages are split
+const MESSAGE_LIMIT: usize = 4096;
+
+/// Telegram's limits: about 30 messages per second overall, one per second to a
+/// private chat and 20 per minute to a group
+const MESSAGES_PER_SECOND: f64 = 30.0;
+const PRIVATE_CHAT_MESSAGES_PER_SECOND: f64 = 1.0;
+const GROUP_MESSAGES_PER_SECOND: f64 = 20.0 / 60.0;
+
+/// Attempts per message when Telegram answers with "retry after"
+const MAX_SEND_ATTEMPTS: u32 = 3;
+
+/// Page of the inline /balance navigation, encoded in callback data. Networks and
+/// addresses are referred to by position, since names may exceed Telegram's 64-byte limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BalanceView {
+    /// Network list with portfolio totals and gas prices
+    Networks,
+    /// One page of a network's addresses
+    Addresses { network: usize, page: usize },
+    /// Balances of one address; `page` is the address page to go back to
+    Address { network: usize, page: usize, address: usize },
 }
 
-/// Alert state for tracking when alerts were last sent
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct AlertState {
-    /// Last time alert was sent (Unix timestamp in seconds)
-    last_sent: u64,
-    /// Number of alerts sent (used to determine next interval)
-    alert_count: u32,
-}
-
-impl AlertState {
-    fn new() -> Self {
-        Self {
-            last_sent: 0,
-            alert_count: 0,
-        }
-    }
-
-    /// Get the required interval before next alert based on alert count
-    /// 1st: immediate, 2nd: 10min, 3rd: 1hr, 4th: 5hr, 5th: 20hr, 6th+: 20hr
-    fn get_next_interval_secs(&self) -> u64 {
-        match self.alert_count {
-            0 => 0,           // First alert - immediate
-            1 => 10 * 60,     // 10 minutes
-            2 => 60 * 60,     // 1 hour
-            3 => 5 * 60 * 60, // 5 hours
-            _ => 20 * 60 * 60, // 20 hours (for 4th and beyond)
+impl BalanceView {
+    /// Encode as callback data
+    pub fn to_data(&self) -> String {
+        match self {
+            Self::Networks => "bal".to_string(),
+            Self::Addresses { network, page } => format!("bal:{}:{}", network, page),
+            Self::Address { network, page, address } => format!("bal:{}:{}:{}", network, page, address),
         }
     }
 
-    /// Check if enough time has passed to send another alert
-    fn should_send_alert(&self) -> bool {
-        let now = SystemTime::now()
-            .duration_since(SystemTime::UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
-
-        let required_interval = self.get_next_interval_secs();
-        now >= self.last_sent + required_interval
-    }
-
-    /// Record that an alert was sent
-    fn record_alert_sent(&mut self) {
-        self.last_sent = SystemTime::now()
-            .duration_since(SystemTime::UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
-        self.alert_count += 1;
-    }
-
-    /// Reset alert state (e.g., when balance goes back above threshold)
-    fn reset(&mut self) {
-        self.last_sent = 0;
-        self.alert_count = 0;
-    }
-}
-
-/// Storage for alert states
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct AlertStateStorage {
-    /// Map of "network:alias" to alert state
-    states: HashMap<String, AlertState>,
-}
-
-impl AlertStateStorage {
-    fn new() -> Self {
-        Self {
-            states: HashMap::new(),
+    /// Decode callback data, None if it isn't a /balance page
+    pub fn parse(data: &str) -> Option<Self> {
+        let mut parts = data.split(':');
+        if parts.next()? != "bal" {
+            return None;
         }
-    }
-
-    fn load_from_file<P: AsRef<Path>>(path: P) -> Self {
-        let path = path.as_ref();
-        if !path.exists() {
-            return Self::new();
+        let numbers: Vec<usize> = parts.map(|part| part.parse().ok()).collect::<Option<_>>()?;
+        match numbers[..] {
+            [] => Some(Self::Networks),
+            [network, page] => Some(Self::Addresses { network, page }),
+            [network, page, address] => Some(Self::Address { network, page, address }),
+            _ => None,
         }
-
-        fs::read_to_string(path)
-            .ok()
-            .and_then(|content| serde_json::from_str(&content).ok())
-            .unwrap_or_else(Self::new)
-    }
-
-    fn save_to_file<P: AsRef<Path>>(&self, path: P) -> Result<()> {
-        let content = serde_json::to_string_pretty(&self)?;
-        fs::write(path, content)?;
-        Ok(())
-    }
-
-    fn make_key(network: &str, alias: &str) -> String {
-        format!("{}:{}", network, alias)
-    }
-
-    fn get_or_create(&mut self, network: &str, alias: &str) -> &mut AlertState {
-        let key = Self::make_key(network, alias);
-        self.states.entry(key).or_insert_with(AlertState::new)
     }
 }
 
-/// Storage for registered chat IDs
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct ChatStorage {
-    registrations: Vec<ChatRegistration>,
-}
-
-impl ChatStorage {
-    fn new() -> Self {
-        Self {
-            registrations: Vec::new(),
-        }
-    }
-
-    fn load_from_file<P: AsRef<Path>>(path: P) -> Self {
-        let path = path.as_ref();
-        if !path.exists() {
-            return Self::new();
+/// Group balances by network, keeping the order networks first appear in
+fn group_by_network(balances: &[BalanceInfo]) -> Vec<(String, Vec<&BalanceInfo>)> {
+    let mut networks: Vec<(String, Vec<&BalanceInfo>)> = Vec::new();
+    for balance in balances {
+        match networks.iter_mut().find(|(name, _)| *name == balance.network_name) {
+            Some((_, addresses)) => addresses.push(balance),
+            None => networks.push((balance.network_name.clone(), vec![balance])),
         }
-
-        fs::read_to_string(path)
-            .ok()
-            .and_then(|content| serde_json::from_str(&content).ok())
-            .unwrap_or_else(Self::new)
-    }
-
-    fn save_to_file<P: AsRef<Path>>(&self, path: P) -> Result<()> {
-        let content = serde_json::to_string_pretty(&self)?;
-        fs::write(path, content)?;
-        Ok(())
     }
+    networks
 }
 
 /// Telegram notifier for balance changes
 #[derive(Clone)]
 pub struct TelegramNotifier {
     bot: Bot,
+    /// Bot name from config, None for the main bot
+    bot_name: Option<String>,
+    channel_name: String,
     registered_chats: Arc<RwLock<HashMap<ChatId, ChatRegistration>>>,
     latest_balances: Arc<RwLock<Vec<BalanceInfo>>>,
-    allowed_users: Vec<String>,
-    storage_path: String,
-    daily_report_config: Option<DailyReportConfig>,
+    access: TelegramAccess,
+    registry: Option<Arc<AddressRegistry>>,
+    entities: Option<Arc<Entities>>,
+    runways: Option<Arc<RunwayForecasts>>,
+    gas: Option<Arc<GasTracker>>,
+    rpc: Option<Arc<RpcHealth>>,
+    health: Option<Arc<HealthTracker>>,
+    low_balance: Option<LowBalanceTracker>,
+    maintenance: Option<Arc<MaintenanceSchedule>>,
+    escalator: Option<Arc<Escalator>>,
+    started_at: Instant,
+    backend: Arc<dyn StateBackend>,
+    audit: AuditLog,
     balance_storage: Arc<RwLock<BalanceStorage>>,
+    history: Option<Arc<RwLock<HistoryStore>>>,
     show_full_address: bool,
-    alert_state_storage: Arc<RwLock<AlertStateStorage>>,
-    alert_state_path: String,
+    alerts: AlertSettings,
+    mute_low_balance: bool,
+    topics: TelegramTopicsConfig,
+    templates: MessageTemplates,
+    silent: TelegramSilentConfig,
+    /// Block explorer base URL per network name
+    explorers: HashMap<String, Url>,
+    send_queue: Arc<SendQueue>,
 }
 
 impl TelegramNotifier {
-    pub fn new(config: &TelegramConfig, balance_storage: Arc<RwLock<BalanceStorage>>, data_dir: &str) -> Self {
+    pub async fn new(
+        config: &TelegramConfig,
+        bot_name: Option<&str>,
+        balance_storage: Arc<RwLock<BalanceStorage>>,
+        backend: Arc<dyn StateBackend>,
+    ) -> Self {
         let bot = Bot::new(&config.bot_token);
-        let storage_path = format!("{}/telegram_chats.json", data_dir);
 
         // Load previously registered chats
-        let storage = ChatStorage::load_from_file(&storage_path);
+        let storage = backend.load_chats(bot_name).await.unwrap_or_else(|e| {
+            warn!("Failed to load telegram chats from {} backend: {}", backend.name(), e);
+            ChatStorage::new()
+        });
 
         // Filter only authorized users (auto-cleanup on startup)
-        // If "all" is in allowed_users, keep all registered chats
-        let is_public = config.allowed_users.iter().any(|u| u == "all");
         let registered_chats: HashMap<ChatId, ChatRegistration> = storage
             .registrations
             .into_iter()
-            .filter(|reg| is_public || config.allowed_users.contains(&reg.username))
+            .filter(|reg| config.access.allows(reg.chat_id, reg.user_id, Some(&reg.username)))
             .map(|reg| (ChatId(reg.chat_id), reg))
             .collect();
 
-        let alert_state_path = format!("{}/alert_states.json", data_dir);
-        let alert_state_storage = AlertStateStorage::load_from_file(&alert_state_path);
-
         Self {
             bot,
+            bot_name: bot_name.map(str::to_string),
+            channel_name: config.channel_name(),
             registered_chats: Arc::new(RwLock::new(registered_chats)),
             latest_balances: Arc::new(RwLock::new(Vec::new())),
-            allowed_users: config.allowed_users.clone(),
-            storage_path,
-            daily_report_config: config.daily_report.clone(),
+            access: config.access.clone(),
+            registry: None,
+            entities: None,
+            runways: None,
+            gas: None,
+            rpc: None,
+            health: None,
+            low_balance: None,
+            maintenance: None,
+            escalator: None,
+            started_at: Instant::now(),
+            audit: AuditLog::new(Arc::clone(&backend)),
+            backend,
             balance_storage,
+            history: None,
             show_full_address: config.show_full_address,
-            alert_state_storage: Arc::new(RwLock::new(alert_state_storage)),
-            alert_state_path,
+            alerts: config.alerts.clone(),
+            mute_low_balance: config.mute_low_balance,
+            topics: config.topics.clone(),
+            templates: config.templates.clone(),
+            silent: config.silent.clone(),
+            explorers: HashMap::new(),
+            send_queue: Arc::new(SendQueue::new(MESSAGES_PER_SECOND)),
+        }
+    }
+
+    /// Record deliveries in a shared audit log instead of a private one
+    pub fn with_audit(mut self, audit: AuditLog) -> Self {
+        self.audit = audit;
+        self
+    }
+
+    /// Link addresses to the block explorer of their network
+    pub fn with_explorers(mut self, explorers: HashMap<String, Url>) -> Self {
+        self.explorers = explorers;
+        self
+    }
+
+    /// Show tracked gas prices in /balance
+    pub fn with_gas(mut self, gas: Arc<GasTracker>) -> Self {
+        self.gas = Some(gas);
+        self
+    }
+
+    /// Enable /rpcstatus
+    pub fn with_rpc(mut self, rpc: Arc<RpcHealth>) -> Self {
+        self.rpc = Some(rpc);
+        self
+    }
+
+    /// Show last check per network in /status
+    pub fn with_health(mut self, health: Arc<HealthTracker>) -> Self {
+        self.health = Some(health);
+        self
+    }
+
+    /// Show pending low balance alert throttles in /status
+    pub fn with_low_balance(mut self, low_balance: LowBalanceTracker) -> Self {
+        self.low_balance = Some(low_balance);
+        self
+    }
+
+    /// Enable /maintenance and show open maintenance windows in /status
+    pub fn with_maintenance(mut self, maintenance: Arc<MaintenanceSchedule>) -> Self {
+        self.maintenance = Some(maintenance);
+        self
+    }
+
+    /// Enable the Acknowledge button on critical alerts and show unacknowledged ones in /status
+    pub fn with_escalator(mut self, escalator: Arc<Escalator>) -> Self {
+        self.escalator = Some(escalator);
+        self
+    }
+
+    /// Use balance history for day-over-day portfolio changes
+    pub fn with_history(mut self, history: Arc<RwLock<HistoryStore>>) -> Self {
+        self.history = Some(history);
+        self
+    }
+
+    /// Enable /add and /remove for admins
+    pub fn with_registry(mut self, registry: Arc<AddressRegistry>) -> Self {
+        self.registry = Some(registry);
+        self
+    }
+
+    /// Show entity totals in the portfolio and enable /balance <entity>
+    pub fn with_entities(mut self, entities: Arc<Entities>) -> Self {
+        self.entities = Some(entities);
+        self
+    }
+
+    /// Show runway forecasts in /report
+    pub fn with_runways(mut self, runways: Arc<RunwayForecasts>) -> Self {
+        self.runways = Some(runways);
+        self
+    }
+
+    /// Role of an allowed user
+    pub fn role(&self, user: &teloxide::types::User) -> Role {
+        self.access.role(user.id.0 as i64, user.username.as_deref())
+    }
+
+    /// Handle /add arguments: "<network> <address> <alias>"
+    async fn add_address(&self, args: &str) -> String {
+        let Some(registry) = &self.registry else {
+            return "❌ Adding addresses is not available.".to_string();
+        };
+
+        // Network names and aliases may contain spaces, so split around the address
+        let words: Vec<&str> = args.split_whitespace().collect();
+        let parsed = words.iter().enumerate()
+            .find_map(|(i, word)| Some((i, word.parse::<Address>().ok()?)));
+        let (network, address, alias) = match parsed {
+            Some((i, address)) if i > 0 && i + 1 < words.len() => {
+                (words[..i].join(" "), address, words[i + 1..].join(" "))
+            }
+            _ => return "Usage: /add &lt;network&gt; &lt;address&gt; &lt;alias&gt;".to_string(),
+        };
+
+        match registry.add(&network, &alias, address).await {
+            Ok(()) => format!(
+                "✅ Now monitoring <b>{}</b> on <b>{}</b>\n<code>{:?}</code>",
+                html::escape(&alias),
+                html::escape(&network),
+                address
+            ),
+            Err(e) => format!("❌ {}", html::escape(&e.to_string())),
         }
     }
 
-    /// Check if user is allowed to use the bot
-    pub fn is_user_allowed(&self, username: Option<&str>) -> bool {
-        // Special case: if "all" is in allowed_users, allow everyone
-        if self.allowed_users.iter().any(|u| u == "all") {
-            return true;
+    /// Handle /remove arguments: "<alias>"
+    async fn remove_address(&self, alias: &str) -> String {
+        let Some(registry) = &self.registry else {
+            return "❌ Removing addresses is not available.".to_string();
+        };
+        if alias.is_empty() {
+            return "Usage: /remove &lt;alias&gt;".to_string();
         }
 
-        // Check if username is in whitelist
-        if let Some(username) = username {
-            self.allowed_users.iter().any(|u| u == username)
-        } else {
-            false
+        match registry.remove(alias).await {
+            Ok(removed) => {
+                let mut storage = self.balance_storage.write().await;
+                for entry in &removed {
+                    storage.remove(&entry.network, &entry.config.alias);
+                }
+                let networks: Vec<&str> = removed.iter().map(|e| e.network.as_str()).collect();
+                format!("🗑️ Stopped monitoring <b>{}</b> on {}", html::escape(alias), html::escape(&networks.join(", ")))
+            }
+            Err(e) => format!("❌ {}", html::escape(&e.to_string())),
+        }
+    }
+
+    /// Send a test alert, a synthetic balance change and a sample report to the registered
+    /// chats through the regular delivery, for /testalert
+    pub async fn send_test_messages(&self, username: Option<&str>) -> String {
+        let source = match username {
+            Some(username) => format!("/testalert from @{}", username),
+            None => "/testalert".to_string(),
+        };
+        // Changes are shown on a monitored network, so chats subscribed to it receive them
+        let network = self.latest_balances.read().await.first().map(|balance| (balance.network_name.clone(), balance.chain_id));
+        let (network_name, chain_id) = network.unwrap_or_else(|| ("Test network".to_string(), 0));
+
+        let results = [
+            self.send_alert(&MonitorAlert::test(&source)).await,
+            self.send_change_alert(&test_change_alert(&network_name, chain_id)).await,
+            self.send_report(&test_report(&network_name, chain_id)).await,
+        ];
+        match results.into_iter().find_map(Result::err) {
+            Some(e) => format!("❌ Failed to send test messages: {}", e),
+            None => format!(
+                "🧪 Sent a test alert, a balance change on {} and a sample report to {} registered chat(s)",
+                html::escape(&network_name),
+                self.get_registered_chats_count().await
+            ),
         }
     }
 
+    /// Check if user is allowed to use the bot in a chat
+    pub fn is_user_allowed(&self, chat_id: ChatId, user: &teloxide::types::User) -> bool {
+        self.access.allows(chat_id.0, user.id.0 as i64, user.username.as_deref())
+    }
+
     /// Check if bot is in public mode (allows all users)
     pub fn is_public_mode(&self) -> bool {
-        self.allowed_users.iter().any(|u| u == "all")
+        self.access.is_public()
     }
 
     /// Get count of registered chats
@@ -233,31 +362,33 @@ impl TelegramNotifier {
     /// Register a chat for alerts
     pub async fn register_chat(&self, chat_id: ChatId, user: &teloxide::types::User) {
         let username = user.username.clone().unwrap_or_default();
+        let mut chats = self.registered_chats.write().await;
+        // Keep subscriptions and mute when an already registered chat runs /start again
+        let existing = chats.get(&chat_id);
         let registration = ChatRegistration {
             chat_id: chat_id.0,
             user_id: user.id.0 as i64,
             username,
+            subscriptions: existing.map(|r| r.subscriptions.clone()).unwrap_or_default(),
+            muted_until: existing.and_then(|r| r.muted_until),
         };
-
-        let mut chats = self.registered_chats.write().await;
         let was_new = chats.insert(chat_id, registration).is_none();
 
-        // Save to file if it's a new chat
+        // Persist if it's a new chat
         if was_new {
-            drop(chats); // Release lock before file I/O
+            drop(chats); // Release lock before storage I/O
             if let Err(e) = self.save_chats().await {
-                eprintln!("Failed to save telegram chats: {}", e);
+                warn!("Failed to save telegram chats: {}", e);
             }
         }
     }
 
-    /// Save registered chats to file
+    /// Save registered chats to the state backend
     async fn save_chats(&self) -> Result<()> {
         let chats = self.registered_chats.read().await;
         let registrations: Vec<ChatRegistration> = chats.values().cloned().collect();
-        let storage = ChatStorage { registrations };
-        storage.save_to_file(&self.storage_path)?;
-        Ok(())
+        drop(chats);
+        self.backend.save_chats(self.bot_name.as_deref(), &ChatStorage { registrations }).await
     }
 
     /// Check if chat is registered
@@ -272,501 +403,1134 @@ impl TelegramNotifier {
         if chats.remove(&chat_id).is_some() {
             drop(chats);
             if let Err(e) = self.save_chats().await {
-                eprintln!("Failed to save telegram chats after unregister: {}", e);
+                warn!("Failed to save telegram chats after unregister: {}", e);
             }
         }
     }
 
-    /// Send alert for balance changes to all registered chats
-    pub async fn send_alert(&self, changes: &BalanceChangeSummary) -> Result<()> {
-        if !changes.has_changes() {
-            return Ok(());
-        }
-
-        let message = self.format_change_message(changes);
-        let chats = self.registered_chats.read().await;
-        let is_public = self.is_public_mode();
+    /// Handle /subscribe arguments: "[<network|*> [/ <alias>]]", lists filters when empty
+    async fn subscribe(&self, chat_id: ChatId, args: &str) -> String {
+        let args = args.trim();
+        let mut chats = self.registered_chats.write().await;
+        let Some(registration) = chats.get_mut(&chat_id) else {
+            return "Please start the bot first with /start to receive updates.".to_string();
+        };
 
-        for (&chat_id, registration) in chats.iter() {
-            // Check if user is still authorized (skip check in public mode)
-            if !is_public && !self.allowed_users.contains(&registration.username) {
-                eprintln!("Skipping alert to chat {} (user '{}' no longer authorized)", chat_id, registration.username);
-                continue;
+        if args.is_empty() {
+            if registration.subscriptions.is_empty() {
+                return "📬 This chat receives alerts for all addresses.\n\n\
+                        Usage: /subscribe &lt;network|*&gt; [/ &lt;alias&gt;]".to_string();
             }
-
-            if let Err(e) = self
-                .bot
-                .send_message(chat_id, message.clone())
-                .parse_mode(teloxide::types::ParseMode::Html)
-                .await
-            {
-                eprintln!("Failed to send alert to chat {}: {}", chat_id, e);
+            let mut message = "📬 <b>Subscriptions</b>\n".to_string();
+            for subscription in &registration.subscriptions {
+                message.push_str(&format!("• {}\n", subscription.describe()));
             }
+            return message;
         }
 
-        Ok(())
-    }
-
-    /// Update stored balances
-    pub async fn update_balances(&self, balances: Vec<BalanceInfo>) {
-        let mut stored = self.latest_balances.write().await;
-        *stored = balances;
-    }
+        let Some(subscription) = parse_subscription(args) else {
+            return "Usage: /subscribe &lt;network|*&gt; [/ &lt;alias&gt;]".to_string();
+        };
+        if registration.subscriptions.contains(&subscription) {
+            return format!("Already subscribed to <b>{}</b>", subscription.describe());
+        }
+        let description = subscription.describe();
+        registration.subscriptions.push(subscription);
+        drop(chats);
 
-    /// Get latest balances
-    pub async fn get_balances(&self) -> Vec<BalanceInfo> {
-        self.latest_balances.read().await.clone()
+        if let Err(e) = self.save_chats().await {
+            warn!("Failed to save telegram chats: {}", e);
+        }
+        format!(
+            "✅ Subscribed to <b>{}</b>\n\
+             Alerts for other addresses will no longer be sent to this chat.",
+            description
+        )
     }
 
-    /// Format change message for Telegram
-    fn format_change_message(&self, changes: &BalanceChangeSummary) -> String {
-        let mut message = format!("🔔 <b>Balance Alert</b>\n\n");
+    /// Handle /unsubscribe arguments: "[<network|*> [/ <alias>]]", clears all filters when empty
+    async fn unsubscribe(&self, chat_id: ChatId, args: &str) -> String {
+        let args = args.trim();
+        let mut chats = self.registered_chats.write().await;
+        let Some(registration) = chats.get_mut(&chat_id) else {
+            return "Please start the bot first with /start to receive updates.".to_string();
+        };
 
-        // Network and address (full or shortened)
-        let display_addr = if self.show_full_address {
-            changes.address.clone()
+        let message = if args.is_empty() {
+            registration.subscriptions.clear();
+            "✅ Subscriptions cleared, this chat receives alerts for all addresses.".to_string()
         } else {
-            Self::shorten_address(&changes.address)
+            let Some(subscription) = parse_subscription(args) else {
+                return "Usage: /unsubscribe [&lt;network|*&gt; [/ &lt;alias&gt;]]".to_string();
+            };
+            let count = registration.subscriptions.len();
+            registration.subscriptions.retain(|s| s != &subscription);
+            if registration.subscriptions.len() == count {
+                return format!("❌ Not subscribed to <b>{}</b>", subscription.describe());
+            }
+            if registration.subscriptions.is_empty() {
+                format!("🗑️ Unsubscribed from <b>{}</b>, this chat receives alerts for all addresses.", subscription.describe())
+            } else {
+                format!("🗑️ Unsubscribed from <b>{}</b>", subscription.describe())
+            }
         };
-        message.push_str(&format!("🌐 <b>{}</b> (Chain ID: {})\n", changes.network_name, changes.chain_id));
-        message.push_str(&format!("📍 <b>{}</b>\n", changes.alias));
-        message.push_str(&format!("<code>{}</code>\n\n", display_addr));
-
-        // Format ETH changes
-        if let Some(eth) = &changes.eth_change {
-            if !matches!(eth.change, BalanceChange::NoChange) {
-                let (emoji, sign) = match eth.change {
-                    BalanceChange::Increase => ("📈", "+"),
-                    BalanceChange::Decrease => ("📉", ""),
-                    BalanceChange::NoChange => ("", ""),
-                };
-
-                let diff = Self::calculate_diff(&eth.new_balance, &eth.old_balance);
-                let percent = Self::calculate_percent_change(&eth.new_balance, &eth.old_balance);
+        drop(chats);
 
-                message.push_str(&format!("💰 <b>ETH</b>\n"));
-                if percent.abs() >= 0.01 {
-                    message.push_str(&format!("{} <b>{}{}</b> ({:+.2}%)\n", emoji, sign, diff, percent));
-                } else {
-                    message.push_str(&format!("{} <b>{}{}</b>\n", emoji, sign, diff));
-                }
-                message.push_str(&format!("{} → {}\n\n", eth.old_formatted, eth.new_formatted));
-            }
+        if let Err(e) = self.save_chats().await {
+            warn!("Failed to save telegram chats: {}", e);
         }
+        message
+    }
 
-        // Format token changes
-        for token in &changes.token_changes {
-            if !matches!(token.change, BalanceChange::NoChange) {
-                let (emoji, sign) = match token.change {
-                    BalanceChange::Increase => ("📈", "+"),
-                    BalanceChange::Decrease => ("📉", ""),
-                    BalanceChange::NoChange => ("", ""),
-                };
+    /// Handle /mute arguments: "<duration>", e.g. 30m, 2h or 1d
+    async fn mute(&self, chat_id: ChatId, args: &str) -> String {
+        let Some(duration) = parse_mute_duration(args.trim()) else {
+            return "Usage: /mute &lt;duration&gt;, e.g. /mute 30m, /mute 2h or /mute 1d".to_string();
+        };
 
-                let diff = Self::calculate_diff(&token.new_balance, &token.old_balance);
-                let percent = Self::calculate_percent_change(&token.new_balance, &token.old_balance);
+        let Some(until) = expiry(Utc::now().timestamp() as u64, duration) else {
+            return "❌ Mute duration is too long".to_string();
+        };
+        let mut chats = self.registered_chats.write().await;
+        let Some(registration) = chats.get_mut(&chat_id) else {
+            return "Please start the bot first with /start to receive updates.".to_string();
+        };
+        registration.muted_until = Some(until);
+        drop(chats);
 
-                message.push_str(&format!("💰 <b>{}</b>\n", token.alias));
-                if percent.abs() >= 0.01 {
-                    message.push_str(&format!("{} <b>{}{}</b> ({:+.2}%)\n", emoji, sign, diff, percent));
-                } else {
-                    message.push_str(&format!("{} <b>{}{}</b>\n", emoji, sign, diff));
-                }
-                message.push_str(&format!("{} → {}\n\n", token.old_formatted, token.new_formatted));
-            }
+        if let Err(e) = self.save_chats().await {
+            warn!("Failed to save telegram chats: {}", e);
         }
 
-        message
-    }
-
-    /// Shorten address for display (0xabcd...1234)
-    fn shorten_address(address: &str) -> String {
-        if address.len() > 10 {
-            format!("{}...{}", &address[..6], &address[address.len()-4..])
+        let until_local = DateTime::from_timestamp(until as i64, 0)
+            .map(|t| t.with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string())
+            .unwrap_or_default();
+        let low_balance_note = if self.mute_low_balance {
+            "Low balance alerts are muted too."
         } else {
-            address.to_string()
-        }
+            "Low balance alerts will still be sent."
+        };
+        format!("🔕 Balance change alerts muted until <b>{}</b>.\n{}\nUse /unmute to resume.", until_local, low_balance_note)
     }
 
-    /// Calculate difference between two U256 values as formatted string
-    fn calculate_diff(new: &U256, old: &U256) -> String {
-        use alloy::primitives::utils::format_units;
+    /// Handle /maintenance arguments: "<duration> [network]", "off", or nothing to list open windows
+    async fn maintenance(&self, args: &str, username: Option<&str>) -> String {
+        let Some(maintenance) = &self.maintenance else {
+            return "❌ Maintenance windows are not available.".to_string();
+        };
+        let now = Utc::now().timestamp() as u64;
 
-        if new > old {
-            let diff = *new - *old;
-            format_units(diff, 18).unwrap_or_else(|_| diff.to_string())
-        } else {
-            let diff = *old - *new;
-            format_units(diff, 18).unwrap_or_else(|_| diff.to_string())
+        let args = args.trim();
+        if args.is_empty() {
+            let active = maintenance.active(now).await;
+            if active.is_empty() {
+                return "No maintenance window is open.".to_string();
+            }
+            let mut message = "🛠️ <b>Maintenance</b>\n".to_string();
+            for window in active {
+                message.push_str(&format!(
+                    "{} ({}): {} left\n",
+                    html::escape(&window.name),
+                    html::escape(&window.scope),
+                    format_duration(window.until.saturating_sub(now))
+                ));
+            }
+            return message;
+        }
+        if args == "off" {
+            return match maintenance.end(now).await {
+                0 => "No ad-hoc maintenance window is open.".to_string(),
+                ended => format!("✅ Ended {} maintenance window(s), alerts resume.", ended),
+            };
         }
-    }
 
-    /// Calculate percent change
-    fn calculate_percent_change(new: &U256, old: &U256) -> f64 {
-        if *old == U256::ZERO {
-            return 0.0;
+        let (duration, network) = match args.split_once(char::is_whitespace) {
+            Some((duration, network)) => (duration, Some(network.trim().to_string())),
+            None => (args, None),
+        };
+        let Some(duration) = parse_mute_duration(duration) else {
+            return "Usage: /maintenance &lt;duration&gt; [network], /maintenance off, or /maintenance to list open windows".to_string();
+        };
+        if let (Some(network), Some(health)) = (&network, &self.health) {
+            if !health.all().await.iter().any(|n| &n.network_name == network) {
+                return format!("❌ Unknown network: {}", html::escape(network));
+            }
         }
 
-        let old_f64 = old.to_string().parse::<f64>().unwrap_or(0.0);
-        let new_f64 = new.to_string().parse::<f64>().unwrap_or(0.0);
+        let Some(until) = expiry(now, duration) else {
+            return "❌ Maintenance duration is too long".to_string();
+        };
+        let started_by = format!("@{}", username.unwrap_or("unknown"));
+        maintenance.start(network.clone(), until, &started_by).await;
+        let until_local = DateTime::from_timestamp(until as i64, 0)
+            .map(|t| t.with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string())
+            .unwrap_or_default();
+        format!(
+            "🛠️ Maintenance on <b>{}</b> until <b>{}</b>.\n\
+             Balance change, rule and anomaly alerts are suppressed, balances are still recorded.\n\
+             Use /maintenance off to end it.",
+            html::escape(network.as_deref().unwrap_or("all networks")),
+            until_local
+        )
+    }
 
-        if old_f64 == 0.0 {
-            return 0.0;
+    /// Handle /unmute
+    async fn unmute(&self, chat_id: ChatId) -> String {
+        let mut chats = self.registered_chats.write().await;
+        let Some(registration) = chats.get_mut(&chat_id) else {
+            return "Please start the bot first with /start to receive updates.".to_string();
+        };
+        if registration.muted_until.take().is_none() {
+            return "This chat is not muted.".to_string();
         }
+        drop(chats);
 
-        ((new_f64 - old_f64) / old_f64) * 100.0
+        if let Err(e) = self.save_chats().await {
+            warn!("Failed to save telegram chats: {}", e);
+        }
+        "🔔 Alerts resumed.".to_string()
     }
 
-    /// Format balance status message
-    fn format_balance_message(&self, balances: &[BalanceInfo]) -> String {
-        if balances.is_empty() {
-            return "No balance data available yet.".to_string();
-        }
+    /// Send HTML message to all registered and still authorized chats
+    async fn broadcast(&self, message: &str, subject: &AuditSubject) {
+        self.broadcast_where(message, subject, |_| true).await;
+    }
 
-        let mut message = String::from("💰 <b>Current Balances</b>\n\n");
+    /// Send to matching chats, recording each delivery in the audit log
+    async fn broadcast_where(
+        &self,
+        message: &str,
+        subject: &AuditSubject,
+        filter: impl Fn(&ChatRegistration) -> bool,
+    ) {
+        self.broadcast_markup_where(message, None, subject, filter).await;
+    }
 
-        for balance in balances {
-            let display_addr = if self.show_full_address {
-                format!("{:?}", balance.address)
-            } else {
-                Self::shorten_address(&format!("{:?}", balance.address))
-            };
-            message.push_str(&format!("🌐 <b>{}</b> (Chain ID: {})\n", balance.network_name, balance.chain_id));
-            message.push_str(&format!("📍 <b>{}</b>\n", balance.alias));
-            message.push_str(&format!("<code>{}</code>\n\n", display_addr));
+    /// Send to matching chats with an optional inline keyboard
+    async fn broadcast_markup_where(
+        &self,
+        message: &str,
+        keyboard: Option<InlineKeyboardMarkup>,
+        subject: &AuditSubject,
+        filter: impl Fn(&ChatRegistration) -> bool,
+    ) {
+        // Pick the chats up front: sending waits on pacing and rate limits, which must not
+        // hold up /start, /mute and the other commands that update the registrations
+        let targets: Vec<ChatId> = {
+            let chats = self.registered_chats.read().await;
+            chats
+                .iter()
+                .filter(|(_, registration)| filter(registration))
+                .filter(|(&chat_id, registration)| {
+                    // Check if user or chat is still authorized
+                    let allowed = self.access.allows(registration.chat_id, registration.user_id, Some(&registration.username));
+                    if !allowed {
+                        warn!("Skipping message to chat {} (user '{}' no longer authorized)", chat_id, registration.username);
+                    }
+                    allowed
+                })
+                .map(|(&chat_id, _)| chat_id)
+                .collect()
+        };
 
-            message.push_str(&format!("💵 ETH: <b>{}</b>\n", balance.eth_formatted));
+        for chat_id in targets {
+            self.send_to_chat(chat_id, message, keyboard.clone(), subject).await;
+        }
+    }
 
-            for token in &balance.token_balances {
-                message.push_str(&format!("💵 {}: <b>{}</b>\n", token.alias, token.formatted));
+    /// Send HTML message to one chat, split into parts if it's too long for Telegram, and
+    /// record the delivery in the audit log. The keyboard is attached to the last part.
+    async fn send_to_chat(&self, chat_id: ChatId, message: &str, keyboard: Option<InlineKeyboardMarkup>, subject: &AuditSubject) {
+        let parts = split_message(message, MESSAGE_LIMIT);
+        let last = parts.len() - 1;
+        let mut result = Ok(());
+        for (i, part) in parts.into_iter().enumerate() {
+            let keyboard = if i == last { keyboard.clone() } else { None };
+            result = self.send_part(chat_id, part, keyboard, subject).await;
+            if result.is_err() {
+                break;
             }
-            message.push_str("\n");
         }
+        if let Err(e) = &result {
+            warn!("Failed to send message to chat {}: {}", chat_id, e);
+        }
+        self.audit.record(subject.entry(self.name(), Some(chat_id.to_string()), &result)).await;
+    }
 
-        message
+    /// Send one part through the send queue within the rate limits, waiting out "retry after"
+    /// responses. In groups it goes to the forum topic configured for its kind or network.
+    async fn send_part(&self, chat_id: ChatId, text: String, keyboard: Option<InlineKeyboardMarkup>, subject: &AuditSubject) -> Result<()> {
+        let chat_rate = if chat_id.is_user() { PRIVATE_CHAT_MESSAGES_PER_SECOND } else { GROUP_MESSAGES_PER_SECOND };
+        // Private chats have no topics
+        let thread_id = self.topics.thread_id(&subject.kind, subject.network.as_deref()).filter(|_| !chat_id.is_user());
+        let silent = self.silent.is_silent(&subject.kind, subject.severity);
+        let mut attempt = 1;
+        loop {
+            self.send_queue.acquire(chat_id.0, chat_rate).await;
+            let mut request = self.bot.send_message(chat_id, text.clone()).parse_mode(teloxide::types::ParseMode::Html);
+            if let Some(keyboard) = keyboard.clone() {
+                request = request.reply_markup(keyboard);
+            }
+            if let Some(thread_id) = thread_id {
+                request = request.message_thread_id(ThreadId(MessageId(thread_id)));
+            }
+            if silent {
+                request = request.disable_notification(true);
+            }
+            match request.await {
+                Ok(_) => return Ok(()),
+                Err(teloxide::RequestError::RetryAfter(secs)) if attempt < MAX_SEND_ATTEMPTS => {
+                    warn!("Telegram rate limit hit for chat {}, retrying in {}s", chat_id, secs.seconds());
+                    tokio::time::sleep(secs.duration()).await;
+                    attempt += 1;
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
     }
 
-    /// Generate daily diff report for all addresses and networks
-    async fn format_daily_report(&self) -> String {
-        let balances = self.latest_balances.read().await;
-        let storage = self.balance_storage.read().await;
+    /// Message body shared by monitor alerts
+    pub fn format_alert_message(&self, alert: &MonitorAlert) -> String {
+        if let Some(template) = &self.templates.alert {
+            return render_template(template, &[
+                ("title", html::escape(&alert.title)),
+                ("network", html::escape(&alert.network_name)),
+                ("chain_id", alert.chain_id.map(|id| id.to_string()).unwrap_or_default()),
+                ("details", html::escape(&alert.lines.join("\n"))),
+            ]);
+        }
+        let mut message = format!(
+            "🔔 <b>{}</b>\n\n🌐 <b>{}</b>{}\n",
+            html::escape(&alert.title),
+            html::escape(&alert.network_name),
+            alert.chain_suffix()
+        );
+        for line in &alert.lines {
+            message.push_str(&format!("{}\n", html::escape(line)));
+        }
+        message
+    }
 
-        if balances.is_empty() {
-            return "📊 <b>Daily Balance Report</b>\n\nNo balance data available yet.".to_string();
+    /// Check if a chat receives a critical alert: drained alerts go to every chat, and critical
+    /// low balance tiers follow the alias subscriptions and mute setting of regular low balance alerts
+    pub fn wants_critical_alert(&self, registration: &ChatRegistration, alert: &MonitorAlert, now: u64) -> bool {
+        match alert.kind.as_str() {
+            DRAINED_KIND => true,
+            LOW_BALANCE_KIND => {
+                let alias = alert.data.get("alias").and_then(|alias| alias.as_str()).unwrap_or_default();
+                registration.wants(&alert.network_name, alias) && !(self.mute_low_balance && registration.is_muted(now))
+            }
+            _ => registration.wants_network(&alert.network_name) && !registration.is_muted(now),
         }
+    }
 
-        let mut message = String::from("📊 <b>Daily Balance Report</b>\n");
-        message.push_str(&format!("📅 {}\n\n", Local::now().format("%Y-%m-%d %H:%M:%S")));
+    fn ack_keyboard(ack_id: u64) -> InlineKeyboardMarkup {
+        let button = InlineKeyboardButton::callback("✅ Acknowledge", format!("{}{}", ACK_CALLBACK_PREFIX, ack_id));
+        InlineKeyboardMarkup::new(vec![vec![button]])
+    }
 
-        let mut total_changes = 0;
-        let mut has_any_changes = false;
+    /// Send an escalated critical alert to the escalation chats, whether or not they're registered
+    pub async fn send_escalation(&self, chats: &[i64], alert: &MonitorAlert, ack_id: u64) {
+        let message = self.format_alert_message(alert);
+        let subject = AuditSubject::alert(alert);
+        for &chat_id in chats {
+            self.send_to_chat(ChatId(chat_id), &message, Some(Self::ack_keyboard(ack_id)), &subject).await;
+        }
+    }
 
-        for balance in balances.iter() {
-            if let Some(previous) = storage.get(&balance.network_name, &balance.alias) {
-                let display_addr = if self.show_full_address {
-                    format!("{:?}", balance.address)
-                } else {
-                    Self::shorten_address(&format!("{:?}", balance.address))
-                };
-                let mut address_changes = Vec::new();
-
-                // Check ETH balance changes
-                if balance.eth_balance != previous.eth_balance {
-                    let (emoji, sign) = if balance.eth_balance > previous.eth_balance {
-                        ("📈", "+")
-                    } else {
-                        ("📉", "")
-                    };
-                    let diff = Self::calculate_diff(&balance.eth_balance, &previous.eth_balance);
-                    let percent = Self::calculate_percent_change(&balance.eth_balance, &previous.eth_balance);
-
-                    let change_str = if percent.abs() >= 0.01 {
-                        format!("{} ETH: {}{} ({:+.2}%) | {} → {}",
-                            emoji, sign, diff, percent, previous.eth_formatted, balance.eth_formatted)
-                    } else {
-                        format!("{} ETH: {}{} | {} → {}",
-                            emoji, sign, diff, previous.eth_formatted, balance.eth_formatted)
-                    };
-                    address_changes.push(change_str);
-                    total_changes += 1;
-                }
+    /// Handle the Acknowledge button of critical alert `id`
+    async fn acknowledge(&self, id: u64, username: &str) -> String {
+        let Some(escalator) = &self.escalator else {
+            return "Acknowledgements are not enabled.".to_string();
+        };
+        let now = Utc::now().timestamp() as u64;
+        match escalator.acknowledge(id, username, now).await {
+            AckOutcome::Acknowledged => format!("✅ Alert #{} acknowledged by @{}", id, username),
+            AckOutcome::AlreadyAcknowledged(ack) => format!("Alert #{} was already acknowledged by @{}", id, ack.by),
+            AckOutcome::Unknown => format!("Alert #{} is no longer tracked", id),
+        }
+    }
 
-                // Check token balance changes
-                let previous_tokens: HashMap<_, _> = previous.token_balances.iter()
-                    .map(|t| (t.alias.as_str(), t))
-                    .collect();
+    /// Format the most recent audit log entries for /history
+    async fn delivery_history(&self, args: &str) -> String {
+        let limit = match args {
+            "" => 10,
+            count => match count.parse::<usize>() {
+                Ok(count) if (1..=50).contains(&count) => count,
+                _ => return "Usage: /history [count], count between 1 and 50".to_string(),
+            },
+        };
 
-                for token in &balance.token_balances {
-                    if let Some(prev_token) = previous_tokens.get(token.alias.as_str()) {
-                        if token.balance != prev_token.balance {
-                            let (emoji, sign) = if token.balance > prev_token.balance {
-                                ("📈", "+")
-                            } else {
-                                ("📉", "")
-                            };
-                            let diff = Self::calculate_diff(&token.balance, &prev_token.balance);
-                            let percent = Self::calculate_percent_change(&token.balance, &prev_token.balance);
-
-                            let change_str = if percent.abs() >= 0.01 {
-                                format!("{} {}: {}{} ({:+.2}%) | {} → {}",
-                                    emoji, token.alias, sign, diff, percent, prev_token.formatted, token.formatted)
-                            } else {
-                                format!("{} {}: {}{} | {} → {}",
-                                    emoji, token.alias, sign, diff, prev_token.formatted, token.formatted)
-                            };
-                            address_changes.push(change_str);
-                            total_changes += 1;
-                        }
-                    }
-                }
+        let query = AuditQuery { limit: Some(limit), ..Default::default() };
+        let entries = match self.audit.query(&query).await {
+            Ok(entries) => entries,
+            Err(e) => return format!("❌ Failed to read audit log: {}", html::escape(&e.to_string())),
+        };
+        if entries.is_empty() {
+            return "No alerts delivered yet.".to_string();
+        }
 
-                if !address_changes.is_empty() {
-                    has_any_changes = true;
-                    message.push_str(&format!("🌐 <b>{}</b> | 📍 <b>{}</b>\n", balance.network_name, balance.alias));
-                    message.push_str(&format!("<code>{}</code>\n", display_addr));
-                    for change in address_changes {
-                        message.push_str(&format!("   {}\n", change));
-                    }
-                    message.push_str("\n");
-                }
+        let mut message = "📜 <b>Recent alert deliveries</b>\n".to_string();
+        for entry in entries {
+            let sent_at = DateTime::from_timestamp(entry.sent_at as i64, 0)
+                .map(|dt| dt.format("%Y-%m-%d %H:%M:%S UTC").to_string())
+                .unwrap_or_default();
+            let status = if entry.success { "✅" } else { "❌" };
+            let chat = entry.chat.map(|chat| format!(" → {}", chat)).unwrap_or_default();
+            message.push_str(&format!(
+                "\n{} {} · {}{}\n   {}: {} <code>{}</code>\n",
+                status,
+                sent_at,
+                entry.channel,
+                chat,
+                entry.kind,
+                html::escape(&entry.subject),
+                &entry.content_hash[..entry.content_hash.len().min(12)],
+            ));
+            if let Some(error) = entry.error {
+                message.push_str(&format!("   {}\n", html::escape(&error)));
             }
         }
+        message
+    }
 
-        if !has_any_changes {
-            message.push_str("✅ No balance changes detected in the last period.\n");
-        } else {
-            message.push_str(&format!("📈 <b>Total changes:</b> {}\n", total_changes));
+    /// Format per-node RPC status for /rpcstatus
+    fn format_rpc_status(&self) -> String {
+        let networks = self.rpc.as_ref().map(|rpc| rpc.all()).unwrap_or_default();
+        if networks.is_empty() {
+            return "No RPC nodes tracked.".to_string();
+        }
+        let now = Utc::now().timestamp() as u64;
+
+        let mut message = "📡 <b>RPC node status</b>\n".to_string();
+        for network in networks {
+            message.push_str(&format!("\n🌐 <b>{}</b>\n", html::escape(&network.network_name)));
+            for node in &network.nodes {
+                let icon = match (node.is_used(), node.is_up()) {
+                    _ if node.excluded.is_some() => "⛔",
+                    (false, _) => "⚪",
+                    (true, true) => "🟢",
+                    (true, false) => "🔴",
+                };
+                let latency = node.avg_latency_ms.map(|ms| format!(", {:.0} ms", ms)).unwrap_or_default();
+                let failovers = if node.failovers > 0 { format!(", failed {}x", node.failovers) } else { String::new() };
+                message.push_str(&format!(
+                    "{} {}: {} ok / {} errors{}{}\n",
+                    icon,
+                    node.node,
+                    node.successes,
+                    node.errors,
+                    latency,
+                    failovers,
+                ));
+                if let Some(reason) = &node.excluded {
+                    message.push_str(&format!("   Excluded: {}\n", html::escape(reason)));
+                } else if let (false, Some(error)) = (node.is_up(), &node.last_error) {
+                    let since = node.down_since
+                        .map(|since| format!(" (down {})", format_duration(now.saturating_sub(since))))
+                        .unwrap_or_default();
+                    message.push_str(&format!("   {}{}\n", html::escape(error), since));
+                }
+            }
         }
-
         message
     }
 
-    /// Check for low balance alerts and send if needed (with throttling)
-    pub async fn check_low_balance_alerts(&self, balance: &BalanceInfo, min_eth_threshold: Option<f64>, token_thresholds: &HashMap<String, f64>) -> Result<()> {
-        let display_addr = if self.show_full_address {
-            format!("{:?}", balance.address)
-        } else {
-            Self::shorten_address(&format!("{:?}", balance.address))
-        };
-
-        // Check if we should send alert for this address
-        let mut alert_storage = self.alert_state_storage.write().await;
-        let alert_state = alert_storage.get_or_create(&balance.network_name, &balance.alias);
-
-        // Check ETH balance
-        let eth_is_low = if let Some(threshold) = min_eth_threshold {
-            let eth_value: f64 = balance.eth_formatted.parse().unwrap_or(0.0);
-            eth_value < threshold && eth_value > 0.0
-        } else {
-            false
+    /// Format watcher diagnostics for /status
+    async fn format_status(&self) -> String {
+        let now = Utc::now().timestamp() as u64;
+        let mut message = format!(
+            "🩺 <b>Watcher status</b>\n\nUptime: {}\n",
+            format_duration(self.started_at.elapsed().as_secs())
+        );
+
+        let balances = self.get_balances().await;
+        let tokens: usize = balances.iter().map(|b| b.token_balances.len()).sum();
+        message.push_str(&format!("Monitored: {} address(es), {} token balance(s)\n", balances.len(), tokens));
+
+        let networks = match &self.health {
+            Some(health) => health.all().await,
+            None => Vec::new(),
         };
-
-        // Check token balances
-        let tokens_are_low = balance.token_balances.iter().any(|token| {
-            if let Some(&threshold) = token_thresholds.get(&token.alias) {
-                let token_value: f64 = token.formatted.parse().unwrap_or(0.0);
-                token_value < threshold && token_value > 0.0
-            } else {
-                false
+        if !networks.is_empty() {
+            message.push_str("\n🌐 <b>Networks</b>\n");
+        }
+        for network in &networks {
+            let icon = if network.is_ok() { "✅" } else { "❌" };
+            let last_check = match network.last_success {
+                Some(at) => format!("last check {} ago", format_duration(now.saturating_sub(at))),
+                None => "no successful check yet".to_string(),
+            };
+            let rpc = self.rpc.as_ref().and_then(|rpc| rpc.network(&network.network_name)).map(|status| {
+                let up = status.nodes.iter().filter(|n| n.excluded.is_none() && (!n.is_used() || n.is_up())).count();
+                let failovers: u64 = status.nodes.iter().map(|n| n.failovers).sum();
+                let failovers = if failovers > 0 { format!(", {} failovers", failovers) } else { String::new() };
+                format!(", RPC {}/{} up{}", up, status.nodes.len(), failovers)
+            });
+            message.push_str(&format!(
+                "{} {}: {}{}\n",
+                icon,
+                html::escape(&network.network_name),
+                last_check,
+                rpc.unwrap_or_default()
+            ));
+            if let (false, Some(error)) = (network.is_ok(), &network.last_error) {
+                message.push_str(&format!("   {}\n", html::escape(error)));
             }
-        });
-
-        let balance_is_low = eth_is_low || tokens_are_low;
+        }
 
-        // If balance is back to normal, reset alert state
-        if !balance_is_low {
-            if alert_state.alert_count > 0 {
-                alert_state.reset();
-                // Save state
-                if let Err(e) = alert_storage.save_to_file(&self.alert_state_path) {
-                    eprintln!("Failed to save alert state: {}", e);
+        match self.backend.storage_sizes().await {
+            Ok(sizes) => {
+                message.push_str(&format!("\n💾 <b>Storage</b> ({})\n", self.backend.name()));
+                for (name, size) in sizes {
+                    message.push_str(&format!("{}: {}\n", html::escape(&name), format_bytes(size)));
                 }
             }
-            return Ok(());
+            Err(e) => message.push_str(&format!("\n💾 Storage sizes unavailable: {}\n", html::escape(&e.to_string()))),
         }
 
-        // Check if we should send alert based on throttling
-        if !alert_state.should_send_alert() {
-            return Ok(()); // Too soon to send another alert
+        if let Some(maintenance) = &self.maintenance {
+            let active = maintenance.active(now).await;
+            if !active.is_empty() {
+                message.push_str(&format!("\n🛠️ <b>Maintenance</b>: {}\n", active.len()));
+            }
+            for window in active {
+                message.push_str(&format!(
+                    "{} ({}): {} left\n",
+                    html::escape(&window.name),
+                    html::escape(&window.scope),
+                    format_duration(window.until.saturating_sub(now))
+                ));
+            }
         }
 
-        // Build alert messages
-        let mut alerts = Vec::new();
-
-        if eth_is_low {
-            if let Some(threshold) = min_eth_threshold {
-                let next_interval = match alert_state.alert_count {
-                    0 => "Next alert in 10 minutes".to_string(),
-                    1 => "Next alert in 1 hour".to_string(),
-                    2 => "Next alert in 5 hours".to_string(),
-                    3 => "Next alert in 20 hours".to_string(),
-                    _ => "Alerts every 20 hours".to_string(),
-                };
-
-                alerts.push(format!("⚠️ <b>LOW BALANCE ALERT #{}</b>\n\n\
-                                    🌐 <b>{}</b> (Chain ID: {})\n\
-                                    📍 <b>{}</b>\n\
-                                    <code>{}</code>\n\n\
-                                    💰 ETH: <b>{}</b>\n\
-                                    📉 Below threshold: <b>{}</b> ETH\n\
-                                    🚨 <b>Please top up your balance!</b>\n\n\
-                                    ⏰ {}",
-                    alert_state.alert_count + 1,
-                    balance.network_name,
-                    balance.chain_id,
-                    balance.alias,
-                    display_addr,
-                    balance.eth_formatted,
-                    threshold,
-                    next_interval
+        if let Some(escalator) = &self.escalator {
+            let unacknowledged = escalator.unacknowledged().await;
+            if !unacknowledged.is_empty() {
+                message.push_str(&format!("\n📟 <b>Unacknowledged critical alerts</b>: {}\n", unacknowledged.len()));
+            }
+            for tracked in unacknowledged {
+                let state = if tracked.escalated_at.is_some() { "escalated" } else { "open" };
+                message.push_str(&format!(
+                    "#{} {} ({}, raised {} ago)\n",
+                    tracked.id,
+                    html::escape(&tracked.alert.title),
+                    state,
+                    format_duration(now.saturating_sub(tracked.raised_at))
                 ));
             }
         }
 
-        for token in &balance.token_balances {
-            if let Some(&threshold) = token_thresholds.get(&token.alias) {
-                let token_value: f64 = token.formatted.parse().unwrap_or(0.0);
-                if token_value < threshold && token_value > 0.0 {
-                    let next_interval = match alert_state.alert_count {
-                        0 => "Next alert in 10 minutes".to_string(),
-                        1 => "Next alert in 1 hour".to_string(),
-                        2 => "Next alert in 5 hours".to_string(),
-                        3 => "Next alert in 20 hours".to_string(),
-                        _ => "Alerts every 20 hours".to_string(),
-                    };
-
-                    alerts.push(format!("⚠️ <b>LOW BALANCE ALERT #{}</b>\n\n\
-                                        🌐 <b>{}</b> (Chain ID: {})\n\
-                                        📍 <b>{}</b>\n\
-                                        <code>{}</code>\n\n\
-                                        💰 {}: <b>{}</b>\n\
-                                        📉 Below threshold: <b>{}</b>\n\
-                                        🚨 <b>Please top up your balance!</b>\n\n\
-                                        ⏰ {}",
-                        alert_state.alert_count + 1,
-                        balance.network_name,
-                        balance.chain_id,
-                        balance.alias,
-                        display_addr,
-                        token.alias,
-                        token.formatted,
-                        threshold,
-                        next_interval
-                    ));
-                }
+        if let Some(low_balance) = &self.low_balance {
+            let throttled = low_balance.throttled().await;
+            message.push_str(&format!("\n⏳ <b>Low balance throttles</b>: {}\n", throttled.len()));
+            for status in throttled {
+                let next = status.next_alert_at.saturating_sub(now);
+                message.push_str(&format!(
+                    "{}: {} alert(s) sent, next in {}\n",
+                    html::escape(&status.key),
+                    status.alert_count,
+                    format_duration(next)
+                ));
             }
         }
 
-        // Send alerts
-        if !alerts.is_empty() {
-            let chats = self.registered_chats.read().await;
-            let is_public = self.is_public_mode();
+        message
+    }
 
-            for (&chat_id, registration) in chats.iter() {
-                if !is_public && !self.allowed_users.contains(&registration.username) {
-                    continue;
+    /// Build a CSV or XLSX export as (file name, content), or an error message for the chat
+    async fn export_file(&self, args: &str) -> std::result::Result<(String, Vec<u8>), String> {
+        let (format, args) = ExportFormat::split(args);
+        let kind = ExportKind::parse(args).map_err(|e| format!("❌ {}", e))?;
+        let file_name = kind.file_name(Utc::now().date_naive(), format);
+
+        let file = match kind {
+            ExportKind::Balances => {
+                let storage = self.balance_storage.read().await;
+                export_balances(&storage.balances.values().cloned().collect::<Vec<_>>(), format)
+            }
+            ExportKind::History { from, to } => {
+                let Some(history) = &self.history else {
+                    return Err("❌ History is disabled, only /export balances is available.".to_string());
+                };
+                // Built from a copy on a blocking thread, so monitoring can keep writing history
+                let history = history.read().await.clone();
+                tokio::task::spawn_blocking(move || export_history(&history, from, to, format))
+                    .await
+                    .map_err(|e| format!("❌ Export failed: {}", e))?
+            }
+        };
+        let file = file.map_err(|e| format!("❌ Export failed: {}", e))?;
+        Ok((file_name, file))
+    }
+
+    /// Get latest balances
+    pub async fn get_balances(&self) -> Vec<BalanceInfo> {
+        self.latest_balances.read().await.clone()
+    }
+
+    /// Format change message for Telegram
+    pub fn format_change_message(&self, changes: &BalanceChangeSummary) -> String {
+        let address = self.address_html(&changes.network_name, &changes.address);
+
+        // Changed assets, then NFT holdings entering or leaving the wallet
+        let mut assets: Vec<String> = changes.changed_assets().map(|change| self.format_asset_change(change)).collect();
+        for nft in &changes.nft_changes {
+            let emoji = if nft.new_count > nft.old_count { "📥" } else { "📤" };
+            assets.push(format!("🖼️ <b>{}</b>\n{} {}\n", html::escape(&nft.label), emoji, nft.describe()));
+        }
+
+        // Transactions behind the change
+        let mut transactions = String::new();
+        if !changes.transactions.is_empty() {
+            transactions.push_str("🧾 <b>Transactions</b>\n");
+            for tx in &changes.transactions {
+                let counterparty = match tx.counterparty {
+                    Some(address) => self.address_html(&changes.network_name, &format!("{:?}", address)),
+                    None => "contract creation".to_string(),
+                };
+                let hash = Self::shorten_address(&format!("{:?}", tx.tx_hash));
+                let hash = match &tx.url {
+                    Some(url) => format!("<a href=\"{}\">{}</a>", url, hash),
+                    None => format!("<code>{}</code>", hash),
+                };
+                transactions.push_str(&format!("• {} {} {} · {}\n", html::escape(&tx.asset), tx.preposition(), counterparty, hash));
+            }
+        }
+
+        if let Some(template) = &self.templates.balance_change {
+            return render_template(template, &[
+                ("alias", html::escape(&changes.alias)),
+                ("network", html::escape(&changes.network_name)),
+                ("chain_id", changes.chain_id.to_string()),
+                ("address", address),
+                ("changes", assets.join("\n")),
+                ("transactions", transactions),
+            ]);
+        }
+
+        let mut message = "🔔 <b>Balance Alert</b>\n\n".to_string();
+        message.push_str(&format!("🌐 <b>{}</b> (Chain ID: {})\n", html::escape(&changes.network_name), changes.chain_id));
+        message.push_str(&format!("📍 <b>{}</b>\n", html::escape(&changes.alias)));
+        message.push_str(&format!("{}\n\n", address));
+        for asset in &assets {
+            message.push_str(asset);
+            message.push('\n');
+        }
+        message.push_str(&transactions);
+        message
+    }
+
+    /// Values of the asset_change template placeholders
+    fn asset_change_vars(change: &TokenBalanceChange) -> [(&'static str, String); 8] {
+        let (emoji, sign) = match change.change {
+            BalanceChange::Increase => ("📈", "+"),
+            _ => ("📉", ""),
+        };
+        let percent = Self::calculate_percent_change(&change.new_balance, &change.old_balance);
+        [
+            ("emoji", emoji.to_string()),
+            ("asset", html::escape(&change.alias)),
+            ("sign", sign.to_string()),
+            ("diff", Self::calculate_diff(&change.new_balance, &change.old_balance, change.decimals)),
+            ("percent", if percent.abs() >= 0.01 { format!("{:+.2}%", percent) } else { String::new() }),
+            ("old", change.old_formatted.clone()),
+            ("new", change.new_formatted.clone()),
+            ("usd", usd_suffix(change.usd_value)),
+        ]
+    }
+
+    /// One changed asset of a balance change alert
+    fn format_asset_change(&self, change: &TokenBalanceChange) -> String {
+        let vars = Self::asset_change_vars(change);
+        if let Some(template) = &self.templates.asset_change {
+            return render_template(template, &vars);
+        }
+        let [(_, emoji), (_, asset), (_, sign), (_, diff), (_, percent), (_, old), (_, new), (_, usd)] = vars;
+        let percent = if percent.is_empty() { percent } else { format!(" ({})", percent) };
+        format!("💰 <b>{}</b>\n{} <b>{}{}</b>{}\n{} → {}{}\n", asset, emoji, sign, diff, percent, old, new, usd)
+    }
+
+    /// Address shown in messages, full or shortened, linked to the network's block explorer
+    /// when it has an explorer_url
+    fn address_html(&self, network: &str, address: &str) -> String {
+        let display = if self.show_full_address { address.to_string() } else { Self::shorten_address(address) };
+        let url = self.explorers.get(network).and_then(|explorer| explorer.join(&format!("address/{}", address)).ok());
+        match url {
+            Some(url) => format!("<a href=\"{}\">{}</a>", url, display),
+            None => format!("<code>{}</code>", display),
+        }
+    }
+
+    /// Shorten address for display (0xabcd...1234)
+    fn shorten_address(address: &str) -> String {
+        if address.len() > 10 {
+            format!("{}...{}", &address[..6], &address[address.len()-4..])
+        } else {
+            address.to_string()
+        }
+    }
+
+    /// Calculate difference between two U256 values with `decimals` decimals as formatted string
+    fn calculate_diff(new: &U256, old: &U256, decimals: u8) -> String {
+        use alloy::primitives::utils::format_units;
+
+        if new > old {
+            let diff = *new - *old;
+            format_units(diff, decimals).unwrap_or_else(|_| diff.to_string())
+        } else {
+            let diff = *old - *new;
+            format_units(diff, decimals).unwrap_or_else(|_| diff.to_string())
+        }
+    }
+
+    /// Calculate percent change
+    fn calculate_percent_change(new: &U256, old: &U256) -> f64 {
+        if *old == U256::ZERO {
+            return 0.0;
+        }
+
+        let old_f64 = old.to_string().parse::<f64>().unwrap_or(0.0);
+        let new_f64 = new.to_string().parse::<f64>().unwrap_or(0.0);
+
+        if old_f64 == 0.0 {
+            return 0.0;
+        }
+
+        ((new_f64 - old_f64) / old_f64) * 100.0
+    }
+
+    /// Portfolio totals over all stored balances, compared against 24h ago
+    async fn build_portfolio(&self) -> Portfolio {
+        let previous_day = previous_day_snapshot(self.history.as_deref()).await;
+        let storage = self.balance_storage.read().await;
+        let balances: Vec<BalanceInfo> = storage.balances.values().cloned().collect();
+        let portfolio = Portfolio::build(&balances, previous_day.as_ref());
+        match &self.entities {
+            Some(entities) => portfolio.with_entities(entities),
+            None => portfolio,
+        }
+    }
+
+    /// Format portfolio totals section, empty if nothing is priced
+    fn format_portfolio(portfolio: &Portfolio) -> String {
+        if portfolio.is_empty() {
+            return String::new();
+        }
+
+        let mut message = String::from("💼 <b>Portfolio</b> (change vs 24h ago)\n");
+        for address in &portfolio.addresses {
+            message.push_str(&format!("📍 {} | {}: {}\n", html::escape(&address.network_name), html::escape(&address.alias), address.total.describe()));
+        }
+        for network in &portfolio.networks {
+            message.push_str(&format!("🌐 {}: <b>{}</b>\n", html::escape(&network.network_name), network.total.describe()));
+        }
+        for entity in &portfolio.entities {
+            message.push_str(&format!("👤 {} ({} addresses): <b>{}</b>\n", html::escape(&entity.name), entity.addresses, entity.total.describe()));
+        }
+        message.push_str(&format!("💰 <b>Total: {}</b>\n", portfolio.total.describe()));
+
+        message
+    }
+
+    /// Format balances of a single address
+    fn format_address_balance(&self, balance: &BalanceInfo) -> String {
+        let mut message = format!("🌐 <b>{}</b> (Chain ID: {})\n", html::escape(&balance.network_name), balance.chain_id);
+        message.push_str(&format!("📍 <b>{}</b>\n", html::escape(&balance.alias)));
+        message.push_str(&format!("{}\n\n", self.address_html(&balance.network_name, &format!("{:?}", balance.address))));
+
+        message.push_str(&format!("💵 {}: <b>{}</b>{}\n", html::escape(&balance.native_symbol), balance.eth_formatted, usd_suffix(balance.eth_usd_value)));
+
+        for token in &balance.token_balances {
+            message.push_str(&format!("💵 {}: <b>{}</b>{}\n", html::escape(&token.alias), token.formatted, usd_suffix(token.usd_value)));
+        }
+        message
+    }
+
+    /// Format tracked gas prices section, empty without gas tracking
+    fn format_gas(gas: &[GasPrice]) -> String {
+        if gas.is_empty() {
+            return String::new();
+        }
+
+        let mut message = String::from("\n⛽ <b>Gas</b>\n");
+        for price in gas {
+            message.push_str(&format!("{}: <b>{:.2}</b> gwei", html::escape(&price.network_name), price.base_fee_gwei()));
+            if let Some(priority) = price.priority_fee_gwei() {
+                message.push_str(&format!(" + {:.2} tip", priority));
+            }
+            if let Some(blob_fee) = price.blob_base_fee_wei {
+                message.push_str(&format!(", blob {} gwei", format_gwei(blob_fee)));
+                if let Some(range) = price.blob_base_fee_24h {
+                    message.push_str(&format!(" (24h {} – {})", format_gwei(range.min_wei), format_gwei(range.max_wei)));
                 }
+            }
+            message.push('\n');
+        }
+        message
+    }
 
-                for alert in &alerts {
-                    if let Err(e) = self
-                        .bot
-                        .send_message(chat_id, alert.clone())
-                        .parse_mode(teloxide::types::ParseMode::Html)
-                        .await
-                    {
-                        eprintln!("Failed to send low balance alert to chat {}: {}", chat_id, e);
-                    }
+    /// First /balance page: the network list, or the address list if there is only one network
+    async fn balance_start_view(&self) -> BalanceView {
+        let balances = self.get_balances().await;
+        if group_by_network(&balances).len() == 1 {
+            BalanceView::Addresses { network: 0, page: 0 }
+        } else {
+            BalanceView::Networks
+        }
+    }
+
+    /// Render a /balance page with its navigation keyboard
+    async fn balance_view(&self, view: BalanceView) -> (String, InlineKeyboardMarkup) {
+        let balances = self.get_balances().await;
+        if balances.is_empty() {
+            return ("No balance data available yet.".to_string(), InlineKeyboardMarkup::default());
+        }
+        let networks = group_by_network(&balances);
+
+        match view {
+            BalanceView::Addresses { network, page } if network < networks.len() => {
+                let (name, addresses) = &networks[network];
+                let pages = addresses.len().div_ceil(BALANCE_PAGE_SIZE);
+                let page = page.min(pages - 1);
+                let first = page * BALANCE_PAGE_SIZE;
+                let shown = &addresses[first..(first + BALANCE_PAGE_SIZE).min(addresses.len())];
+
+                let mut message = format!("🌐 <b>{}</b> (Chain ID: {})\n", html::escape(name), shown[0].chain_id);
+                if pages > 1 {
+                    message.push_str(&format!("Page {}/{}\n", page + 1, pages));
+                }
+                message.push('\n');
+                let mut rows = Vec::new();
+                for (offset, balance) in shown.iter().enumerate() {
+                    message.push_str(&format!(
+                        "📍 {}: <b>{}</b> {}{}\n",
+                        html::escape(&balance.alias),
+                        balance.eth_formatted,
+                        html::escape(&balance.native_symbol),
+                        usd_suffix(balance.eth_usd_value)
+                    ));
+                    let address = BalanceView::Address { network, page, address: first + offset };
+                    rows.push(vec![InlineKeyboardButton::callback(balance.alias.clone(), address.to_data())]);
                 }
+
+                let mut nav = Vec::new();
+                if page > 0 {
+                    let prev = BalanceView::Addresses { network, page: page - 1 };
+                    nav.push(InlineKeyboardButton::callback("◀️ Prev", prev.to_data()));
+                }
+                if page + 1 < pages {
+                    let next = BalanceView::Addresses { network, page: page + 1 };
+                    nav.push(InlineKeyboardButton::callback("Next ▶️", next.to_data()));
+                }
+                if !nav.is_empty() {
+                    rows.push(nav);
+                }
+                rows.push(vec![InlineKeyboardButton::callback("⬅️ Networks", BalanceView::Networks.to_data())]);
+                (message, InlineKeyboardMarkup::new(rows))
+            }
+            BalanceView::Address { network, page, address }
+                if networks.get(network).is_some_and(|(_, addresses)| address < addresses.len()) =>
+            {
+                let message = self.format_address_balance(networks[network].1[address]);
+                let back = BalanceView::Addresses { network, page };
+                let keyboard = InlineKeyboardMarkup::new(vec![vec![InlineKeyboardButton::callback("⬅️ Back", back.to_data())]]);
+                (message, keyboard)
             }
+            // Network list; also shown when a page no longer exists after addresses changed
+            _ => {
+                let mut message = String::from("💰 <b>Current Balances</b>\n\n");
+                let buttons: Vec<InlineKeyboardButton> = networks
+                    .iter()
+                    .enumerate()
+                    .map(|(network, (name, addresses))| {
+                        message.push_str(&format!("🌐 <b>{}</b>: {} address(es)\n", html::escape(name), addresses.len()));
+                        let view = BalanceView::Addresses { network, page: 0 };
+                        InlineKeyboardButton::callback(format!("{} ({})", name, addresses.len()), view.to_data())
+                    })
+                    .collect();
+                message.push('\n');
 
-            // Record that alert was sent
-            alert_state.record_alert_sent();
+                let portfolio = self.build_portfolio().await;
+                message.push_str(&Self::format_portfolio(&portfolio));
+                message.push_str(&Self::format_gas(&self.gas_prices().await));
 
-            // Save state
-            if let Err(e) = alert_storage.save_to_file(&self.alert_state_path) {
-                eprintln!("Failed to save alert state: {}", e);
+                let rows = buttons.chunks(2).map(|row| row.to_vec()).collect::<Vec<_>>();
+                (message, InlineKeyboardMarkup::new(rows))
             }
         }
+    }
 
-        Ok(())
+    /// Latest tracked gas prices, empty when gas tracking is off
+    async fn gas_prices(&self) -> Vec<GasPrice> {
+        match &self.gas {
+            Some(gas) => gas.all().await,
+            None => Vec::new(),
+        }
     }
 
-    /// Send daily report to all registered chats
-    async fn send_daily_report(&self) -> Result<()> {
-        let message = self.format_daily_report().await;
-        let chats = self.registered_chats.read().await;
-        let is_public = self.is_public_mode();
+    /// Build diff report from latest balances against stored baseline
+    async fn build_report(&self) -> BalanceReport {
+        let balances = self.get_balances().await;
+        self.build_report_of(&balances).await
+    }
+
+    async fn build_report_of(&self, balances: &[BalanceInfo]) -> BalanceReport {
+        let previous_day = previous_day_snapshot(self.history.as_deref()).await;
+        let baseline = report_baseline(self.backend.as_ref(), self.history.as_deref()).await;
+        let storage = self.balance_storage.read().await;
+        let mut report = BalanceReport::build(balances, baseline.as_ref().unwrap_or(&storage), previous_day.as_ref());
+        if let Some(entities) = &self.entities {
+            report = report.with_entities(entities);
+        }
+        match &self.runways {
+            Some(runways) => report.with_runways(balances, runways),
+            None => report,
+        }
+    }
+
+    /// Latest balances of an entity's addresses, or else of the addresses carrying a
+    /// tag, with the header to show them under; None if neither matches
+    async fn tagged_balances(&self, tag: &str) -> Option<(String, Vec<BalanceInfo>)> {
+        let (header, members) = match self.entities.as_ref().and_then(|entities| entities.members(tag)) {
+            Some(members) => (format!("👤 <b>{}</b>", html::escape(tag)), members.clone()),
+            None => (format!("🏷️ <b>{}</b>", html::escape(tag)), self.registry.as_ref()?.tagged(tag)),
+        };
+        if members.is_empty() {
+            return None;
+        }
+        let mut balances = self.get_balances().await;
+        balances.retain(|balance| members.contains(&(balance.network_name.clone(), balance.alias.clone())));
+        Some((header, balances))
+    }
 
-        for (&chat_id, registration) in chats.iter() {
-            if !is_public && !self.allowed_users.contains(&registration.username) {
-                continue;
+    /// Balances of a group's or entity's addresses with their total per network, for /balance <tag>
+    async fn group_balance(&self, tag: &str) -> String {
+        let Some((header, balances)) = self.tagged_balances(tag).await else {
+            return format!("❌ No entity or tagged address is called '{}'", html::escape(tag));
+        };
+        let mut message = format!("{}\n", header);
+        if balances.is_empty() {
+            message.push_str("\nNo balance data available yet.");
+            return message;
+        }
+        for (network, addresses) in group_by_network(&balances) {
+            message.push_str(&format!("\n🌐 <b>{}</b>\n", html::escape(&network)));
+            for balance in &addresses {
+                message.push_str(&format!(
+                    "📍 {}: <b>{}</b> {}{}\n",
+                    html::escape(&balance.alias),
+                    balance.eth_formatted,
+                    html::escape(&balance.native_symbol),
+                    usd_suffix(balance.eth_usd_value)
+                ));
             }
+            if let Some(total) = GroupTotal::of(addresses) {
+                message.push_str(&format!("Σ Total: <b>{}</b> {} ({} addresses)\n", total.formatted, html::escape(&total.symbol), total.addresses));
+            }
+        }
+        if let Some(total) = self.entities.as_ref().and_then(|entities| entities.total(tag, &balances)) {
+            message.push_str(&format!("\n💰 <b>Total: {}</b> on {} networks\n", format_usd(total.usd), total.networks.len()));
+        }
+        message
+    }
 
-            if let Err(e) = self
-                .bot
-                .send_message(chat_id, message.clone())
-                .parse_mode(teloxide::types::ParseMode::Html)
-                .await
-            {
-                eprintln!("Failed to send daily report to chat {}: {}", chat_id, e);
+    /// Diff report limited to a group's addresses, for /report <tag>
+    async fn group_report(&self, tag: &str) -> String {
+        let Some((header, balances)) = self.tagged_balances(tag).await else {
+            return format!("❌ No entity or tagged address is called '{}'", html::escape(tag));
+        };
+        let report = self.build_report_of(&balances).await;
+        format!("{}\n{}", header, self.format_report(&report))
+    }
+
+    /// Format balance changes of one check cycle, one compact block per address
+    fn format_digest_message(&self, digest: &ChangeDigest) -> String {
+        let mut message = format!("🔔 <b>Balance Alert</b> · {} addresses\n\n", digest.len());
+        message.push_str(&format!("🌐 <b>{}</b> (Chain ID: {})\n\n", html::escape(&digest.network_name), digest.chain_id));
+
+        for summary in digest.listed() {
+            let address = self.address_html(&summary.network_name, &summary.address);
+            message.push_str(&format!("📍 <b>{}</b> {}\n", html::escape(&summary.alias), address));
+
+            for change in summary.changed_assets() {
+                if let Some(template) = &self.templates.asset_change {
+                    message.push_str(&format!("   {}\n", render_template(template, &Self::asset_change_vars(change))));
+                    continue;
+                }
+                let (emoji, sign) = match change.change {
+                    BalanceChange::Increase => ("📈", "+"),
+                    _ => ("📉", ""),
+                };
+                let diff = Self::calculate_diff(&change.new_balance, &change.old_balance, change.decimals);
+                message.push_str(&format!("   {} {}: {}{} | {} → {}{}\n",
+                    emoji, html::escape(&change.alias), sign, diff, change.old_formatted, change.new_formatted,
+                    usd_suffix(change.usd_value)));
+            }
+            for nft in &summary.nft_changes {
+                message.push_str(&format!("   🖼️ {}: {}\n", html::escape(&nft.label), nft.describe()));
             }
+            message.push('\n');
         }
 
-        Ok(())
+        for line in digest.summary_lines() {
+            message.push_str(&format!("{}\n", line));
+        }
+        message
     }
 
-    /// Start daily report scheduler
-    pub fn spawn_daily_report_scheduler(self) {
-        if let Some(ref report_config) = self.daily_report_config {
-            if !report_config.enabled {
-                return;
-            }
-
-            let report_time = report_config.time.clone();
-            tokio::spawn(async move {
-                loop {
-                    // Parse target time (HH:MM)
-                    let target_time = if let Ok(time) = NaiveTime::parse_from_str(&report_time, "%H:%M") {
-                        time
-                    } else {
-                        eprintln!("Invalid daily report time format: {}. Expected HH:MM", report_time);
-                        return;
-                    };
-
-                    // Calculate sleep duration until next report time
-                    let now = Local::now();
-                    let target_datetime = now.date_naive().and_time(target_time);
-
-                    let duration = if now.time() < target_time {
-                        // Target is today
-                        (target_datetime - now.naive_local()).to_std().unwrap()
-                    } else {
-                        // Target is tomorrow
-                        let tomorrow = now.date_naive().succ_opt().unwrap().and_time(target_time);
-                        (tomorrow - now.naive_local()).to_std().unwrap()
-                    };
-
-                    println!("Next daily report scheduled in {} hours", duration.as_secs() / 3600);
-                    tokio::time::sleep(duration).await;
-
-                    // Send report
-                    if let Err(e) = self.send_daily_report().await {
-                        eprintln!("Failed to send daily report: {}", e);
-                    }
+    /// Format diff report for all addresses and networks
+    fn format_report(&self, report: &BalanceReport) -> String {
+        if !report.has_data() {
+            return "📊 <b>Daily Balance Report</b>\n\nNo balance data available yet.".to_string();
+        }
 
-                    // Sleep for a minute to avoid sending multiple reports
-                    tokio::time::sleep(std::time::Duration::from_secs(60)).await;
+        let mut changes = String::new();
+        for summary in &report.changes {
+            changes.push_str(&format!("🌐 <b>{}</b> | 📍 <b>{}</b>\n", html::escape(&summary.network_name), html::escape(&summary.alias)));
+            changes.push_str(&format!("{}\n", self.address_html(&summary.network_name, &summary.address)));
+
+            for change in summary.changed_assets() {
+                if let Some(template) = &self.templates.asset_change {
+                    changes.push_str(&format!("   {}\n", render_template(template, &Self::asset_change_vars(change))));
+                    continue;
                 }
-            });
+                let (emoji, sign) = match change.change {
+                    BalanceChange::Increase => ("📈", "+"),
+                    _ => ("📉", ""),
+                };
+                let diff = Self::calculate_diff(&change.new_balance, &change.old_balance, change.decimals);
+                let percent = Self::calculate_percent_change(&change.new_balance, &change.old_balance);
+
+                if percent.abs() >= 0.01 {
+                    changes.push_str(&format!("   {} {}: {}{} ({:+.2}%) | {} → {}{}\n",
+                        emoji, html::escape(&change.alias), sign, diff, percent, change.old_formatted, change.new_formatted,
+                        usd_suffix(change.usd_value)));
+                } else {
+                    changes.push_str(&format!("   {} {}: {}{} | {} → {}{}\n",
+                        emoji, html::escape(&change.alias), sign, diff, change.old_formatted, change.new_formatted,
+                        usd_suffix(change.usd_value)));
+                }
+            }
+            for nft in &summary.nft_changes {
+                changes.push_str(&format!("   🖼️ {}: {}\n", html::escape(&nft.label), nft.describe()));
+            }
+            changes.push('\n');
+        }
+        let portfolio = if report.portfolio.is_empty() { String::new() } else { Self::format_portfolio(&report.portfolio) };
+        let mut failovers = String::new();
+        if !report.failovers.is_empty() {
+            failovers.push_str("🔌 <b>RPC failovers</b>\n");
+        }
+        for node in &report.failovers {
+            failovers.push_str(&format!("{}: {}\n", html::escape(&node.network_name), html::escape(&node.describe())));
         }
+        let mut runways = String::new();
+        if !report.runways.is_empty() {
+            runways.push_str("⏳ <b>Runway</b>\n");
+        }
+        for runway in &report.runways {
+            runways.push_str(&format!("{}: {}\n", html::escape(&runway.network_name), html::escape(&runway.describe())));
+        }
+
+        if let Some(template) = &self.templates.report {
+            return render_template(template, &[
+                ("date", report.generated_at.format("%Y-%m-%d %H:%M:%S").to_string()),
+                ("changes", changes),
+                ("total_changes", report.total_changes().to_string()),
+                ("portfolio", portfolio),
+                ("failovers", failovers),
+                ("runways", runways),
+            ]);
+        }
+
+        let mut message = String::from("📊 <b>Daily Balance Report</b>\n");
+        message.push_str(&format!("📅 {}\n\n", report.generated_at.format("%Y-%m-%d %H:%M:%S")));
+        message.push_str(&changes);
+
+        if report.changes.is_empty() {
+            message.push_str("✅ No balance changes detected in the last period.\n");
+        } else {
+            message.push_str(&format!("📈 <b>Total changes:</b> {}\n", report.total_changes()));
+        }
+
+        if !portfolio.is_empty() {
+            message.push('\n');
+            message.push_str(&portfolio);
+        }
+        if !failovers.is_empty() {
+            message.push('\n');
+            message.push_str(&failovers);
+        }
+        if !runways.is_empty() {
+            message.push('\n');
+            message.push_str(&runways);
+        }
+
+        message
+    }
+
+    /// Format low balance alert message
+    pub fn format_low_balance_message(&self, alert: &LowBalanceAlert) -> String {
+        let address = self.address_html(&alert.network_name, &format!("{:?}", alert.address));
+        let unit = if alert.is_native { format!(" {}", html::escape(&alert.asset)) } else { String::new() };
+
+        if let Some(template) = &self.templates.low_balance {
+            return render_template(template, &[
+                ("emoji", alert.severity.emoji().to_string()),
+                ("alert_number", alert.alert_number.to_string()),
+                ("alias", html::escape(&alert.alias)),
+                ("network", html::escape(&alert.network_name)),
+                ("chain_id", alert.chain_id.to_string()),
+                ("address", address),
+                ("asset", html::escape(&alert.asset)),
+                ("balance", html::escape(&alert.formatted)),
+                ("usd", usd_suffix(alert.usd_value)),
+                ("threshold", format!("{}{}", alert.threshold, unit)),
+                ("call_to_action", html::escape(alert.call_to_action())),
+                ("next_alert", html::escape(&alert.next_alert)),
+                ("runway", html::escape(&alert.runway_text())),
+                ("top_up", top_up_html(alert)),
+            ]);
+        }
+        let runway = match alert.runway_text() {
+            text if text.is_empty() => text,
+            text => format!("⏳ {}\n", text),
+        };
+
+        format!("{} <b>LOW BALANCE ALERT #{}</b>\n\n\
+                 🌐 <b>{}</b> (Chain ID: {})\n\
+                 📍 <b>{}</b>\n\
+                 {}\n\n\
+                 💰 {}: <b>{}</b>{}\n\
+                 📉 Below threshold: <b>{}</b>{}\n\
+                 {}{}\
+                 🚨 <b>{}</b>\n\n\
+                 ⏰ {}",
+            alert.severity.emoji(),
+            alert.alert_number,
+            html::escape(&alert.network_name),
+            alert.chain_id,
+            html::escape(&alert.alias),
+            address,
+            html::escape(&alert.asset),
+            alert.formatted,
+            usd_suffix(alert.usd_value),
+            alert.threshold,
+            unit,
+            html::escape(&runway),
+            top_up_html(alert),
+            html::escape(alert.call_to_action()),
+            alert.next_alert
+        )
     }
 
     /// Start bot command handler in background
     pub fn spawn_command_handler(self) {
         tokio::spawn(async move {
-            let handler = Update::filter_message()
-                .filter_command::<Command>()
-                .endpoint(handle_command);
+            let handler = dptree::entry()
+                .branch(Update::filter_message().filter_command::<Command>().endpoint(handle_command))
+                .branch(Update::filter_callback_query().endpoint(handle_callback));
 
             let mut dispatcher = Dispatcher::builder(self.bot.clone(), handler)
                 .dependencies(dptree::deps![self.clone()])
@@ -778,19 +1542,168 @@ impl TelegramNotifier {
     }
 }
 
+#[async_trait]
+impl Notifier for TelegramNotifier {
+    fn name(&self) -> &str {
+        &self.channel_name
+    }
+
+    fn sends(&self, subject: &AuditSubject) -> bool {
+        self.alerts.allows(&subject.kind)
+    }
+
+    async fn send_change_alert(&self, changes: &BalanceChangeSummary) -> Result<()> {
+        if !self.alerts.balance_change || !changes.has_changes() {
+            return Ok(());
+        }
+
+        let message = self.format_change_message(changes);
+        let now = Utc::now().timestamp() as u64;
+        self.broadcast_where(&message, &AuditSubject::change(changes), |registration| {
+            registration.wants(&changes.network_name, &changes.alias) && !registration.is_muted(now)
+        })
+        .await;
+        Ok(())
+    }
+
+    /// Each chat gets one message with the changes of the addresses it's subscribed to
+    async fn send_change_digest(&self, digest: &ChangeDigest) -> Result<()> {
+        if !self.alerts.balance_change || digest.is_empty() {
+            return Ok(());
+        }
+
+        let now = Utc::now().timestamp() as u64;
+        let wanted = |registration: &ChatRegistration| -> Vec<String> {
+            if registration.is_muted(now) {
+                return Vec::new();
+            }
+            digest.changes.iter()
+                .filter(|changes| registration.wants(&changes.network_name, &changes.alias))
+                .map(|changes| changes.alias.clone())
+                .collect()
+        };
+        let selections: HashSet<Vec<String>> = self.registered_chats.read().await.values()
+            .map(wanted)
+            .filter(|aliases| !aliases.is_empty())
+            .collect();
+
+        for aliases in selections {
+            let chat_digest = digest.filtered(|changes| aliases.contains(&changes.alias));
+            let (message, subject) = match chat_digest.changes.as_slice() {
+                [changes] => (self.format_change_message(changes), AuditSubject::change(changes)),
+                _ => (self.format_digest_message(&chat_digest), AuditSubject::digest(&chat_digest)),
+            };
+            self.broadcast_where(&message, &subject, |registration| wanted(registration) == aliases).await;
+        }
+        Ok(())
+    }
+
+    async fn send_low_balance_alert(&self, alert: &LowBalanceAlert) -> Result<()> {
+        if !self.alerts.low_balance {
+            return Ok(());
+        }
+
+        let message = self.format_low_balance_message(alert);
+        let now = Utc::now().timestamp() as u64;
+        self.broadcast_where(&message, &AuditSubject::low_balance(alert), |registration| {
+            registration.wants(&alert.network_name, &alert.alias)
+                && !(self.mute_low_balance && registration.is_muted(now))
+        })
+        .await;
+        Ok(())
+    }
+
+    async fn send_alert(&self, alert: &MonitorAlert) -> Result<()> {
+        let message = self.format_alert_message(alert);
+
+        if alert.kind == HEARTBEAT_KIND || alert.kind == TEST_ALERT_KIND || alert.kind == DRAINED_KIND {
+            self.broadcast(&message, &AuditSubject::alert(alert)).await;
+            return Ok(());
+        }
+
+        let now = Utc::now().timestamp() as u64;
+        self.broadcast_where(&message, &AuditSubject::alert(alert), |registration| {
+            registration.wants_network(&alert.network_name) && !registration.is_muted(now)
+        })
+        .await;
+        Ok(())
+    }
+
+    async fn send_critical_alert(&self, alert: &MonitorAlert, ack_id: u64) -> Result<()> {
+        let message = self.format_alert_message(alert);
+        let now = Utc::now().timestamp() as u64;
+        self.broadcast_markup_where(&message, Some(Self::ack_keyboard(ack_id)), &AuditSubject::alert(alert), |registration| {
+            self.wants_critical_alert(registration, alert, now)
+        })
+        .await;
+        Ok(())
+    }
+
+    async fn send_report(&self, report: &BalanceReport) -> Result<()> {
+        let message = self.format_report(report);
+        self.broadcast(&message, &AuditSubject::report(report)).await;
+        Ok(())
+    }
+
+    async fn update_balances(&self, balances: &[BalanceInfo]) {
+        let mut stored = self.latest_balances.write().await;
+        *stored = balances.to_vec();
+    }
+
+    fn audits_deliveries(&self) -> bool {
+        true
+    }
+}
+
 #[derive(BotCommands, Clone)]
 #[command(rename_rule = "lowercase", description = "Available commands:")]
 enum Command {
     #[command(description = "Start bot and register for alerts")]
     Start,
-    #[command(description = "Show current balances")]
-    Balance,
-    #[command(description = "Generate and send balance diff report")]
-    Report,
+    #[command(description = "Show current balances, or those of a group or entity: /balance [tag|entity]")]
+    Balance(String),
+    #[command(description = "Generate and send balance diff report, optionally for a group or entity: /report [tag|entity]")]
+    Report(String),
+    #[command(description = "Add address to monitor (admin): /add <network> <address> <alias>")]
+    Add(String),
+    #[command(description = "Stop monitoring an address added with /add (admin): /remove <alias>")]
+    Remove(String),
+    #[command(description = "Only receive alerts for a network and/or alias (admin): /subscribe <network|*> [/ <alias>]")]
+    Subscribe(String),
+    #[command(description = "Remove a subscription, or all of them without arguments (admin)")]
+    Unsubscribe(String),
+    #[command(description = "Mute balance change alerts in this chat (admin): /mute <duration> (e.g. 30m, 2h, 1d)")]
+    Mute(String),
+    #[command(description = "Resume alerts muted with /mute (admin)")]
+    Unmute,
+    #[command(description = "Suppress change alerts during maintenance (admin): /maintenance <duration> [network] | off")]
+    Maintenance(String),
+    #[command(description = "Show request counts, latency and errors of each RPC node")]
+    Rpcstatus,
+    #[command(description = "Export balances as CSV or XLSX: /export [balances | history <from> <to>] [xlsx]")]
+    Export(String),
+    #[command(description = "Show uptime, last checks, RPC health, storage and alert throttles")]
+    Status,
+    #[command(description = "Show recent alert deliveries (admin): /history [count]")]
+    History(String),
+    #[command(description = "Send a test alert, balance change and report to every registered chat (admin)")]
+    Testalert,
     #[command(description = "Show help")]
     Help,
 }
 
+impl Command {
+    /// Role needed to run the command; listing maintenance windows or subscriptions is a query
+    fn required_role(&self) -> Role {
+        match self {
+            Command::Add(_) | Command::Remove(_) | Command::History(_) | Command::Mute(_) | Command::Unmute | Command::Unsubscribe(_)
+            | Command::Testalert => Role::Admin,
+            Command::Maintenance(args) | Command::Subscribe(args) if !args.trim().is_empty() => Role::Admin,
+            _ => Role::Viewer,
+        }
+    }
+}
+
 async fn handle_command(
     bot: Bot,
     msg: Message,
@@ -805,12 +1718,11 @@ async fn handle_command(
 
     // Centralized authorization check for all commands except Help
     if !matches!(cmd, Command::Help) {
-        if !notifier.is_user_allowed(user.username.as_deref()) {
-            let message = if user.username.is_none() {
-                "❌ Sorry, you need to set a Telegram username to use this bot."
-            } else {
-                "❌ Sorry, you are not authorized to use this bot."
-            };
+        if !notifier.is_user_allowed(msg.chat.id, user) {
+            let message = format!(
+                "❌ Sorry, you are not authorized to use this bot.\n\nYour user ID: {}\nThis chat's ID: {}",
+                user.id, msg.chat.id
+            );
             bot.send_message(msg.chat.id, message).await?;
 
             // Unregister chat if it was previously registered
@@ -820,6 +1732,15 @@ async fn handle_command(
         }
     }
 
+    if notifier.role(user) < cmd.required_role() {
+        let message = format!(
+            "❌ This command is only available to admins. Viewers can use /balance, /report, /status and other queries.\n\nYour user ID: {}",
+            user.id
+        );
+        bot.send_message(msg.chat.id, message).await?;
+        return Ok(());
+    }
+
     match cmd {
         Command::Start => {
             notifier.register_chat(msg.chat.id, user).await;
@@ -832,7 +1753,7 @@ async fn handle_command(
                 .parse_mode(teloxide::types::ParseMode::Html)
                 .await?;
         }
-        Command::Balance => {
+        Command::Balance(ref tag) => {
             if !notifier.is_registered(msg.chat.id).await {
                 bot.send_message(
                     msg.chat.id,
@@ -842,13 +1763,82 @@ async fn handle_command(
                 return Ok(());
             }
 
-            let balances = notifier.get_balances().await;
-            let message = notifier.format_balance_message(&balances);
+            if !tag.trim().is_empty() {
+                bot.send_message(msg.chat.id, notifier.group_balance(tag.trim()).await)
+                    .parse_mode(teloxide::types::ParseMode::Html)
+                    .await?;
+                return Ok(());
+            }
+            let (message, keyboard) = notifier.balance_view(notifier.balance_start_view().await).await;
             bot.send_message(msg.chat.id, message)
                 .parse_mode(teloxide::types::ParseMode::Html)
+                .reply_markup(keyboard)
                 .await?;
         }
-        Command::Report => {
+        Command::Report(ref tag) => {
+            if !notifier.is_registered(msg.chat.id).await {
+                bot.send_message(
+                    msg.chat.id,
+                    "Please start the bot first with /start to receive updates.",
+                )
+                .await?;
+                return Ok(());
+            }
+
+            let message = match tag.trim() {
+                "" => notifier.format_report(&notifier.build_report().await),
+                tag => notifier.group_report(tag).await,
+            };
+            bot.send_message(msg.chat.id, message)
+                .parse_mode(teloxide::types::ParseMode::Html)
+                .await?;
+        }
+        Command::Add(ref args) | Command::Remove(ref args) | Command::History(ref args) | Command::Maintenance(ref args) => {
+            let message = match cmd {
+                Command::Add(_) => notifier.add_address(args).await,
+                Command::Remove(_) => notifier.remove_address(args.trim()).await,
+                Command::Maintenance(_) => notifier.maintenance(args, user.username.as_deref()).await,
+                _ => notifier.delivery_history(args.trim()).await,
+            };
+            bot.send_message(msg.chat.id, message)
+                .parse_mode(teloxide::types::ParseMode::Html)
+                .await?;
+        }
+        Command::Subscribe(ref args) | Command::Unsubscribe(ref args) => {
+            let message = match cmd {
+                Command::Subscribe(_) => notifier.subscribe(msg.chat.id, args).await,
+                _ => notifier.unsubscribe(msg.chat.id, args).await,
+            };
+            bot.send_message(msg.chat.id, message)
+                .parse_mode(teloxide::types::ParseMode::Html)
+                .await?;
+        }
+        Command::Mute(ref args) => {
+            let message = notifier.mute(msg.chat.id, args).await;
+            bot.send_message(msg.chat.id, message)
+                .parse_mode(teloxide::types::ParseMode::Html)
+                .await?;
+        }
+        Command::Export(ref args) => {
+            if !notifier.is_registered(msg.chat.id).await {
+                bot.send_message(
+                    msg.chat.id,
+                    "Please start the bot first with /start to receive updates.",
+                )
+                .await?;
+                return Ok(());
+            }
+
+            match notifier.export_file(args).await {
+                Ok((file_name, file)) => {
+                    bot.send_document(msg.chat.id, InputFile::memory(file).file_name(file_name)).await?;
+                }
+                Err(message) => {
+                    bot.send_message(msg.chat.id, message).await?;
+                }
+            }
+        }
+        Command::Rpcstatus | Command::Status => {
             if !notifier.is_registered(msg.chat.id).await {
                 bot.send_message(
                     msg.chat.id,
@@ -858,19 +1848,45 @@ async fn handle_command(
                 return Ok(());
             }
 
-            let report = notifier.format_daily_report().await;
-            bot.send_message(msg.chat.id, report)
+            let message = match cmd {
+                Command::Rpcstatus => notifier.format_rpc_status(),
+                _ => notifier.format_status().await,
+            };
+            bot.send_message(msg.chat.id, message)
                 .parse_mode(teloxide::types::ParseMode::Html)
                 .await?;
         }
+        Command::Unmute => {
+            let message = notifier.unmute(msg.chat.id).await;
+            bot.send_message(msg.chat.id, message).await?;
+        }
+        Command::Testalert => {
+            let message = notifier.send_test_messages(user.username.as_deref()).await;
+            bot.send_message(msg.chat.id, message).await?;
+        }
         Command::Help => {
             let help_text = "🤖 <b>Balance Monitor Bot</b>\n\n\
                              Available commands:\n\
                              /start - Register for balance alerts\n\
-                             /balance - Show current balances\n\
-                             /report - Get balance diff report (cumulative across all addresses and networks)\n\
+                             /balance [tag|entity] - Show current balances, or those of a group or entity and their total\n\
+                             /report [tag|entity] - Get balance diff report (cumulative across all addresses and networks, or one group or entity)\n\
+                             /add &lt;network&gt; &lt;address&gt; &lt;alias&gt; - Start monitoring an address (admins only)\n\
+                             /remove &lt;alias&gt; - Stop monitoring an address added with /add (admins only)\n\
+                             /subscribe &lt;network|*&gt; [/ &lt;alias&gt;] - Only receive alerts for matching addresses (admins only)\n\
+                             /subscribe - List this chat's subscriptions\n\
+                             /unsubscribe [&lt;network|*&gt; [/ &lt;alias&gt;]] - Remove one subscription, or all without arguments (admins only)\n\
+                             /mute &lt;duration&gt; - Mute balance change alerts in this chat, e.g. /mute 2h (admins only)\n\
+                             /unmute - Resume alerts (admins only)\n\
+                             /maintenance &lt;duration&gt; [network] - Suppress balance change alerts during planned operations, /maintenance off to end (admins only)\n\
+                             /maintenance - List open maintenance windows\n\
+                             /rpcstatus - Show request counts, latency and errors of each RPC node\n\
+                             /export [balances | history &lt;from&gt; &lt;to&gt;] [xlsx] - Get balances, or end-of-day balances per day (YYYY-MM-DD), as CSV or XLSX\n\
+                             /status - Show uptime, last check per network, RPC health, storage sizes and alert throttles\n\
+                             /history [count] - Show recent alert deliveries (admins only)\n\
+                             /testalert - Send a test alert, balance change and report to every registered chat (admins only)\n\
                              /help - Show this message\n\n\
                              The bot will automatically send alerts when balance changes are detected.\n\
+                             Without subscriptions a chat receives alerts for every address.\n\
                              If enabled in config, daily reports will be sent automatically.";
             bot.send_message(msg.chat.id, help_text)
                 .parse_mode(teloxide::types::ParseMode::Html)
@@ -880,3 +1896,106 @@ async fn handle_command(
 
     Ok(())
 }
+
+/// Navigate /balance pages and acknowledge critical alerts from inline keyboard buttons
+async fn handle_callback(bot: Bot, query: CallbackQuery, notifier: TelegramNotifier) -> Result<(), teloxide::RequestError> {
+    let ack_id = query.data.as_deref().and_then(|data| data.strip_prefix(ACK_CALLBACK_PREFIX)?.parse::<u64>().ok());
+    if let Some(id) = ack_id {
+        let username = query.from.username.clone().unwrap_or_else(|| query.from.id.to_string());
+        let allowed = query.message.as_ref().is_some_and(|message| notifier.is_user_allowed(message.chat().id, &query.from));
+        if !allowed {
+            bot.answer_callback_query(query.id.clone()).text("You are not allowed to acknowledge alerts.").await?;
+            return Ok(());
+        }
+        let reply = notifier.acknowledge(id, &username).await;
+        bot.answer_callback_query(query.id.clone()).text(reply.clone()).await?;
+        if let Some(message) = query.message.as_ref() {
+            bot.edit_message_reply_markup(message.chat().id, message.id())
+                .reply_markup(InlineKeyboardMarkup::default())
+                .await?;
+            bot.send_message(message.chat().id, reply).await?;
+        }
+        return Ok(());
+    }
+
+    bot.answer_callback_query(query.id.clone()).await?;
+
+    let (Some(view), Some(message)) = (query.data.as_deref().and_then(BalanceView::parse), query.message.as_ref()) else {
+        return Ok(());
+    };
+    let chat_id = message.chat().id;
+    if !notifier.is_user_allowed(chat_id, &query.from) || !notifier.is_registered(chat_id).await {
+        return Ok(());
+    }
+
+    let (text, keyboard) = notifier.balance_view(view).await;
+    bot.edit_message_text(chat_id, message.id(), text)
+        .parse_mode(teloxide::types::ParseMode::Html)
+        .reply_markup(keyboard)
+        .await?;
+    Ok(())
+}
+
+/// Suggested top-up of a low balance alert: the payment link, calldata for tokens and
+/// the funding Safe's transaction builder, one per line; empty without a suggestion
+fn top_up_html(alert: &LowBalanceAlert) -> String {
+    let Some(top_up) = &alert.top_up else { return String::new() };
+    let mut lines = format!("💸 <b>{}</b>\n<code>{}</code>\n", html::escape(&top_up.describe()), html::escape(&top_up.payment_link));
+    if !top_up.data.is_empty() {
+        lines.push_str(&format!("Calldata: <code>{}</code>\n", top_up.data));
+    }
+    if let Some(url) = &top_up.safe_url {
+        lines.push_str(&format!("<a href=\"{}\">Open the Safe transaction builder</a>\n", html::escape(url.as_str())));
+    }
+    lines
+}
+
+/// Format seconds as "45s", "12m" or "3h 5m"
+fn format_duration(secs: u64) -> String {
+    match secs {
+        0..60 => format!("{}s", secs),
+        60..3600 => format!("{}m", secs / 60),
+        _ => format!("{}h {}m", secs / 3600, secs % 3600 / 60),
+    }
+}
+
+/// Format a byte count as "512 B", "12.3 KB" or "4.5 MB"
+fn format_bytes(bytes: u64) -> String {
+    match bytes {
+        0..1024 => format!("{} B", bytes),
+        1024..1_048_576 => format!("{:.1} KB", bytes as f64 / 1024.0),
+        _ => format!("{:.1} MB", bytes as f64 / 1_048_576.0),
+    }
+}
+
+/// Parse "<network|*> [/ <alias>]" into a subscription; "*" matches any network
+fn parse_subscription(args: &str) -> Option<ChatSubscription> {
+    let (network, alias) = match args.split_once('/') {
+        Some((network, alias)) => (network.trim(), alias.trim()),
+        None => (args.trim(), ""),
+    };
+    let part = |value: &str| (!value.is_empty() && value != "*").then(|| value.to_string());
+
+    let subscription = ChatSubscription { network: part(network), alias: part(alias) };
+    // A filter matching everything is the same as having no subscriptions
+    (subscription.network.is_some() || subscription.alias.is_some()).then_some(subscription)
+}
+
+/// Parse a mute duration like "90s", "30m", "2h" or "1d" into seconds
+fn parse_mute_duration(value: &str) -> Option<u64> {
+    let unit_at = value.find(|c: char| !c.is_ascii_digit())?;
+    let (amount, unit) = value.split_at(unit_at);
+    let seconds = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        _ => return None,
+    };
+    amount.parse::<u64>().ok().filter(|&amount| amount > 0)?.checked_mul(seconds)
+}
+
+/// Unix time `duration` seconds after `now`, None if that's past any valid timestamp
+fn expiry(now: u64, duration: u64) -> Option<u64> {
+    now.checked_add(duration).filter(|&until| i64::try_from(until).is_ok_and(|until| DateTime::from_timestamp(until, 0).is_some()))
+}
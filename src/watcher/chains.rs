@@ -0,0 +1,264 @@
+use super::{publish_check, send_drained_alert, send_low_balance_alert, SharedState};
+use crate::{
+    compare_balances, drained_assets, log_balance_changes, log_balance_check, ChangeDigest, CosmosEvent,
+    CosmosMonitor, EventBus, HealthTracker, MonitorAlert, MonitorEvent, Notifier, NotifierSet, TronMonitor,
+    ValidatorEvent, ValidatorMonitor,
+};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::{error, info, warn};
+
+/// Check beacon-chain validators every interval and alert on their events
+pub(super) async fn monitor_validators(
+    monitor: ValidatorMonitor,
+    notifiers: Arc<NotifierSet>,
+    health: Arc<HealthTracker>,
+    events: EventBus,
+) {
+    let config = monitor.config();
+    info!("🛰️  Starting validator monitor: {} validator(s) on {}", config.validators.len(), config.name);
+
+    loop {
+        events.publish(MonitorEvent::CheckStarted { network: config.name.clone(), chain_id: Some(config.chain_id) });
+        match monitor.check().await {
+            Ok(results) => {
+                publish_check(&health, &events, MonitorEvent::CheckCompleted {
+                    network: config.name.clone(),
+                    chain_id: Some(config.chain_id),
+                    addresses: config.validators.len(),
+                    errors: 0,
+                    error: None,
+                })
+                .await;
+                for (validator, info, events) in results {
+                    let title = if events.contains(&ValidatorEvent::Slashed) {
+                        format!("🚨 Validator slashed: {}", validator.alias)
+                    } else {
+                        format!("🛰️ Validator alert: {}", validator.alias)
+                    };
+                    info!("{} (index {})", title, info.index);
+
+                    let mut lines = vec![
+                        format!("Index: {}", info.index),
+                        format!("Status: {}", info.status),
+                        format!("Balance: {:.6} ETH (effective {} ETH)", info.balance_eth(), info.effective_balance_eth()),
+                    ];
+                    lines.extend(events.iter().map(|event| event.describe()));
+
+                    let alert = MonitorAlert {
+                        kind: "validator".to_string(),
+                        network_name: config.name.clone(),
+                        chain_id: Some(config.chain_id),
+                        title,
+                        lines,
+                        data: serde_json::json!({
+                            "alias": validator.alias,
+                            "index": info.index,
+                            "pubkey": info.validator.pubkey,
+                            "status": info.status,
+                            "balance_gwei": info.balance,
+                            "effective_balance_gwei": info.validator.effective_balance,
+                            "slashed": info.validator.slashed,
+                            "events": events.iter().map(|event| event.kind()).collect::<Vec<_>>(),
+                        }),
+                        dedup_id: Some(events.iter().map(|event| event.kind()).collect::<Vec<_>>().join(",")),
+                    };
+                    if let Err(e) = notifiers.send_alert(&alert).await {
+                        warn!("Failed to send validator alert: {}", e);
+                    }
+                }
+            }
+            Err(e) => {
+                error!("Error checking validators: {}", e);
+                publish_check(&health, &events, MonitorEvent::CheckCompleted {
+                    network: config.name.clone(),
+                    chain_id: Some(config.chain_id),
+                    addresses: 0,
+                    errors: config.validators.len(),
+                    error: Some(e.to_string()),
+                })
+                .await;
+            }
+        }
+
+        tokio::time::sleep(config.interval).await;
+    }
+}
+
+/// Poll a Cosmos chain's LCD endpoint every interval and alert on balance events
+pub(super) async fn monitor_cosmos_chain(
+    monitor: CosmosMonitor,
+    notifiers: Arc<NotifierSet>,
+    health: Arc<HealthTracker>,
+    events: EventBus,
+    interval: std::time::Duration,
+) {
+    let config = monitor.config();
+    info!("🌌 Starting Cosmos monitor: {} address(es) on {}", config.addresses.len(), config.name);
+
+    loop {
+        events.publish(MonitorEvent::CheckStarted { network: config.name.clone(), chain_id: None });
+        let mut checked = 0;
+        let mut failed = 0;
+        let mut last_error = None;
+        for (address, result) in monitor.check().await {
+            let cosmos_events = match result {
+                Ok(cosmos_events) => cosmos_events,
+                Err(e) => {
+                    error!("Error checking balance on {}: {}: {}", config.name, address.alias, e);
+                    last_error = Some(e.to_string());
+                    failed += 1;
+                    continue;
+                }
+            };
+            checked += 1;
+            if cosmos_events.is_empty() {
+                continue;
+            }
+            let title = if cosmos_events.iter().any(|event| matches!(event, CosmosEvent::LowBalance { .. })) {
+                format!("⚠️ Low Balance Alert: {}", address.alias)
+            } else {
+                format!("🔔 Balance Alert: {}", address.alias)
+            };
+            info!("{} on {}", title, config.name);
+
+            let mut lines = vec![format!("Address: {}", address.address), format!("Chain: {}", config.chain_id)];
+            lines.extend(cosmos_events.iter().map(|event| event.describe()));
+
+            let alert = MonitorAlert {
+                kind: "cosmos_balance".to_string(),
+                network_name: config.name.clone(),
+                chain_id: None,
+                title,
+                lines,
+                data: serde_json::json!({
+                    "alias": address.alias,
+                    "address": address.address,
+                    "cosmos_chain_id": config.chain_id,
+                    "events": cosmos_events.iter().map(|event| event.describe()).collect::<Vec<_>>(),
+                }),
+                dedup_id: Some(cosmos_events.iter().map(|event| event.dedup_id()).collect::<Vec<_>>().join(",")),
+            };
+            if let Err(e) = notifiers.send_alert(&alert).await {
+                warn!("Failed to send Cosmos alert: {}", e);
+            }
+        }
+
+        publish_check(&health, &events, MonitorEvent::CheckCompleted {
+            network: config.name.clone(),
+            chain_id: None,
+            addresses: checked,
+            errors: failed,
+            error: last_error,
+        })
+        .await;
+
+        tokio::time::sleep(interval).await;
+    }
+}
+
+/// Poll TronGrid and feed the balances through the same storage and alerts as EVM networks
+pub(super) async fn monitor_tron_network(monitor: TronMonitor, shared: SharedState) {
+    let SharedState {
+        config, storage, history, notifiers, low_balance_tracker, maintenance, escalator, backend, health, events, ..
+    } = shared;
+    let tron = monitor.config();
+    let interval = tron.interval.unwrap_or(config.interval);
+    let alert_settings = config.get_alert_settings();
+    let grouping = config.grouping.clone().filter(|_| alert_settings.balance_change);
+    info!("🚀 Starting monitoring for network: {} (TronGrid)", tron.name);
+
+    let address_thresholds: HashMap<String, f64> = tron.addresses.iter()
+        .filter_map(|addr| Some((addr.alias.clone(), addr.min_balance_trx?)))
+        .collect();
+    let token_thresholds: HashMap<String, f64> = tron.tokens.iter()
+        .filter_map(|token| Some((token.alias.clone(), token.min_balance?)))
+        .collect();
+
+    loop {
+        events.publish(MonitorEvent::CheckStarted { network: tron.name.clone(), chain_id: Some(tron.chain_id) });
+        let mut all_balances = Vec::new();
+        let mut last_error = None;
+        let mut failed = 0;
+        let mut digest = grouping.as_ref().map(|grouping| ChangeDigest::new(&tron.name, tron.chain_id, grouping.max_items));
+
+        for result in monitor.check_addresses().await {
+            let balance_info = match result {
+                Ok(balance_info) => balance_info,
+                Err(e) => {
+                    error!("Error checking balance on {}: {}", tron.name, e);
+                    events.publish(MonitorEvent::RpcError { network: tron.name.clone(), error: e.to_string() });
+                    last_error = Some(e.to_string());
+                    failed += 1;
+                    continue;
+                }
+            };
+
+            log_balance_check(&balance_info);
+            events.publish(MonitorEvent::BalanceFetched(balance_info.clone()));
+            let (changes, previous) = {
+                let storage_read = storage.read().await;
+                let previous = storage_read.get(&balance_info.network_name, &balance_info.alias).cloned();
+                (compare_balances(&balance_info, &storage_read), previous)
+            };
+            if let (true, Some(previous)) = (config.drained.enabled, &previous) {
+                let drained = drained_assets(&balance_info, previous, config.drained.dust_percent);
+                if !drained.is_empty() {
+                    send_drained_alert(&notifiers, escalator.as_deref(), &balance_info, &drained).await;
+                }
+            }
+            if changes.has_changes() {
+                log_balance_changes(&changes);
+                events.publish(MonitorEvent::change_detected(&changes));
+                let now = chrono::Utc::now().timestamp() as u64;
+                if let Some(window) = maintenance.suppressing(&tron.name, &balance_info.alias, now).await {
+                    info!("🛠️ Change alert for {} suppressed by {}", balance_info.alias, window);
+                } else if let Some(ref mut digest) = digest {
+                    digest.push(changes);
+                } else if alert_settings.balance_change {
+                    if let Err(e) = notifiers.send_change_alert(&changes).await {
+                        warn!("Failed to send alert: {}", e);
+                    }
+                }
+            }
+
+            if alert_settings.low_balance {
+                let trx_threshold = address_thresholds.get(&balance_info.alias).copied();
+                for alert in low_balance_tracker.check(&balance_info, trx_threshold, &token_thresholds).await {
+                    send_low_balance_alert(&notifiers, escalator.as_deref(), &alert).await;
+                }
+            }
+
+            storage.write().await.update(&balance_info);
+            if let Some(ref history) = history {
+                let now = chrono::Utc::now().timestamp() as u64;
+                if let Err(e) = history.write().await.record(&balance_info, now) {
+                    warn!("Failed to record balance history: {}", e);
+                }
+            }
+            all_balances.push(balance_info);
+        }
+
+        if let Some(digest) = digest.filter(|digest| !digest.is_empty()) {
+            if let Err(e) = notifiers.send_change_digest(&digest).await {
+                warn!("Failed to send change digest: {}", e);
+            }
+        }
+
+        publish_check(&health, &events, MonitorEvent::CheckCompleted {
+            network: tron.name.clone(),
+            chain_id: Some(tron.chain_id),
+            addresses: all_balances.len(),
+            errors: failed,
+            error: last_error,
+        })
+        .await;
+
+        notifiers.update_balances(&all_balances).await;
+        if let Err(e) = backend.save_balances(&*storage.read().await).await {
+            warn!("Failed to save storage: {}", e);
+        }
+
+        tokio::time::sleep(interval).await;
+    }
+}
@@ -0,0 +1,545 @@
+mod chains;
+mod network;
+
+use chains::{monitor_cosmos_chain, monitor_tron_network, monitor_validators};
+use network::monitor_network;
+
+use crate::{
+    create_state_backend, spawn_api_server, spawn_backups, spawn_daily_report_scheduler, spawn_heartbeat,
+    spawn_watchlist_reload, AddressRegistry, AlertDedup, AlertSeverity, ApiState, AuditLog, BalanceInfo,
+    BalanceMonitorConfig, BalanceStorage, Config, CosmosMonitor, CosmosStates, CustomCheck, CustomChecks,
+    DiscordNotifier, DrainedAsset, DryRunNotifier, Entities, EscalationConfig, EscalationStep, Escalator,
+    EventBus, GasTracker, HealthTracker, HistoryStore, InstanceLease, LeaderElectionConfig, LowBalanceAlert,
+    LowBalanceTracker, MaintenanceSchedule, MatrixNotifier, MonitorAlert, MonitorEvent, NetworkConfig, Notifier,
+    NotifierSet, RpcHealth, RuleEngine, RunwayForecasts, SlackNotifier, StateBackend, StorageConfig,
+    TelegramNotifier, TronMonitor, TwilioNotifier, ValidatorMonitor, Watchlist, WebhookNotifier,
+    MULTICALL3_ADDRESS,
+};
+use eyre::Result;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tracing::{error, info, info_span, warn, Instrument};
+
+/// Balance monitor settings of a network
+pub fn balance_monitor_config(config: &Config, network: &NetworkConfig) -> BalanceMonitorConfig {
+    let mut monitor_config =
+        BalanceMonitorConfig::new(network.addresses.clone(), network.tokens.clone(), config.interval_for(network));
+    if network.multicall {
+        monitor_config = monitor_config.with_multicall(network.multicall_address.unwrap_or(MULTICALL3_ADDRESS));
+    }
+    monitor_config
+        .with_nfts(network.nfts.clone())
+        .with_confirmations(network.confirmations)
+        .with_concurrency(config.concurrency_for(network))
+        .with_batch_rpc(network.batch_rpc)
+}
+
+/// Slack, Discord, Matrix, Twilio and webhook channels configured
+pub fn webhook_notifiers(config: &Config) -> Vec<Box<dyn Notifier>> {
+    let mut notifiers: Vec<Box<dyn Notifier>> = Vec::new();
+    if let Some(slack_config) = &config.slack {
+        notifiers.push(Box::new(SlackNotifier::new(slack_config)));
+    }
+
+    if let Some(discord_config) = &config.discord {
+        notifiers.push(Box::new(DiscordNotifier::new(discord_config)));
+    }
+
+    if let Some(matrix_config) = &config.matrix {
+        notifiers.push(Box::new(MatrixNotifier::new(matrix_config)));
+    }
+
+    if let Some(twilio_config) = &config.twilio {
+        notifiers.push(Box::new(TwilioNotifier::new(twilio_config)));
+    }
+
+    for webhook_config in &config.webhooks {
+        notifiers.push(Box::new(WebhookNotifier::new(webhook_config)));
+    }
+    notifiers
+}
+
+/// Load balance history if enabled
+pub fn load_history(config: &Config) -> Result<Option<HistoryStore>> {
+    if !config.history.enabled {
+        return Ok(None);
+    }
+    let retention = std::time::Duration::from_secs(config.history.retention_days * 24 * 60 * 60);
+    let sample_interval = std::time::Duration::from_secs(config.history.sample_interval_secs);
+    Ok(Some(HistoryStore::load_from_file(config.history_path(), retention, sample_interval)?))
+}
+
+/// Monitors every configured network, chain and channel until shutdown
+pub struct Watcher {
+    config: Config,
+    dry_run: bool,
+    custom_checks: CustomChecks,
+    events: EventBus,
+}
+
+impl Watcher {
+    pub fn new(config: Config) -> Self {
+        Self { config, dry_run: false, custom_checks: CustomChecks::new(), events: EventBus::new() }
+    }
+
+    /// Log alerts instead of sending them, leaving leases, escalation and backups alone
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Add a check run every cycle on each EVM network it applies to
+    pub fn register_check(mut self, check: impl CustomCheck + 'static) -> Self {
+        self.custom_checks = self.custom_checks.with_check(check);
+        self
+    }
+
+    /// Replace the registered checks, e.g. to change how often their alerts repeat
+    pub fn with_custom_checks(mut self, custom_checks: CustomChecks) -> Self {
+        self.custom_checks = custom_checks;
+        self
+    }
+
+    /// Checks registered so far
+    pub fn custom_checks(&self) -> &CustomChecks {
+        &self.custom_checks
+    }
+
+    /// Bus the monitors publish their events on; subscribe before calling `run`
+    pub fn events(&self) -> &EventBus {
+        &self.events
+    }
+
+    /// Connect the state backend, start every monitor and run until they stop, the
+    /// lease is lost or a shutdown signal arrives
+    pub async fn run(self) -> Result<()> {
+        let Watcher { config, dry_run, custom_checks, events } = self;
+
+        // Create data directory if it doesn't exist
+        std::fs::create_dir_all(&config.data_dir)?;
+
+        // Connect state backend (files in data_dir or PostgreSQL)
+        let backend = create_state_backend(&config.storage, &config.data_dir, config.state_cipher()?).await?;
+
+        // One instance at a time works on the state. File state is always locked; with leader
+        // election this instance waits as a standby until the leader's lease expires.
+        let lease = match (&config.leader_election, &config.storage) {
+            _ if dry_run => None,
+            (Some(election), _) => Some((InstanceLease::new(Arc::clone(&backend), election), true)),
+            (None, StorageConfig::File) => {
+                Some((InstanceLease::new(Arc::clone(&backend), &LeaderElectionConfig::default()), false))
+            }
+            (None, StorageConfig::Postgres { .. }) => None,
+        };
+        let lease = match lease {
+            Some((lease, standby)) => {
+                lease.acquire(standby).await?;
+                Some(lease)
+            }
+            None => None,
+        };
+
+        // Load previous balance storage
+        let storage = Arc::new(RwLock::new(backend.load_balances().await?));
+
+        // Load balance history if enabled
+        let history = load_history(&config)?.map(|history| Arc::new(RwLock::new(history)));
+
+        // Monitored addresses, including ones added at runtime
+        let registry = Arc::new(AddressRegistry::new(&config.networks, Arc::clone(&backend)).await?);
+
+        // Latest gas prices of networks with gas tracking
+        let gas = Arc::new(GasTracker::new());
+
+        // Last successful check per network for the health endpoints
+        let health = Arc::new(HealthTracker::new());
+        let now = chrono::Utc::now().timestamp() as u64;
+        for network in &config.networks {
+            let interval = config.interval_for(network).as_secs();
+            health.register(&network.name, Some(network.chain_id), interval, network.rpc_nodes.len(), now).await;
+        }
+        for tron in &config.tron {
+            let interval = tron.interval.unwrap_or(config.interval).as_secs();
+            health.register(&tron.name, Some(tron.chain_id), interval, 1, now).await;
+        }
+        if let Some(beacon) = &config.beacon {
+            health.register(&beacon.name, Some(beacon.chain_id), beacon.interval.as_secs(), 1, now).await;
+        }
+        for chain in &config.cosmos {
+            let interval = chain.interval.unwrap_or(config.interval).as_secs();
+            health.register(&chain.name, None, interval, 1, now).await;
+        }
+
+        // Every alert delivery is recorded in the state backend and published on the event bus
+        let audit = AuditLog::new(Arc::clone(&backend)).with_events(events.clone());
+
+        // Per-node RPC request counters, filled in by each network's provider
+        let rpc = Arc::new(RpcHealth::new());
+
+        // Low balance alert throttling state shared by all networks
+        let throttle = config.low_balance_throttle.clone().unwrap_or_default();
+        let low_balance_tracker = LowBalanceTracker::new(Arc::clone(&backend)).await.with_schedule(throttle.clone());
+
+        // Alert rules evaluated on every balance check
+        let rules = Arc::new(RuleEngine::new(&config.rules)?.with_address_tags(&config.networks));
+
+        // Holders with addresses on several networks, totalled in reports
+        let entities = Arc::new(Entities::new(&config.entities, &config.networks));
+
+        // Time until each wallet reaches its low balance threshold, from the spend rate in history
+        let forecasts = Arc::new(RunwayForecasts::new(std::time::Duration::from_secs(config.history.runway_lookback_days * 24 * 60 * 60)));
+
+        // Flagged counterparties checked against attributed transactions
+        let watchlist = match &config.watchlist {
+            Some(watchlist_config) => {
+                let watchlist = Arc::new(Watchlist::load(watchlist_config).await?);
+                info!("🚩 Watchlist: {} flagged addresses from {}", watchlist.len().await, watchlist_config.path);
+                spawn_watchlist_reload(Arc::clone(&watchlist));
+                Some(watchlist)
+            }
+            None => None,
+        };
+
+        // Maintenance windows from the config and /maintenance
+        let maintenance = Arc::new(MaintenanceSchedule::new(&config.maintenance)?);
+
+        let custom_checks = Arc::new(custom_checks);
+        if !custom_checks.is_empty() {
+            info!("🧩 Custom checks: {}", custom_checks.names().join(", "));
+        }
+
+        // Acknowledgement and escalation of critical alerts; a dry run doesn't page anyone
+        let escalator = match &config.escalation {
+            Some(escalation) if !dry_run => Some(Arc::new(Escalator::new(escalation, Arc::clone(&backend)).await)),
+            _ => None,
+        };
+
+        // Initialize notification channels
+        let mut notifiers = NotifierSet::new();
+        notifiers.set_audit(audit.clone());
+        // Active instances sharing the state backend send each alert once
+        if let Some(dedup) = config.alert_dedup.as_ref().filter(|_| !dry_run) {
+            notifiers.set_dedup(AlertDedup::new(Arc::clone(&backend), dedup));
+        }
+        // Escalated alerts go to telegram_chats through the first bot
+        let mut telegram = None;
+
+        for (telegram_config, bot_name) in config.telegram_chat_stores() {
+            let mut notifier = TelegramNotifier::new(telegram_config, bot_name, Arc::clone(&storage), Arc::clone(&backend))
+                .await
+                .with_registry(Arc::clone(&registry))
+                .with_entities(Arc::clone(&entities))
+                .with_runways(Arc::clone(&forecasts))
+                .with_gas(Arc::clone(&gas))
+                .with_rpc(Arc::clone(&rpc))
+                .with_health(Arc::clone(&health))
+                .with_low_balance(low_balance_tracker.clone())
+                .with_maintenance(Arc::clone(&maintenance))
+                .with_explorers(config.explorers())
+                .with_audit(audit.clone());
+            if let Some(ref history) = history {
+                notifier = notifier.with_history(Arc::clone(history));
+            }
+            if let Some(ref escalator) = escalator {
+                notifier = notifier.with_escalator(Arc::clone(escalator));
+            }
+
+            // Count loaded chats
+            let loaded_chats = notifier.get_registered_chats_count().await;
+            if loaded_chats > 0 {
+                info!("📲 Loaded {} authorized chat(s) for {}", loaded_chats, telegram_config.channel_name());
+            }
+
+            // A dry run logs alerts instead, and leaves the bot's commands to the running watcher
+            if dry_run {
+                notifiers.push(Box::new(DryRunNotifier::new(Box::new(notifier))));
+                continue;
+            }
+
+            // Spawn command handler
+            notifier.clone().spawn_command_handler();
+
+            telegram.get_or_insert_with(|| notifier.clone());
+            notifiers.push(Box::new(notifier));
+        }
+
+        for notifier in webhook_notifiers(&config) {
+            if dry_run {
+                notifiers.push(Box::new(DryRunNotifier::new(notifier)));
+            } else {
+                notifiers.push(notifier);
+            }
+        }
+
+        let notifiers = Arc::new(notifiers);
+
+        // Spawn daily report scheduler if configured
+        if let Some(daily_report) = config.telegram_configs().find_map(|t| t.daily_report.clone()) {
+            spawn_daily_report_scheduler(
+                Arc::clone(&notifiers),
+                Arc::clone(&storage),
+                history.clone(),
+                Arc::clone(&backend),
+                daily_report,
+                Arc::clone(&entities),
+                Arc::clone(&rpc),
+                Arc::clone(&forecasts),
+            );
+        }
+
+        if let Some(heartbeat) = config.heartbeat.clone().filter(|_| !dry_run) {
+            spawn_heartbeat(heartbeat, Arc::clone(&notifiers), Arc::clone(&health));
+        }
+
+        if let Some(backup) = config.backup.clone().filter(|_| !dry_run) {
+            spawn_backups(
+                backup,
+                Arc::clone(&backend),
+                config.telegram_bot_names(),
+                PathBuf::from(&config.data_dir),
+                config.history_path(),
+                config.state_cipher()?,
+            );
+        }
+
+        if let (Some(escalation), Some(escalator)) = (config.escalation.clone(), &escalator) {
+            tokio::spawn(run_escalations(Arc::clone(escalator), escalation, Arc::clone(&notifiers), telegram));
+        }
+
+        // Start HTTP API if configured; a dry run leaves the listen address to the running watcher
+        if let Some(api_config) = config.api.as_ref().filter(|_| !dry_run) {
+            let mut api_state = ApiState::new(Arc::clone(&storage))
+                .with_gas(Arc::clone(&gas))
+                .with_audit(audit.clone())
+                .with_health(Arc::clone(&health), api_config.unhealthy_after_intervals)
+                .with_rpc(Arc::clone(&rpc))
+                .with_low_balance(low_balance_tracker.clone())
+                .with_registry(Arc::clone(&registry));
+            if let Some(ref history) = history {
+                api_state = api_state.with_history(Arc::clone(history));
+            }
+            spawn_api_server(api_config, api_state).await?;
+        }
+
+        // Beacon-chain validators are checked on their own schedule
+        if let Some(beacon_config) = config.beacon.clone() {
+            let notifiers = Arc::clone(&notifiers);
+            let span = info_span!("network", network = %beacon_config.name, chain_id = beacon_config.chain_id);
+            let monitor = ValidatorMonitor::new(beacon_config).with_throttle(throttle.clone());
+            tokio::spawn(monitor_validators(monitor, notifiers, Arc::clone(&health), events.clone()).instrument(span));
+        }
+
+        // Cosmos SDK chains are polled through their LCD endpoints
+        let cosmos_states = CosmosStates::new(Arc::clone(&backend)).await;
+        for chain in config.cosmos.clone() {
+            let interval = chain.interval.unwrap_or(config.interval);
+            let notifiers = Arc::clone(&notifiers);
+            let span = info_span!("network", network = %chain.name, chain_id = %chain.chain_id);
+            let monitor =
+                CosmosMonitor::new(chain).with_throttle(throttle.clone()).with_states(cosmos_states.clone());
+            tokio::spawn(monitor_cosmos_chain(monitor, notifiers, Arc::clone(&health), events.clone(), interval).instrument(span));
+        }
+
+        info!("✅ Balance monitoring started");
+        if dry_run {
+            info!("🧪 Dry run: alerts are logged, not sent");
+        }
+        info!("💾 Data directory: {}", config.data_dir);
+        info!("💾 State backend: {}", backend.name());
+        if config.history.enabled {
+            info!("🗂️  History: {} days retention", config.history.retention_days);
+        }
+
+        // Spawn monitoring task for each network
+        let mut handles = Vec::new();
+
+        let shared = SharedState {
+            config: Arc::new(config),
+            storage,
+            history,
+            notifiers,
+            low_balance_tracker,
+            rules,
+            maintenance,
+            custom_checks,
+            entities,
+            forecasts,
+            watchlist,
+            escalator,
+            backend,
+            registry,
+            gas,
+            rpc,
+            health,
+            events,
+        };
+
+        for network in shared.config.networks.clone() {
+            let shared = shared.clone();
+
+            let span = info_span!("network", network = %network.name, chain_id = network.chain_id);
+            let handle = tokio::spawn(async move {
+                if let Err(e) = monitor_network(network, shared).await {
+                    error!("Network monitoring error: {}", e);
+                }
+            }.instrument(span));
+
+            handles.push(handle);
+        }
+
+        for tron in shared.config.tron.clone() {
+            let shared = shared.clone();
+            let span = info_span!("network", network = %tron.name, chain_id = tron.chain_id);
+            handles.push(tokio::spawn(monitor_tron_network(TronMonitor::new(tron), shared).instrument(span)));
+        }
+
+        // Wait for all tasks to complete (they run indefinitely). Losing the lease stops this
+        // instance so it can't send alerts alongside the new leader; on shutdown the lease is
+        // released so a standby takes over right away.
+        let monitors = futures::future::join_all(handles);
+        match lease {
+            Some(lease) => tokio::select! {
+                _ = monitors => lease.release().await,
+                lost = lease.hold() => return Err(lost),
+                _ = shutdown_signal() => lease.release().await,
+            },
+            None => tokio::select! {
+                _ = monitors => {}
+                _ = shutdown_signal() => {}
+            },
+        }
+
+        Ok(())
+    }
+}
+
+/// Resolves on Ctrl-C or SIGTERM
+async fn shutdown_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        match signal(SignalKind::terminate()) {
+            Ok(mut terminate) => {
+                tokio::select! {
+                    _ = tokio::signal::ctrl_c() => {}
+                    _ = terminate.recv() => {}
+                }
+            }
+            Err(_) => {
+                let _ = tokio::signal::ctrl_c().await;
+            }
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = tokio::signal::ctrl_c().await;
+    }
+}
+
+/// Services shared by all network monitors
+#[derive(Clone)]
+struct SharedState {
+    config: Arc<Config>,
+    storage: Arc<RwLock<BalanceStorage>>,
+    history: Option<Arc<RwLock<HistoryStore>>>,
+    notifiers: Arc<NotifierSet>,
+    low_balance_tracker: LowBalanceTracker,
+    rules: Arc<RuleEngine>,
+    maintenance: Arc<MaintenanceSchedule>,
+    custom_checks: Arc<CustomChecks>,
+    entities: Arc<Entities>,
+    forecasts: Arc<RunwayForecasts>,
+    watchlist: Option<Arc<Watchlist>>,
+    escalator: Option<Arc<Escalator>>,
+    backend: Arc<dyn StateBackend>,
+    registry: Arc<AddressRegistry>,
+    gas: Arc<GasTracker>,
+    rpc: Arc<RpcHealth>,
+    health: Arc<HealthTracker>,
+    events: EventBus,
+}
+
+/// Record a finished check cycle in the health tracker, then publish it. Health is updated
+/// directly since bus subscribers that fall behind skip events.
+async fn publish_check(health: &HealthTracker, events: &EventBus, event: MonitorEvent) {
+    health.record_event(&event).await;
+    events.publish(event);
+}
+
+/// Re-send and escalate critical alerts nobody acknowledged in time
+async fn run_escalations(
+    escalator: Arc<Escalator>,
+    config: EscalationConfig,
+    notifiers: Arc<NotifierSet>,
+    telegram: Option<TelegramNotifier>,
+) {
+    info!("📟 Escalating critical alerts unacknowledged after {} min", config.ack_timeout_mins);
+
+    loop {
+        tokio::time::sleep(std::time::Duration::from_secs(30)).await;
+
+        let now = chrono::Utc::now().timestamp() as u64;
+        for step in escalator.due(now).await {
+            let tracked = step.tracked();
+            let alert = step.alert(now);
+            match &step {
+                EscalationStep::Resend(_) => {
+                    info!("🔁 Re-sending unacknowledged alert #{}: {}", tracked.id, tracked.alert.title);
+                    if let Err(e) = notifiers.send_critical_alert_to(&alert, &tracked.channels, tracked.id).await {
+                        warn!("Failed to re-send alert #{}: {}", tracked.id, e);
+                    }
+                }
+                EscalationStep::Escalate(_) => {
+                    info!("📟 Escalating unacknowledged alert #{}: {}", tracked.id, tracked.alert.title);
+                    if let (Some(telegram), false) = (&telegram, config.telegram_chats.is_empty()) {
+                        telegram.send_escalation(&config.telegram_chats, &alert, tracked.id).await;
+                    }
+                    if !config.channels.is_empty() {
+                        if let Err(e) = notifiers.send_critical_alert_to(&alert, &config.channels, tracked.id).await {
+                            warn!("Failed to escalate alert #{}: {}", tracked.id, e);
+                        }
+                    }
+                    if let Some(pagerduty) = escalator.pagerduty() {
+                        if let Err(e) = pagerduty.trigger(tracked).await {
+                            warn!("Failed to open PagerDuty incident for alert #{}: {}", tracked.id, e);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Send a drained alert to every channel right away; with escalation configured it
+/// can be acknowledged like a critical rule alert
+async fn send_drained_alert(notifiers: &NotifierSet, escalator: Option<&Escalator>, balance: &BalanceInfo, assets: &[DrainedAsset]) {
+    let alert = MonitorAlert::drained(balance, assets);
+    warn!("{} on {}", alert.title, balance.network_name);
+
+    let result = match escalator {
+        Some(escalator) => {
+            let now = chrono::Utc::now().timestamp() as u64;
+            notifiers.send_escalated_alert_to(escalator, &alert, &[], now).await
+        }
+        None => notifiers.send_alert(&alert).await,
+    };
+    if let Err(e) = result {
+        warn!("Failed to send drained alert: {}", e);
+    }
+}
+
+/// Send a low balance alert; critical tiers go through the escalator so they can be acknowledged
+async fn send_low_balance_alert(notifiers: &NotifierSet, escalator: Option<&Escalator>, alert: &LowBalanceAlert) {
+    let result = match (escalator, alert.severity) {
+        (Some(escalator), AlertSeverity::Critical) => {
+            let critical = MonitorAlert::low_balance(alert);
+            let now = chrono::Utc::now().timestamp() as u64;
+            notifiers.send_escalated_alert_to(escalator, &critical, &alert.channels, now).await
+        }
+        _ => notifiers.send_low_balance_alert(alert).await,
+    };
+    if let Err(e) = result {
+        warn!("Failed to send low balance alert: {}", e);
+    }
+}
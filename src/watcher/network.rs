@@ -0,0 +1,1361 @@
+use super::{balance_monitor_config, publish_check, send_drained_alert, send_low_balance_alert, SharedState};
+use crate::{
+    compare_balances, create_fallback_provider, create_ws_provider, discover_tokens, drained_assets,
+    format_gwei, format_usd, log_balance_changes, log_balance_check, lookup_transactions,
+    runway_threshold_eth, spawn_chain_id_checks, unexpected_destinations, verify_chain_ids, AddressConfig,
+    AddressRegistry, AddressType, AlertSettings, AlertSeverity, AnomalyDetector, AssetPrices, BalanceChange,
+    BalanceChangeSummary, BalanceInfo, BalanceMonitor, BalanceStorage, ChangeDigest, CheckContext, Config,
+    ContractWatcher, CorridorEvent, CorridorMonitor, CustomChecks, DrainedConfig, Entities, EntityEvent,
+    Escalator, EventBus, FallbackConfig, FeedIssue, FeedMonitor, GasConfig, GasLevel, GasPrice, GasTracker,
+    GroupEvent, GroupMonitor, GroupingConfig, HealthTracker, HistoryStore, InactivityEvent, InactivityMonitor,
+    IndexerClient, LowBalanceTracker, MaintenanceSchedule, MonitorAlert, MonitorEvent, MonitorMode,
+    NetworkConfig, NetworkRpcStatus, NodeStatus, Notifier, NotifierSet, PriceOracle, RollupEvent,
+    RollupMonitor, RpcHealth, RpcNodeConfig, RuleEngine, RunwayForecasts, SafeWatcher, StateBackend,
+    ThresholdTier, TokenConfig, TopUpTargets, ViewCallLevel, ViewCallMonitor, Watchlist,
+};
+use alloy::primitives::Address;
+use alloy::providers::Provider;
+use alloy::transports::http::reqwest::Url;
+use eyre::Result;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::RwLock;
+use tracing::{error, info, warn};
+
+/// Create the fallback provider of one of a network's RPC node pools, tracked in
+/// `/rpcstatus` under `pool_name`
+async fn create_pool_provider(
+    config: &Config,
+    network: &NetworkConfig,
+    pool_name: &str,
+    nodes: Vec<RpcNodeConfig>,
+    rpc: &RpcHealth,
+) -> Result<impl Provider> {
+    let node_urls: Vec<Url> = nodes.iter().map(|node| node.url.clone()).collect();
+    let trackers = rpc.register(pool_name, &node_urls);
+
+    // Exclude nodes serving another chain before the first check, then re-verify periodically
+    verify_chain_ids(pool_name, network.chain_id, &nodes, &trackers).await;
+    spawn_chain_id_checks(pool_name.to_string(), network.chain_id, nodes.clone(), trackers.clone());
+
+    let provider_config = FallbackConfig::new(nodes, config.active_transport_count)
+        .with_tracking(trackers)
+        .with_timeout(config.rpc_timeout_for(network))
+        .with_strategy(config.rpc_strategy_for(network));
+    create_fallback_provider(provider_config)
+}
+
+/// Key of a node's failover and down state; a node listed in both the routine and
+/// the archive pool is tracked once per pool
+fn node_key(status: &NetworkRpcStatus, node: &NodeStatus) -> String {
+    format!("{}/{}", status.network_name, node.id)
+}
+
+pub(super) async fn monitor_network(mut network: NetworkConfig, shared: SharedState) -> Result<()> {
+    let SharedState {
+        config, storage, history, notifiers, low_balance_tracker, rules, maintenance, custom_checks, entities, forecasts, watchlist,
+        escalator, backend, registry, gas, rpc, health, events,
+    } = shared;
+    let interval = config.interval_for(&network);
+    let alert_settings = config.get_alert_settings();
+
+    info!("🌐 Starting monitor for network: {} (Chain ID: {})", network.name, network.chain_id);
+
+    // Build threshold maps for low balance alerts
+    let mut address_thresholds: HashMap<String, f64> = HashMap::new();
+    for addr in &network.addresses {
+        if let Some(threshold) = addr.min_balance_eth {
+            address_thresholds.insert(addr.alias.clone(), threshold);
+        }
+    }
+
+    // Gas runway settings (min tx count, gas limit per tx), evaluated against live base fee
+    let mut address_runways: HashMap<String, (u64, u64)> = HashMap::new();
+    for addr in &network.addresses {
+        if let Some(runway) = addr.min_tx_runway {
+            address_runways.insert(addr.alias.clone(), (runway, addr.tx_gas_limit));
+        }
+    }
+
+    // Threshold tiers with their own severity and routing
+    let address_tiers: HashMap<String, Vec<ThresholdTier>> = network.addresses.iter()
+        .filter(|addr| !addr.thresholds.is_empty())
+        .map(|addr| (addr.alias.clone(), addr.thresholds.clone()))
+        .collect();
+    // Approved destinations of outgoing transfers
+    let allowed_destinations: HashMap<String, Vec<Address>> = network.addresses.iter()
+        .filter(|addr| !addr.allowed_destinations.is_empty())
+        .map(|addr| (addr.alias.clone(), addr.allowed_destinations.clone()))
+        .collect();
+    let token_tiers: HashMap<String, Vec<ThresholdTier>> = network.tokens.iter()
+        .filter(|token| !token.thresholds.is_empty())
+        .map(|token| (token.alias.clone(), token.thresholds.clone()))
+        .collect();
+
+    let mut token_thresholds: HashMap<String, f64> = HashMap::new();
+    for token in &network.tokens {
+        if let Some(threshold) = token.min_balance {
+            token_thresholds.insert(token.alias.clone(), threshold);
+        }
+    }
+
+    // USD thresholds, converted to asset amounts with each cycle's prices
+    let address_usd_thresholds: HashMap<String, f64> = network.addresses.iter()
+        .filter_map(|addr| Some((addr.alias.clone(), addr.min_balance_usd?)))
+        .collect();
+    let token_usd_thresholds: HashMap<String, f64> = network.tokens.iter()
+        .filter_map(|token| Some((token.alias.clone(), token.min_balance_usd?)))
+        .collect();
+    let top_ups = TopUpTargets::new(&network);
+
+    // Price oracle, only when this network has price sources
+    let has_prices = network.price.is_some() || network.tokens.iter().any(|t| t.price.is_some());
+    let prices = has_prices.then(|| PriceOracle::new(&config.pricing));
+
+    // Create provider for this network, and one for historical queries if it has archive nodes
+    let provider = create_pool_provider(&config, &network, &network.name, network.resolved_rpc_nodes(), &rpc).await?;
+    let archive = if network.archive_rpc_nodes.is_empty() {
+        None
+    } else {
+        let pool_name = format!("{} (archive)", network.name);
+        Some(create_pool_provider(&config, &network, &pool_name, network.resolved_archive_rpc_nodes(), &rpc).await?)
+    };
+
+    let indexer = network.indexer.as_ref().map(|indexer| IndexerClient::new(indexer, network.chain_id));
+
+    // Rollup checks, reading the bridge through the L1 network's nodes and sharing its
+    // node trackers, so nodes it excluded or sees failing are skipped here too
+    let rollup = match &network.rollup {
+        Some(rollup) => {
+            let mut monitor = RollupMonitor::new(rollup.clone());
+            let l1 = rollup.l1_network.as_ref().and_then(|name| config.networks.iter().find(|n| &n.name == name));
+            if let (Some(l1), Some(_)) = (l1, rollup.bridge) {
+                let l1_nodes = l1.resolved_rpc_nodes();
+                let l1_urls: Vec<Url> = l1_nodes.iter().map(|node| node.url.clone()).collect();
+                let l1_config = FallbackConfig::new(l1_nodes, config.active_transport_count)
+                    .with_tracking(rpc.register(&l1.name, &l1_urls))
+                    .with_timeout(config.rpc_timeout_for(l1))
+                    .with_strategy(config.rpc_strategy_for(l1));
+                monitor = monitor.with_l1_provider(create_fallback_provider(l1_config)?.erased(), l1.chain_id);
+            }
+            Some(monitor)
+        }
+        None => None,
+    };
+
+    // Track the tokens the addresses hold besides the configured ones
+    if let Some(discovery) = &network.token_discovery {
+        let holders: Vec<Address> = network.addresses.iter().map(|addr| addr.address).collect();
+        match discover_tokens(archive.as_ref().unwrap_or(&provider), indexer.as_ref(), discovery, &holders, &network.tokens).await {
+            Ok(tokens) if !tokens.is_empty() => {
+                let aliases: Vec<&str> = tokens.iter().map(|token| token.alias.as_str()).collect();
+                info!("🔎 Discovered {} tokens on {}: {}", tokens.len(), network.name, aliases.join(", "));
+                network.tokens.extend(tokens);
+            }
+            Ok(_) => info!("🔎 No new tokens discovered on {}", network.name),
+            Err(e) => warn!("Token discovery failed on {}, tracking configured tokens only: {}", network.name, e),
+        }
+    }
+
+    // Create monitor for this network
+    let monitor = BalanceMonitor::new(provider, balance_monitor_config(&config, &network));
+    let contracts = ContractWatcher::new(network.contracts.clone());
+    let view_calls = ViewCallMonitor::new(network.view_calls.clone())?;
+    let feeds = FeedMonitor::new(network.feeds.clone());
+    let inactivity = InactivityMonitor::new(&network.addresses);
+    let corridors = CorridorMonitor::new(&network.addresses);
+    let groups = GroupMonitor::new(&config.groups, &network.name);
+    let anomalies = AnomalyDetector::new(&network.addresses);
+    let safes = SafeWatcher::new(
+        network.addresses.iter().filter(|addr| addr.kind == AddressType::Safe).cloned().collect(),
+    );
+
+    let network_monitor = NetworkMonitor {
+        network,
+        cycles: AtomicU64::new(0),
+        rpc_down: std::sync::Mutex::new(HashSet::new()),
+        monitor,
+        archive,
+        indexer,
+        rollup,
+        storage,
+        history,
+        notifiers,
+        low_balance_tracker,
+        alert_settings,
+        rules,
+        maintenance,
+        custom_checks,
+        entities,
+        forecasts,
+        watchlist,
+        grouping: config.grouping.clone(),
+        drained: config.drained.clone(),
+        escalator,
+        backend,
+        registry,
+        gas,
+        rpc,
+        health,
+        events,
+        node_up: std::sync::Mutex::new(HashMap::new()),
+        node_down_mins: config.rpc_node_down_mins,
+        nodes_down: std::sync::Mutex::new(HashSet::new()),
+        contracts,
+        view_calls,
+        feeds,
+        safes,
+        inactivity,
+        corridors,
+        groups,
+        anomalies,
+        address_thresholds,
+        address_runways,
+        token_thresholds,
+        address_tiers,
+        token_tiers,
+        allowed_destinations,
+        prices,
+        address_usd_thresholds,
+        token_usd_thresholds,
+        top_ups,
+    };
+
+    match network_monitor.network.mode {
+        MonitorMode::Poll => network_monitor.run_polling(interval).await,
+        MonitorMode::Ws => network_monitor.run_ws(interval).await,
+    }
+}
+
+/// Per-network monitoring state shared by the polling and WebSocket loops
+struct NetworkMonitor<P> {
+    network: NetworkConfig,
+    /// Check cycles started, numbered in log spans
+    cycles: AtomicU64,
+    /// Node pools, the network's and its archive pool, whose RPC nodes are all failing
+    rpc_down: std::sync::Mutex<HashSet<String>>,
+    monitor: BalanceMonitor<P>,
+    /// Archive node pool for historical queries, None to use the routine nodes
+    archive: Option<P>,
+    /// Transaction history API used for attribution instead of the nodes
+    indexer: Option<IndexerClient>,
+    rollup: Option<RollupMonitor>,
+    storage: Arc<RwLock<BalanceStorage>>,
+    history: Option<Arc<RwLock<HistoryStore>>>,
+    notifiers: Arc<NotifierSet>,
+    low_balance_tracker: LowBalanceTracker,
+    alert_settings: AlertSettings,
+    rules: Arc<RuleEngine>,
+    maintenance: Arc<MaintenanceSchedule>,
+    custom_checks: Arc<CustomChecks>,
+    entities: Arc<Entities>,
+    /// Runway forecasts of the checked assets, shared with reports
+    forecasts: Arc<RunwayForecasts>,
+    watchlist: Option<Arc<Watchlist>>,
+    grouping: Option<GroupingConfig>,
+    drained: DrainedConfig,
+    escalator: Option<Arc<Escalator>>,
+    backend: Arc<dyn StateBackend>,
+    registry: Arc<AddressRegistry>,
+    gas: Arc<GasTracker>,
+    rpc: Arc<RpcHealth>,
+    health: Arc<HealthTracker>,
+    events: EventBus,
+    /// Whether each RPC node answered at the previous check and its failover count
+    /// then, for failover events, by pool and node ID
+    node_up: std::sync::Mutex<HashMap<String, (bool, u64)>>,
+    /// Minutes without an answer after which a node is reported down
+    node_down_mins: Option<u64>,
+    /// Pool and node IDs of nodes reported down, until they answer again
+    nodes_down: std::sync::Mutex<HashSet<String>>,
+    contracts: ContractWatcher,
+    view_calls: ViewCallMonitor,
+    feeds: FeedMonitor,
+    safes: SafeWatcher,
+    inactivity: InactivityMonitor,
+    corridors: CorridorMonitor,
+    groups: GroupMonitor,
+    anomalies: AnomalyDetector,
+    address_thresholds: HashMap<String, f64>,
+    address_runways: HashMap<String, (u64, u64)>,
+    token_thresholds: HashMap<String, f64>,
+    address_tiers: HashMap<String, Vec<ThresholdTier>>,
+    token_tiers: HashMap<String, Vec<ThresholdTier>>,
+    allowed_destinations: HashMap<String, Vec<Address>>,
+    prices: Option<PriceOracle>,
+    address_usd_thresholds: HashMap<String, f64>,
+    token_usd_thresholds: HashMap<String, f64>,
+    top_ups: TopUpTargets,
+}
+
+impl<P: Provider> NetworkMonitor<P> {
+    /// Check balances every `interval`
+    async fn run_polling(&self, interval: std::time::Duration) -> Result<()> {
+        loop {
+            self.run_cycle().await;
+            tokio::time::sleep(interval).await;
+        }
+    }
+
+    /// Check balances when a new block is announced over WebSocket, at most once per
+    /// `interval`. Reconnects after `interval` if the subscription drops.
+    async fn run_ws(&self, interval: std::time::Duration) -> Result<()> {
+        let ws_url = self.network.ws_url.clone()
+            .ok_or_else(|| eyre::eyre!("ws_url is required for ws mode on network '{}'", self.network.name))?;
+
+        // Initial check so state is populated before the first block arrives
+        self.run_cycle().await;
+
+        loop {
+            match self.watch_new_heads(ws_url.clone(), interval).await {
+                Ok(()) => warn!("WebSocket subscription closed on {}, reconnecting", self.network.name),
+                Err(e) => warn!("WebSocket error on {}: {}, reconnecting", self.network.name, e),
+            }
+
+            tokio::time::sleep(interval).await;
+            // Catch up on anything missed while disconnected
+            self.run_cycle().await;
+        }
+    }
+
+    /// Subscribe to newHeads and run a check cycle for a new block once `interval` has
+    /// passed since the previous cycle. On fast chains most blocks are skipped, so the
+    /// RPC budget doesn't grow with the block rate.
+    async fn watch_new_heads(&self, ws_url: Url, interval: std::time::Duration) -> Result<()> {
+        let ws_provider = create_ws_provider(ws_url).await?;
+        let mut subscription = ws_provider.subscribe_blocks().await?;
+        info!("🔌 Subscribed to new blocks on {}", self.network.name);
+
+        // A cycle ran right before subscribing
+        let mut last_cycle = tokio::time::Instant::now();
+        loop {
+            match subscription.recv().await {
+                // The next cycle reads the balances at its own head, so skipped blocks aren't missed
+                Ok(_header) if last_cycle.elapsed() < interval => {}
+                Ok(_header) => {
+                    last_cycle = tokio::time::Instant::now();
+                    self.run_cycle().await;
+                }
+                // Missed heads don't matter, the next cycle reads the latest balances
+                Err(RecvError::Lagged(skipped)) => {
+                    warn!("New block subscription on {} fell behind, {} blocks skipped", self.network.name, skipped)
+                }
+                Err(RecvError::Closed) => return Ok(()),
+            }
+        }
+    }
+
+    /// Publish an `RpcFailover` event for every node that went down or came back up,
+    /// or failed in between, since the previous check
+    fn publish_failovers(&self, status: &NetworkRpcStatus) {
+        let mut node_up = self.node_up.lock().unwrap_or_else(|e| e.into_inner());
+        for node in status.nodes.iter().filter(|node| node.is_used()) {
+            let up = node.is_up();
+            let previous = node_up.insert(node_key(status, node), (up, node.failovers));
+            if previous.is_some_and(|(was_up, failovers)| was_up != up || failovers < node.failovers) {
+                self.events.publish(MonitorEvent::RpcFailover {
+                    network: status.network_name.clone(),
+                    node: node.node.clone(),
+                    up,
+                    failovers: node.failovers,
+                    error: node.last_error.clone().filter(|_| !up),
+                });
+            }
+        }
+    }
+
+    /// Alert when a node hasn't answered for `node_down_mins`, and again once it answers
+    async fn check_down_nodes(&self, status: &NetworkRpcStatus) {
+        let Some(mins) = self.node_down_mins else { return };
+        let now = chrono::Utc::now().timestamp() as u64;
+        let mut alerts = Vec::new();
+        {
+            let mut nodes_down = self.nodes_down.lock().unwrap_or_else(|e| e.into_inner());
+            for node in &status.nodes {
+                let down_for = node.down_since.map(|since| now.saturating_sub(since));
+                let is_down = down_for.is_some_and(|secs| secs >= mins * 60);
+                let key = node_key(status, node);
+                if is_down && nodes_down.insert(key.clone()) {
+                    let mut lines = vec![format!("No answer for {} min", down_for.unwrap_or_default() / 60)];
+                    lines.extend(node.last_error.as_ref().map(|error| format!("Last error: {}", error)));
+                    let title = format!("🔴 RPC node {} down on {}", node.node, status.network_name);
+                    alerts.push((title, lines, node, key, true));
+                } else if node.down_since.is_none() && nodes_down.remove(&key) {
+                    let lines = vec![format!("Failovers since start: {}", node.failovers)];
+                    let title = format!("🟢 RPC node {} answering again on {}", node.node, status.network_name);
+                    alerts.push((title, lines, node, key, false));
+                }
+            }
+        }
+
+        for (title, lines, node, key, down) in alerts {
+            warn!("{}", title);
+            let alert = MonitorAlert {
+                kind: "rpc_node".to_string(),
+                network_name: self.network.name.clone(),
+                chain_id: Some(self.network.chain_id),
+                title,
+                lines,
+                data: serde_json::json!({ "node": node, "down": down }),
+                dedup_id: Some(key),
+            };
+            if let Err(e) = self.notifiers.send_alert(&alert).await {
+                warn!("Failed to send RPC node alert: {}", e);
+            }
+        }
+    }
+
+    /// Check the node health of the network's pool and of its archive pool, if any
+    async fn check_rpc_nodes(&self) {
+        let mut pools = vec![self.network.name.clone()];
+        if self.archive.is_some() {
+            pools.push(format!("{} (archive)", self.network.name));
+        }
+        for pool in pools {
+            if let Some(status) = self.rpc.network(&pool) {
+                self.check_rpc_pool(&status).await;
+            }
+        }
+    }
+
+    /// Alert once when every used RPC node of a pool is failing, and again when one recovers
+    async fn check_rpc_pool(&self, status: &NetworkRpcStatus) {
+        self.publish_failovers(status);
+        self.check_down_nodes(status).await;
+
+        let all_failing = status.all_failing();
+        {
+            let mut rpc_down = self.rpc_down.lock().unwrap_or_else(|e| e.into_inner());
+            let was_failing = if all_failing {
+                !rpc_down.insert(status.network_name.clone())
+            } else {
+                rpc_down.remove(&status.network_name)
+            };
+            if was_failing == all_failing {
+                return;
+            }
+        }
+
+        let title = if all_failing {
+            error!("All RPC nodes failing on {}", status.network_name);
+            format!("🚨 All RPC nodes failing on {}", status.network_name)
+        } else {
+            info!("RPC nodes recovered on {}", status.network_name);
+            format!("✅ RPC nodes recovered on {}", status.network_name)
+        };
+        let lines = status.nodes.iter()
+            .filter(|node| node.is_used() || node.excluded.is_some())
+            .map(|node| match (&node.last_error, node.is_up()) {
+                _ if node.excluded.is_some() => format!("{}: excluded, {}", node.node, node.excluded.as_deref().unwrap_or_default()),
+                (Some(error), false) => format!("{}: {}", node.node, error),
+                _ => format!("{}: up", node.node),
+            })
+            .collect();
+
+        let alert = MonitorAlert {
+            kind: "rpc_status".to_string(),
+            network_name: self.network.name.clone(),
+            chain_id: Some(self.network.chain_id),
+            title,
+            lines,
+            data: serde_json::json!({
+                "all_failing": all_failing,
+                "nodes": status.nodes,
+            }),
+            dedup_id: None,
+        };
+        if let Err(e) = self.notifiers.send_alert(&alert).await {
+            warn!("Failed to send RPC status alert: {}", e);
+        }
+    }
+
+    /// Record gas prices and alert when the base fee or blob base fee crosses a configured threshold
+    async fn track_gas(&self, base_fee: u128) {
+        let Some(gas_config) = &self.network.gas else {
+            return;
+        };
+
+        let blob_base_fee_wei = if gas_config.tracks_blob_fee() {
+            self.monitor.get_blob_base_fee().await
+                .map_err(|e| warn!("Failed to fetch blob base fee on {}: {}", self.network.name, e))
+                .ok()
+        } else {
+            None
+        };
+        let price = GasPrice {
+            network_name: self.network.name.clone(),
+            chain_id: self.network.chain_id,
+            base_fee_wei: base_fee,
+            priority_fee_wei: self.monitor.get_priority_fee().await.ok(),
+            blob_base_fee_wei,
+            blob_base_fee_24h: None,
+            updated_at: chrono::Utc::now().timestamp() as u64,
+        };
+        let base_fee_gwei = price.base_fee_gwei();
+        let priority_fee_gwei = price.priority_fee_gwei();
+
+        let crossings = self.gas.record(price, gas_config).await;
+        if let (Some(level), Some(blob_fee)) = (crossings.blob_fee, blob_base_fee_wei) {
+            self.send_blob_fee_alert(level, blob_fee, gas_config).await;
+        }
+        let (title, threshold) = match crossings.base_fee {
+            Some(GasLevel::Above) => ("⛽ Gas above threshold", gas_config.above_gwei),
+            Some(GasLevel::Below) => ("⛽ Gas below threshold, cheap to execute", gas_config.below_gwei),
+            _ => return,
+        };
+        info!("{} on {}: {:.2} gwei", title, self.network.name, base_fee_gwei);
+
+        let mut lines = vec![format!("Base fee: {:.2} gwei", base_fee_gwei)];
+        if let Some(priority) = priority_fee_gwei {
+            lines.push(format!("Priority fee: {:.2} gwei", priority));
+        }
+        if let Some(threshold) = threshold {
+            lines.push(format!("Threshold: {} gwei", threshold));
+        }
+
+        let alert = MonitorAlert {
+            kind: "gas_price".to_string(),
+            network_name: self.network.name.clone(),
+            chain_id: Some(self.network.chain_id),
+            title: title.to_string(),
+            lines,
+            data: serde_json::json!({
+                "base_fee_gwei": base_fee_gwei,
+                "priority_fee_gwei": priority_fee_gwei,
+                "threshold_gwei": threshold,
+            }),
+            dedup_id: None,
+        };
+        if let Err(e) = self.notifiers.send_alert(&alert).await {
+            warn!("Failed to send gas alert: {}", e);
+        }
+    }
+
+    /// Alert on a blob base fee crossing, with its 24 hour range
+    async fn send_blob_fee_alert(&self, level: GasLevel, blob_fee: u128, gas_config: &GasConfig) {
+        let (title, threshold) = match level {
+            GasLevel::Above => ("🫧 Blob fee above threshold", gas_config.blob_above_gwei),
+            GasLevel::Below => ("🫧 Blob fee below threshold", gas_config.blob_below_gwei),
+            GasLevel::Normal => return,
+        };
+        info!("{} on {}: {} gwei", title, self.network.name, format_gwei(blob_fee));
+
+        let mut lines = vec![format!("Blob base fee: {} gwei", format_gwei(blob_fee))];
+        let range = self.gas.get(&self.network.name).await.and_then(|price| price.blob_base_fee_24h);
+        if let Some(range) = range {
+            lines.push(format!("24h range: {} – {} gwei", format_gwei(range.min_wei), format_gwei(range.max_wei)));
+        }
+        if let Some(threshold) = threshold {
+            lines.push(format!("Threshold: {} gwei", threshold));
+        }
+
+        let alert = MonitorAlert {
+            kind: "blob_fee".to_string(),
+            network_name: self.network.name.clone(),
+            chain_id: Some(self.network.chain_id),
+            title: title.to_string(),
+            lines,
+            data: serde_json::json!({
+                "blob_base_fee_wei": blob_fee.to_string(),
+                "blob_base_fee_24h": range,
+                "threshold_gwei": threshold,
+            }),
+            dedup_id: None,
+        };
+        if let Err(e) = self.notifiers.send_alert(&alert).await {
+            warn!("Failed to send blob fee alert: {}", e);
+        }
+    }
+
+    /// Alert on bytecode, owner or proxy changes of watched contracts
+    async fn watch_contracts(&self) {
+        for (contract, changes) in self.contracts.check(self.monitor.provider()).await {
+            info!("🛡️  Contract {} changed on {}", contract.alias, self.network.name);
+
+            let mut lines = vec![format!("Address: {}", contract.address)];
+            for change in &changes {
+                info!(contract = %contract.alias, "{}: {} → {}", change.field, change.old, change.new);
+                lines.push(format!("{}: {} → {}", change.field, change.old, change.new));
+            }
+
+            let alert = MonitorAlert {
+                kind: "contract_change".to_string(),
+                network_name: self.network.name.clone(),
+                chain_id: Some(self.network.chain_id),
+                title: format!("🛡️ Contract changed: {}", contract.alias),
+                lines,
+                data: serde_json::json!({
+                    "alias": contract.alias,
+                    "address": contract.address,
+                    "changes": changes.iter().map(|c| c.to_json()).collect::<Vec<_>>(),
+                }),
+                dedup_id: Some(changes.iter().map(|c| format!("{}:{}->{}", c.field, c.old, c.new)).collect::<Vec<_>>().join(",")),
+            };
+            if let Err(e) = self.notifiers.send_alert(&alert).await {
+                warn!("Failed to send contract alert: {}", e);
+            }
+        }
+    }
+
+    /// Run custom checks and route their alerts like rule alerts
+    async fn run_custom_checks(&self, balances: &[BalanceInfo]) {
+        let now = chrono::Utc::now().timestamp() as u64;
+        let context = CheckContext { network: &self.network, provider: self.monitor.provider(), balances, now };
+        for custom in self.custom_checks.run(&context).await {
+            let alias = custom.alias.as_deref().unwrap_or_default();
+            if let Some(window) = self.maintenance.suppressing(&self.network.name, alias, now).await {
+                info!("🛠️ {} alert suppressed by {}", custom.alert.kind, window);
+                continue;
+            }
+            info!("🧩 {} on {}", custom.alert.title, self.network.name);
+            let result = match (&self.escalator, custom.severity) {
+                (Some(escalator), AlertSeverity::Critical) => {
+                    self.notifiers.send_escalated_alert_to(escalator, &custom.alert, &custom.channels, now).await
+                }
+                _ => self.notifiers.send_alert_to(&custom.alert, &custom.channels).await,
+            };
+            if let Err(e) = result {
+                warn!("Failed to send {} alert: {}", custom.alert.kind, e);
+            }
+        }
+    }
+
+    /// Alert on changed or out-of-range view call values
+    async fn check_view_calls(&self) {
+        for (call, update) in self.view_calls.check(self.monitor.provider()).await {
+            let config = &call.config;
+            let title = match update.crossed {
+                Some(ViewCallLevel::Below) => format!("👁️ {} below minimum", config.alias),
+                Some(ViewCallLevel::Above) => format!("👁️ {} above maximum", config.alias),
+                _ => format!("👁️ {} changed", config.alias),
+            };
+            info!("{} on {}: {}", title, self.network.name, update.value.formatted);
+
+            let mut lines = vec![format!("Call: {} on {}", call.describe(), config.address)];
+            match &update.changed_from {
+                Some(previous) => lines.push(format!("Value: {} → {}", previous, update.value.formatted)),
+                None => lines.push(format!("Value: {}", update.value.formatted)),
+            }
+            match update.crossed {
+                Some(ViewCallLevel::Below) => lines.extend(config.min.map(|min| format!("Minimum: {}", min))),
+                Some(ViewCallLevel::Above) => lines.extend(config.max.map(|max| format!("Maximum: {}", max))),
+                _ => {}
+            }
+
+            let alert = MonitorAlert {
+                kind: "view_call".to_string(),
+                network_name: self.network.name.clone(),
+                chain_id: Some(self.network.chain_id),
+                title,
+                lines,
+                data: serde_json::json!({
+                    "alias": config.alias,
+                    "address": config.address,
+                    "call": call.describe(),
+                    "value": update.value.formatted,
+                    "previous_value": update.changed_from,
+                    "min": config.min,
+                    "max": config.max,
+                }),
+                dedup_id: update.changed_from.as_ref().map(|previous| format!("{}->{}", previous, update.value.formatted)),
+            };
+            if let Err(e) = self.notifiers.send_alert(&alert).await {
+                warn!("Failed to send view call alert: {}", e);
+            }
+        }
+    }
+
+    /// Alert on stale or sharply moving Chainlink feeds
+    async fn check_feeds(&self) {
+        let now = chrono::Utc::now().timestamp() as u64;
+        for (feed, round, issues) in self.feeds.check(self.monitor.provider(), now).await {
+            let title = match issues.first() {
+                Some(FeedIssue::Stale { .. }) => format!("📡 {} feed is stale", feed.alias),
+                _ => format!("📡 {} feed moved", feed.alias),
+            };
+            info!("{} on {}: {}", title, self.network.name, round.answer);
+
+            let mut lines = vec![format!("Answer: {}", round.answer)];
+            lines.extend(issues.iter().map(|issue| issue.describe()));
+
+            let alert = MonitorAlert {
+                kind: "price_feed".to_string(),
+                network_name: self.network.name.clone(),
+                chain_id: Some(self.network.chain_id),
+                title,
+                lines,
+                data: serde_json::json!({
+                    "alias": feed.alias,
+                    "address": feed.address,
+                    "answer": round.answer,
+                    "updated_at": round.updated_at,
+                    "stale": issues.iter().any(|issue| matches!(issue, FeedIssue::Stale { .. })),
+                    "deviation_percent": issues.iter().find_map(|issue| match issue {
+                        FeedIssue::Deviation { percent, .. } => Some(*percent),
+                        _ => None,
+                    }),
+                }),
+                dedup_id: None,
+            };
+            if let Err(e) = self.notifiers.send_alert(&alert).await {
+                warn!("Failed to send feed alert: {}", e);
+            }
+        }
+    }
+
+    /// Alert when the sequencer stalls or resumes, and when the L1 bridge runs low
+    async fn check_rollup(&self) {
+        let Some(rollup) = &self.rollup else { return };
+        let now = chrono::Utc::now().timestamp() as u64;
+        let config = rollup.config();
+        for event in rollup.check(self.monitor.provider(), now).await {
+            let (title, lines, data) = match event {
+                RollupEvent::SequencerStalled { block, age } => (
+                    format!("🧱 Sequencer stalled on {}", self.network.name),
+                    vec![
+                        format!("No new block for {} min (limit: {} min)", age.as_secs() / 60, config.max_block_age.as_secs() / 60),
+                        format!("Latest block: {}", block),
+                        "Balances read from this chain won't change until it resumes".to_string(),
+                    ],
+                    serde_json::json!({ "stalled": true, "block": block, "block_age_secs": age.as_secs() }),
+                ),
+                RollupEvent::SequencerResumed { block } => (
+                    format!("✅ Sequencer producing blocks again on {}", self.network.name),
+                    vec![format!("Latest block: {}", block)],
+                    serde_json::json!({ "stalled": false, "block": block }),
+                ),
+                RollupEvent::BridgeLow { balance, min } | RollupEvent::BridgeRecovered { balance, min } => {
+                    let low = matches!(event, RollupEvent::BridgeLow { .. });
+                    let title = if low {
+                        format!("🌉 {} bridge balance low", self.network.name)
+                    } else {
+                        format!("✅ {} bridge balance recovered", self.network.name)
+                    };
+                    (
+                        title,
+                        vec![format!("Balance on {}: {} ETH (minimum: {} ETH)", config.l1_network.as_deref().unwrap_or_default(), balance, min)],
+                        serde_json::json!({
+                            "bridge_low": low,
+                            "bridge": config.bridge,
+                            "l1_network": config.l1_network,
+                            "bridge_balance": balance,
+                            "min_bridge_balance": min,
+                        }),
+                    )
+                }
+            };
+            warn!("{}", title);
+
+            let alert = MonitorAlert {
+                kind: "rollup".to_string(),
+                network_name: self.network.name.clone(),
+                chain_id: Some(self.network.chain_id),
+                title,
+                lines,
+                data,
+                dedup_id: None,
+            };
+            if let Err(e) = self.notifiers.send_alert(&alert).await {
+                warn!("Failed to send rollup alert: {}", e);
+            }
+        }
+    }
+
+    /// Alert on balance changes far outside the address's usual changes
+    async fn check_anomalies(&self, balance: &BalanceInfo, previous: &BalanceInfo, now: u64) {
+        let Some(ref history) = self.history else {
+            return;
+        };
+        let anomalies = self.anomalies.check(&*history.read().await, balance, previous, now);
+        for anomaly in anomalies {
+            let title = format!("📊 Unusual {} change on {}", anomaly.asset, balance.alias);
+            info!("{} ({}): {:+} ({:.1}σ)", title, self.network.name, anomaly.change, anomaly.z_score);
+
+            let mut lines = vec![format!("Address: {:?}", balance.address)];
+            lines.extend(anomaly.lines());
+            let alert = MonitorAlert {
+                kind: "anomaly".to_string(),
+                network_name: self.network.name.clone(),
+                chain_id: Some(self.network.chain_id),
+                title,
+                lines,
+                data: serde_json::json!({
+                    "alias": balance.alias,
+                    "address": balance.address,
+                    "asset": anomaly.asset,
+                    "change": anomaly.change,
+                    "mean": anomaly.stats.mean,
+                    "stddev": anomaly.stats.stddev,
+                    "samples": anomaly.stats.samples,
+                    "z_score": anomaly.z_score,
+                }),
+                dedup_id: None,
+            };
+            if let Err(e) = self.notifiers.send_alert(&alert).await {
+                warn!("Failed to send anomaly alert: {}", e);
+            }
+        }
+    }
+
+    /// Alert when balances of an address stop changing for longer than allowed, and when they move again
+    async fn check_inactivity(&self, balance: &BalanceInfo, last_changed: u64, now: u64) {
+        let Some(event) = self.inactivity.check(&balance.alias, last_changed, now).await else {
+            return;
+        };
+        let (title, lines, inactive) = match event {
+            InactivityEvent::Inactive { since, limit_secs } => (
+                format!("💤 {} balance hasn't changed", balance.alias),
+                vec![
+                    format!("Address: {:?}", balance.address),
+                    format!("No change for {}h (limit {}h)", now.saturating_sub(since) / 3600, limit_secs / 3600),
+                ],
+                true,
+            ),
+            InactivityEvent::Active { inactive_secs } => (
+                format!("✅ {} balance is changing again", balance.alias),
+                vec![
+                    format!("Address: {:?}", balance.address),
+                    format!("Was unchanged for {}h", inactive_secs / 3600),
+                ],
+                false,
+            ),
+        };
+        info!("{} on {}", title, self.network.name);
+
+        let alert = MonitorAlert {
+            kind: "inactivity".to_string(),
+            network_name: self.network.name.clone(),
+            chain_id: Some(self.network.chain_id),
+            title,
+            lines,
+            data: serde_json::json!({
+                "alias": balance.alias,
+                "address": balance.address,
+                "inactive": inactive,
+                "last_changed": last_changed,
+            }),
+            dedup_id: None,
+        };
+        if let Err(e) = self.notifiers.send_alert(&alert).await {
+            warn!("Failed to send inactivity alert: {}", e);
+        }
+    }
+
+    /// Alert when the native balance leaves its expected corridor, and when it's back inside
+    async fn check_corridor(&self, balance: &BalanceInfo) {
+        let Some(event) = self.corridors.check(&balance.alias, balance.eth_balance, balance.native_decimals).await else {
+            return;
+        };
+        let symbol = &balance.native_symbol;
+        let (title, bound, inside) = match event {
+            CorridorEvent::Below { min } => (format!("📉 {} balance below expected range", balance.alias), format!("minimum {} {}", min, symbol), false),
+            CorridorEvent::Above { max } => (format!("📈 {} balance above expected range", balance.alias), format!("maximum {} {}", max, symbol), false),
+            CorridorEvent::Inside => (format!("✅ {} balance back in expected range", balance.alias), String::new(), true),
+        };
+        info!("{} on {}", title, self.network.name);
+
+        let mut lines = vec![
+            format!("Address: {:?}", balance.address),
+            format!("Balance: {} {}", balance.eth_formatted, symbol),
+        ];
+        if !inside {
+            lines.push(format!("Expected {}", bound));
+        }
+        let alert = MonitorAlert {
+            kind: "corridor".to_string(),
+            network_name: self.network.name.clone(),
+            chain_id: Some(self.network.chain_id),
+            title,
+            lines,
+            data: serde_json::json!({
+                "alias": balance.alias,
+                "address": balance.address,
+                "balance": balance.eth_formatted,
+                "inside": inside,
+            }),
+            dedup_id: None,
+        };
+        if let Err(e) = self.notifiers.send_alert(&alert).await {
+            warn!("Failed to send corridor alert: {}", e);
+        }
+    }
+
+    /// Evaluate aggregate rules on the cycle's balances
+    async fn check_rule_totals(&self, addresses: &[AddressConfig], balances: &[BalanceInfo]) {
+        let now = chrono::Utc::now().timestamp() as u64;
+        for rule_alert in self.rules.evaluate_totals(&self.network.name, addresses, balances, now) {
+            if let Some(window) = self.maintenance.suppressing(&self.network.name, "", now).await {
+                info!("🛠️ {} rule alert suppressed by {}", rule_alert.rule, window);
+                continue;
+            }
+            info!("{} on {}", rule_alert.alert.title, self.network.name);
+            let result = match (&self.escalator, rule_alert.severity) {
+                (Some(escalator), AlertSeverity::Critical) => {
+                    self.notifiers.send_escalated_alert_to(escalator, &rule_alert.alert, &rule_alert.channels, now).await
+                }
+                _ => self.notifiers.send_alert_to(&rule_alert.alert, &rule_alert.channels).await,
+            };
+            if let Err(e) = result {
+                warn!("Failed to send {} rule alert: {}", rule_alert.rule, e);
+            }
+        }
+    }
+
+    /// Alert when the USD total of an entity across networks drops below its minimum, and when
+    /// it recovers. Every network checks the latest stored balances after its cycle; the
+    /// shared state makes sure each transition is sent once.
+    async fn check_entities(&self) {
+        let balances: Vec<BalanceInfo> = self.storage.read().await.balances.values().cloned().collect();
+        for (total, event) in self.entities.check(&balances) {
+            let (title, below, min) = match event {
+                EntityEvent::Below { min } => (format!("📉 {} total below {}", total.name, format_usd(min)), true, min),
+                EntityEvent::Recovered { min } => (format!("✅ {} total back above {}", total.name, format_usd(min)), false, min),
+            };
+            info!("{}", title);
+
+            let alert = MonitorAlert {
+                kind: "entity_balance".to_string(),
+                network_name: total.networks.join(", "),
+                chain_id: None,
+                title,
+                lines: vec![
+                    format!("Total: {} on {}", format_usd(total.usd), total.networks.join(", ")),
+                    format!("Minimum: {}", format_usd(min)),
+                ],
+                data: serde_json::json!({
+                    "entity": total.name,
+                    "networks": total.networks,
+                    "total_usd": total.usd,
+                    "min_total_usd": min,
+                    "below": below,
+                }),
+                dedup_id: None,
+            };
+            if let Err(e) = self.notifiers.send_alert(&alert).await {
+                warn!("Failed to send entity alert: {}", e);
+            }
+        }
+    }
+
+    /// Alert when the total balance of a tagged group drops below its minimum, and when it recovers
+    async fn check_groups(&self, addresses: &[AddressConfig], balances: &[BalanceInfo]) {
+        for (group, total, event) in self.groups.check(addresses, balances).await {
+            let (title, below, min) = match event {
+                GroupEvent::Below { min } => (format!("📉 {} total below {} {}", group.tag, min, total.symbol), true, min),
+                GroupEvent::Recovered { min } => (format!("✅ {} total back above {} {}", group.tag, min, total.symbol), false, min),
+            };
+            info!("{} on {}", title, self.network.name);
+
+            let alert = MonitorAlert {
+                kind: "group_balance".to_string(),
+                network_name: self.network.name.clone(),
+                chain_id: Some(self.network.chain_id),
+                title,
+                lines: vec![
+                    format!("Total: {} {} across {} address(es)", total.formatted, total.symbol, total.addresses),
+                    format!("Minimum: {} {}", min, total.symbol),
+                ],
+                data: serde_json::json!({
+                    "tag": group.tag,
+                    "total": total.formatted,
+                    "addresses": total.addresses,
+                    "min_total_balance": min,
+                    "below": below,
+                }),
+                dedup_id: None,
+            };
+            if let Err(e) = self.notifiers.send_alert(&alert).await {
+                warn!("Failed to send group alert: {}", e);
+            }
+        }
+    }
+
+    /// Alert when a Safe's owners or signing threshold change
+    async fn check_safes(&self) {
+        for (safe, change) in self.safes.check(self.monitor.provider()).await {
+            info!("🔐 Safe {} signers changed on {}", safe.alias, self.network.name);
+
+            let mut lines = vec![format!("Address: {}", safe.address)];
+            lines.extend(change.lines());
+            for line in &lines {
+                info!(safe = %safe.alias, "{}", line);
+            }
+
+            let mut data = change.to_json();
+            data["alias"] = serde_json::json!(safe.alias);
+            data["address"] = serde_json::json!(safe.address);
+
+            let alert = MonitorAlert {
+                kind: "safe_change".to_string(),
+                network_name: self.network.name.clone(),
+                chain_id: Some(self.network.chain_id),
+                title: format!("🔐 Safe signers changed: {}", safe.alias),
+                lines,
+                data,
+                dedup_id: Some(change.lines().join(",")),
+            };
+            if let Err(e) = self.notifiers.send_alert(&alert).await {
+                warn!("Failed to send Safe alert: {}", e);
+            }
+        }
+    }
+
+    /// Attach the transactions behind a balance change, searching blocks `from_block..=to_block`
+    async fn attribute_transactions(&self, changes: &mut BalanceChangeSummary, from_block: u64, to_block: u64) {
+        let config = &self.network.tx_lookup;
+        if !config.enabled {
+            return;
+        }
+
+        let Ok(address) = changes.address.parse::<Address>() else {
+            return;
+        };
+        let native = changes.eth_change.as_ref().is_some_and(|c| !matches!(c.change, BalanceChange::NoChange));
+        let tokens: Vec<&TokenConfig> = self.monitor.tokens().iter()
+            .filter(|token| changes.token_changes.iter()
+                .any(|c| c.alias == token.alias && !matches!(c.change, BalanceChange::NoChange)))
+            .collect();
+
+        let explorer = self.network.explorer_url.as_ref();
+        let result = match &self.indexer {
+            Some(indexer) => indexer.lookup_transactions(address, native, &tokens, from_block, to_block, explorer).await,
+            None => {
+                let provider = self.archive.as_ref().unwrap_or(self.monitor.provider());
+                lookup_transactions(provider, config, address, native, &tokens, from_block, to_block, explorer).await
+            }
+        };
+        match result {
+            Ok(transactions) => changes.transactions = transactions,
+            Err(e) => warn!("Failed to look up transactions for {} on {}: {}", changes.alias, self.network.name, e),
+        }
+    }
+
+    /// Send a critical alert for each outgoing transfer to a destination not on the
+    /// address's `allowed_destinations`, and for each drop of an asset whose outgoing
+    /// transaction wasn't found, so a failed lookup doesn't count as an allowed transfer.
+    /// Like drained alerts, these bypass maintenance windows.
+    async fn check_destinations(&self, changes: &BalanceChangeSummary) {
+        let Some(allowed) = self.allowed_destinations.get(&changes.alias) else { return };
+        let unexpected = unexpected_destinations(&changes.transactions, allowed)
+            .into_iter()
+            .map(|tx| MonitorAlert::unexpected_destination(changes, tx));
+        let unknown = changes.unattributed_outflows()
+            .into_iter()
+            .map(|change| MonitorAlert::unknown_destination(changes, change));
+        for alert in unexpected.chain(unknown) {
+            warn!("{} on {}: {}", alert.title, self.network.name, alert.lines.join("; "));
+            let result = match &self.escalator {
+                Some(escalator) => {
+                    let now = chrono::Utc::now().timestamp() as u64;
+                    self.notifiers.send_escalated_alert_to(escalator, &alert, &[], now).await
+                }
+                None => self.notifiers.send_alert(&alert).await,
+            };
+            if let Err(e) = result {
+                warn!("Failed to send {} alert: {}", alert.kind, e);
+            }
+        }
+    }
+
+    /// Send a critical alert for each attributed transfer whose counterparty is on the
+    /// watchlist. Like drained alerts, they bypass maintenance windows.
+    async fn check_flagged(&self, changes: &BalanceChangeSummary) {
+        let Some(watchlist) = &self.watchlist else { return };
+        for (tx, label) in watchlist.flagged(&changes.transactions).await {
+            let alert = MonitorAlert::flagged(changes, tx, &label);
+            warn!("{} on {}: {:?}", alert.title, self.network.name, tx.tx_hash);
+            let result = match &self.escalator {
+                Some(escalator) => {
+                    let now = chrono::Utc::now().timestamp() as u64;
+                    self.notifiers.send_escalated_alert_to(escalator, &alert, watchlist.channels(), now).await
+                }
+                None => self.notifiers.send_alert_to(&alert, watchlist.channels()).await,
+            };
+            if let Err(e) = result {
+                warn!("Failed to send flagged counterparty alert: {}", e);
+            }
+        }
+    }
+
+    /// Run a single check cycle: fetch balances, send alerts and persist storage
+    #[tracing::instrument(name = "cycle", skip_all, fields(cycle = self.cycles.fetch_add(1, Ordering::Relaxed) + 1))]
+    async fn run_cycle(&self) {
+        let network = &self.network;
+        let storage = &self.storage;
+        let notifiers = &self.notifiers;
+        let alert_settings = &self.alert_settings;
+        self.events.publish(MonitorEvent::CheckStarted { network: network.name.clone(), chain_id: Some(network.chain_id) });
+
+        // Fetch base fee once for gas tracking and runway thresholds
+        let needs_runway = alert_settings.low_balance && !self.address_runways.is_empty();
+        let base_fee = if needs_runway || network.gas.is_some() {
+            self.monitor.get_base_fee().await
+                .map_err(|e| warn!("Failed to fetch base fee on {}: {}", network.name, e))
+                .ok()
+        } else {
+            None
+        };
+        if let Some(base_fee) = base_fee {
+            self.track_gas(base_fee).await;
+        }
+
+        if !self.contracts.is_empty() {
+            self.watch_contracts().await;
+        }
+        if !self.view_calls.is_empty() {
+            self.check_view_calls().await;
+        }
+        if !self.feeds.is_empty() {
+            self.check_feeds().await;
+        }
+        if self.rollup.is_some() {
+            self.check_rollup().await;
+        }
+        if !self.safes.is_empty() {
+            self.check_safes().await;
+        }
+
+        // Refresh effective thresholds from gas runway settings using current base fee
+        let mut effective_thresholds = self.address_thresholds.clone();
+        if let (true, Some(base_fee)) = (needs_runway, base_fee) {
+            for (alias, &(runway, gas_limit)) in &self.address_runways {
+                let runway_threshold = runway_threshold_eth(runway, gas_limit, base_fee);
+                let threshold = effective_thresholds.entry(alias.clone()).or_insert(0.0);
+                *threshold = threshold.max(runway_threshold);
+            }
+        }
+
+        // Resolve USD prices and convert USD thresholds into asset amounts
+        let asset_prices = match &self.prices {
+            Some(oracle) => oracle.resolve(self.monitor.provider(), network).await,
+            None => AssetPrices::default(),
+        };
+        let mut token_thresholds = self.token_thresholds.clone();
+        if let Some(price) = asset_prices.native {
+            for (alias, usd) in &self.address_usd_thresholds {
+                let threshold = effective_thresholds.entry(alias.clone()).or_insert(0.0);
+                *threshold = threshold.max(usd / price);
+            }
+        }
+        for (alias, usd) in &self.token_usd_thresholds {
+            if let Some(price) = asset_prices.tokens.get(alias) {
+                let threshold = token_thresholds.entry(alias.clone()).or_insert(0.0);
+                *threshold = threshold.max(usd / price);
+            }
+        }
+        let mut token_tiers = self.token_tiers.clone();
+        for (alias, &threshold) in &token_thresholds {
+            token_tiers.entry(alias.clone()).or_default().push(ThresholdTier::below(threshold));
+        }
+
+        // Detect reorgs that replaced the block the previous balances were read at
+        let last_block = storage.read().await.latest_block(&network.name);
+        if let Some((number, hash)) = last_block {
+            match self.monitor.is_canonical(number, hash).await {
+                Ok(true) => {}
+                Ok(false) => warn!(
+                    "Reorg detected on {}: block {} is no longer canonical, previous balances may include reverted transactions",
+                    network.name, number
+                ),
+                Err(e) => warn!("Failed to verify block {} on {}: {}", number, network.name, e),
+            }
+        }
+
+        let addresses = self.registry.addresses(&network.name).await;
+        let results = self.monitor.check_addresses(&addresses, network.name.clone(), network.chain_id).await;
+        let mut all_balances = Vec::new();
+        let mut last_error = None;
+        let mut failed = 0;
+        let mut digest = self.grouping.as_ref()
+            .filter(|_| alert_settings.balance_change)
+            .map(|grouping| ChangeDigest::new(&network.name, network.chain_id, grouping.max_items));
+
+        // Process each result
+        for result in results {
+            match result {
+                Ok(mut balance_info) => {
+                    asset_prices.apply(&mut balance_info);
+                    log_balance_check(&balance_info);
+                    self.events.publish(MonitorEvent::BalanceFetched(balance_info.clone()));
+
+                    // Compare with previous balances
+                    let (mut changes, previous) = {
+                        let storage_read = storage.read().await;
+                        let previous = storage_read.get(&balance_info.network_name, &balance_info.alias).cloned();
+                        (compare_balances(&balance_info, &storage_read), previous)
+                    };
+                    let previous_block = previous.as_ref().and_then(|previous| previous.block_number);
+                    let now = chrono::Utc::now().timestamp() as u64;
+                    let maintenance = self.maintenance.suppressing(&network.name, &balance_info.alias, now).await;
+
+                    // Skip small moves, like daily rebases of tokens without shares
+                    for token in self.monitor.tokens() {
+                        let has_shares = balance_info.token_balances.iter()
+                            .any(|t| t.alias == token.alias && t.shares.is_some());
+                        if let Some(percent) = token.min_change_percent(has_shares) {
+                            changes.ignore_changes_below(&token.alias, percent);
+                        }
+                    }
+
+                    // Log only if there are changes
+                    if changes.has_changes() {
+                        if let (Some(from), Some(to)) = (previous_block, balance_info.block_number) {
+                            self.attribute_transactions(&mut changes, from + 1, to).await;
+                            self.check_flagged(&changes).await;
+                            self.check_destinations(&changes).await;
+                        }
+                        log_balance_changes(&changes);
+                        self.events.publish(MonitorEvent::change_detected(&changes));
+
+                        // Send alert to all channels if balance_change alerts are enabled
+                        if let Some(ref window) = maintenance {
+                            info!("🛠️ Change alert for {} suppressed by {}", balance_info.alias, window);
+                        } else if let Some(ref mut digest) = digest {
+                            digest.push(changes);
+                        } else if alert_settings.balance_change {
+                            if let Err(e) = notifiers.send_change_alert(&changes).await {
+                                warn!("Failed to send alert: {}", e);
+                            }
+                        }
+                    }
+
+                    // Drained balances alert right away, regardless of throttling and maintenance
+                    if let (true, Some(previous)) = (self.drained.enabled, &previous) {
+                        let drained = drained_assets(&balance_info, previous, self.drained.dust_percent);
+                        if !drained.is_empty() {
+                            send_drained_alert(notifiers, self.escalator.as_deref(), &balance_info, &drained).await;
+                        }
+                    }
+
+                    let mut native_tiers = self.address_tiers.get(&balance_info.alias).cloned().unwrap_or_default();
+                    if let Some(&threshold) = effective_thresholds.get(&balance_info.alias) {
+                        native_tiers.push(ThresholdTier::below(threshold));
+                    }
+
+                    // Forecast when each asset reaches its next threshold at the recent spend rate
+                    let runways = match &self.history {
+                        Some(history) => {
+                            self.forecasts.forecast(&*history.read().await, &balance_info, &native_tiers, &token_tiers, now)
+                        }
+                        None => HashMap::new(),
+                    };
+
+                    // Check for low balance alerts if enabled
+                    if alert_settings.low_balance {
+                        let mut alerts = self.low_balance_tracker
+                            .check_tiers(&balance_info, &native_tiers, &token_tiers)
+                            .await;
+                        for alert in &mut alerts {
+                            alert.runway = runways.get(&alert.asset).copied();
+                            alert.top_up = self.top_ups.suggest(&balance_info, &alert.asset, alert.is_native);
+                        }
+                        for alert in &alerts {
+                            send_low_balance_alert(notifiers, self.escalator.as_deref(), alert).await;
+                        }
+                    }
+
+                    // Compare changes with the address's usual changes in history
+                    if let (false, None, Some(previous)) = (self.anomalies.is_empty(), &maintenance, &previous) {
+                        self.check_anomalies(&balance_info, previous, now).await;
+                    }
+
+                    // Evaluate alert rules against the previous check
+                    if !self.rules.is_empty() {
+                        let mut rule_alerts = self.rules.evaluate_with_runways(&balance_info, previous.as_ref(), &runways, now);
+                        if let (true, Some(history)) = (self.rules.has_budgets(), &self.history) {
+                            rule_alerts.extend(self.rules.evaluate_budgets(&*history.read().await, &balance_info, now));
+                        }
+                        for rule_alert in rule_alerts {
+                            if let Some(ref window) = maintenance {
+                                info!("🛠️ {} rule alert for {} suppressed by {}", rule_alert.rule, balance_info.alias, window);
+                                continue;
+                            }
+                            let result = match (&self.escalator, rule_alert.severity) {
+                                (Some(escalator), AlertSeverity::Critical) => {
+                                    notifiers.send_escalated_alert_to(escalator, &rule_alert.alert, &rule_alert.channels, now).await
+                                }
+                                _ => notifiers.send_alert_to(&rule_alert.alert, &rule_alert.channels).await,
+                            };
+                            if let Err(e) = result {
+                                warn!("Failed to send {} rule alert: {}", rule_alert.rule, e);
+                            }
+                        }
+                    }
+
+                    // Store balance for later
+                    all_balances.push(balance_info.clone());
+
+                    // Update storage with new balance
+                    let last_changed = {
+                        let mut storage_write = storage.write().await;
+                        storage_write.update_at(&balance_info, now);
+                        storage_write.last_changed(&balance_info.network_name, &balance_info.alias)
+                    };
+                    if let (false, Some(last_changed)) = (self.inactivity.is_empty(), last_changed) {
+                        self.check_inactivity(&balance_info, last_changed, now).await;
+                    }
+                    if !self.corridors.is_empty() {
+                        self.check_corridor(&balance_info).await;
+                    }
+
+                    // Append to balance history
+                    if let Some(ref history) = self.history {
+                        let now = chrono::Utc::now().timestamp() as u64;
+                        if let Err(e) = history.write().await.record(&balance_info, now) {
+                            warn!("Failed to record balance history: {}", e);
+                        }
+                    }
+                }
+                Err(e) => {
+                    error!("Error checking balance on {}: {}", network.name, e);
+                    self.events.publish(MonitorEvent::RpcError { network: network.name.clone(), error: e.to_string() });
+                    last_error = Some(e.to_string());
+                    failed += 1;
+                }
+            }
+        }
+
+        if !self.groups.is_empty() {
+            self.check_groups(&addresses, &all_balances).await;
+        }
+        if self.rules.has_aggregates() {
+            self.check_rule_totals(&addresses, &all_balances).await;
+        }
+        if self.entities.has_thresholds() {
+            self.check_entities().await;
+        }
+        if !self.custom_checks.is_empty() {
+            self.run_custom_checks(&all_balances).await;
+        }
+
+        // Send changes grouped during the cycle as one message
+        if let Some(digest) = digest.filter(|digest| !digest.is_empty()) {
+            if let Err(e) = notifiers.send_change_digest(&digest).await {
+                warn!("Failed to send change digest: {}", e);
+            }
+        }
+
+        publish_check(&self.health, &self.events, MonitorEvent::CheckCompleted {
+            network: network.name.clone(),
+            chain_id: Some(network.chain_id),
+            addresses: all_balances.len(),
+            errors: failed,
+            error: last_error,
+        })
+        .await;
+        self.check_rpc_nodes().await;
+
+        // Update notifiers with latest balances
+        notifiers.update_balances(&all_balances).await;
+
+        // Persist balances after each check
+        {
+            let storage_read = storage.read().await;
+            if let Err(e) = self.backend.save_balances(&storage_read).await {
+                warn!("Failed to save storage: {}", e);
+            }
+        }
+    }
+}
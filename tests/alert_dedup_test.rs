@@ -0,0 +1,216 @@
+use async_trait::async_trait;
+use eyre::{eyre, Result};
+use serde_json::json;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use Oxwatcher::logger::BalanceChangeSummary;
+use Oxwatcher::{
+    test_change_alert, AlertDedup, AlertDedupConfig, BalanceReport, DedupKey, EscalationConfig, Escalator,
+    FileBackend, LowBalanceAlert, MonitorAlert, Notifier, NotifierSet, StateBackend, HEARTBEAT_KIND,
+};
+
+/// Channel that counts deliveries, or fails them while `down` is set
+struct CountingNotifier {
+    name: &'static str,
+    sent: Arc<AtomicUsize>,
+    down: Arc<AtomicBool>,
+}
+
+impl CountingNotifier {
+    fn deliver(&self) -> Result<()> {
+        if self.down.load(Ordering::SeqCst) {
+            return Err(eyre!("{} returned 503", self.name));
+        }
+        self.sent.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Notifier for CountingNotifier {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    async fn send_change_alert(&self, _changes: &BalanceChangeSummary) -> Result<()> {
+        self.deliver()
+    }
+
+    async fn send_low_balance_alert(&self, _alert: &LowBalanceAlert) -> Result<()> {
+        self.deliver()
+    }
+
+    async fn send_alert(&self, _alert: &MonitorAlert) -> Result<()> {
+        self.deliver()
+    }
+
+    async fn send_report(&self, _report: &BalanceReport) -> Result<()> {
+        self.deliver()
+    }
+}
+
+fn temp_backend(name: &str) -> Arc<dyn StateBackend> {
+    let data_dir = std::env::temp_dir().join(format!("oxwatcher-dedup-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&data_dir);
+    std::fs::create_dir_all(&data_dir).unwrap();
+    Arc::new(FileBackend::new(data_dir))
+}
+
+/// Notifier set of one instance sharing `backend`, counting into `sent`
+fn instance(backend: &Arc<dyn StateBackend>, sent: &Arc<AtomicUsize>) -> NotifierSet {
+    let mut notifiers = NotifierSet::new();
+    notifiers.push(Box::new(CountingNotifier { name: "slack", sent: Arc::clone(sent), down: Arc::default() }));
+    notifiers.set_dedup(AlertDedup::new(Arc::clone(backend), &AlertDedupConfig { window_secs: 300 }));
+    notifiers
+}
+
+#[tokio::test]
+async fn test_file_backend_claims_alert_once_per_window() {
+    let backend = temp_backend("claims");
+    let window = Duration::from_secs(300);
+
+    assert!(backend.claim_alert("Ethereum", "Treasury", "abc", window).await.unwrap());
+    assert!(!backend.claim_alert("Ethereum", "Treasury", "abc", window).await.unwrap());
+    // Any other part of the key is another alert
+    assert!(backend.claim_alert("Ethereum", "Treasury", "def", window).await.unwrap());
+    assert!(backend.claim_alert("Arbitrum", "Treasury", "abc", window).await.unwrap());
+
+    // Claims expire with the window
+    assert!(backend.claim_alert("Base", "Hot wallet", "abc", Duration::ZERO).await.unwrap());
+    assert!(backend.claim_alert("Base", "Hot wallet", "abc", Duration::ZERO).await.unwrap());
+}
+
+#[tokio::test]
+async fn test_two_instances_send_each_alert_once() {
+    let backend = temp_backend("instances");
+    let sent = Arc::new(AtomicUsize::new(0));
+    let first = instance(&backend, &sent);
+    let second = instance(&backend, &sent);
+
+    // Both instances see the same change; the second one priced it differently
+    let changes = test_change_alert("Ethereum", 1);
+    let mut repriced = changes.clone();
+    if let Some(change) = repriced.eth_change.as_mut() {
+        change.usd_value = Some(123.0);
+    }
+    first.send_change_alert(&changes).await.unwrap();
+    second.send_change_alert(&repriced).await.unwrap();
+    assert_eq!(sent.load(Ordering::SeqCst), 1);
+
+    let alert = MonitorAlert {
+        kind: "gas_price".to_string(),
+        network_name: "Ethereum".to_string(),
+        chain_id: Some(1),
+        title: "Gas above 50 gwei".to_string(),
+        lines: vec![],
+        data: json!({}),
+        dedup_id: None,
+    };
+    first.send_alert(&alert).await.unwrap();
+    second.send_alert(&alert).await.unwrap();
+    assert_eq!(sent.load(Ordering::SeqCst), 2);
+
+    // Every instance sends its own heartbeat
+    let heartbeat = MonitorAlert { kind: HEARTBEAT_KIND.to_string(), ..alert };
+    assert_eq!(DedupKey::alert(&heartbeat), None);
+    first.send_alert(&heartbeat).await.unwrap();
+    second.send_alert(&heartbeat).await.unwrap();
+    assert_eq!(sent.load(Ordering::SeqCst), 4);
+}
+
+#[tokio::test]
+async fn test_only_the_instance_sending_a_critical_alert_tracks_it() {
+    let backend = temp_backend("escalation");
+    let sent = Arc::new(AtomicUsize::new(0));
+    let config = EscalationConfig { ack_timeout_mins: 15, resends: 1, telegram_chats: vec![], channels: vec![], pagerduty: None };
+    let (first, second) = (instance(&backend, &sent), instance(&backend, &sent));
+    let first_escalator = Escalator::new(&config, Arc::clone(&backend)).await;
+    let second_escalator = Escalator::new(&config, Arc::clone(&backend)).await;
+
+    let alert = MonitorAlert {
+        kind: "drained".to_string(),
+        network_name: "Ethereum".to_string(),
+        chain_id: Some(1),
+        title: "🚨 Treasury drained".to_string(),
+        lines: vec![],
+        data: json!({}),
+        dedup_id: None,
+    };
+    first.send_escalated_alert_to(&first_escalator, &alert, &[], 0).await.unwrap();
+    second.send_escalated_alert_to(&second_escalator, &alert, &[], 0).await.unwrap();
+    assert_eq!(sent.load(Ordering::SeqCst), 1);
+
+    // The other instance has nothing of its own to re-send
+    let tracked = backend.load_escalations().await.unwrap().alerts;
+    assert_eq!(tracked.len(), 1);
+    assert_eq!(first_escalator.due(15 * 60).await.len(), 1);
+    assert!(second_escalator.due(15 * 60).await.is_empty());
+}
+
+#[tokio::test]
+async fn test_failed_delivery_releases_its_channel_claim() {
+    let backend = temp_backend("release");
+    let slack_sent = Arc::new(AtomicUsize::new(0));
+    let discord_sent = Arc::new(AtomicUsize::new(0));
+    let slack_down = Arc::new(AtomicBool::new(true));
+    let dedup = AlertDedup::new(Arc::clone(&backend), &AlertDedupConfig { window_secs: 300 });
+    let instance = || {
+        let mut notifiers = NotifierSet::new();
+        notifiers.push(Box::new(CountingNotifier { name: "slack", sent: Arc::clone(&slack_sent), down: Arc::clone(&slack_down) }));
+        notifiers.push(Box::new(CountingNotifier { name: "discord", sent: Arc::clone(&discord_sent), down: Arc::default() }));
+        notifiers.set_dedup(dedup.clone());
+        notifiers
+    };
+    let (first, second) = (instance(), instance());
+
+    // Slack is down for the first instance; Discord still gets the alert once
+    let changes = test_change_alert("Ethereum", 1);
+    first.send_change_alert(&changes).await.unwrap();
+    assert_eq!((slack_sent.load(Ordering::SeqCst), discord_sent.load(Ordering::SeqCst)), (0, 1));
+
+    // The failed Slack claim was released, so the other instance sends it
+    slack_down.store(false, Ordering::SeqCst);
+    second.send_change_alert(&changes).await.unwrap();
+    assert_eq!((slack_sent.load(Ordering::SeqCst), discord_sent.load(Ordering::SeqCst)), (1, 1));
+
+    first.send_change_alert(&changes).await.unwrap();
+    assert_eq!((slack_sent.load(Ordering::SeqCst), discord_sent.load(Ordering::SeqCst)), (1, 1));
+
+    backend.release_alert("Ethereum", "Treasury", "missing").await.unwrap();
+}
+
+#[test]
+fn test_dedup_key_covers_balances() {
+    let changes = test_change_alert("Ethereum", 1);
+    let key = DedupKey::change(&changes);
+    assert_eq!(key.network, "Ethereum");
+    assert_eq!(key.subject, changes.alias);
+
+    let mut other = changes.clone();
+    if let Some(change) = other.eth_change.as_mut() {
+        change.new_balance += alloy::primitives::U256::from(1);
+    }
+    assert_ne!(DedupKey::change(&other).hash, key.hash);
+}
+
+#[test]
+fn test_dedup_key_of_alert_ignores_live_values() {
+    let stalled = |minutes: u64| MonitorAlert {
+        kind: "rollup".to_string(),
+        network_name: "Base".to_string(),
+        chain_id: Some(8453),
+        title: "🧱 Sequencer stalled on Base".to_string(),
+        lines: vec![format!("No new block for {} min (limit: 5 min)", minutes), "Latest block: 100".to_string()],
+        data: json!({ "stalled": true, "block": 100, "block_age_secs": minutes * 60 }),
+        dedup_id: None,
+    };
+    // Instances that noticed the stall a minute apart send the same alert
+    assert_eq!(DedupKey::alert(&stalled(6)), DedupKey::alert(&stalled(7)));
+
+    // Another transition of the same subject is another alert
+    let resumed = MonitorAlert { title: "✅ Sequencer producing blocks again on Base".to_string(), ..stalled(6) };
+    assert_ne!(DedupKey::alert(&resumed), DedupKey::alert(&stalled(6)));
+    let changed = |dedup_id: &str| MonitorAlert { dedup_id: Some(dedup_id.to_string()), ..stalled(6) };
+    assert_ne!(DedupKey::alert(&changed("1->2")), DedupKey::alert(&changed("2->3")));
+}
@@ -0,0 +1,270 @@
+use alloy::primitives::U256;
+use Oxwatcher::{
+    AlertSeverity, BalanceInfo, Condition, NetworkConfig, Observation, RuleConfig, RuleEngine,
+    TokenBalance,
+};
+
+mod common;
+
+const DAY: u64 = 24 * 3600;
+
+fn balance_info(eth: &str) -> BalanceInfo {
+    common::balance_info("treasury", eth)
+}
+
+fn rule(name: &str, condition: &str) -> RuleConfig {
+    RuleConfig {
+        name: name.to_string(),
+        condition: condition.to_string(),
+        severity: AlertSeverity::Critical,
+        networks: vec![],
+        addresses: vec![],
+        tags: vec![],
+        aggregate: false,
+        assets: vec![],
+        channels: vec![],
+        budget: None,
+    }
+}
+
+fn observation(balance: f64, previous: Option<f64>, unchanged_secs: u64) -> Observation {
+    Observation { balance, previous, unchanged_secs, ..Default::default() }
+}
+
+#[test]
+fn test_condition_parses_and_or_parentheses_and_units() {
+    let condition = Condition::parse("decrease > 1 ETH or (balance < 5 && no_change_for >= 7d)").unwrap();
+    assert_eq!(condition.to_string(), "decrease > 1 or (balance < 5 and no_change_for >= 1w)");
+
+    assert!(condition.matches(&observation(8.0, Some(10.0), 0)));
+    assert!(!condition.matches(&observation(4.0, Some(4.5), 0)));
+    assert!(condition.matches(&observation(4.0, Some(4.0), 7 * DAY)));
+    assert!(!condition.matches(&observation(6.0, Some(6.0), 7 * DAY)));
+
+    let percent = Condition::parse("decrease_percent > 50%").unwrap();
+    assert!(percent.matches(&observation(4.0, Some(10.0), 0)));
+    assert!(!percent.matches(&observation(6.0, Some(10.0), 0)));
+    assert!(!percent.matches(&observation(4.0, None, 0)));
+}
+
+#[test]
+fn test_condition_rejects_invalid_input() {
+    for input in [
+        "",
+        "balance",
+        "balance >",
+        "volume > 1",
+        "balance > 1 and",
+        "(balance > 1",
+        "balance > 5%",
+        "no_change_for > 7",
+        "outflow > 10",
+        "outflow(1) > 10",
+        "outflow(1h > 10",
+    ] {
+        assert!(Condition::parse(input).is_err(), "{} should not parse", input);
+    }
+}
+
+#[test]
+fn test_rule_fires_when_condition_starts_to_hold_and_on_each_change() {
+    let engine = RuleEngine::new(&[rule("Treasury drained", "balance < 1")]).unwrap();
+
+    assert!(engine.evaluate(&balance_info("2.0"), None, 0).is_empty());
+
+    let alerts = engine.evaluate(&balance_info("0.5"), Some(&balance_info("2.0")), 60);
+    assert_eq!(alerts.len(), 1);
+    assert_eq!(alerts[0].alert.kind, "rule");
+    assert_eq!(alerts[0].alert.title, "🚨 Treasury drained: treasury");
+    assert!(alerts[0].alert.lines.contains(&"Balance: 2.0 → 0.5 ETH".to_string()));
+
+    // Still matching without a change: no repeat
+    assert!(engine.evaluate(&balance_info("0.5"), Some(&balance_info("0.5")), 120).is_empty());
+    // Still matching after another change: alert again
+    assert_eq!(engine.evaluate(&balance_info("0.2"), Some(&balance_info("0.5")), 180).len(), 1);
+
+    // Recovery resets the rule
+    assert!(engine.evaluate(&balance_info("3.0"), Some(&balance_info("0.2")), 240).is_empty());
+    assert_eq!(engine.evaluate(&balance_info("0.1"), Some(&balance_info("3.0")), 300).len(), 1);
+}
+
+#[test]
+fn test_no_change_for_counts_from_last_balance_change() {
+    let engine = RuleEngine::new(&[rule("Dormant", "no_change_for > 1d")]).unwrap();
+
+    assert!(engine.evaluate(&balance_info("1.0"), None, 0).is_empty());
+    assert!(engine.evaluate(&balance_info("1.0"), Some(&balance_info("1.0")), DAY).is_empty());
+    assert_eq!(engine.evaluate(&balance_info("1.0"), Some(&balance_info("1.0")), DAY + 1).len(), 1);
+    assert!(engine.evaluate(&balance_info("1.0"), Some(&balance_info("1.0")), 2 * DAY).is_empty());
+
+    assert!(engine.evaluate(&balance_info("2.0"), Some(&balance_info("1.0")), 3 * DAY).is_empty());
+    assert_eq!(engine.evaluate(&balance_info("2.0"), Some(&balance_info("2.0")), 4 * DAY + 1).len(), 1);
+}
+
+#[test]
+fn test_rule_scope_selects_addresses_and_assets() {
+    let mut usdc_rule = rule("USDC outflow", "decrease > 100");
+    usdc_rule.assets = vec!["USDC".to_string()];
+    usdc_rule.channels = vec!["slack".to_string()];
+    let mut other_address = rule("Hot wallet", "balance < 100");
+    other_address.addresses = vec!["hot".to_string()];
+    let engine = RuleEngine::new(&[usdc_rule, other_address]).unwrap();
+
+    let with_usdc = |eth: &str, usdc: &str| {
+        let mut info = balance_info(eth);
+        info.token_balances.push(common::token_balance("USDC", usdc, 18));
+        info
+    };
+
+    let alerts = engine.evaluate(&with_usdc("1.0", "50"), Some(&with_usdc("500", "500")), 0);
+    assert_eq!(alerts.len(), 1);
+    assert_eq!(alerts[0].rule, "USDC outflow");
+    assert_eq!(alerts[0].channels, vec!["slack".to_string()]);
+    assert_eq!(alerts[0].alert.data["asset"], "USDC");
+}
+
+#[test]
+fn test_outflow_sums_decreases_within_window() {
+    let condition = Condition::parse("outflow(1h) > 10 ETH").unwrap();
+    assert_eq!(condition.to_string(), "outflow(1h) > 10");
+    assert_eq!(condition.max_window(), 3600);
+
+    let drained = |recent_changes: Vec<(u64, f64)>| Observation { recent_changes, ..Default::default() };
+    assert!(condition.matches(&drained(vec![(3000, -4.0), (1200, -4.0), (0, -3.0)])));
+    // Inflows don't offset outflows
+    assert!(condition.matches(&drained(vec![(3000, -4.0), (1200, 20.0), (600, -4.0), (0, -3.0)])));
+    // Changes older than the window don't count
+    assert!(!condition.matches(&drained(vec![(3600, -4.0), (1200, -4.0), (0, -3.0)])));
+}
+
+#[test]
+fn test_slow_drain_fires_rule_across_checks() {
+    let engine = RuleEngine::new(&[rule("Slow drain", "outflow(1h) > 10")]).unwrap();
+
+    let mut previous = balance_info("100");
+    let mut alerts = Vec::new();
+    for (i, eth) in ["97", "94", "91", "88"].iter().enumerate() {
+        let current = balance_info(eth);
+        alerts = engine.evaluate(&current, Some(&previous), i as u64 * 600);
+        previous = current;
+        if i < 3 {
+            assert!(alerts.is_empty(), "only {} ETH out after check {}", 3 * (i + 1), i);
+        }
+    }
+    assert_eq!(alerts.len(), 1);
+    assert_eq!(alerts[0].rule, "Slow drain");
+
+    // The same decreases spread over more than an hour don't match
+    let engine = RuleEngine::new(&[rule("Slow drain", "outflow(1h) > 10")]).unwrap();
+    let mut previous = balance_info("100");
+    for (i, eth) in ["97", "94", "91", "88"].iter().enumerate() {
+        let current = balance_info(eth);
+        assert!(engine.evaluate(&current, Some(&previous), i as u64 * 1800).is_empty());
+        previous = current;
+    }
+}
+
+#[test]
+fn test_window_metrics_survive_clock_jumps_and_huge_windows() {
+    let engine = RuleEngine::new(&[rule("Drain", "outflow(1000000000000w) > 10")]).unwrap();
+
+    // The clock going back between checks must not underflow the change ages
+    assert!(engine.evaluate(&balance_info("95"), Some(&balance_info("100")), 10_000).is_empty());
+    let alerts = engine.evaluate(&balance_info("85"), Some(&balance_info("95")), 5_000);
+    assert_eq!(alerts.len(), 1);
+    assert_eq!(alerts[0].rule, "Drain");
+}
+
+#[test]
+fn test_invalid_rule_condition_is_rejected() {
+    let error = RuleEngine::new(&[rule("Broken", "balance >> 1")]).err().unwrap();
+    assert!(error.to_string().contains("rule 'Broken'"));
+}
+
+#[test]
+fn test_balance_rules_compare_raw_amounts_with_token_decimals() {
+    let token = |alias: &str, balance: U256, formatted: &str, decimals: u8| {
+        let mut info = balance_info("1.0");
+        info.token_balances.push(TokenBalance {
+            alias: alias.to_string(),
+            balance,
+            formatted: formatted.to_string(),
+            decimals,
+            usd_value: None,
+            shares: None,
+        });
+        info
+    };
+    let mut above = rule("Reserve above 1B", "balance > 1000000000");
+    above.assets = vec!["RSV".to_string()];
+    let mut below = rule("Dust below 0.3", "balance < 0.3");
+    below.assets = vec!["DUST".to_string()];
+    let engine = RuleEngine::new(&[above, below]).unwrap();
+
+    // One base unit over 1e9 tokens parses to exactly 1e9 as f64
+    let reserve = U256::from(10).pow(U256::from(27)) + U256::from(1);
+    let alerts = engine.evaluate(&token("RSV", reserve, "1000000000.000000000000000001", 18), None, 0);
+    assert_eq!(alerts.len(), 1);
+    assert_eq!(alerts[0].rule, "Reserve above 1B");
+
+    // 0.29999999999999999999 with 20 decimals parses to 0.3 as f64
+    let dust = U256::from(3) * U256::from(10).pow(U256::from(19)) - U256::from(1);
+    let alerts = engine.evaluate(&token("DUST", dust, "0.29999999999999999999", 20), None, 0);
+    assert_eq!(alerts.len(), 1);
+    assert_eq!(alerts[0].rule, "Dust below 0.3");
+
+    // Changes are compared in base units too: 1e9 tokens and one base unit is more than 1e9
+    let mut outflow = rule("Outflow over 1B", "decrease > 1000000000");
+    outflow.assets = vec!["RSV".to_string()];
+    let engine = RuleEngine::new(&[outflow]).unwrap();
+    let before = token("RSV", reserve + reserve, "2000000000.000000000000000002", 18);
+    let exact = token("RSV", reserve - U256::from(1), "1000000000", 18);
+    assert_eq!(engine.evaluate(&exact, Some(&before), 0).len(), 1);
+}
+
+fn hot_wallet(alias: &str, eth: &str) -> BalanceInfo {
+    common::balance_info(alias, eth)
+}
+
+#[test]
+fn test_aggregate_rule_alerts_on_the_total_of_tagged_addresses() {
+    let networks: Vec<NetworkConfig> = serde_yaml::from_str(
+        r#"
+- name: Ethereum
+  chain_id: 1
+  rpc_nodes: ["https://eth.llamarpc.com"]
+  addresses:
+    - alias: hot-1
+      address: "0x1111111111111111111111111111111111111111"
+      tags: [hot]
+    - alias: hot-2
+      address: "0x2222222222222222222222222222222222222222"
+      tags: [hot]
+    - alias: treasury
+      address: "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045"
+"#,
+    )
+    .unwrap();
+    let hot_total = RuleConfig { tags: vec!["hot".to_string()], aggregate: true, ..rule("Hot wallets low", "balance < 5") };
+    let engine = RuleEngine::new(&[hot_total]).unwrap().with_address_tags(&networks);
+    let addresses = &networks[0].addresses;
+
+    // Aggregate rules aren't evaluated per address
+    assert!(engine.evaluate(&hot_wallet("hot-1", "1"), None, 0).is_empty());
+
+    // 3 + 3 ETH is above the limit, even though each wallet holds less than 5
+    let balances = [hot_wallet("hot-1", "3"), hot_wallet("hot-2", "3"), hot_wallet("treasury", "100")];
+    assert!(engine.evaluate_totals("Ethereum", addresses, &balances, 0).is_empty());
+
+    let balances = [hot_wallet("hot-1", "3"), hot_wallet("hot-2", "1.5"), hot_wallet("treasury", "100")];
+    let alerts = engine.evaluate_totals("Ethereum", addresses, &balances, 60);
+    assert_eq!(alerts.len(), 1);
+    assert_eq!(alerts[0].alert.data["addresses"], serde_json::json!(["hot-1", "hot-2"]));
+    assert_eq!(alerts[0].alert.data["balance"], "4.500000000000000000");
+    assert_eq!(alerts[0].alert.data["previous_balance"], "6.000000000000000000");
+    assert!(engine.evaluate_totals("Ethereum", addresses, &balances, 120).is_empty());
+
+    // A cycle missing one of the addresses is skipped
+    assert!(engine.evaluate_totals("Ethereum", addresses, &balances[..1], 180).is_empty());
+    assert!(engine.evaluate_totals("Base", addresses, &balances, 180).is_empty());
+}
@@ -0,0 +1,83 @@
+use std::time::Duration;
+use Oxwatcher::{AddressConfig, AnomalyConfig, AnomalyDetector, BalanceInfo, ChangeStats, HistoryStore};
+
+mod common;
+
+const DAY: u64 = 24 * 3600;
+
+fn balance_info(alias: &str, eth: u64) -> BalanceInfo {
+    common::balance_info(alias, &eth.to_string())
+}
+
+/// History of a distributor paying out 9-11 ETH a day
+fn payout_history(name: &str) -> HistoryStore {
+    let path = std::env::temp_dir().join(format!("oxwatcher-anomaly-{}-{}.jsonl", name, std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let mut history = HistoryStore::load_from_file(&path, Duration::from_secs(60 * DAY), Duration::from_secs(DAY)).unwrap();
+
+    let mut balance = 1_000;
+    for day in 0..20 {
+        history.record(&balance_info("distributor", balance), day * DAY).unwrap();
+        balance -= 9 + day % 3;
+    }
+    history
+}
+
+fn detector(anomaly: AnomalyConfig) -> AnomalyDetector {
+    let mut distributor = AddressConfig::new("distributor", common::ACCOUNT);
+    distributor.anomaly = Some(anomaly);
+    AnomalyDetector::new(&[distributor, AddressConfig::new("treasury", common::ACCOUNT)])
+}
+
+#[test]
+fn test_change_stats_skip_unchanged_samples() {
+    let stats = ChangeStats::from_balances(&[100.0, 90.0, 90.0, 80.0, 74.0]).unwrap();
+    assert_eq!(stats.samples, 3);
+    assert!((stats.mean + 26.0 / 3.0).abs() < 1e-9);
+
+    assert!(ChangeStats::from_balances(&[5.0, 5.0]).is_none());
+
+    // Fixed payouts: deviation is at least 10% of the usual change
+    let fixed = ChangeStats::from_balances(&[30.0, 20.0, 10.0]).unwrap();
+    assert_eq!(fixed.stddev, 0.0);
+    assert!((fixed.z_score(-11.0) - 1.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_detects_changes_far_from_usual_payouts() {
+    let history = payout_history("detect");
+    let detector = detector(AnomalyConfig::default());
+    let previous = balance_info("distributor", 800);
+    let now = 20 * DAY;
+
+    assert!(detector.check(&history, &balance_info("distributor", 790), &previous, now).is_empty());
+
+    let anomalies = detector.check(&history, &balance_info("distributor", 500), &previous, now);
+    assert_eq!(anomalies.len(), 1);
+    assert_eq!(anomalies[0].asset, "ETH");
+    assert_eq!(anomalies[0].change, -300.0);
+    assert_eq!(anomalies[0].stats.samples, 19);
+    assert!(anomalies[0].z_score > 3.0);
+
+    // An unusual increase counts as well
+    assert_eq!(detector.check(&history, &balance_info("distributor", 900), &previous, now).len(), 1);
+
+    // Addresses without an anomaly setting are not checked
+    assert!(detector.check(&history, &balance_info("treasury", 0), &balance_info("treasury", 800), now).is_empty());
+}
+
+#[test]
+fn test_sensitivity_and_min_samples() {
+    let history = payout_history("settings");
+    let previous = balance_info("distributor", 800);
+    let current = balance_info("distributor", 788);
+    let now = 20 * DAY;
+
+    assert!(detector(AnomalyConfig::default()).check(&history, &current, &previous, now).is_empty());
+    let sensitive = AnomalyConfig { sensitivity: 1.0, ..Default::default() };
+    assert_eq!(detector(sensitive).check(&history, &current, &previous, now).len(), 1);
+
+    // Not enough history to judge yet
+    let sensitive_short = AnomalyConfig { sensitivity: 1.0, lookback_days: Some(5), ..Default::default() };
+    assert!(detector(sensitive_short).check(&history, &current, &previous, now).is_empty());
+}
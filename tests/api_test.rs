@@ -0,0 +1,239 @@
+use alloy::primitives::U256;
+use axum::body::{to_bytes, Body};
+use axum::http::{Request, StatusCode};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+use tower::ServiceExt;
+use Oxwatcher::{
+    router, AddressRegistry, ApiState, AuditEntry, AuditLog, BalanceInfo, BalanceStorage, FileBackend,
+    GasConfig, GasPrice, GasTracker, HealthTracker, HistoryStore, LowBalanceTracker, NetworkConfig,
+    StateBackend, ThrottleConfig,
+};
+
+mod common;
+
+
+fn balance_info(wei: u64) -> BalanceInfo {
+    BalanceInfo { eth_balance: U256::from(wei), eth_formatted: wei.to_string(), ..common::balance_info("treasury", "0") }
+}
+
+async fn get(state: ApiState, uri: &str) -> (StatusCode, Value) {
+    let response = router(state)
+        .oneshot(Request::get(uri).body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    let status = response.status();
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    (status, serde_json::from_slice(&body).unwrap())
+}
+
+#[tokio::test]
+async fn test_api_balances() {
+    let mut storage = BalanceStorage::new();
+    storage.update(&balance_info(100));
+    let state = ApiState::new(Arc::new(RwLock::new(storage)));
+
+    let (status, body) = get(state.clone(), "/balances").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body.as_array().unwrap().len(), 1);
+
+    let (status, body) = get(state.clone(), "/balances/Ethereum/treasury").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["eth_balance"], "100");
+
+    let (status, _) = get(state.clone(), "/balances/Ethereum/unknown").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    // /changes needs history
+    let (status, _) = get(state, "/changes?since=0").await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+}
+
+#[tokio::test]
+async fn test_api_balances_by_tag() {
+    let networks: Vec<NetworkConfig> = serde_yaml::from_str(
+        r#"
+- name: Ethereum
+  chain_id: 1
+  rpc_nodes: ["https://eth.llamarpc.com"]
+  addresses:
+    - alias: treasury
+      address: "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045"
+      tags: [cold]
+    - alias: relayer
+      address: "0x1111111111111111111111111111111111111111"
+      tags: [hot]
+"#,
+    )
+    .unwrap();
+    let dir = std::env::temp_dir().join(format!("oxwatcher-api-tags-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let registry = AddressRegistry::new(&networks, Arc::new(FileBackend::new(&dir))).await.unwrap();
+
+    let mut storage = BalanceStorage::new();
+    storage.update(&balance_info(100));
+    storage.update(&BalanceInfo { alias: "relayer".to_string(), ..balance_info(5) });
+    let state = ApiState::new(Arc::new(RwLock::new(storage))).with_registry(Arc::new(registry));
+
+    let (status, body) = get(state.clone(), "/balances?tag=hot").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body.as_array().unwrap().len(), 1);
+    assert_eq!(body[0]["alias"], "relayer");
+
+    let (status, _) = get(state, "/balances?tag=unknown").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_api_changes_since() {
+    let path = std::env::temp_dir().join(format!("oxwatcher-api-{}.jsonl", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let mut history = HistoryStore::load_from_file(&path, Duration::from_secs(u64::MAX / 2), Duration::from_secs(3600))
+        .unwrap();
+    history.record(&balance_info(100), 1_000).unwrap();
+    history.record(&balance_info(250), 2_000).unwrap();
+
+    let mut storage = BalanceStorage::new();
+    storage.update(&balance_info(250));
+    let state = ApiState::new(Arc::new(RwLock::new(storage))).with_history(Arc::new(RwLock::new(history)));
+
+    let (status, body) = get(state.clone(), "/changes?since=1500").await;
+    assert_eq!(status, StatusCode::OK);
+    let changes = body["changes"].as_array().unwrap();
+    assert_eq!(changes.len(), 1);
+    assert_eq!(changes[0]["changes"][0]["old_balance"], "100");
+    assert_eq!(changes[0]["changes"][0]["direction"], "increase");
+
+    let (_, body) = get(state.clone(), "/changes?since=1970-01-01T00:40:00Z").await;
+    assert!(body["changes"].as_array().unwrap().is_empty());
+
+    let (status, _) = get(state, "/changes?since=yesterday").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_api_gas() {
+    let gas = Arc::new(GasTracker::new());
+    let price = GasPrice {
+        network_name: "Ethereum".to_string(),
+        chain_id: 1,
+        base_fee_wei: 12_500_000_000,
+        priority_fee_wei: Some(1_000_000_000),
+        blob_base_fee_wei: None,
+        blob_base_fee_24h: None,
+        updated_at: 1_000,
+    };
+    gas.record(price, &GasConfig::default()).await;
+
+    let state = ApiState::new(Arc::new(RwLock::new(BalanceStorage::new()))).with_gas(gas);
+    let (status, body) = get(state, "/gas").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body[0]["network_name"], "Ethereum");
+    assert_eq!(body[0]["base_fee_wei"], 12_500_000_000u64);
+}
+
+#[tokio::test]
+async fn test_api_audit() {
+    let dir = std::env::temp_dir().join(format!("oxwatcher-api-audit-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    let backend = Arc::new(FileBackend::new(&dir));
+    for (sent_at, channel, success) in [(1_000, "slack", true), (2_000, "telegram", false)] {
+        let entry = AuditEntry {
+            sent_at,
+            channel: channel.to_string(),
+            chat: None,
+            kind: "low_balance".to_string(),
+            network: Some("Ethereum".to_string()),
+            subject: "treasury ETH below 1".to_string(),
+            content_hash: "ab".repeat(32),
+            success,
+            error: (!success).then(|| "Forbidden".to_string()),
+        };
+        backend.append_audit(&entry).await.unwrap();
+    }
+
+    let storage = Arc::new(RwLock::new(BalanceStorage::new()));
+    let (status, _) = get(ApiState::new(Arc::clone(&storage)), "/audit").await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+
+    let state = ApiState::new(storage).with_audit(AuditLog::new(backend));
+    let (status, body) = get(state.clone(), "/audit").await;
+    assert_eq!(status, StatusCode::OK);
+    let entries = body.as_array().unwrap();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0]["channel"], "telegram");
+    assert_eq!(entries[0]["error"], "Forbidden");
+
+    let (_, body) = get(state.clone(), "/audit?channel=Slack&since=1970-01-01T00:00:00Z").await;
+    assert_eq!(body.as_array().unwrap().len(), 1);
+
+    let (_, body) = get(state.clone(), "/audit?until=1500&network=Base").await;
+    assert!(body.as_array().unwrap().is_empty());
+
+    let (status, _) = get(state, "/audit?since=yesterday").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_api_health_and_ready() {
+    let now = chrono::Utc::now().timestamp() as u64;
+    let health = Arc::new(HealthTracker::new());
+    health.register("Ethereum", Some(1), 60, 2, now).await;
+    health.register("Base", Some(8453), 60, 1, now - 600).await;
+
+    let state = ApiState::new(Arc::new(RwLock::new(BalanceStorage::new()))).with_health(Arc::clone(&health), 3);
+
+    // Base has not had a successful check for 10 intervals
+    let (status, body) = get(state.clone(), "/health").await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(body["status"], "unhealthy");
+    assert_eq!(body["networks"][0]["network_name"], "Base");
+    assert_eq!(body["networks"][0]["healthy"], false);
+    assert_eq!(body["networks"][1]["healthy"], true);
+
+    let (status, body) = get(state.clone(), "/ready").await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(body["pending"].as_array().unwrap().len(), 2);
+
+    health.record_check("Base", true, None, now).await;
+    health.record_check("Ethereum", false, Some("connection refused".to_string()), now).await;
+    health.record_check("Ethereum", true, Some("token call reverted".to_string()), now).await;
+
+    let (status, body) = get(state.clone(), "/health").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["networks"][1]["last_error"], "token call reverted");
+    assert_eq!(body["networks"][1]["consecutive_failures"], 0);
+
+    let (status, _) = get(state, "/ready").await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_api_throttles() {
+    let state = ApiState::new(Arc::new(RwLock::new(BalanceStorage::new())));
+    let (status, _) = get(state.clone(), "/throttles").await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+
+    let dir = std::env::temp_dir().join(format!("oxwatcher-api-throttles-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let schedule = ThrottleConfig { intervals_mins: vec![5], repeat_mins: Some(60) };
+    let tracker = LowBalanceTracker::new(Arc::new(FileBackend::new(dir.to_string_lossy().to_string())))
+        .await
+        .with_schedule(schedule);
+    tracker.check(&balance_info(100), Some(500.0), &HashMap::new()).await;
+
+    let (status, body) = get(state.with_low_balance(tracker), "/throttles").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["schedule"]["intervals_mins"][0], 5);
+    assert_eq!(body["schedule"]["repeat_mins"], 60);
+    let throttles = body["throttles"].as_array().unwrap();
+    assert_eq!(throttles.len(), 1);
+    assert_eq!(throttles[0]["key"], "Ethereum:treasury");
+    assert_eq!(throttles[0]["alert_count"], 1);
+    assert_eq!(throttles[0]["next_alert_at"].as_u64().unwrap(), throttles[0]["last_sent"].as_u64().unwrap() + 300);
+}
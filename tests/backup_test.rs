@@ -0,0 +1,153 @@
+use std::path::PathBuf;
+use Oxwatcher::storage::AlertState;
+use Oxwatcher::{
+    prune_backups, sign_v4, AlertStateStorage, AuditEntry, BalanceStorage, ChatRegistration, ChatStorage, FileBackend,
+    StateBackend, StateBackup,
+};
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("oxwatcher-backup-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn audit_entry(sent_at: u64) -> AuditEntry {
+    AuditEntry {
+        sent_at,
+        channel: "telegram".to_string(),
+        chat: None,
+        kind: "low_balance".to_string(),
+        network: Some("Ethereum".to_string()),
+        subject: "Relayer".to_string(),
+        content_hash: "abc".to_string(),
+        success: true,
+        error: None,
+    }
+}
+
+fn chats(chat_id: i64) -> ChatStorage {
+    ChatStorage {
+        registrations: vec![ChatRegistration {
+            chat_id,
+            user_id: 7,
+            username: "alice".to_string(),
+            subscriptions: vec![],
+            muted_until: None,
+        }],
+    }
+}
+
+#[test]
+fn test_sign_v4_matches_aws_example() {
+    // GET Object example from the AWS Signature Version 4 documentation
+    let empty_hash = "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
+    let signature = sign_v4(
+        "wJalrXUtnFEMI/K7MDENG/bPxRfiCYEXAMPLEKEY",
+        "us-east-1",
+        "GET",
+        "/test.txt",
+        &[
+            ("host", "examplebucket.s3.amazonaws.com"),
+            ("range", "bytes=0-9"),
+            ("x-amz-content-sha256", empty_hash),
+            ("x-amz-date", "20130524T000000Z"),
+        ],
+        empty_hash,
+        "20130524T000000Z",
+    );
+    assert_eq!(signature, "f0e8bdb87c964420e857bd35b5d6ed310bd44f0170aba48dd91039c6036bdb41");
+}
+
+#[tokio::test]
+async fn test_backup_restores_into_empty_state() {
+    let source_dir = temp_dir("source");
+    let source = FileBackend::new(&source_dir);
+    let mut states = AlertStateStorage::new();
+    states.states.insert("Ethereum:Relayer".to_string(), AlertState::new());
+    source.save_alert_states(&states).await.unwrap();
+    source.save_chats(None, &chats(1)).await.unwrap();
+    source.save_chats(Some("ops"), &chats(2)).await.unwrap();
+    source.append_audit(&audit_entry(100)).await.unwrap();
+    source.append_audit(&audit_entry(200)).await.unwrap();
+
+    let backup = StateBackup::collect(&source, &["ops".to_string()], &source_dir.join("history.jsonl")).await.unwrap();
+    let path = source_dir.join("backup.json.gz");
+    backup.save_to_file(&path, None).unwrap();
+    assert!(std::fs::read(&path).unwrap().starts_with(&[0x1f, 0x8b]), "backups are gzip-compressed");
+
+    let target_dir = temp_dir("target");
+    let target = FileBackend::new(&target_dir);
+    let backup = StateBackup::load_from_file(&path, None).unwrap();
+    backup.restore(&target, &target_dir.join("history.jsonl")).await.unwrap();
+
+    assert!(target.load_alert_states().await.unwrap().states.contains_key("Ethereum:Relayer"));
+    assert_eq!(target.load_chats(None).await.unwrap().registrations[0].chat_id, 1);
+    assert_eq!(target.load_chats(Some("ops")).await.unwrap().registrations[0].chat_id, 2);
+    assert_eq!(target.load_audit_log().await.unwrap(), vec![audit_entry(100), audit_entry(200)]);
+    assert!(target_dir.join("history.jsonl").exists());
+
+    // Restoring again doesn't duplicate the audit log
+    backup.restore(&target, &target_dir.join("history.jsonl")).await.unwrap();
+    assert_eq!(target.load_audit_log().await.unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn test_restoring_a_backup_without_report_baseline_clears_it() {
+    let source_dir = temp_dir("no-baseline");
+    let backup = StateBackup::collect(&FileBackend::new(&source_dir), &[], &source_dir.join("history.jsonl")).await.unwrap();
+    assert!(backup.report_baseline.is_none());
+
+    let target_dir = temp_dir("no-baseline-target");
+    let target = FileBackend::new(&target_dir);
+    target.save_report_baseline(&BalanceStorage::new()).await.unwrap();
+    backup.restore(&target, &target_dir.join("history.jsonl")).await.unwrap();
+    assert!(target.load_report_baseline().await.unwrap().is_none());
+}
+
+#[test]
+fn test_prune_keeps_newest_backups() {
+    let dir = temp_dir("prune");
+    for name in [
+        "oxwatcher-backup-20261014T030000Z.json",
+        "oxwatcher-backup-20261015T030000Z.json",
+        "oxwatcher-backup-20261016T030000Z.json.gz",
+        "notes.txt",
+    ] {
+        std::fs::write(dir.join(name), "{}").unwrap();
+    }
+
+    prune_backups(&dir, 2).unwrap();
+    let mut names: Vec<_> = std::fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .collect();
+    names.sort();
+    assert_eq!(
+        names,
+        vec!["notes.txt", "oxwatcher-backup-20261015T030000Z.json", "oxwatcher-backup-20261016T030000Z.json.gz"]
+    );
+}
+
+#[tokio::test]
+async fn test_restore_reads_plain_backups_and_rejects_bad_bot_names() {
+    let dir = temp_dir("plain");
+    let mut backup = StateBackup::collect(&FileBackend::new(&dir), &[], &dir.join("history.jsonl")).await.unwrap();
+    backup.bot_chats.insert("../../etc/cron.d/evil".to_string(), chats(3));
+
+    // Backups written before compression are plain JSON
+    let path = dir.join("backup.json");
+    let mut json = serde_json::to_value(&backup).unwrap();
+    json["schema_version"] = 2.into();
+    std::fs::write(&path, serde_json::to_vec(&json).unwrap()).unwrap();
+    let backup = StateBackup::load_from_file(&path, None).unwrap();
+
+    let target_dir = temp_dir("plain-target");
+    let target = FileBackend::new(&target_dir);
+    let error = backup.restore(&target, &target_dir.join("history.jsonl")).await.unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "backup has chats of bot '../../etc/cron.d/evil', but bot names may only contain letters, digits, - and _"
+    );
+    assert!(std::fs::read_dir(&target_dir).unwrap().next().is_none(), "nothing is restored");
+}
@@ -38,6 +38,12 @@ async fn test_eth_balance_changes_detection() -> Result<()> {
         address: account,
         eth_balance: balance_initial,
         eth_formatted: format_units_manual(balance_initial, 18),
+        native_symbol: "ETH".to_string(),
+        native_decimals: 18,
+        eth_usd_value: None,
+        nft_balances: vec![],
+        block_number: None,
+        block_hash: None,
         token_balances: vec![],
     };
 
@@ -65,6 +71,12 @@ async fn test_eth_balance_changes_detection() -> Result<()> {
         address: account,
         eth_balance: balance_new,
         eth_formatted: format_units_manual(balance_new, 18),
+        native_symbol: "ETH".to_string(),
+        native_decimals: 18,
+        eth_usd_value: None,
+        nft_balances: vec![],
+        block_number: None,
+        block_hash: None,
         token_balances: vec![],
     };
 
@@ -110,10 +122,19 @@ async fn test_token_balance_changes_detection() -> Result<()> {
         address: account,
         eth_balance: U256::ZERO,
         eth_formatted: "0".to_string(),
+        native_symbol: "ETH".to_string(),
+        native_decimals: 18,
+        eth_usd_value: None,
+        nft_balances: vec![],
+        block_number: None,
+        block_hash: None,
         token_balances: vec![TokenBalance {
             alias: "USDT".to_string(),
             balance: initial_balance,
             formatted: format_units_manual(initial_balance, 6), // USDT has 6 decimals
+            decimals: 6,
+            usd_value: None,
+            shares: None,
         }],
     };
 
@@ -142,10 +163,19 @@ async fn test_token_balance_changes_detection() -> Result<()> {
         address: account,
         eth_balance: U256::ZERO,
         eth_formatted: "0".to_string(),
+        native_symbol: "ETH".to_string(),
+        native_decimals: 18,
+        eth_usd_value: None,
+        nft_balances: vec![],
+        block_number: None,
+        block_hash: None,
         token_balances: vec![TokenBalance {
             alias: "USDT".to_string(),
             balance: new_balance,
             formatted: format_units_manual(new_balance, 6),
+            decimals: 6,
+            usd_value: None,
+            shares: None,
         }],
     };
 
@@ -189,6 +219,12 @@ async fn test_no_changes_detection() -> Result<()> {
         address: account,
         eth_balance: balance,
         eth_formatted: format_units_manual(balance, 18),
+        native_symbol: "ETH".to_string(),
+        native_decimals: 18,
+        eth_usd_value: None,
+        nft_balances: vec![],
+        block_number: None,
+        block_hash: None,
         token_balances: vec![],
     };
 
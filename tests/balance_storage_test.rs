@@ -0,0 +1,69 @@
+use alloy::primitives::{b256, B256, U256};
+use Oxwatcher::{BalanceInfo, BalanceStorage};
+
+mod common;
+
+const HASH_A: B256 = b256!("88e96d4537bea4d9c05d12549907b32561d3bf31f45aae734cdc119f13406cb6");
+const HASH_B: B256 = b256!("b495a1d7e6663152ae92708da4843337b958146015a2802f4193a410044698c9");
+
+fn balance_info(network: &str, alias: &str, block: Option<(u64, B256)>) -> BalanceInfo {
+    BalanceInfo {
+        network_name: network.to_string(),
+        block_number: block.map(|(number, _)| number),
+        block_hash: block.map(|(_, hash)| hash),
+        ..common::balance_info(alias, "0")
+    }
+}
+
+#[test]
+fn test_latest_block_per_network() {
+    let mut storage = BalanceStorage::new();
+    assert!(storage.latest_block("Ethereum").is_none());
+
+    storage.update(&balance_info("Ethereum", "treasury", Some((100, HASH_A))));
+    storage.update(&balance_info("Ethereum", "relayer", Some((101, HASH_B))));
+    storage.update(&balance_info("Ethereum", "legacy", None));
+    storage.update(&balance_info("Polygon", "treasury", Some((5_000, HASH_A))));
+
+    assert_eq!(storage.latest_block("Ethereum"), Some((101, HASH_B)));
+    assert_eq!(storage.latest_block("Polygon"), Some((5_000, HASH_A)));
+}
+
+#[test]
+fn test_block_fields_round_trip() {
+    let info = balance_info("Ethereum", "treasury", Some((100, HASH_A)));
+    let json = serde_json::to_string(&info).unwrap();
+    let decoded: BalanceInfo = serde_json::from_str(&json).unwrap();
+    assert_eq!(decoded.block_number, Some(100));
+    assert_eq!(decoded.block_hash, Some(HASH_A));
+
+    // Snapshots saved before block tracking still load
+    let legacy = json.replace(",\"block_number\":100", "").replace(&format!(",\"block_hash\":\"{:?}\"", HASH_A), "");
+    let decoded: BalanceInfo = serde_json::from_str(&legacy).unwrap();
+    assert!(decoded.block_number.is_none() && decoded.block_hash.is_none());
+}
+
+#[test]
+fn test_last_changed_only_moves_when_balances_change() {
+    let mut storage = BalanceStorage::new();
+    let mut info = balance_info("Ethereum", "distributor", Some((100, HASH_A)));
+
+    storage.update_at(&info, 1_000);
+    assert_eq!(storage.last_changed("Ethereum", "distributor"), Some(1_000));
+
+    // A newer block with the same balances is not a change
+    info.block_number = Some(101);
+    storage.update_at(&info, 2_000);
+    assert_eq!(storage.last_changed("Ethereum", "distributor"), Some(1_000));
+
+    info.eth_balance = U256::from(5u64);
+    storage.update_at(&info, 3_000);
+    assert_eq!(storage.last_changed("Ethereum", "distributor"), Some(3_000));
+
+    // Survives a save and reload, and storage files without it still load
+    let json = serde_json::to_string(&storage).unwrap();
+    let decoded: BalanceStorage = serde_json::from_str(&json).unwrap();
+    assert_eq!(decoded.last_changed("Ethereum", "distributor"), Some(3_000));
+    let legacy: BalanceStorage = serde_json::from_str(r#"{"balances":{}}"#).unwrap();
+    assert!(legacy.last_changed.is_empty());
+}
@@ -0,0 +1,52 @@
+use alloy::eips::BlockId;
+use alloy::primitives::{Address, U256};
+use alloy::providers::ProviderBuilder;
+use alloy::rpc::client::RpcClient;
+use alloy::rpc::json_rpc::{RequestPacket, ResponsePacket};
+use alloy::transports::{TransportError, TransportFut};
+use serde_json::{json, Value};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tower::service_fn;
+use Oxwatcher::{BalanceMonitor, BalanceMonitorConfig, MAX_BATCH_SIZE};
+
+/// Answers eth_getBalance with the last byte of the address, recording the size of each HTTP request
+fn node(requests: Arc<Mutex<Vec<usize>>>) -> RpcClient {
+    let transport = service_fn(move |packet: RequestPacket| -> TransportFut<'static> {
+        let calls = match &packet {
+            RequestPacket::Single(request) => vec![request.clone()],
+            RequestPacket::Batch(requests) => requests.clone(),
+        };
+        requests.lock().unwrap().push(calls.len());
+        let responses: Vec<Value> = calls
+            .iter()
+            .map(|call| {
+                let params: Value = serde_json::from_str(call.params().unwrap().get()).unwrap();
+                let address: Address = params[0].as_str().unwrap().parse().unwrap();
+                json!({ "jsonrpc": "2.0", "id": call.id(), "result": U256::from(address[19]) })
+            })
+            .collect();
+        let body = match packet {
+            RequestPacket::Single(_) => responses[0].clone(),
+            RequestPacket::Batch(_) => Value::Array(responses),
+        };
+        Box::pin(async move { Ok::<ResponsePacket, TransportError>(serde_json::from_value(body).unwrap()) })
+    });
+    RpcClient::builder().transport(transport, false)
+}
+
+#[tokio::test]
+async fn test_native_balances_are_read_in_batches() {
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let provider = ProviderBuilder::new().connect_client(node(Arc::clone(&requests)));
+    let config = BalanceMonitorConfig::new(Vec::new(), Vec::new(), Duration::from_secs(60)).with_batch_rpc(true);
+    let monitor = BalanceMonitor::new(provider, config);
+
+    let addresses: Vec<Address> = (1..=MAX_BATCH_SIZE as u8 + 20).map(Address::with_last_byte).collect();
+    let balances = monitor.batch_native_balances(&addresses, BlockId::latest()).await.unwrap();
+
+    // Balances come back in address order, with one request per full batch
+    assert_eq!(balances.len(), addresses.len());
+    assert!(balances.iter().zip(1u64..).all(|(balance, i)| *balance == U256::from(i)));
+    assert_eq!(*requests.lock().unwrap(), [MAX_BATCH_SIZE, 20]);
+}
@@ -0,0 +1,89 @@
+use alloy::primitives::{Address, B256, U256};
+use alloy::providers::ProviderBuilder;
+use alloy::rpc::client::RpcClient;
+use alloy::rpc::json_rpc::{RequestPacket, ResponsePacket, SerializedRequest};
+use alloy::rpc::types::Block;
+use alloy::transports::{TransportError, TransportFut};
+use serde_json::{json, Value};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tower::service_fn;
+use Oxwatcher::{AddressConfig, BalanceMonitor, BalanceMonitorConfig};
+
+/// Node whose head is `head`; records the methods called
+fn node(head: Arc<AtomicU64>, methods: Arc<Mutex<Vec<String>>>) -> RpcClient {
+    let answer = move |call: &SerializedRequest| {
+        methods.lock().unwrap().push(call.method().to_string());
+        let result = match call.method() {
+            "eth_getBlockByNumber" => {
+                let number = head.load(Ordering::Relaxed);
+                let mut block = serde_json::to_value(Block::<Value>::default()).unwrap();
+                block["number"] = json!(U256::from(number));
+                block["hash"] = json!(B256::with_last_byte(number as u8));
+                block
+            }
+            _ => json!(U256::from(1_000_000_000_000_000_000u64)),
+        };
+        json!({ "jsonrpc": "2.0", "id": call.id(), "result": result })
+    };
+    let transport = service_fn(move |packet: RequestPacket| -> TransportFut<'static> {
+        let body = match &packet {
+            RequestPacket::Single(call) => answer(call),
+            RequestPacket::Batch(calls) => Value::Array(calls.iter().map(&answer).collect()),
+        };
+        Box::pin(async move { Ok::<ResponsePacket, TransportError>(serde_json::from_value(body).unwrap()) })
+    });
+    RpcClient::builder().transport(transport, false)
+}
+
+fn address(alias: &str, last_byte: u8) -> AddressConfig {
+    serde_yaml::from_str(&format!("alias: {}\naddress: \"{}\"", alias, Address::with_last_byte(last_byte))).unwrap()
+}
+
+#[tokio::test]
+async fn test_balances_are_reused_until_the_head_moves() {
+    let head = Arc::new(AtomicU64::new(100));
+    let methods = Arc::new(Mutex::new(Vec::new()));
+    let provider = ProviderBuilder::new().connect_client(node(Arc::clone(&head), Arc::clone(&methods)));
+    let monitor = BalanceMonitor::new(provider, BalanceMonitorConfig::new(Vec::new(), Vec::new(), Duration::from_secs(60)));
+    let calls = || std::mem::take(&mut *methods.lock().unwrap());
+
+    let addresses = vec![address("treasury", 1), address("hot", 2)];
+    let balances = monitor.check_addresses(&addresses, "Ethereum".to_string(), 1).await;
+    assert!(balances.iter().all(|balance| balance.as_ref().unwrap().block_number == Some(100)));
+    assert_eq!(calls(), ["eth_getBlockByNumber", "eth_getBalance", "eth_getBalance"]);
+
+    // Same head: only the block is fetched, and only a new address is read
+    let addresses = vec![address("treasury", 1), address("cold", 3), address("hot", 2)];
+    let balances = monitor.check_addresses(&addresses, "Ethereum".to_string(), 1).await;
+    let aliases: Vec<String> = balances.into_iter().map(|balance| balance.unwrap().alias).collect();
+    assert_eq!(aliases, ["treasury", "cold", "hot"]);
+    assert_eq!(calls(), ["eth_getBlockByNumber", "eth_getBalance"]);
+    assert_eq!(monitor.last_block().await.map(|(number, _)| number), Some(100));
+
+    // New head: everything is read again
+    head.store(101, Ordering::Relaxed);
+    monitor.check_addresses(&addresses, "Ethereum".to_string(), 1).await;
+    assert_eq!(calls(), ["eth_getBlockByNumber", "eth_getBalance", "eth_getBalance", "eth_getBalance"]);
+    assert_eq!(monitor.last_block().await.map(|(number, _)| number), Some(101));
+}
+
+#[tokio::test]
+async fn test_unchanged_head_sends_no_reads_with_multicall() {
+    let head = Arc::new(AtomicU64::new(100));
+    let methods = Arc::new(Mutex::new(Vec::new()));
+    let provider = ProviderBuilder::new().connect_client(node(Arc::clone(&head), Arc::clone(&methods)));
+    let config = BalanceMonitorConfig::new(Vec::new(), Vec::new(), Duration::from_secs(60))
+        .with_multicall(Oxwatcher::MULTICALL3_ADDRESS);
+    let monitor = BalanceMonitor::new(provider, config);
+    let calls = || std::mem::take(&mut *methods.lock().unwrap());
+
+    let addresses = vec![address("treasury", 1), address("hot", 2)];
+    monitor.check_addresses(&addresses, "Ethereum".to_string(), 1).await;
+    calls();
+
+    // Every balance is cached: no empty multicall goes out
+    monitor.check_addresses(&addresses, "Ethereum".to_string(), 1).await;
+    assert_eq!(calls(), ["eth_getBlockByNumber"]);
+}
@@ -0,0 +1,117 @@
+use std::time::Duration;
+use Oxwatcher::{BalanceInfo, BudgetPeriod, Config, HistoryStore, RuleConfig, RuleEngine};
+
+mod common;
+
+const HOUR: u64 = 3600;
+/// Wednesday 2023-11-15 00:00 UTC
+const DAY_START: u64 = 1_700_006_400;
+
+fn balance(eth: f64) -> BalanceInfo {
+    common::balance_info("Relayer", &eth.to_string())
+}
+
+fn history(name: &str, points: &[(u64, f64)]) -> HistoryStore {
+    let path = std::env::temp_dir().join(format!("oxwatcher-budget-{}-{}.jsonl", name, std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let mut history = HistoryStore::load_from_file(&path, Duration::from_secs(30 * 24 * HOUR), Duration::from_secs(HOUR)).unwrap();
+    for &(timestamp, eth) in points {
+        history.record(&balance(eth), timestamp).unwrap();
+    }
+    history
+}
+
+fn engine(yaml: &str) -> RuleEngine {
+    let rule: RuleConfig = serde_yaml::from_str(yaml).unwrap();
+    RuleEngine::new(&[rule]).unwrap()
+}
+
+#[test]
+fn test_daily_budget_alerts_when_projected_then_exceeded() {
+    let rules = engine("name: Relayer gas\nbudget:\n  amount: 0.5");
+    assert!(rules.has_budgets());
+    // 10 ETH before the day starts, 0.2 spent, then topped up by 3 ETH
+    let history = history("daily", &[(DAY_START - HOUR, 10.0), (DAY_START + 3 * HOUR, 9.8), (DAY_START + 4 * HOUR, 12.8)]);
+
+    // 0.4 spent in 5 hours projects to 1.92 for the day; the top-up doesn't offset it
+    let alerts = rules.evaluate_budgets(&history, &balance(12.6), DAY_START + 5 * HOUR);
+    assert_eq!(alerts.len(), 1);
+    let data = &alerts[0].alert.data;
+    assert_eq!(data["level"], "projected");
+    assert!((data["spent"].as_f64().unwrap() - 0.4).abs() < 1e-9);
+    assert!((data["projected"].as_f64().unwrap() - 1.92).abs() < 1e-9);
+    assert_eq!(alerts[0].alert.title, "⚠️ Relayer gas: Relayer projected over budget");
+    assert!(rules.evaluate_budgets(&history, &balance(12.6), DAY_START + 5 * HOUR).is_empty());
+
+    let alerts = rules.evaluate_budgets(&history, &balance(12.2), DAY_START + 6 * HOUR);
+    assert_eq!(alerts.len(), 1);
+    assert_eq!(alerts[0].alert.data["level"], "exceeded");
+    assert!(rules.evaluate_budgets(&history, &balance(12.1), DAY_START + 7 * HOUR).is_empty());
+
+    // A new day starts from the last balance of the previous one
+    let tomorrow = DAY_START + 24 * HOUR;
+    assert!(rules.evaluate_budgets(&history, &balance(12.8), tomorrow + 5 * HOUR).is_empty());
+    let alerts = rules.evaluate_budgets(&history, &balance(12.2), tomorrow + 5 * HOUR);
+    assert_eq!(alerts[0].alert.data["level"], "exceeded");
+}
+
+#[test]
+fn test_projection_waits_for_part_of_the_period() {
+    let history = history("projection", &[(DAY_START - HOUR, 10.0)]);
+
+    // 0.2 spent in the first hour would project to 4.8
+    let rules = engine("name: Relayer gas\nbudget:\n  amount: 0.5");
+    assert!(rules.evaluate_budgets(&history, &balance(9.8), DAY_START + HOUR).is_empty());
+
+    let rules = engine("name: Relayer gas\nbudget:\n  amount: 0.5\n  projected: false");
+    assert!(rules.evaluate_budgets(&history, &balance(9.8), DAY_START + 12 * HOUR).is_empty());
+    assert_eq!(rules.evaluate_budgets(&history, &balance(9.4), DAY_START + 12 * HOUR).len(), 1);
+}
+
+#[test]
+fn test_budget_periods_start_at_utc_midnight_and_monday() {
+    assert_eq!(BudgetPeriod::Day.start(DAY_START + 5 * HOUR), DAY_START);
+    // Monday 2023-11-13
+    assert_eq!(BudgetPeriod::Week.start(DAY_START + 5 * HOUR), DAY_START - 2 * 24 * HOUR);
+    assert_eq!(BudgetPeriod::Week.start(DAY_START - 2 * 24 * HOUR), DAY_START - 2 * 24 * HOUR);
+}
+
+#[test]
+fn test_budget_rule_validation() {
+    let config: Config = serde_yaml::from_str(
+        r#"
+interval_secs: 60
+history:
+  enabled: false
+networks:
+  - name: Ethereum
+    chain_id: 1
+    rpc_nodes: ["https://eth.llamarpc.com"]
+    addresses:
+      - alias: Relayer
+        address: "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045"
+rules:
+  - name: Relayer gas
+    addresses: [Relayer]
+    budget:
+      amount: 0.5
+      period: week
+  - name: Both
+    condition: balance < 1
+    budget:
+      amount: 0
+"#,
+    )
+    .unwrap();
+
+    assert_eq!(config.rules[0].budget.as_ref().unwrap().period, BudgetPeriod::Week);
+    assert_eq!(
+        config.problems(),
+        vec![
+            "rule 'Relayer gas': budgets need history enabled".to_string(),
+            "rule 'Both' can't have both a condition and a budget".to_string(),
+            "rule 'Both': budget amount must be greater than 0".to_string(),
+            "rule 'Both': budgets need history enabled".to_string(),
+        ]
+    );
+}
@@ -0,0 +1,81 @@
+use std::sync::Arc;
+use Oxwatcher::{ChatRegistration, ChatStorage, ChatSubscription, FileBackend, StateBackend};
+
+fn subscription(network: Option<&str>, alias: Option<&str>) -> ChatSubscription {
+    ChatSubscription {
+        network: network.map(str::to_string),
+        alias: alias.map(str::to_string),
+    }
+}
+
+fn registration(subscriptions: Vec<ChatSubscription>) -> ChatRegistration {
+    ChatRegistration {
+        chat_id: 42,
+        user_id: 7,
+        username: "l2team".to_string(),
+        subscriptions,
+        muted_until: None,
+    }
+}
+
+#[test]
+fn test_chat_subscriptions_filter_alerts() {
+    // No subscriptions receives everything
+    assert!(registration(vec![]).wants("Ethereum", "treasury"));
+
+    let chat = registration(vec![
+        subscription(Some("Arbitrum"), None),
+        subscription(None, Some("sequencer")),
+    ]);
+    assert!(chat.wants("Arbitrum", "treasury"));
+    assert!(chat.wants("Ethereum", "sequencer"));
+    assert!(!chat.wants("Ethereum", "treasury"));
+
+    let exact = subscription(Some("Base"), Some("treasury"));
+    assert!(exact.matches("Base", "treasury"));
+    assert!(!exact.matches("Base", "hot wallet"));
+    assert_eq!(exact.describe(), "Base / treasury");
+    assert_eq!(subscription(None, Some("treasury")).describe(), "* / treasury");
+}
+
+#[test]
+fn test_chat_mute_expires() {
+    let mut chat = registration(vec![]);
+    assert!(!chat.is_muted(1_000));
+
+    chat.muted_until = Some(2_000);
+    assert!(chat.is_muted(1_000));
+    assert!(!chat.is_muted(2_000));
+}
+
+#[tokio::test]
+async fn test_chat_subscriptions_persist() {
+    let dir = std::env::temp_dir().join(format!("oxwatcher-chats-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+
+    // Chats saved before subscriptions existed load with none
+    std::fs::write(
+        dir.join("telegram_chats.json"),
+        r#"{"registrations":[{"chat_id":1,"user_id":2,"username":"old"}]}"#,
+    )
+    .unwrap();
+    let backend = Arc::new(FileBackend::new(&dir));
+    let loaded = backend.load_chats(None).await.unwrap();
+    assert!(loaded.registrations[0].subscriptions.is_empty());
+
+    let mut chat = registration(vec![subscription(Some("Arbitrum"), None)]);
+    chat.muted_until = Some(2_000);
+    let storage = ChatStorage { registrations: vec![chat] };
+    backend.save_chats(None, &storage).await.unwrap();
+
+    let loaded = backend.load_chats(None).await.unwrap();
+    assert_eq!(loaded.registrations[0].subscriptions, vec![subscription(Some("Arbitrum"), None)]);
+    assert_eq!(loaded.registrations[0].muted_until, Some(2_000));
+
+    // Each additional bot has its own chats
+    assert!(backend.load_chats(Some("status")).await.unwrap().registrations.is_empty());
+    backend.save_chats(Some("status"), &ChatStorage::new()).await.unwrap();
+    assert_eq!(backend.load_chats(None).await.unwrap().registrations.len(), 1);
+    assert!(dir.join("telegram_chats_status.json").exists());
+}
@@ -0,0 +1,40 @@
+//! Fixtures shared by the integration tests. Each test crate uses only some of them.
+#![allow(dead_code)]
+
+use alloy::primitives::{address, Address};
+use Oxwatcher::{to_units, BalanceInfo, TokenBalance};
+
+pub const ACCOUNT: Address = address!("d8dA6BF26964aF9D7eEd9e03E53415D37aA96045");
+
+/// Balance of `alias` on Ethereum holding `eth` ETH and no tokens; override the fields a
+/// test cares about with struct update syntax
+pub fn balance_info(alias: &str, eth: &str) -> BalanceInfo {
+    BalanceInfo {
+        network_name: "Ethereum".to_string(),
+        chain_id: 1,
+        alias: alias.to_string(),
+        address: ACCOUNT,
+        eth_balance: to_units(eth.parse().unwrap(), 18),
+        eth_formatted: eth.to_string(),
+        native_symbol: "ETH".to_string(),
+        native_decimals: 18,
+        eth_usd_value: None,
+        nft_balances: vec![],
+        block_number: None,
+        block_hash: None,
+        token_balances: vec![],
+    }
+}
+
+/// Balance of `amount` tokens with `decimals` decimals
+pub fn token_balance(alias: &str, amount: &str, decimals: u8) -> TokenBalance {
+    TokenBalance {
+        alias: alias.to_string(),
+        balance: to_units(amount.parse().unwrap(), decimals),
+        formatted: amount.to_string(),
+        decimals,
+        usd_value: None,
+        shares: None,
+    }
+}
+
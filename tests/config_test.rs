@@ -0,0 +1,748 @@
+use alloy::primitives::U256;
+use std::time::Duration;
+use Oxwatcher::{
+    address_diagnostics, diagnose_config, expand_env, format_gwei, AlertSeverity, Config, ConfirmationTag, Confirmations, GasCrossings, GasLevel, GasPrice, GasTracker,
+    NetworkConfig, NftStandard, render_template, Role, Severity, TelegramConfig, ThresholdTier,
+};
+
+fn network(extra: &str) -> NetworkConfig {
+    serde_yaml::from_str(&format!(
+        r#"
+name: Polygon
+chain_id: 137
+rpc_nodes: ["https://polygon-rpc.com"]
+addresses: []
+{}
+"#,
+        extra
+    ))
+    .unwrap()
+}
+
+#[test]
+fn test_confirmations_setting() {
+    assert_eq!(network("").confirmations, Confirmations::Blocks(0));
+    assert_eq!(network("confirmations: 32").confirmations, Confirmations::Blocks(32));
+    assert_eq!(network("confirmations: finalized").confirmations, Confirmations::Tag(ConfirmationTag::Finalized));
+    assert_eq!(network("confirmations: safe").confirmations.to_string(), "safe");
+    assert!(serde_yaml::from_str::<NetworkConfig>("name: x\nchain_id: 1\nrpc_nodes: []\naddresses: []\nconfirmations: pending").is_err());
+}
+
+#[test]
+fn test_nft_collections() {
+    let network = network(
+        r#"
+nfts:
+  - alias: Treasury Punks
+    address: 0xb47e3cd837dDF8e4c57F05d70Ab865de6e193BBB
+  - alias: Badges
+    address: 0x76BE3b62873462d2142405439777e971754E8E77
+    standard: erc1155
+    token_ids: [10, "0x2a"]
+"#,
+    );
+
+    assert_eq!(network.nfts[0].standard, NftStandard::Erc721);
+    assert!(network.nfts[0].token_ids.is_empty());
+    assert_eq!(network.nfts[1].standard, NftStandard::Erc1155);
+    assert_eq!(network.nfts[1].token_ids, vec![U256::from(10), U256::from(42)]);
+}
+
+#[tokio::test]
+async fn test_gas_alerts_once_per_crossing() {
+    let config = network("gas:\n  above_gwei: 50\n  below_gwei: 10").gas.unwrap();
+    let tracker = GasTracker::new();
+    let price = |gwei: u128| GasPrice {
+        network_name: "Polygon".to_string(),
+        chain_id: 137,
+        base_fee_wei: gwei * 1_000_000_000,
+        priority_fee_wei: None,
+        blob_base_fee_wei: None,
+        blob_base_fee_24h: None,
+        updated_at: 0,
+    };
+
+    assert_eq!(tracker.record(price(30), &config).await.base_fee, None);
+    assert_eq!(tracker.record(price(60), &config).await.base_fee, Some(GasLevel::Above));
+    assert_eq!(tracker.record(price(70), &config).await.base_fee, None);
+    assert_eq!(tracker.record(price(5), &config).await.base_fee, Some(GasLevel::Below));
+    assert_eq!(tracker.record(price(30), &config).await.base_fee, None);
+    assert_eq!(tracker.get("Polygon").await.unwrap().base_fee_gwei(), 30.0);
+}
+
+#[tokio::test]
+async fn test_blob_fee_crossings_and_daily_range() {
+    let config = network("gas:\n  blob_above_gwei: 5\n  blob_below_gwei: 0.001").gas.unwrap();
+    assert!(config.tracks_blob_fee());
+    let tracker = GasTracker::new();
+    let price = |blob_wei: u128, at: u64| GasPrice {
+        network_name: "Ethereum".to_string(),
+        chain_id: 1,
+        base_fee_wei: 10_000_000_000,
+        priority_fee_wei: None,
+        blob_base_fee_wei: Some(blob_wei),
+        blob_base_fee_24h: None,
+        updated_at: at,
+    };
+    const HOUR: u64 = 3600;
+
+    assert_eq!(tracker.record(price(2_000_000_000, 0), &config).await, GasCrossings::default());
+    let crossings = tracker.record(price(8_000_000_000, HOUR), &config).await;
+    assert_eq!(crossings, GasCrossings { base_fee: None, blob_fee: Some(GasLevel::Above) });
+    assert_eq!(tracker.record(price(9_000_000_000, 2 * HOUR), &config).await.blob_fee, None);
+    assert_eq!(tracker.record(price(1, 3 * HOUR), &config).await.blob_fee, Some(GasLevel::Below));
+
+    let range = tracker.get("Ethereum").await.unwrap().blob_base_fee_24h.unwrap();
+    assert_eq!((range.min_wei, range.max_wei, range.samples), (1, 9_000_000_000, 4));
+
+    // Readings older than 24 hours drop out of the range
+    tracker.record(price(3_000_000_000, 25 * HOUR + HOUR / 2), &config).await;
+    let range = tracker.get("Ethereum").await.unwrap().blob_base_fee_24h.unwrap();
+    assert_eq!((range.min_wei, range.max_wei, range.samples), (1, 9_000_000_000, 3));
+
+    assert_eq!(format_gwei(1), "0.000000001");
+    assert_eq!(format_gwei(12_500_000_000), "12.5");
+    assert_eq!(format_gwei(3_000_000_000), "3");
+}
+
+#[test]
+fn test_rpc_nodes_accept_urls_and_objects() {
+    let network: NetworkConfig = serde_yaml::from_str(
+        r#"
+name: Ethereum
+chain_id: 1
+rpc_nodes:
+  - https://eth.llamarpc.com
+  - url: https://rpc.example.com
+    headers:
+      x-api-key: secret
+  - url: https://node.internal:8545
+    basic_auth:
+      username: watcher
+      password: hunter2
+    rate_limit: 50
+rpc_rate_limit: 5
+addresses: []
+"#,
+    )
+    .unwrap();
+
+    assert_eq!(network.rpc_nodes.len(), 3);
+    assert_eq!(network.rpc_nodes[0].url.as_str(), "https://eth.llamarpc.com/");
+    assert!(network.rpc_nodes[0].headers.is_empty() && network.rpc_nodes[0].basic_auth.is_none());
+    assert_eq!(network.rpc_nodes[1].headers["x-api-key"], "secret");
+    assert_eq!(network.rpc_nodes[2].basic_auth.as_ref().unwrap().username, "watcher");
+
+    // Network rate limit applies to nodes without their own
+    let limits: Vec<Option<f64>> = network.resolved_rpc_nodes().iter().map(|node| node.rate_limit).collect();
+    assert_eq!(limits, vec![Some(5.0), Some(5.0), Some(50.0)]);
+    assert!(network.archive_rpc_nodes.is_empty());
+}
+
+#[test]
+fn test_archive_rpc_nodes_form_a_separate_pool() {
+    let network: NetworkConfig = serde_yaml::from_str(
+        r#"
+name: Ethereum
+chain_id: 1
+rpc_nodes: [https://eth.llamarpc.com]
+archive_rpc_nodes:
+  - url: https://archive.example.com
+    headers:
+      x-api-key: secret
+  - url: https://archive-backup.example.com
+    rate_limit: 2
+rpc_rate_limit: 5
+addresses: []
+"#,
+    )
+    .unwrap();
+
+    assert_eq!(network.resolved_rpc_nodes().len(), 1);
+    let archive = network.resolved_archive_rpc_nodes();
+    assert_eq!(archive[0].url.as_str(), "https://archive.example.com/");
+    assert_eq!(archive[0].headers["x-api-key"], "secret");
+    let limits: Vec<Option<f64>> = archive.iter().map(|node| node.rate_limit).collect();
+    assert_eq!(limits, vec![Some(5.0), Some(2.0)]);
+}
+
+#[test]
+fn test_rate_limits_must_be_positive_and_finite() {
+    let config: Config = serde_yaml::from_str(
+        r#"
+interval_secs: 60
+networks:
+  - name: Ethereum
+    chain_id: 1
+    rpc_nodes:
+      - url: https://eth.llamarpc.com
+        rate_limit: -1
+    archive_rpc_nodes:
+      - url: https://archive.example.com
+        rate_limit: .inf
+    rpc_rate_limit: .nan
+    addresses:
+      - address: "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045"
+        alias: vitalik
+"#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        config.problems(),
+        vec![
+            "rpc_rate_limit must be greater than 0 for network 'Ethereum'",
+            "rate_limit must be greater than 0 for RPC node eth.llamarpc.com on network 'Ethereum'",
+            "rate_limit must be greater than 0 for RPC node archive.example.com on network 'Ethereum'",
+        ]
+    );
+}
+
+#[test]
+fn test_concurrency_falls_back_to_global_limit() {
+    let config: Config = serde_yaml::from_str(
+        r#"
+interval_secs: 60
+networks:
+  - name: Ethereum
+    chain_id: 1
+    rpc_nodes: ["https://eth.llamarpc.com"]
+    addresses: []
+  - name: Polygon
+    chain_id: 137
+    rpc_nodes: ["https://polygon-rpc.com"]
+    addresses: []
+    concurrency: 2
+"#,
+    )
+    .unwrap();
+
+    assert_eq!(config.concurrency.get(), 10);
+    assert_eq!(config.concurrency_for(&config.networks[0]).get(), 10);
+    assert_eq!(config.concurrency_for(&config.networks[1]).get(), 2);
+    assert!(serde_yaml::from_str::<NetworkConfig>("name: x\nchain_id: 1\nrpc_nodes: []\naddresses: []\nconcurrency: 0").is_err());
+}
+
+#[test]
+fn test_rpc_timeout_falls_back_to_global_timeout() {
+    let config: Config = serde_yaml::from_str(
+        r#"
+interval_secs: 60
+rpc_timeout_secs: 15
+networks:
+  - name: Ethereum
+    chain_id: 1
+    rpc_nodes: ["https://eth.llamarpc.com"]
+    addresses: []
+  - name: Arbitrum
+    chain_id: 42161
+    rpc_nodes: ["https://arb1.arbitrum.io/rpc"]
+    addresses: []
+    rpc_timeout_secs: 5
+"#,
+    )
+    .unwrap();
+
+    assert_eq!(config.rpc_timeout_for(&config.networks[0]), Duration::from_secs(15));
+    assert_eq!(config.rpc_timeout_for(&config.networks[1]), Duration::from_secs(5));
+    assert_eq!(network("").rpc_timeout, None);
+}
+
+#[test]
+fn test_explorers_by_network() {
+    let config: Config = serde_yaml::from_str(
+        r#"
+interval_secs: 60
+networks:
+  - name: Ethereum
+    chain_id: 1
+    rpc_nodes: ["https://eth.llamarpc.com"]
+    explorer_url: https://etherscan.io/
+    addresses: []
+  - name: Polygon
+    chain_id: 137
+    rpc_nodes: ["https://polygon-rpc.com"]
+    addresses: []
+"#,
+    )
+    .unwrap();
+
+    let explorers = config.explorers();
+    assert_eq!(explorers.len(), 1);
+    assert_eq!(
+        explorers["Ethereum"].join("address/0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045").unwrap().as_str(),
+        "https://etherscan.io/address/0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045"
+    );
+}
+
+#[test]
+fn test_threshold_tiers() {
+    let network = network(
+        r#"
+tokens:
+  - alias: USDC
+    address: 0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48
+    min_balance: 1000
+    thresholds:
+      - below: 500
+        severity: critical
+        channels: [telegram]
+        message: "{alias} is almost out of {asset}"
+"#,
+    );
+
+    let tier = &network.tokens[0].thresholds[0];
+    assert_eq!(tier.severity, AlertSeverity::Critical);
+    assert_eq!(tier.channels, vec!["telegram".to_string()]);
+    assert_eq!(ThresholdTier::below(1000.0).severity, AlertSeverity::Warning);
+}
+
+#[test]
+fn test_telegram_topics() {
+    let telegram: TelegramConfig = serde_yaml::from_str(
+        r#"
+bot_token: "123:abc"
+topics:
+  alerts:
+    report: 7
+  networks:
+    Ethereum: 3
+  default: 1
+"#,
+    )
+    .unwrap();
+
+    let topics = &telegram.topics;
+    assert_eq!(topics.thread_id("low_balance", Some("Ethereum")), Some(3));
+    assert_eq!(topics.thread_id("report", None), Some(7));
+    // The alert type wins over the network
+    assert_eq!(topics.thread_id("report", Some("Ethereum")), Some(7));
+    assert_eq!(topics.thread_id("gas", Some("Polygon")), Some(1));
+
+    let none: TelegramConfig = serde_yaml::from_str(r#"bot_token: "123:abc""#).unwrap();
+    assert_eq!(none.topics.thread_id("report", Some("Ethereum")), None);
+}
+
+#[test]
+fn test_telegram_bots() {
+    let yaml = |bots: &str| {
+        format!(
+            r#"
+interval_secs: 60
+networks:
+  - name: Ethereum
+    chain_id: 1
+    rpc_nodes: ["https://eth.llamarpc.com"]
+    addresses:
+      - address: "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045"
+        alias: vitalik
+telegram:
+  bot_token: "123:ops"
+telegram_bots:
+{}
+rules:
+  - name: Outflow
+    condition: decrease > 10 ETH
+    channels: [telegram:status]
+"#,
+            bots
+        )
+    };
+
+    let config: Config = serde_yaml::from_str(&yaml("  - name: status\n    bot_token: \"456:status\"\n    allowed_users: [all]")).unwrap();
+    assert!(config.problems().is_empty(), "{:?}", config.problems());
+    let names: Vec<String> = config.telegram_configs().map(TelegramConfig::channel_name).collect();
+    assert_eq!(names, vec!["Telegram", "Telegram:status"]);
+    assert!(config.telegram_bots[0].access.is_public());
+    assert!(!config.telegram.as_ref().unwrap().access.is_public());
+
+    let config: Config = serde_yaml::from_str(&yaml("  - bot_token: \"123:ops\"")).unwrap();
+    assert_eq!(
+        config.problems(),
+        vec![
+            "rule 'Outflow' uses unknown channel 'telegram:status', expected telegram, telegram:<bot name>, slack, discord, matrix, twilio or webhook",
+            "every telegram_bots entry needs a name",
+            "telegram uses the same bot_token as another bot",
+        ]
+    );
+
+    let config: Config = serde_yaml::from_str(&yaml("  - name: ../status\n    bot_token: \"456:status\"")).unwrap();
+    assert_eq!(
+        config.problems(),
+        vec![
+            "rule 'Outflow' uses unknown channel 'telegram:status', expected telegram, telegram:<bot name>, slack, discord, matrix, twilio or webhook",
+            "telegram bot name '../status' may only contain letters, digits, '-' and '_'",
+        ]
+    );
+
+    // Naming the main bot routes to it without moving its chats
+    let mut config: Config = serde_yaml::from_str(&yaml("  - name: public\n    bot_token: \"456:status\"")).unwrap();
+    config.telegram.as_mut().unwrap().name = Some("Status".to_string());
+    assert!(config.problems().is_empty(), "{:?}", config.problems());
+    let stores: Vec<Option<&str>> = config.telegram_chat_stores().map(|(_, name)| name).collect();
+    assert_eq!(stores, vec![None, Some("public")]);
+}
+
+#[test]
+fn test_matrix_rooms_must_be_room_ids() {
+    let config: Config = serde_yaml::from_str(
+        r##"
+interval_secs: 60
+networks:
+  - name: Ethereum
+    chain_id: 1
+    rpc_nodes: ["https://eth.llamarpc.com"]
+    addresses:
+      - address: "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045"
+        alias: vitalik
+matrix:
+  homeserver_url: "https://matrix.example.org"
+  access_token: "syt_token"
+  rooms: ["!ops:example.org", "#alerts:example.org"]
+rules:
+  - name: Outflow
+    condition: decrease > 10 ETH
+    channels: [matrix]
+"##,
+    )
+    .unwrap();
+
+    assert_eq!(
+        config.problems(),
+        vec!["matrix room '#alerts:example.org' must be a room ID like !abcdef:example.org"]
+    );
+
+    let mut config = config;
+    let matrix = config.matrix.as_mut().unwrap();
+    matrix.homeserver_url = "mailto:ops@example.org".parse().unwrap();
+    matrix.rooms.pop();
+    assert_eq!(config.problems(), vec!["matrix homeserver_url 'mailto:ops@example.org' must be an http(s) URL"]);
+}
+
+#[test]
+fn test_telegram_silent_alerts() {
+    let telegram: TelegramConfig = serde_yaml::from_str(
+        r#"
+bot_token: "123:abc"
+silent:
+  alerts: [report, heartbeat]
+  severities: [info]
+"#,
+    )
+    .unwrap();
+
+    let silent = &telegram.silent;
+    assert!(silent.is_silent("report", None));
+    assert!(silent.is_silent("low_balance", Some(AlertSeverity::Info)));
+    assert!(!silent.is_silent("low_balance", Some(AlertSeverity::Critical)));
+    assert!(!silent.is_silent("balance_change", None));
+
+    let none: TelegramConfig = serde_yaml::from_str(r#"bot_token: "123:abc""#).unwrap();
+    assert!(!none.silent.is_silent("report", Some(AlertSeverity::Info)));
+}
+
+#[test]
+fn test_telegram_access_by_username_user_id_and_chat_id() {
+    let telegram: TelegramConfig = serde_yaml::from_str(
+        r#"
+bot_token: "123:abc"
+allowed_users: [alice]
+allowed_user_ids: [42]
+allowed_chat_ids: [-1001234567890]
+"#,
+    )
+    .unwrap();
+
+    let access = &telegram.access;
+    assert!(!access.is_public());
+    assert!(access.allows(7, 7, Some("alice")));
+    assert!(access.allows(42, 42, None));
+    // Anyone in an allowed group, with or without a username
+    assert!(access.allows(-1001234567890, 99, None));
+    assert!(!access.allows(-100999, 99, Some("mallory")));
+    assert!(!access.allows(99, 99, None));
+
+    let public: TelegramConfig = serde_yaml::from_str("bot_token: \"123:abc\"\nallowed_users: [all]").unwrap();
+    assert!(public.access.allows(99, 99, None));
+}
+
+#[test]
+fn test_telegram_roles() {
+    let telegram: TelegramConfig = serde_yaml::from_str(
+        r#"
+bot_token: "123:abc"
+allowed_users: [alice, bob]
+admins: [alice]
+admin_user_ids: [42]
+"#,
+    )
+    .unwrap();
+
+    let access = &telegram.access;
+    assert_eq!(access.role(1, Some("alice")), Role::Admin);
+    assert_eq!(access.role(42, None), Role::Admin);
+    assert_eq!(access.role(2, Some("bob")), Role::Viewer);
+    assert!(Role::Viewer < Role::Admin);
+}
+
+#[test]
+fn test_message_templates() {
+    let telegram: TelegramConfig = serde_yaml::from_str(
+        r#"
+bot_token: "123:abc"
+templates:
+  low_balance: "{emoji} {alias}: {balance} {asset} < {threshold}"
+  alert: "{title} ({network})\n{detail}"
+"#,
+    )
+    .unwrap();
+    assert!(telegram.templates.balance_change.is_none());
+
+    let rendered = render_template(
+        telegram.templates.low_balance.as_deref().unwrap(),
+        &[("emoji", "⚠️".to_string()), ("alias", "relayer".to_string()), ("balance", "0.1".to_string()), ("asset", "ETH".to_string())],
+    );
+    // Placeholders without a value stay visible
+    assert_eq!(rendered, "⚠️ relayer: 0.1 ETH < {threshold}");
+    assert_eq!(render_template("{a}{b} {{a}} {", &[("a", "1".to_string()), ("b", "2".to_string())]), "12 {1} {");
+
+    let mut config: Config = serde_yaml::from_str(
+        r#"
+interval_secs: 60
+networks:
+  - name: Ethereum
+    chain_id: 1
+    rpc_nodes: ["https://eth.llamarpc.com"]
+    addresses:
+      - alias: treasury
+        address: "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045"
+"#,
+    )
+    .unwrap();
+    config.telegram = Some(telegram);
+    assert_eq!(
+        config.problems(),
+        vec!["telegram alert template uses unknown placeholder {detail}, expected one of: title, network, chain_id, details".to_string()]
+    );
+}
+
+#[test]
+fn test_problems_lists_every_validation_error() {
+    let config: Config = serde_yaml::from_str(
+        r#"
+interval_secs: 60
+rpc_timeout_secs: 0
+rpc_node_down_mins: 0
+networks:
+  - name: Ethereum
+    chain_id: 1
+    rpc_nodes: []
+    addresses: []
+telegram:
+  bot_token: "123:abc"
+  daily_report:
+    time: "9am"
+webhooks:
+  - url: https://example.com/hook
+    retry:
+      max_attempts: 50
+"#,
+    )
+    .unwrap();
+
+    let problems = config.problems();
+    assert_eq!(problems.len(), 6);
+    assert!(problems.contains(&"rpc_timeout_secs must be greater than 0".to_string()));
+    assert!(problems.contains(&"rpc_node_down_mins must be greater than 0".to_string()));
+    assert!(problems.contains(&"rpc_nodes list cannot be empty for network 'Ethereum'".to_string()));
+    assert!(problems.contains(&"addresses list cannot be empty for network 'Ethereum'".to_string()));
+    assert!(problems.contains(&"telegram daily_report time '9am' must be HH:MM".to_string()));
+    assert!(problems.contains(&"webhook retry.max_attempts must be between 1 and 10 for 'https://example.com/hook'".to_string()));
+}
+
+#[tokio::test]
+async fn test_address_diagnostics_flag_checksums_and_unquoted_numbers() {
+    let yaml = r#"
+interval_secs: 60
+networks:
+  - name: Ethereum
+    chain_id: 1
+    rpc_nodes: ["https://eth.llamarpc.com"]
+    addresses:
+      - alias: Vitalik
+        address: 0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045
+      - alias: Lowercase
+        address: "0xdac17f958d2ee523a2206206994597c13d831ec7"
+      - alias: Typo
+        address: "0xD8dA6BF26964aF9D7eEd9e03E53415D37aA96045"
+      - alias: Burn
+        address: 0x0000000000000000000000000000000000000000
+tron:
+  - name: Tron
+    api_url: https://api.trongrid.io
+    addresses:
+      - alias: Hot wallet
+        address: TLa2f6VPqDgRE67v1736s7bJ8Ray5wYjU7
+"#;
+
+    let diagnostics = address_diagnostics(&serde_yaml::from_str(yaml).unwrap());
+    assert_eq!(diagnostics.len(), 3);
+    assert_eq!(diagnostics[0].severity, Severity::Warning);
+    assert!(diagnostics[0].message.contains("use 0xdAC17F958D2ee523a2206206994597C13D831ec7"));
+    assert_eq!(diagnostics[1].severity, Severity::Error);
+    assert!(diagnostics[1].message.starts_with("networks[0].addresses[2].address: checksum mismatch"));
+    assert_eq!(diagnostics[2].message, "networks[0].addresses[3].address: address must be quoted");
+
+    // The number also fails to parse as a config; the warning alone doesn't
+    let errors = diagnose_config(yaml, true).await.into_iter().filter(|d| d.is_error()).count();
+    assert_eq!(errors, 3);
+}
+
+#[test]
+fn test_expand_env_substitutes_variables() {
+    std::env::set_var("OXWATCHER_TEST_RPC_KEY", "abc123");
+    let mut yaml: serde_yaml::Value = serde_yaml::from_str(
+        "url: https://eth-mainnet.g.alchemy.com/v2/${OXWATCHER_TEST_RPC_KEY}\n\
+         interval: ${OXWATCHER_TEST_UNSET:-60}\n\
+         literal: $${NOT_A_VARIABLE}\n\
+         # bot_token: ${OXWATCHER_TEST_COMMENTED_OUT}\n",
+    )
+    .unwrap();
+    expand_env(&mut yaml).unwrap();
+
+    assert_eq!(
+        yaml,
+        serde_yaml::from_str::<serde_yaml::Value>(
+            "url: https://eth-mainnet.g.alchemy.com/v2/abc123\n\
+             interval: \"60\"\n\
+             literal: \"${NOT_A_VARIABLE}\"\n"
+        )
+        .unwrap()
+    );
+
+    let mut yaml = serde_yaml::from_str("a: ${OXWATCHER_TEST_MISSING_A}\nb: ${OXWATCHER_TEST_MISSING_B}").unwrap();
+    let error = expand_env(&mut yaml).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "environment variable(s) used in config are not set: OXWATCHER_TEST_MISSING_A, OXWATCHER_TEST_MISSING_B"
+    );
+}
+
+#[test]
+fn test_env_values_with_yaml_syntax_are_kept_whole() {
+    std::env::set_var("OXWATCHER_TEST_SMTP_PASSWORD", "p #1: *x\n&y");
+    std::env::set_var("OXWATCHER_TEST_INTERVAL", "90");
+    let yaml = r#"
+interval_secs: ${OXWATCHER_TEST_INTERVAL}
+telegram:
+  bot_token: ${OXWATCHER_TEST_SMTP_PASSWORD}   # see ${OXWATCHER_TEST_ONLY_IN_COMMENT}
+networks:
+  - name: Ethereum
+    chain_id: 1
+    rpc_nodes: ["https://eth.llamarpc.com"]
+    addresses: []
+"#;
+
+    // The unset variable in the comment doesn't stop the config from loading
+    let config = Config::parse(yaml).unwrap();
+    assert_eq!(config.interval, std::time::Duration::from_secs(90));
+    assert_eq!(config.telegram.unwrap().bot_token, "p #1: *x\n&y");
+}
+
+#[test]
+fn test_number_shaped_env_secrets_stay_as_written() {
+    for secret in ["0x1F", "+1", "1e3", "1_000", "true", "007"] {
+        std::env::set_var("OXWATCHER_TEST_NUMERIC_SECRET", secret);
+        let yaml = r#"
+interval_secs: ${OXWATCHER_TEST_INTERVAL:-90}
+telegram:
+  bot_token: ${OXWATCHER_TEST_NUMERIC_SECRET}
+networks:
+  - name: Ethereum
+    chain_id: ${OXWATCHER_TEST_CHAIN_ID:-0x1}
+    rpc_nodes: ["https://eth.llamarpc.com"]
+    addresses: []
+"#;
+        let config = Config::parse(yaml).unwrap();
+        assert_eq!(config.telegram.unwrap().bot_token, secret);
+        // Number settings still read numbers from variables
+        assert_eq!(config.interval, std::time::Duration::from_secs(90));
+        assert_eq!(config.networks[0].chain_id, 1);
+    }
+}
+
+#[test]
+fn test_secrets_are_read_from_files() {
+    let path = std::env::temp_dir().join(format!("oxwatcher_bot_token_{}", std::process::id()));
+    std::fs::write(&path, "123456:secret-token\n").unwrap();
+
+    let yaml = format!(
+        r#"
+interval_secs: 60
+networks:
+  - name: Ethereum
+    chain_id: 1
+    rpc_nodes: ["https://eth.llamarpc.com"]
+    addresses: []
+telegram:
+  bot_token_file: {}
+"#,
+        path.display()
+    );
+    let config = Config::parse(&yaml).unwrap();
+    assert_eq!(config.telegram.unwrap().bot_token, "123456:secret-token");
+
+    let both = yaml.replace("  bot_token_file:", "  bot_token: inline\n  bot_token_file:");
+    assert!(Config::parse(&both).unwrap_err().to_string().contains("bot_token and bot_token_file are both set"));
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_address_books_are_shared_across_networks() {
+    let path = std::env::temp_dir().join(format!("oxwatcher_address_book_{}.yaml", std::process::id()));
+    std::fs::write(
+        &path,
+        "- alias: Cold Wallet\n  address: \"0x00000000219ab540356cBB839Cbe05303d7705Fa\"\n",
+    )
+    .unwrap();
+
+    let yaml = format!(
+        r#"
+interval_secs: 60
+address_books:
+  treasury:
+    - alias: Hot Wallet
+      address: 0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045
+      min_balance_eth: 1.0
+  cold: {}
+networks:
+  - name: Ethereum
+    chain_id: 1
+    rpc_nodes: ["https://eth.llamarpc.com"]
+    address_books: [treasury, cold]
+  - name: Base
+    chain_id: 8453
+    rpc_nodes: ["https://mainnet.base.org"]
+    address_books: [treasury]
+    addresses:
+      - alias: Hot Wallet (Base)
+        address: 0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045
+        min_balance_eth: 0.05
+"#,
+        path.display()
+    );
+    let config = Config::parse(&yaml).unwrap();
+
+    let ethereum: Vec<&str> = config.networks[0].addresses.iter().map(|a| a.alias.as_str()).collect();
+    assert_eq!(ethereum, vec!["Hot Wallet", "Cold Wallet"]);
+    assert_eq!(config.networks[1].addresses.len(), 1);
+    assert_eq!(config.networks[1].addresses[0].alias, "Hot Wallet (Base)");
+    assert_eq!(config.networks[1].addresses[0].min_balance_eth, Some(0.05));
+
+    let unknown = yaml.replace("address_books: [treasury]\n", "address_books: [ops]\n");
+    assert_eq!(
+        Config::parse(&unknown).unwrap_err().to_string(),
+        "network 'Base' uses unknown address book 'ops'"
+    );
+    std::fs::remove_file(path).unwrap();
+}
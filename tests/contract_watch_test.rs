@@ -0,0 +1,83 @@
+use alloy::primitives::{address, b256, Address, Bytes, U256};
+use alloy::providers::ProviderBuilder;
+use alloy::rpc::json_rpc::ErrorPayload;
+use alloy::transports::mock::Asserter;
+use Oxwatcher::{read_contract_state, ContractState, ContractWatcher, SafeState, SafeWatcher};
+
+const PROXY: Address = address!("A0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48");
+
+fn state(implementation: Address) -> ContractState {
+    ContractState {
+        code_hash: b256!("c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470"),
+        owner: None,
+        implementation: Some(implementation),
+        admin: Some(address!("807a96288A1A408dBC13DE2b1d087d10356395d2")),
+    }
+}
+
+#[tokio::test]
+async fn test_contract_watcher_reports_proxy_upgrade() {
+    let watcher = ContractWatcher::new(vec![]);
+    let v1 = address!("43506849D7C04F9138D1A2050bbF3A0c054402dd");
+    let v2 = address!("a2327a938Febf5FEC13baCFb16Ae10EcBc4cbDCF");
+
+    // First reading is the baseline
+    assert!(watcher.record(PROXY, state(v1)).await.is_none());
+    assert!(watcher.record(PROXY, state(v1)).await.is_none());
+
+    let mut upgraded = state(v2);
+    upgraded.owner = Some(v1);
+    let changes = watcher.record(PROXY, upgraded).await.unwrap();
+    assert_eq!(changes.len(), 2);
+    assert_eq!(changes[0].field, "owner");
+    assert_eq!(changes[0].old, "none");
+    assert_eq!(changes[1].field, "implementation");
+    assert_eq!(changes[1].new, "0xa2327a938Febf5FEC13baCFb16Ae10EcBc4cbDCF");
+}
+
+#[tokio::test]
+async fn test_safe_watcher_reports_owner_and_threshold_changes() {
+    let alice = address!("1111111111111111111111111111111111111111");
+    let bob = address!("2222222222222222222222222222222222222222");
+    let carol = address!("3333333333333333333333333333333333333333");
+    let watcher = SafeWatcher::new(vec![]);
+
+    let before = SafeState { owners: vec![alice, bob], threshold: 2, nonce: 10 };
+    assert!(watcher.record(PROXY, before.clone()).await.is_none());
+
+    // Executed transactions alone don't change the signing setup
+    assert!(watcher.record(PROXY, SafeState { nonce: 11, ..before }).await.is_none());
+
+    let change = watcher
+        .record(PROXY, SafeState { owners: vec![alice, carol], threshold: 1, nonce: 12 })
+        .await
+        .unwrap();
+    assert_eq!(change.added_owners, vec![carol]);
+    assert_eq!(change.removed_owners, vec![bob]);
+    assert_eq!(change.threshold, Some((2, 1)));
+    assert_eq!(change.lines().last().unwrap(), "Signers: 1 of 2 (nonce 12)");
+}
+
+#[tokio::test]
+async fn test_only_reverted_owner_call_reads_as_no_owner() {
+    let reading = |error: ErrorPayload| async move {
+        let asserter = Asserter::new();
+        let provider = ProviderBuilder::new().connect_mocked_client(asserter.clone());
+        asserter.push_success(&Bytes::from_static(&[0x60, 0x80]));
+        asserter.push_failure(error);
+        asserter.push_success(&U256::ZERO);
+        asserter.push_success(&U256::ZERO);
+        read_contract_state(&provider, PROXY).await
+    };
+
+    let reverted = ErrorPayload { code: 3, message: "execution reverted".into(), data: None };
+    assert_eq!(reading(reverted).await.unwrap().owner, None);
+    let reverted = ErrorPayload { code: -32000, message: "execution reverted".into(), data: None };
+    assert_eq!(reading(reverted).await.unwrap().owner, None);
+
+    // A busy node fails the reading instead of reporting the owner as removed
+    let rate_limited = ErrorPayload { code: 429, message: "Too Many Requests".into(), data: None };
+    assert!(reading(rate_limited).await.is_err());
+    let overloaded = ErrorPayload { code: -32603, message: "internal error".into(), data: None };
+    assert!(reading(overloaded).await.is_err());
+}
@@ -0,0 +1,42 @@
+use alloy::primitives::{address, U256};
+use Oxwatcher::{to_units, AddressConfig, CorridorConfig, CorridorEvent, CorridorMonitor};
+
+fn monitor() -> CorridorMonitor {
+    let mut hot_wallet = AddressConfig::new("hot wallet", address!("d8dA6BF26964aF9D7eEd9e03E53415D37aA96045"));
+    hot_wallet.corridor = Some(CorridorConfig { min: Some(5.0), max: Some(50.0) });
+    let treasury = AddressConfig::new("treasury", address!("d8dA6BF26964aF9D7eEd9e03E53415D37aA96045"));
+    CorridorMonitor::new(&[hot_wallet, treasury])
+}
+
+fn eth(amount: f64) -> U256 {
+    to_units(amount, 18)
+}
+
+#[tokio::test]
+async fn test_leaving_the_corridor_is_reported_once_per_side() {
+    let monitor = monitor();
+
+    assert_eq!(monitor.check("hot wallet", eth(20.0), 18).await, None);
+    assert_eq!(monitor.check("hot wallet", eth(80.0), 18).await, Some(CorridorEvent::Above { max: 50.0 }));
+    assert_eq!(monitor.check("hot wallet", eth(90.0), 18).await, None);
+
+    // Swinging straight to the other side is reported as well
+    assert_eq!(monitor.check("hot wallet", eth(1.0), 18).await, Some(CorridorEvent::Below { min: 5.0 }));
+    assert_eq!(monitor.check("hot wallet", eth(10.0), 18).await, Some(CorridorEvent::Inside));
+    assert_eq!(monitor.check("hot wallet", eth(12.0), 18).await, None);
+
+    // One wei under the minimum is outside
+    assert_eq!(monitor.check("hot wallet", eth(5.0) - U256::from(1), 18).await, Some(CorridorEvent::Below { min: 5.0 }));
+}
+
+#[tokio::test]
+async fn test_open_ended_corridors_and_addresses_without_one() {
+    let mut inflow_only = AddressConfig::new("deposit", address!("d8dA6BF26964aF9D7eEd9e03E53415D37aA96045"));
+    inflow_only.corridor = Some(CorridorConfig { min: None, max: Some(100.0) });
+    let deposit = CorridorMonitor::new(&[inflow_only]);
+    assert_eq!(deposit.check("deposit", U256::ZERO, 18).await, None);
+    assert!(matches!(deposit.check("deposit", eth(101.0), 18).await, Some(CorridorEvent::Above { .. })));
+
+    assert_eq!(monitor().check("treasury", eth(1_000_000.0), 18).await, None);
+    assert!(CorridorMonitor::new(&[]).is_empty());
+}
@@ -0,0 +1,133 @@
+use alloy::primitives::U256;
+use std::sync::Arc;
+use Oxwatcher::{CosmosChainConfig, CosmosCoin, CosmosEvent, CosmosMonitor, CosmosStates, FileBackend, StateBackend};
+
+const ATOM_IBC: &str = "ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2";
+
+fn monitor() -> CosmosMonitor {
+    let config: CosmosChainConfig = serde_yaml::from_str(&format!(
+        r#"
+name: Osmosis
+chain_id: osmosis-1
+lcd_url: https://lcd.osmosis.zone/
+denoms:
+  - denom: uosmo
+    symbol: OSMO
+  - denom: {ATOM_IBC}
+    symbol: ATOM
+addresses:
+  - alias: Operator
+    address: osmo1qqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqpqg3ft
+    min_balances:
+      OSMO: 10
+    rewards_above:
+      OSMO: 5
+"#
+    ))
+    .unwrap();
+    CosmosMonitor::new(config)
+}
+
+fn coin(denom: &str, amount: u64) -> CosmosCoin {
+    CosmosCoin { denom: denom.to_string(), amount: U256::from(amount) }
+}
+
+#[tokio::test]
+async fn test_cosmos_balance_changes_use_denom_symbols() {
+    let monitor = monitor();
+    let address = &monitor.config().addresses[0];
+
+    // First reading is the baseline
+    let events = monitor.record(address, &[coin("uosmo", 20_000_000)], &[]).await;
+    assert!(events.is_empty());
+
+    // An IBC token arrives
+    let events = monitor.record(address, &[coin("uosmo", 20_000_000), coin(ATOM_IBC, 1_500_000)], &[]).await;
+    assert_eq!(
+        events,
+        vec![CosmosEvent::Changed { symbol: "ATOM".to_string(), old: "0.000000".to_string(), new: "1.500000".to_string() }]
+    );
+}
+
+#[tokio::test]
+async fn test_cosmos_low_balance_includes_missing_denom() {
+    let monitor = monitor();
+    let address = &monitor.config().addresses[0];
+
+    // The LCD omits denoms with a zero balance
+    let events = monitor.record(address, &[], &[]).await;
+    assert!(matches!(&events[..], [CosmosEvent::LowBalance { symbol, alert_number: 1, .. }] if symbol == "OSMO"));
+
+    // Throttled until the next scheduled reminder
+    let events = monitor.record(address, &[coin("uosmo", 5_000_000)], &[]).await;
+    assert!(!events.iter().any(|event| matches!(event, CosmosEvent::LowBalance { .. })));
+
+    // Recovery resets the schedule
+    monitor.record(address, &[coin("uosmo", 50_000_000)], &[]).await;
+    let events = monitor.record(address, &[coin("uosmo", 1_000_000)], &[]).await;
+    assert!(events.iter().any(|event| matches!(event, CosmosEvent::LowBalance { alert_number: 1, .. })));
+}
+
+#[tokio::test]
+async fn test_cosmos_rewards_alert_once_per_crossing() {
+    let monitor = monitor();
+    let address = &monitor.config().addresses[0];
+    let balances = [coin("uosmo", 20_000_000)];
+
+    assert!(monitor.record(address, &balances, &[coin("uosmo", 1_000_000)]).await.is_empty());
+
+    let events = monitor.record(address, &balances, &[coin("uosmo", 6_000_000)]).await;
+    assert!(matches!(&events[..], [CosmosEvent::RewardsReady { symbol, .. }] if symbol == "OSMO"));
+    assert!(monitor.record(address, &balances, &[coin("uosmo", 7_000_000)]).await.is_empty());
+
+    // Claimed, then accrued again
+    assert!(monitor.record(address, &balances, &[]).await.is_empty());
+    assert!(monitor.record(address, &balances, &[coin("uosmo", 6_000_000)]).await.len() == 1);
+}
+
+#[tokio::test]
+async fn test_cosmos_state_survives_a_restart() {
+    let data_dir = std::env::temp_dir().join(format!("oxwatcher-cosmos-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&data_dir);
+    std::fs::create_dir_all(&data_dir).unwrap();
+    let backend: Arc<dyn StateBackend> = Arc::new(FileBackend::new(&data_dir));
+
+    let running = monitor().with_states(CosmosStates::new(Arc::clone(&backend)).await);
+    let address = &running.config().addresses[0];
+    let events = running.record(address, &[coin("uosmo", 5_000_000)], &[]).await;
+    assert!(matches!(&events[..], [CosmosEvent::LowBalance { alert_number: 1, .. }]));
+
+    // The balance moved while the watcher was down: the change is reported and the
+    // low balance reminder stays throttled
+    let restarted = monitor().with_states(CosmosStates::new(Arc::clone(&backend)).await);
+    let address = &restarted.config().addresses[0];
+    let events = restarted.record(address, &[coin("uosmo", 4_000_000)], &[]).await;
+    assert_eq!(
+        events,
+        vec![CosmosEvent::Changed { symbol: "OSMO".to_string(), old: "5.000000".to_string(), new: "4.000000".to_string() }]
+    );
+
+    let _ = std::fs::remove_dir_all(&data_dir);
+}
+
+#[tokio::test]
+async fn test_cosmos_check_reports_lcd_errors() {
+    let config: CosmosChainConfig = serde_yaml::from_str(
+        r#"
+name: Osmosis
+chain_id: osmosis-1
+lcd_url: http://127.0.0.1:9/
+addresses:
+  - alias: Operator
+    address: osmo1qqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqpqg3ft
+"#,
+    )
+    .unwrap();
+    let monitor = CosmosMonitor::new(config);
+
+    // Failed addresses are returned with their error, for the health endpoints
+    let results = monitor.check().await;
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].0.alias, "Operator");
+    assert!(results[0].1.is_err());
+}
@@ -0,0 +1,112 @@
+use alloy::providers::ProviderBuilder;
+use async_trait::async_trait;
+use serde_json::json;
+use Oxwatcher::{
+    AlertSeverity, CheckContext, Config, CustomAlert, CustomCheck, CustomChecks, MonitorAlert, NetworkConfig, Watcher,
+};
+
+fn network(name: &str) -> NetworkConfig {
+    serde_yaml::from_str(&format!(
+        r#"
+name: {}
+chain_id: 1
+rpc_nodes: ["http://localhost:8545"]
+addresses:
+  - alias: treasury
+    address: "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045"
+"#,
+        name
+    ))
+    .unwrap()
+}
+
+/// Raises the same critical alert every cycle, on Ethereum only
+struct ReserveCheck;
+
+#[async_trait]
+impl CustomCheck for ReserveCheck {
+    fn name(&self) -> &str {
+        "reserve"
+    }
+
+    fn applies_to(&self, network: &NetworkConfig) -> bool {
+        network.name == "Ethereum"
+    }
+
+    async fn run(&self, context: &CheckContext<'_>) -> eyre::Result<Vec<CustomAlert>> {
+        let alert = MonitorAlert {
+            kind: "reserve".to_string(),
+            network_name: context.network.name.clone(),
+            chain_id: Some(context.network.chain_id),
+            title: "Reserve below target".to_string(),
+            lines: vec![format!("{} balances read", context.balances.len())],
+            data: json!({}),
+            dedup_id: None,
+        };
+        Ok(vec![CustomAlert::new(alert).with_severity(AlertSeverity::Critical).with_channels(vec!["telegram".to_string()])])
+    }
+}
+
+struct FailingCheck;
+
+#[async_trait]
+impl CustomCheck for FailingCheck {
+    fn name(&self) -> &str {
+        "failing"
+    }
+
+    async fn run(&self, _context: &CheckContext<'_>) -> eyre::Result<Vec<CustomAlert>> {
+        eyre::bail!("contract reverted")
+    }
+}
+
+async fn run(checks: &CustomChecks, network: &NetworkConfig, now: u64) -> Vec<CustomAlert> {
+    let provider = ProviderBuilder::new().connect_http("http://localhost:8545".parse().unwrap());
+    checks.run(&CheckContext { network, provider: &provider, balances: &[], now }).await
+}
+
+#[tokio::test]
+async fn test_custom_checks_run_where_they_apply_and_repeat_after_cooldown() {
+    let checks = CustomChecks::new().with_check(FailingCheck).with_check(ReserveCheck).with_repeat_after(600);
+    assert_eq!(checks.names(), ["failing", "reserve"]);
+
+    let ethereum = network("Ethereum");
+
+    // A failing check doesn't keep the others from alerting
+    let alerts = run(&checks, &ethereum, 1_000).await;
+    assert_eq!(alerts.len(), 1);
+    assert_eq!(alerts[0].severity, AlertSeverity::Critical);
+    assert_eq!(alerts[0].channels, ["telegram"]);
+    assert_eq!(alerts[0].alert.lines, ["0 balances read"]);
+
+    // The same alert is held back until the cooldown passed
+    assert!(run(&checks, &ethereum, 1_300).await.is_empty());
+    assert_eq!(run(&checks, &ethereum, 1_600).await.len(), 1);
+
+    // Checks only run on networks they apply to
+    assert!(run(&checks, &network("Base"), 2_000).await.is_empty());
+}
+
+#[test]
+fn test_checks_register_on_the_watcher() {
+    let config: Config = serde_yaml::from_str(
+        r#"
+interval_secs: 60
+networks:
+  - name: Ethereum
+    chain_id: 1
+    rpc_nodes: ["http://localhost:8545"]
+    addresses:
+      - alias: treasury
+        address: "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045"
+"#,
+    )
+    .unwrap();
+
+    let watcher = Watcher::new(config).register_check(ReserveCheck).register_check(FailingCheck);
+    assert_eq!(watcher.custom_checks().names(), ["reserve", "failing"]);
+
+    // A replaced set drops the checks registered before
+    let watcher = watcher.with_custom_checks(CustomChecks::new().with_check(FailingCheck));
+    assert_eq!(watcher.custom_checks().names(), ["failing"]);
+}
@@ -0,0 +1,120 @@
+use alloy::primitives::{address, Address, TxHash, U256};
+use Oxwatcher::{
+    unexpected_destinations, AlertSeverity, AuditSubject, BalanceChange, BalanceChangeSummary, Config, MonitorAlert,
+    TokenBalanceChange, TransferDirection, TxAttribution, UNEXPECTED_DESTINATION_KIND, UNKNOWN_DESTINATION_KIND,
+};
+
+const EXCHANGE: Address = address!("28C6c06298d514Db089934071355E5743bf21d60");
+const UNKNOWN: Address = address!("098B716B8Aaf21512996dC57EB0615e2383E2f96");
+
+fn transfer(direction: TransferDirection, counterparty: Option<Address>, value: u64) -> TxAttribution {
+    TxAttribution {
+        tx_hash: TxHash::with_last_byte(1),
+        block_number: 100,
+        asset: "ETH".to_string(),
+        direction,
+        counterparty,
+        value: U256::from(value),
+        url: None,
+    }
+}
+
+#[test]
+fn test_outgoing_transfers_outside_the_allow_list_are_unexpected() {
+    let transactions = [
+        transfer(TransferDirection::Outgoing, Some(EXCHANGE), 5),
+        transfer(TransferDirection::Outgoing, Some(UNKNOWN), 5),
+        transfer(TransferDirection::Incoming, Some(UNKNOWN), 5),
+        // Gas paid for a token transfer moves no value
+        transfer(TransferDirection::Outgoing, Some(UNKNOWN), 0),
+        // Contract creation has no destination to allow
+        transfer(TransferDirection::Outgoing, None, 5),
+    ];
+    let unexpected = unexpected_destinations(&transactions, &[EXCHANGE]);
+    let destinations: Vec<Option<Address>> = unexpected.iter().map(|tx| tx.counterparty).collect();
+    assert_eq!(destinations, [Some(UNKNOWN), None]);
+}
+
+#[test]
+fn test_unexpected_destination_alert_is_critical() {
+    let changes = BalanceChangeSummary {
+        network_name: "Ethereum".to_string(),
+        chain_id: 1,
+        alias: "cold".to_string(),
+        address: "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045".to_string(),
+        eth_change: None,
+        token_changes: vec![],
+        nft_changes: vec![],
+        transactions: vec![],
+    };
+    let alert = MonitorAlert::unexpected_destination(&changes, &transfer(TransferDirection::Outgoing, Some(UNKNOWN), 5));
+    assert_eq!(alert.kind, UNEXPECTED_DESTINATION_KIND);
+    assert_eq!(alert.title, "🚨 cold sent funds to an unexpected destination");
+    assert_eq!(alert.data["transaction"]["counterparty"], format!("{:?}", UNKNOWN));
+    assert_eq!(AuditSubject::alert(&alert).severity, Some(AlertSeverity::Critical));
+}
+
+/// Drop of `asset` from 10 to 4
+fn decrease(asset: &str) -> TokenBalanceChange {
+    TokenBalanceChange {
+        alias: asset.to_string(),
+        old_balance: U256::from(10),
+        new_balance: U256::from(4),
+        old_formatted: "10".to_string(),
+        new_formatted: "4".to_string(),
+        decimals: 0,
+        usd_value: None,
+        change: BalanceChange::Decrease,
+    }
+}
+
+#[test]
+fn test_outflows_without_a_found_transaction_have_an_unknown_destination() {
+    let mut usdc = transfer(TransferDirection::Outgoing, Some(EXCHANGE), 6);
+    usdc.asset = "USDC".to_string();
+    let mut changes = BalanceChangeSummary {
+        network_name: "Ethereum".to_string(),
+        chain_id: 1,
+        alias: "cold".to_string(),
+        address: "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045".to_string(),
+        eth_change: Some(decrease("ETH")),
+        token_changes: vec![decrease("USDC"), decrease("DAI")],
+        nft_changes: vec![],
+        // Gas paid for the USDC transfer accounts for the ETH drop
+        transactions: vec![usdc, transfer(TransferDirection::Outgoing, Some(UNKNOWN), 0)],
+    };
+    let unattributed: Vec<&str> = changes.unattributed_outflows().iter().map(|change| change.alias.as_str()).collect();
+    assert_eq!(unattributed, ["DAI"]);
+
+    // A failed or too short lookup leaves every drop unexplained
+    changes.transactions.clear();
+    let unattributed: Vec<&str> = changes.unattributed_outflows().iter().map(|change| change.alias.as_str()).collect();
+    assert_eq!(unattributed, ["ETH", "USDC", "DAI"]);
+
+    let alert = MonitorAlert::unknown_destination(&changes, &decrease("DAI"));
+    assert_eq!(alert.kind, UNKNOWN_DESTINATION_KIND);
+    assert_eq!(alert.title, "🚨 cold sent DAI to an unknown destination");
+    assert_eq!(AuditSubject::alert(&alert).severity, Some(AlertSeverity::Critical));
+}
+
+#[test]
+fn test_allowed_destinations_need_tx_lookup() {
+    let config: Config = serde_yaml::from_str(
+        r#"
+interval_secs: 60
+networks:
+  - name: Ethereum
+    chain_id: 1
+    rpc_nodes: ["https://eth.llamarpc.com"]
+    tx_lookup:
+      enabled: false
+    addresses:
+      - alias: cold
+        address: "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045"
+        allowed_destinations: ["0x28C6c06298d514Db089934071355E5743bf21d60"]
+"#,
+    )
+    .unwrap();
+    assert_eq!(config.networks[0].addresses[0].allowed_destinations, [EXCHANGE]);
+    assert_eq!(config.problems(), ["address 'cold' on Ethereum: allowed_destinations needs tx_lookup enabled"]);
+}
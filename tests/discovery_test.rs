@@ -0,0 +1,118 @@
+use alloy::primitives::{Address, Bytes, B256, U256};
+use alloy::providers::ProviderBuilder;
+use alloy::rpc::client::RpcClient;
+use alloy::rpc::json_rpc::{RequestPacket, ResponsePacket, SerializedRequest};
+use alloy::sol_types::{SolCall, SolEvent, SolValue};
+use alloy::transports::{TransportError, TransportFut};
+use serde_json::{json, Value};
+use std::sync::{Arc, Mutex};
+use tower::service_fn;
+use Oxwatcher::contracts::IERC20;
+use Oxwatcher::{discover_tokens, TokenConfig, TokenDiscoveryConfig};
+
+const HOLDER: Address = Address::with_last_byte(0xaa);
+const DAI: Address = Address::with_last_byte(1);
+const NFT: Address = Address::with_last_byte(2);
+const DUST: Address = Address::with_last_byte(3);
+const CONFIGURED: Address = Address::with_last_byte(4);
+const OTHER_USDC: Address = Address::with_last_byte(5);
+const SPAM: Address = Address::with_last_byte(6);
+const MARKUP: Address = Address::with_last_byte(7);
+
+/// Contract answers: (decimals, symbol, balance of HOLDER); None decimals reverts like an NFT
+fn token(address: Address) -> (Option<u8>, &'static str, u64) {
+    match address {
+        DAI => (Some(18), "DAI", 2_000_000_000_000_000_000),
+        DUST => (Some(18), "DUST", 1000),
+        OTHER_USDC => (Some(6), "USDC", 5_000_000),
+        SPAM => (Some(18), "Visit https://claim-rewards.example", 1_000_000_000_000_000_000),
+        MARKUP => (Some(0), "<b>&", 1),
+        _ => (None, "", 0),
+    }
+}
+
+/// Node at block 4999 whose Transfer logs show every token above reaching HOLDER;
+/// records the block ranges of eth_getLogs
+fn node(ranges: Arc<Mutex<Vec<(u64, u64)>>>) -> RpcClient {
+    let answer = move |call: &SerializedRequest| {
+        let params: Value = call.params().map(|params| serde_json::from_str(params.get()).unwrap()).unwrap_or_default();
+        let result = match call.method() {
+            "eth_blockNumber" => json!(U256::from(4999)),
+            "eth_getLogs" => {
+                let block = |key: &str| u64::from_str_radix(params[0][key].as_str().unwrap().trim_start_matches("0x"), 16).unwrap();
+                ranges.lock().unwrap().push((block("fromBlock"), block("toBlock")));
+                let logs: Vec<Value> = [DAI, NFT, DUST, CONFIGURED, OTHER_USDC, SPAM, MARKUP]
+                    .iter()
+                    .map(|token| {
+                        json!({
+                            "address": token,
+                            "topics": [IERC20::Transfer::SIGNATURE_HASH, Address::ZERO.into_word(), HOLDER.into_word()],
+                            "data": "0x",
+                            "blockNumber": "0x1",
+                            "blockHash": B256::ZERO,
+                            "transactionHash": B256::ZERO,
+                            "transactionIndex": "0x0",
+                            "logIndex": "0x0",
+                            "removed": false,
+                        })
+                    })
+                    .collect();
+                json!(logs)
+            }
+            "eth_call" => {
+                let to: Address = params[0]["to"].as_str().unwrap().parse().unwrap();
+                let input = params[0]["input"].as_str().or(params[0]["data"].as_str()).unwrap();
+                let selector = alloy::hex::decode(&input[2..10]).unwrap();
+                let (decimals, symbol, balance) = token(to);
+                let Some(decimals) = decimals else {
+                    return json!({ "jsonrpc": "2.0", "id": call.id(), "error": { "code": 3, "message": "execution reverted" } });
+                };
+                let data = if selector == IERC20::decimalsCall::SELECTOR {
+                    U256::from(decimals).abi_encode()
+                } else if selector == IERC20::symbolCall::SELECTOR {
+                    symbol.to_string().abi_encode()
+                } else {
+                    U256::from(balance).abi_encode()
+                };
+                json!(Bytes::from(data))
+            }
+            method => panic!("unexpected {}", method),
+        };
+        json!({ "jsonrpc": "2.0", "id": call.id(), "result": result })
+    };
+    let transport = service_fn(move |packet: RequestPacket| -> TransportFut<'static> {
+        let body = match &packet {
+            RequestPacket::Single(call) => answer(call),
+            RequestPacket::Batch(calls) => Value::Array(calls.iter().map(&answer).collect()),
+        };
+        Box::pin(async move { Ok::<ResponsePacket, TransportError>(serde_json::from_value(body).unwrap()) })
+    });
+    RpcClient::builder().transport(transport, false)
+}
+
+#[tokio::test]
+async fn test_tokens_received_and_held_are_discovered() {
+    let ranges = Arc::new(Mutex::new(Vec::new()));
+    let provider = ProviderBuilder::new().connect_client(node(Arc::clone(&ranges)));
+    let config: TokenDiscoveryConfig = serde_yaml::from_str("lookback_blocks: 5000\nmin_balance: 0.001").unwrap();
+    let known = [TokenConfig::new("USDC", CONFIGURED)];
+
+    let tokens = discover_tokens(&provider, None, &config, &[HOLDER], &known).await.unwrap();
+    let found: Vec<(&str, Address, Option<u8>)> = tokens.iter().map(|t| (t.alias.as_str(), t.address, t.decimals)).collect();
+    assert_eq!(
+        found,
+        [
+            ("DAI", DAI, Some(18)),
+            ("USDC 0x0000...0005", OTHER_USDC, Some(6)),
+            ("Token 0x0000...0006", SPAM, Some(18)),
+            // Symbols with markup don't become the alias
+            ("Token 0x0000...0007", MARKUP, Some(0)),
+        ]
+    );
+    assert_eq!(*ranges.lock().unwrap(), [(0, 1999), (2000, 3999), (4000, 4999)]);
+
+    // Ignored contracts are skipped
+    let config = TokenDiscoveryConfig { ignore: vec![SPAM, MARKUP], ..config };
+    let tokens = discover_tokens(&provider, None, &config, &[HOLDER], &known).await.unwrap();
+    assert_eq!(tokens.len(), 2);
+}
@@ -0,0 +1,48 @@
+use Oxwatcher::{drained_assets, BalanceInfo, Config, MonitorAlert, DRAINED_KIND};
+
+mod common;
+
+
+fn balance_info(eth: &str, usdc: &str) -> BalanceInfo {
+    BalanceInfo {
+        token_balances: vec![common::token_balance("USDC", usdc, 6)],
+        ..common::balance_info("hot wallet", eth)
+    }
+}
+
+#[test]
+fn test_balances_dropping_to_dust_are_drained() {
+    let previous = balance_info("12.5", "50000");
+
+    let drained = drained_assets(&balance_info("0", "49000"), &previous, 1.0);
+    assert_eq!(drained.len(), 1);
+    assert_eq!(drained[0].asset, "ETH");
+    assert_eq!(drained[0].old_formatted, "12.5");
+
+    // Dust left behind still counts, a large withdrawal doesn't
+    let drained = drained_assets(&balance_info("2", "400"), &previous, 1.0);
+    assert_eq!(drained.iter().map(|asset| asset.asset.as_str()).collect::<Vec<_>>(), vec!["USDC"]);
+    assert!(drained_assets(&balance_info("2", "400"), &previous, 0.0).is_empty());
+
+    // Addresses that were already empty are not drained again
+    assert!(drained_assets(&balance_info("0", "0"), &balance_info("0", "0"), 1.0).is_empty());
+}
+
+#[test]
+fn test_drained_alert_lists_assets() {
+    let current = balance_info("0", "0");
+    let drained = drained_assets(&current, &balance_info("3", "100"), 1.0);
+    let alert = MonitorAlert::drained(&current, &drained);
+
+    assert_eq!(alert.kind, DRAINED_KIND);
+    assert_eq!(alert.title, "🆘 hot wallet drained");
+    assert_eq!(alert.lines[1], "ETH: 3 → 0");
+    assert_eq!(alert.data["assets"][1]["asset"], "USDC");
+}
+
+#[test]
+fn test_drained_alerts_are_enabled_by_default() {
+    let config: Config = serde_yaml::from_str("interval_secs: 60\nnetworks: []").unwrap();
+    assert!(config.drained.enabled);
+    assert_eq!(config.drained.dust_percent, 1.0);
+}
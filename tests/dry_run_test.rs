@@ -0,0 +1,130 @@
+use async_trait::async_trait;
+use eyre::Result;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use Oxwatcher::logger::BalanceChangeSummary;
+use Oxwatcher::{
+    AuditSubject, BalanceReport, Config, DryRunNotifier, LowBalanceAlert, MonitorAlert, Notifier, NotifierSet,
+    StorageConfig,
+};
+
+/// Channel named "slack" that counts deliveries and only sends gas alerts
+#[derive(Default)]
+struct CountingNotifier {
+    sent: Arc<AtomicUsize>,
+}
+
+#[async_trait]
+impl Notifier for CountingNotifier {
+    fn name(&self) -> &str {
+        "slack"
+    }
+
+    async fn send_change_alert(&self, _changes: &BalanceChangeSummary) -> Result<()> {
+        self.sent.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+
+    async fn send_low_balance_alert(&self, _alert: &LowBalanceAlert) -> Result<()> {
+        self.sent.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+
+    async fn send_alert(&self, _alert: &MonitorAlert) -> Result<()> {
+        self.sent.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+
+    async fn send_report(&self, _report: &BalanceReport) -> Result<()> {
+        self.sent.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+
+    fn sends(&self, subject: &AuditSubject) -> bool {
+        subject.kind == "gas_price"
+    }
+}
+
+fn alert(kind: &str) -> MonitorAlert {
+    MonitorAlert {
+        kind: kind.to_string(),
+        network_name: "Ethereum".to_string(),
+        chain_id: Some(1),
+        title: "Gas above 50 gwei".to_string(),
+        lines: vec![],
+        data: serde_json::Value::Null,
+        dedup_id: None,
+    }
+}
+
+#[tokio::test]
+async fn test_dry_run_channel_keeps_name_and_filters_but_sends_nothing() {
+    let channel = CountingNotifier::default();
+    let sent = Arc::clone(&channel.sent);
+    let dry_run = DryRunNotifier::new(Box::new(channel));
+
+    assert_eq!(dry_run.name(), "slack");
+    assert!(dry_run.sends(&AuditSubject::alert(&alert("gas_price"))));
+    assert!(!dry_run.sends(&AuditSubject::alert(&alert("rule"))));
+
+    let mut notifiers = NotifierSet::new();
+    notifiers.push(Box::new(dry_run));
+    notifiers.send_alert_to(&alert("gas_price"), &["slack".to_string()]).await.unwrap();
+    notifiers.send_alert(&alert("rule")).await.unwrap();
+    assert_eq!(sent.load(Ordering::SeqCst), 0);
+}
+
+#[test]
+fn test_dry_run_state_is_a_copy_of_data_dir() {
+    let base = std::env::temp_dir().join(format!("oxwatcher-dry-run-test-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&base);
+    let data_dir = base.join("data");
+    std::fs::create_dir_all(data_dir.join("backups")).unwrap();
+    std::fs::write(data_dir.join("alert_states.json"), "{}").unwrap();
+    std::fs::create_dir_all(data_dir.join("history")).unwrap();
+    std::fs::write(data_dir.join("history/Ethereum.jsonl"), "{}\n").unwrap();
+
+    let mut config: Config = serde_yaml::from_str(&format!(
+        r#"
+interval_secs: 60
+data_dir: {}
+storage:
+  backend: postgres
+  url: postgres://localhost/oxwatcher
+networks: []
+"#,
+        data_dir.display()
+    ))
+    .unwrap();
+
+    let dry_run_dir = base.join("dry-run");
+    let guard = config.use_dry_run_dir(&dry_run_dir).unwrap();
+    assert_eq!(config.data_dir, dry_run_dir.to_string_lossy());
+    assert!(matches!(config.storage, StorageConfig::File));
+    assert_eq!(std::fs::read_to_string(dry_run_dir.join("alert_states.json")).unwrap(), "{}");
+    assert_eq!(std::fs::read_to_string(dry_run_dir.join("history/Ethereum.jsonl")).unwrap(), "{}\n");
+    assert!(!dry_run_dir.join("backups").exists());
+
+    // Writes stay in the copy
+    std::fs::write(dry_run_dir.join("alert_states.json"), "{\"states\":{}}").unwrap();
+    assert_eq!(std::fs::read_to_string(data_dir.join("alert_states.json")).unwrap(), "{}");
+
+    // The copy is removed with the guard, the real state stays
+    drop(guard);
+    assert!(!dry_run_dir.exists());
+    assert!(data_dir.join("alert_states.json").exists());
+
+    // A copy under data_dir doesn't copy itself
+    let nested = data_dir.join("dry-run");
+    let yaml = format!("interval_secs: 60\ndata_dir: {}\nnetworks: []", data_dir.display());
+    let mut config: Config = serde_yaml::from_str(&yaml).unwrap();
+    let guard = config.use_dry_run_dir(&nested).unwrap();
+    assert!(nested.join("alert_states.json").exists());
+    assert!(!nested.join("dry-run").exists());
+    drop(guard);
+
+    // Without an existing data_dir the dry run starts from empty state
+    let mut config: Config = serde_yaml::from_str("interval_secs: 60\ndata_dir: /nonexistent/oxwatcher\nnetworks: []").unwrap();
+    let _guard = config.use_dry_run_dir(&base.join("empty")).unwrap();
+    assert!(base.join("empty").is_dir());
+}
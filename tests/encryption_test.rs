@@ -0,0 +1,75 @@
+use Oxwatcher::{ChatRegistration, ChatStorage, Config, FileBackend, StateBackend, StateCipher};
+
+const KEY: &str = "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f";
+const OTHER_KEY: &str = "1f1e1d1c1b1a191817161514131211100f0e0d0c0b0a09080706050403020100";
+
+fn chats() -> ChatStorage {
+    ChatStorage {
+        registrations: vec![ChatRegistration {
+            chat_id: -1001234567890,
+            user_id: 7,
+            username: "alice".to_string(),
+            subscriptions: vec![],
+            muted_until: None,
+        }],
+    }
+}
+
+#[test]
+fn test_cipher_round_trip() {
+    let cipher = StateCipher::from_hex(KEY).unwrap();
+    let first = cipher.encrypt(b"bot token").unwrap();
+    // A fresh nonce every time
+    assert_ne!(first, cipher.encrypt(b"bot token").unwrap());
+    assert_eq!(cipher.decrypt(&first).unwrap(), b"bot token");
+
+    let other = StateCipher::from_hex(OTHER_KEY).unwrap();
+    assert!(other.decrypt(&first).is_err());
+    assert!(StateCipher::from_hex("abcd").is_err());
+    assert!(StateCipher::from_hex(&"z".repeat(64)).is_err());
+}
+
+#[test]
+fn test_config_decrypts_encrypted_values() {
+    let secret = StateCipher::from_hex(KEY).unwrap().encrypt_secret("/var/lib/oxwatcher").unwrap();
+    let config = Config::parse(&format!(
+        "interval_secs: 60\nnetworks: []\ndata_dir: \"{}\"\nencryption:\n  key: {}\n",
+        secret, KEY
+    ))
+    .unwrap();
+    assert_eq!(config.data_dir, "/var/lib/oxwatcher");
+    assert!(!config.problems().iter().any(|p| p.starts_with("encryption:")));
+
+    let error = Config::parse(&format!("interval_secs: 60\nnetworks: []\ndata_dir: \"{}\"\n", secret)).unwrap_err();
+    assert!(error.to_string().contains("no encryption.key"));
+
+    let config = Config::parse("interval_secs: 60\nnetworks: []\nencryption:\n  key: abcd\n").unwrap();
+    assert!(config.problems().iter().any(|p| p.starts_with("encryption:")));
+}
+
+#[tokio::test]
+async fn test_chats_are_encrypted_at_rest() {
+    let dir = std::env::temp_dir().join(format!("oxwatcher-encryption-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+
+    // Chats saved before encryption was enabled are still read
+    FileBackend::new(&dir).save_chats(None, &chats()).await.unwrap();
+    let backend = FileBackend::new(&dir).with_cipher(StateCipher::from_hex(KEY).unwrap());
+    assert_eq!(backend.load_chats(None).await.unwrap().registrations.len(), 1);
+
+    backend.save_chats(None, &chats()).await.unwrap();
+    let content = std::fs::read_to_string(dir.join("telegram_chats.json")).unwrap();
+    assert!(!content.contains("alice"));
+    assert!(!content.contains("1234567890"));
+    assert_eq!(backend.load_chats(None).await.unwrap().registrations[0].username, "alice");
+
+    let error = FileBackend::new(&dir).load_chats(None).await.unwrap_err();
+    assert!(error.to_string().contains("is encrypted"));
+    let wrong_key = FileBackend::new(&dir).with_cipher(StateCipher::from_hex(OTHER_KEY).unwrap());
+    assert!(wrong_key.load_chats(None).await.is_err());
+
+    // Other state stays readable without the key
+    backend.save_alert_states(&Default::default()).await.unwrap();
+    assert!(FileBackend::new(&dir).load_alert_states().await.is_ok());
+}
@@ -0,0 +1,99 @@
+use Oxwatcher::{BalanceInfo, Config, Entities, EntityEvent, Portfolio};
+
+mod common;
+
+
+fn config() -> Config {
+    serde_yaml::from_str(
+        r#"
+interval_secs: 60
+networks:
+  - name: Ethereum
+    chain_id: 1
+    rpc_nodes: ["https://eth.llamarpc.com"]
+    addresses:
+      - alias: relayer-1
+        address: "0x1111111111111111111111111111111111111111"
+      - alias: treasury
+        address: "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045"
+  - name: Base
+    chain_id: 8453
+    rpc_nodes: ["https://mainnet.base.org"]
+    addresses:
+      - alias: relayer
+        address: "0x1111111111111111111111111111111111111111"
+      - alias: base treasury
+        address: "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045"
+entities:
+  - name: relayer-1
+    address: "0x1111111111111111111111111111111111111111"
+    min_total_usd: 1000
+  - name: treasury
+    aliases: [treasury, base treasury]
+"#,
+    )
+    .unwrap()
+}
+
+fn balance_info(network: &str, alias: &str, usd: Option<f64>) -> BalanceInfo {
+    BalanceInfo { network_name: network.to_string(), eth_usd_value: usd, ..common::balance_info(alias, "0") }
+}
+
+#[test]
+fn test_entities_match_addresses_by_address_or_alias() {
+    let config = config();
+    assert!(config.problems().is_empty(), "{:?}", config.problems());
+    let entities = Entities::new(&config.entities, &config.networks);
+
+    assert_eq!(entities.names(), ["relayer-1", "treasury"]);
+    assert_eq!(entities.entity_of("Base", "relayer"), Some("relayer-1"));
+    assert_eq!(entities.entity_of("Base", "base treasury"), Some("treasury"));
+    assert_eq!(entities.members("relayer-1").unwrap().len(), 2);
+    assert!(entities.members("unknown").is_none());
+
+    let balances = [
+        balance_info("Ethereum", "relayer-1", Some(300.0)),
+        balance_info("Base", "relayer", Some(200.0)),
+        balance_info("Ethereum", "treasury", Some(10_000.0)),
+    ];
+    let portfolio = Portfolio::build(&balances, None).with_entities(&entities);
+    assert_eq!(portfolio.entities.len(), 2);
+    assert_eq!(portfolio.entities[0].name, "relayer-1");
+    assert_eq!(portfolio.entities[0].addresses, 2);
+    assert_eq!(portfolio.entities[0].total.usd, 500.0);
+    assert_eq!(portfolio.entities[1].addresses, 1);
+}
+
+#[test]
+fn test_entity_alerts_once_when_its_usd_total_drops_below_the_minimum() {
+    let config = config();
+    let entities = Entities::new(&config.entities, &config.networks);
+    assert!(entities.has_thresholds());
+
+    let balances = [balance_info("Ethereum", "relayer-1", Some(600.0)), balance_info("Base", "relayer", Some(300.0))];
+    let events = entities.check(&balances);
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].0.networks, ["Base", "Ethereum"]);
+    assert_eq!(events[0].0.usd, 900.0);
+    assert_eq!(events[0].1, EntityEvent::Below { min: 1000.0 });
+    assert!(entities.check(&balances).is_empty());
+
+    // Without a price on every network the total is unknown and nothing changes
+    let unpriced = [balance_info("Ethereum", "relayer-1", Some(900.0)), balance_info("Base", "relayer", None)];
+    assert!(entities.check(&unpriced).is_empty());
+
+    let balances = [balance_info("Ethereum", "relayer-1", Some(900.0)), balance_info("Base", "relayer", Some(300.0))];
+    assert_eq!(entities.check(&balances)[0].1, EntityEvent::Recovered { min: 1000.0 });
+}
+
+#[test]
+fn test_entity_validation() {
+    let mut config = config();
+    config.entities.push(config.entities[0].clone());
+    config.entities.push(serde_yaml::from_str("name: nobody\naliases: [missing]\nmin_total_usd: 0").unwrap());
+
+    let problems = config.problems();
+    assert!(problems.contains(&"entity 'relayer-1' is defined more than once".to_string()), "{:?}", problems);
+    assert!(problems.contains(&"entity 'nobody' matches no address, set its address or aliases".to_string()));
+    assert!(problems.contains(&"entity 'nobody': min_total_usd must be positive".to_string()));
+}
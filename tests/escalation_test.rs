@@ -0,0 +1,140 @@
+use std::sync::Arc;
+use Oxwatcher::{
+    AckOutcome, EscalationConfig, EscalationStep, Escalator, FileBackend, MonitorAlert, PagerDutyClient, PagerDutyConfig,
+    StateBackend,
+};
+
+const MINUTE: u64 = 60;
+
+fn config() -> EscalationConfig {
+    EscalationConfig {
+        ack_timeout_mins: 15,
+        resends: 1,
+        telegram_chats: vec![-100123],
+        channels: vec![],
+        pagerduty: None,
+    }
+}
+
+fn backend(name: &str) -> Arc<dyn StateBackend> {
+    let dir = std::env::temp_dir().join(format!("oxwatcher-escalation-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    Arc::new(FileBackend::new(dir))
+}
+
+fn drained() -> MonitorAlert {
+    MonitorAlert {
+        kind: "rule".to_string(),
+        network_name: "Ethereum".to_string(),
+        chain_id: Some(1),
+        title: "🚨 Treasury drained: treasury".to_string(),
+        lines: vec!["Balance: 2.0 → 0.5 ETH".to_string()],
+        data: serde_json::Value::Null,
+        dedup_id: None,
+    }
+}
+
+#[tokio::test]
+async fn test_unacknowledged_alert_is_resent_then_escalated_once() {
+    let escalator = Escalator::new(&config(), backend("steps")).await;
+    let id = escalator.open(&drained(), &["telegram".to_string()], 0).await;
+    assert_eq!(id, 1);
+
+    assert!(escalator.due(14 * MINUTE).await.is_empty());
+
+    let steps = escalator.due(15 * MINUTE).await;
+    assert!(matches!(steps.as_slice(), [EscalationStep::Resend(tracked)] if tracked.id == id));
+    let resent = steps[0].alert(15 * MINUTE);
+    assert_eq!(resent.title, "🔁 Unacknowledged: 🚨 Treasury drained: treasury");
+    assert_eq!(steps[0].tracked().channels, vec!["telegram".to_string()]);
+
+    assert!(escalator.due(29 * MINUTE).await.is_empty());
+    let steps = escalator.due(30 * MINUTE).await;
+    assert!(matches!(steps.as_slice(), [EscalationStep::Escalate(_)]));
+    assert!(steps[0].alert(30 * MINUTE).lines.contains(&"Raised 30 min ago, not acknowledged".to_string()));
+
+    // Escalated alerts wait for an acknowledgement without further steps
+    assert!(escalator.due(60 * MINUTE).await.is_empty());
+    assert_eq!(escalator.unacknowledged().await.len(), 1);
+}
+
+#[tokio::test]
+async fn test_acknowledged_alert_stops_escalating() {
+    let escalator = Escalator::new(&config(), backend("ack")).await;
+    let id = escalator.open(&drained(), &[], 0).await;
+
+    assert_eq!(escalator.acknowledge(id, "ops", 5 * MINUTE).await, AckOutcome::Acknowledged);
+    match escalator.acknowledge(id, "someone-else", 6 * MINUTE).await {
+        AckOutcome::AlreadyAcknowledged(ack) => {
+            assert_eq!(ack.by, "ops");
+            assert_eq!(ack.at, 5 * MINUTE);
+        }
+        other => panic!("unexpected outcome {:?}", other),
+    }
+    assert_eq!(escalator.acknowledge(id + 1, "ops", 6 * MINUTE).await, AckOutcome::Unknown);
+
+    assert!(escalator.due(60 * MINUTE).await.is_empty());
+    assert!(escalator.unacknowledged().await.is_empty());
+}
+
+#[tokio::test]
+async fn test_alert_lifecycle_survives_restart() {
+    let backend = backend("restart");
+    let escalator = Escalator::new(&config(), Arc::clone(&backend)).await;
+    let first = escalator.open(&drained(), &[], 0).await;
+    escalator.due(15 * MINUTE).await;
+    drop(escalator);
+
+    let escalator = Escalator::new(&config(), backend).await;
+    let unacknowledged = escalator.unacknowledged().await;
+    assert_eq!(unacknowledged.len(), 1);
+    assert_eq!(unacknowledged[0].resends, 1);
+    assert_eq!(unacknowledged[0].alert.title, drained().title);
+
+    // IDs keep counting so old Acknowledge buttons never match a new alert
+    assert_eq!(escalator.open(&drained(), &[], 20 * MINUTE).await, first + 1);
+    assert!(matches!(escalator.due(30 * MINUTE).await.as_slice(), [EscalationStep::Escalate(tracked)] if tracked.id == first));
+
+    // Forgotten a week after being raised
+    escalator.due(8 * 24 * 60 * MINUTE).await;
+    assert!(escalator.unacknowledged().await.is_empty());
+}
+
+#[tokio::test]
+async fn test_pagerduty_events_share_dedup_key() {
+    let escalator = Escalator::new(&config(), backend("pagerduty")).await;
+    escalator.open(&drained(), &[], 0).await;
+    let tracked = escalator.unacknowledged().await.remove(0);
+
+    let pagerduty = PagerDutyClient::new(&PagerDutyConfig { routing_key: "R0UT1NG".to_string() });
+    let trigger = pagerduty.event("trigger", &tracked);
+    assert_eq!(trigger["routing_key"], "R0UT1NG");
+    assert_eq!(trigger["dedup_key"], "oxwatcher-alert-1");
+    assert_eq!(trigger["payload"]["severity"], "critical");
+    assert_eq!(trigger["payload"]["summary"], "🚨 Treasury drained: treasury (Ethereum)");
+
+    let acknowledge = pagerduty.event("acknowledge", &tracked);
+    assert_eq!(acknowledge["dedup_key"], trigger["dedup_key"]);
+    assert!(acknowledge.get("payload").is_none());
+}
+
+#[tokio::test]
+async fn test_instances_sharing_a_backend_keep_each_others_alerts() {
+    let backend = backend("shared");
+    let first = Escalator::new(&config(), Arc::clone(&backend)).await;
+    let second = Escalator::new(&config(), Arc::clone(&backend)).await;
+
+    // IDs come from the backend, so neither instance reuses the other's
+    let a = first.open(&drained(), &[], 0).await;
+    let b = second.open(&drained(), &[], 0).await;
+    assert_ne!(a, b);
+    assert_eq!(first.unacknowledged().await.len(), 2);
+
+    // An acknowledgement on one instance stops the other re-sending the alert
+    assert_eq!(second.acknowledge(a, "ops", 5 * MINUTE).await, AckOutcome::Acknowledged);
+    let steps = first.due(15 * MINUTE).await;
+    assert!(matches!(steps.as_slice(), [EscalationStep::Resend(tracked)] if tracked.id == b));
+    assert!(second.due(15 * MINUTE).await.is_empty());
+    assert_eq!(backend.load_escalations().await.unwrap().alerts.len(), 2);
+}
@@ -0,0 +1,156 @@
+use serde_json::Value;
+use std::sync::Arc;
+use Oxwatcher::{AuditEntry, AuditLog, EventBus, FileBackend, HealthTracker, MonitorEvent, StateBackend};
+
+fn parse(line: &str) -> Value {
+    assert!(!line.contains('\n'), "event must fit on one line: {}", line);
+    serde_json::from_str(line).unwrap()
+}
+
+#[test]
+fn test_event_line_has_tag_timestamp_and_fields() {
+    let event = MonitorEvent::RpcFailover {
+        network: "Ethereum".to_string(),
+        node: "rpc.ankr.com".to_string(),
+        up: false,
+        failovers: 3,
+        error: Some("HTTP error 429".to_string()),
+    };
+    let line = parse(&event.to_line(1_700_000_000));
+    assert_eq!(line["event"], "rpc_failover");
+    assert_eq!(line["timestamp"], 1_700_000_000);
+    assert_eq!(line["network"], "Ethereum");
+    assert_eq!(line["node"], "rpc.ankr.com");
+    assert_eq!(line["up"], false);
+    assert_eq!(line["failovers"], 3);
+    assert_eq!(line["error"], "HTTP error 429");
+
+    let event = MonitorEvent::CheckCompleted {
+        network: "Base".to_string(),
+        chain_id: Some(8453),
+        addresses: 3,
+        errors: 1,
+        error: Some("timeout".to_string()),
+    };
+    let line = parse(&event.to_line(1_700_000_060));
+    assert_eq!(line["event"], "check_completed");
+    assert_eq!(line["chain_id"], 8453);
+    assert_eq!(line["addresses"], 3);
+    assert_eq!(line["errors"], 1);
+    assert_eq!(line["error"], "timeout");
+
+    let event = MonitorEvent::CheckStarted { network: "cosmoshub".to_string(), chain_id: None };
+    assert!(parse(&event.to_line(1_700_000_120)).get("chain_id").is_none());
+}
+
+#[test]
+fn test_alert_sent_event_omits_missing_fields() {
+    let entry = AuditEntry {
+        sent_at: 1_700_000_000,
+        channel: "Slack".to_string(),
+        chat: None,
+        kind: "low_balance".to_string(),
+        network: Some("Ethereum".to_string()),
+        subject: "treasury ETH below 1".to_string(),
+        content_hash: "ab".repeat(32),
+        success: true,
+        error: None,
+    };
+    let line = parse(&MonitorEvent::alert_sent(&entry).to_line(entry.sent_at));
+    assert_eq!(line["event"], "alert_sent");
+    assert_eq!(line["channel"], "Slack");
+    assert_eq!(line["kind"], "low_balance");
+    assert_eq!(line["success"], true);
+    assert!(line.get("chat").is_none());
+    assert!(line.get("error").is_none());
+    assert!(line.get("content_hash").is_none());
+}
+
+#[tokio::test]
+async fn test_event_bus_delivers_to_every_subscriber() {
+    let bus = EventBus::new();
+    // Nobody listens yet; the event is dropped
+    bus.publish(MonitorEvent::RpcError { network: "Ethereum".to_string(), error: "timeout".to_string() });
+
+    let mut first = bus.subscribe();
+    let mut second = bus.subscribe();
+    bus.publish(MonitorEvent::CheckStarted { network: "Ethereum".to_string(), chain_id: Some(1) });
+
+    for receiver in [&mut first, &mut second] {
+        match receiver.recv().await.unwrap() {
+            MonitorEvent::CheckStarted { network, chain_id } => assert_eq!((network.as_str(), chain_id), ("Ethereum", Some(1))),
+            other => panic!("unexpected event {:?}", other),
+        }
+        assert!(receiver.try_recv().is_err());
+    }
+}
+
+#[tokio::test]
+async fn test_health_tracker_follows_check_events() {
+    let health = HealthTracker::new();
+    health.register("Base", Some(8453), 60, 1, 1_700_000_000).await;
+
+    let completed = |addresses, error: Option<&str>| MonitorEvent::CheckCompleted {
+        network: "Base".to_string(),
+        chain_id: Some(8453),
+        addresses,
+        errors: usize::from(error.is_some()),
+        error: error.map(str::to_string),
+    };
+    // Nothing could be read: the cycle failed
+    health.record_event(&completed(0, Some("connection refused"))).await;
+    let failed = health.get("Base").await.unwrap();
+    assert_eq!(failed.consecutive_failures, 1);
+    assert_eq!(failed.last_error.as_deref(), Some("connection refused"));
+    assert!(failed.last_success.is_none());
+
+    // Some addresses were read: the cycle succeeded, the error is kept
+    health.record_event(&completed(2, Some("timeout"))).await;
+    let recovered = health.get("Base").await.unwrap();
+    assert!(recovered.is_ok());
+    assert_eq!(recovered.error_count, 2);
+    assert_eq!(recovered.last_error.as_deref(), Some("timeout"));
+
+    // Other events leave it alone
+    health.record_event(&MonitorEvent::CheckStarted { network: "Base".to_string(), chain_id: Some(8453) }).await;
+    assert_eq!(health.get("Base").await.unwrap().error_count, 2);
+}
+
+#[tokio::test]
+async fn test_audit_log_writes_deliveries_past_a_lagging_subscriber() {
+    let dir = std::env::temp_dir().join(format!("oxwatcher-events-audit-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let backend = Arc::new(FileBackend::new(dir.to_string_lossy().to_string()));
+
+    let bus = EventBus::new();
+    let audit = AuditLog::new(backend.clone()).with_events(bus.clone());
+    let mut stream = bus.subscribe();
+
+    let entry = |subject: usize| AuditEntry {
+        sent_at: 1_700_000_000,
+        channel: "telegram".to_string(),
+        chat: Some("-100123".to_string()),
+        kind: "balance_change".to_string(),
+        network: Some("Base".to_string()),
+        subject: format!("treasury-{}", subject),
+        content_hash: "cd".repeat(32),
+        success: false,
+        error: Some("HTTP error 502".to_string()),
+    };
+    audit.record(entry(0)).await;
+    assert!(matches!(stream.recv().await.unwrap(), MonitorEvent::AlertSent { .. }));
+
+    // The stream falls behind by more than the bus holds; every delivery is still recorded
+    for subject in 1..2_000 {
+        audit.record(entry(subject)).await;
+    }
+    assert!(matches!(stream.recv().await, Err(tokio::sync::broadcast::error::RecvError::Lagged(_))));
+
+    let entries = backend.load_audit_log().await.unwrap();
+    assert_eq!(entries.len(), 2_000);
+    assert_eq!(entries[0].sent_at, 1_700_000_000);
+    assert_eq!(entries[0].chat.as_deref(), Some("-100123"));
+    assert_eq!(entries[0].content_hash, "cd".repeat(32));
+    assert_eq!(entries[0].error.as_deref(), Some("HTTP error 502"));
+}
@@ -0,0 +1,109 @@
+use alloy::primitives::U256;
+use chrono::NaiveDate;
+use std::time::Duration;
+use Oxwatcher::{
+    balances_csv, export_balances, history_csv, BalanceInfo, ExportFormat, ExportKind, HistoryStore, TokenBalance,
+    MAX_HISTORY_DAYS,
+};
+
+mod common;
+
+const DAY: u64 = 24 * 3600;
+
+fn balance_info(alias: &str, eth: &str) -> BalanceInfo {
+    BalanceInfo { eth_usd_value: Some(4200.5), block_number: Some(21_000_000), ..common::balance_info(alias, eth) }
+}
+
+fn date(value: &str) -> NaiveDate {
+    value.parse().unwrap()
+}
+
+#[test]
+fn test_balances_csv_has_one_row_per_asset_and_quotes_fields() {
+    let mut treasury = balance_info("treasury, main", "1.5");
+    treasury.token_balances.push(TokenBalance {
+        alias: "USDC".to_string(),
+        balance: U256::from(2_500_000u64),
+        formatted: "2.5".to_string(),
+        decimals: 18,
+        usd_value: None,
+        shares: None,
+    });
+
+    let csv = balances_csv(&[treasury]);
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines[0], "network,chain_id,alias,address,asset,balance,usd_value,block_number");
+    assert_eq!(
+        lines[1],
+        "Ethereum,1,\"treasury, main\",0xd8da6bf26964af9d7eed9e03e53415d37aa96045,ETH,1.5,4200.50,21000000"
+    );
+    assert!(lines[2].contains(",USDC,2.5,,"));
+    assert_eq!(lines.len(), 3);
+}
+
+#[test]
+fn test_history_csv_exports_end_of_day_snapshots() {
+    let path = std::env::temp_dir().join(format!("oxwatcher-export-{}.jsonl", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let mut history = HistoryStore::load_from_file(&path, Duration::from_secs(3650 * DAY), Duration::from_secs(3600)).unwrap();
+
+    let oct_1 = date("2026-10-01").and_hms_opt(0, 0, 0).unwrap().and_utc().timestamp() as u64;
+    history.record(&balance_info("relayer", "1.0"), oct_1 + 3600).unwrap();
+    // Last change of the day wins
+    history.record(&balance_info("relayer", "0.8"), oct_1 + 20 * 3600).unwrap();
+    history.record(&balance_info("relayer", "0.5"), oct_1 + DAY + 3600).unwrap();
+
+    let csv = history_csv(&history, date("2026-09-30"), date("2026-10-03"));
+    let rows: Vec<&str> = csv.lines().skip(1).collect();
+    assert_eq!(rows.len(), 3, "no row before the first record");
+    assert!(rows[0].starts_with("2026-10-01,Ethereum,1,relayer,"));
+    assert!(rows[0].contains(",ETH,0.8,"));
+    assert!(rows[1].starts_with("2026-10-02,") && rows[1].contains(",ETH,0.5,"));
+    assert!(rows[2].starts_with("2026-10-03,") && rows[2].contains(",ETH,0.5,"));
+}
+
+#[test]
+fn test_export_kind_parsing() {
+    assert_eq!(ExportKind::parse("").unwrap(), ExportKind::Balances);
+    assert_eq!(
+        ExportKind::parse("history 2026-09-01 2026-09-30").unwrap(),
+        ExportKind::History { from: date("2026-09-01"), to: date("2026-09-30") }
+    );
+    assert!(ExportKind::parse("history 2026-09-30 2026-09-01").is_err());
+    assert!(ExportKind::parse("history 2026-09-01").is_err());
+    assert!(ExportKind::parse("history 09/01/2026 2026-09-30").is_err());
+    // Ranges are capped so one command can't loop over millennia
+    assert!(ExportKind::parse("history 2025-01-01 2025-12-31").is_ok());
+    assert!(ExportKind::parse("history 2024-01-01 2024-12-31").is_ok());
+    let error = ExportKind::parse("history 0001-01-01 9999-12-31").unwrap_err();
+    assert_eq!(error.to_string(), format!("history exports cover at most {} days", MAX_HISTORY_DAYS));
+    assert_eq!(ExportKind::Balances.file_name(date("2026-10-16"), ExportFormat::Csv), "balances-2026-10-16.csv");
+
+    assert_eq!(ExportFormat::split("history 2026-09-01 2026-09-30 xlsx"), (ExportFormat::Xlsx, "history 2026-09-01 2026-09-30"));
+    assert_eq!(ExportFormat::split("xlsx"), (ExportFormat::Xlsx, ""));
+    assert_eq!(ExportFormat::split("balances"), (ExportFormat::Csv, "balances"));
+    assert_eq!(ExportKind::Balances.file_name(date("2026-10-16"), ExportFormat::Xlsx), "balances-2026-10-16.xlsx");
+}
+
+#[test]
+fn test_exports_neutralize_formulas() {
+    let mut wallet = balance_info("=HYPERLINK(\"https://evil.example\")", "1.5");
+    wallet.token_balances.push(TokenBalance {
+        alias: "@SUM(A1)".to_string(),
+        balance: U256::from(1u64),
+        formatted: "1".to_string(),
+        decimals: 0,
+        usd_value: None,
+        shares: None,
+    });
+    wallet.network_name = "-net".to_string();
+
+    let csv = balances_csv(&[wallet.clone()]);
+    let lines: Vec<&str> = csv.lines().collect();
+    assert!(lines[1].starts_with("'-net,1,\"'=HYPERLINK(\"\"https://evil.example\"\")\",0x"));
+    assert!(lines[2].contains(",'@SUM(A1),1,,"));
+
+    // XLSX is a zip archive with the text in its shared strings, not in formulas
+    let xlsx = export_balances(&[wallet], ExportFormat::Xlsx).unwrap();
+    assert!(xlsx.starts_with(b"PK"));
+}
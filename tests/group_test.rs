@@ -0,0 +1,84 @@
+use Oxwatcher::{AddressConfig, BalanceInfo, Config, GroupEvent, GroupMonitor, GroupTotal};
+
+mod common;
+
+
+fn config() -> Config {
+    serde_yaml::from_str(
+        r#"
+interval_secs: 60
+networks:
+  - name: Ethereum
+    chain_id: 1
+    rpc_nodes: ["https://eth.llamarpc.com"]
+    addresses:
+      - alias: relayer-1
+        address: "0x1111111111111111111111111111111111111111"
+        tags: [relayers]
+      - alias: relayer-2
+        address: "0x2222222222222222222222222222222222222222"
+        tags: [relayers, hot]
+      - alias: treasury
+        address: "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045"
+groups:
+  - tag: relayers
+    networks: [Ethereum]
+    min_total_balance: 1.0
+"#,
+    )
+    .unwrap()
+}
+
+fn balance_info(alias: &str, eth: f64) -> BalanceInfo {
+    common::balance_info(alias, &eth.to_string())
+}
+
+#[test]
+fn test_group_total_sums_native_balances() {
+    let balances = [balance_info("relayer-1", 0.25), balance_info("relayer-2", 0.5)];
+    let total = GroupTotal::of(&balances).unwrap();
+    assert_eq!(total.addresses, 2);
+    assert_eq!(total.formatted, "0.750000000000000000");
+    assert_eq!(total.symbol, "ETH");
+    assert!(GroupTotal::of(&[]).is_none());
+}
+
+#[tokio::test]
+async fn test_group_alerts_once_when_the_total_drops_below_its_minimum() {
+    let config = config();
+    assert!(config.problems().is_empty());
+    let addresses: &[AddressConfig] = &config.networks[0].addresses;
+    let monitor = GroupMonitor::new(&config.groups, "Ethereum");
+    assert!(GroupMonitor::new(&config.groups, "Base").is_empty());
+
+    // Each relayer is fine on its own, but together they hold less than 1 ETH
+    let balances = [balance_info("relayer-1", 0.4), balance_info("relayer-2", 0.5), balance_info("treasury", 100.0)];
+    let events = monitor.check(addresses, &balances).await;
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].0.tag, "relayers");
+    assert_eq!(events[0].1.addresses, 2);
+    assert_eq!(events[0].2, GroupEvent::Below { min: 1.0 });
+    assert!(monitor.check(addresses, &balances).await.is_empty());
+
+    // A group with an unread address is skipped rather than reported recovered or low
+    assert!(monitor.check(addresses, &[balance_info("relayer-1", 5.0)]).await.is_empty());
+
+    let balances = [balance_info("relayer-1", 0.6), balance_info("relayer-2", 0.5)];
+    let events = monitor.check(addresses, &balances).await;
+    assert_eq!(events[0].2, GroupEvent::Recovered { min: 1.0 });
+}
+
+#[test]
+fn test_group_validation() {
+    let mut config = config();
+    config.networks[0].addresses[0].tags.push("two words".to_string());
+    config.groups[0].networks.push("Polygon".to_string());
+    config.groups.push(config.groups[0].clone());
+    config.groups.push(serde_yaml::from_str("tag: cold").unwrap());
+
+    let problems = config.problems();
+    assert!(problems.contains(&"address 'relayer-1' on Ethereum: tags must be non-empty and without spaces".to_string()));
+    assert!(problems.iter().any(|p| p.contains("group 'relayers'") && p.contains("Polygon")), "{:?}", problems);
+    assert!(problems.iter().any(|p| p == "group 'relayers' is defined more than once"), "{:?}", problems);
+    assert!(problems.contains(&"group 'cold' matches no address tags".to_string()), "{:?}", problems);
+}
@@ -0,0 +1,71 @@
+use alloy::primitives::U256;
+use std::time::Duration;
+use Oxwatcher::{BalanceInfo, HistoryStore};
+
+mod common;
+
+const HOUR: u64 = 3600;
+const DAY: u64 = 24 * HOUR;
+
+fn balance_info(wei: u64) -> BalanceInfo {
+    BalanceInfo { eth_balance: U256::from(wei), eth_formatted: wei.to_string(), ..common::balance_info("treasury", "0") }
+}
+
+fn temp_history_path(name: &str) -> std::path::PathBuf {
+    let path = std::env::temp_dir().join(format!("oxwatcher-{}-{}.jsonl", name, std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+#[test]
+fn test_history_records_changes_and_samples() {
+    let path = temp_history_path("record");
+    let mut history = HistoryStore::load_from_file(&path, Duration::from_secs(30 * DAY), Duration::from_secs(HOUR)).unwrap();
+
+    assert!(history.record(&balance_info(100), 0).unwrap());
+    // Unchanged balance within sample interval is skipped
+    assert!(!history.record(&balance_info(100), 60).unwrap());
+    // Changed balance is recorded immediately
+    assert!(history.record(&balance_info(90), 120).unwrap());
+    // Unchanged balance after sample interval is recorded again
+    assert!(history.record(&balance_info(90), 120 + HOUR).unwrap());
+
+    let points = history.get_range("Ethereum", "treasury", 0, 2 * HOUR);
+    assert_eq!(points.len(), 3);
+
+    // History survives reload
+    let reloaded = HistoryStore::load_from_file(&path, Duration::from_secs(30 * DAY), Duration::from_secs(HOUR)).unwrap();
+    assert_eq!(reloaded.get_range("Ethereum", "treasury", 0, 2 * HOUR).len(), 3);
+}
+
+#[test]
+fn test_history_get_at_and_snapshot() {
+    let path = temp_history_path("get-at");
+    let mut history = HistoryStore::load_from_file(&path, Duration::from_secs(30 * DAY), Duration::from_secs(HOUR)).unwrap();
+
+    history.record(&balance_info(100), DAY).unwrap();
+    history.record(&balance_info(50), 2 * DAY).unwrap();
+
+    assert!(history.get_at("Ethereum", "treasury", DAY - 1).is_none());
+    assert_eq!(history.get_at("Ethereum", "treasury", DAY + HOUR).unwrap().balance.eth_balance, U256::from(100));
+    assert_eq!(history.get_at("Ethereum", "treasury", 3 * DAY).unwrap().balance.eth_balance, U256::from(50));
+
+    let snapshot = history.snapshot_at(DAY + HOUR);
+    assert_eq!(snapshot.get("Ethereum", "treasury").unwrap().eth_balance, U256::from(100));
+}
+
+#[test]
+fn test_history_prunes_old_points() {
+    let path = temp_history_path("prune");
+    let mut history = HistoryStore::load_from_file(&path, Duration::from_secs(7 * DAY), Duration::from_secs(HOUR)).unwrap();
+
+    history.record(&balance_info(100), 0).unwrap();
+    history.record(&balance_info(90), DAY).unwrap();
+    history.record(&balance_info(80), 10 * DAY).unwrap();
+    history.prune(10 * DAY).unwrap();
+
+    // Newest point before the cutoff is kept so get_at works at the edge
+    let points = history.get_range("Ethereum", "treasury", 0, 11 * DAY);
+    assert_eq!(points.len(), 2);
+    assert_eq!(points[0].balance.eth_balance, U256::from(90));
+}
@@ -0,0 +1,37 @@
+use alloy::primitives::address;
+use Oxwatcher::{AddressConfig, InactivityEvent, InactivityMonitor};
+
+const HOUR: u64 = 3600;
+
+fn monitor() -> InactivityMonitor {
+    let mut distributor = AddressConfig::new("distributor", address!("d8dA6BF26964aF9D7eEd9e03E53415D37aA96045"));
+    distributor.max_inactive_hours = Some(24);
+    let treasury = AddressConfig::new("treasury", address!("d8dA6BF26964aF9D7eEd9e03E53415D37aA96045"));
+    InactivityMonitor::new(&[distributor, treasury])
+}
+
+#[tokio::test]
+async fn test_inactive_address_is_reported_once_until_it_changes() {
+    let monitor = monitor();
+
+    assert_eq!(monitor.check("distributor", 0, 24 * HOUR).await, None);
+    assert_eq!(
+        monitor.check("distributor", 0, 24 * HOUR + 1).await,
+        Some(InactivityEvent::Inactive { since: 0, limit_secs: 24 * HOUR })
+    );
+    assert_eq!(monitor.check("distributor", 0, 48 * HOUR).await, None);
+
+    assert_eq!(
+        monitor.check("distributor", 50 * HOUR, 50 * HOUR).await,
+        Some(InactivityEvent::Active { inactive_secs: 50 * HOUR })
+    );
+    assert_eq!(monitor.check("distributor", 50 * HOUR, 60 * HOUR).await, None);
+}
+
+#[tokio::test]
+async fn test_addresses_without_limit_are_ignored() {
+    let monitor = monitor();
+    assert!(!monitor.is_empty());
+    assert_eq!(monitor.check("treasury", 0, 1_000 * HOUR).await, None);
+    assert!(InactivityMonitor::new(&[]).is_empty());
+}
@@ -0,0 +1,116 @@
+use alloy::primitives::{address, Address, U256};
+use axum::extract::Query;
+use axum::routing::get;
+use axum::{Json, Router};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use Oxwatcher::{IndexerClient, IndexerConfig, TokenConfig, TransferDirection};
+
+const SAFE: Address = address!("849D52316331967b6fF1198e5E32A0eB168D039d");
+const EXCHANGE: Address = address!("28C6c06298d514Db089934071355E5743bf21d60");
+const USDC: Address = address!("A0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48");
+const OTHER_TOKEN: Address = address!("dAC17F958D2ee523a2206206994597C13D831ec7");
+
+fn record(hash: u8, block: u64, from: Address, to: Address, value: u64) -> Value {
+    json!({
+        "hash": format!("0x{:064x}", hash),
+        "blockNumber": block.to_string(),
+        "from": format!("{:?}", from).to_lowercase(),
+        "to": format!("{:?}", to).to_lowercase(),
+        "value": value.to_string(),
+        "isError": "0",
+    })
+}
+
+fn with(mut record: Value, key: &str, value: &str) -> Value {
+    record[key] = json!(value);
+    record
+}
+
+/// Etherscan-style account API; answers "Invalid API Key" without the key "secret"
+async fn account(Query(query): Query<HashMap<String, String>>) -> Json<Value> {
+    if query.get("apikey").map(String::as_str) != Some("secret") || query["chainid"] != "1" {
+        return Json(json!({ "status": "0", "message": "NOTOK", "result": "Invalid API Key" }));
+    }
+    if query["address"] == format!("{:?}", EXCHANGE) {
+        return Json(json!({ "status": "1", "message": "OK", "result": busy_history(&query) }));
+    }
+    let records = match query["action"].as_str() {
+        "txlist" => vec![
+            record(1, 101, SAFE, EXCHANGE, 0),
+            with(record(2, 102, SAFE, EXCHANGE, 5), "isError", "1"),
+            // Someone calling the address without value
+            record(3, 103, EXCHANGE, SAFE, 0),
+        ],
+        "txlistinternal" => vec![record(4, 104, SAFE, EXCHANGE, 7)],
+        "tokentx" => vec![
+            with(record(5, 100, EXCHANGE, SAFE, 9), "contractAddress", &format!("{:?}", USDC)),
+            with(record(6, 100, EXCHANGE, SAFE, 9), "contractAddress", &format!("{:?}", OTHER_TOKEN)),
+        ],
+        _ => return Json(json!({ "status": "0", "message": "No transactions found", "result": [] })),
+    };
+    Json(json!({ "status": "1", "message": "OK", "result": records }))
+}
+
+/// Two records per block from 200 to 204, one of them unparseable, answering at most
+/// `offset` records from `startblock` like Etherscan
+fn busy_history(query: &HashMap<String, String>) -> Vec<Value> {
+    let start: u64 = query["startblock"].parse().unwrap();
+    let offset: usize = query["offset"].parse().unwrap();
+    (200..=204u64)
+        .flat_map(|block| [0, 1].map(|i| (block, i)))
+        .map(|(block, i)| record(i, block, EXCHANGE, SAFE, 1))
+        .map(|record| if record["blockNumber"] == "202" && record["hash"].as_str().unwrap().ends_with('1') { with(record, "hash", "0xbad") } else { record })
+        .filter(|record| record["blockNumber"].as_str().unwrap().parse::<u64>().unwrap() >= start)
+        .take(offset)
+        .collect()
+}
+
+async fn indexer(api_key: &str) -> IndexerClient {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/api", listener.local_addr().unwrap());
+    tokio::spawn(async move { axum::serve(listener, Router::new().route("/api", get(account))).await });
+    let config: IndexerConfig = serde_yaml::from_str(&format!("url: {}\napi_key: {}", url, api_key)).unwrap();
+    IndexerClient::new(&config, 1)
+}
+
+#[tokio::test]
+async fn test_indexer_attributes_transactions_including_internal_ones() {
+    let indexer = indexer("secret").await;
+    let usdc = TokenConfig::new("USDC", USDC);
+    let explorer = "https://etherscan.io/".parse().unwrap();
+
+    let found = indexer.lookup_transactions(SAFE, true, &[&usdc], 100, 110, Some(&explorer)).await.unwrap();
+    let summary: Vec<(u64, &str, TransferDirection, Option<Address>, U256)> = found
+        .iter()
+        .map(|tx| (tx.block_number, tx.asset.as_str(), tx.direction, tx.counterparty, tx.value))
+        .collect();
+    assert_eq!(
+        summary,
+        [
+            (100, "USDC", TransferDirection::Incoming, Some(EXCHANGE), U256::from(9)),
+            (101, "ETH", TransferDirection::Outgoing, Some(EXCHANGE), U256::ZERO),
+            (104, "ETH", TransferDirection::Outgoing, Some(EXCHANGE), U256::from(7)),
+        ]
+    );
+    assert_eq!(found[0].url.as_ref().unwrap().as_str(), format!("https://etherscan.io/tx/0x{:064x}", 5));
+
+    // "No transactions found" is an empty list
+    assert!(indexer.transfers("txlistpending", SAFE, 100, 110).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_indexer_reports_api_errors() {
+    let indexer = indexer("wrong").await;
+    let error = indexer.lookup_transactions(SAFE, true, &[], 100, 110, None).await.unwrap_err();
+    assert_eq!(error.to_string(), "indexer API NOTOK (0): Invalid API Key");
+}
+
+#[tokio::test]
+async fn test_indexer_reads_on_past_full_pages() {
+    let indexer = indexer("secret").await.with_page_size(3);
+
+    let transfers = indexer.transfers("txlist", EXCHANGE, 200, 210).await.unwrap();
+    let blocks: Vec<&str> = transfers.iter().map(|transfer| transfer.block_number.as_str()).collect();
+    assert_eq!(blocks, ["200", "200", "201", "201", "202", "203", "203", "204", "204"]);
+}
@@ -0,0 +1,262 @@
+use async_trait::async_trait;
+use eyre::{eyre, Result};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::time::Instant;
+use Oxwatcher::{
+    AlertStateStorage, AuditEntry, AuditQuery, BalanceStorage, ChatStorage, Config, CosmosStateStorage,
+    DynamicAddressStorage, EscalationStorage, FileBackend, InstanceLease, LeaderElectionConfig, StateBackend,
+    TrackedAlert,
+};
+
+fn temp_dir(name: &str) -> PathBuf {
+    let data_dir = std::env::temp_dir().join(format!("oxwatcher-lease-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&data_dir);
+    std::fs::create_dir_all(&data_dir).unwrap();
+    data_dir
+}
+
+fn temp_backend(name: &str) -> Arc<dyn StateBackend> {
+    Arc::new(FileBackend::new(temp_dir(name)))
+}
+
+/// Backend keeping one lease in memory on the tokio clock, whose lease calls fail while
+/// `down` is set. Everything else goes to a file backend.
+struct FlakyLeaseBackend {
+    inner: FileBackend,
+    lease: Mutex<Option<(String, Instant)>>,
+    down: AtomicBool,
+}
+
+#[async_trait]
+impl StateBackend for FlakyLeaseBackend {
+    fn name(&self) -> &str {
+        "flaky"
+    }
+
+    async fn load_balances(&self) -> Result<BalanceStorage> {
+        self.inner.load_balances().await
+    }
+    async fn save_balances(&self, balances: &BalanceStorage) -> Result<()> {
+        self.inner.save_balances(balances).await
+    }
+    async fn load_chats(&self, bot: Option<&str>) -> Result<ChatStorage> {
+        self.inner.load_chats(bot).await
+    }
+    async fn save_chats(&self, bot: Option<&str>, chats: &ChatStorage) -> Result<()> {
+        self.inner.save_chats(bot, chats).await
+    }
+    async fn load_alert_states(&self) -> Result<AlertStateStorage> {
+        self.inner.load_alert_states().await
+    }
+    async fn save_alert_states(&self, states: &AlertStateStorage) -> Result<()> {
+        self.inner.save_alert_states(states).await
+    }
+    async fn load_dynamic_addresses(&self) -> Result<DynamicAddressStorage> {
+        self.inner.load_dynamic_addresses().await
+    }
+    async fn save_dynamic_addresses(&self, addresses: &DynamicAddressStorage) -> Result<()> {
+        self.inner.save_dynamic_addresses(addresses).await
+    }
+    async fn load_report_baseline(&self) -> Result<Option<BalanceStorage>> {
+        self.inner.load_report_baseline().await
+    }
+    async fn save_report_baseline(&self, baseline: &BalanceStorage) -> Result<()> {
+        self.inner.save_report_baseline(baseline).await
+    }
+    async fn clear_report_baseline(&self) -> Result<()> {
+        self.inner.clear_report_baseline().await
+    }
+    async fn load_escalations(&self) -> Result<EscalationStorage> {
+        self.inner.load_escalations().await
+    }
+    async fn save_escalations(&self, escalations: &EscalationStorage) -> Result<()> {
+        self.inner.save_escalations(escalations).await
+    }
+    async fn next_escalation_id(&self) -> Result<u64> {
+        self.inner.next_escalation_id().await
+    }
+    async fn save_escalation(&self, tracked: &TrackedAlert) -> Result<()> {
+        self.inner.save_escalation(tracked).await
+    }
+    async fn delete_escalations(&self, ids: &[u64]) -> Result<()> {
+        self.inner.delete_escalations(ids).await
+    }
+    async fn load_cosmos_states(&self) -> Result<CosmosStateStorage> {
+        self.inner.load_cosmos_states().await
+    }
+    async fn save_cosmos_states(&self, states: &CosmosStateStorage) -> Result<()> {
+        self.inner.save_cosmos_states(states).await
+    }
+    async fn append_audit(&self, entry: &AuditEntry) -> Result<()> {
+        self.inner.append_audit(entry).await
+    }
+    async fn load_audit(&self, query: &AuditQuery) -> Result<Vec<AuditEntry>> {
+        self.inner.load_audit(query).await
+    }
+    async fn load_audit_log(&self) -> Result<Vec<AuditEntry>> {
+        self.inner.load_audit_log().await
+    }
+    async fn storage_sizes(&self) -> Result<Vec<(String, u64)>> {
+        self.inner.storage_sizes().await
+    }
+
+    async fn acquire_lease(&self, _name: &str, holder: &str, duration: Duration) -> Result<Option<String>> {
+        if self.down.load(Ordering::SeqCst) {
+            return Err(eyre!("connection refused"));
+        }
+        let mut lease = self.lease.lock().unwrap();
+        match lease.as_ref() {
+            Some((other, expires_at)) if other != holder && *expires_at > Instant::now() => Ok(Some(other.clone())),
+            _ => {
+                *lease = Some((holder.to_string(), Instant::now() + duration));
+                Ok(None)
+            }
+        }
+    }
+    async fn release_lease(&self, _name: &str, holder: &str) -> Result<()> {
+        let mut lease = self.lease.lock().unwrap();
+        if lease.as_ref().is_some_and(|(other, _)| other == holder) {
+            *lease = None;
+        }
+        Ok(())
+    }
+
+    async fn claim_alert(&self, network: &str, subject: &str, hash: &str, window: Duration) -> Result<bool> {
+        self.inner.claim_alert(network, subject, hash, window).await
+    }
+    async fn release_alert(&self, network: &str, subject: &str, hash: &str) -> Result<()> {
+        self.inner.release_alert(network, subject, hash).await
+    }
+}
+
+#[tokio::test]
+async fn test_file_lease_excludes_other_holders() {
+    let backend = temp_backend("exclusive");
+    let lease = Duration::from_secs(30);
+
+    assert_eq!(backend.acquire_lease("oxwatcher", "host-a:1", lease).await.unwrap(), None);
+    // Renewing our own lease works, another holder is refused
+    assert_eq!(backend.acquire_lease("oxwatcher", "host-a:1", lease).await.unwrap(), None);
+    assert_eq!(
+        backend.acquire_lease("oxwatcher", "host-b:2", lease).await.unwrap(),
+        Some("host-a:1".to_string())
+    );
+    // Leases with other names are independent
+    assert_eq!(backend.acquire_lease("other", "host-b:2", lease).await.unwrap(), None);
+
+    // Only the holder can release it
+    backend.release_lease("oxwatcher", "host-b:2").await.unwrap();
+    assert!(backend.acquire_lease("oxwatcher", "host-b:2", lease).await.unwrap().is_some());
+    backend.release_lease("oxwatcher", "host-a:1").await.unwrap();
+    assert_eq!(backend.acquire_lease("oxwatcher", "host-b:2", lease).await.unwrap(), None);
+}
+
+#[tokio::test]
+async fn test_file_lease_is_held_until_its_process_exits() {
+    // Each backend opens the lock file on its own, like another process would
+    let data_dir = temp_dir("process");
+    let first = FileBackend::new(data_dir.clone());
+    let second = FileBackend::new(data_dir);
+
+    assert_eq!(first.acquire_lease("oxwatcher", "host-a:1", Duration::ZERO).await.unwrap(), None);
+    // The recorded expiry doesn't matter while the holder is alive
+    assert_eq!(
+        second.acquire_lease("oxwatcher", "host-b:2", Duration::from_secs(30)).await.unwrap(),
+        Some("host-a:1".to_string())
+    );
+
+    // The lock goes away with its holder, even without a release
+    drop(first);
+    assert_eq!(second.acquire_lease("oxwatcher", "host-b:2", Duration::from_secs(30)).await.unwrap(), None);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 8)]
+async fn test_concurrent_file_leases_have_one_holder() {
+    let data_dir = temp_dir("race");
+    let backends: Vec<_> = (0..16).map(|_| Arc::new(FileBackend::new(data_dir.clone()))).collect();
+
+    let attempts: Vec<_> = backends
+        .iter()
+        .enumerate()
+        .map(|(i, backend)| {
+            let backend = Arc::clone(backend);
+            tokio::spawn(async move {
+                backend.acquire_lease("oxwatcher", &format!("host-{}", i), Duration::from_secs(30)).await.unwrap()
+            })
+        })
+        .collect();
+    let mut holders = 0;
+    for attempt in attempts {
+        if attempt.await.unwrap().is_none() {
+            holders += 1;
+        }
+    }
+    assert_eq!(holders, 1);
+}
+
+#[tokio::test]
+async fn test_standby_takes_over_after_leader_stops() {
+    let data_dir = temp_dir("standby");
+    let config = LeaderElectionConfig { name: "oxwatcher".to_string(), lease_secs: 1 };
+
+    let leader = InstanceLease::new(Arc::new(FileBackend::new(data_dir.clone())), &config).with_holder("leader");
+    leader.acquire(false).await.unwrap();
+
+    // Without standby a second instance refuses to start
+    let standby = InstanceLease::new(Arc::new(FileBackend::new(data_dir)), &config).with_holder("standby");
+    let err = standby.acquire(false).await.unwrap_err();
+    assert!(err.to_string().contains("leader"));
+
+    // The leader gives up its lease; the standby waiting for it takes over
+    let waiting = tokio::spawn(async move {
+        standby.acquire(true).await.unwrap();
+        standby
+    });
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    leader.release().await;
+    let _standby = tokio::time::timeout(Duration::from_secs(10), waiting).await.unwrap().unwrap();
+
+    // The old leader finds its lease taken over on the next renewal
+    let lost = tokio::time::timeout(Duration::from_secs(10), leader.hold()).await.unwrap();
+    assert!(lost.to_string().contains("taken over by standby"));
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_leader_steps_down_before_its_lease_expires() {
+    let backend = Arc::new(FlakyLeaseBackend {
+        inner: FileBackend::new(temp_dir("flaky")),
+        lease: Mutex::default(),
+        down: AtomicBool::new(false),
+    });
+    let config = LeaderElectionConfig { name: "oxwatcher".to_string(), lease_secs: 30 };
+    let leader = InstanceLease::new(backend.clone(), &config).with_holder("leader");
+    leader.acquire(false).await.unwrap();
+
+    // The backend goes away right after the lease was taken
+    backend.down.store(true, Ordering::SeqCst);
+    let started = Instant::now();
+    let lost = leader.hold().await;
+    assert!(lost.to_string().contains("about to expire"), "{}", lost);
+    assert!(started.elapsed() < Duration::from_secs(30));
+
+    // The leader stopped while its lease still kept a standby out
+    backend.down.store(false, Ordering::SeqCst);
+    assert_eq!(
+        backend.acquire_lease("oxwatcher", "standby", Duration::from_secs(30)).await.unwrap(),
+        Some("leader".to_string())
+    );
+}
+
+#[test]
+fn test_leader_election_config() {
+    let config = Config::parse("interval_secs: 60\nnetworks: []\nleader_election: {}\n").unwrap();
+    let election = config.leader_election.as_ref().unwrap();
+    assert_eq!(election.name, "oxwatcher");
+    assert_eq!(election.lease_secs, 30);
+
+    let config = Config::parse("interval_secs: 60\nnetworks: []\nleader_election:\n  lease_secs: 1\n").unwrap();
+    assert!(config.problems().iter().any(|p| p.contains("lease_secs")));
+}
@@ -0,0 +1,33 @@
+use std::io::Write;
+use Oxwatcher::{LogRotation, RotatingFile};
+
+#[test]
+fn test_log_file_rotates_by_size_and_keeps_max_files() {
+    let dir = std::env::temp_dir().join(format!("oxwatcher-logs-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    let path = dir.join("oxwatcher.log");
+
+    let file = RotatingFile::open(&path, LogRotation::Never, Some(100), 2).unwrap();
+    let line = [b'x'; 60];
+    for _ in 0..5 {
+        (&file).write_all(&line).unwrap();
+        // Rotated names are timestamped to the millisecond
+        std::thread::sleep(std::time::Duration::from_millis(5));
+    }
+
+    // Every write after the first overflowed the 100-byte limit; only the newest two rotations are kept
+    assert_eq!(std::fs::metadata(&path).unwrap().len(), 60);
+    let rotated = file.rotated_files();
+    assert_eq!(rotated.len(), 2);
+    for path in &rotated {
+        assert_eq!(std::fs::metadata(path).unwrap().len(), 60);
+    }
+
+    // Reopening appends to the active file
+    drop(file);
+    let file = RotatingFile::open(&path, LogRotation::Never, None, 2).unwrap();
+    (&file).write_all(b"more").unwrap();
+    assert_eq!(std::fs::metadata(&path).unwrap().len(), 64);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,92 @@
+use chrono::{Local, TimeZone};
+use Oxwatcher::{MaintenanceSchedule, MaintenanceWindowConfig};
+
+fn local(day: u32, hour: u32, minute: u32) -> u64 {
+    Local.with_ymd_and_hms(2026, 10, day, hour, minute, 0).unwrap().timestamp() as u64
+}
+
+fn saturday_nights() -> MaintenanceWindowConfig {
+    MaintenanceWindowConfig {
+        name: Some("Weekly rebalancing".to_string()),
+        cron: Some("0 2 * * SAT".to_string()),
+        duration_mins: Some(120),
+        networks: vec!["Ethereum".to_string()],
+        addresses: vec!["Treasury".to_string()],
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn test_recurring_window_is_open_for_its_duration() {
+    // 2026-10-17 is a Saturday
+    let schedule = MaintenanceSchedule::new(&[saturday_nights()]).unwrap();
+
+    assert_eq!(schedule.suppressing("Ethereum", "Treasury", local(17, 1, 59)).await, None);
+    assert_eq!(
+        schedule.suppressing("Ethereum", "Treasury", local(17, 2, 0)).await.as_deref(),
+        Some("Weekly rebalancing")
+    );
+    assert!(schedule.suppressing("Ethereum", "Treasury", local(17, 3, 59)).await.is_some());
+    assert_eq!(schedule.suppressing("Ethereum", "Treasury", local(17, 4, 0)).await, None);
+    assert_eq!(schedule.suppressing("Ethereum", "Treasury", local(18, 2, 30)).await, None);
+
+    // Other networks and addresses are not affected
+    assert_eq!(schedule.suppressing("Polygon", "Treasury", local(17, 2, 30)).await, None);
+    assert_eq!(schedule.suppressing("Ethereum", "Hot Wallet", local(17, 2, 30)).await, None);
+
+    let active = schedule.active(local(17, 2, 30)).await;
+    assert_eq!(active.len(), 1);
+    assert_eq!(active[0].scope, "Ethereum / Treasury");
+    assert_eq!(active[0].until, local(17, 4, 0));
+}
+
+#[tokio::test]
+async fn test_one_off_window_between_start_and_end() {
+    let window = MaintenanceWindowConfig {
+        start: Some("2026-10-20 09:00".to_string()),
+        end: Some("2026-10-20 12:00".to_string()),
+        ..Default::default()
+    };
+    let schedule = MaintenanceSchedule::new(&[window]).unwrap();
+
+    assert_eq!(schedule.suppressing("Polygon", "Any", local(20, 8, 59)).await, None);
+    assert!(schedule.suppressing("Polygon", "Any", local(20, 9, 0)).await.is_some());
+    assert_eq!(schedule.suppressing("Polygon", "Any", local(20, 12, 0)).await, None);
+}
+
+#[tokio::test]
+async fn test_adhoc_window_until_ended() {
+    let schedule = MaintenanceSchedule::new(&[]).unwrap();
+    let now = local(16, 12, 0);
+
+    schedule.start(Some("Ethereum".to_string()), now + 3600, "@ops").await;
+    assert_eq!(
+        schedule.suppressing("Ethereum", "Treasury", now).await.as_deref(),
+        Some("ad-hoc maintenance by @ops")
+    );
+    assert_eq!(schedule.suppressing("Polygon", "Treasury", now).await, None);
+    assert_eq!(schedule.suppressing("Ethereum", "Treasury", now + 3600).await, None);
+
+    assert_eq!(schedule.end(now).await, 1);
+    assert_eq!(schedule.suppressing("Ethereum", "Treasury", now).await, None);
+    assert_eq!(schedule.end(now).await, 0);
+}
+
+#[test]
+fn test_invalid_windows_are_rejected() {
+    let invalid = [
+        MaintenanceWindowConfig { cron: Some("0 2 * * SAT".to_string()), ..Default::default() },
+        MaintenanceWindowConfig { cron: Some("not a cron".to_string()), duration_mins: Some(60), ..Default::default() },
+        MaintenanceWindowConfig { cron: Some("0 2 * * SAT".to_string()), duration_mins: Some(u64::MAX), ..Default::default() },
+        MaintenanceWindowConfig {
+            start: Some("2026-10-20 12:00".to_string()),
+            end: Some("2026-10-20 09:00".to_string()),
+            ..Default::default()
+        },
+        MaintenanceWindowConfig { start: Some("tomorrow".to_string()), end: Some("2026-10-20 09:00".to_string()), ..Default::default() },
+        MaintenanceWindowConfig::default(),
+    ];
+    for window in invalid {
+        assert!(MaintenanceSchedule::new(std::slice::from_ref(&window)).is_err(), "{:?} should be rejected", window);
+    }
+}
@@ -0,0 +1,894 @@
+use alloy::primitives::{address, b256, U256};
+use async_trait::async_trait;
+use eyre::Result;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+use Oxwatcher::{
+    compare_balances, fit_embed, split_message, to_units, AlertSeverity, AuditLog, AuditQuery, AuditSubject, BalanceInfo,
+    BalanceReport, BalanceStorage, BalanceView, ChangeDigest, ChatRegistration, ChatSubscription, FileBackend, HealthTracker, Heartbeat,
+    LowBalanceAlert, LowBalanceTracker, MatrixConfig, MatrixNotifier, MonitorAlert, NftBalance, Notifier,
+    NotifierSet, SendQueue, StateBackend, TelegramConfig, TelegramNotifier, ThresholdTier, ThrottleConfig, TokenBalance,
+    TransferDirection, TwilioConfig, TwilioNotifier, TxAttribution, WebhookConfig, WebhookNotifier,
+    LOW_BALANCE_KIND,
+};
+use Oxwatcher::logger::BalanceChangeSummary;
+
+mod common;
+
+
+/// Notifier that counts deliveries
+#[derive(Default)]
+struct CountingNotifier {
+    change_alerts: Arc<AtomicUsize>,
+    low_balance_alerts: Arc<AtomicUsize>,
+    reports: Arc<AtomicUsize>,
+}
+
+#[async_trait]
+impl Notifier for CountingNotifier {
+    fn name(&self) -> &str {
+        "counting"
+    }
+
+    async fn send_change_alert(&self, _changes: &BalanceChangeSummary) -> Result<()> {
+        self.change_alerts.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+
+    async fn send_low_balance_alert(&self, _alert: &LowBalanceAlert) -> Result<()> {
+        self.low_balance_alerts.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+
+    async fn send_alert(&self, _alert: &MonitorAlert) -> Result<()> {
+        Ok(())
+    }
+
+    async fn send_report(&self, _report: &BalanceReport) -> Result<()> {
+        self.reports.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+}
+
+fn balance_info(eth: &str) -> BalanceInfo {
+    common::balance_info("relayer", eth)
+}
+
+/// Change of one address's ETH balance from `old` to `new` whole ETH
+fn eth_change(alias: &str, old: u64, new: u64) -> BalanceChangeSummary {
+    let info = |eth: u64| {
+        let mut info = balance_info(&eth.to_string());
+        info.alias = alias.to_string();
+        info.eth_balance = U256::from(eth) * U256::from(10).pow(U256::from(18));
+        info
+    };
+    let mut storage = BalanceStorage::new();
+    storage.update(&info(old));
+    compare_balances(&info(new), &storage)
+}
+
+fn temp_data_dir(name: &str) -> String {
+    let dir = std::env::temp_dir().join(format!("oxwatcher-{}-{}", name, std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir.to_string_lossy().to_string()
+}
+
+#[tokio::test]
+async fn test_notifier_set_fans_out_to_all_channels() -> Result<()> {
+    let first = CountingNotifier::default();
+    let second = CountingNotifier::default();
+    let first_reports = Arc::clone(&first.reports);
+    let second_reports = Arc::clone(&second.reports);
+
+    let mut notifiers = NotifierSet::new();
+    notifiers.push(Box::new(first));
+    notifiers.push(Box::new(second));
+    assert_eq!(notifiers.len(), 2);
+
+    let report = BalanceReport::build(&[balance_info("1.0")], &BalanceStorage::new(), None);
+    notifiers.send_report(&report).await?;
+
+    assert_eq!(first_reports.load(Ordering::SeqCst), 1);
+    assert_eq!(second_reports.load(Ordering::SeqCst), 1);
+    Ok(())
+}
+
+/// Notifier whose deliveries hang until `release` is notified
+struct StalledNotifier {
+    release: Arc<tokio::sync::Notify>,
+}
+
+#[async_trait]
+impl Notifier for StalledNotifier {
+    fn name(&self) -> &str {
+        "stalled"
+    }
+
+    async fn send_change_alert(&self, _changes: &BalanceChangeSummary) -> Result<()> {
+        self.release.notified().await;
+        Ok(())
+    }
+
+    async fn send_low_balance_alert(&self, _alert: &LowBalanceAlert) -> Result<()> {
+        Ok(())
+    }
+
+    async fn send_alert(&self, _alert: &MonitorAlert) -> Result<()> {
+        Ok(())
+    }
+
+    async fn send_report(&self, _report: &BalanceReport) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn test_notifier_set_sends_past_a_stalled_channel() -> Result<()> {
+    let release = Arc::new(tokio::sync::Notify::new());
+    let counting = CountingNotifier::default();
+    let change_alerts = Arc::clone(&counting.change_alerts);
+
+    let mut notifiers = NotifierSet::new();
+    notifiers.push(Box::new(StalledNotifier { release: Arc::clone(&release) }));
+    notifiers.push(Box::new(counting));
+
+    let changes = eth_change("relayer", 2, 1);
+    let delivery = notifiers.send_change_alert(&changes);
+    tokio::pin!(delivery);
+    // The second channel gets the alert while the first one still hangs
+    tokio::select! {
+        _ = &mut delivery => panic!("delivery finished before the stalled channel was released"),
+        _ = async {
+            while change_alerts.load(Ordering::SeqCst) == 0 {
+                tokio::task::yield_now().await;
+            }
+        } => {}
+    }
+    release.notify_one();
+    delivery.await?;
+    assert_eq!(change_alerts.load(Ordering::SeqCst), 1);
+    Ok(())
+}
+
+/// Notifier whose deliveries all fail
+struct FailingNotifier(&'static str);
+
+#[async_trait]
+impl Notifier for FailingNotifier {
+    fn name(&self) -> &str {
+        self.0
+    }
+
+    async fn send_change_alert(&self, _changes: &BalanceChangeSummary) -> Result<()> {
+        Err(eyre::eyre!("503 Service Unavailable"))
+    }
+
+    async fn send_low_balance_alert(&self, _alert: &LowBalanceAlert) -> Result<()> {
+        Err(eyre::eyre!("503 Service Unavailable"))
+    }
+
+    async fn send_alert(&self, _alert: &MonitorAlert) -> Result<()> {
+        Err(eyre::eyre!("503 Service Unavailable"))
+    }
+
+    async fn send_report(&self, _report: &BalanceReport) -> Result<()> {
+        Err(eyre::eyre!("503 Service Unavailable"))
+    }
+}
+
+#[tokio::test]
+async fn test_notifier_set_fails_only_when_every_channel_fails() -> Result<()> {
+    let changes = eth_change("relayer", 2, 1);
+    let alert = MonitorAlert::test("cli");
+
+    // One working channel is enough
+    let mut notifiers = NotifierSet::new();
+    notifiers.push(Box::new(FailingNotifier("slack")));
+    notifiers.push(Box::new(CountingNotifier::default()));
+    notifiers.send_change_alert(&changes).await?;
+    notifiers.send_alert(&alert).await?;
+
+    let mut notifiers = NotifierSet::new();
+    notifiers.push(Box::new(FailingNotifier("slack")));
+    notifiers.push(Box::new(FailingNotifier("discord")));
+    let err = notifiers.send_change_alert(&changes).await.unwrap_err().to_string();
+    assert!(err.contains("slack: 503") && err.contains("discord: 503"), "{}", err);
+    assert!(notifiers.send_critical_alert_to(&alert, &[], 1).await.is_err());
+    // Nothing was attempted, so nothing failed
+    assert!(notifiers.send_alert_to(&alert, &["email".to_string()]).await.is_ok());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_notifier_set_records_deliveries_in_audit_log() -> Result<()> {
+    let backend = Arc::new(FileBackend::new(temp_data_dir("audit")));
+    let _ = std::fs::remove_file(std::path::Path::new(&temp_data_dir("audit")).join("audit.jsonl"));
+
+    let mut notifiers = NotifierSet::new();
+    notifiers.push(Box::new(CountingNotifier::default()));
+    notifiers.push(Box::new(CountingNotifier::default()));
+    notifiers.set_audit(AuditLog::new(backend.clone()));
+
+    let alert = MonitorAlert {
+        kind: "gas_price".to_string(),
+        network_name: "Ethereum".to_string(),
+        chain_id: Some(1),
+        title: "Gas above 50 gwei".to_string(),
+        lines: vec![],
+        data: serde_json::Value::Null,
+        dedup_id: None,
+    };
+    notifiers.send_alert(&alert).await?;
+
+    let entries = backend.load_audit(&AuditQuery::default()).await?;
+    assert_eq!(entries.len(), 2);
+    assert!(entries.iter().all(|e| e.channel == "counting" && e.kind == "gas_price" && e.success));
+    assert_eq!(entries[0].content_hash, entries[1].content_hash);
+    assert_eq!(entries[0].subject, "Gas above 50 gwei");
+
+    let other_network = AuditQuery { network: Some("Base".to_string()), ..Default::default() };
+    assert!(backend.load_audit(&other_network).await?.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_change_digest_collapses_changes_beyond_max_items() {
+    let mut digest = ChangeDigest::new("Ethereum", 1, 2);
+    digest.push(eth_change("payout-1", 10, 9));
+    digest.push(eth_change("payout-2", 10, 8));
+    digest.push(eth_change("payout-3", 10, 9));
+
+    assert_eq!(digest.listed().len(), 2);
+    assert_eq!(digest.collapsed(), 1);
+    let net = digest.net_changes();
+    assert_eq!((net.len(), net[0].asset.as_str(), net[0].formatted()), (1, "ETH", "-4".to_string()));
+    assert_eq!(digest.summary_lines(), vec!["…and 1 more address".to_string(), "Net change: -4 ETH".to_string()]);
+    assert_eq!(digest.directions(), (false, true));
+
+    let json = digest.to_json();
+    assert_eq!(json["count"], 3);
+    assert_eq!(json["changes"].as_array().unwrap().len(), 3);
+    assert_eq!(json["net_changes"]["ETH"], -4.0);
+
+    let filtered = digest.filtered(|changes| changes.alias != "payout-2");
+    assert_eq!(filtered.len(), 2);
+    assert_eq!(filtered.collapsed(), 0);
+    assert_eq!(filtered.summary_lines(), vec!["Net change: -2 ETH".to_string()]);
+
+    // Sums are exact in base units, even next to large balances
+    let mut digest = ChangeDigest::new("Ethereum", 1, 2);
+    let whale = U256::from(10).pow(U256::from(27));
+    for _ in 0..2 {
+        let mut change = eth_change("whale", 1, 2);
+        let eth = change.eth_change.as_mut().unwrap();
+        (eth.old_balance, eth.new_balance) = (whale, whale + U256::from(1));
+        digest.push(change);
+    }
+    assert_eq!(digest.net_changes()[0].formatted(), "+0.000000000000000002");
+}
+
+#[tokio::test]
+async fn test_digest_falls_back_to_single_alerts_and_audits_once() -> Result<()> {
+    let backend = Arc::new(FileBackend::new(temp_data_dir("digest")));
+    let _ = std::fs::remove_file(std::path::Path::new(&temp_data_dir("digest")).join("audit.jsonl"));
+
+    let channel = CountingNotifier::default();
+    let change_alerts = Arc::clone(&channel.change_alerts);
+    let mut notifiers = NotifierSet::new();
+    notifiers.push(Box::new(channel));
+    notifiers.set_audit(AuditLog::new(backend.clone()));
+
+    let mut digest = ChangeDigest::new("Ethereum", 1, 10);
+    for i in 0..3 {
+        digest.push(eth_change(&format!("payout-{}", i), 10, 9));
+    }
+    notifiers.send_change_digest(&digest).await?;
+
+    // Channels without digest support get one alert per address
+    assert_eq!(change_alerts.load(Ordering::SeqCst), 3);
+    let entries = backend.load_audit(&AuditQuery::default()).await?;
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].kind, "balance_change");
+    assert_eq!(entries[0].subject, "3 balance changes");
+
+    // A digest with one change is a regular change alert
+    let single = digest.filtered(|changes| changes.alias == "payout-0");
+    notifiers.send_change_digest(&single).await?;
+    assert_eq!(change_alerts.load(Ordering::SeqCst), 4);
+    assert_eq!(backend.load_audit(&AuditQuery::default()).await?[0].subject, "payout-0");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_heartbeat_counts_ok_networks_and_errors_in_period() {
+    let health = HealthTracker::new();
+    health.register("Ethereum", Some(1), 60, 2, 1_000).await;
+    health.register("Base", Some(8453), 60, 1, 1_000).await;
+    health.record_check("Ethereum", true, Some("timeout".to_string()), 1_060).await;
+    health.record_check("Ethereum", true, Some("timeout".to_string()), 1_120).await;
+    health.record_check("Base", false, Some("connection refused".to_string()), 1_060).await;
+
+    // One Ethereum error happened in the previous period
+    let previous: HashMap<String, u64> = [("Ethereum".to_string(), 1)].into();
+    let heartbeat = Heartbeat::build(&health.all().await, &previous, 24, 7_200);
+    assert_eq!(heartbeat.networks_total, 2);
+    assert_eq!(heartbeat.networks_ok, 1);
+    assert_eq!(heartbeat.failing, vec!["Base".to_string()]);
+    assert_eq!(heartbeat.errors, 2);
+
+    let alert = heartbeat.to_alert();
+    assert_eq!(alert.kind, "heartbeat");
+    assert!(alert.title.contains("1 of 2 network(s) failing"));
+    assert!(alert.lines.contains(&"RPC errors in last 24h: 2".to_string()));
+}
+
+#[test]
+fn test_test_alert_is_not_tied_to_a_network() {
+    let alert = MonitorAlert::test("send-test-alert");
+    assert_eq!(alert.kind, Oxwatcher::TEST_ALERT_KIND);
+    assert_eq!(alert.chain_id, None);
+    assert!(alert.lines[0].contains("send-test-alert"));
+}
+
+#[tokio::test]
+async fn test_low_balance_tracker_throttles_repeated_alerts() {
+    let tracker = LowBalanceTracker::new(Arc::new(FileBackend::new(temp_data_dir("throttle")))).await;
+    let no_tokens = HashMap::new();
+
+    // First low reading alerts immediately
+    let alerts = tracker.check(&balance_info("0.1"), Some(0.5), &no_tokens).await;
+    assert_eq!(alerts.len(), 1);
+    assert_eq!(alerts[0].alert_number, 1);
+    assert!(alerts[0].is_native);
+
+    // Second reading right after is throttled
+    let alerts = tracker.check(&balance_info("0.1"), Some(0.5), &no_tokens).await;
+    assert!(alerts.is_empty());
+
+    let throttled = tracker.throttled().await;
+    assert_eq!(throttled.len(), 1);
+    assert_eq!(throttled[0].key, "Ethereum:relayer");
+    assert_eq!(throttled[0].next_alert_at, throttled[0].last_sent + 10 * 60);
+
+    // Recovery resets the streak, so the next drop alerts immediately again
+    assert!(tracker.check(&balance_info("1.0"), Some(0.5), &no_tokens).await.is_empty());
+    assert!(tracker.throttled().await.is_empty());
+    let alerts = tracker.check(&balance_info("0.1"), Some(0.5), &no_tokens).await;
+    assert_eq!(alerts.len(), 1);
+    assert_eq!(alerts[0].alert_number, 1);
+}
+
+#[tokio::test]
+async fn test_low_balance_threshold_tiers() {
+    let tracker = LowBalanceTracker::new(Arc::new(FileBackend::new(temp_data_dir("tiers")))).await;
+    let tiers = vec![
+        ThresholdTier::below(2.0),
+        ThresholdTier {
+            below: 0.5,
+            severity: AlertSeverity::Critical,
+            channels: vec!["slack".to_string()],
+            message: Some("Refill {alias} now, {balance} {asset} left".to_string()),
+        },
+    ];
+    let no_tokens = HashMap::new();
+
+    let alerts = tracker.check_tiers(&balance_info("1.5"), &tiers, &no_tokens).await;
+    assert_eq!(alerts.len(), 1);
+    assert_eq!(alerts[0].severity, AlertSeverity::Warning);
+    assert_eq!(alerts[0].threshold, 2.0);
+    assert!(alerts[0].channels.is_empty());
+    assert_eq!(alerts[0].call_to_action(), "Please top up your balance!");
+    let warning = alerts[0].clone();
+    assert!(tracker.check_tiers(&balance_info("1.2"), &tiers, &no_tokens).await.is_empty());
+
+    // Dropping into the critical tier alerts right away and starts a new streak
+    let alerts = tracker.check_tiers(&balance_info("0.3"), &tiers, &no_tokens).await;
+    assert_eq!(alerts.len(), 1);
+    assert_eq!(alerts[0].severity, AlertSeverity::Critical);
+    assert_eq!(alerts[0].alert_number, 1);
+    assert_eq!(alerts[0].channels, vec!["slack".to_string()]);
+    assert_eq!(alerts[0].message.as_deref(), Some("Refill relayer now, 0.3 ETH left"));
+    assert!(tracker.check_tiers(&balance_info("0.2"), &tiers, &no_tokens).await.is_empty());
+
+    // Routed only to the tier's channels
+    let channel = CountingNotifier::default();
+    let delivered = Arc::clone(&channel.low_balance_alerts);
+    let mut notifiers = NotifierSet::new();
+    notifiers.push(Box::new(channel));
+    notifiers.send_low_balance_alert(&alerts[0]).await.unwrap();
+    assert_eq!(delivered.load(Ordering::SeqCst), 0);
+    notifiers.send_low_balance_alert(&warning).await.unwrap();
+    assert_eq!(delivered.load(Ordering::SeqCst), 1);
+
+    // Critical tiers are sent as critical alerts that keep their severity and call to action
+    let critical = MonitorAlert::low_balance(&alerts[0]);
+    assert_eq!(critical.kind, LOW_BALANCE_KIND);
+    assert_eq!(critical.title, "🚨 Low balance #1: relayer");
+    assert_eq!(critical.lines[1], "ETH: 0.3 (below 0.5 ETH)");
+    assert_eq!(critical.lines.last().unwrap(), "Refill relayer now, 0.3 ETH left");
+    assert_eq!(critical.data["severity"], "critical");
+}
+
+/// Notifier with low balance alerts switched on or off, counting the alerts it sends
+struct ToggledNotifier {
+    low_balance: bool,
+    alerts: Arc<AtomicUsize>,
+}
+
+#[async_trait]
+impl Notifier for ToggledNotifier {
+    fn name(&self) -> &str {
+        "toggled"
+    }
+
+    fn sends(&self, subject: &AuditSubject) -> bool {
+        self.low_balance || subject.kind != LOW_BALANCE_KIND
+    }
+
+    async fn send_change_alert(&self, _changes: &BalanceChangeSummary) -> Result<()> {
+        Ok(())
+    }
+
+    async fn send_low_balance_alert(&self, _alert: &LowBalanceAlert) -> Result<()> {
+        Ok(())
+    }
+
+    async fn send_alert(&self, _alert: &MonitorAlert) -> Result<()> {
+        self.alerts.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+
+    async fn send_report(&self, _report: &BalanceReport) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn test_critical_low_balance_alerts_follow_toggles_subscriptions_and_mute() -> Result<()> {
+    let tracker = LowBalanceTracker::new(Arc::new(FileBackend::new(temp_data_dir("critical-tiers")))).await;
+    let tiers = vec![ThresholdTier { severity: AlertSeverity::Critical, ..ThresholdTier::below(0.5) }];
+    let alerts = tracker.check_tiers(&balance_info("0.3"), &tiers, &HashMap::new()).await;
+    let critical = MonitorAlert::low_balance(&alerts[0]);
+
+    // Channels with low balance alerts disabled skip the critical tier too, and aren't audited
+    let backend = Arc::new(FileBackend::new(temp_data_dir("critical-low-balance")));
+    let _ = std::fs::remove_file(std::path::Path::new(&temp_data_dir("critical-low-balance")).join("audit.jsonl"));
+    let enabled = Arc::new(AtomicUsize::new(0));
+    let disabled = Arc::new(AtomicUsize::new(0));
+    let mut notifiers = NotifierSet::new();
+    notifiers.push(Box::new(ToggledNotifier { low_balance: true, alerts: Arc::clone(&enabled) }));
+    notifiers.push(Box::new(ToggledNotifier { low_balance: false, alerts: Arc::clone(&disabled) }));
+    notifiers.set_audit(AuditLog::new(backend.clone()));
+    notifiers.send_critical_alert_to(&critical, &[], 1).await?;
+    assert_eq!(enabled.load(Ordering::SeqCst), 1);
+    assert_eq!(disabled.load(Ordering::SeqCst), 0);
+    assert_eq!(backend.load_audit(&AuditQuery::default()).await?.len(), 1);
+
+    let chat = |subscriptions: Vec<ChatSubscription>, muted_until: Option<u64>| ChatRegistration {
+        chat_id: 42,
+        user_id: 7,
+        username: "ops".to_string(),
+        subscriptions,
+        muted_until,
+    };
+    let subscribed = |alias: &str| vec![ChatSubscription { network: None, alias: Some(alias.to_string()) }];
+    let telegram = |mute_low_balance: bool| async move {
+        let config: TelegramConfig =
+            serde_yaml::from_str(&format!("bot_token: \"123:abc\"\nmute_low_balance: {}", mute_low_balance)).unwrap();
+        let backend: Arc<dyn StateBackend> = Arc::new(FileBackend::new(temp_data_dir("telegram-critical")));
+        TelegramNotifier::new(&config, None, Arc::new(RwLock::new(BalanceStorage::new())), backend).await
+    };
+
+    // Telegram chats get the alert only for the aliases they subscribed to
+    let unmuting = telegram(false).await;
+    assert!(unmuting.wants_critical_alert(&chat(subscribed("relayer"), None), &critical, 1_000));
+    assert!(!unmuting.wants_critical_alert(&chat(subscribed("treasury"), None), &critical, 1_000));
+
+    // Muted chats still get it unless mute covers low balance alerts
+    assert!(unmuting.wants_critical_alert(&chat(vec![], Some(2_000)), &critical, 1_000));
+    let muting = telegram(true).await;
+    assert!(!muting.wants_critical_alert(&chat(vec![], Some(2_000)), &critical, 1_000));
+    assert!(muting.wants_critical_alert(&chat(vec![], Some(2_000)), &critical, 2_000));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_low_balance_compares_raw_balances() {
+    assert_eq!(to_units(1.5, 6), U256::from(1_500_000));
+    assert_eq!(to_units(0.1, 18), U256::from(100_000_000_000_000_000u64));
+    assert_eq!(to_units(0.1234567, 6), U256::from(123_456));
+    assert_eq!(to_units(-1.0, 18), U256::ZERO);
+
+    let tracker = LowBalanceTracker::new(Arc::new(FileBackend::new(temp_data_dir("raw")))).await;
+    let usdc = |balance: u64| TokenBalance {
+        alias: "USDC".to_string(),
+        balance: U256::from(balance),
+        formatted: (balance as f64 / 1e6).to_string(),
+        decimals: 6,
+        usd_value: None,
+        shares: None,
+    };
+    let thresholds = HashMap::from([("USDC".to_string(), 100.0)]);
+
+    // Uses the token's own decimals: 100 USDC is 100_000_000 base units
+    let mut balance = balance_info("1");
+    balance.token_balances = vec![usdc(100_000_000)];
+    assert!(tracker.check(&balance, None, &thresholds).await.is_empty());
+    balance.token_balances = vec![usdc(99_999_999)];
+    let alerts = tracker.check(&balance, None, &thresholds).await;
+    assert_eq!(alerts.len(), 1);
+    assert_eq!(alerts[0].asset, "USDC");
+
+    // One wei below a threshold is too small a difference for floats, not for U256
+    let mut balance = balance_info("1");
+    balance.alias = "whale".to_string();
+    balance.eth_balance = to_units(1e9, 18) - U256::from(1);
+    balance.eth_formatted = "999999999.999999999999999999".to_string();
+    assert_eq!(tracker.check(&balance, Some(1e9), &HashMap::new()).await.len(), 1);
+
+    // An empty wallet is below every threshold
+    let mut balance = balance_info("0");
+    balance.alias = "empty".to_string();
+    balance.token_balances = vec![usdc(0)];
+    let alerts = tracker.check(&balance, Some(0.5), &thresholds).await;
+    let assets: Vec<&str> = alerts.iter().map(|alert| alert.asset.as_str()).collect();
+    assert_eq!(assets, vec!["ETH", "USDC"]);
+}
+
+#[tokio::test]
+async fn test_low_balance_throttle_schedule() {
+    let default = ThrottleConfig::default();
+    let waits: Vec<u64> = (0..6).map(|sent| default.interval_secs(sent) / 60).collect();
+    assert_eq!(waits, vec![0, 10, 60, 300, 1200, 1200]);
+
+    let schedule = ThrottleConfig { intervals_mins: vec![5, 30], repeat_mins: Some(240) };
+    assert_eq!(schedule.interval_secs(1), 5 * 60);
+    assert_eq!(schedule.interval_secs(2), 30 * 60);
+    assert_eq!(schedule.interval_secs(7), 240 * 60);
+    assert!(!schedule.is_repeating(2));
+    assert!(schedule.is_repeating(3));
+
+    // Only a repeat interval means alerts at a fixed interval
+    let fixed = ThrottleConfig { intervals_mins: vec![], repeat_mins: Some(90) };
+    assert_eq!(fixed.interval_secs(1), 90 * 60);
+
+    let tracker = LowBalanceTracker::new(Arc::new(FileBackend::new(temp_data_dir("throttle-schedule"))))
+        .await
+        .with_schedule(schedule);
+    let alerts = tracker.check(&balance_info("0.1"), Some(0.5), &HashMap::new()).await;
+    assert_eq!(alerts[0].next_alert, "Next alert in 5 minutes");
+    let throttled = tracker.throttled().await;
+    assert_eq!(throttled[0].next_alert_at, throttled[0].last_sent + 5 * 60);
+
+    let fixed_tracker = LowBalanceTracker::new(Arc::new(FileBackend::new(temp_data_dir("throttle-fixed"))))
+        .await
+        .with_schedule(fixed);
+    let alerts = fixed_tracker.check(&balance_info("0.1"), Some(0.5), &HashMap::new()).await;
+    assert_eq!(alerts[0].next_alert, "Alerts every 90 minutes");
+}
+
+#[tokio::test]
+async fn test_low_balance_tracker_restores_state_from_backend() {
+    let dir = temp_data_dir("restore");
+    let no_tokens = HashMap::new();
+
+    let tracker = LowBalanceTracker::new(Arc::new(FileBackend::new(&dir))).await;
+    assert_eq!(tracker.check(&balance_info("0.1"), Some(0.5), &no_tokens).await.len(), 1);
+
+    let sizes = FileBackend::new(&dir).storage_sizes().await.unwrap();
+    assert!(sizes.iter().any(|(name, size)| name == "alert_states.json" && *size > 0));
+
+    // A restarted tracker keeps throttling the ongoing streak
+    let restarted = LowBalanceTracker::new(Arc::new(FileBackend::new(&dir))).await;
+    assert!(restarted.check(&balance_info("0.1"), Some(0.5), &no_tokens).await.is_empty());
+}
+
+#[tokio::test]
+async fn test_webhook_gives_up_on_a_hung_endpoint() {
+    // Accepts connections but never answers
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let hung = tokio::spawn(async move {
+        let mut connections = Vec::new();
+        while let Ok((socket, _)) = listener.accept().await {
+            connections.push(socket);
+        }
+    });
+
+    let config: WebhookConfig = serde_yaml::from_str(&format!(
+        "url: http://127.0.0.1:{}/hook\nretry:\n  max_attempts: 2\n  backoff_secs: 0\n  timeout_secs: 1\n",
+        port
+    ))
+    .unwrap();
+    let alert = MonitorAlert {
+        kind: "gas_price".to_string(),
+        network_name: "Ethereum".to_string(),
+        chain_id: Some(1),
+        title: "Gas above 50 gwei".to_string(),
+        lines: vec![],
+        data: serde_json::json!({}),
+        dedup_id: None,
+    };
+    let result = tokio::time::timeout(Duration::from_secs(10), WebhookNotifier::new(&config).send_alert(&alert)).await;
+    assert!(result.expect("each attempt times out").is_err());
+    hung.abort();
+}
+
+#[test]
+fn test_webhook_signature_matches_hmac_sha256() {
+    let signature = WebhookNotifier::sign("key", b"The quick brown fox jumps over the lazy dog");
+    assert_eq!(
+        signature,
+        "sha256=f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8"
+    );
+}
+
+#[test]
+fn test_change_summary_json_includes_transactions() {
+    let mut storage = BalanceStorage::new();
+    storage.update(&balance_info("0"));
+    let mut current = balance_info("1");
+    current.eth_balance = U256::from(10).pow(U256::from(18));
+
+    let mut changes = compare_balances(&current, &storage);
+    changes.transactions.push(TxAttribution {
+        tx_hash: b256!("5c504ed432cb51138bcf09aa5e8a410dd4a1e204ef84bfed1be16dfba1b22060"),
+        block_number: 46147,
+        asset: "ETH".to_string(),
+        direction: TransferDirection::Incoming,
+        counterparty: Some(address!("a1e4380a3b1f749673e270229993ee55f35663b4")),
+        value: U256::from(10).pow(U256::from(18)),
+        url: "https://etherscan.io/tx/0x5c504ed432cb51138bcf09aa5e8a410dd4a1e204ef84bfed1be16dfba1b22060".parse().ok(),
+    });
+
+    let json = changes.to_json();
+    let tx = &json["transactions"][0];
+    assert_eq!(tx["direction"], "incoming");
+    assert_eq!(tx["block_number"], 46147);
+    assert_eq!(tx["counterparty"], "0xa1e4380a3b1f749673e270229993ee55f35663b4");
+    assert_eq!(
+        tx["url"],
+        "https://etherscan.io/tx/0x5c504ed432cb51138bcf09aa5e8a410dd4a1e204ef84bfed1be16dfba1b22060"
+    );
+}
+
+#[test]
+fn test_nft_changes_entered_and_left() {
+    let nft = |token_id: Option<u64>, count: u64| NftBalance {
+        alias: "Treasury Punks".to_string(),
+        token_id: token_id.map(U256::from),
+        count: U256::from(count),
+    };
+
+    let mut previous = balance_info("0");
+    previous.nft_balances = vec![nft(None, 3), nft(Some(42), 1), nft(Some(7), 0)];
+    let mut storage = BalanceStorage::new();
+    storage.update(&previous);
+
+    let mut current = balance_info("0");
+    current.nft_balances = vec![nft(None, 3), nft(Some(42), 0), nft(Some(7), 1), nft(Some(9), 1)];
+    let changes = compare_balances(&current, &storage);
+
+    // Unchanged counts and newly tracked IDs produce no changes
+    assert!(changes.has_changes());
+    assert_eq!(changes.nft_changes.len(), 2);
+    assert_eq!(changes.nft_changes[0].label, "Treasury Punks #42");
+    assert!(changes.nft_changes[0].left());
+    assert_eq!(changes.nft_changes[0].describe(), "left wallet (1 → 0)");
+    assert!(changes.nft_changes[1].entered());
+    assert_eq!(changes.to_json()["nft_changes"][1]["nft"], "Treasury Punks #7");
+}
+
+#[test]
+fn test_rebasing_token_changes_follow_shares() {
+    let steth = |balance: u64, shares: Option<u64>| TokenBalance {
+        alias: "stETH".to_string(),
+        balance: U256::from(balance),
+        formatted: balance.to_string(),
+        decimals: 18,
+        usd_value: None,
+        shares: shares.map(U256::from),
+    };
+
+    let mut previous = balance_info("0");
+    previous.token_balances = vec![steth(1_000_000, Some(900_000))];
+    let mut storage = BalanceStorage::new();
+    storage.update(&previous);
+
+    // A daily rebase grows the balance but not the shares
+    let mut current = balance_info("0");
+    current.token_balances = vec![steth(1_000_100, Some(900_000))];
+    assert!(!compare_balances(&current, &storage).has_changes());
+
+    // Without shares, a change threshold hides the rebase but not a transfer
+    current.token_balances = vec![steth(1_000_100, None)];
+    let mut changes = compare_balances(&current, &storage);
+    changes.ignore_changes_below("stETH", 1.0);
+    assert!(!changes.has_changes());
+
+    current.token_balances = vec![steth(800_000, None)];
+    let mut changes = compare_balances(&current, &storage);
+    changes.ignore_changes_below("stETH", 1.0);
+    assert!(changes.has_changes());
+}
+
+#[test]
+fn test_balance_view_callback_data_round_trips() {
+    let views = [
+        BalanceView::Networks,
+        BalanceView::Addresses { network: 3, page: 1 },
+        BalanceView::Address { network: 3, page: 1, address: 12 },
+    ];
+    for view in views {
+        let data = view.to_data();
+        assert!(data.len() <= 64);
+        assert_eq!(BalanceView::parse(&data), Some(view));
+    }
+
+    assert_eq!(BalanceView::parse("bal:1"), None);
+    assert_eq!(BalanceView::parse("bal:x:0"), None);
+    assert_eq!(BalanceView::parse("other:1:0"), None);
+}
+
+#[tokio::test]
+async fn test_report_baseline_persists_between_reports() {
+    let backend = FileBackend::new(temp_data_dir("report-baseline"));
+    assert!(backend.load_report_baseline().await.unwrap().is_none());
+
+    let mut at_last_report = BalanceStorage::new();
+    at_last_report.update(&balance_info("1.0"));
+    backend.save_report_baseline(&at_last_report).await.unwrap();
+
+    // Diffed against the previous report, not the latest check
+    let mut current = balance_info("0.5");
+    current.eth_balance = U256::from(5u64);
+    let baseline = backend.load_report_baseline().await.unwrap().unwrap();
+    let report = BalanceReport::build(&[current], &baseline, None);
+    assert_eq!(report.total_changes(), 1);
+}
+
+#[test]
+fn test_split_message_at_line_breaks() {
+    assert_eq!(split_message("short", 10), vec!["short"]);
+    assert_eq!(split_message("", 10), vec![""]);
+    assert_eq!(split_message("aaaa\nbbbb\ncccc\n", 10), vec!["aaaa\nbbbb\n", "cccc\n"]);
+
+    // Lines over the limit are cut, counting characters rather than bytes
+    let parts = split_message("ab\nééééééé\ncd", 4);
+    assert_eq!(parts, vec!["ab\n", "éééé", "ééé\n", "cd"]);
+    assert!(parts.iter().all(|part| part.chars().count() <= 4));
+
+    // Tags and entities are never cut, and tags open at a cut are closed and reopened
+    let parts = split_message("<b>Tom &amp; Jerry</b>", 16);
+    assert_eq!(parts, vec!["<b>Tom &amp;</b>", "<b> Jerry</b>"]);
+    let parts = split_message("<a href=\"https://example.com\">link</a>\n<code>0xabcdef</code>", 40);
+    assert_eq!(parts, vec!["<a href=\"https://example.com\">link</a>\n", "<code>0xabcdef</code>"]);
+    assert!(parts.iter().all(|part| part.chars().count() <= 40));
+}
+
+#[test]
+fn test_discord_embeds_fit_limits() {
+    let fields: Vec<serde_json::Value> = (0..40)
+        .map(|i| serde_json::json!({ "name": format!("Token {}", i), "value": "x".repeat(1500), "inline": false }))
+        .collect();
+    let embed = fit_embed(serde_json::json!({
+        "title": "t".repeat(300),
+        "description": format!("first line\n{}", "d".repeat(5000)),
+        "fields": fields,
+        "footer": { "text": "Next alert in 1h" },
+    }));
+
+    let text_len = |value: &serde_json::Value| value.as_str().unwrap().chars().count();
+    let title = &embed["title"];
+    assert_eq!(text_len(title), 256);
+    assert!(title.as_str().unwrap().ends_with('…'));
+    // Long texts are cut at a line break
+    assert_eq!(embed["description"], "first line…");
+
+    let fields = embed["fields"].as_array().unwrap();
+    assert!(fields.len() <= 25);
+    assert!(fields.iter().all(|field| text_len(&field["value"]) <= 1024));
+    let hidden = 40 - (fields.len() - 1);
+    assert_eq!(fields.last().unwrap()["value"], format!("…and {} more", hidden));
+    let total: usize = text_len(title)
+        + text_len(&embed["description"])
+        + text_len(&embed["footer"]["text"])
+        + fields.iter().map(|field| text_len(&field["name"]) + text_len(&field["value"])).sum::<usize>();
+    assert!(total <= 6000);
+
+    // Embeds within the limits are left as they are
+    let embed = serde_json::json!({ "title": "Gas above 50 gwei", "description": "Ethereum", "color": 1 });
+    assert_eq!(fit_embed(embed.clone()), embed);
+}
+
+#[tokio::test]
+async fn test_telegram_templates_escape_variables() {
+    let config: TelegramConfig = serde_yaml::from_str(
+        r#"
+bot_token: "123:abc"
+templates:
+  alert: "<b>{title}</b> {network}: {details}"
+  balance_change: "<b>{alias}</b> on {network}"
+"#,
+    )
+    .unwrap();
+    let backend: Arc<dyn StateBackend> = Arc::new(FileBackend::new(temp_data_dir("telegram-templates")));
+    let telegram = TelegramNotifier::new(&config, None, Arc::new(RwLock::new(BalanceStorage::new())), backend).await;
+
+    let mut changes = eth_change("<ops & co>", 1, 2);
+    changes.network_name = "L2 <test>".to_string();
+    assert_eq!(telegram.format_change_message(&changes), "<b>&lt;ops &amp; co&gt;</b> on L2 &lt;test&gt;");
+
+    let mut alert = MonitorAlert::test("<script>");
+    alert.title = "Gas > 50 & rising".to_string();
+    assert_eq!(
+        telegram.format_alert_message(&alert),
+        "<b>Gas &gt; 50 &amp; rising</b> All networks: Sent by &lt;script&gt; to check that this channel receives alerts."
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_send_queue_paces_each_chat() {
+    let queue = SendQueue::new(30.0);
+    let start = tokio::time::Instant::now();
+
+    // One message per second to the same chat, while other chats aren't held up
+    queue.acquire(1, 1.0).await;
+    queue.acquire(2, 1.0).await;
+    assert!(start.elapsed() < Duration::from_millis(100));
+    queue.acquire(1, 1.0).await;
+    assert!(start.elapsed() >= Duration::from_secs(1));
+}
+
+#[test]
+fn test_matrix_send_url_targets_room() {
+    let config: MatrixConfig = serde_yaml::from_str(
+        r#"
+homeserver_url: "https://matrix.example.org/"
+access_token: "syt_token"
+rooms: ["!ops:example.org"]
+"#,
+    )
+    .unwrap();
+    let matrix = MatrixNotifier::new(&config);
+
+    assert_eq!(
+        matrix.send_url("!ops:example.org", "txn-1").unwrap().as_str(),
+        "https://matrix.example.org/_matrix/client/v3/rooms/!ops:example.org/send/m.room.message/txn-1"
+    );
+
+    let config = MatrixConfig { homeserver_url: "mailto:ops@example.org".parse().unwrap(), ..config };
+    assert!(MatrixNotifier::new(&config).send_url("!ops:example.org", "txn-1").is_err());
+}
+
+#[test]
+fn test_twilio_texts_critical_alerts_within_hourly_cap() {
+    assert!(TwilioNotifier::is_texted("low_balance", Some(AlertSeverity::Critical)));
+    assert!(TwilioNotifier::is_texted("drained", None));
+    assert!(TwilioNotifier::is_texted("test", None));
+    assert!(!TwilioNotifier::is_texted("low_balance", Some(AlertSeverity::Warning)));
+    assert!(!TwilioNotifier::is_texted("balance_change", None));
+
+    let config: TwilioConfig = serde_yaml::from_str(
+        r#"
+account_sid: "AC123"
+auth_token: "secret"
+from: "+14155550100"
+to: ["+14155550101"]
+max_per_hour: 2
+"#,
+    )
+    .unwrap();
+    let twilio = TwilioNotifier::new(&config);
+
+    let start = tokio::time::Instant::now();
+    assert!(twilio.take_slot(start));
+    assert!(twilio.take_slot(start + Duration::from_secs(60)));
+    assert!(!twilio.take_slot(start + Duration::from_secs(120)));
+    // The first alert leaves the window after an hour
+    assert!(twilio.take_slot(start + Duration::from_secs(3600)));
+    assert!(!twilio.take_slot(start + Duration::from_secs(3601)));
+}
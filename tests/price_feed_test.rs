@@ -0,0 +1,48 @@
+use Oxwatcher::{FeedConfig, FeedIssue, FeedMonitor, FeedRound};
+
+fn feed() -> FeedConfig {
+    serde_yaml::from_str(
+        r#"
+alias: ETH / USD
+address: 0x5f4eC3Df9cbd43714FE2740f5E3616155c5b8419
+heartbeat_secs: 3600
+deviation_percent: 5
+"#,
+    )
+    .unwrap()
+}
+
+#[tokio::test]
+async fn test_feed_deviation_between_checks() {
+    let feed = feed();
+    let monitor = FeedMonitor::new(vec![feed.clone()]);
+    let round = |answer: f64| FeedRound { answer, updated_at: 1_000 };
+
+    assert!(monitor.record(&feed, round(2000.0), 1_000).await.is_empty());
+    assert!(monitor.record(&feed, round(2080.0), 1_000).await.is_empty());
+
+    let issues = monitor.record(&feed, round(1900.0), 1_000).await;
+    match issues.as_slice() {
+        [FeedIssue::Deviation { previous, percent }] => {
+            assert_eq!(*previous, 2080.0);
+            assert!((percent + 8.65).abs() < 0.01);
+        }
+        other => panic!("unexpected issues: {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_feed_stale_alerts_once() {
+    let feed = feed();
+    let monitor = FeedMonitor::new(vec![feed.clone()]);
+    let round = FeedRound { answer: 2000.0, updated_at: 1_000 };
+
+    assert!(monitor.record(&feed, round, 4_000).await.is_empty());
+    assert_eq!(monitor.record(&feed, round, 5_000).await.len(), 1);
+    assert!(monitor.record(&feed, round, 6_000).await.is_empty());
+
+    // A fresh answer resets the staleness
+    let fresh = FeedRound { answer: 2000.0, updated_at: 6_500 };
+    assert!(monitor.record(&feed, fresh, 7_000).await.is_empty());
+    assert_eq!(monitor.record(&feed, fresh, 11_000).await.len(), 1);
+}
@@ -0,0 +1,96 @@
+use alloy::primitives::aliases::U80;
+use alloy::primitives::{I256, U256};
+use std::collections::HashMap;
+use std::time::Duration;
+use Oxwatcher::{check_chainlink_round, format_usd, AssetPrices, BalanceInfo, BalanceStorage, IAggregatorV3, Portfolio};
+
+mod common;
+
+
+#[test]
+fn test_format_usd() {
+    assert_eq!(format_usd(8400.4), "$8,400");
+    assert_eq!(format_usd(1234567.0), "$1,234,567");
+    assert_eq!(format_usd(999.5), "$999.50");
+    assert_eq!(format_usd(0.0), "$0.00");
+    assert_eq!(format_usd(-2500.0), "-$2,500");
+}
+
+#[test]
+fn test_asset_prices_fill_usd_values() {
+    let mut balance = BalanceInfo {
+        token_balances: vec![common::token_balance("USDC", "100", 6), common::token_balance("UNPRICED", "5", 18)],
+        ..common::balance_info("hot-wallet", "2.5")
+    };
+
+    let prices = AssetPrices {
+        native: Some(3360.0),
+        tokens: HashMap::from([("USDC".to_string(), 1.0)]),
+    };
+    prices.apply(&mut balance);
+
+    assert_eq!(balance.eth_usd_value, Some(8400.0));
+    assert_eq!(balance.token_balances[0].usd_value, Some(100.0));
+    assert_eq!(balance.token_balances[1].usd_value, None);
+}
+
+fn priced_balance(network: &str, alias: &str, usd: Option<f64>) -> BalanceInfo {
+    BalanceInfo { network_name: network.to_string(), eth_usd_value: usd, ..common::balance_info(alias, "0") }
+}
+
+#[test]
+fn test_portfolio_totals_and_day_change() {
+    let balances = vec![
+        priced_balance("Polygon", "relayer", Some(50.0)),
+        priced_balance("Ethereum", "treasury", Some(1000.0)),
+        priced_balance("Ethereum", "hot-wallet", Some(200.0)),
+        priced_balance("Ethereum", "unpriced", None),
+    ];
+
+    let mut previous = BalanceStorage::new();
+    previous.update(&priced_balance("Ethereum", "treasury", Some(800.0)));
+
+    let portfolio = Portfolio::build(&balances, Some(&previous));
+
+    assert_eq!(portfolio.addresses.len(), 3);
+    assert_eq!(portfolio.addresses[0].alias, "hot-wallet");
+    assert_eq!(portfolio.networks.len(), 2);
+    assert_eq!(portfolio.networks[0].network_name, "Ethereum");
+    assert_eq!(portfolio.networks[0].total.usd, 1200.0);
+    assert_eq!(portfolio.total.usd, 1250.0);
+
+    assert_eq!(portfolio.addresses[1].total.describe(), "$1,000 (+$200.00, +25.00%)");
+
+    // Totals only show a change when every included address has a baseline
+    assert_eq!(portfolio.total.previous_usd, None);
+    assert_eq!(portfolio.networks[1].total.describe(), "$50.00");
+
+    previous.update(&priced_balance("Ethereum", "hot-wallet", Some(250.0)));
+    let portfolio = Portfolio::build(&balances[1..3], Some(&previous));
+    assert_eq!(portfolio.total.describe(), "$1,200 (+$150.00, +14.29%)");
+}
+
+#[test]
+fn test_chainlink_rounds_must_be_fresh_and_complete() {
+    let round = |round_id: u64, answer: i64, updated_at: u64, answered_in_round: u64| IAggregatorV3::latestRoundDataReturn {
+        roundId: U80::from(round_id),
+        answer: I256::try_from(answer).unwrap(),
+        startedAt: U256::from(updated_at),
+        updatedAt: U256::from(updated_at),
+        answeredInRound: U80::from(answered_in_round),
+    };
+    let heartbeat = Duration::from_secs(3600);
+    let now = 10_000;
+
+    assert!(check_chainlink_round(&round(7, 2000_0000_0000, 9_000, 7), now, heartbeat).is_ok());
+    // Updated exactly one heartbeat ago is still fresh
+    assert!(check_chainlink_round(&round(7, 2000_0000_0000, now - 3600, 7), now, heartbeat).is_ok());
+
+    let error = check_chainlink_round(&round(7, 2000_0000_0000, 1_000, 7), now, heartbeat).unwrap_err();
+    assert_eq!(error.to_string(), "answer is stale: last updated 150 min ago (heartbeat: 60 min)");
+    let error = check_chainlink_round(&round(7, 2000_0000_0000, 9_000, 6), now, heartbeat).unwrap_err();
+    assert_eq!(error.to_string(), "answer is from round 6 before the latest round 7");
+    assert!(check_chainlink_round(&round(7, 2000_0000_0000, 0, 7), now, heartbeat).is_err());
+    assert!(check_chainlink_round(&round(7, 0, 9_000, 7), now, heartbeat).is_err());
+    assert!(check_chainlink_round(&round(7, -1, 9_000, 7), now, heartbeat).is_err());
+}
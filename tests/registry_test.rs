@@ -0,0 +1,86 @@
+use alloy::primitives::address;
+use std::sync::Arc;
+use Oxwatcher::{AddressRegistry, FileBackend, NetworkConfig};
+
+fn networks() -> Vec<NetworkConfig> {
+    serde_yaml::from_str(
+        r#"
+- name: Ethereum
+  chain_id: 1
+  rpc_nodes: ["https://eth.llamarpc.com"]
+  addresses:
+    - alias: treasury
+      address: "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045"
+"#,
+    )
+    .unwrap()
+}
+
+fn temp_data_dir(name: &str) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("oxwatcher-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[tokio::test]
+async fn test_registry_add_remove_and_persist() {
+    let dir = temp_data_dir("registry");
+    let backend = Arc::new(FileBackend::new(&dir));
+    let registry = AddressRegistry::new(&networks(), backend.clone()).await.unwrap();
+
+    let hot_wallet = address!("0x1111111111111111111111111111111111111111");
+    registry.add("Ethereum", "hot wallet", hot_wallet).await.unwrap();
+    assert_eq!(registry.addresses("Ethereum").await.len(), 2);
+
+    // Duplicates, unknown networks and config addresses are rejected
+    assert!(registry.add("Ethereum", "hot wallet", hot_wallet).await.is_err());
+    assert!(registry.add("Polygon", "other", hot_wallet).await.is_err());
+    assert!(registry.remove("treasury").await.is_err());
+
+    // Runtime additions survive a restart
+    let reloaded = AddressRegistry::new(&networks(), backend.clone()).await.unwrap();
+    let addresses = reloaded.addresses("Ethereum").await;
+    assert_eq!(addresses[1].alias, "hot wallet");
+    assert_eq!(addresses[1].address, hot_wallet);
+
+    let removed = reloaded.remove("hot wallet").await.unwrap();
+    assert_eq!(removed.len(), 1);
+    assert_eq!(reloaded.addresses("Ethereum").await.len(), 1);
+    assert!(AddressRegistry::new(&networks(), backend).await.unwrap().dynamic_addresses().await.is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_concurrent_adds_of_one_address_keep_one() {
+    let dir = temp_data_dir("registry-race");
+    let registry = Arc::new(AddressRegistry::new(&networks(), Arc::new(FileBackend::new(&dir))).await.unwrap());
+
+    let hot_wallet = address!("0x1111111111111111111111111111111111111111");
+    let adds: Vec<_> = (0..8)
+        .map(|i| {
+            let registry = Arc::clone(&registry);
+            tokio::spawn(async move { registry.add("Ethereum", &format!("hot wallet {}", i), hot_wallet).await })
+        })
+        .collect();
+    let mut added = 0;
+    for add in adds {
+        if add.await.unwrap().is_ok() {
+            added += 1;
+        }
+    }
+    assert_eq!(added, 1);
+    assert_eq!(registry.dynamic_addresses().await.len(), 1);
+}
+
+#[tokio::test]
+async fn test_aliases_with_markup_or_control_characters_are_rejected() {
+    let dir = temp_data_dir("registry-alias");
+    let registry = AddressRegistry::new(&networks(), Arc::new(FileBackend::new(&dir))).await.unwrap();
+
+    let hot_wallet = address!("0x1111111111111111111111111111111111111111");
+    for alias in ["a<b", "a>b", "R&D", "hot\nwallet"] {
+        assert!(registry.add("Ethereum", alias, hot_wallet).await.is_err(), "{:?} was accepted", alias);
+    }
+    assert!(registry.dynamic_addresses().await.is_empty());
+    registry.add("Ethereum", "hot wallet (ops)", hot_wallet).await.unwrap();
+}
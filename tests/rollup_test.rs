@@ -0,0 +1,141 @@
+use alloy::primitives::{address, B256, U256};
+use alloy::providers::{Provider, ProviderBuilder};
+use alloy::rpc::client::RpcClient;
+use alloy::rpc::json_rpc::{RequestPacket, ResponsePacket};
+use alloy::rpc::types::Block;
+use alloy::transports::{TransportError, TransportFut};
+use serde_json::{json, Value};
+use std::time::Duration;
+use tower::service_fn;
+use Oxwatcher::{Config, RollupConfig, RollupEvent, RollupMonitor};
+
+const NOW: u64 = 1_700_000_000;
+
+fn rollup(yaml: &str) -> RollupConfig {
+    serde_yaml::from_str(yaml).unwrap()
+}
+
+fn eth(amount: u64) -> U256 {
+    U256::from(amount) * U256::from(10).pow(U256::from(18))
+}
+
+/// Mainnet node whose latest block is 500 with `timestamp`, and whose balances are `balance` wei
+fn node(timestamp: u64, balance: U256) -> RpcClient {
+    let transport = service_fn(move |packet: RequestPacket| -> TransportFut<'static> {
+        let RequestPacket::Single(call) = &packet else { panic!("unexpected batch") };
+        let result = match call.method() {
+            "eth_getBlockByNumber" => {
+                let mut block = serde_json::to_value(Block::<Value>::default()).unwrap();
+                block["number"] = json!(U256::from(500));
+                block["hash"] = json!(B256::with_last_byte(1));
+                block["timestamp"] = json!(U256::from(timestamp));
+                block
+            }
+            "eth_chainId" => json!(U256::from(1)),
+            _ => json!(balance),
+        };
+        let body = json!({ "jsonrpc": "2.0", "id": call.id(), "result": result });
+        Box::pin(async move { Ok::<ResponsePacket, TransportError>(serde_json::from_value(body).unwrap()) })
+    });
+    RpcClient::builder().transport(transport, false)
+}
+
+#[tokio::test]
+async fn test_sequencer_stall_is_reported_once_until_blocks_resume() {
+    let monitor = RollupMonitor::new(rollup("max_block_age_secs: 300"));
+
+    assert_eq!(monitor.record_block(100, NOW - 10, NOW).await, None);
+    assert_eq!(
+        monitor.record_block(100, NOW - 10, NOW + 600).await,
+        Some(RollupEvent::SequencerStalled { block: 100, age: Duration::from_secs(610) })
+    );
+    assert_eq!(monitor.record_block(100, NOW - 10, NOW + 1200).await, None);
+    assert_eq!(monitor.record_block(101, NOW + 1198, NOW + 1200).await, Some(RollupEvent::SequencerResumed { block: 101 }));
+}
+
+#[tokio::test]
+async fn test_bridge_balance_crossing_minimum() {
+    let monitor = RollupMonitor::new(rollup(
+        "l1_network: Ethereum\nbridge: \"0x49048044D57e1C92A77f79988d21Fa8fAF74E97e\"\nmin_bridge_balance: 1000",
+    ));
+
+    assert_eq!(monitor.record_bridge_balance(eth(5000)).await, None);
+    assert_eq!(monitor.record_bridge_balance(eth(900)).await, Some(RollupEvent::BridgeLow { balance: 900.0, min: 1000.0 }));
+    assert_eq!(monitor.record_bridge_balance(eth(800)).await, None);
+    assert_eq!(
+        monitor.record_bridge_balance(eth(1000)).await,
+        Some(RollupEvent::BridgeRecovered { balance: 1000.0, min: 1000.0 })
+    );
+    // One wei short of the minimum is low, though it rounds to the minimum as f64
+    assert_eq!(
+        monitor.record_bridge_balance(eth(1000) - U256::from(1)).await,
+        Some(RollupEvent::BridgeLow { balance: 1000.0, min: 1000.0 })
+    );
+
+    // Without a minimum the balance is only read
+    let monitor = RollupMonitor::new(rollup("bridge: \"0x49048044D57e1C92A77f79988d21Fa8fAF74E97e\""));
+    assert_eq!(monitor.record_bridge_balance(U256::ZERO).await, None);
+}
+
+#[tokio::test]
+async fn test_check_reads_l2_head_and_l1_bridge() {
+    let l2 = ProviderBuilder::new().connect_client(node(NOW - 900, U256::ZERO));
+    let l1 = ProviderBuilder::new().connect_client(node(NOW, U256::from(10).pow(U256::from(20))));
+    let monitor = RollupMonitor::new(rollup(
+        "l1_network: Ethereum\nbridge: \"0x49048044D57e1C92A77f79988d21Fa8fAF74E97e\"\nmin_bridge_balance: 1000",
+    ))
+    .with_l1_provider(l1.clone().erased(), 1);
+
+    let events = monitor.check(&l2, NOW).await;
+    assert_eq!(
+        events,
+        vec![
+            RollupEvent::SequencerStalled { block: 500, age: Duration::from_secs(900) },
+            RollupEvent::BridgeLow { balance: 100.0, min: 1000.0 },
+        ]
+    );
+
+    // A bridge read from another chain is skipped
+    let monitor = RollupMonitor::new(monitor.config().clone()).with_l1_provider(l1.erased(), 10);
+    assert_eq!(monitor.check(&l2, NOW).await, vec![RollupEvent::SequencerStalled { block: 500, age: Duration::from_secs(900) }]);
+}
+
+#[test]
+fn test_rollup_config_validation() {
+    let config: Config = serde_yaml::from_str(
+        r#"
+interval_secs: 60
+networks:
+  - name: Base
+    chain_id: 8453
+    rpc_nodes: ["https://mainnet.base.org"]
+    addresses:
+      - alias: Relayer
+        address: "0x0000000000000000000000000000000000000001"
+    rollup:
+      max_block_age_secs: 0
+      l1_network: Mainnet
+      bridge: "0x49048044D57e1C92A77f79988d21Fa8fAF74E97e"
+  - name: Optimism
+    chain_id: 10
+    rpc_nodes: ["https://mainnet.optimism.io"]
+    addresses:
+      - alias: Relayer
+        address: "0x0000000000000000000000000000000000000001"
+    rollup:
+      min_bridge_balance: 100
+"#,
+    )
+    .unwrap();
+
+    let problems = config.problems();
+    assert_eq!(
+        problems,
+        vec![
+            "rollup max_block_age_secs must be greater than 0 for network 'Base'".to_string(),
+            "rollup l1_network of network 'Base' must be another configured network, got 'Mainnet'".to_string(),
+            "rollup min_bridge_balance of network 'Optimism' requires bridge".to_string(),
+        ]
+    );
+    assert_eq!(address!("49048044D57e1C92A77f79988d21Fa8fAF74E97e"), config.networks[0].rollup.as_ref().unwrap().bridge.unwrap());
+}
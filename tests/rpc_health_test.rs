@@ -0,0 +1,223 @@
+use alloy::rpc::json_rpc::{RequestPacket, ResponsePacket};
+use alloy::transports::{TransportError, TransportErrorKind, TransportFut};
+use alloy::providers::Provider;
+use reqwest::Url;
+use std::collections::HashMap;
+use std::num::NonZeroUsize;
+use std::time::Duration;
+use tower::{service_fn, Service};
+use Oxwatcher::{
+    create_fallback_provider, BalanceReport, BalanceStorage, FallbackConfig, RateLimiter, RpcHealth, RpcNodeConfig,
+    TrackedTransport,
+};
+
+fn urls() -> Vec<Url> {
+    vec![
+        "https://eth-mainnet.g.alchemy.com/v2/secret-key".parse().unwrap(),
+        "https://rpc.ankr.com/eth".parse().unwrap(),
+        "https://cloudflare-eth.com".parse().unwrap(),
+    ]
+}
+
+#[test]
+fn test_rpc_health_hides_url_paths_and_counts_requests() {
+    let rpc = RpcHealth::new();
+    let nodes = rpc.register("Ethereum", &urls());
+
+    nodes[0].record_success(100, 1_000);
+    nodes[0].record_success(200, 1_010);
+    nodes[1].record_error("HTTP error 429", 50, 1_020);
+
+    let status = rpc.network("Ethereum").unwrap();
+    assert_eq!(status.nodes[0].node, "eth-mainnet.g.alchemy.com");
+    assert_eq!(status.nodes[0].successes, 2);
+    assert_eq!(status.nodes[0].last_latency_ms, Some(200));
+    assert_eq!(status.nodes[0].avg_latency_ms, Some(120.0));
+    assert_eq!(status.nodes[1].consecutive_errors, 1);
+    assert_eq!(status.nodes[1].last_error.as_deref(), Some("HTTP error 429"));
+    assert!(!status.nodes[2].is_used());
+    assert!(!status.all_failing());
+    assert!(rpc.network("Base").is_none());
+}
+
+#[test]
+fn test_rpc_health_redacts_keys_from_errors() {
+    let rpc = RpcHealth::new();
+    let urls: Vec<Url> = vec![
+        "https://eth-mainnet.g.alchemy.com/v2/secret-key".parse().unwrap(),
+        "https://api.etherscan.io/?apikey=secret-key".parse().unwrap(),
+    ];
+    let nodes = rpc.register("Ethereum", &urls);
+
+    nodes[0].record_error(
+        "error sending request for url (https://eth-mainnet.g.alchemy.com/v2/secret-key): connection refused",
+        5,
+        1_000,
+    );
+    nodes[1].record_error("HTTP error 401 for /?apikey=secret-key", 5, 1_000);
+
+    let status = rpc.network("Ethereum").unwrap();
+    let first = status.nodes[0].last_error.as_deref().unwrap();
+    assert_eq!(first, "error sending request for url (eth-mainnet.g.alchemy.com): connection refused");
+    let second = status.nodes[1].last_error.as_deref().unwrap();
+    assert!(!second.contains("secret-key"), "{}", second);
+}
+
+#[test]
+fn test_nodes_on_one_host_get_distinct_ids() {
+    let rpc = RpcHealth::new();
+    let urls: Vec<Url> = vec![
+        "https://eth-mainnet.g.alchemy.com/v2/first-key".parse().unwrap(),
+        "https://eth-mainnet.g.alchemy.com/v2/second-key".parse().unwrap(),
+        "https://cloudflare-eth.com".parse().unwrap(),
+    ];
+    rpc.register("Ethereum", &urls);
+
+    let status = rpc.network("Ethereum").unwrap();
+    assert_eq!(status.nodes[0].node, status.nodes[1].node);
+    assert_ne!(status.nodes[0].id, status.nodes[1].id);
+    assert!(status.nodes[0].id.starts_with("eth-mainnet.g.alchemy.com#"));
+    assert!(!status.nodes[0].id.contains("key"));
+    assert_eq!(status.nodes[2].id, "cloudflare-eth.com");
+}
+
+#[test]
+fn test_registering_the_same_nodes_again_shares_their_trackers() {
+    let rpc = RpcHealth::new();
+    let nodes = rpc.register("Ethereum", &urls());
+    nodes[0].set_excluded(Some("chain ID 10 instead of 1".to_string()));
+
+    let shared = rpc.register("Ethereum", &urls());
+    assert!(shared[0].excluded().is_some());
+    shared[1].record_error("timeout", 30_000, 1_000);
+    assert_eq!(rpc.network("Ethereum").unwrap().nodes[1].consecutive_errors, 1);
+
+    let replaced = rpc.register("Ethereum", &urls()[1..]);
+    assert!(replaced[0].excluded().is_none());
+    assert_eq!(rpc.network("Ethereum").unwrap().nodes[0].consecutive_errors, 0);
+}
+
+#[test]
+fn test_all_failing_ignores_unused_nodes() {
+    let rpc = RpcHealth::new();
+    let nodes = rpc.register("Ethereum", &urls());
+    assert!(!rpc.network("Ethereum").unwrap().all_failing());
+
+    nodes[0].record_error("connection refused", 5, 1_000);
+    nodes[1].record_error("timeout", 30_000, 1_000);
+    assert!(rpc.network("Ethereum").unwrap().all_failing());
+
+    nodes[1].record_success(80, 1_060);
+    assert!(!rpc.network("Ethereum").unwrap().all_failing());
+}
+
+#[test]
+fn test_failovers_count_runs_of_errors_and_feed_the_report() {
+    let rpc = RpcHealth::new();
+    let nodes = rpc.register("Ethereum", &urls());
+
+    nodes[1].record_error("HTTP error 429", 50, 1_000);
+    nodes[1].record_error("HTTP error 429", 50, 1_010);
+    let status = rpc.network("Ethereum").unwrap();
+    assert_eq!(status.nodes[1].failovers, 1);
+    assert_eq!(status.nodes[1].down_since, Some(1_000));
+
+    nodes[1].record_success(80, 1_020);
+    nodes[1].record_error("timeout", 30_000, 1_030);
+    let status = rpc.network("Ethereum").unwrap();
+    assert_eq!(status.nodes[1].failovers, 2);
+    assert_eq!(status.nodes[1].down_since, Some(1_030));
+
+    // The report counts failovers since the previous one
+    let reported = HashMap::from([(("Ethereum".to_string(), "rpc.ankr.com".to_string()), 1)]);
+    let report = BalanceReport::build(&[], &BalanceStorage::new(), None).with_failovers(&rpc.all(), &reported);
+    assert_eq!(report.failovers.len(), 1);
+    assert_eq!(report.failovers[0].network_name, "Ethereum");
+    assert_eq!(report.failovers[0].describe(), "rpc.ankr.com failed 1 time");
+    let report = BalanceReport::build(&[], &BalanceStorage::new(), None).with_failovers(&rpc.all(), &HashMap::new());
+    assert_eq!(report.failovers[0].describe(), "rpc.ankr.com failed 2 times");
+}
+
+#[tokio::test]
+async fn test_tracked_transport_records_errors() {
+    let rpc = RpcHealth::new();
+    let nodes = rpc.register("Ethereum", &urls()[..1]);
+
+    let failing = service_fn(|_req: RequestPacket| -> TransportFut<'static> {
+        Box::pin(async { Err::<ResponsePacket, TransportError>(TransportErrorKind::custom_str("node down")) })
+    });
+    let mut transport = TrackedTransport::new(failing, Some(nodes[0].clone()));
+
+    assert!(transport.call(RequestPacket::Batch(vec![])).await.is_err());
+
+    let status = rpc.network("Ethereum").unwrap();
+    assert_eq!(status.nodes[0].errors, 1);
+    assert!(status.nodes[0].last_error.as_deref().unwrap().contains("node down"));
+    assert!(status.all_failing());
+}
+
+#[tokio::test]
+async fn test_rate_limiter_allows_one_second_burst_then_spaces_requests() {
+    let limiter = RateLimiter::new(4.0);
+
+    // 4 rps: the first four requests go out immediately
+    for _ in 0..4 {
+        assert!(limiter.reserve().is_zero());
+    }
+    let fifth = limiter.reserve();
+    let sixth = limiter.reserve();
+    assert!(fifth > Duration::from_millis(200) && fifth <= Duration::from_millis(250));
+    assert!(sixth > Duration::from_millis(450) && sixth <= Duration::from_millis(500));
+}
+
+#[tokio::test]
+async fn test_excluded_node_fails_without_sending_request() {
+    let rpc = RpcHealth::new();
+    let nodes = rpc.register("Ethereum", &urls()[..2]);
+    nodes[0].set_excluded(Some("chain ID 11155111 instead of 1".to_string()));
+
+    let unreachable = service_fn(|_req: RequestPacket| -> TransportFut<'static> {
+        panic!("excluded node must not receive requests")
+    });
+    let mut transport = TrackedTransport::new(unreachable, Some(nodes[0].clone()));
+
+    let error = transport.call(RequestPacket::Batch(vec![])).await.unwrap_err();
+    assert!(error.to_string().contains("chain ID 11155111 instead of 1"));
+
+    let status = rpc.network("Ethereum").unwrap();
+    assert_eq!(status.nodes[0].errors, 0);
+    assert!(status.all_failing());
+
+    nodes[1].record_success(80, 1_000);
+    assert!(!rpc.network("Ethereum").unwrap().all_failing());
+
+    nodes[0].set_excluded(None);
+    assert!(nodes[0].excluded().is_none());
+}
+
+#[tokio::test]
+async fn test_request_timeout_counts_against_node() {
+    // Accepts connections but never answers
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url: Url = format!("http://{}", listener.local_addr().unwrap()).parse().unwrap();
+    tokio::spawn(async move {
+        let mut connections = Vec::new();
+        while let Ok((socket, _)) = listener.accept().await {
+            connections.push(socket);
+        }
+    });
+
+    let rpc = RpcHealth::new();
+    let nodes = rpc.register("Ethereum", std::slice::from_ref(&url));
+    let config = FallbackConfig::new(vec![RpcNodeConfig::from(url)], NonZeroUsize::MIN)
+        .with_tracking(nodes)
+        .with_timeout(Duration::from_millis(200));
+    let provider = create_fallback_provider(config).unwrap();
+
+    let result = tokio::time::timeout(Duration::from_secs(5), provider.get_block_number()).await;
+    assert!(result.expect("request should time out on its own").is_err());
+
+    let status = rpc.network("Ethereum").unwrap();
+    assert_eq!(status.nodes[0].errors, 1);
+    assert!(status.all_failing());
+}
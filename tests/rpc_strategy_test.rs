@@ -0,0 +1,111 @@
+use alloy::providers::Provider;
+use alloy::transports::http::reqwest::Url;
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::routing::post;
+use axum::{Json, Router};
+use serde_json::{json, Value};
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use Oxwatcher::{create_fallback_provider, Config, FallbackConfig, RpcHealth, RpcNodeConfig, RpcStrategy};
+
+/// JSON-RPC node answering eth_blockNumber with `block`, or HTTP 500 while `failing` is set
+async fn node(block: u64) -> (Url, Arc<AtomicBool>) {
+    let failing = Arc::new(AtomicBool::new(false));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap()).parse().unwrap();
+    let handler = move |State(failing): State<Arc<AtomicBool>>, Json(request): Json<Value>| async move {
+        if failing.load(Ordering::SeqCst) {
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+        Ok(Json(json!({ "jsonrpc": "2.0", "id": request["id"], "result": format!("0x{:x}", block) })))
+    };
+    let app = Router::new().route("/", post(handler)).with_state(failing.clone());
+    tokio::spawn(async move { axum::serve(listener, app).await });
+    (url, failing)
+}
+
+#[tokio::test]
+async fn test_priority_prefers_first_node_until_it_fails() {
+    let (primary, primary_failing) = node(1).await;
+    let (public, _) = node(2).await;
+    let rpc = RpcHealth::new();
+    let nodes = rpc.register("Ethereum", &[primary.clone(), public.clone()]);
+    let config = FallbackConfig::new(vec![primary.into(), public.into()], NonZeroUsize::MIN)
+        .with_tracking(nodes)
+        .with_strategy(RpcStrategy::Priority);
+    let provider = create_fallback_provider(config).unwrap();
+
+    for _ in 0..3 {
+        assert_eq!(provider.get_block_number().await.unwrap(), 1);
+    }
+
+    primary_failing.store(true, Ordering::SeqCst);
+    assert_eq!(provider.get_block_number().await.unwrap(), 2);
+    // The failing node moves to the back instead of being tried first every time
+    assert_eq!(provider.get_block_number().await.unwrap(), 2);
+
+    let status = rpc.network("Ethereum").unwrap();
+    assert_eq!((status.nodes[0].successes, status.nodes[0].errors), (3, 1));
+    assert_eq!(status.nodes[1].successes, 2);
+}
+
+#[tokio::test]
+async fn test_priority_overflows_to_next_node_at_rate_limit() {
+    let (primary, _) = node(1).await;
+    let (public, _) = node(2).await;
+    let mut paid = RpcNodeConfig::from(primary);
+    paid.rate_limit = Some(1.0);
+    let config = FallbackConfig::new(vec![paid, public.into()], NonZeroUsize::MIN).with_strategy(RpcStrategy::Priority);
+    let provider = create_fallback_provider(config).unwrap();
+
+    assert_eq!(provider.get_block_number().await.unwrap(), 1);
+    assert_eq!(provider.get_block_number().await.unwrap(), 2);
+}
+
+#[tokio::test]
+async fn test_round_robin_spreads_requests() {
+    let (first, _) = node(1).await;
+    let (second, _) = node(1).await;
+    let rpc = RpcHealth::new();
+    let nodes = rpc.register("Ethereum", &[first.clone(), second.clone()]);
+    let config = FallbackConfig::new(vec![first.into(), second.into()], NonZeroUsize::MIN)
+        .with_tracking(nodes)
+        .with_strategy(RpcStrategy::RoundRobin);
+    let provider = create_fallback_provider(config).unwrap();
+
+    for _ in 0..4 {
+        provider.get_block_number().await.unwrap();
+    }
+    let status = rpc.network("Ethereum").unwrap();
+    assert_eq!(status.nodes[0].successes, 2);
+    assert_eq!(status.nodes[1].successes, 2);
+}
+
+#[test]
+fn test_network_strategy_overrides_global() {
+    let config: Config = serde_yaml::from_str(
+        r#"
+interval_secs: 60
+rpc_strategy: priority
+networks:
+  - name: Ethereum
+    chain_id: 1
+    rpc_nodes: ["https://eth.llamarpc.com"]
+    addresses: []
+  - name: Polygon
+    chain_id: 137
+    rpc_strategy: round_robin
+    rpc_nodes: ["https://polygon-rpc.com"]
+    addresses: []
+"#,
+    )
+    .unwrap();
+
+    assert_eq!(config.rpc_strategy_for(&config.networks[0]), RpcStrategy::Priority);
+    assert_eq!(config.rpc_strategy_for(&config.networks[1]), RpcStrategy::RoundRobin);
+
+    let config: Config = serde_yaml::from_str("interval_secs: 60\nnetworks: []").unwrap();
+    assert_eq!(config.rpc_strategy, RpcStrategy::Latency);
+}
@@ -0,0 +1,112 @@
+use std::collections::HashMap;
+use std::time::Duration;
+use Oxwatcher::{
+    AlertSeverity, BalanceInfo, BalanceReport, BalanceStorage, Condition, HistoryStore, Observation, RuleConfig,
+    RuleEngine, Runway, RunwayForecasts, ThresholdTier,
+};
+
+mod common;
+
+const DAY: u64 = 24 * 3600;
+const NOW: u64 = 1_700_000_000;
+
+fn balance(alias: &str, eth: f64) -> BalanceInfo {
+    common::balance_info(alias, &eth.to_string())
+}
+
+/// History of a relayer spending 1 ETH a day for the last two days, with a top-up in between
+fn history(name: &str) -> HistoryStore {
+    let path = std::env::temp_dir().join(format!("oxwatcher-runway-{}-{}.jsonl", name, std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let mut history = HistoryStore::load_from_file(&path, Duration::from_secs(30 * DAY), Duration::from_secs(3600)).unwrap();
+    for (timestamp, eth) in [(NOW - 2 * DAY, 10.0), (NOW - DAY, 9.0), (NOW - DAY + 60, 14.0)] {
+        history.record(&balance("Relayer", eth), timestamp).unwrap();
+    }
+    history
+}
+
+#[test]
+fn test_runway_runs_to_the_next_threshold_below_the_balance() {
+    let history = history("threshold");
+    let forecasts = RunwayForecasts::new(Duration::from_secs(7 * DAY));
+    let tiers = vec![ThresholdTier::below(5.0), ThresholdTier::below(20.0)];
+
+    // 2 ETH spent over the 2 days of history: 1 ETH a day, 8 ETH above the 5 ETH threshold
+    let runways = forecasts.forecast(&history, &balance("Relayer", 13.0), &tiers, &HashMap::new(), NOW);
+    let runway = runways["ETH"];
+    assert!((runway.daily_spend - 1.0).abs() < 1e-9);
+    assert_eq!(runway.floor, 5.0);
+    assert_eq!(runway.remaining, Duration::from_secs(8 * DAY));
+    assert_eq!(runway.describe(), "~8.0 days");
+
+    // Below every threshold it runs to empty
+    let runways = forecasts.forecast(&history, &balance("Relayer", 13.0), &[ThresholdTier::below(15.0)], &HashMap::new(), NOW);
+    assert_eq!(runways["ETH"].floor, 0.0);
+    assert_eq!(runways["ETH"].remaining, Duration::from_secs(13 * DAY));
+
+    let latest = forecasts.all();
+    assert_eq!(latest.len(), 1);
+    assert_eq!(latest[0].describe(), "Relayer ETH: ~13.0 days at 1.0000/day");
+
+    let runway = Runway { daily_spend: 2.0, floor: 0.0, remaining: Duration::from_secs(12 * 3600) };
+    assert_eq!(runway.describe(), "~12 hours");
+}
+
+#[test]
+fn test_no_runway_without_spending_or_history() {
+    let history = history("unknown");
+    let forecasts = RunwayForecasts::new(Duration::from_secs(7 * DAY));
+
+    // Unknown address: no history to estimate from
+    assert!(forecasts.forecast(&history, &balance("Treasury", 10.0), &[], &HashMap::new(), NOW).is_empty());
+
+    // Only the top-up falls within a 12 hour lookback
+    let forecasts = RunwayForecasts::new(Duration::from_secs(DAY / 2));
+    assert!(forecasts.forecast(&history, &balance("Relayer", 14.0), &[], &HashMap::new(), NOW).is_empty());
+    assert!(forecasts.all().is_empty());
+
+    // A runway longer than a Duration holds, or of a non-finite balance, isn't forecast
+    let forecasts = RunwayForecasts::new(Duration::from_secs(7 * DAY));
+    for eth in [1e300, f64::INFINITY] {
+        assert!(forecasts.forecast(&history, &balance("Relayer", eth), &[], &HashMap::new(), NOW).is_empty());
+    }
+    assert!(forecasts.all().is_empty());
+}
+
+#[test]
+fn test_runway_rule_condition() {
+    let condition = Condition::parse("runway < 3d").unwrap();
+    assert_eq!(condition.to_string(), "runway < 3d");
+    let observation = |runway_secs| Observation { balance: 5.0, runway_secs, ..Default::default() };
+    assert!(condition.matches(&observation(Some(2 * DAY))));
+    assert!(!condition.matches(&observation(Some(4 * DAY))));
+    // Not spending never runs out
+    assert!(!condition.matches(&observation(None)));
+    assert!(Condition::parse("runway < 3").is_err());
+
+    let rule: RuleConfig = serde_yaml::from_str("name: Short runway\ncondition: runway < 3d").unwrap();
+    let rules = RuleEngine::new(&[rule]).unwrap();
+    let runway = Runway { daily_spend: 1.0, floor: 0.0, remaining: Duration::from_secs(2 * DAY) };
+    let runways = HashMap::from([("ETH".to_string(), runway)]);
+    let alerts = rules.evaluate_with_runways(&balance("Relayer", 2.0), None, &runways, NOW);
+    assert_eq!(alerts.len(), 1);
+    assert_eq!(alerts[0].severity, AlertSeverity::Warning);
+    assert!(alerts[0].alert.lines.contains(&"Runway: ~2.0 days at 1.0000 ETH/day".to_string()));
+    assert!(rules.evaluate(&balance("Relayer", 2.0), None, NOW + 60).is_empty());
+}
+
+#[test]
+fn test_report_lists_runways_under_30_days() {
+    let history = history("report");
+    let forecasts = RunwayForecasts::new(Duration::from_secs(7 * DAY));
+    forecasts.forecast(&history, &balance("Relayer", 13.0), &[], &HashMap::new(), NOW);
+    let balances = vec![balance("Relayer", 13.0)];
+
+    let report = BalanceReport::build(&balances, &BalanceStorage::new(), None).with_runways(&balances, &forecasts);
+    assert_eq!(report.runways.len(), 1);
+    assert_eq!(report.runways[0].runway.remaining, Duration::from_secs(13 * DAY));
+
+    forecasts.forecast(&history, &balance("Relayer", 40.0), &[], &HashMap::new(), NOW);
+    let report = BalanceReport::build(&balances, &BalanceStorage::new(), None).with_runways(&balances, &forecasts);
+    assert!(report.runways.is_empty());
+}
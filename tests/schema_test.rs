@@ -0,0 +1,80 @@
+use serde_json::json;
+use std::path::PathBuf;
+use Oxwatcher::{
+    migrate_state, schema_version, AlertStateStorage, BalanceStorage, ChatStorage, FileBackend, StateBackend,
+    STATE_SCHEMA_VERSION,
+};
+
+fn data_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("oxwatcher-schema-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn read_json(path: PathBuf) -> serde_json::Value {
+    serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap()
+}
+
+#[test]
+fn test_unversioned_state_is_migrated() {
+    let mut state = json!({ "states": {} });
+    assert_eq!(schema_version(&state), 1);
+    assert_eq!(migrate_state(&mut state).unwrap(), 1);
+    assert_eq!(schema_version(&state), STATE_SCHEMA_VERSION);
+
+    // Already current: nothing to do
+    assert_eq!(migrate_state(&mut state).unwrap(), STATE_SCHEMA_VERSION);
+}
+
+#[test]
+fn test_newer_schema_is_rejected() {
+    let dir = data_dir("newer");
+    let path = dir.join("alert_states.json");
+    std::fs::write(&path, json!({ "schema_version": STATE_SCHEMA_VERSION + 1, "states": {} }).to_string()).unwrap();
+
+    let error = AlertStateStorage::load_from_file(&path).unwrap_err();
+    assert!(error.to_string().contains("newer than the supported version"));
+}
+
+#[test]
+fn test_state_files_load_old_format_and_save_the_version() {
+    let dir = data_dir("load");
+    let balances = dir.join("balances.json");
+    let chats = dir.join("telegram_chats.json");
+    std::fs::write(&balances, r#"{"balances":{}}"#).unwrap();
+    std::fs::write(&chats, r#"{"registrations":[{"chat_id":42,"user_id":7,"username":"alice"}]}"#).unwrap();
+
+    let storage = BalanceStorage::load_from_file(&balances).unwrap();
+    storage.save_to_file(&balances).unwrap();
+    assert_eq!(schema_version(&read_json(balances)), STATE_SCHEMA_VERSION);
+
+    let storage = ChatStorage::load_from_file(&chats, None).unwrap();
+    assert_eq!(storage.registrations[0].chat_id, 42);
+    storage.save_to_file(&chats, None).unwrap();
+    assert_eq!(schema_version(&read_json(chats)), STATE_SCHEMA_VERSION);
+}
+
+#[tokio::test]
+async fn test_file_backend_migrates_in_place() {
+    let dir = data_dir("backend");
+    std::fs::write(dir.join("alert_states.json"), r#"{"states":{}}"#).unwrap();
+    std::fs::write(dir.join("telegram_chats_ops.json"), r#"{"registrations":[]}"#).unwrap();
+    std::fs::write(dir.join("dynamic_addresses.json"), "{}").unwrap();
+
+    let backend = FileBackend::new(&dir);
+    let mut upgraded = backend.migrate_state().await.unwrap();
+    upgraded.sort();
+    assert_eq!(
+        upgraded,
+        vec![
+            format!("alert_states.json: schema version 1 → {}", STATE_SCHEMA_VERSION),
+            format!("telegram_chats_ops.json: schema version 1 → {}", STATE_SCHEMA_VERSION),
+        ]
+    );
+    assert_eq!(schema_version(&read_json(dir.join("telegram_chats_ops.json"))), STATE_SCHEMA_VERSION);
+    assert_eq!(std::fs::read_to_string(dir.join("dynamic_addresses.json")).unwrap(), "{}");
+
+    // A second run has nothing left to upgrade
+    assert!(backend.migrate_state().await.unwrap().is_empty());
+}
@@ -0,0 +1,40 @@
+use std::sync::Arc;
+use Oxwatcher::storage::AlertState;
+use Oxwatcher::{create_state_backend, AlertStateStorage, StateBackend, StorageConfig};
+
+#[tokio::test]
+async fn test_concurrent_saves_leave_valid_state() {
+    let data_dir = std::env::temp_dir().join(format!("oxwatcher-state-store-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&data_dir);
+
+    // The file backend creates data_dir itself
+    let backend: Arc<dyn StateBackend> =
+        create_state_backend(&StorageConfig::File, &data_dir.to_string_lossy(), None).await.unwrap();
+    assert!(data_dir.is_dir());
+
+    let tasks: Vec<_> = (0..32)
+        .map(|i| {
+            let backend = Arc::clone(&backend);
+            tokio::spawn(async move {
+                let mut states = AlertStateStorage::new();
+                for j in 0..=i {
+                    states.states.insert(format!("Ethereum:wallet-{}", j), AlertState::new());
+                }
+                backend.save_alert_states(&states).await.unwrap();
+                backend.load_alert_states().await.unwrap();
+            })
+        })
+        .collect();
+    for task in tasks {
+        task.await.unwrap();
+    }
+
+    // The file holds one complete save and no temporary file is left behind
+    let states = backend.load_alert_states().await.unwrap();
+    assert!((1..=32).contains(&states.states.len()));
+    let names: Vec<_> = std::fs::read_dir(&data_dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .collect();
+    assert_eq!(names, vec!["alert_states.json"]);
+}
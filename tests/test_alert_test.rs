@@ -0,0 +1,28 @@
+use alloy::primitives::Address;
+use Oxwatcher::{test_change_alert, test_report, BalanceChange, TEST_WALLET_ALIAS};
+
+#[test]
+fn test_synthetic_change_alert() {
+    let changes = test_change_alert("Ethereum", 1);
+    assert!(changes.has_changes());
+    assert_eq!(changes.network_name, "Ethereum");
+    assert_eq!(changes.alias, TEST_WALLET_ALIAS);
+    assert_eq!(changes.address, format!("{:?}", Address::ZERO));
+
+    let eth = changes.eth_change.unwrap();
+    assert!(matches!(eth.change, BalanceChange::Decrease));
+    assert_eq!((eth.old_formatted.as_str(), eth.new_formatted.as_str()), ("10", "8.5"));
+    assert_eq!(changes.token_changes.len(), 1);
+    assert_eq!(changes.token_changes[0].alias, "USDC");
+    assert!(matches!(changes.token_changes[0].change, BalanceChange::Increase));
+}
+
+#[test]
+fn test_sample_report_contains_the_change() {
+    let report = test_report("Base", 8453);
+    assert!(report.has_data());
+    assert_eq!(report.address_count, 1);
+    assert_eq!(report.changes.len(), 1);
+    assert_eq!(report.changes[0].network_name, "Base");
+    assert_eq!(report.changes[0].alias, TEST_WALLET_ALIAS);
+}
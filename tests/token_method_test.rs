@@ -0,0 +1,108 @@
+use alloy::primitives::{keccak256, Address, B256, U256};
+use alloy::providers::ProviderBuilder;
+use alloy::rpc::client::RpcClient;
+use alloy::rpc::json_rpc::{RequestPacket, ResponsePacket, SerializedRequest};
+use alloy::rpc::types::Block;
+use alloy::transports::{TransportError, TransportFut};
+use serde_json::{json, Value};
+use std::time::Duration;
+use tower::service_fn;
+use Oxwatcher::{AddressConfig, BalanceMonitor, BalanceMonitorConfig, Config, TokenConfig};
+
+/// Node answering balanceOf with 7 and balanceOfUnderlying with `underlying`
+fn node(underlying: U256) -> RpcClient {
+    let answer = move |call: &SerializedRequest| {
+        let result = match call.method() {
+            "eth_getBlockByNumber" => {
+                let mut block = serde_json::to_value(Block::<Value>::default()).unwrap();
+                block["number"] = json!(U256::from(100));
+                block["hash"] = json!(B256::with_last_byte(100));
+                block
+            }
+            "eth_call" => {
+                let params: Value = serde_json::from_str(call.params().unwrap().get()).unwrap();
+                let input = params[0]["input"].as_str().or(params[0]["data"].as_str()).unwrap().to_string();
+                let method = format!("0x{}", alloy::hex::encode(&keccak256("balanceOfUnderlying(address)")[..4]));
+                let balance = if input.starts_with(&method) { underlying } else { U256::from(7) };
+                json!(B256::from(balance))
+            }
+            _ => json!(U256::ZERO),
+        };
+        json!({ "jsonrpc": "2.0", "id": call.id(), "result": result })
+    };
+    let transport = service_fn(move |packet: RequestPacket| -> TransportFut<'static> {
+        let body = match &packet {
+            RequestPacket::Single(call) => answer(call),
+            RequestPacket::Batch(calls) => Value::Array(calls.iter().map(answer).collect()),
+        };
+        Box::pin(async move { Ok::<ResponsePacket, TransportError>(serde_json::from_value(body).unwrap()) })
+    });
+    RpcClient::builder().transport(transport, false)
+}
+
+fn token(yaml: &str) -> TokenConfig {
+    serde_yaml::from_str(yaml).unwrap()
+}
+
+#[tokio::test]
+async fn test_token_balance_is_read_through_its_balance_method() {
+    let tokens = vec![
+        token("alias: USDC\naddress: \"0x0000000000000000000000000000000000000001\"\ndecimals: 6"),
+        token(
+            "alias: cUSDC\naddress: \"0x0000000000000000000000000000000000000002\"\ndecimals: 6\n\
+             balance_method: balanceOfUnderlying(address)\nbalance_scale: 0.5",
+        ),
+    ];
+    let provider = ProviderBuilder::new().connect_client(node(U256::from(3000)));
+    let monitor = BalanceMonitor::new(provider, BalanceMonitorConfig::new(Vec::new(), tokens, Duration::from_secs(60)));
+
+    let addresses = [AddressConfig::new("treasury", Address::with_last_byte(9))];
+    let balances = monitor.check_addresses(&addresses, "Ethereum".to_string(), 1).await;
+    let balance = balances.into_iter().next().unwrap().unwrap();
+    let tokens: Vec<(&str, U256)> = balance.token_balances.iter().map(|t| (t.alias.as_str(), t.balance)).collect();
+    assert_eq!(tokens, [("USDC", U256::from(7)), ("cUSDC", U256::from(1500))]);
+}
+
+#[tokio::test]
+async fn test_balance_overflowing_its_scale_is_a_failed_read() {
+    let tokens = vec![token(
+        "alias: cUSDC\naddress: \"0x0000000000000000000000000000000000000002\"\ndecimals: 6\n\
+         balance_method: balanceOfUnderlying(address)\nbalance_scale: 2",
+    )];
+    let provider = ProviderBuilder::new().connect_client(node(U256::MAX));
+    let monitor = BalanceMonitor::new(provider, BalanceMonitorConfig::new(Vec::new(), tokens, Duration::from_secs(60)));
+
+    let addresses = [AddressConfig::new("treasury", Address::with_last_byte(9))];
+    let balances = monitor.check_addresses(&addresses, "Ethereum".to_string(), 1).await;
+    let balance = balances.into_iter().next().unwrap().unwrap();
+    assert!(balance.token_balances.is_empty());
+}
+
+#[test]
+fn test_balance_method_is_validated() {
+    let config: Config = serde_yaml::from_str(
+        r#"
+interval_secs: 60
+networks:
+  - name: Ethereum
+    chain_id: 1
+    rpc_nodes: ["https://eth.llamarpc.com"]
+    addresses:
+      - alias: treasury
+        address: "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045"
+    tokens:
+      - alias: cUSDC
+        address: "0x39AA39c021dfbaE8faC545936693aC917d5E7563"
+        balance_method: "balanceOfUnderlying(uint256)"
+        balance_scale: 0
+"#,
+    )
+    .unwrap();
+    assert_eq!(
+        config.problems(),
+        [
+            "token 'cUSDC' on network 'Ethereum': balance_method 'balanceOfUnderlying(uint256)' must take the address as its only argument",
+            "balance_scale of token 'cUSDC' on network 'Ethereum' must be positive",
+        ]
+    );
+}
@@ -0,0 +1,125 @@
+use alloy::primitives::{address, hex, Address, U256};
+use Oxwatcher::{BalanceInfo, Config, NetworkConfig, TokenBalance, TopUpTargets};
+
+mod common;
+
+const RELAYER: Address = address!("d8dA6BF26964aF9D7eEd9e03E53415D37aA96045");
+const USDC: Address = address!("A0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48");
+
+fn network(chain_id: u64, safe: &str) -> NetworkConfig {
+    serde_yaml::from_str(&format!(
+        r#"
+name: Ethereum
+chain_id: {chain_id}
+rpc_nodes: ["https://eth.llamarpc.com"]
+{safe}
+addresses:
+  - alias: Relayer
+    address: "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045"
+    top_up_to: 10
+  - alias: Treasury
+    address: "0x0000000000000000000000000000000000000001"
+tokens:
+  - alias: USDC
+    address: "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48"
+    top_up_to: 5000
+"#
+    ))
+    .unwrap()
+}
+
+fn balance(alias: &str, wei: U256, usdc: u64) -> BalanceInfo {
+    BalanceInfo {
+        eth_balance: wei,
+        token_balances: vec![TokenBalance { balance: U256::from(usdc), ..common::token_balance("USDC", "0", 6) }],
+        ..common::balance_info(alias, "0")
+    }
+}
+
+#[test]
+fn test_native_top_up_to_target() {
+    let targets = TopUpTargets::new(&network(1, ""));
+    // 7.5 ETH held, 2.5 ETH short of the target
+    let held = U256::from(75) * U256::from(10).pow(U256::from(17));
+    let top_up = targets.suggest(&balance("Relayer", held, 0), "ETH", true).unwrap();
+
+    assert_eq!(top_up.describe(), "Top up 2.5 ETH");
+    assert_eq!(top_up.to, RELAYER);
+    assert_eq!(top_up.value, U256::from(25) * U256::from(10).pow(U256::from(17)));
+    assert!(top_up.data.is_empty());
+    assert_eq!(top_up.payment_link, format!("ethereum:{}@1?value=2500000000000000000", RELAYER));
+    assert_eq!(top_up.safe_url, None);
+
+    // At the target, or without one, nothing is suggested
+    let target = U256::from(10).pow(U256::from(19));
+    assert_eq!(targets.suggest(&balance("Relayer", target, 0), "ETH", true), None);
+    assert_eq!(targets.suggest(&balance("Treasury", U256::ZERO, 0), "ETH", true), None);
+}
+
+#[test]
+fn test_token_top_up_calls_transfer() {
+    let targets = TopUpTargets::new(&network(1, ""));
+    let top_up = targets.suggest(&balance("Relayer", U256::ZERO, 1_000_000_000), "USDC", false).unwrap();
+
+    assert_eq!(top_up.describe(), "Top up 4000 USDC");
+    assert_eq!(top_up.to, USDC);
+    assert_eq!(top_up.value, U256::ZERO);
+    // transfer(RELAYER, 4000e6)
+    assert_eq!(
+        top_up.data.as_ref(),
+        hex!(
+            "a9059cbb000000000000000000000000d8da6bf26964af9d7eed9e03e53415d37aa96045"
+            "00000000000000000000000000000000000000000000000000000000ee6b2800"
+        )
+    );
+    assert_eq!(top_up.payment_link, format!("ethereum:{}@1/transfer?address={}&uint256=4000000000", USDC, RELAYER));
+    assert_eq!(targets.suggest(&balance("Relayer", U256::ZERO, 5_000_000_000), "USDC", false), None);
+}
+
+#[test]
+fn test_safe_link_on_supported_chains() {
+    let safe = "top_up_safe: \"0x0000000000000000000000000000000000000002\"";
+    let targets = TopUpTargets::new(&network(8453, safe));
+    let top_up = targets.suggest(&balance("Relayer", U256::ZERO, 0), "ETH", true).unwrap();
+    let url = top_up.safe_url.unwrap();
+    assert_eq!(url.host_str(), Some("app.safe.global"));
+    let params: Vec<(String, String)> = url.query_pairs().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+    assert_eq!(params, vec![
+        ("safe".to_string(), "base:0x0000000000000000000000000000000000000002".to_string()),
+        ("appUrl".to_string(), "https://apps-portal.safe.global/tx-builder".to_string()),
+    ]);
+
+    // Chains without a known Safe prefix only get the payment link
+    let targets = TopUpTargets::new(&network(999_999, safe));
+    assert_eq!(targets.suggest(&balance("Relayer", U256::ZERO, 0), "ETH", true).unwrap().safe_url, None);
+}
+
+#[test]
+fn test_top_up_target_validation() {
+    let config: Config = serde_yaml::from_str(
+        r#"
+interval_secs: 60
+networks:
+  - name: Ethereum
+    chain_id: 1
+    rpc_nodes: ["https://eth.llamarpc.com"]
+    addresses:
+      - alias: Relayer
+        address: "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045"
+        top_up_to: 0
+    tokens:
+      - alias: USDC
+        address: "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48"
+        top_up_to: -5
+"#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        config.problems(),
+        vec![
+            "top_up_to for 'Relayer' on network 'Ethereum' must be positive".to_string(),
+            "top_up_to for token 'USDC' on network 'Ethereum' must be positive".to_string(),
+        ]
+    );
+}
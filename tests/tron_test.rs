@@ -0,0 +1,57 @@
+use alloy::primitives::{address, U256};
+use Oxwatcher::{compare_balances, format_tron_address, parse_tron_address, BalanceStorage, TronConfig, TronMonitor};
+
+const USDT: &str = "TR7NHqjeKQxGTCi8q8ZY4pL8otSzgjLj6t";
+
+#[test]
+fn test_tron_address_round_trip() {
+    let usdt = address!("a614f803b6fd780986a42c78ec9c7f77e6ded13c");
+    assert_eq!(parse_tron_address(USDT).unwrap(), usdt);
+    assert_eq!(parse_tron_address("41a614f803b6fd780986a42c78ec9c7f77e6ded13c").unwrap(), usdt);
+    assert_eq!(format_tron_address(&usdt), USDT);
+
+    // Bad checksum
+    assert!(parse_tron_address("TR7NHqjeKQxGTCi8q8ZY4pL8otSzgjLj6u").is_err());
+}
+
+#[test]
+fn test_tron_account_balances() {
+    let config: TronConfig = serde_yaml::from_str(&format!(
+        r#"
+addresses:
+  - alias: Treasury
+    address: TNR8TefgTphcrwQQodMxFDa6BPggc2CZeE
+    min_balance_trx: 100
+tokens:
+  - alias: USDT
+    address: {USDT}
+    decimals: 6
+"#
+    ))
+    .unwrap();
+    assert_eq!(config.name, "Tron");
+    assert_eq!(config.chain_id, 728126428);
+    let treasury = config.addresses[0].address;
+    let monitor = TronMonitor::new(config);
+
+    let body = format!(
+        r#"{{"data":[{{"balance":1500000,"trc20":[{{"{USDT}":"2500000000"}}]}}],"success":true}}"#
+    );
+    let info = monitor.parse_account("Treasury", treasury, &body).unwrap();
+    assert_eq!(info.network_name, "Tron");
+    assert_eq!(info.native_symbol, "TRX");
+    assert_eq!(info.eth_balance, U256::from(1_500_000));
+    assert_eq!(info.eth_formatted, "1.500000");
+    assert_eq!(info.token_balances[0].formatted, "2500.000000");
+
+    // Unactivated accounts have no data and zero balances
+    let empty = monitor.parse_account("Treasury", treasury, r#"{"data":[],"success":true}"#).unwrap();
+    assert_eq!(empty.eth_balance, U256::ZERO);
+    assert_eq!(empty.token_balances[0].balance, U256::ZERO);
+
+    // Changes are labelled with the native symbol
+    let mut storage = BalanceStorage::new();
+    storage.update(&empty);
+    let changes = compare_balances(&info, &storage);
+    assert_eq!(changes.eth_change.unwrap().alias, "TRX");
+}
@@ -0,0 +1,86 @@
+use Oxwatcher::{BeaconConfig, ThrottleConfig, ValidatorEvent, ValidatorInfo, ValidatorMonitor};
+
+fn monitor() -> ValidatorMonitor {
+    let config: BeaconConfig = serde_yaml::from_str(
+        r#"
+url: http://localhost:5052
+missed_attestations: 2
+validators:
+  - alias: Validator 1
+    id: 123456
+"#,
+    )
+    .unwrap();
+    assert_eq!(config.validators[0].id, "123456");
+    ValidatorMonitor::new(config)
+}
+
+/// Validator entry as returned by /eth/v1/beacon/states/head/validators
+fn validator(balance: u64, status: &str, slashed: bool) -> ValidatorInfo {
+    serde_json::from_value(serde_json::json!({
+        "index": "123456",
+        "balance": balance.to_string(),
+        "status": status,
+        "validator": {
+            "pubkey": "0x93247f2209abcacf57b75a51dafae777f9dd38bc7053d1af526f220a7489a6d3a2753e5f3e8b1cfe39b56f43611df74a",
+            "withdrawal_credentials": "0x00",
+            "effective_balance": "32000000000",
+            "slashed": slashed,
+        },
+    }))
+    .unwrap()
+}
+
+#[tokio::test]
+async fn test_validator_missed_attestation_streak() {
+    let monitor = monitor();
+    let info = validator(32_000_000_000, "active_ongoing", false);
+    assert!(info.matches("123456"));
+    assert!(info.matches("0x93247F2209ABCACF57B75A51DAFAE777F9DD38BC7053D1AF526F220A7489A6D3A2753E5F3E8B1CFE39B56F43611DF74A"));
+
+    assert!(monitor.record("Validator 1", info.clone(), Some(false)).await.is_empty());
+    let events = monitor.record("Validator 1", info.clone(), Some(false)).await;
+    assert!(matches!(events.as_slice(), [ValidatorEvent::MissedAttestations { streak: 2, alert_number: 1, .. }]));
+
+    // Repeats are throttled until the validator attests again
+    assert!(monitor.record("Validator 1", info.clone(), Some(false)).await.is_empty());
+    assert!(monitor.record("Validator 1", info.clone(), Some(true)).await.is_empty());
+    assert!(monitor.record("Validator 1", info.clone(), Some(false)).await.is_empty());
+}
+
+#[tokio::test]
+async fn test_missed_attestation_alerts_follow_the_configured_schedule() {
+    let next_alert = |events: Vec<ValidatorEvent>| match events.as_slice() {
+        [ValidatorEvent::MissedAttestations { next_alert, .. }] => next_alert.clone(),
+        events => panic!("unexpected events: {:?}", events),
+    };
+    let info = validator(32_000_000_000, "active_ongoing", false);
+    let hourly = ThrottleConfig { intervals_mins: vec![], repeat_mins: Some(60) };
+
+    let monitor = monitor().with_throttle(hourly.clone());
+    monitor.record("Validator 1", info.clone(), Some(false)).await;
+    assert_eq!(next_alert(monitor.record("Validator 1", info.clone(), Some(false)).await), "Alerts every 1 hour");
+
+    // beacon.throttle wins over the low balance schedule
+    let mut config = monitor.config().clone();
+    config.throttle = Some(ThrottleConfig { intervals_mins: vec![], repeat_mins: Some(120) });
+    let monitor = ValidatorMonitor::new(config).with_throttle(hourly);
+    monitor.record("Validator 1", info.clone(), Some(false)).await;
+    assert_eq!(next_alert(monitor.record("Validator 1", info, Some(false)).await), "Alerts every 2 hours");
+}
+
+#[tokio::test]
+async fn test_validator_slashing_and_balance_drop() {
+    let monitor = monitor();
+    assert!(monitor.record("Validator 1", validator(32_000_000_000, "active_ongoing", false), None).await.is_empty());
+
+    // Small penalties stay below the default 0.01 ETH drop
+    let events = monitor.record("Validator 1", validator(31_999_990_000, "active_ongoing", false), None).await;
+    assert!(events.is_empty());
+
+    let events = monitor.record("Validator 1", validator(31_000_000_000, "active_slashed", true), None).await;
+    assert_eq!(events.len(), 3);
+    assert_eq!(events[0], ValidatorEvent::Slashed);
+    assert_eq!(events[1].describe(), "Status: active_ongoing → active_slashed");
+    assert_eq!(events[2].kind(), "balance_decreased");
+}
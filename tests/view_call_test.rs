@@ -0,0 +1,61 @@
+use alloy::primitives::U256;
+use Oxwatcher::{ViewCall, ViewCallConfig, ViewCallLevel, ViewCallMonitor};
+
+fn view_call(yaml: &str) -> ViewCallConfig {
+    serde_yaml::from_str(yaml).unwrap()
+}
+
+fn encoded(value: u64) -> Vec<u8> {
+    U256::from(value).to_be_bytes::<32>().to_vec()
+}
+
+#[test]
+fn test_view_call_parses_signature_and_args() {
+    let config = view_call(
+        r#"
+alias: Treasury shares
+address: 0x83F20F44975D03b1b09e64809B757c47f942BEeA
+function: balanceOf(address)
+args: ["0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045"]
+returns: uint256
+decimals: 18
+"#,
+    );
+    let call = ViewCall::new(config.clone()).unwrap();
+    assert_eq!(call.describe(), "balanceOf(0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045)");
+    assert_eq!(call.decode(&encoded(1_500_000_000_000_000_000)).unwrap().number, Some(1.5));
+
+    // Argument count and thresholds on non-numeric types are checked
+    assert!(ViewCall::new(ViewCallConfig { args: vec![], ..config.clone() }).is_err());
+    assert!(ViewCall::new(ViewCallConfig { returns: "bool".to_string(), min: Some(1.0), ..config }).is_err());
+}
+
+#[tokio::test]
+async fn test_view_call_alerts_on_change_and_threshold_crossing() {
+    let config = view_call(
+        r#"
+alias: Vault assets
+address: 0x83F20F44975D03b1b09e64809B757c47f942BEeA
+function: totalAssets()
+returns: uint256
+decimals: 6
+min: 1000
+"#,
+    );
+    let call = ViewCall::new(config.clone()).unwrap();
+    let monitor = ViewCallMonitor::new(vec![config.clone()]).unwrap();
+
+    // Baseline in range doesn't alert
+    let value = call.decode(&encoded(5_000_000_000)).unwrap();
+    assert_eq!(value.formatted, "5000.000000");
+    assert!(monitor.record(&config, value.clone()).await.is_none());
+    assert!(monitor.record(&config, value).await.is_none());
+
+    let update = monitor.record(&config, call.decode(&encoded(500_000_000)).unwrap()).await.unwrap();
+    assert_eq!(update.changed_from.as_deref(), Some("5000.000000"));
+    assert_eq!(update.crossed, Some(ViewCallLevel::Below));
+
+    // Staying below the minimum only reports the change
+    let update = monitor.record(&config, call.decode(&encoded(400_000_000)).unwrap()).await.unwrap();
+    assert_eq!(update.crossed, None);
+}
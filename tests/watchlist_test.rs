@@ -0,0 +1,131 @@
+use alloy::primitives::{address, Address, TxHash, U256};
+use std::time::{Duration, SystemTime};
+use Oxwatcher::{
+    parse_watchlist, AlertSeverity, AuditSubject, BalanceChangeSummary, Config, MonitorAlert,
+    TransferDirection, TxAttribution, Watchlist, WatchlistConfig, FLAGGED_KIND,
+};
+
+const EXPLOITER: Address = address!("098B716B8Aaf21512996dC57EB0615e2383E2f96");
+const MIXER: Address = address!("d90e2f925DA726b50C4Ed8D0Fb90Ad053324F31b");
+
+fn transfer(direction: TransferDirection, counterparty: Address) -> TxAttribution {
+    TxAttribution {
+        tx_hash: TxHash::with_last_byte(1),
+        block_number: 100,
+        asset: "ETH".to_string(),
+        direction,
+        counterparty: Some(counterparty),
+        value: U256::from(1),
+        url: None,
+    }
+}
+
+fn watchlist_file(name: &str, text: &str) -> String {
+    let path = std::env::temp_dir().join(format!("oxwatcher-watchlist-{}-{}.txt", name, std::process::id()));
+    std::fs::write(&path, text).unwrap();
+    path.to_string_lossy().to_string()
+}
+
+fn config(path: &str, outgoing: bool) -> WatchlistConfig {
+    serde_yaml::from_str(&format!("path: {}\noutgoing: {}", path, outgoing)).unwrap()
+}
+
+#[test]
+fn test_watchlist_parses_addresses_with_optional_labels() {
+    let entries = parse_watchlist(&format!(
+        "# Exploiters\n{} Ronin bridge exploiter\n\n{:?}  # no label\n",
+        EXPLOITER, MIXER
+    ))
+    .unwrap();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[&EXPLOITER], "Ronin bridge exploiter");
+    assert_eq!(entries[&MIXER], "flagged");
+
+    let error = parse_watchlist(&format!("{}\n0x1234 short", EXPLOITER)).unwrap_err();
+    assert!(error.to_string().starts_with("line 2: invalid address '0x1234'"), "{}", error);
+}
+
+#[tokio::test]
+async fn test_watchlist_flags_incoming_transfers_and_reloads_changes() {
+    let path = watchlist_file("reload", &format!("{} exploiter\n", EXPLOITER));
+    let watchlist = Watchlist::load(&config(&path, false)).await.unwrap();
+    assert_eq!(watchlist.len().await, 1);
+
+    let transactions = [
+        transfer(TransferDirection::Incoming, EXPLOITER),
+        transfer(TransferDirection::Outgoing, EXPLOITER),
+        transfer(TransferDirection::Incoming, MIXER),
+    ];
+    let flagged = watchlist.flagged(&transactions).await;
+    assert_eq!(flagged.len(), 1);
+    assert_eq!(flagged[0].0.direction, TransferDirection::Incoming);
+    assert_eq!(flagged[0].1, "exploiter");
+
+    // Unchanged file isn't read again
+    assert!(!watchlist.reload().await.unwrap());
+
+    // A changed file replaces the list; an invalid one keeps the previous list
+    std::fs::write(&path, format!("{}\n{} mixer\n", EXPLOITER, MIXER)).unwrap();
+    let file = std::fs::File::options().write(true).open(&path).unwrap();
+    file.set_modified(SystemTime::now() + Duration::from_secs(60)).unwrap();
+    assert!(watchlist.reload().await.unwrap());
+    assert_eq!(watchlist.flagged(&transactions).await.len(), 2);
+
+    std::fs::write(&path, "not an address\n").unwrap();
+    file.set_modified(SystemTime::now() + Duration::from_secs(120)).unwrap();
+    assert!(watchlist.reload().await.is_err());
+    assert_eq!(watchlist.label(&MIXER).await.as_deref(), Some("mixer"));
+
+    // Outgoing transfers count with `outgoing`
+    std::fs::write(&path, format!("{}\n", EXPLOITER)).unwrap();
+    let watchlist = Watchlist::load(&config(&path, true)).await.unwrap();
+    assert_eq!(watchlist.flagged(&transactions).await.len(), 2);
+}
+
+#[test]
+fn test_flagged_alert_is_critical() {
+    let changes = BalanceChangeSummary {
+        network_name: "Ethereum".to_string(),
+        chain_id: 1,
+        alias: "treasury".to_string(),
+        address: "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045".to_string(),
+        eth_change: None,
+        token_changes: vec![],
+        nft_changes: vec![],
+        transactions: vec![],
+    };
+    let alert = MonitorAlert::flagged(&changes, &transfer(TransferDirection::Incoming, EXPLOITER), "exploiter");
+    assert_eq!(alert.kind, FLAGGED_KIND);
+    assert_eq!(alert.title, "🚩 treasury transfer from flagged address");
+    assert_eq!(alert.data["transaction"]["counterparty"], format!("{:?}", EXPLOITER));
+    assert_eq!(AuditSubject::alert(&alert).severity, Some(AlertSeverity::Critical));
+}
+
+#[test]
+fn test_watchlist_needs_tx_lookup() {
+    let config: Config = serde_yaml::from_str(
+        r#"
+interval_secs: 60
+networks:
+  - name: Ethereum
+    chain_id: 1
+    rpc_nodes: ["https://eth.llamarpc.com"]
+    tx_lookup:
+      enabled: false
+    addresses:
+      - alias: treasury
+        address: "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045"
+watchlist:
+  path: flagged.txt
+  channels: [pager]
+"#,
+    )
+    .unwrap();
+    assert_eq!(
+        config.problems(),
+        [
+            "watchlist needs tx_lookup enabled on at least one network",
+            "watchlist uses unknown channel 'pager', expected telegram, telegram:<bot name>, slack, discord, matrix, twilio or webhook",
+        ]
+    );
+}